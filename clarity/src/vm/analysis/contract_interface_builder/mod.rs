@@ -22,7 +22,8 @@ use crate::vm::analysis::types::ContractAnalysis;
 use crate::vm::analysis::CheckResult;
 use crate::vm::types::signatures::CallableSubtype;
 use crate::vm::types::{
-    FixedFunction, FunctionArg, FunctionType, TupleTypeSignature, TypeSignature,
+    BufferLength, FixedFunction, FunctionArg, FunctionType, ListTypeData, StringUTF8Length,
+    TupleTypeSignature, TypeSignature,
 };
 use crate::vm::{CheckErrors, ClarityName, ClarityVersion};
 
@@ -228,6 +229,55 @@ impl ContractInterfaceAtomType {
             }
         }
     }
+
+    /// The inverse of `from_type_signature`. Returns `None` for trait
+    ///  references, which have no value type, and for malformed types.
+    pub fn to_type_signature(&self) -> Option<TypeSignature> {
+        use crate::vm::types::SequenceSubtype::*;
+        use crate::vm::types::StringSubtype::*;
+        use crate::vm::types::TypeSignature::*;
+
+        let sig = match self {
+            ContractInterfaceAtomType::none => NoType,
+            ContractInterfaceAtomType::int128 => IntType,
+            ContractInterfaceAtomType::uint128 => UIntType,
+            ContractInterfaceAtomType::bool => BoolType,
+            ContractInterfaceAtomType::principal => PrincipalType,
+            ContractInterfaceAtomType::buffer { length } => {
+                SequenceType(BufferType(BufferLength::try_from(*length).ok()?))
+            }
+            ContractInterfaceAtomType::string_utf8 { length } => {
+                SequenceType(StringType(UTF8(StringUTF8Length::try_from(*length).ok()?)))
+            }
+            ContractInterfaceAtomType::string_ascii { length } => {
+                SequenceType(StringType(ASCII(BufferLength::try_from(*length).ok()?)))
+            }
+            ContractInterfaceAtomType::tuple(entries) => {
+                let fields = entries
+                    .iter()
+                    .map(|entry| {
+                        Some((
+                            ClarityName::try_from(entry.name.clone()).ok()?,
+                            entry.type_f.to_type_signature()?,
+                        ))
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                TupleType(TupleTypeSignature::try_from(fields).ok()?)
+            }
+            ContractInterfaceAtomType::optional(sig) => {
+                TypeSignature::new_option(sig.to_type_signature()?).ok()?
+            }
+            ContractInterfaceAtomType::response { ok, error } => {
+                TypeSignature::new_response(ok.to_type_signature()?, error.to_type_signature()?)
+                    .ok()?
+            }
+            ContractInterfaceAtomType::list { type_f, length } => SequenceType(ListType(
+                ListTypeData::new_list(type_f.to_type_signature()?, *length).ok()?,
+            )),
+            ContractInterfaceAtomType::trait_reference => return None,
+        };
+        Some(sig)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -0,0 +1,418 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use hashbrown::HashSet;
+
+use crate::vm::ast::span_locator::SpanLocator;
+use crate::vm::diagnostic::{Diagnostic, Level};
+use crate::vm::functions::define::DefineFunctionsParsed;
+use crate::vm::functions::NativeFunctions;
+use crate::vm::representations::{ClarityName, SymbolicExpression};
+use crate::vm::variables::NativeVariables;
+
+#[cfg(test)]
+mod tests;
+
+/// The kinds of top-level definitions that are reported when unused
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum UnusedKind {
+    PrivateFunction,
+    Constant,
+    DataVar,
+}
+
+impl UnusedKind {
+    fn describe(&self) -> &'static str {
+        match self {
+            UnusedKind::PrivateFunction => "private function",
+            UnusedKind::Constant => "constant",
+            UnusedKind::DataVar => "data-var",
+        }
+    }
+}
+
+///
+/// A static-analysis pass that looks for code which is legal, but is likely
+///  to be a mistake. It never rejects a contract: it only produces
+///  `Level::Warning` diagnostics, for use by developer tools.
+///
+/// The checks are:
+///  * private functions, constants and data-vars that are never referenced
+///  * `(is-ok ...)`/`(is-err ...)` whose result is discarded in a sequence
+///    of statements, which leaves the inspected response unchecked
+///  * local bindings that shadow an enclosing local binding
+///  * `unwrap-panic`/`unwrap-err-panic` in public functions
+///  * `tx-sender` compared with a constant or data-var in an `asserts!`, an
+///    authorization check which should usually look at `contract-caller`
+///
+pub struct LintChecker<'a> {
+    spans: &'a SpanLocator,
+    diagnostics: Vec<Diagnostic>,
+    used_names: HashSet<ClarityName>,
+}
+
+impl LintChecker<'_> {
+    /// Run all of the lints over a contract's expressions, placing the warnings with `spans`
+    pub fn run(expressions: &[SymbolicExpression], spans: &SpanLocator) -> Vec<Diagnostic> {
+        let mut checker = LintChecker {
+            spans,
+            diagnostics: vec![],
+            used_names: HashSet::new(),
+        };
+
+        let mut defined = vec![];
+        for expr in expressions.iter() {
+            match DefineFunctionsParsed::try_parse(expr).ok().flatten() {
+                Some(define_type) => {
+                    if let Some((name, kind)) = checker.check_definition(&define_type) {
+                        defined.push((name, kind, expr));
+                    }
+                }
+                None => checker.check_expression(expr, &mut vec![], false),
+            }
+        }
+
+        for (name, kind, expr) in defined.into_iter() {
+            if !checker.used_names.contains(name) {
+                checker.warn(
+                    expr,
+                    format!("{} `{}` is never used", kind.describe(), name),
+                    Some("remove it, or check whether a call to it is missing".into()),
+                );
+            }
+        }
+
+        checker.diagnostics
+    }
+
+    fn warn(&mut self, expr: &SymbolicExpression, message: String, suggestion: Option<String>) {
+        self.diagnostics.push(Diagnostic {
+            level: Level::Warning,
+            message,
+            spans: vec![self.spans.span(expr)],
+            suggestion,
+        });
+    }
+
+    /// Check a top-level definition, returning its name if it is one that
+    /// should be reported when unused.
+    fn check_definition<'a>(
+        &mut self,
+        define_type: &DefineFunctionsParsed<'a>,
+    ) -> Option<(&'a ClarityName, UnusedKind)> {
+        match define_type {
+            DefineFunctionsParsed::Constant { name, value } => {
+                self.check_expression(value, &mut vec![], false);
+                Some((name, UnusedKind::Constant))
+            }
+            DefineFunctionsParsed::PersistedVariable { name, initial, .. } => {
+                self.check_expression(initial, &mut vec![], false);
+                Some((name, UnusedKind::DataVar))
+            }
+            DefineFunctionsParsed::BoundedFungibleToken { max_supply, .. } => {
+                self.check_expression(max_supply, &mut vec![], false);
+                None
+            }
+            DefineFunctionsParsed::PrivateFunction { signature, body } => {
+                let name = self.check_function(signature, body, false)?;
+                Some((name, UnusedKind::PrivateFunction))
+            }
+            DefineFunctionsParsed::PublicFunction { signature, body } => {
+                self.check_function(signature, body, true);
+                None
+            }
+            DefineFunctionsParsed::ReadOnlyFunction { signature, body } => {
+                self.check_function(signature, body, false);
+                None
+            }
+            DefineFunctionsParsed::NonFungibleToken { .. }
+            | DefineFunctionsParsed::UnboundedFungibleToken { .. }
+            | DefineFunctionsParsed::Map { .. }
+            | DefineFunctionsParsed::Trait { .. }
+            | DefineFunctionsParsed::UseTrait { .. }
+            | DefineFunctionsParsed::ImplTrait { .. } => None,
+        }
+    }
+
+    /// Check a function body with its arguments in scope, returning the function's name
+    fn check_function<'a>(
+        &mut self,
+        signature: &'a [SymbolicExpression],
+        body: &SymbolicExpression,
+        is_public: bool,
+    ) -> Option<&'a ClarityName> {
+        let (name, args) = signature.split_first()?;
+        let arg_names = args
+            .iter()
+            .filter_map(|arg| arg.match_list()?.first()?.match_atom().cloned())
+            .collect();
+        self.check_expression(body, &mut vec![arg_names], is_public);
+        name.match_atom()
+    }
+
+    /// Bind `name` in the innermost scope, warning if an enclosing scope already binds it
+    fn bind(
+        &mut self,
+        binder: &SymbolicExpression,
+        scopes: &mut [Vec<ClarityName>],
+        innermost: &mut Vec<ClarityName>,
+    ) {
+        let Some(name) = binder.match_atom() else {
+            return;
+        };
+        if scopes.iter().any(|scope| scope.contains(name)) {
+            self.warn(
+                binder,
+                format!("`{name}` shadows an enclosing binding of the same name"),
+                Some("rename one of the bindings".into()),
+            );
+        }
+        innermost.push(name.clone());
+    }
+
+    /// Check the statements of a `begin` or `let` body. Every statement but the
+    /// last one is evaluated only for its side effects.
+    fn check_statements(
+        &mut self,
+        statements: &[SymbolicExpression],
+        scopes: &mut Vec<Vec<ClarityName>>,
+        is_public: bool,
+    ) {
+        if let Some((_last, intermediary)) = statements.split_last() {
+            for statement in intermediary.iter() {
+                let inspector = statement
+                    .match_list()
+                    .and_then(|list| list.first())
+                    .and_then(|head| head.match_atom())
+                    .filter(|name| {
+                        [NativeFunctions::IsOkay, NativeFunctions::IsErr]
+                            .iter()
+                            .any(|f| f.get_name_str() == name.as_str())
+                    });
+                if let Some(inspector) = inspector {
+                    self.warn(
+                        statement,
+                        format!(
+                            "the result of `{inspector}` is discarded, so the response it inspects is never checked"
+                        ),
+                        Some("use `try!`, `unwrap!` or `asserts!` to act on the response".into()),
+                    );
+                }
+            }
+        }
+        for statement in statements.iter() {
+            self.check_expression(statement, scopes, is_public);
+        }
+    }
+
+    fn check_expression(
+        &mut self,
+        expr: &SymbolicExpression,
+        scopes: &mut Vec<Vec<ClarityName>>,
+        is_public: bool,
+    ) {
+        if let Some(name) = expr.match_atom() {
+            self.used_names.insert(name.clone());
+            return;
+        }
+        let Some(list) = expr.match_list() else {
+            return;
+        };
+        let Some((head, args)) = list.split_first() else {
+            return;
+        };
+        let Some(function_name) = head.match_atom() else {
+            for child in list.iter() {
+                self.check_expression(child, scopes, is_public);
+            }
+            return;
+        };
+        self.used_names.insert(function_name.clone());
+
+        match NativeFunctions::lookup_by_name(function_name) {
+            Some(NativeFunctions::Let) => {
+                let Some((bindings, body)) = args.split_first() else {
+                    return;
+                };
+                let mut scope = vec![];
+                for binding in bindings.match_list().unwrap_or_default() {
+                    let Some([binder, value]) = binding.match_list() else {
+                        continue;
+                    };
+                    self.check_expression(value, scopes, is_public);
+                    self.bind(binder, scopes, &mut scope);
+                }
+                scopes.push(scope);
+                self.check_statements(body, scopes, is_public);
+                scopes.pop();
+            }
+            Some(NativeFunctions::Begin) => {
+                self.check_statements(args, scopes, is_public);
+            }
+            Some(NativeFunctions::Match) => {
+                let Some((input, branches)) = args.split_first() else {
+                    return;
+                };
+                self.check_expression(input, scopes, is_public);
+                match branches {
+                    // (match opt some-name some-branch none-branch)
+                    [some_name, some_branch, none_branch] => {
+                        let mut scope = vec![];
+                        self.bind(some_name, scopes, &mut scope);
+                        scopes.push(scope);
+                        self.check_expression(some_branch, scopes, is_public);
+                        scopes.pop();
+                        self.check_expression(none_branch, scopes, is_public);
+                    }
+                    // (match res ok-name ok-branch err-name err-branch)
+                    [ok_name, ok_branch, err_name, err_branch] => {
+                        let mut scope = vec![];
+                        self.bind(ok_name, scopes, &mut scope);
+                        scopes.push(scope);
+                        self.check_expression(ok_branch, scopes, is_public);
+                        scopes.pop();
+
+                        let mut scope = vec![];
+                        self.bind(err_name, scopes, &mut scope);
+                        scopes.push(scope);
+                        self.check_expression(err_branch, scopes, is_public);
+                        scopes.pop();
+                    }
+                    _ => {
+                        for branch in branches.iter() {
+                            self.check_expression(branch, scopes, is_public);
+                        }
+                    }
+                }
+            }
+            Some(NativeFunctions::TupleCons) => {
+                // only the values are evaluated: the keys are field names
+                for pair in args.iter() {
+                    if let Some([_key, value]) = pair.match_list() {
+                        self.check_expression(value, scopes, is_public);
+                    }
+                }
+            }
+            Some(NativeFunctions::TupleGet) => {
+                // the first argument is a field name
+                for arg in args.iter().skip(1) {
+                    self.check_expression(arg, scopes, is_public);
+                }
+            }
+            Some(NativeFunctions::Unwrap) | Some(NativeFunctions::UnwrapErr) if is_public => {
+                self.warn(
+                    expr,
+                    format!("`{function_name}` in a public function aborts the transaction with a runtime error"),
+                    Some("use `unwrap!` or `unwrap-err!` to return an error code instead".into()),
+                );
+                for arg in args.iter() {
+                    self.check_expression(arg, scopes, is_public);
+                }
+            }
+            Some(NativeFunctions::Asserts) => {
+                if let Some(condition) = args.first() {
+                    self.check_authorization(condition, scopes);
+                }
+                for arg in args.iter() {
+                    self.check_expression(arg, scopes, is_public);
+                }
+            }
+            _ => {
+                for arg in args.iter() {
+                    self.check_expression(arg, scopes, is_public);
+                }
+            }
+        }
+    }
+
+    /// Check the condition of an `asserts!` for `tx-sender` compared with a fixed principal, as
+    /// in `(asserts! (is-eq tx-sender OWNER) ...)`. Such a check lets any contract the sender
+    /// calls act as them. Comparisons with a principal the call is about, like an argument or an
+    /// owner looked up in a map, are left alone, as are conditions which also look at
+    /// `contract-caller`.
+    fn check_authorization(&mut self, condition: &SymbolicExpression, scopes: &[Vec<ClarityName>]) {
+        let contract_caller = NativeVariables::ContractCaller.get_name_str();
+        if Self::mentions(condition, contract_caller) {
+            return;
+        }
+        let tx_sender = NativeVariables::TxSender.get_name_str();
+        let mut comparisons = vec![];
+        Self::collect_comparisons(condition, &mut comparisons);
+        for comparison in comparisons.into_iter() {
+            let Some((_, operands)) = comparison.match_list().and_then(|list| list.split_first())
+            else {
+                continue;
+            };
+            let (senders, others): (Vec<_>, Vec<_>) = operands
+                .iter()
+                .partition(|operand| operand.match_atom().map(|a| a.as_str()) == Some(tx_sender));
+            if !senders.is_empty()
+                && !others.is_empty()
+                && others
+                    .iter()
+                    .all(|operand| Self::is_fixed_principal(operand, scopes))
+            {
+                self.warn(
+                    comparison,
+                    "`tx-sender` is compared with a fixed principal to authorize this call; any contract the sender calls can pass the check on their behalf".into(),
+                    Some("compare `contract-caller` instead".into()),
+                );
+            }
+        }
+    }
+
+    /// Does `expr` refer to the variable `name` anywhere?
+    fn mentions(expr: &SymbolicExpression, name: &str) -> bool {
+        match expr.match_list() {
+            Some(list) => list.iter().any(|item| Self::mentions(item, name)),
+            None => expr.match_atom().map(|a| a.as_str()) == Some(name),
+        }
+    }
+
+    /// Collect the `is-eq` comparisons which `condition` combines with `and` and `or`
+    fn collect_comparisons<'b>(
+        condition: &'b SymbolicExpression,
+        comparisons: &mut Vec<&'b SymbolicExpression>,
+    ) {
+        let Some((head, args)) = condition.match_list().and_then(|list| list.split_first()) else {
+            return;
+        };
+        match head
+            .match_atom()
+            .and_then(|name| NativeFunctions::lookup_by_name(name))
+        {
+            Some(NativeFunctions::Equals) => comparisons.push(condition),
+            Some(NativeFunctions::And) | Some(NativeFunctions::Or) => {
+                for arg in args.iter() {
+                    Self::collect_comparisons(arg, comparisons);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Is `expr` a principal fixed by the contract: a constant, or a data-var?
+    fn is_fixed_principal(expr: &SymbolicExpression, scopes: &[Vec<ClarityName>]) -> bool {
+        if let Some(name) = expr.match_atom() {
+            return NativeVariables::lookup_by_name(name).is_none()
+                && !scopes.iter().any(|scope| scope.contains(name));
+        }
+        matches!(
+            expr.match_list(),
+            Some([head, _]) if head.match_atom().map(|a| a.as_str())
+                == Some(NativeFunctions::FetchVar.get_name_str())
+        )
+    }
+}
@@ -0,0 +1,182 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use stacks_common::types::StacksEpochId;
+
+use crate::vm::analysis::lint_checker::LintChecker;
+use crate::vm::ast::parse;
+use crate::vm::ast::span_locator::SpanLocator;
+use crate::vm::diagnostic::Level;
+use crate::vm::representations::Span;
+use crate::vm::types::QualifiedContractIdentifier;
+use crate::vm::ClarityVersion;
+
+fn lint(contract: &str) -> Vec<String> {
+    let expressions = parse(
+        &QualifiedContractIdentifier::transient(),
+        contract,
+        ClarityVersion::Clarity2,
+        StacksEpochId::Epoch25,
+    )
+    .unwrap();
+    LintChecker::run(&expressions, &SpanLocator::new(contract, &expressions))
+        .into_iter()
+        .map(|diagnostic| {
+            assert_eq!(diagnostic.level, Level::Warning);
+            diagnostic.message
+        })
+        .collect()
+}
+
+#[test]
+fn test_clean_contract() {
+    let contract = "(define-constant OWNER tx-sender)
+        (define-data-var counter uint u0)
+        (define-private (bump (by uint)) (var-set counter (+ (var-get counter) by)))
+        (define-public (increment)
+          (begin
+            (asserts! (is-eq contract-caller OWNER) (err u1))
+            (bump u1)
+            (ok (get value { value: (var-get counter) }))))";
+    assert!(lint(contract).is_empty());
+}
+
+#[test]
+fn test_unused_definitions() {
+    let contract = "(define-constant UNUSED u1)
+        (define-data-var unused-var uint u0)
+        (define-private (unused-fn) true)
+        (define-public (foo) (ok { unused-var: u1 }))";
+    assert_eq!(
+        lint(contract),
+        vec![
+            "constant `UNUSED` is never used",
+            "data-var `unused-var` is never used",
+            "private function `unused-fn` is never used",
+        ]
+    );
+}
+
+#[test]
+fn test_discarded_response_check() {
+    let contract = "(define-public (foo (p principal))
+        (begin
+          (is-ok (stx-transfer? u1 tx-sender p))
+          (ok true)))";
+    assert_eq!(
+        lint(contract),
+        vec!["the result of `is-ok` is discarded, so the response it inspects is never checked"]
+    );
+
+    // the last statement is the value of the sequence, so it is not discarded
+    let contract = "(define-read-only (foo (r (response int int))) (begin (is-err r)))";
+    assert!(lint(contract).is_empty());
+}
+
+#[test]
+fn test_shadowed_bindings() {
+    let contract = "(define-read-only (foo (x int))
+        (let ((y 1))
+          (match (some y) x (+ x y) (let ((y 2)) y))))";
+    assert_eq!(
+        lint(contract),
+        vec![
+            "`x` shadows an enclosing binding of the same name",
+            "`y` shadows an enclosing binding of the same name",
+        ]
+    );
+}
+
+#[test]
+fn test_unwrap_panic_in_public() {
+    let contract = "(define-map m uint uint)
+        (define-public (foo) (ok (unwrap-panic (map-get? m u1))))
+        (define-read-only (bar) (unwrap-panic (map-get? m u1)))";
+    assert_eq!(
+        lint(contract),
+        vec!["`unwrap-panic` in a public function aborts the transaction with a runtime error"]
+    );
+}
+
+#[test]
+fn test_tx_sender_authorization() {
+    let contract = "(define-constant OWNER 'SP000000000000000000002Q6VF78)
+        (define-data-var admin principal OWNER)
+        (define-public (set-admin (new-admin principal))
+          (begin
+            (asserts! (is-eq tx-sender OWNER) (err u1))
+            (ok (var-set admin new-admin))))
+        (define-public (pause)
+          (begin
+            (asserts! (or (is-eq tx-sender (var-get admin)) (is-eq tx-sender OWNER)) (err u1))
+            (ok true)))";
+    let warning = "`tx-sender` is compared with a fixed principal to authorize this call; any contract the sender calls can pass the check on their behalf";
+    assert_eq!(lint(contract), vec![warning, warning, warning]);
+}
+
+#[test]
+fn test_tx_sender_ownership() {
+    // comparisons with the principal a call is about, or which also look at
+    //  `contract-caller`, are not authorization checks against a fixed principal
+    let contract = "(define-constant OWNER 'SP000000000000000000002Q6VF78)
+        (define-map listings uint { owner: principal })
+        (define-public (transfer (amount uint) (sender principal) (recipient principal))
+          (begin
+            (asserts! (is-eq tx-sender sender) (err u1))
+            (stx-transfer? amount sender recipient)))
+        (define-public (unlist (id uint))
+          (let ((listing (unwrap! (map-get? listings id) (err u2))))
+            (asserts! (is-eq tx-sender (get owner listing)) (err u1))
+            (ok (map-delete listings id))))
+        (define-public (admin)
+          (begin
+            (asserts! (and (is-eq tx-sender OWNER) (is-eq contract-caller OWNER)) (err u1))
+            (ok (is-eq tx-sender OWNER))))";
+    assert!(lint(contract).is_empty());
+}
+
+#[test]
+fn test_warnings_have_spans() {
+    let contract = "(define-constant UNUSED u1)
+(define-read-only (foo) (begin (is-ok (ok 1)) true))";
+    let expressions = parse(
+        &QualifiedContractIdentifier::transient(),
+        contract,
+        ClarityVersion::Clarity2,
+        StacksEpochId::Epoch25,
+    )
+    .unwrap();
+    let spans: Vec<_> = LintChecker::run(&expressions, &SpanLocator::new(contract, &expressions))
+        .into_iter()
+        .map(|diagnostic| diagnostic.spans)
+        .collect();
+    assert_eq!(
+        spans,
+        vec![
+            vec![Span {
+                start_line: 2,
+                start_column: 32,
+                end_line: 2,
+                end_column: 45,
+            }],
+            vec![Span {
+                start_line: 1,
+                start_column: 1,
+                end_line: 1,
+                end_column: 27,
+            }],
+        ]
+    );
+}
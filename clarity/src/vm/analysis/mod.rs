@@ -18,6 +18,7 @@ pub mod analysis_db;
 pub mod arithmetic_checker;
 pub mod contract_interface_builder;
 pub mod errors;
+pub mod lint_checker;
 pub mod read_only_checker;
 pub mod trait_checker;
 pub mod type_checker;
@@ -29,16 +30,19 @@ pub use self::analysis_db::AnalysisDatabase;
 use self::arithmetic_checker::ArithmeticOnlyChecker;
 use self::contract_interface_builder::build_contract_interface;
 pub use self::errors::{CheckError, CheckErrors, CheckResult};
+use self::lint_checker::LintChecker;
 use self::read_only_checker::ReadOnlyChecker;
 use self::trait_checker::TraitChecker;
 use self::type_checker::v2_05::TypeChecker as TypeChecker2_05;
 use self::type_checker::v2_1::TypeChecker as TypeChecker2_1;
 pub use self::types::{AnalysisPass, ContractAnalysis};
+use crate::vm::ast::span_locator::SpanLocator;
 use crate::vm::ast::{build_ast_with_rules, ASTRules};
 use crate::vm::costs::LimitedCostTracker;
 #[cfg(feature = "canonical")]
 use crate::vm::database::MemoryBackingStore;
 use crate::vm::database::STORE_CONTRACT_SRC_INTERFACE;
+use crate::vm::diagnostic::Diagnostic;
 use crate::vm::representations::SymbolicExpression;
 use crate::vm::types::{QualifiedContractIdentifier, TypeSignature};
 use crate::vm::ClarityVersion;
@@ -177,5 +181,76 @@ pub fn run_analysis(
     }
 }
 
+/// Used by developer tools only. Runs the same passes as `run_analysis()`, but
+/// keeps going after a top-level expression fails to check, so that every
+/// broken definition gets a diagnostic of its own, and then runs the
+/// `LintChecker`. Returns the analysis (which is incomplete if there were
+/// errors) and all of the diagnostics, errors first, placed with `spans`.
+///
+/// The type checker can only recover from errors from epoch 2.1 on; in
+/// earlier epochs it stops at the first one. Costs are not tracked, and the
+/// contract is never saved to `analysis_db`.
+pub fn run_analysis_with_diagnostics(
+    contract_identifier: &QualifiedContractIdentifier,
+    expressions: &[SymbolicExpression],
+    analysis_db: &mut AnalysisDatabase,
+    epoch: StacksEpochId,
+    version: ClarityVersion,
+    spans: &SpanLocator,
+) -> (ContractAnalysis, Vec<Diagnostic>) {
+    let mut contract_analysis = ContractAnalysis::new(
+        contract_identifier.clone(),
+        expressions.to_vec(),
+        LimitedCostTracker::new_free(),
+        epoch,
+        version,
+    );
+
+    analysis_db.begin();
+    let mut errors = ReadOnlyChecker::run_collecting(&epoch, &contract_analysis, analysis_db);
+    match epoch {
+        StacksEpochId::Epoch20 | StacksEpochId::Epoch2_05 => {
+            if let Err(e) =
+                TypeChecker2_05::run_pass(&epoch, &mut contract_analysis, analysis_db, true)
+            {
+                errors.push(e);
+            }
+        }
+        StacksEpochId::Epoch10 => errors.push(
+            CheckErrors::Expects("Epoch 1.0 is not a valid epoch for analysis".into()).into(),
+        ),
+        StacksEpochId::Epoch21
+        | StacksEpochId::Epoch22
+        | StacksEpochId::Epoch23
+        | StacksEpochId::Epoch24
+        | StacksEpochId::Epoch25
+        | StacksEpochId::Epoch30
+        | StacksEpochId::Epoch31 => errors.extend(TypeChecker2_1::run_pass_collecting(
+            &epoch,
+            &mut contract_analysis,
+            analysis_db,
+            true,
+        )),
+    }
+    if let Err(e) = TraitChecker::run_pass(&epoch, &mut contract_analysis, analysis_db) {
+        errors.push(e);
+    }
+    if let Err(e) = analysis_db.roll_back() {
+        errors.push(e);
+    }
+
+    let mut diagnostics: Vec<_> = errors
+        .into_iter()
+        .map(|mut e| {
+            if let Some(exprs) = e.expressions.as_ref() {
+                e.diagnostic.spans = exprs.iter().map(|expr| spans.span(expr)).collect();
+            }
+            e.diagnostic
+        })
+        .collect();
+    diagnostics.extend(LintChecker::run(expressions, spans));
+    (contract_analysis, diagnostics)
+}
+
 #[cfg(test)]
 mod tests;
@@ -96,6 +96,45 @@ impl<'a, 'b> ReadOnlyChecker<'a, 'b> {
         Ok(())
     }
 
+    /// Used by developer tools only. Like `run()`, but checks every top-level
+    /// expression and returns one error per failing expression instead of
+    /// stopping at the first. A function whose definition fails the check is
+    /// recorded as read-only, so that its callers are not reported as well.
+    pub fn run_collecting(
+        epoch: &StacksEpochId,
+        contract_analysis: &ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+    ) -> Vec<CheckError> {
+        let mut command =
+            ReadOnlyChecker::new(analysis_db, epoch, &contract_analysis.clarity_version);
+        let mut errors = vec![];
+        for exp in contract_analysis.expressions.iter() {
+            if let Err(mut error) = command.check_top_level_expression(exp) {
+                if !error.has_expression() {
+                    error.set_expression(exp);
+                }
+                if let Some(function_name) = Self::defined_function_name(exp) {
+                    command
+                        .defined_functions
+                        .insert(function_name.clone(), true);
+                }
+                errors.push(error);
+            }
+        }
+        errors
+    }
+
+    /// The name of the function defined by `expression`, if it is a function definition.
+    fn defined_function_name(expression: &SymbolicExpression) -> Option<&ClarityName> {
+        use crate::vm::functions::define::DefineFunctionsParsed::*;
+        match DefineFunctionsParsed::try_parse(expression).ok()?? {
+            PrivateFunction { signature, .. }
+            | PublicFunction { signature, .. }
+            | ReadOnlyFunction { signature, .. } => signature.first()?.match_atom(),
+            _ => None,
+        }
+    }
+
     /// Checks the top-level expression `expression` to determine whether it is
     /// read-only compliant. `expression` maybe have composite structure that can be
     /// parsed into multiple expressions.
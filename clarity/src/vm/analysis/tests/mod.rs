@@ -366,3 +366,64 @@ fn test_write_attempt_in_readonly() {
     assert!(format!("{}", err.diagnostic)
         .contains("expecting read-only statements, detected a writing operation"));
 }
+
+#[test]
+fn test_run_analysis_with_diagnostics() {
+    use crate::vm::analysis::run_analysis_with_diagnostics;
+    use crate::vm::ast::parse;
+    use crate::vm::ast::span_locator::SpanLocator;
+    use crate::vm::database::MemoryBackingStore;
+    use crate::vm::diagnostic::Level;
+    use crate::vm::types::QualifiedContractIdentifier;
+
+    let contract = "(define-private (broken) (+ 1 u1))
+        (define-read-only (writes) (begin (var-set v 1) true))
+        (define-data-var v int 0)
+        (define-public (uses-broken) (ok (broken)))
+        (define-public (also-broken) (ok (- true)))
+        (define-read-only (unused-result) (begin (is-ok (ok 1)) (writes)))";
+    let contract_id = QualifiedContractIdentifier::transient();
+    let expressions = parse(
+        &contract_id,
+        contract,
+        ClarityVersion::Clarity2,
+        StacksEpochId::Epoch25,
+    )
+    .unwrap();
+    let mut marf = MemoryBackingStore::new();
+    let (analysis, diagnostics) = run_analysis_with_diagnostics(
+        &contract_id,
+        &expressions,
+        &mut marf.as_analysis_db(),
+        StacksEpochId::Epoch25,
+        ClarityVersion::Clarity2,
+        &SpanLocator::new(contract, &expressions),
+    );
+
+    let errors: Vec<_> = diagnostics
+        .iter()
+        .filter(|d| d.level == Level::Error)
+        .map(|d| d.message.as_str())
+        .collect();
+    let warnings: Vec<_> = diagnostics
+        .iter()
+        .filter(|d| d.level == Level::Warning)
+        .collect();
+
+    // one read-only violation and two type errors: calling `broken` does not
+    //  report an error of its own
+    assert_eq!(errors.len(), 3, "{errors:?}");
+    assert!(errors[0].contains("read-only"));
+    assert!(errors[1].contains("expecting expression of type 'int', found 'uint'"));
+    assert!(errors[2].contains("expecting expression of type 'int'"));
+    assert_eq!(warnings.len(), 1);
+
+    // diagnostics are placed whether or not this build tracks spans
+    let broken = diagnostics.iter().find(|d| d.message == errors[1]).unwrap();
+    assert_eq!(broken.spans[0].start_line, 1);
+    assert_eq!(warnings[0].spans[0].start_line, 6);
+
+    // what could be checked is still available
+    assert!(analysis.public_function_types.contains_key("uses-broken"));
+    assert!(analysis.type_map.is_some());
+}
@@ -146,6 +146,30 @@ impl TypeChecker<'_, '_> {
             }
         }
     }
+
+    /// Used by developer tools only. Like `run_pass()`, but keeps checking after
+    /// a top-level expression fails, and always stores whatever was learned about
+    /// the contract (including the type map) in `contract_analysis`.
+    pub fn run_pass_collecting(
+        epoch: &StacksEpochId,
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        build_type_map: bool,
+    ) -> Vec<CheckError> {
+        let cost_track = contract_analysis.take_contract_cost_tracker();
+        let mut command = TypeChecker::new(
+            epoch,
+            analysis_db,
+            cost_track,
+            &contract_analysis.contract_identifier,
+            &contract_analysis.clarity_version,
+            build_type_map,
+        );
+        let errors = command.run_collecting(contract_analysis);
+        let cost_track = command.into_contract_analysis(contract_analysis);
+        contract_analysis.replace_contract_cost_tracker(cost_track);
+        errors
+    }
 }
 
 pub type TypeResult = CheckResult<TypeSignature>;
@@ -1097,6 +1121,100 @@ impl<'a, 'b> TypeChecker<'a, 'b> {
         Ok(())
     }
 
+    /// Type check every top-level expression, returning one error per failing
+    /// expression instead of stopping at the first.
+    fn run_collecting(&mut self, contract_analysis: &ContractAnalysis) -> Vec<CheckError> {
+        let mut errors = vec![];
+        let mut local_context = TypingContext::new(StacksEpochId::Epoch21, self.clarity_version);
+
+        for exp in contract_analysis.expressions.iter() {
+            let result = match self.try_type_check_define(exp, &mut local_context) {
+                Ok(Some(())) => Ok(()),
+                Ok(None) => self.type_check(exp, &local_context).map(|_| ()),
+                Err(e) => {
+                    self.recover_definition(exp);
+                    Err(e)
+                }
+            };
+            if let Err(mut error) = result {
+                if !error.has_expression() {
+                    error.set_expression(exp);
+                }
+                errors.push(error);
+            }
+        }
+        errors
+    }
+
+    /// After a definition fails to check, register the defined name with
+    /// whatever type can be recovered from its declaration, so that each later
+    /// use of the name does not report an error of its own. Function return
+    /// types are unknown, and are registered as `NoType`.
+    fn recover_definition(&mut self, expression: &SymbolicExpression) {
+        self.function_return_tracker = None;
+        let Ok(Some(define_type)) = DefineFunctionsParsed::try_parse(expression) else {
+            return;
+        };
+        match define_type {
+            DefineFunctionsParsed::PrivateFunction { signature, .. }
+            | DefineFunctionsParsed::PublicFunction { signature, .. }
+            | DefineFunctionsParsed::ReadOnlyFunction { signature, .. } => {
+                let Some((function_name, args)) = signature.split_first() else {
+                    return;
+                };
+                let Some(function_name) = function_name.match_atom() else {
+                    return;
+                };
+                if self
+                    .contract_context
+                    .get_function_type(function_name)
+                    .is_some()
+                {
+                    return;
+                }
+                let Ok(args) = parse_name_type_pairs::<()>(StacksEpochId::Epoch21, args, &mut ())
+                else {
+                    return;
+                };
+                let function_type = FunctionType::Fixed(FixedFunction {
+                    args: args
+                        .into_iter()
+                        .map(|(arg_name, arg_type)| FunctionArg::new(arg_type, arg_name))
+                        .collect(),
+                    returns: TypeSignature::NoType,
+                });
+                let _ = match define_type {
+                    DefineFunctionsParsed::PublicFunction { .. } => self
+                        .contract_context
+                        .add_public_function_type(function_name.clone(), function_type),
+                    DefineFunctionsParsed::ReadOnlyFunction { .. } => self
+                        .contract_context
+                        .add_read_only_function_type(function_name.clone(), function_type),
+                    _ => self
+                        .contract_context
+                        .add_private_function_type(function_name.clone(), function_type),
+                };
+            }
+            DefineFunctionsParsed::Constant { name, .. } => {
+                let _ = self
+                    .contract_context
+                    .add_variable_type(name.clone(), TypeSignature::NoType);
+            }
+            DefineFunctionsParsed::PersistedVariable {
+                name, data_type, ..
+            } => {
+                if let Ok(data_type) =
+                    TypeSignature::parse_type_repr(StacksEpochId::Epoch21, data_type, &mut ())
+                {
+                    let _ = self
+                        .contract_context
+                        .add_persisted_variable_type(name.clone(), data_type);
+                }
+            }
+            _ => {}
+        }
+    }
+
     // Type check an expression, with an expected_type that should _admit_ the expression.
     pub fn type_check_expects(
         &mut self,
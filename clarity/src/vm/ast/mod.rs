@@ -17,6 +17,7 @@
 pub mod definition_sorter;
 pub mod expression_identifier;
 pub mod parser;
+pub mod span_locator;
 pub mod traits_resolver;
 
 pub mod errors;
@@ -0,0 +1,372 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Expressions only carry their spans with the `developer-mode` feature, which
+//! the node leaves off to keep contract ASTs small. Developer tools which need
+//! to point at source locations in any build use a `SpanLocator` instead: it
+//! lexes the source again, and matches what was written against the parsed
+//! expressions to find the span of each one by its id.
+
+use hashbrown::HashMap;
+
+use crate::vm::ast::parser::v2::lexer::token::{PlacedToken, Token};
+use crate::vm::ast::parser::v2::lexer::Lexer;
+use crate::vm::representations::{Span, SymbolicExpression, SymbolicExpressionType};
+
+/// A list, tuple or atom as it is written in the source
+struct Written {
+    span: Span,
+    /// The name of an atom written as an identifier or operator
+    name: Option<String>,
+    /// The elements of a list, or the keys and values of a tuple, in turn
+    children: Option<Vec<Written>>,
+    is_tuple: bool,
+}
+
+impl Written {
+    fn atom(span: Span, name: Option<String>) -> Written {
+        Written {
+            span,
+            name,
+            children: None,
+            is_tuple: false,
+        }
+    }
+
+    /// Could `expr` have been parsed from this?
+    fn matches(&self, expr: &SymbolicExpression) -> bool {
+        match (&expr.expr, &self.children) {
+            (SymbolicExpressionType::List(items), Some(children)) if self.is_tuple => {
+                // `{ a: 1 }` is expanded to `(tuple (a 1))`
+                children.len() % 2 == 0
+                    && items.len() == children.len() / 2 + 1
+                    && items.iter().skip(1).zip(children.chunks(2)).all(
+                        |(pair, written)| match pair.match_list() {
+                            Some([key, value]) => {
+                                written[0].matches(key) && written[1].matches(value)
+                            }
+                            _ => false,
+                        },
+                    )
+            }
+            (SymbolicExpressionType::List(items), Some(children)) => {
+                items.len() == children.len()
+                    && items
+                        .iter()
+                        .zip(children.iter())
+                        .all(|(item, written)| written.matches(item))
+            }
+            (SymbolicExpressionType::List(_), None) | (_, Some(_)) => false,
+            (SymbolicExpressionType::Atom(name), None) => self
+                .name
+                .as_deref()
+                .is_none_or(|written| written == name.as_str()),
+            (_, None) => true,
+        }
+    }
+
+    /// Record the spans of `expr` and its sub-expressions, which must match this.  Like the
+    /// parser, the `tuple` atom and key-value pairs a tuple is expanded into get no span.
+    fn record(&self, expr: &SymbolicExpression, spans: &mut HashMap<u64, Span>) {
+        spans.insert(expr.id, self.span.clone());
+        let (Some(items), Some(children)) = (expr.match_list(), self.children.as_ref()) else {
+            return;
+        };
+        if self.is_tuple {
+            for (pair, written) in items.iter().skip(1).zip(children.chunks(2)) {
+                if let Some([key, value]) = pair.match_list() {
+                    written[0].record(key, spans);
+                    written[1].record(value, spans);
+                }
+            }
+        } else {
+            for (item, written) in items.iter().zip(children.iter()) {
+                written.record(item, spans);
+            }
+        }
+    }
+}
+
+/// Reads the expressions written in a stream of tokens
+struct Reader {
+    tokens: Vec<PlacedToken>,
+    next: usize,
+}
+
+impl Reader {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next).map(|placed| &placed.token)
+    }
+
+    /// Read the next expression, if there is one before the end of the enclosing list or tuple
+    fn read(&mut self) -> Option<Written> {
+        loop {
+            let placed = self.tokens.get(self.next)?.clone();
+            self.next += 1;
+            let span = placed.span;
+            return match placed.token {
+                Token::Whitespace
+                | Token::Comment(_)
+                | Token::Placeholder(_)
+                | Token::Colon
+                | Token::Comma => continue,
+                Token::Eof | Token::Rparen | Token::Rbrace => None,
+                Token::Lparen => Some(self.read_sequence(span, Token::Rparen)),
+                Token::Lbrace => Some(self.read_sequence(span, Token::Rbrace)),
+                Token::Principal(_) | Token::Dot => {
+                    // `'SP...`, `'SP....contract`, `.contract`, and either with a `.trait`
+                    let mut span = span;
+                    let mut dots = u8::from(placed.token == Token::Dot);
+                    if dots == 1 {
+                        self.extend(&mut span);
+                    }
+                    while dots < 2 && self.peek() == Some(&Token::Dot) {
+                        self.next += 1;
+                        self.extend(&mut span);
+                        dots += 1;
+                    }
+                    Some(Written::atom(span, None))
+                }
+                Token::Ident(name) => Some(Written::atom(span, Some(name))),
+                Token::Plus
+                | Token::Minus
+                | Token::Multiply
+                | Token::Divide
+                | Token::Less
+                | Token::LessEqual
+                | Token::Greater
+                | Token::GreaterEqual => Some(Written::atom(span, Some(placed.token.to_string()))),
+                Token::Int(_)
+                | Token::Uint(_)
+                | Token::AsciiString(_)
+                | Token::Utf8String(_)
+                | Token::Bytes(_)
+                | Token::TraitIdent(_) => Some(Written::atom(span, None)),
+            };
+        }
+    }
+
+    /// Extend `span` over the next token, if it is an identifier
+    fn extend(&mut self, span: &mut Span) {
+        if let Some(PlacedToken {
+            span: name_span,
+            token: Token::Ident(_),
+        }) = self.tokens.get(self.next)
+        {
+            span.end_line = name_span.end_line;
+            span.end_column = name_span.end_column;
+            self.next += 1;
+        }
+    }
+
+    /// Read the elements of a list or tuple, up to its closing token
+    fn read_sequence(&mut self, mut span: Span, closing: Token) -> Written {
+        let mut children = vec![];
+        while let Some(child) = self.read() {
+            children.push(child);
+        }
+        if let Some(last) = self.next.checked_sub(1).and_then(|i| self.tokens.get(i)) {
+            if last.token == closing {
+                span.end_line = last.span.end_line;
+                span.end_column = last.span.end_column;
+            }
+        }
+        Written {
+            span,
+            name: None,
+            children: Some(children),
+            is_tuple: closing == Token::Rbrace,
+        }
+    }
+}
+
+/// The spans of a contract's expressions, by expression id
+#[derive(Debug, Clone, Default)]
+pub struct SpanLocator {
+    spans: HashMap<u64, Span>,
+}
+
+impl SpanLocator {
+    /// Locate `expressions`, as parsed from `source_code`.  If this build tracks spans, the
+    /// expressions' own spans are used instead.
+    pub fn new(source_code: &str, expressions: &[SymbolicExpression]) -> SpanLocator {
+        if Span::TRACKED {
+            return SpanLocator::default();
+        }
+        SpanLocator::locate(source_code, expressions)
+    }
+
+    fn locate(source_code: &str, expressions: &[SymbolicExpression]) -> SpanLocator {
+        let mut spans = HashMap::new();
+        let Ok(mut lexer) = Lexer::new(source_code, false) else {
+            return SpanLocator { spans };
+        };
+        let mut tokens = vec![];
+        loop {
+            // outside of fail-fast mode, the lexer reports errors as diagnostics instead
+            let Ok(token) = lexer.read_token() else {
+                return SpanLocator { spans };
+            };
+            let at_end = token.token == Token::Eof;
+            tokens.push(token);
+            if at_end {
+                break;
+            }
+        }
+
+        let mut reader = Reader { tokens, next: 0 };
+        let mut written = vec![];
+        while reader.peek().is_some_and(|token| *token != Token::Eof) {
+            // a stray closing token ends no expression: skip it
+            if let Some(expr) = reader.read() {
+                written.push(expr);
+            }
+        }
+
+        // top-level expressions are sorted by their dependencies, so they are matched up by
+        // their contents rather than their order
+        let mut matched = vec![false; written.len()];
+        for expr in expressions.iter() {
+            let found = written
+                .iter()
+                .zip(matched.iter_mut())
+                .find(|(written, matched)| !**matched && written.matches(expr));
+            if let Some((written, matched)) = found {
+                *matched = true;
+                written.record(expr, &mut spans);
+            }
+        }
+        SpanLocator { spans }
+    }
+
+    /// The span of `expr`, or `Span::ZERO` if it could not be located
+    pub fn span(&self, expr: &SymbolicExpression) -> Span {
+        if Span::TRACKED {
+            return expr.span().clone();
+        }
+        self.spans.get(&expr.id).cloned().unwrap_or(Span::ZERO)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use stacks_common::types::StacksEpochId;
+
+    use super::SpanLocator;
+    use crate::vm::ast::parse;
+    use crate::vm::representations::{Span, SymbolicExpression};
+    use crate::vm::types::QualifiedContractIdentifier;
+    use crate::vm::ClarityVersion;
+
+    const CONTRACT: &str = "(define-public (transfer (to principal))
+  ;; a comment
+  (begin
+    (print { to: to, from: .other.some-trait, at: 'ST000000000000000000002AMW42H })
+    (ok (+ u1 (* 2 3)))))
+(define-constant OWNER 'ST000000000000000000002AMW42H.owner)
+(define-private (helper) (>= 1 OWNER-ID))
+(define-constant OWNER-ID 1)";
+
+    fn parse_contract() -> Vec<SymbolicExpression> {
+        parse(
+            &QualifiedContractIdentifier::transient(),
+            CONTRACT,
+            ClarityVersion::Clarity2,
+            StacksEpochId::Epoch25,
+        )
+        .unwrap()
+    }
+
+    /// Find the `define-constant` of `name`
+    fn find_definition<'a>(
+        expressions: &'a [SymbolicExpression],
+        name: &str,
+    ) -> &'a SymbolicExpression {
+        expressions
+            .iter()
+            .find(|expr| {
+                expr.match_list().unwrap()[1]
+                    .match_atom()
+                    .is_some_and(|atom| atom.as_str() == name)
+            })
+            .unwrap()
+    }
+
+    fn span(start_line: u32, start_column: u32, end_line: u32, end_column: u32) -> Span {
+        Span {
+            start_line,
+            start_column,
+            end_line,
+            end_column,
+        }
+    }
+
+    #[test]
+    fn test_locate_spans() {
+        let expressions = parse_contract();
+        let spans = SpanLocator::locate(CONTRACT, &expressions);
+
+        // `OWNER-ID` is sorted ahead of `helper`, which uses it
+        let owner_id = find_definition(&expressions, "OWNER-ID");
+        assert_eq!(spans.span(owner_id), span(8, 1, 8, 28));
+
+        let transfer = &expressions[0];
+        assert_eq!(spans.span(transfer), span(1, 1, 5, 25));
+        let body = &transfer.match_list().unwrap()[2];
+        assert_eq!(spans.span(body), span(3, 3, 5, 24));
+
+        let print = &body.match_list().unwrap()[1];
+        let tuple = &print.match_list().unwrap()[1];
+        assert_eq!(spans.span(tuple), span(4, 12, 4, 82));
+        let fields = tuple.match_list().unwrap();
+        assert_eq!(spans.span(&fields[0]), Span::ZERO);
+        assert_eq!(spans.span(&fields[1]), Span::ZERO);
+        let from = &fields[2].match_list().unwrap()[1];
+        assert_eq!(spans.span(from), span(4, 28, 4, 44));
+        let at = &fields[3].match_list().unwrap()[1];
+        assert_eq!(spans.span(at), span(4, 51, 4, 80));
+
+        let owner = find_definition(&expressions, "OWNER");
+        assert_eq!(
+            spans.span(&owner.match_list().unwrap()[2]),
+            span(6, 24, 6, 59)
+        );
+    }
+
+    #[test]
+    fn test_locate_spans_of_unmatched_source() {
+        let expressions = parse_contract();
+        let spans = SpanLocator::locate("(define-constant OTHER 1)", &expressions);
+        assert!(spans.spans.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "developer-mode")]
+    fn test_located_spans_match_tracked_spans() {
+        fn check(spans: &SpanLocator, exprs: &[SymbolicExpression]) {
+            for expr in exprs.iter() {
+                assert_eq!(
+                    spans.spans.get(&expr.id).unwrap_or(&Span::ZERO),
+                    expr.span()
+                );
+                if let Some(items) = expr.match_list() {
+                    check(spans, items);
+                }
+            }
+        }
+        let expressions = parse_contract();
+        check(&SpanLocator::locate(CONTRACT, &expressions), &expressions);
+    }
+}
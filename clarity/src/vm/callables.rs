@@ -368,6 +368,10 @@ impl DefinedFunction {
         &self.arg_types
     }
 
+    pub fn get_body(&self) -> &SymbolicExpression {
+        &self.body
+    }
+
     pub fn canonicalize_types(&mut self, epoch: &StacksEpochId) {
         for i in 0..self.arguments.len() {
             self.arg_types[i] = self.arg_types[i].canonicalize(epoch);
@@ -3,14 +3,89 @@ use std::fs::File;
 use std::io::Write;
 
 use hashbrown::{HashMap, HashSet};
+use stacks_common::util::get_epoch_time_secs;
 
 use super::functions::define::DefineFunctionsParsed;
+use super::functions::NativeFunctions;
 use super::EvalHook;
+use crate::vm::errors::{Error, ShortReturnType};
 use crate::vm::types::QualifiedContractIdentifier;
 use crate::vm::SymbolicExpression;
 
+/// An expression identified by its contract and its id within that contract
+type ContractExpression = (QualifiedContractIdentifier, u64);
+
 pub struct CoverageReporter {
     executed_lines: HashMap<QualifiedContractIdentifier, HashMap<u32, u64>>,
+    /// Times each branch was taken, by contract, and by (block, branch)
+    executed_branches: HashMap<QualifiedContractIdentifier, HashMap<(u64, u32), u64>>,
+    /// Expressions whose evaluation means a branch was taken, mapped to the
+    ///  (block, branch) they belong to. Filled in as branching expressions
+    ///  are evaluated.
+    branch_targets: HashMap<ContractExpression, (u64, u32)>,
+    /// The inputs of short-circuiting expressions, mapped to the block they belong to
+    short_circuit_inputs: HashMap<ContractExpression, u64>,
+    short_circuit_blocks: HashSet<ContractExpression>,
+    /// Short-circuiting blocks whose input was evaluated without an error. If
+    ///  the block then returns early, it is the block itself that short-circuited.
+    short_circuit_ready: HashSet<ContractExpression>,
+}
+
+/// How the branches of a branching expression are told apart
+enum Branching<'a> {
+    /// Each of these expressions is a branch, taken when it is evaluated:
+    ///  the arms of `if` and `match`, and the operands of `and` and `or`
+    Targets(Vec<&'a SymbolicExpression>),
+    /// Branch 0 is taken when the expression completes, and branch 1 when
+    ///  it returns early after `input` was evaluated: `asserts!`, `unwrap!`,
+    ///  `unwrap-err!` and `try!`
+    ShortCircuit { input: &'a SymbolicExpression },
+}
+
+impl Branching<'_> {
+    fn classify(expr: &SymbolicExpression) -> Option<Branching<'_>> {
+        let (head, args) = expr.match_list()?.split_first()?;
+        let function = NativeFunctions::lookup_by_name(head.match_atom()?)?;
+        let branching = match (function, args) {
+            (NativeFunctions::If, [_condition, then_branch, else_branch]) => {
+                Branching::Targets(vec![then_branch, else_branch])
+            }
+            (NativeFunctions::Match, [_input, _some_name, some_branch, none_branch]) => {
+                Branching::Targets(vec![some_branch, none_branch])
+            }
+            (NativeFunctions::Match, [_input, _ok_name, ok_branch, _err_name, err_branch]) => {
+                Branching::Targets(vec![ok_branch, err_branch])
+            }
+            (NativeFunctions::And | NativeFunctions::Or, operands) if !operands.is_empty() => {
+                Branching::Targets(operands.iter().collect())
+            }
+            (
+                NativeFunctions::Asserts
+                | NativeFunctions::UnwrapRet
+                | NativeFunctions::UnwrapErrRet,
+                [input, _],
+            ) => Branching::ShortCircuit { input },
+            (NativeFunctions::TryRet, [input]) => Branching::ShortCircuit { input },
+            _ => return None,
+        };
+        Some(branching)
+    }
+
+    fn branch_count(&self) -> u32 {
+        match self {
+            Branching::Targets(targets) => targets.len() as u32,
+            Branching::ShortCircuit { .. } => 2,
+        }
+    }
+}
+
+/// A branching expression, as registered in a `.clarcovref` file
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+struct BranchBlock {
+    line: u32,
+    /// The id of the branching expression
+    block: u64,
+    branches: u32,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -18,11 +93,90 @@ struct ContractFileInfo {
     contract: String,
     src_file: String,
     executable_lines: Vec<u32>,
+    #[serde(default)]
+    branch_blocks: Vec<BranchBlock>,
 }
 
 #[derive(Serialize, Deserialize)]
 struct CoverageFileInfo {
     coverage: HashMap<String, Vec<(u32, u64)>>,
+    /// (block, branch, times taken), by contract
+    #[serde(default)]
+    branch_coverage: HashMap<String, Vec<(u64, u32, u64)>>,
+}
+
+/// The coverage of one contract, summed over every coverage file
+struct ContractCoverage {
+    info: ContractFileInfo,
+    line_hits: BTreeMap<u32, u64>,
+    branch_hits: HashMap<(u64, u32), u64>,
+}
+
+impl ContractCoverage {
+    fn line_count(&self, line: u32) -> u64 {
+        self.line_hits.get(&line).cloned().unwrap_or(0)
+    }
+
+    fn branch_count(&self, block: u64, branch: u32) -> u64 {
+        self.branch_hits.get(&(block, branch)).cloned().unwrap_or(0)
+    }
+
+    fn lines_hit(&self) -> usize {
+        self.info
+            .executable_lines
+            .iter()
+            .filter(|line| self.line_count(**line) > 0)
+            .count()
+    }
+
+    /// Each branch of each block, with the number of times it was taken
+    fn branches(&self) -> impl Iterator<Item = (&BranchBlock, u32, u64)> {
+        self.info.branch_blocks.iter().flat_map(move |block| {
+            (0..block.branches)
+                .map(move |branch| (block, branch, self.branch_count(block.block, branch)))
+        })
+    }
+
+    fn branches_found(&self) -> usize {
+        self.branches().count()
+    }
+
+    fn branches_hit(&self) -> usize {
+        self.branches().filter(|(_, _, count)| *count > 0).count()
+    }
+
+    /// The (hit, found) branch counts of the blocks on a line
+    fn line_branches(&self, line: u32) -> (usize, usize) {
+        self.branches()
+            .filter(|(block, _, _)| block.line == line)
+            .fold((0, 0), |(hit, found), (_, _, count)| {
+                (hit + usize::from(count > 0), found + 1)
+            })
+    }
+}
+
+fn rate(hit: usize, found: usize) -> f64 {
+    if found == 0 {
+        1.0
+    } else {
+        hit as f64 / found as f64
+    }
+}
+
+/// Escape text for inclusion in XML or HTML
+fn escape_markup(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
 }
 
 impl Default for CoverageReporter {
@@ -35,6 +189,79 @@ impl CoverageReporter {
     pub fn new() -> CoverageReporter {
         CoverageReporter {
             executed_lines: HashMap::new(),
+            executed_branches: HashMap::new(),
+            branch_targets: HashMap::new(),
+            short_circuit_inputs: HashMap::new(),
+            short_circuit_blocks: HashSet::new(),
+            short_circuit_ready: HashSet::new(),
+        }
+    }
+
+    fn count_branch(&mut self, contract: &QualifiedContractIdentifier, block: u64, branch: u32) {
+        *self
+            .executed_branches
+            .entry(contract.clone())
+            .or_default()
+            .entry((block, branch))
+            .or_insert(0) += 1;
+    }
+
+    /// Track branches as an expression begins evaluation
+    fn report_branch_eval(
+        &mut self,
+        expr: &SymbolicExpression,
+        contract: &QualifiedContractIdentifier,
+    ) {
+        let key = (contract.clone(), expr.id);
+        if let Some((block, branch)) = self.branch_targets.get(&key).cloned() {
+            self.count_branch(contract, block, branch);
+        }
+        match Branching::classify(expr) {
+            Some(Branching::Targets(targets)) => {
+                for (branch, target) in targets.into_iter().enumerate() {
+                    self.branch_targets
+                        .insert((contract.clone(), target.id), (expr.id, branch as u32));
+                }
+            }
+            Some(Branching::ShortCircuit { input }) => {
+                self.short_circuit_inputs
+                    .insert((contract.clone(), input.id), expr.id);
+                self.short_circuit_blocks.insert(key);
+            }
+            None => {}
+        }
+    }
+
+    /// Track short-circuiting branches as an expression finishes evaluation
+    fn report_branch_result(
+        &mut self,
+        expr: &SymbolicExpression,
+        contract: &QualifiedContractIdentifier,
+        result: &Result<crate::vm::Value, Error>,
+    ) {
+        if let Some(block) = self
+            .short_circuit_inputs
+            .get(&(contract.clone(), expr.id))
+            .cloned()
+        {
+            if result.is_ok() {
+                self.short_circuit_ready.insert((contract.clone(), block));
+            }
+        }
+        let key = (contract.clone(), expr.id);
+        if !self.short_circuit_blocks.contains(&key) {
+            return;
+        }
+        let ready = self.short_circuit_ready.remove(&key);
+        match result {
+            Ok(_) => self.count_branch(contract, expr.id, 0),
+            Err(Error::ShortReturn(ShortReturnType::ExpectedValue(_)))
+            | Err(Error::ShortReturn(ShortReturnType::AssertionFailed(_)))
+                if ready =>
+            {
+                self.count_branch(contract, expr.id, 1)
+            }
+            Err(_) => {}
         }
     }
 
@@ -90,7 +317,22 @@ impl CoverageReporter {
             coverage.insert(contract.to_string(), executed_lines);
         }
 
-        let out = CoverageFileInfo { coverage };
+        let mut branch_coverage = HashMap::with_capacity(self.executed_branches.len());
+        for (contract, execution_map) in self.executed_branches.iter() {
+            let mut executed_branches = execution_map
+                .iter()
+                .map(|((block, branch), count)| (*block, *branch, *count))
+                .collect::<Vec<_>>();
+
+            executed_branches.sort();
+
+            branch_coverage.insert(contract.to_string(), executed_branches);
+        }
+
+        let out = CoverageFileInfo {
+            coverage,
+            branch_coverage,
+        };
         if let Err(e) = serde_json::to_writer(f, &out) {
             error!(
                 "Failed to serialize JSON to coverage file {}: {}",
@@ -103,9 +345,9 @@ impl CoverageReporter {
         Ok(())
     }
 
-    fn executable_lines(exprs: &[SymbolicExpression]) -> Vec<u32> {
-        let mut lines = vec![];
-        let mut lines_seen = HashSet::new();
+    /// Every expression of a contract that can be evaluated
+    fn executable_expressions(exprs: &[SymbolicExpression]) -> Vec<&SymbolicExpression> {
+        let mut executable = vec![];
         for expression in exprs.iter() {
             let mut frontier = vec![expression];
             while let Some(cur_expr) = frontier.pop() {
@@ -148,15 +390,26 @@ impl CoverageReporter {
                 }
 
                 if let Some(children) = cur_expr.match_list() {
-                    // don't count list expressions as a whole, just their children
                     frontier.extend(children);
-                } else {
-                    let line = cur_expr.span().start_line;
-                    if !lines_seen.contains(&line) {
-                        lines_seen.insert(line);
-                        lines.push(line);
-                    }
                 }
+                executable.push(cur_expr);
+            }
+        }
+        executable
+    }
+
+    fn executable_lines(exprs: &[SymbolicExpression]) -> Vec<u32> {
+        let mut lines = vec![];
+        let mut lines_seen = HashSet::new();
+        for expr in CoverageReporter::executable_expressions(exprs) {
+            // don't count list expressions as a whole, just their children
+            if expr.match_list().is_some() {
+                continue;
+            }
+            let line = expr.span().start_line;
+            if !lines_seen.contains(&line) {
+                lines_seen.insert(line);
+                lines.push(line);
             }
         }
 
@@ -164,6 +417,22 @@ impl CoverageReporter {
         lines
     }
 
+    fn branch_blocks(exprs: &[SymbolicExpression]) -> Vec<BranchBlock> {
+        let mut blocks: Vec<_> = CoverageReporter::executable_expressions(exprs)
+            .into_iter()
+            .filter_map(|expr| {
+                let branching = Branching::classify(expr)?;
+                Some(BranchBlock {
+                    line: expr.span().start_line,
+                    block: expr.id,
+                    branches: branching.branch_count(),
+                })
+            })
+            .collect();
+        blocks.sort_by_key(|block| (block.line, block.block));
+        blocks
+    }
+
     pub fn register_src_file<P: AsRef<std::path::Path> + Copy>(
         contract: &QualifiedContractIdentifier,
         src_file_name: &str,
@@ -173,11 +442,13 @@ impl CoverageReporter {
         let f = File::create(filename)?;
 
         let executable_lines = CoverageReporter::executable_lines(ast);
+        let branch_blocks = CoverageReporter::branch_blocks(ast);
 
         let json = ContractFileInfo {
             contract: contract.to_string(),
             src_file: src_file_name.to_string(),
             executable_lines,
+            branch_blocks,
         };
 
         if let Err(e) = serde_json::to_writer(f, &json) {
@@ -191,43 +462,262 @@ impl CoverageReporter {
         Ok(())
     }
 
-    pub fn produce_lcov<P: AsRef<std::path::Path>>(
-        out_filename: &str,
+    /// Load the registered contracts, and sum their coverage over every coverage file
+    fn merge_coverage<P: AsRef<std::path::Path>>(
         register_files: &[P],
         coverage_files: &[P],
-    ) -> std::io::Result<()> {
-        let mut out = File::create(out_filename)?;
+    ) -> std::io::Result<Vec<ContractCoverage>> {
+        let mut coverages = Vec::with_capacity(coverage_files.len());
+        for coverage_filename in coverage_files.iter() {
+            let cov_reader = File::open(coverage_filename)?;
+            let coverage: CoverageFileInfo = serde_json::from_reader(cov_reader)?;
+            coverages.push(coverage);
+        }
 
+        let mut contracts = Vec::with_capacity(register_files.len());
         for contract_filename in register_files.iter() {
             let reader = File::open(contract_filename)?;
             let info: ContractFileInfo = serde_json::from_reader(reader)?;
-            let mut summed_coverage = BTreeMap::new();
-            for coverage_filename in coverage_files.iter() {
-                let cov_reader = File::open(coverage_filename)?;
-                let coverage: CoverageFileInfo = serde_json::from_reader(cov_reader)?;
+            let mut line_hits = BTreeMap::new();
+            let mut branch_hits = HashMap::new();
+            for coverage in coverages.iter() {
                 if let Some(contract_coverage) = coverage.coverage.get(&info.contract) {
                     for (line, count) in contract_coverage.iter() {
-                        if let Some(line_count) = summed_coverage.get_mut(line) {
-                            *line_count += *count;
-                        } else {
-                            summed_coverage.insert(*line, *count);
-                        }
+                        *line_hits.entry(*line).or_insert(0) += *count;
+                    }
+                }
+                if let Some(contract_coverage) = coverage.branch_coverage.get(&info.contract) {
+                    for (block, branch, count) in contract_coverage.iter() {
+                        *branch_hits.entry((*block, *branch)).or_insert(0) += *count;
                     }
                 }
             }
+            contracts.push(ContractCoverage {
+                info,
+                line_hits,
+                branch_hits,
+            });
+        }
+        Ok(contracts)
+    }
+
+    pub fn produce_lcov<P: AsRef<std::path::Path>>(
+        out_filename: &str,
+        register_files: &[P],
+        coverage_files: &[P],
+    ) -> std::io::Result<()> {
+        let mut out = File::create(out_filename)?;
+
+        for contract in CoverageReporter::merge_coverage(register_files, coverage_files)? {
+            let info = &contract.info;
             writeln!(out, "TN:{}", &info.contract)?;
             writeln!(out, "SF:{}", &info.src_file)?;
             for line in info.executable_lines.iter() {
-                let count = summed_coverage.get(line).cloned().unwrap_or(0);
-                writeln!(out, "DA:{},{}", line, count)?;
+                writeln!(out, "DA:{},{}", line, contract.line_count(*line))?;
             }
-            writeln!(out, "LH:{}", summed_coverage.len())?;
+            writeln!(out, "LH:{}", contract.line_hits.len())?;
             writeln!(out, "LF:{}", &info.executable_lines.len())?;
+            for block in info.branch_blocks.iter() {
+                // `-` marks the branches of a block that was never reached
+                let reached = (0..block.branches)
+                    .any(|branch| contract.branch_count(block.block, branch) > 0);
+                for branch in 0..block.branches {
+                    let taken = if reached {
+                        contract.branch_count(block.block, branch).to_string()
+                    } else {
+                        "-".to_string()
+                    };
+                    writeln!(
+                        out,
+                        "BRDA:{},{},{},{}",
+                        block.line, block.block, branch, taken
+                    )?;
+                }
+            }
+            writeln!(out, "BRF:{}", contract.branches_found())?;
+            writeln!(out, "BRH:{}", contract.branches_hit())?;
             writeln!(out, "end_of_record")?;
         }
 
         Ok(())
     }
+
+    /// Write a Cobertura XML report, with one class per contract
+    pub fn produce_cobertura<P: AsRef<std::path::Path>>(
+        out_filename: &str,
+        register_files: &[P],
+        coverage_files: &[P],
+    ) -> std::io::Result<()> {
+        let contracts = CoverageReporter::merge_coverage(register_files, coverage_files)?;
+        let mut out = File::create(out_filename)?;
+
+        let lines_valid: usize = contracts
+            .iter()
+            .map(|c| c.info.executable_lines.len())
+            .sum();
+        let lines_covered: usize = contracts.iter().map(|c| c.lines_hit()).sum();
+        let branches_valid: usize = contracts.iter().map(|c| c.branches_found()).sum();
+        let branches_covered: usize = contracts.iter().map(|c| c.branches_hit()).sum();
+        let line_rate = rate(lines_covered, lines_valid);
+        let branch_rate = rate(branches_covered, branches_valid);
+
+        writeln!(out, r#"<?xml version="1.0" ?>"#)?;
+        writeln!(
+            out,
+            r#"<!DOCTYPE coverage SYSTEM "http://cobertura.sourceforge.net/xml/coverage-04.dtd">"#
+        )?;
+        writeln!(
+            out,
+            r#"<coverage line-rate="{line_rate:.4}" branch-rate="{branch_rate:.4}" lines-covered="{lines_covered}" lines-valid="{lines_valid}" branches-covered="{branches_covered}" branches-valid="{branches_valid}" complexity="0" version="clarity" timestamp="{}">"#,
+            get_epoch_time_secs()
+        )?;
+        writeln!(out, "  <sources>\n    <source>.</source>\n  </sources>")?;
+        writeln!(out, "  <packages>")?;
+        writeln!(
+            out,
+            r#"    <package name="contracts" line-rate="{line_rate:.4}" branch-rate="{branch_rate:.4}" complexity="0">"#
+        )?;
+        writeln!(out, "      <classes>")?;
+        for contract in contracts.iter() {
+            let info = &contract.info;
+            writeln!(
+                out,
+                r#"        <class name="{}" filename="{}" line-rate="{:.4}" branch-rate="{:.4}" complexity="0">"#,
+                escape_markup(&info.contract),
+                escape_markup(&info.src_file),
+                rate(contract.lines_hit(), info.executable_lines.len()),
+                rate(contract.branches_hit(), contract.branches_found()),
+            )?;
+            writeln!(out, "          <methods/>")?;
+            writeln!(out, "          <lines>")?;
+            for line in info.executable_lines.iter() {
+                let hits = contract.line_count(*line);
+                match contract.line_branches(*line) {
+                    (_, 0) => writeln!(
+                        out,
+                        r#"            <line number="{line}" hits="{hits}" branch="false"/>"#
+                    )?,
+                    (hit, found) => writeln!(
+                        out,
+                        r#"            <line number="{line}" hits="{hits}" branch="true" condition-coverage="{}% ({hit}/{found})"/>"#,
+                        hit * 100 / found
+                    )?,
+                }
+            }
+            writeln!(out, "          </lines>")?;
+            writeln!(out, "        </class>")?;
+        }
+        writeln!(out, "      </classes>")?;
+        writeln!(out, "    </package>")?;
+        writeln!(out, "  </packages>")?;
+        writeln!(out, "</coverage>")?;
+        Ok(())
+    }
+
+    /// Write a self-contained HTML report, with a summary table and the
+    ///  annotated source of each contract
+    pub fn produce_html<P: AsRef<std::path::Path>>(
+        out_filename: &str,
+        register_files: &[P],
+        coverage_files: &[P],
+    ) -> std::io::Result<()> {
+        let contracts = CoverageReporter::merge_coverage(register_files, coverage_files)?;
+        let mut out = File::create(out_filename)?;
+
+        writeln!(
+            out,
+            r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Clarity coverage report</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; }}
+table {{ border-collapse: collapse; }}
+th, td {{ padding: 0.2em 0.8em; text-align: left; }}
+.summary td, .summary th {{ border: 1px solid #ccc; }}
+.source {{ font-family: monospace; white-space: pre; }}
+.source td {{ padding: 0 0.5em; }}
+.number {{ color: #888; text-align: right; }}
+.covered {{ background: #dfd; }}
+.uncovered {{ background: #fdd; }}
+.partial {{ background: #ffc; }}
+</style>
+</head>
+<body>
+<h1>Clarity coverage report</h1>
+<table class="summary">
+<tr><th>Contract</th><th>Lines</th><th>Branches</th></tr>"#
+        )?;
+        for (index, contract) in contracts.iter().enumerate() {
+            writeln!(
+                out,
+                r##"<tr><td><a href="#contract-{index}">{}</a></td><td>{}/{} ({:.1}%)</td><td>{}/{} ({:.1}%)</td></tr>"##,
+                escape_markup(&contract.info.contract),
+                contract.lines_hit(),
+                contract.info.executable_lines.len(),
+                100.0 * rate(contract.lines_hit(), contract.info.executable_lines.len()),
+                contract.branches_hit(),
+                contract.branches_found(),
+                100.0 * rate(contract.branches_hit(), contract.branches_found()),
+            )?;
+        }
+        writeln!(out, "</table>")?;
+
+        for (index, contract) in contracts.iter().enumerate() {
+            let info = &contract.info;
+            writeln!(
+                out,
+                r#"<h2 id="contract-{index}">{}</h2>"#,
+                escape_markup(&info.contract)
+            )?;
+            let Ok(source) = std::fs::read_to_string(&info.src_file) else {
+                writeln!(
+                    out,
+                    "<p>Source file {} is unavailable.</p>",
+                    escape_markup(&info.src_file)
+                )?;
+                continue;
+            };
+            writeln!(out, r#"<p>{}</p>"#, escape_markup(&info.src_file))?;
+            writeln!(
+                out,
+                r#"<table class="source"><tr><th>Line</th><th>Hits</th><th>Branches</th><th></th></tr>"#
+            )?;
+            for (line, text) in (1..).zip(source.lines()) {
+                let executable = info.executable_lines.contains(&line);
+                let hits = contract.line_count(line);
+                let (branches_hit, branches_found) = contract.line_branches(line);
+                let class = if !executable && branches_found == 0 {
+                    ""
+                } else if hits == 0 && branches_hit == 0 {
+                    "uncovered"
+                } else if branches_hit < branches_found {
+                    "partial"
+                } else {
+                    "covered"
+                };
+                let hits = if executable {
+                    hits.to_string()
+                } else {
+                    String::new()
+                };
+                let branches = if branches_found > 0 {
+                    format!("{branches_hit}/{branches_found}")
+                } else {
+                    String::new()
+                };
+                writeln!(
+                    out,
+                    r#"<tr class="{class}"><td class="number">{line}</td><td class="number">{hits}</td><td class="number">{branches}</td><td>{}</td></tr>"#,
+                    escape_markup(text)
+                )?;
+            }
+            writeln!(out, "</table>")?;
+        }
+        writeln!(out, "</body>\n</html>")?;
+        Ok(())
+    }
 }
 
 impl EvalHook for CoverageReporter {
@@ -238,15 +728,17 @@ impl EvalHook for CoverageReporter {
         expr: &SymbolicExpression,
     ) {
         self.report_eval(expr, &env.contract_context.contract_identifier);
+        self.report_branch_eval(expr, &env.contract_context.contract_identifier);
     }
 
     fn did_finish_eval(
         &mut self,
-        _env: &mut crate::vm::Environment,
+        env: &mut crate::vm::Environment,
         _context: &crate::vm::LocalContext,
-        _expr: &SymbolicExpression,
-        _res: &core::result::Result<crate::vm::Value, crate::vm::errors::Error>,
+        expr: &SymbolicExpression,
+        res: &core::result::Result<crate::vm::Value, crate::vm::errors::Error>,
     ) {
+        self.report_branch_result(expr, &env.contract_context.contract_identifier, res);
     }
 
     fn did_complete(
@@ -24,7 +24,7 @@ use stacks_common::types::chainstate::{
     TrieHash, VRFSeed,
 };
 use stacks_common::types::{StacksEpoch as GenericStacksEpoch, StacksEpochId};
-use stacks_common::util::hash::{to_hex, Hash160, Sha512Trunc256Sum};
+use stacks_common::util::hash::{Hash160, Sha512Trunc256Sum};
 
 use super::clarity_store::SpecialCaseHandler;
 use super::key_value_wrapper::ValueResult;
@@ -36,7 +36,7 @@ use crate::vm::database::structures::{
     ClarityDeserializable, ClaritySerializable, DataMapMetadata, DataVariableMetadata,
     FungibleTokenMetadata, NonFungibleTokenMetadata, STXBalance, STXBalanceSnapshot,
 };
-use crate::vm::database::{ClarityBackingStore, RollbackWrapper};
+use crate::vm::database::{ClarityBackingStore, RollbackWrapper, StoredValue};
 use crate::vm::errors::{
     CheckErrors, Error, InterpreterError, InterpreterResult as Result, RuntimeErrorType,
 };
@@ -502,7 +502,7 @@ impl<'a> ClarityDatabase<'a> {
     }
 
     pub fn put_data<T: ClaritySerializable>(&mut self, key: &str, value: &T) -> Result<()> {
-        self.store.put_data(key, &value.serialize())
+        self.store.put_data(key, value.serialize_stored())
     }
 
     /// Like `put()`, but returns the serialized byte size of the stored value
@@ -511,9 +511,13 @@ impl<'a> ClarityDatabase<'a> {
         key: &str,
         value: &T,
     ) -> Result<u64> {
-        let serialized = value.serialize();
-        self.store.put_data(key, &serialized)?;
-        Ok(byte_len_of_serialization(&serialized))
+        let serialized = value.serialize_stored();
+        let size = match &serialized {
+            StoredValue::Bytes(bytes) => bytes.len() as u64,
+            StoredValue::Text(text) => byte_len_of_serialization(text),
+        };
+        self.store.put_data(key, serialized)?;
+        Ok(size)
     }
 
     pub fn get_data<T>(&mut self, key: &str) -> Result<Option<T>>
@@ -563,8 +567,7 @@ impl<'a> ClarityDatabase<'a> {
         };
 
         let size = serialized.len() as u64;
-        let hex_serialized = to_hex(serialized.as_slice());
-        self.store.put_data(key, &hex_serialized)?;
+        self.store.put_data(key, StoredValue::Bytes(serialized))?;
 
         Ok(pre_sanitized_size.unwrap_or(size))
     }
@@ -1381,8 +1384,7 @@ impl ClarityDatabase<'_> {
             InterpreterError::Expect("BUG: valid tuple representation did not serialize".into())
         })?;
 
-        let value_str = to_hex(&value_bytes);
-        self.put_data(&key, &value_str)
+        self.store.put_data(&key, StoredValue::Bytes(value_bytes))
     }
 
     pub fn get_microblock_pubkey_hash_height(
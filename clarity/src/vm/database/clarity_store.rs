@@ -14,6 +14,8 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::borrow::Cow;
+
 #[cfg(feature = "canonical")]
 use rusqlite::Connection;
 use stacks_common::types::chainstate::{StacksBlockId, TrieHash};
@@ -31,6 +33,25 @@ use crate::vm::Value;
 
 pub struct NullBackingStore {}
 
+/// A value of the committed datastore
+#[derive(Debug, Clone, PartialEq)]
+pub enum StoredValue {
+    /// Serialized bytes, such as those of a Clarity value, which are written as hex
+    Bytes(Vec<u8>),
+    /// Anything else, such as JSON, which is written as it is
+    Text(String),
+}
+
+impl StoredValue {
+    /// The string form of this value, which is what the MARF commits to
+    pub fn to_serialized(&self) -> Cow<'_, str> {
+        match self {
+            StoredValue::Bytes(bytes) => Cow::Owned(to_hex(bytes)),
+            StoredValue::Text(text) => Cow::Borrowed(text),
+        }
+    }
+}
+
 pub type SpecialCaseHandler = &'static dyn Fn(
     // the current Clarity global context
     &mut GlobalContext,
@@ -53,9 +74,21 @@ pub type SpecialCaseHandler = &'static dyn Fn(
 //    attempt to continue processing in the event of an unexpected storage error.
 pub trait ClarityBackingStore {
     /// put K-V data into the committed datastore
-    fn put_all_data(&mut self, items: Vec<(String, String)>) -> Result<()>;
+    fn put_all_data(&mut self, items: Vec<(String, StoredValue)>) -> Result<()>;
     /// fetch K-V out of the committed datastore
     fn get_data(&mut self, key: &str) -> Result<Option<String>>;
+    /// fetch K-V out of the committed datastore, where V is a hex string, as the bytes it
+    ///  encodes. Stores which keep values as bytes override this to skip the hex round trip.
+    fn get_data_bytes(&mut self, key: &str) -> Result<Option<Vec<u8>>> {
+        self.get_data(key)?
+            .map(|hex| {
+                hex_bytes(&hex).map_err(|_| {
+                    InterpreterError::Expect("ERROR: stored value is not a hex string".into())
+                        .into()
+                })
+            })
+            .transpose()
+    }
     /// fetch Hash(K)-V out of the commmitted datastore
     fn get_data_from_path(&mut self, hash: &TrieHash) -> Result<Option<String>>;
     /// fetch K-V out of the committed datastore, along with the byte representation
@@ -243,7 +276,7 @@ impl ClarityBackingStore for NullBackingStore {
         panic!("NullBackingStore can't get current block height")
     }
 
-    fn put_all_data(&mut self, mut _items: Vec<(String, String)>) -> Result<()> {
+    fn put_all_data(&mut self, mut _items: Vec<(String, StoredValue)>) -> Result<()> {
         panic!("NullBackingStore cannot put")
     }
 
@@ -15,6 +15,8 @@
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::hash::Hash;
+#[cfg(not(feature = "rollback_value_check"))]
+use std::marker::PhantomData;
 
 use hashbrown::HashMap;
 use stacks_common::types::chainstate::{StacksBlockId, TrieHash};
@@ -22,7 +24,7 @@ use stacks_common::types::StacksEpochId;
 use stacks_common::util::hash::Sha512Trunc256Sum;
 
 use super::clarity_store::SpecialCaseHandler;
-use super::{ClarityBackingStore, ClarityDeserializable};
+use super::{ClarityBackingStore, ClarityDeserializable, StoredValue};
 use crate::vm::database::clarity_store::make_contract_hash_key;
 use crate::vm::errors::{InterpreterError, InterpreterResult};
 use crate::vm::types::serialization::SerializationError;
@@ -30,26 +32,26 @@ use crate::vm::types::{QualifiedContractIdentifier, TypeSignature};
 use crate::vm::Value;
 
 #[cfg(feature = "rollback_value_check")]
-type RollbackValueCheck = String;
+type RollbackValueCheck<V> = V;
 #[cfg(not(feature = "rollback_value_check"))]
-type RollbackValueCheck = ();
+type RollbackValueCheck<V> = PhantomData<V>;
 
 #[cfg(not(feature = "rollback_value_check"))]
-fn rollback_value_check(_value: &str, _check: &RollbackValueCheck) {}
+fn rollback_value_check<V>(_value: &V, _check: &RollbackValueCheck<V>) {}
 
 #[cfg(not(feature = "rollback_value_check"))]
-fn rollback_edits_push<T>(edits: &mut Vec<(T, RollbackValueCheck)>, key: T, _value: &str) {
-    edits.push((key, ()));
+fn rollback_edits_push<T, V>(edits: &mut Vec<(T, RollbackValueCheck<V>)>, key: T, _value: &V) {
+    edits.push((key, PhantomData));
 }
 // this function is used to check the lookup map when committing at the "bottom" of the
 //   wrapper -- i.e., when committing to the underlying store. for the _unchecked_ implementation
 //   this is used to get the edit _value_ out of the lookupmap, for used in the subsequent `put_all`
 //   command.
 #[cfg(not(feature = "rollback_value_check"))]
-fn rollback_check_pre_bottom_commit<T>(
-    edits: Vec<(T, RollbackValueCheck)>,
-    lookup_map: &mut HashMap<T, Vec<String>>,
-) -> Result<Vec<(T, String)>, InterpreterError>
+fn rollback_check_pre_bottom_commit<T, V: PartialEq + std::fmt::Debug>(
+    edits: Vec<(T, RollbackValueCheck<V>)>,
+    lookup_map: &mut HashMap<T, Vec<V>>,
+) -> Result<Vec<(T, V)>, InterpreterError>
 where
     T: Eq + Hash + Clone,
 {
@@ -59,8 +61,8 @@ where
 
     let output = edits
         .into_iter()
-        .map(|(key, _)| {
-            let value = rollback_lookup_map(&key, &(), lookup_map)?;
+        .map(|(key, check)| {
+            let value = rollback_lookup_map(&key, &check, lookup_map)?;
             Ok((key, value))
         })
         .collect();
@@ -70,23 +72,23 @@ where
 }
 
 #[cfg(feature = "rollback_value_check")]
-fn rollback_value_check(value: &String, check: &RollbackValueCheck) {
+fn rollback_value_check<V: PartialEq + std::fmt::Debug>(value: &V, check: &RollbackValueCheck<V>) {
     assert_eq!(value, check)
 }
 #[cfg(feature = "rollback_value_check")]
-fn rollback_edits_push<T>(edits: &mut Vec<(T, RollbackValueCheck)>, key: T, value: &str)
+fn rollback_edits_push<T, V: Clone>(edits: &mut Vec<(T, RollbackValueCheck<V>)>, key: T, value: &V)
 where
     T: Eq + Hash + Clone,
 {
-    edits.push((key, value.to_owned()));
+    edits.push((key, value.clone()));
 }
 // this function is used to check the lookup map when committing at the "bottom" of the
 //   wrapper -- i.e., when committing to the underlying store.
 #[cfg(feature = "rollback_value_check")]
-fn rollback_check_pre_bottom_commit<T>(
-    edits: Vec<(T, RollbackValueCheck)>,
-    lookup_map: &mut HashMap<T, Vec<String>>,
-) -> Result<Vec<(T, String)>, InterpreterError>
+fn rollback_check_pre_bottom_commit<T, V: PartialEq + std::fmt::Debug>(
+    edits: Vec<(T, RollbackValueCheck<V>)>,
+    lookup_map: &mut HashMap<T, Vec<V>>,
+) -> Result<Vec<(T, V)>, InterpreterError>
 where
     T: Eq + Hash + Clone,
 {
@@ -109,8 +111,11 @@ pub struct ValueResult {
 }
 
 pub struct RollbackContext {
-    edits: Vec<(String, RollbackValueCheck)>,
-    metadata_edits: Vec<((QualifiedContractIdentifier, String), RollbackValueCheck)>,
+    edits: Vec<(String, RollbackValueCheck<StoredValue>)>,
+    metadata_edits: Vec<(
+        (QualifiedContractIdentifier, String),
+        RollbackValueCheck<String>,
+    )>,
 }
 
 pub struct RollbackWrapper<'a> {
@@ -119,7 +124,7 @@ pub struct RollbackWrapper<'a> {
     // lookup_map is a history of edits for a given key.
     //   in order of least-recent to most-recent at the tail.
     //   this allows ~ O(1) lookups, and ~ O(1) commits, roll-backs (amortized by # of PUTs).
-    lookup_map: HashMap<String, Vec<String>>,
+    lookup_map: HashMap<String, Vec<StoredValue>>,
     metadata_lookup_map: HashMap<(QualifiedContractIdentifier, String), Vec<String>>,
     // stack keeps track of the most recent rollback context, which tells us which
     //   edits were performed by which context. at the moment, each context's edit history
@@ -137,7 +142,7 @@ pub struct RollbackWrapper<'a> {
 //   a real mess of lifetime parameters in the database/context
 //   and eval code.
 pub struct RollbackWrapperPersistedLog {
-    lookup_map: HashMap<String, Vec<String>>,
+    lookup_map: HashMap<String, Vec<StoredValue>>,
     metadata_lookup_map: HashMap<(QualifiedContractIdentifier, String), Vec<String>>,
     stack: Vec<RollbackContext>,
 }
@@ -175,11 +180,11 @@ impl RollbackWrapperPersistedLog {
     }
 }
 
-fn rollback_lookup_map<T>(
+fn rollback_lookup_map<T, V: PartialEq + std::fmt::Debug>(
     key: &T,
-    value: &RollbackValueCheck,
-    lookup_map: &mut HashMap<T, Vec<String>>,
-) -> Result<String, InterpreterError>
+    value: &RollbackValueCheck<V>,
+    lookup_map: &mut HashMap<T, Vec<V>>,
+) -> Result<V, InterpreterError>
 where
     T: Eq + Hash + Clone,
 {
@@ -306,13 +311,14 @@ impl<'a> RollbackWrapper<'a> {
     }
 }
 
-fn inner_put_data<T>(
-    lookup_map: &mut HashMap<T, Vec<String>>,
-    edits: &mut Vec<(T, RollbackValueCheck)>,
+fn inner_put_data<T, V>(
+    lookup_map: &mut HashMap<T, Vec<V>>,
+    edits: &mut Vec<(T, RollbackValueCheck<V>)>,
     key: T,
-    value: String,
+    value: V,
 ) where
     T: Eq + Hash + Clone,
+    V: Clone,
 {
     let key_edit_deque = lookup_map.entry(key.clone()).or_default();
     rollback_edits_push(edits, key, &value);
@@ -320,7 +326,7 @@ fn inner_put_data<T>(
 }
 
 impl RollbackWrapper<'_> {
-    pub fn put_data(&mut self, key: &str, value: &str) -> InterpreterResult<()> {
+    pub fn put_data(&mut self, key: &str, value: StoredValue) -> InterpreterResult<()> {
         let current = self.stack.last_mut().ok_or_else(|| {
             InterpreterError::Expect(
                 "ERROR: Clarity VM attempted PUT on non-nested context.".into(),
@@ -331,7 +337,7 @@ impl RollbackWrapper<'_> {
             &mut self.lookup_map,
             &mut current.edits,
             key.to_string(),
-            value.to_string(),
+            value,
         );
         Ok(())
     }
@@ -395,7 +401,7 @@ impl RollbackWrapper<'_> {
         if self.query_pending_data {
             if let Some(pending_value) = self.lookup_map.get(key).and_then(|x| x.last()) {
                 // if there's pending data and we're querying pending data, return here
-                return Some(T::deserialize(pending_value)).transpose();
+                return Some(T::deserialize(&pending_value.to_serialized())).transpose();
             }
         }
         // otherwise, lookup from store
@@ -437,6 +443,22 @@ impl RollbackWrapper<'_> {
         })
     }
 
+    /// Like `deserialize_value`, for a value which was already decoded from hex
+    pub fn deserialize_value_bytes(
+        value_bytes: &[u8],
+        expected: &TypeSignature,
+        epoch: &StacksEpochId,
+    ) -> Result<ValueResult, SerializationError> {
+        let serialized_byte_len = value_bytes.len() as u64;
+        let sanitize = epoch.value_sanitizing();
+        let value = Value::deserialize_read(&mut &value_bytes[..], Some(expected), sanitize)?;
+
+        Ok(ValueResult {
+            value,
+            serialized_byte_len,
+        })
+    }
+
     /// Get a Clarity value from the underlying Clarity KV store.
     /// Returns Some if found, with the Clarity Value and the serialized byte length of the value.
     pub fn get_value(
@@ -453,14 +475,22 @@ impl RollbackWrapper<'_> {
 
         if self.query_pending_data {
             if let Some(x) = self.lookup_map.get(key).and_then(|x| x.last()) {
-                return Ok(Some(Self::deserialize_value(x, expected, epoch)?));
+                let value = match x {
+                    StoredValue::Bytes(bytes) => {
+                        Self::deserialize_value_bytes(bytes, expected, epoch)?
+                    }
+                    StoredValue::Text(value_hex) => {
+                        Self::deserialize_value(value_hex, expected, epoch)?
+                    }
+                };
+                return Ok(Some(value));
             }
         }
-        let stored_data = self.store.get_data(key).map_err(|_| {
+        let stored_data = self.store.get_data_bytes(key).map_err(|_| {
             SerializationError::DeserializationError("ERROR: Clarity backing store failure".into())
         })?;
         match stored_data {
-            Some(x) => Ok(Some(Self::deserialize_value(&x, expected, epoch)?)),
+            Some(x) => Ok(Some(Self::deserialize_value_bytes(&x, expected, epoch)?)),
             None => Ok(None),
         }
     }
@@ -482,7 +512,7 @@ impl RollbackWrapper<'_> {
     ) -> InterpreterResult<()> {
         let key = make_contract_hash_key(contract);
         let value = self.store.make_contract_commitment(content_hash);
-        self.put_data(&key, &value)
+        self.put_data(&key, StoredValue::Text(value))
     }
 
     pub fn insert_metadata(
@@ -14,16 +14,16 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
-use rusqlite::types::{FromSql, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
 use rusqlite::{params, Connection, OptionalExtension};
 use stacks_common::types::chainstate::{BlockHeaderHash, StacksBlockId, TrieHash};
 use stacks_common::types::sqlite::NO_PARAMS;
 use stacks_common::util::db::tx_busy_handler;
-use stacks_common::util::hash::Sha512Trunc256Sum;
+use stacks_common::util::hash::{hex_bytes, to_hex, Sha512Trunc256Sum};
 
 use super::clarity_store::{make_contract_hash_key, ContractCommitment};
 use super::{
-    ClarityBackingStore, ClarityDatabase, ClarityDeserializable, SpecialCaseHandler,
+    ClarityBackingStore, ClarityDatabase, ClarityDeserializable, SpecialCaseHandler, StoredValue,
     NULL_BURN_STATE_DB, NULL_HEADER_DB,
 };
 use crate::vm::analysis::{AnalysisDatabase, CheckErrors};
@@ -39,12 +39,49 @@ pub struct SqliteConnection {
     conn: Connection,
 }
 
-fn sqlite_put(conn: &Connection, key: &str, value: &str) -> Result<()> {
+/// Values of the data table are stored as bytes or text, as they were handed over. Bytes are
+///  read back as the hex string which encodes them.
+impl ToSql for StoredValue {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput> {
+        match self {
+            StoredValue::Bytes(bytes) => Ok(ToSqlOutput::Borrowed(ValueRef::Blob(bytes))),
+            StoredValue::Text(text) => Ok(ToSqlOutput::Borrowed(ValueRef::Text(text.as_bytes()))),
+        }
+    }
+}
+
+/// The bytes which a value of the data table encodes, if it is a lowercase hex string, as
+///  serialized Clarity values were stored before the data table kept them as bytes
+fn legacy_hex_bytes(value: &str) -> Option<Vec<u8>> {
+    // `to_hex` only writes lowercase digits, so anything else would not read back the same
+    if value.bytes().any(|b| b.is_ascii_uppercase()) {
+        return None;
+    }
+    hex_bytes(value).ok()
+}
+
+/// Decode a value of the data table to the string which was written
+pub fn side_store_value_to_string(value: ValueRef) -> FromSqlResult<String> {
+    match value {
+        ValueRef::Blob(bytes) => Ok(to_hex(bytes)),
+        _ => String::column_result(value),
+    }
+}
+
+/// Decode a value of the data table to the bytes which its hex string encodes
+fn side_store_value_to_bytes(value: ValueRef) -> FromSqlResult<Vec<u8>> {
+    match value {
+        ValueRef::Blob(bytes) => Ok(bytes.to_vec()),
+        _ => hex_bytes(value.as_str()?).map_err(|e| FromSqlError::Other(Box::new(e))),
+    }
+}
+
+fn sqlite_put(conn: &Connection, key: &str, value: &StoredValue) -> Result<()> {
     let params = params![key, value];
     match conn.execute("REPLACE INTO data_table (key, value) VALUES (?, ?)", params) {
         Ok(_) => Ok(()),
         Err(e) => {
-            error!("Failed to insert/replace ({},{}): {:?}", key, value, &e);
+            error!("Failed to insert/replace ({},{:?}): {:?}", key, value, &e);
             Err(InterpreterError::DBError(SQL_FAIL_MESSAGE.into()).into())
         }
     }
@@ -57,7 +94,7 @@ fn sqlite_get(conn: &Connection, key: &str) -> Result<Option<String>> {
         .query_row(
             "SELECT value FROM data_table WHERE key = ?",
             params,
-            |row| row.get(0),
+            |row| Ok(side_store_value_to_string(row.get_ref(0)?)?),
         )
         .optional()
     {
@@ -72,6 +109,24 @@ fn sqlite_get(conn: &Connection, key: &str) -> Result<Option<String>> {
     res
 }
 
+fn sqlite_get_bytes(conn: &Connection, key: &str) -> Result<Option<Vec<u8>>> {
+    trace!("sqlite_get_bytes {}", key);
+    match conn
+        .query_row(
+            "SELECT value FROM data_table WHERE key = ?",
+            params![key],
+            |row| Ok(side_store_value_to_bytes(row.get_ref(0)?)?),
+        )
+        .optional()
+    {
+        Ok(x) => Ok(x),
+        Err(e) => {
+            error!("Failed to query '{}': {:?}", key, &e);
+            Err(InterpreterError::DBError(SQL_FAIL_MESSAGE.into()).into())
+        }
+    }
+}
+
 fn sqlite_has_entry(conn: &Connection, key: &str) -> Result<bool> {
     Ok(sqlite_get(conn, key)?.is_some())
 }
@@ -133,7 +188,7 @@ pub fn sqlite_get_metadata_manual(
 }
 
 impl SqliteConnection {
-    pub fn put(conn: &Connection, key: &str, value: &str) -> Result<()> {
+    pub fn put(conn: &Connection, key: &str, value: &StoredValue) -> Result<()> {
         sqlite_put(conn, key, value)
     }
 
@@ -141,6 +196,67 @@ impl SqliteConnection {
         sqlite_get(conn, key)
     }
 
+    /// Get a value which is a hex string, as the bytes it encodes
+    pub fn get_bytes(conn: &Connection, key: &str) -> Result<Option<Vec<u8>>> {
+        sqlite_get_bytes(conn, key)
+    }
+
+    /// Rewrite up to `limit` values which were stored as hex text, as written before values
+    ///  were stored as bytes, into their compact form, looking only at rows after `after_rowid`.
+    ///  Returns the number of rewritten values, and the rowid to pass to the next call; once
+    ///  the number is less than `limit`, the table is fully compacted.
+    pub fn compact_data_table(
+        conn: &Connection,
+        after_rowid: i64,
+        limit: u32,
+    ) -> Result<(u32, i64)> {
+        let sql_err = |e| {
+            error!("Failed to compact the data table: {:?}", &e);
+            InterpreterError::DBError(SQL_FAIL_MESSAGE.into())
+        };
+        let mut last_rowid = after_rowid;
+        let rows: Vec<(String, Vec<u8>)> = {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT rowid, key, value FROM data_table WHERE typeof(value) = 'text' AND rowid > ?
+                     ORDER BY rowid LIMIT ?",
+                )
+                .map_err(sql_err)?;
+            let mut rows = vec![];
+            // text values which are not hex stay as they are, so skip past them
+            'scan: while rows.len() < limit as usize {
+                let batch: Vec<(i64, String, String)> = stmt
+                    .query_map(params![last_rowid, limit], |row| {
+                        Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+                    })
+                    .map_err(sql_err)?
+                    .collect::<std::result::Result<_, _>>()
+                    .map_err(sql_err)?;
+                if batch.is_empty() {
+                    break;
+                }
+                for (rowid, key, value) in batch {
+                    if rows.len() == limit as usize {
+                        break 'scan;
+                    }
+                    last_rowid = rowid;
+                    if let Some(bytes) = legacy_hex_bytes(&value) {
+                        rows.push((key, bytes));
+                    }
+                }
+            }
+            rows
+        };
+        for (key, value) in rows.iter() {
+            conn.execute(
+                "UPDATE data_table SET value = ? WHERE key = ?",
+                params![value, key],
+            )
+            .map_err(sql_err)?;
+        }
+        Ok((rows.len() as u32, last_rowid))
+    }
+
     pub fn insert_metadata(
         conn: &Connection,
         bhh: &StacksBlockId,
@@ -326,6 +442,10 @@ impl ClarityBackingStore for MemoryBackingStore {
         SqliteConnection::get(self.get_side_store(), key)
     }
 
+    fn get_data_bytes(&mut self, key: &str) -> Result<Option<Vec<u8>>> {
+        SqliteConnection::get_bytes(self.get_side_store(), key)
+    }
+
     fn get_data_from_path(&mut self, hash: &TrieHash) -> Result<Option<String>> {
         SqliteConnection::get(self.get_side_store(), hash.to_string().as_str())
     }
@@ -369,7 +489,7 @@ impl ClarityBackingStore for MemoryBackingStore {
         None
     }
 
-    fn put_all_data(&mut self, items: Vec<(String, String)>) -> Result<()> {
+    fn put_all_data(&mut self, items: Vec<(String, StoredValue)>) -> Result<()> {
         for (key, value) in items.into_iter() {
             SqliteConnection::put(self.get_side_store(), &key, &value)?;
         }
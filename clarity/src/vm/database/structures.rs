@@ -21,12 +21,17 @@ use stacks_common::util::hash::{hex_bytes, to_hex};
 
 use crate::vm::analysis::ContractAnalysis;
 use crate::vm::contracts::Contract;
-use crate::vm::database::ClarityDatabase;
+use crate::vm::database::{ClarityDatabase, StoredValue};
 use crate::vm::errors::{Error, InterpreterError, RuntimeErrorType};
 use crate::vm::types::{PrincipalData, TypeSignature};
 
 pub trait ClaritySerializable {
     fn serialize(&self) -> String;
+    /// The value to write to the backing store, whose string form is `serialize()`.
+    ///  Types which serialize to hex override this to hand over their bytes instead.
+    fn serialize_stored(&self) -> StoredValue {
+        StoredValue::Text(self.serialize())
+    }
 }
 
 pub trait ClarityDeserializable<T> {
@@ -161,9 +166,9 @@ pub struct STXBalanceSnapshot<'db, 'conn> {
 
 type Result<T> = std::result::Result<T, Error>;
 
-impl ClaritySerializable for STXBalance {
+impl STXBalance {
     #[allow(clippy::expect_used)]
-    fn serialize(&self) -> String {
+    fn serialize_bytes(&self) -> Vec<u8> {
         let mut buffer = Vec::new();
         match self {
             STXBalance::Unlocked { amount } => {
@@ -247,7 +252,17 @@ impl ClaritySerializable for STXBalance {
                     .expect("STXBalance serialization: failed writing unlock_height.");
             }
         }
-        to_hex(buffer.as_slice())
+        buffer
+    }
+}
+
+impl ClaritySerializable for STXBalance {
+    fn serialize(&self) -> String {
+        to_hex(&self.serialize_bytes())
+    }
+
+    fn serialize_stored(&self) -> StoredValue {
+        StoredValue::Bytes(self.serialize_bytes())
     }
 }
 
@@ -0,0 +1,236 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A line-oriented command prompt for the debugger
+
+use std::io::{BufRead, Write};
+
+use hashbrown::HashMap;
+
+use super::{ContractSelector, DebugFrontend, PauseReason, Paused, Resume, StackFrame};
+use crate::vm::representations::ContractName;
+use crate::vm::types::QualifiedContractIdentifier;
+
+const HELP: &str = "Commands:
+  c, continue           run until the next breakpoint
+  s, step               step to the next line, entering function calls
+  n, next               step to the next line, stepping over function calls
+  f, finish             run until the current function returns
+  b, break [CONTRACT:]LINE
+                        set a breakpoint in the given (or the current) contract
+  d, delete ID          delete a breakpoint
+  breakpoints           list the breakpoints
+  bt, backtrace         print the call stack
+  locals                print the local bindings
+  vars                  print the contract's data-vars
+  map NAME KEY          print the entry of map NAME for the key expression KEY
+  p, print EXPR         evaluate an expression (writes are rolled back)
+  q, quit               run to completion, ignoring breakpoints
+  h, help               print this message";
+
+/// Parse a breakpoint location of the form `[CONTRACT:]LINE`. `CONTRACT` is
+///  either a fully qualified contract identifier, or a bare contract name which
+///  matches the contract of that name from any issuer. Without it, the
+///  breakpoint is set in `default_contract`.
+pub fn parse_breakpoint(
+    spec: &str,
+    default_contract: Option<&QualifiedContractIdentifier>,
+) -> Result<(ContractSelector, u32), String> {
+    let (contract, line) = match spec.rsplit_once(':') {
+        Some((contract, line)) => (Some(contract), line),
+        None => (None, spec),
+    };
+    let line = line
+        .trim()
+        .parse::<u32>()
+        .map_err(|_| format!("invalid line number `{line}`"))?;
+    let contract = match contract {
+        Some(contract) if contract.contains('.') => ContractSelector::Identifier(
+            QualifiedContractIdentifier::parse(contract).map_err(|e| e.to_string())?,
+        ),
+        Some(contract) => ContractSelector::Name(
+            ContractName::try_from(contract.to_string()).map_err(|e| e.to_string())?,
+        ),
+        None => ContractSelector::Identifier(
+            default_contract
+                .ok_or("no current contract: specify one as CONTRACT:LINE")?
+                .clone(),
+        ),
+    };
+    Ok((contract, line))
+}
+
+fn describe_frame(frame: &StackFrame) -> String {
+    let function = match &frame.function {
+        Some(function) => function.as_str(),
+        None => "<top-level>",
+    };
+    format!(
+        "{} at {}:{}:{}",
+        function, frame.contract, frame.span.start_line, frame.span.start_column
+    )
+}
+
+/// A debugger front-end which reads commands from `input` and prints to `output`
+pub struct CommandLineFrontend<R: BufRead, W: Write> {
+    input: R,
+    output: W,
+    sources: HashMap<QualifiedContractIdentifier, Option<String>>,
+}
+
+impl<R: BufRead, W: Write> CommandLineFrontend<R, W> {
+    pub fn new(input: R, output: W) -> CommandLineFrontend<R, W> {
+        CommandLineFrontend {
+            input,
+            output,
+            sources: HashMap::new(),
+        }
+    }
+
+    pub fn into_output(self) -> W {
+        self.output
+    }
+
+    fn print_location(&mut self, paused: &mut Paused) -> std::io::Result<()> {
+        let reason = match paused.reason {
+            PauseReason::Entry => "entry".to_string(),
+            PauseReason::Breakpoint(id) => format!("breakpoint {id}"),
+            PauseReason::Step => "step".to_string(),
+        };
+        let frame_description = paused
+            .stack()
+            .last()
+            .map(describe_frame)
+            .unwrap_or_default();
+        writeln!(self.output, "Paused ({reason}) in {frame_description}")?;
+
+        let contract = paused.contract().clone();
+        let line = paused.expression().span().start_line;
+        if !self.sources.contains_key(&contract) {
+            let source = paused.source();
+            self.sources.insert(contract.clone(), source);
+        }
+        let source_line = self
+            .sources
+            .get(&contract)
+            .and_then(|source| source.as_ref())
+            .and_then(|source| source.lines().nth((line as usize).checked_sub(1)?));
+        if let Some(source_line) = source_line {
+            writeln!(self.output, "{line:>5} | {source_line}")?;
+        }
+        Ok(())
+    }
+
+    /// Run one command. Returns `Some` if the command resumes execution.
+    fn run_command(&mut self, paused: &mut Paused, line: &str) -> std::io::Result<Option<Resume>> {
+        let line = line.trim();
+        let (command, rest) = line.split_once(' ').unwrap_or((line, ""));
+        let rest = rest.trim();
+        match command {
+            "c" | "continue" => return Ok(Some(Resume::Continue)),
+            "s" | "step" => return Ok(Some(Resume::StepIn)),
+            "n" | "next" => return Ok(Some(Resume::StepOver)),
+            "f" | "finish" => return Ok(Some(Resume::StepOut)),
+            "q" | "quit" => return Ok(Some(Resume::Detach)),
+            "b" | "break" => {
+                let contract = paused.contract().clone();
+                let added = parse_breakpoint(rest, Some(&contract)).and_then(|(contract, line)| {
+                    let message = format!("at {contract}:{line}");
+                    let id = paused.breakpoints.add(contract, line)?;
+                    Ok(format!("Breakpoint {id} {message}"))
+                });
+                match added {
+                    Ok(message) => writeln!(self.output, "{message}")?,
+                    Err(e) => writeln!(self.output, "{e}")?,
+                }
+            }
+            "d" | "delete" => match rest.parse::<usize>() {
+                Ok(id) if paused.breakpoints.remove(id) => {
+                    writeln!(self.output, "Deleted breakpoint {id}")?
+                }
+                _ => writeln!(self.output, "No breakpoint `{rest}`")?,
+            },
+            "breakpoints" => {
+                for breakpoint in paused.breakpoints.iter() {
+                    writeln!(
+                        self.output,
+                        "{}: {}:{}",
+                        breakpoint.id, breakpoint.contract, breakpoint.line
+                    )?;
+                }
+            }
+            "bt" | "backtrace" => {
+                for (depth, frame) in paused.stack().iter().rev().enumerate() {
+                    writeln!(self.output, "#{depth} {}", describe_frame(frame))?;
+                }
+            }
+            "locals" => {
+                for (name, value) in paused.locals() {
+                    writeln!(self.output, "{name} = {value}")?;
+                }
+            }
+            "vars" => {
+                for (name, value) in paused.data_vars() {
+                    match value {
+                        Ok(value) => writeln!(self.output, "{name} = {value}")?,
+                        Err(e) => writeln!(self.output, "{name}: {e}")?,
+                    }
+                }
+            }
+            "map" => match rest.split_once(' ') {
+                Some((map, key)) => match paused.map_entry(map, key) {
+                    Ok(value) => writeln!(self.output, "{value}")?,
+                    Err(e) => writeln!(self.output, "{e}")?,
+                },
+                None => writeln!(self.output, "Usage: map NAME KEY")?,
+            },
+            "p" | "print" => match paused.evaluate(rest) {
+                Ok(value) => writeln!(self.output, "{value}")?,
+                Err(e) => writeln!(self.output, "{e}")?,
+            },
+            "h" | "help" => writeln!(self.output, "{HELP}")?,
+            "" => {}
+            _ => writeln!(self.output, "Unknown command `{command}`; try `help`")?,
+        }
+        Ok(None)
+    }
+}
+
+impl<R: BufRead, W: Write> DebugFrontend for CommandLineFrontend<R, W> {
+    fn paused(&mut self, paused: &mut Paused) -> Resume {
+        if let Err(e) = self.print_location(paused) {
+            warn!("Failed to write to the debugger output: {e}");
+            return Resume::Detach;
+        }
+        loop {
+            let _ = write!(self.output, "(clarity-debug) ");
+            let _ = self.output.flush();
+            let mut line = String::new();
+            match self.input.read_line(&mut line) {
+                // at EOF, there is nobody left to drive the debugger
+                Ok(0) | Err(_) => return Resume::Detach,
+                Ok(_) => {}
+            }
+            match self.run_command(paused, &line) {
+                Ok(Some(resume)) => return resume,
+                Ok(None) => {}
+                Err(e) => {
+                    warn!("Failed to write to the debugger output: {e}");
+                    return Resume::Detach;
+                }
+            }
+        }
+    }
+}
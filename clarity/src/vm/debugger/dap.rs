@@ -0,0 +1,386 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A Debug Adapter Protocol front-end for the debugger.
+//!
+//! The adapter serves a single thread, and uses the same `Content-Length`
+//! framing as the language server. Breakpoints are set per source file: the
+//! file's stem is taken as the name of the contract it defines. Lines are
+//! 1-based, as is the DAP default.
+
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+use hashbrown::HashMap;
+use serde_json::{json, Value as JsonValue};
+
+use super::{Breakpoints, ContractSelector, DebugFrontend, PauseReason, Paused, Resume};
+use crate::vm::representations::ContractName;
+use crate::vm::tooling::lsp::{read_message, write_message};
+
+/// The only thread the adapter reports
+const THREAD_ID: u64 = 1;
+/// `variablesReference` of the local bindings scope
+const LOCALS_REFERENCE: u64 = 1;
+/// `variablesReference` of the data-vars scope
+const DATA_VARS_REFERENCE: u64 = 2;
+
+/// What the client asked for before execution started
+#[derive(Debug, Default, PartialEq)]
+pub struct LaunchOptions {
+    pub stop_on_entry: bool,
+}
+
+pub struct DapFrontend<R: BufRead, W: Write> {
+    input: R,
+    output: W,
+    seq: u64,
+    /// Source file paths, by the name of the contract they define
+    sources: HashMap<ContractName, String>,
+    disconnected: bool,
+}
+
+impl<R: BufRead, W: Write> DapFrontend<R, W> {
+    pub fn new(input: R, output: W) -> DapFrontend<R, W> {
+        DapFrontend {
+            input,
+            output,
+            seq: 0,
+            sources: HashMap::new(),
+            disconnected: false,
+        }
+    }
+
+    pub fn into_output(self) -> W {
+        self.output
+    }
+
+    fn send(&mut self, mut message: JsonValue) -> io::Result<()> {
+        self.seq += 1;
+        message["seq"] = json!(self.seq);
+        write_message(&mut self.output, &message)
+    }
+
+    fn respond(&mut self, request: &JsonValue, body: JsonValue) -> io::Result<()> {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "command": request["command"],
+            "success": true,
+            "body": body,
+        }))
+    }
+
+    fn respond_error(&mut self, request: &JsonValue, message: &str) -> io::Result<()> {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "command": request["command"],
+            "success": false,
+            "message": message,
+        }))
+    }
+
+    fn event(&mut self, event: &str, body: JsonValue) -> io::Result<()> {
+        self.send(json!({
+            "type": "event",
+            "event": event,
+            "body": body,
+        }))
+    }
+
+    /// Handle a `setBreakpoints` request, replacing the breakpoints of its source
+    fn set_breakpoints(
+        &mut self,
+        breakpoints: &mut Breakpoints,
+        request: &JsonValue,
+    ) -> io::Result<()> {
+        let arguments = &request["arguments"];
+        let path = arguments["source"]["path"].as_str().unwrap_or_default();
+        let Some(name) = Path::new(path)
+            .file_stem()
+            .and_then(|stem| ContractName::try_from(stem.to_string_lossy().to_string()).ok())
+        else {
+            return self.respond_error(request, "source path does not name a contract");
+        };
+        self.sources.insert(name.clone(), path.to_string());
+
+        let contract = ContractSelector::Name(name);
+        breakpoints.clear(&contract);
+        let lines: Vec<u64> = arguments["breakpoints"]
+            .as_array()
+            .map(|requested| {
+                requested
+                    .iter()
+                    .filter_map(|breakpoint| breakpoint["line"].as_u64())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let verified: Vec<_> = lines
+            .into_iter()
+            .filter_map(|line| u32::try_from(line).ok())
+            .map(|line| match breakpoints.add(contract.clone(), line) {
+                Ok(id) => json!({ "id": id, "verified": true, "line": line }),
+                Err(e) => json!({ "verified": false, "line": line, "message": e }),
+            })
+            .collect();
+        self.respond(request, json!({ "breakpoints": verified }))
+    }
+
+    /// Handle the requests which are valid at any time. Returns false if the
+    ///  request was not one of them.
+    fn handle_common(
+        &mut self,
+        breakpoints: &mut Breakpoints,
+        request: &JsonValue,
+    ) -> io::Result<bool> {
+        match request["command"].as_str().unwrap_or_default() {
+            "setBreakpoints" => self.set_breakpoints(breakpoints, request)?,
+            "setExceptionBreakpoints" => self.respond(request, json!({}))?,
+            "threads" => self.respond(
+                request,
+                json!({ "threads": [{ "id": THREAD_ID, "name": "main" }] }),
+            )?,
+            "disconnect" => {
+                self.disconnected = true;
+                self.respond(request, json!({}))?;
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Process requests until the client is done configuring the session,
+    ///  then return the options it launched with. Returns `Ok(None)` if the
+    ///  client disconnected instead.
+    pub fn configure(
+        &mut self,
+        breakpoints: &mut Breakpoints,
+    ) -> io::Result<Option<LaunchOptions>> {
+        let mut options = LaunchOptions::default();
+        while let Some(request) = read_message(&mut self.input)? {
+            if self.handle_common(breakpoints, &request)? {
+                if self.disconnected {
+                    return Ok(None);
+                }
+                continue;
+            }
+            match request["command"].as_str().unwrap_or_default() {
+                "initialize" => {
+                    self.respond(
+                        &request,
+                        json!({
+                            "supportsConfigurationDoneRequest": true,
+                            "supportsEvaluateForHovers": true,
+                        }),
+                    )?;
+                    self.event("initialized", json!({}))?;
+                }
+                "launch" | "attach" => {
+                    options.stop_on_entry = request["arguments"]["stopOnEntry"]
+                        .as_bool()
+                        .unwrap_or(false);
+                    self.respond(&request, json!({}))?;
+                }
+                "configurationDone" => {
+                    self.respond(&request, json!({}))?;
+                    return Ok(Some(options));
+                }
+                command => {
+                    self.respond_error(&request, &format!("unsupported request `{command}`"))?
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn stack_trace(&self, paused: &Paused) -> JsonValue {
+        let frames: Vec<_> = paused
+            .stack()
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(id, frame)| {
+                let name = match &frame.function {
+                    Some(function) => format!("{}::{}", frame.contract, function),
+                    None => format!("{}", frame.contract),
+                };
+                let mut source = json!({ "name": frame.contract.name.as_str() });
+                if let Some(path) = self.sources.get(&frame.contract.name) {
+                    source["path"] = json!(path);
+                }
+                json!({
+                    "id": id,
+                    "name": name,
+                    "source": source,
+                    "line": frame.span.start_line,
+                    "column": frame.span.start_column,
+                    "endLine": frame.span.end_line,
+                    "endColumn": frame.span.end_column,
+                })
+            })
+            .collect();
+        json!({ "stackFrames": frames, "totalFrames": frames.len() })
+    }
+
+    fn variables(paused: &mut Paused, reference: u64) -> JsonValue {
+        let variables: Vec<_> = match reference {
+            LOCALS_REFERENCE => paused
+                .locals()
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            DATA_VARS_REFERENCE => paused
+                .data_vars()
+                .into_iter()
+                .map(|(name, value)| {
+                    let value = match value {
+                        Ok(value) => value.to_string(),
+                        Err(e) => e,
+                    };
+                    (name.to_string(), value)
+                })
+                .collect(),
+            _ => vec![],
+        };
+        let variables: Vec<_> = variables
+            .into_iter()
+            .map(|(name, value)| json!({ "name": name, "value": value, "variablesReference": 0 }))
+            .collect();
+        json!({ "variables": variables })
+    }
+
+    /// Handle one request while paused. Returns `Some` if the request resumes execution.
+    fn handle_paused(
+        &mut self,
+        paused: &mut Paused,
+        request: &JsonValue,
+    ) -> io::Result<Option<Resume>> {
+        if self.handle_common(paused.breakpoints, request)? {
+            return Ok(self.disconnected.then_some(Resume::Detach));
+        }
+        let arguments = &request["arguments"];
+        let resume = match request["command"].as_str().unwrap_or_default() {
+            "continue" => Some(Resume::Continue),
+            "next" => Some(Resume::StepOver),
+            "stepIn" => Some(Resume::StepIn),
+            "stepOut" => Some(Resume::StepOut),
+            "stackTrace" => {
+                let body = self.stack_trace(paused);
+                self.respond(request, body)?;
+                None
+            }
+            "scopes" => {
+                // the local bindings of the other frames are not kept by the
+                //  interpreter, so only the innermost frame has any
+                let locals = if arguments["frameId"].as_u64() == Some(0) {
+                    LOCALS_REFERENCE
+                } else {
+                    0
+                };
+                self.respond(
+                    request,
+                    json!({ "scopes": [
+                        { "name": "Locals", "variablesReference": locals, "expensive": false },
+                        { "name": "Data", "variablesReference": DATA_VARS_REFERENCE, "expensive": false },
+                    ]}),
+                )?;
+                None
+            }
+            "variables" => {
+                let reference = arguments["variablesReference"].as_u64().unwrap_or(0);
+                let body = Self::variables(paused, reference);
+                self.respond(request, body)?;
+                None
+            }
+            "evaluate" => {
+                let expression = arguments["expression"].as_str().unwrap_or_default();
+                match paused.evaluate(expression) {
+                    Ok(value) => self.respond(
+                        request,
+                        json!({ "result": value.to_string(), "variablesReference": 0 }),
+                    )?,
+                    Err(e) => self.respond_error(request, &e)?,
+                }
+                None
+            }
+            command => {
+                self.respond_error(request, &format!("unsupported request `{command}`"))?;
+                None
+            }
+        };
+        if resume.is_some() {
+            self.respond(request, json!({ "allThreadsContinued": true }))?;
+        }
+        Ok(resume)
+    }
+
+    fn serve_paused(&mut self, paused: &mut Paused) -> io::Result<Resume> {
+        let (reason, hit) = match paused.reason {
+            PauseReason::Entry => ("entry", vec![]),
+            PauseReason::Breakpoint(id) => ("breakpoint", vec![id]),
+            PauseReason::Step => ("step", vec![]),
+        };
+        self.event(
+            "stopped",
+            json!({
+                "reason": reason,
+                "threadId": THREAD_ID,
+                "allThreadsStopped": true,
+                "hitBreakpointIds": hit,
+            }),
+        )?;
+        while let Some(request) = read_message(&mut self.input)? {
+            if let Some(resume) = self.handle_paused(paused, &request)? {
+                return Ok(resume);
+            }
+        }
+        Ok(Resume::Detach)
+    }
+
+    /// Report the outcome of the execution, then serve requests until the
+    ///  client disconnects.
+    pub fn finish(&mut self, output: &str, exit_code: i32) -> io::Result<()> {
+        self.event(
+            "output",
+            json!({ "category": "stdout", "output": format!("{output}\n") }),
+        )?;
+        self.event("exited", json!({ "exitCode": exit_code }))?;
+        self.event("terminated", json!({}))?;
+        let mut breakpoints = Breakpoints::default();
+        while !self.disconnected {
+            let Some(request) = read_message(&mut self.input)? else {
+                break;
+            };
+            if !self.handle_common(&mut breakpoints, &request)? {
+                self.respond_error(&request, "the program has terminated")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<R: BufRead, W: Write> DebugFrontend for DapFrontend<R, W> {
+    fn paused(&mut self, paused: &mut Paused) -> Resume {
+        if self.disconnected {
+            return Resume::Detach;
+        }
+        self.serve_paused(paused).unwrap_or_else(|e| {
+            warn!("Debug adapter connection failed: {e}");
+            self.disconnected = true;
+            Resume::Detach
+        })
+    }
+}
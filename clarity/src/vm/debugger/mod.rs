@@ -0,0 +1,470 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A step debugger for Clarity, implemented as an `EvalHook`.
+//!
+//! The `Debugger` tracks the user-defined function frames that the evaluator
+//! enters and leaves, and pauses evaluation when a breakpoint is reached or a
+//! step completes. While paused, a `DebugFrontend` is handed a `Paused` view
+//! of the interpreter, which it can use to inspect the local bindings, the
+//! call stack and the contract's data-vars and maps, before deciding how to
+//! resume. Two front-ends are provided: a line-oriented command prompt
+//! (`cli`) and a Debug Adapter Protocol server (`dap`).
+//!
+//! Breakpoints and line stepping rely on expression spans, which are only
+//! tracked when the `developer-mode` feature is enabled. Without it, setting a
+//! breakpoint fails, and stepping moves from one expression to the next rather
+//! than from one line to the next.
+
+pub mod cli;
+pub mod dap;
+
+use std::fmt;
+
+use stacks_common::types::StacksEpochId;
+
+use crate::vm::ast::{build_ast_with_rules, ASTRules};
+use crate::vm::contexts::{Environment, LocalContext};
+use crate::vm::costs::LimitedCostTracker;
+use crate::vm::errors::Error;
+use crate::vm::representations::{ClarityName, ContractName, Span, SymbolicExpression};
+use crate::vm::types::QualifiedContractIdentifier;
+use crate::vm::{eval, EvalHook, ExecutionResult, Value};
+
+/// The contract(s) a breakpoint applies to
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContractSelector {
+    /// Exactly this contract
+    Identifier(QualifiedContractIdentifier),
+    /// Any contract with this name, regardless of its issuer. Used when a
+    ///  breakpoint is set from a source file, whose name is all that is known.
+    Name(ContractName),
+}
+
+impl ContractSelector {
+    pub fn matches(&self, contract: &QualifiedContractIdentifier) -> bool {
+        match self {
+            ContractSelector::Identifier(identifier) => identifier == contract,
+            ContractSelector::Name(name) => &contract.name == name,
+        }
+    }
+}
+
+impl fmt::Display for ContractSelector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ContractSelector::Identifier(identifier) => write!(f, "{identifier}"),
+            ContractSelector::Name(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Breakpoint {
+    pub id: usize,
+    pub contract: ContractSelector,
+    pub line: u32,
+}
+
+#[derive(Debug, Default)]
+pub struct Breakpoints {
+    next_id: usize,
+    breakpoints: Vec<Breakpoint>,
+}
+
+impl Breakpoints {
+    /// Add a breakpoint, returning its id. Fails if this build does not track expression
+    ///  spans, as evaluation could then never be found on the breakpoint's line.
+    pub fn add(&mut self, contract: ContractSelector, line: u32) -> Result<usize, String> {
+        if !Span::TRACKED {
+            return Err(
+                "breakpoints need expression spans, which are only tracked with the `developer-mode` feature"
+                    .into(),
+            );
+        }
+        self.next_id += 1;
+        self.breakpoints.push(Breakpoint {
+            id: self.next_id,
+            contract,
+            line,
+        });
+        Ok(self.next_id)
+    }
+
+    /// Remove a breakpoint by id. Returns false if there was no such breakpoint.
+    pub fn remove(&mut self, id: usize) -> bool {
+        let len = self.breakpoints.len();
+        self.breakpoints.retain(|breakpoint| breakpoint.id != id);
+        self.breakpoints.len() != len
+    }
+
+    /// Remove every breakpoint set with the given selector
+    pub fn clear(&mut self, contract: &ContractSelector) {
+        self.breakpoints
+            .retain(|breakpoint| &breakpoint.contract != contract);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Breakpoint> {
+        self.breakpoints.iter()
+    }
+
+    fn find(&self, contract: &QualifiedContractIdentifier, line: u32) -> Option<usize> {
+        self.breakpoints
+            .iter()
+            .find(|breakpoint| breakpoint.line == line && breakpoint.contract.matches(contract))
+            .map(|breakpoint| breakpoint.id)
+    }
+}
+
+/// One frame of the Clarity call stack, as seen by the debugger
+#[derive(Debug, Clone, PartialEq)]
+pub struct StackFrame {
+    pub contract: QualifiedContractIdentifier,
+    /// The user-defined function this frame executes, or `None` for
+    ///  top-level expressions
+    pub function: Option<ClarityName>,
+    /// The span of the expression this frame is currently evaluating
+    pub span: Span,
+    /// The id of the function body this frame was pushed for
+    body_id: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PauseReason {
+    /// Execution paused before its first expression
+    Entry,
+    /// A breakpoint, identified by its id, was reached
+    Breakpoint(usize),
+    /// A step completed
+    Step,
+}
+
+/// How execution should resume after a pause
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Resume {
+    /// Run until the next breakpoint
+    Continue,
+    /// Pause at the next line, entering any function call
+    StepIn,
+    /// Pause at the next line of the current function, or of its caller
+    StepOver,
+    /// Pause once the current function returns to its caller
+    StepOut,
+    /// Run to completion, ignoring breakpoints
+    Detach,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum StepMode {
+    Continue,
+    StepIn,
+    StepOver(usize),
+    StepOut(usize),
+    Detached,
+}
+
+/// A view of the interpreter while it is paused
+pub struct Paused<'p, 'a, 'b, 'hooks> {
+    env: &'p mut Environment<'a, 'b, 'hooks>,
+    context: &'p LocalContext<'p>,
+    expr: &'p SymbolicExpression,
+    stack: &'p [StackFrame],
+    pub reason: PauseReason,
+    pub breakpoints: &'p mut Breakpoints,
+}
+
+impl Paused<'_, '_, '_, '_> {
+    /// The expression about to be evaluated
+    pub fn expression(&self) -> &SymbolicExpression {
+        self.expr
+    }
+
+    pub fn contract(&self) -> &QualifiedContractIdentifier {
+        &self.env.contract_context.contract_identifier
+    }
+
+    /// The call stack, outermost frame first
+    pub fn stack(&self) -> &[StackFrame] {
+        self.stack
+    }
+
+    /// The local bindings in scope, innermost first
+    pub fn locals(&self) -> Vec<(ClarityName, Value)> {
+        let mut locals: Vec<(ClarityName, Value)> = vec![];
+        let mut context = Some(self.context);
+        while let Some(current) = context {
+            let mut names: Vec<_> = current.variables.iter().collect();
+            names.sort_by(|a, b| a.0.cmp(b.0));
+            for (name, value) in names {
+                if !locals.iter().any(|(bound, _)| bound == name) {
+                    locals.push((name.clone(), value.clone()));
+                }
+            }
+            context = current.parent;
+        }
+        locals
+    }
+
+    fn epoch(&self) -> StacksEpochId {
+        *self.env.epoch()
+    }
+
+    /// The current values of the contract's data-vars, sorted by name
+    pub fn data_vars(&mut self) -> Vec<(ClarityName, Result<Value, String>)> {
+        let epoch = self.epoch();
+        let contract_context = self.env.contract_context;
+        let mut names: Vec<_> = contract_context.meta_data_var.iter().collect();
+        names.sort_by(|a, b| a.0.cmp(b.0));
+        names
+            .into_iter()
+            .map(|(name, metadata)| {
+                let value = self
+                    .env
+                    .global_context
+                    .database
+                    .lookup_variable(
+                        &contract_context.contract_identifier,
+                        name,
+                        metadata,
+                        &epoch,
+                    )
+                    .map_err(|e| e.to_string());
+                (name.clone(), value)
+            })
+            .collect()
+    }
+
+    /// Look up an entry of one of the contract's maps. `key` is a Clarity
+    ///  expression, evaluated in the paused context.
+    pub fn map_entry(&mut self, map: &str, key: &str) -> Result<Value, String> {
+        let epoch = self.epoch();
+        let contract_context = self.env.contract_context;
+        let metadata = contract_context
+            .meta_data_map
+            .get(map)
+            .ok_or_else(|| format!("no map named `{map}`"))?;
+        let key = self.evaluate(key)?;
+        self.env
+            .global_context
+            .database
+            .fetch_entry(
+                &contract_context.contract_identifier,
+                map,
+                &key,
+                metadata,
+                &epoch,
+            )
+            .map_err(|e| e.to_string())
+    }
+
+    /// The source of the paused contract, as stored in the database
+    pub fn source(&mut self) -> Option<String> {
+        let contract = self.env.contract_context.contract_identifier.clone();
+        self.env.global_context.database.get_contract_src(&contract)
+    }
+
+    /// Evaluate a Clarity expression in the paused context. Any writes it
+    ///  makes are rolled back, and it is not charged to the transaction's costs.
+    pub fn evaluate(&mut self, program: &str) -> Result<Value, String> {
+        let contract = self.contract().clone();
+        let version = *self.env.contract_context.get_clarity_version();
+        let epoch = self.epoch();
+        let expressions = build_ast_with_rules(
+            &contract,
+            program,
+            &mut (),
+            version,
+            epoch,
+            ASTRules::PrecheckSize,
+        )
+        .map_err(|e| e.to_string())?
+        .expressions;
+        let [expr] = expressions.as_slice() else {
+            return Err("expected a single expression".into());
+        };
+
+        let cost_track = std::mem::replace(
+            &mut self.env.global_context.cost_track,
+            LimitedCostTracker::new_free(),
+        );
+        self.env.global_context.begin();
+        let result = eval(expr, self.env, self.context);
+        let rolled_back = self.env.global_context.roll_back();
+        self.env.global_context.cost_track = cost_track;
+        rolled_back.map_err(|e| e.to_string())?;
+        result.map_err(|e| e.to_string())
+    }
+}
+
+/// The user interface of the debugger
+pub trait DebugFrontend {
+    /// Called whenever execution pauses. Returns how execution should resume.
+    fn paused(&mut self, paused: &mut Paused) -> Resume;
+}
+
+/// An `EvalHook` that pauses evaluation at breakpoints and steps, and hands
+///  control to a `DebugFrontend` while paused.
+pub struct Debugger<F: DebugFrontend> {
+    frontend: F,
+    breakpoints: Breakpoints,
+    stack: Vec<StackFrame>,
+    mode: StepMode,
+    stop_on_entry: bool,
+    /// Set when the top frame's line changes, or a frame is pushed. Pauses
+    ///  only happen on new lines, so that a line with several expressions
+    ///  stops once.
+    moved: bool,
+}
+
+impl<F: DebugFrontend> Debugger<F> {
+    pub fn new(frontend: F) -> Debugger<F> {
+        Debugger {
+            frontend,
+            breakpoints: Breakpoints::default(),
+            stack: vec![],
+            mode: StepMode::Continue,
+            stop_on_entry: false,
+            moved: false,
+        }
+    }
+
+    /// Pause before the first expression is evaluated
+    pub fn stop_on_entry(&mut self) {
+        self.stop_on_entry = true;
+    }
+
+    pub fn breakpoints_mut(&mut self) -> &mut Breakpoints {
+        &mut self.breakpoints
+    }
+
+    pub fn frontend(&self) -> &F {
+        &self.frontend
+    }
+
+    pub fn frontend_mut(&mut self) -> &mut F {
+        &mut self.frontend
+    }
+
+    pub fn into_frontend(self) -> F {
+        self.frontend
+    }
+
+    /// Update the tracked call stack for an expression that is about to be evaluated
+    fn enter(&mut self, env: &Environment, expr: &SymbolicExpression) {
+        let contract = &env.contract_context.contract_identifier;
+        let function = env
+            .contract_context
+            .functions
+            .iter()
+            .find(|(_, function)| function.get_body().id == expr.id)
+            .map(|(name, _)| name.clone());
+
+        if function.is_some() || self.stack.is_empty() {
+            self.stack.push(StackFrame {
+                contract: contract.clone(),
+                body_id: function.as_ref().map(|_| expr.id),
+                function,
+                span: expr.span().clone(),
+            });
+            self.moved = true;
+        } else if let Some(frame) = self.stack.last_mut() {
+            // without spans, every expression is on line 0
+            self.moved = !Span::TRACKED || frame.span.start_line != expr.span().start_line;
+            frame.span = expr.span().clone();
+        }
+    }
+
+    fn pause_reason(&self) -> Option<PauseReason> {
+        if self.stop_on_entry {
+            return Some(PauseReason::Entry);
+        }
+        if self.mode == StepMode::Detached || !self.moved {
+            return None;
+        }
+        let frame = self.stack.last()?;
+        if let Some(id) = self
+            .breakpoints
+            .find(&frame.contract, frame.span.start_line)
+        {
+            return Some(PauseReason::Breakpoint(id));
+        }
+        let depth = self.stack.len();
+        match self.mode {
+            StepMode::StepIn => Some(PauseReason::Step),
+            StepMode::StepOver(from) if depth <= from => Some(PauseReason::Step),
+            StepMode::StepOut(from) if depth < from => Some(PauseReason::Step),
+            _ => None,
+        }
+    }
+}
+
+impl<F: DebugFrontend> EvalHook for Debugger<F> {
+    fn will_begin_eval(
+        &mut self,
+        env: &mut Environment,
+        context: &LocalContext,
+        expr: &SymbolicExpression,
+    ) {
+        self.enter(env, expr);
+        let depth = self.stack.len();
+        // stepping out pauses at the first expression evaluated in the caller,
+        //  even if it is on the line of the call
+        if let StepMode::StepOut(from) = self.mode {
+            self.moved |= depth < from;
+        }
+        let Some(reason) = self.pause_reason() else {
+            return;
+        };
+
+        let mut paused = Paused {
+            env,
+            context,
+            expr,
+            stack: &self.stack,
+            reason,
+            breakpoints: &mut self.breakpoints,
+        };
+        self.mode = match self.frontend.paused(&mut paused) {
+            Resume::Continue => StepMode::Continue,
+            Resume::StepIn => StepMode::StepIn,
+            Resume::StepOver => StepMode::StepOver(depth),
+            Resume::StepOut => StepMode::StepOut(depth),
+            Resume::Detach => StepMode::Detached,
+        };
+        self.moved = false;
+        self.stop_on_entry = false;
+    }
+
+    fn did_finish_eval(
+        &mut self,
+        env: &mut Environment,
+        _context: &LocalContext,
+        expr: &SymbolicExpression,
+        _res: &core::result::Result<Value, Error>,
+    ) {
+        let returned = self.stack.last().is_some_and(|frame| {
+            frame.body_id == Some(expr.id)
+                && frame.contract == env.contract_context.contract_identifier
+        });
+        if returned {
+            self.stack.pop();
+        }
+    }
+
+    fn did_complete(&mut self, _result: core::result::Result<&mut ExecutionResult, String>) {}
+}
+
+#[cfg(test)]
+mod tests;
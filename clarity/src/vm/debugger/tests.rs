@@ -0,0 +1,395 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::io::Cursor;
+
+use serde_json::{json, Value as JsonValue};
+use stacks_common::types::StacksEpochId;
+
+use super::cli::parse_breakpoint;
+#[cfg(feature = "developer-mode")]
+use super::cli::CommandLineFrontend;
+use super::dap::{DapFrontend, LaunchOptions};
+use super::*;
+use crate::vm::ast::ASTRules;
+use crate::vm::contexts::OwnedEnvironment;
+use crate::vm::database::MemoryBackingStore;
+use crate::vm::tooling::lsp::{read_message, write_message};
+use crate::vm::types::PrincipalData;
+use crate::vm::ClarityVersion;
+
+const CONTRACT: &str = "(define-data-var counter uint u0)
+(define-map balances principal uint)
+(define-private (bump (by uint))
+  (let ((next (+ (var-get counter) by)))
+    (var-set counter next)
+    next))
+(define-public (main)
+  (begin
+    (map-set balances tx-sender u10)
+    (bump u1)
+    (ok (bump u2))))";
+
+fn contract_id() -> QualifiedContractIdentifier {
+    QualifiedContractIdentifier::local("debugged").unwrap()
+}
+
+/// Deploy `CONTRACT`, then call `main` with the debugger attached
+fn run_main<F: DebugFrontend>(debugger: &mut Debugger<F>) -> Value {
+    let mut marf = MemoryBackingStore::new();
+    let mut owned_env = OwnedEnvironment::new(marf.as_clarity_db(), StacksEpochId::Epoch25);
+    owned_env
+        .initialize_versioned_contract(
+            contract_id(),
+            ClarityVersion::Clarity2,
+            CONTRACT,
+            None,
+            ASTRules::PrecheckSize,
+        )
+        .unwrap();
+    owned_env.add_eval_hook(debugger);
+    let sender = PrincipalData::parse("S1G2081040G2081040G2081040G208105NK8PE5").unwrap();
+    owned_env
+        .execute_transaction(sender, None, contract_id(), "main", &[])
+        .unwrap()
+        .0
+}
+
+/// The pause reason, and the functions on the stack, innermost first
+type Pause = (PauseReason, Vec<String>);
+
+/// A front-end which records every pause, and asks a callback how to resume
+struct ScriptedFrontend {
+    pauses: Vec<Pause>,
+    /// Called on every pause, with the pauses so far (including this one)
+    resume: fn(&mut Paused, &[Pause]) -> Resume,
+}
+
+impl ScriptedFrontend {
+    fn new(resume: fn(&mut Paused, &[Pause]) -> Resume) -> ScriptedFrontend {
+        ScriptedFrontend {
+            pauses: vec![],
+            resume,
+        }
+    }
+}
+
+impl DebugFrontend for ScriptedFrontend {
+    fn paused(&mut self, paused: &mut Paused) -> Resume {
+        let stack = paused
+            .stack()
+            .iter()
+            .rev()
+            .map(|frame| frame.function.as_ref().unwrap().to_string())
+            .collect();
+        self.pauses.push((paused.reason, stack));
+        (self.resume)(paused, &self.pauses)
+    }
+}
+
+#[test]
+fn test_step_into_and_out_of_calls() {
+    // step in until `bump` is entered, then step out of it
+    let mut debugger = Debugger::new(ScriptedFrontend::new(|paused, pauses| {
+        let entered = pauses.iter().any(|(_, stack)| stack.len() == 2);
+        match paused.stack().len() {
+            _ if !entered => Resume::StepIn,
+            2 => Resume::StepOut,
+            _ => Resume::Continue,
+        }
+    }));
+    debugger.stop_on_entry();
+    let result = run_main(&mut debugger);
+    assert_eq!(result, Value::okay(Value::UInt(3)).unwrap());
+
+    let pauses = &debugger.frontend().pauses;
+    assert_eq!(pauses[0], (PauseReason::Entry, vec!["main".to_string()]));
+    // stepping in from `main` reaches the first call to `bump` ...
+    let entered = pauses
+        .iter()
+        .position(|(_, stack)| stack.len() == 2)
+        .unwrap();
+    assert_eq!(pauses[entered].1, vec!["bump", "main"]);
+    // ... and stepping out of it returns to `main`
+    assert_eq!(
+        pauses[entered + 1],
+        (PauseReason::Step, vec!["main".into()])
+    );
+    assert_eq!(pauses.len(), entered + 2);
+}
+
+#[test]
+fn test_detach() {
+    let mut debugger = Debugger::new(ScriptedFrontend::new(|_, _| Resume::Detach));
+    debugger.stop_on_entry();
+    if Span::TRACKED {
+        debugger
+            .breakpoints_mut()
+            .add(ContractSelector::Name("debugged".into()), 5)
+            .unwrap();
+    }
+    run_main(&mut debugger);
+    assert_eq!(debugger.frontend().pauses.len(), 1);
+}
+
+#[test]
+fn test_breakpoints_need_spans() {
+    let mut breakpoints = Breakpoints::default();
+    let added = breakpoints.add(ContractSelector::Name("debugged".into()), 5);
+    assert_eq!(added.is_ok(), Span::TRACKED);
+    assert_eq!(breakpoints.iter().count(), usize::from(Span::TRACKED));
+}
+
+#[test]
+fn test_parse_breakpoint() {
+    let current = contract_id();
+    assert_eq!(
+        parse_breakpoint("12", Some(&current)).unwrap(),
+        (ContractSelector::Identifier(current.clone()), 12)
+    );
+    assert_eq!(
+        parse_breakpoint("S1G2081040G2081040G2081040G208105NK8PE5.tokens:3", None).unwrap(),
+        (
+            ContractSelector::Identifier(
+                QualifiedContractIdentifier::parse(
+                    "S1G2081040G2081040G2081040G208105NK8PE5.tokens"
+                )
+                .unwrap()
+            ),
+            3
+        )
+    );
+    assert_eq!(
+        parse_breakpoint("tokens:3", None).unwrap(),
+        (ContractSelector::Name("tokens".into()), 3)
+    );
+    assert!(parse_breakpoint("12", None).is_err());
+    assert!(parse_breakpoint("tokens:x", None).is_err());
+}
+
+#[cfg(feature = "developer-mode")]
+#[test]
+fn test_breakpoint_and_inspection() {
+    let frontend = ScriptedFrontend::new(|paused, pauses| {
+        // (counter, by) when each call to `bump` reaches the breakpoint
+        let (counter, by) = [(0, 1), (1, 2)][pauses.len() - 1];
+        assert_eq!(paused.expression().span().start_line, 5);
+        let locals: Vec<_> = paused
+            .locals()
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect();
+        assert_eq!(
+            locals,
+            vec![
+                ("next".to_string(), Value::UInt(counter + by)),
+                ("by".to_string(), Value::UInt(by))
+            ]
+        );
+        assert_eq!(
+            paused.data_vars(),
+            vec![("counter".into(), Ok(Value::UInt(counter)))]
+        );
+        assert_eq!(
+            paused.map_entry("balances", "tx-sender"),
+            Ok(Value::some(Value::UInt(10)).unwrap())
+        );
+        // writes made while inspecting are rolled back
+        assert_eq!(
+            paused.evaluate("(var-set counter u100)"),
+            Ok(Value::Bool(true))
+        );
+        assert_eq!(
+            paused.evaluate("(var-get counter)"),
+            Ok(Value::UInt(counter))
+        );
+        assert!(paused.evaluate("(var-get nope)").is_err());
+        Resume::Continue
+    });
+    let mut debugger = Debugger::new(frontend);
+    let id = debugger
+        .breakpoints_mut()
+        .add(ContractSelector::Identifier(contract_id()), 5)
+        .unwrap();
+    run_main(&mut debugger);
+
+    // the breakpoint is hit once per call to `bump`
+    let pauses = &debugger.frontend().pauses;
+    assert_eq!(pauses.len(), 2);
+    assert!(pauses.iter().all(|pause| pause
+        == &(
+            PauseReason::Breakpoint(id),
+            vec!["bump".into(), "main".into()]
+        )));
+}
+
+#[cfg(feature = "developer-mode")]
+#[test]
+fn test_command_line_frontend() {
+    let input =
+        "help\nlocals\nvars\nmap balances tx-sender\np (+ next u1)\nbt\nbreak 10\nn\nquit\n";
+    let frontend = CommandLineFrontend::new(Cursor::new(input), vec![]);
+    let mut debugger = Debugger::new(frontend);
+    debugger
+        .breakpoints_mut()
+        .add(ContractSelector::Name("debugged".into()), 6)
+        .unwrap();
+    run_main(&mut debugger);
+
+    let output = String::from_utf8(debugger.into_frontend().into_output()).unwrap();
+    for expected in [
+        "Paused (breakpoint 1) in bump at S1G2081040G2081040G2081040G208105NK8PE5.debugged:6:",
+        "    6 |     next))",
+        "next = u1\nby = u1\n",
+        "counter = u1\n",
+        "(some u10)\n",
+        "u2\n",
+        "#0 bump at ",
+        "#1 main at ",
+        "Breakpoint 2 at S1G2081040G2081040G2081040G208105NK8PE5.debugged:10",
+        "Paused (step) in main at S1G2081040G2081040G2081040G208105NK8PE5.debugged:11:",
+    ] {
+        assert!(
+            output.contains(expected),
+            "missing {expected:?} in:\n{output}"
+        );
+    }
+}
+
+fn dap_input(requests: &[JsonValue]) -> Cursor<Vec<u8>> {
+    let mut input = vec![];
+    for (seq, request) in requests.iter().enumerate() {
+        let mut request = request.clone();
+        request["seq"] = json!(seq + 1);
+        request["type"] = json!("request");
+        write_message(&mut input, &request).unwrap();
+    }
+    Cursor::new(input)
+}
+
+fn dap_output(output: Vec<u8>) -> Vec<JsonValue> {
+    let mut output = Cursor::new(output);
+    let mut messages = vec![];
+    while let Some(message) = read_message(&mut output).unwrap() {
+        messages.push(message);
+    }
+    messages
+}
+
+#[test]
+fn test_dap_configuration() {
+    let input = dap_input(&[
+        json!({ "command": "initialize", "arguments": { "adapterID": "clarity" } }),
+        json!({ "command": "launch", "arguments": { "stopOnEntry": true } }),
+        json!({
+            "command": "setBreakpoints",
+            "arguments": {
+                "source": { "path": "/src/debugged.clar" },
+                "breakpoints": [{ "line": 5 }, { "line": 10 }]
+            }
+        }),
+        json!({ "command": "configurationDone" }),
+    ]);
+    let mut frontend = DapFrontend::new(input, vec![]);
+    let mut breakpoints = Breakpoints::default();
+    let options = frontend.configure(&mut breakpoints).unwrap();
+    assert_eq!(
+        options,
+        Some(LaunchOptions {
+            stop_on_entry: true
+        })
+    );
+    let lines: Vec<_> = breakpoints.iter().map(|b| b.line).collect();
+    assert_eq!(lines, if Span::TRACKED { vec![5, 10] } else { vec![] });
+    assert!(breakpoints
+        .iter()
+        .all(|b| b.contract.matches(&contract_id())));
+
+    let messages = dap_output(frontend.into_output());
+    let kinds: Vec<_> = messages
+        .iter()
+        .map(|m| {
+            m["command"]
+                .as_str()
+                .or(m["event"].as_str())
+                .unwrap()
+                .to_string()
+        })
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            "initialize",
+            "initialized",
+            "launch",
+            "setBreakpoints",
+            "configurationDone"
+        ]
+    );
+    assert!(messages.iter().all(|m| m["success"] != json!(false)));
+    if Span::TRACKED {
+        assert_eq!(
+            messages[3]["body"]["breakpoints"][1],
+            json!({ "id": 2, "verified": true, "line": 10 })
+        );
+    } else {
+        assert_eq!(
+            messages[3]["body"]["breakpoints"][1]["verified"],
+            json!(false)
+        );
+    }
+}
+
+#[test]
+fn test_dap_session() {
+    let input = dap_input(&[
+        json!({ "command": "threads" }),
+        json!({ "command": "stackTrace", "arguments": { "threadId": 1 } }),
+        json!({ "command": "scopes", "arguments": { "frameId": 0 } }),
+        json!({ "command": "variables", "arguments": { "variablesReference": 2 } }),
+        json!({ "command": "evaluate", "arguments": { "expression": "(var-get counter)" } }),
+        json!({ "command": "evaluate", "arguments": { "expression": "(" } }),
+        json!({ "command": "continue", "arguments": { "threadId": 1 } }),
+        json!({ "command": "disconnect" }),
+    ]);
+    let mut debugger = Debugger::new(DapFrontend::new(input, vec![]));
+    debugger.stop_on_entry();
+    run_main(&mut debugger);
+    debugger.frontend_mut().finish("(ok u3)", 0).unwrap();
+
+    let messages = dap_output(debugger.into_frontend().into_output());
+    assert_eq!(messages[0]["event"], "stopped");
+    assert_eq!(messages[0]["body"]["reason"], "entry");
+    assert_eq!(messages[1]["body"]["threads"][0]["id"], 1);
+    assert_eq!(
+        messages[2]["body"]["stackFrames"][0]["name"],
+        "S1G2081040G2081040G2081040G208105NK8PE5.debugged::main"
+    );
+    assert_eq!(messages[3]["body"]["scopes"][1]["name"], "Data");
+    assert_eq!(
+        messages[4]["body"]["variables"],
+        json!([{ "name": "counter", "value": "u0", "variablesReference": 0 }])
+    );
+    assert_eq!(messages[5]["body"]["result"], "u0");
+    assert_eq!(messages[6]["success"], false);
+    assert_eq!(messages[7]["command"], "continue");
+
+    let events: Vec<_> = messages[8..]
+        .iter()
+        .map(|m| m["event"].as_str().or(m["command"].as_str()).unwrap())
+        .collect();
+    assert_eq!(events, vec!["output", "exited", "terminated", "disconnect"]);
+    assert_eq!(messages[8]["body"]["output"], "(ok u3)\n");
+}
@@ -39,9 +39,14 @@ pub mod docs;
 pub mod version;
 
 pub mod coverage;
+pub mod profiler;
+pub mod tracer;
 
 pub mod events;
 
+#[cfg(feature = "canonical")]
+pub mod debugger;
+
 #[cfg(feature = "canonical")]
 pub mod tooling;
 
@@ -0,0 +1,384 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! An `EvalHook` which attributes execution costs to the expressions and
+//! functions that incurred them.
+//!
+//! The profiler samples the environment's `LimitedCostTracker` as each list
+//! expression begins and finishes evaluation. The difference is the
+//! expression's total (inclusive) cost; its self cost is what remains after
+//! subtracting the totals of the list expressions nested in it. Costs charged
+//! while evaluating atoms (such as variable lookups) are part of the self cost
+//! of the enclosing list expression. Costs charged outside of any expression,
+//! such as loading the called contract, are not attributed.
+//!
+//! Under a free cost tracker, every cost is zero.
+
+use std::fmt;
+use std::io::Write;
+use std::str::FromStr;
+
+use hashbrown::HashMap;
+
+use super::EvalHook;
+use crate::vm::contexts::{Environment, LocalContext};
+use crate::vm::costs::ExecutionCost;
+use crate::vm::errors::Error;
+use crate::vm::representations::ClarityName;
+use crate::vm::types::QualifiedContractIdentifier;
+use crate::vm::{SymbolicExpression, Value};
+
+/// One dimension of an `ExecutionCost`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CostDimension {
+    Runtime,
+    ReadCount,
+    ReadLength,
+    WriteCount,
+    WriteLength,
+}
+
+impl CostDimension {
+    pub const ALL: [CostDimension; 5] = [
+        CostDimension::Runtime,
+        CostDimension::ReadCount,
+        CostDimension::ReadLength,
+        CostDimension::WriteCount,
+        CostDimension::WriteLength,
+    ];
+
+    /// The name of the dimension, as serialized in an `ExecutionCost`
+    pub fn name(&self) -> &'static str {
+        match self {
+            CostDimension::Runtime => "runtime",
+            CostDimension::ReadCount => "read_count",
+            CostDimension::ReadLength => "read_length",
+            CostDimension::WriteCount => "write_count",
+            CostDimension::WriteLength => "write_length",
+        }
+    }
+
+    pub fn of(&self, cost: &ExecutionCost) -> u64 {
+        match self {
+            CostDimension::Runtime => cost.runtime,
+            CostDimension::ReadCount => cost.read_count,
+            CostDimension::ReadLength => cost.read_length,
+            CostDimension::WriteCount => cost.write_count,
+            CostDimension::WriteLength => cost.write_length,
+        }
+    }
+}
+
+impl fmt::Display for CostDimension {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for CostDimension {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<CostDimension, String> {
+        CostDimension::ALL
+            .into_iter()
+            .find(|dimension| dimension.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<_> = CostDimension::ALL.iter().map(|d| d.name()).collect();
+                format!(
+                    "unknown cost dimension `{s}`: expected one of {}",
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// `end - start`, per dimension. The tracker's total can decrease when a
+///  nested cost-tracking context is rolled back, in which case the dimension
+///  is clamped to zero.
+fn cost_between(start: &ExecutionCost, end: &ExecutionCost) -> ExecutionCost {
+    ExecutionCost {
+        runtime: end.runtime.saturating_sub(start.runtime),
+        read_count: end.read_count.saturating_sub(start.read_count),
+        read_length: end.read_length.saturating_sub(start.read_length),
+        write_count: end.write_count.saturating_sub(start.write_count),
+        write_length: end.write_length.saturating_sub(start.write_length),
+    }
+}
+
+fn add_cost(total: &mut ExecutionCost, cost: &ExecutionCost) {
+    total.runtime = total.runtime.saturating_add(cost.runtime);
+    total.read_count = total.read_count.saturating_add(cost.read_count);
+    total.read_length = total.read_length.saturating_add(cost.read_length);
+    total.write_count = total.write_count.saturating_add(cost.write_count);
+    total.write_length = total.write_length.saturating_add(cost.write_length);
+}
+
+/// The costs of a user-defined function, summed over its calls
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FunctionProfile {
+    pub contract: String,
+    pub function: String,
+    pub calls: u64,
+    /// Cost of the function's body, including the functions it called
+    pub total: ExecutionCost,
+    /// Cost of the function's body, excluding the functions it called
+    #[serde(rename = "self")]
+    pub self_cost: ExecutionCost,
+}
+
+/// The costs of a list expression, summed over its evaluations
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExpressionProfile {
+    pub contract: String,
+    /// The id of the expression within its contract
+    pub id: u64,
+    /// The function the expression applies, e.g. `map-set`
+    pub label: String,
+    pub line: u32,
+    pub column: u32,
+    pub evaluations: u64,
+    pub total: ExecutionCost,
+    #[serde(rename = "self")]
+    pub self_cost: ExecutionCost,
+}
+
+/// A list expression which is being evaluated
+struct Frame {
+    contract: QualifiedContractIdentifier,
+    /// Set if the expression is the body of a user-defined function
+    function: Option<ClarityName>,
+    label: String,
+    /// The tracker's total when the expression began evaluation
+    start: ExecutionCost,
+    /// Total cost of the list expressions nested in this one
+    nested: ExecutionCost,
+    /// Total cost of the function calls nested in this one, if it is a
+    ///  function body; otherwise, accumulated into the enclosing frames
+    nested_calls: ExecutionCost,
+}
+
+pub struct CostProfiler {
+    stack: Vec<Frame>,
+    functions: HashMap<(QualifiedContractIdentifier, ClarityName), FunctionProfile>,
+    expressions: HashMap<(QualifiedContractIdentifier, u64), ExpressionProfile>,
+    /// Self cost by call stack, each a `;`-separated list of frames
+    stacks: HashMap<String, ExecutionCost>,
+    /// Total cost of the outermost expressions
+    attributed: ExecutionCost,
+}
+
+impl Default for CostProfiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CostProfiler {
+    pub fn new() -> CostProfiler {
+        CostProfiler {
+            stack: vec![],
+            functions: HashMap::new(),
+            expressions: HashMap::new(),
+            stacks: HashMap::new(),
+            attributed: ExecutionCost::ZERO,
+        }
+    }
+
+    /// The frames of the current call stack, as they appear in folded
+    ///  stacks: user-defined functions as `contract::function`, and other
+    ///  list expressions by the function they apply
+    fn folded_stack(&self) -> String {
+        let frames: Vec<_> = self
+            .stack
+            .iter()
+            .map(|frame| match &frame.function {
+                Some(function) => format!("{}::{}", frame.contract, function),
+                None => frame.label.clone(),
+            })
+            .collect();
+        frames.join(";")
+    }
+
+    fn begin(&mut self, env: &mut Environment, expr: &SymbolicExpression) {
+        let Some(list) = expr.match_list() else {
+            return;
+        };
+        let label = list
+            .first()
+            .and_then(|head| head.match_atom())
+            .map(|name| name.to_string())
+            .unwrap_or_else(|| "<list>".to_string());
+        let function = env
+            .contract_context
+            .functions
+            .iter()
+            .find(|(_, function)| function.get_body().id == expr.id)
+            .map(|(name, _)| name.clone());
+        self.stack.push(Frame {
+            contract: env.contract_context.contract_identifier.clone(),
+            function,
+            label,
+            start: env.global_context.cost_track.get_total(),
+            nested: ExecutionCost::ZERO,
+            nested_calls: ExecutionCost::ZERO,
+        });
+    }
+
+    fn finish(&mut self, env: &mut Environment, expr: &SymbolicExpression) {
+        if expr.match_list().is_none() {
+            return;
+        }
+        let path = self.folded_stack();
+        let Some(frame) = self.stack.pop() else {
+            return;
+        };
+        let total = cost_between(&frame.start, &env.global_context.cost_track.get_total());
+        let self_cost = cost_between(&frame.nested, &total);
+
+        add_cost(
+            self.stacks.entry(path).or_insert(ExecutionCost::ZERO),
+            &self_cost,
+        );
+
+        let expression = self
+            .expressions
+            .entry((frame.contract.clone(), expr.id))
+            .or_insert_with(|| ExpressionProfile {
+                contract: frame.contract.to_string(),
+                id: expr.id,
+                label: frame.label.clone(),
+                line: expr.span().start_line,
+                column: expr.span().start_column,
+                evaluations: 0,
+                total: ExecutionCost::ZERO,
+                self_cost: ExecutionCost::ZERO,
+            });
+        expression.evaluations += 1;
+        add_cost(&mut expression.total, &total);
+        add_cost(&mut expression.self_cost, &self_cost);
+
+        if let Some(function) = &frame.function {
+            let profile = self
+                .functions
+                .entry((frame.contract.clone(), function.clone()))
+                .or_insert_with(|| FunctionProfile {
+                    contract: frame.contract.to_string(),
+                    function: function.to_string(),
+                    calls: 0,
+                    total: ExecutionCost::ZERO,
+                    self_cost: ExecutionCost::ZERO,
+                });
+            profile.calls += 1;
+            add_cost(&mut profile.total, &total);
+            add_cost(
+                &mut profile.self_cost,
+                &cost_between(&frame.nested_calls, &total),
+            );
+        }
+
+        match self.stack.last_mut() {
+            Some(parent) => {
+                add_cost(&mut parent.nested, &total);
+                if frame.function.is_some() {
+                    add_cost(&mut parent.nested_calls, &total);
+                } else {
+                    add_cost(&mut parent.nested_calls, &frame.nested_calls);
+                }
+            }
+            None => add_cost(&mut self.attributed, &total),
+        }
+    }
+
+    /// The profiled functions, most expensive first
+    pub fn functions(&self) -> Vec<FunctionProfile> {
+        let mut functions: Vec<_> = self.functions.values().cloned().collect();
+        functions.sort_by(|a, b| {
+            (b.total.runtime, &a.contract, &a.function).cmp(&(
+                a.total.runtime,
+                &b.contract,
+                &b.function,
+            ))
+        });
+        functions
+    }
+
+    /// The profiled list expressions, highest self cost first
+    pub fn expressions(&self) -> Vec<ExpressionProfile> {
+        let mut expressions: Vec<_> = self.expressions.values().cloned().collect();
+        expressions.sort_by(|a, b| {
+            (b.self_cost.runtime, &a.contract, a.id).cmp(&(a.self_cost.runtime, &b.contract, b.id))
+        });
+        expressions
+    }
+
+    /// The cost of every expression evaluated at the top level
+    pub fn attributed(&self) -> &ExecutionCost {
+        &self.attributed
+    }
+
+    /// Write one dimension of the self costs in the folded-stack format read
+    ///  by flamegraph tools: one `frame;frame;frame cost` line per call stack.
+    pub fn write_folded<W: Write>(
+        &self,
+        output: &mut W,
+        dimension: CostDimension,
+    ) -> std::io::Result<()> {
+        let mut stacks: Vec<_> = self
+            .stacks
+            .iter()
+            .map(|(path, cost)| (path, dimension.of(cost)))
+            .filter(|(_, cost)| *cost > 0)
+            .collect();
+        stacks.sort();
+        for (path, cost) in stacks {
+            writeln!(output, "{path} {cost}")?;
+        }
+        Ok(())
+    }
+
+    /// The per-function and per-expression tables, along with the part of
+    ///  `total` which no expression accounts for
+    pub fn to_json(&self, total: &ExecutionCost) -> serde_json::Value {
+        serde_json::json!({
+            "total": total,
+            "unattributed": cost_between(&self.attributed, total),
+            "functions": self.functions(),
+            "expressions": self.expressions(),
+        })
+    }
+}
+
+impl EvalHook for CostProfiler {
+    fn will_begin_eval(
+        &mut self,
+        env: &mut Environment,
+        _context: &LocalContext,
+        expr: &SymbolicExpression,
+    ) {
+        self.begin(env, expr);
+    }
+
+    fn did_finish_eval(
+        &mut self,
+        env: &mut Environment,
+        _context: &LocalContext,
+        expr: &SymbolicExpression,
+        _res: &Result<Value, Error>,
+    ) {
+        self.finish(env, expr);
+    }
+
+    fn did_complete(&mut self, _result: Result<&mut crate::vm::ExecutionResult, String>) {}
+}
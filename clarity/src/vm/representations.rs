@@ -659,6 +659,10 @@ pub struct Span {
 }
 
 impl Span {
+    /// Whether expressions carry their spans in this build.  They only do with the
+    /// `developer-mode` feature; otherwise every span is `Span::ZERO`.
+    pub const TRACKED: bool = cfg!(feature = "developer-mode");
+
     pub const ZERO: Span = Span {
         start_line: 0,
         start_column: 0,
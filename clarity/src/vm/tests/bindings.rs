@@ -0,0 +1,199 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use stacks_common::types::StacksEpochId;
+
+use crate::vm::analysis::contract_interface_builder::build_contract_interface;
+use crate::vm::tooling::bindings::rust::emit_rust;
+use crate::vm::tooling::bindings::typescript::emit_typescript;
+use crate::vm::tooling::bindings::{runtime, BindingType, Bindings, NamedTypeKind};
+use crate::vm::tooling::mem_type_check;
+use crate::vm::{execute_v2, ClarityVersion, Value};
+
+const CONTRACT: &str = "(define-public (transfer
+    (amount uint)
+    (recipient principal)
+    (memo (optional (buff 34)))
+    (meta {tag: (string-ascii 10), notes: (list 3 (string-utf8 20))}))
+  (ok {amount: amount, recipient: recipient}))
+(define-read-only (get-balance (who principal))
+  (if (is-eq who tx-sender) (ok u1) (err u2)))
+(define-read-only (get-type) (ok {kind: 1}))
+(define-private (helper) true)";
+
+fn bindings() -> Bindings {
+    let (_, analysis) =
+        mem_type_check(CONTRACT, ClarityVersion::Clarity2, StacksEpochId::Epoch25).unwrap();
+    let interface = build_contract_interface(&analysis).unwrap();
+    Bindings::from_interface("token-vault", &interface)
+}
+
+#[test]
+fn test_bindings_model() {
+    let bindings = bindings();
+    assert_eq!(bindings.contract_type_name(), "TokenVault");
+
+    let functions: Vec<_> = bindings
+        .functions
+        .iter()
+        .map(|f| (f.name.as_str(), f.read_only))
+        .collect();
+    assert_eq!(
+        functions,
+        [
+            ("transfer", false),
+            ("get-balance", true),
+            ("get-type", true)
+        ]
+    );
+
+    let types: Vec<_> = bindings
+        .types
+        .iter()
+        .map(|named| (named.name.as_str(), named.encode, named.decode))
+        .collect();
+    assert_eq!(
+        types,
+        [
+            ("TransferMeta", true, false),
+            ("TransferOk", false, true),
+            ("TransferResponse", false, true),
+            ("GetBalanceResponse", false, true),
+            ("GetTypeOk", false, true),
+            ("GetTypeResponse", false, true),
+        ]
+    );
+
+    let transfer = &bindings.functions[0];
+    assert_eq!(
+        transfer.args[2].binding_type,
+        BindingType::Optional(Box::new(BindingType::Buffer))
+    );
+    match &bindings.types[0].kind {
+        NamedTypeKind::Struct(fields) => {
+            let fields: Vec<_> = fields
+                .iter()
+                .map(|field| (field.name.as_str(), field.binding_type.clone()))
+                .collect();
+            assert_eq!(
+                fields,
+                [
+                    (
+                        "notes",
+                        BindingType::List(Box::new(BindingType::StringUtf8))
+                    ),
+                    ("tag", BindingType::StringAscii),
+                ]
+            );
+        }
+        kind => panic!("unexpected kind {kind:?}"),
+    }
+    assert_eq!(
+        bindings.types[3].kind,
+        NamedTypeKind::Response {
+            ok: BindingType::UInt,
+            err: BindingType::UInt
+        }
+    );
+}
+
+#[test]
+fn test_emit_rust() {
+    let code = emit_rust(&bindings());
+    for expected in [
+        "use blockstack_lib::chainstate::stacks::TransactionContractCall;",
+        "use clarity::vm::types::PrincipalData;",
+        "/// `(tuple (notes (list 3 (string-utf8 20))) (tag (string-ascii 10)))`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransferMeta {
+    pub notes: Vec<String>,
+    pub tag: String,
+}",
+        "            (\"notes\", runtime::encode_list(&self.notes, |item| Value::string_utf8_from_bytes(item.clone().into_bytes()))?),",
+        "pub enum GetBalanceResponse {
+    Ok(u128),
+    Err(u128),
+}",
+        "            Ok(ok) => Self::Ok(ok.expect_u128()?),",
+        "    pub fn transfer(&self, amount: u128, recipient: PrincipalData, memo: Option<Vec<u8>>, meta: TransferMeta) -> Result<TransactionContractCall, Error> {
+        self.contract_call(\"transfer\", vec![Value::UInt(amount), Value::Principal(recipient.clone()), runtime::encode_optional(memo.as_ref(), |item| Value::buff_from(item.clone()))?, meta.to_value()?])
+    }",
+        "    pub fn decode_get_type(value: Value) -> Result<GetTypeResponse, Error> {",
+    ] {
+        assert!(code.contains(expected), "missing {expected}\nin\n{code}");
+    }
+    // private functions are not callable, and so not bound
+    assert!(!code.contains("helper"));
+}
+
+#[test]
+fn test_emit_typescript() {
+    let code = emit_typescript(&bindings());
+    for expected in [
+        "import { Cl, ClarityType, ClarityValue, principalToString } from '@stacks/transactions';",
+        "export interface TransferMeta {
+  notes: string[];
+  tag: string;
+}",
+        "export function encodeTransferMeta(value: TransferMeta): ClarityValue {
+  return Cl.tuple({
+    'notes': Cl.list(value.notes.map((item) => Cl.stringUtf8(item))),
+    'tag': Cl.stringAscii(value.tag),
+  });
+}",
+        "export type GetBalanceResponse = { ok: true; value: bigint } | { ok: false; value: bigint };",
+        "  transfer(amount: bigint, recipient: string, memo: Uint8Array | null, meta: TransferMeta): ContractCall {
+    return this.contractCall('transfer', [Cl.uint(amount), Cl.principal(recipient), (memo === null ? Cl.none() : Cl.some(Cl.buffer(memo))), encodeTransferMeta(meta)]);
+  }",
+        "  static decodeGetBalance(value: ClarityValue): GetBalanceResponse {",
+        "function decodeUInt(value: ClarityValue): bigint {",
+    ] {
+        assert!(code.contains(expected), "missing {expected}\nin\n{code}");
+    }
+    assert!(!code.contains("function decodeStringAscii"));
+}
+
+#[test]
+fn test_runtime_round_trip() {
+    let value = runtime::encode_tuple(vec![
+        (
+            "notes",
+            runtime::encode_list(&["a".to_string(), "\u{1F600}".to_string()], |item| {
+                Value::string_utf8_from_bytes(item.clone().into_bytes())
+            })
+            .unwrap(),
+        ),
+        (
+            "memo",
+            runtime::encode_optional(Some(&vec![1u8, 2]), |item| Value::buff_from(item.clone()))
+                .unwrap(),
+        ),
+    ])
+    .unwrap();
+    assert_eq!(
+        value,
+        execute_v2("{notes: (list u\"a\" u\"\\u{1F600}\"), memo: (some 0x0102)}")
+            .unwrap()
+            .unwrap()
+    );
+
+    let mut fields = runtime::Fields::new(value).unwrap();
+    let notes = runtime::decode_list(fields.take("notes").unwrap(), runtime::decode_utf8).unwrap();
+    assert_eq!(notes, ["a", "\u{1F600}"]);
+    let memo =
+        runtime::decode_optional(fields.take("memo").unwrap(), runtime::decode_buffer).unwrap();
+    assert_eq!(memo, Some(vec![1, 2]));
+    assert!(fields.take("memo").is_err());
+}
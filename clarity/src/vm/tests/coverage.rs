@@ -0,0 +1,193 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use stacks_common::types::StacksEpochId;
+
+use crate::vm::ast::{parse, ASTRules};
+use crate::vm::contexts::OwnedEnvironment;
+use crate::vm::coverage::CoverageReporter;
+use crate::vm::database::MemoryBackingStore;
+use crate::vm::types::{PrincipalData, QualifiedContractIdentifier};
+use crate::vm::{ClarityVersion, SymbolicExpression, Value};
+
+const CONTRACT: &str = "(define-public (sign (n int))
+  (if (> n 0) (ok n) (err n)))
+(define-public (pick (n int))
+  (begin
+    (asserts! (< n 10) (err 10))
+    (ok (and (> n 1) (< n 5)))))
+(define-public (never (x (optional int)))
+  (ok (unwrap! x (err 0))))";
+
+/// A fresh, empty directory for the coverage files of one test
+fn coverage_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("clarity-coverage-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Run `CONTRACT` with coverage enabled, and return the paths of its
+///  `.clarcovref` and `.clarcov` files
+fn run_with_coverage(dir: &Path) -> (PathBuf, PathBuf) {
+    let contract_id = QualifiedContractIdentifier::local("covered").unwrap();
+    let src_file = dir.join("covered.clar");
+    fs::write(&src_file, CONTRACT).unwrap();
+
+    let register_file = dir.join("covered.clarcovref");
+    let ast = parse(
+        &contract_id,
+        CONTRACT,
+        ClarityVersion::Clarity2,
+        StacksEpochId::Epoch25,
+    )
+    .unwrap();
+    CoverageReporter::register_src_file(
+        &contract_id,
+        src_file.to_str().unwrap(),
+        &ast,
+        &register_file,
+    )
+    .unwrap();
+
+    let mut coverage = CoverageReporter::new();
+    let mut marf = MemoryBackingStore::new();
+    let mut owned_env = OwnedEnvironment::new(marf.as_clarity_db(), StacksEpochId::Epoch25);
+    owned_env
+        .initialize_versioned_contract(
+            contract_id.clone(),
+            ClarityVersion::Clarity2,
+            CONTRACT,
+            None,
+            ASTRules::PrecheckSize,
+        )
+        .unwrap();
+    owned_env.add_eval_hook(&mut coverage);
+    let sender = PrincipalData::parse("S1G2081040G2081040G2081040G208105NK8PE5").unwrap();
+    for (function, n) in [("sign", 1), ("pick", 3), ("pick", 20)] {
+        owned_env
+            .execute_transaction(
+                sender.clone(),
+                None,
+                contract_id.clone(),
+                function,
+                &[SymbolicExpression::atom_value(Value::Int(n))],
+            )
+            .unwrap();
+    }
+    drop(owned_env);
+
+    let coverage_file = dir.join("run.clarcov");
+    coverage.to_file(&coverage_file).unwrap();
+    (register_file, coverage_file)
+}
+
+#[test]
+fn test_lcov_branches() {
+    let dir = coverage_dir("lcov");
+    let (register_file, coverage_file) = run_with_coverage(&dir);
+    let lcov_file = dir.join("lcov.info");
+    CoverageReporter::produce_lcov(
+        lcov_file.to_str().unwrap(),
+        &[register_file],
+        &[coverage_file],
+    )
+    .unwrap();
+
+    let lcov = fs::read_to_string(&lcov_file).unwrap();
+    let branches: Vec<_> = lcov
+        .lines()
+        .filter_map(|line| line.strip_prefix("BRDA:"))
+        .map(|record| {
+            let fields: Vec<_> = record.split(',').collect();
+            (
+                fields[0].parse::<u32>().unwrap(),
+                fields[2].to_string(),
+                fields[3].to_string(),
+            )
+        })
+        .collect();
+
+    // `if` took only its `then` branch; `asserts!` both passed and failed;
+    //  both operands of `and` were evaluated; `unwrap!` was never reached
+    let taken: Vec<_> = branches
+        .iter()
+        .map(|(_, branch, taken)| (branch.as_str(), taken.as_str()))
+        .collect();
+    assert_eq!(
+        taken,
+        vec![
+            ("0", "1"),
+            ("1", "0"),
+            ("0", "1"),
+            ("1", "1"),
+            ("0", "1"),
+            ("1", "1"),
+            ("0", "-"),
+            ("1", "-"),
+        ]
+    );
+    assert!(lcov.contains("BRF:8\n"));
+    assert!(lcov.contains("BRH:5\n"));
+
+    if cfg!(feature = "developer-mode") {
+        let lines: Vec<_> = branches.iter().map(|(line, _, _)| *line).collect();
+        assert_eq!(lines, vec![2, 2, 5, 5, 6, 6, 8, 8]);
+    }
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_cobertura_and_html() {
+    let dir = coverage_dir("reports");
+    let (register_file, coverage_file) = run_with_coverage(&dir);
+    let register_files = [register_file];
+    let coverage_files = [coverage_file];
+
+    let cobertura_file = dir.join("coverage.xml");
+    CoverageReporter::produce_cobertura(
+        cobertura_file.to_str().unwrap(),
+        &register_files,
+        &coverage_files,
+    )
+    .unwrap();
+    let cobertura = fs::read_to_string(&cobertura_file).unwrap();
+    assert!(cobertura.contains(r#"branches-covered="5" branches-valid="8""#));
+    assert!(cobertura.contains(r#"<class name="S1G2081040G2081040G2081040G208105NK8PE5.covered""#));
+    if cfg!(feature = "developer-mode") {
+        assert!(cobertura.contains(
+            r#"<line number="2" hits="3" branch="true" condition-coverage="50% (1/2)"/>"#
+        ));
+    }
+
+    let html_file = dir.join("coverage.html");
+    CoverageReporter::produce_html(
+        html_file.to_str().unwrap(),
+        &register_files,
+        &coverage_files,
+    )
+    .unwrap();
+    let html = fs::read_to_string(&html_file).unwrap();
+    assert!(html.contains("<td>5/8 (62.5%)</td>"));
+    // the source is escaped
+    assert!(html.contains("(define-public (sign (n int))"));
+    assert!(html.contains("(&gt; n 0)"));
+
+    let _ = fs::remove_dir_all(&dir);
+}
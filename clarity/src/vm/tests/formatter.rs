@@ -0,0 +1,147 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::vm::tooling::formatter::{format_source, is_formatted, FormatError};
+
+const BOOT_CONTRACTS: [(&str, &str); 12] = [
+    (
+        "bns",
+        include_str!("../../../../stackslib/src/chainstate/stacks/boot/bns.clar"),
+    ),
+    (
+        "cost-voting",
+        include_str!("../../../../stackslib/src/chainstate/stacks/boot/cost-voting.clar"),
+    ),
+    (
+        "costs-3",
+        include_str!("../../../../stackslib/src/chainstate/stacks/boot/costs-3.clar"),
+    ),
+    (
+        "genesis",
+        include_str!("../../../../stackslib/src/chainstate/stacks/boot/genesis.clar"),
+    ),
+    (
+        "lockup",
+        include_str!("../../../../stackslib/src/chainstate/stacks/boot/lockup.clar"),
+    ),
+    (
+        "pox",
+        include_str!("../../../../stackslib/src/chainstate/stacks/boot/pox.clar"),
+    ),
+    (
+        "pox-2",
+        include_str!("../../../../stackslib/src/chainstate/stacks/boot/pox-2.clar"),
+    ),
+    (
+        "pox-3",
+        include_str!("../../../../stackslib/src/chainstate/stacks/boot/pox-3.clar"),
+    ),
+    (
+        "pox-4",
+        include_str!("../../../../stackslib/src/chainstate/stacks/boot/pox-4.clar"),
+    ),
+    (
+        "signers",
+        include_str!("../../../../stackslib/src/chainstate/stacks/boot/signers.clar"),
+    ),
+    (
+        "signers-voting",
+        include_str!("../../../../stackslib/src/chainstate/stacks/boot/signers-voting.clar"),
+    ),
+    (
+        "pox-mainnet",
+        include_str!("../../../../stackslib/src/chainstate/stacks/boot/pox-mainnet.clar"),
+    ),
+];
+
+#[test]
+fn test_format_layout() {
+    let source = "
+(define-map   balances principal
+   uint)
+;; the total supply
+(define-data-var supply uint u0) ;; in micro-units
+
+
+(define-public (transfer (amount uint) (sender principal) (recipient principal) (memo (optional (buff 34))))
+  (begin (asserts! (is-eq tx-sender sender) (err u1))
+    ;; debit the sender
+    (map-set balances sender (- (default-to u0 (map-get? balances sender)) amount))
+    (ok (tuple (amount amount) (memo memo)))))
+";
+    let expected = "(define-map balances principal uint)
+;; the total supply
+(define-data-var supply uint u0) ;; in micro-units
+
+(define-public (transfer
+                 (amount uint)
+                 (sender principal)
+                 (recipient principal)
+                 (memo (optional (buff 34))))
+  (begin
+    (asserts! (is-eq tx-sender sender) (err u1))
+    ;; debit the sender
+    (map-set balances
+      sender
+      (- (default-to u0 (map-get? balances sender)) amount))
+    (ok {amount: amount, memo: memo})))
+";
+    assert_eq!(format_source(source).unwrap(), expected);
+    assert!(is_formatted(expected).unwrap());
+    assert!(!is_formatted(source).unwrap());
+}
+
+#[test]
+fn test_format_comments_and_tuples() {
+    let source = "(define-read-only (get-info) ;; no arguments
+  {
+    ;; the height
+    height: block-height, ;; now
+    sender: tx-sender, owner: (tuple (name \"a long name for an owner, which does not fit\") (id u1))
+  })
+";
+    let expected = "(define-read-only (get-info) ;; no arguments
+  {
+    ;; the height
+    height: block-height, ;; now
+    sender: tx-sender,
+    owner: {name: \"a long name for an owner, which does not fit\", id: u1}
+  })
+";
+    assert_eq!(format_source(source).unwrap(), expected);
+    assert_eq!(format_source(expected).unwrap(), expected);
+}
+
+#[test]
+fn test_format_rejects_unparsable() {
+    assert!(matches!(
+        format_source("(define-public (f) (ok true)"),
+        Err(FormatError::Parse(_))
+    ));
+}
+
+/// Formatting the boot contracts keeps their meaning, and formatting again
+///  changes nothing
+#[test]
+fn test_format_boot_contracts_round_trip() {
+    for (name, source) in BOOT_CONTRACTS {
+        let formatted = format_source(source).unwrap_or_else(|e| panic!("{name}: {e}"));
+        assert_eq!(
+            format_source(&formatted).unwrap(),
+            formatted,
+            "{name} is not formatted idempotently"
+        );
+    }
+}
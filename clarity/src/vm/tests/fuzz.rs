@@ -0,0 +1,150 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use stacks_common::types::StacksEpochId;
+
+use crate::vm::analysis::contract_interface_builder::build_contract_interface;
+use crate::vm::ast::ASTRules;
+use crate::vm::contexts::OwnedEnvironment;
+use crate::vm::database::MemoryBackingStore;
+use crate::vm::tooling::fuzz::{
+    clarity_literal, fuzz, test_senders, FuzzConfig, FuzzSession, FuzzTarget, ValueGenerator,
+};
+use crate::vm::tooling::mem_type_check;
+use crate::vm::types::{PrincipalData, QualifiedContractIdentifier};
+use crate::vm::{execute_v2, ClarityVersion, Value};
+
+const CONTRACT: &str = "(define-data-var total uint u0)
+(define-public (add (amount uint))
+  (begin
+    (var-set total (+ (var-get total) amount))
+    (ok true)))
+(define-public (noop
+    (items (list 4 {a: int, b: (optional (string-utf8 5))}))
+    (who principal)
+    (result (response bool (buff 3)))
+    (name (string-ascii 10)))
+  (ok true))
+(define-read-only (invariant-below-limit)
+  (< (var-get total) u10))";
+
+const EPOCH: StacksEpochId = StacksEpochId::Epoch25;
+
+fn fuzz_target(contract_id: &QualifiedContractIdentifier) -> FuzzTarget {
+    let (_, analysis) = mem_type_check(CONTRACT, ClarityVersion::Clarity2, EPOCH).unwrap();
+    let interface = build_contract_interface(&analysis).unwrap();
+    FuzzTarget::from_interface(contract_id.clone(), &interface).unwrap()
+}
+
+#[test]
+fn test_fuzz_target() {
+    let contract_id = QualifiedContractIdentifier::local("fuzzed").unwrap();
+    let target = fuzz_target(&contract_id);
+    let names: Vec<_> = target.functions.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, ["add", "noop"]);
+    assert_eq!(target.invariants.len(), 1);
+    assert!(target.skipped.is_empty());
+
+    let (_, analysis) = mem_type_check(
+        "(define-public (f) (ok true))",
+        ClarityVersion::Clarity2,
+        EPOCH,
+    )
+    .unwrap();
+    let interface = build_contract_interface(&analysis).unwrap();
+    let err = FuzzTarget::from_interface(contract_id, &interface).unwrap_err();
+    assert!(err.contains("no invariants"), "{err}");
+}
+
+#[test]
+fn test_generated_values_are_well_typed() {
+    let contract_id = QualifiedContractIdentifier::local("fuzzed").unwrap();
+    let target = fuzz_target(&contract_id);
+    let mut principals = test_senders(false, 3);
+    principals.push(PrincipalData::Contract(contract_id));
+    let mut generator = ValueGenerator::new(ChaCha8Rng::seed_from_u64(7), principals, EPOCH);
+
+    for _ in 0..200 {
+        for function in target.functions.iter() {
+            for ty in function.args.iter() {
+                let value = generator.generate(ty).unwrap();
+                assert!(ty.admits(&EPOCH, &value).unwrap(), "{value} is not a {ty}");
+
+                // the reproducer's literals evaluate to the same values
+                let literal = clarity_literal(&value);
+                let evaluated = execute_v2(&literal).unwrap().unwrap();
+                assert_eq!(clarity_literal(&evaluated), literal);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_fuzz_shrinks_counterexample() {
+    let contract_id = QualifiedContractIdentifier::local("fuzzed").unwrap();
+    let target = fuzz_target(&contract_id);
+
+    let mut marf = MemoryBackingStore::new();
+    let mut db = marf.as_clarity_db();
+    db.begin();
+    db.set_clarity_epoch_version(EPOCH).unwrap();
+    db.commit().unwrap();
+    let mut owned_env = OwnedEnvironment::new(db, EPOCH);
+    owned_env
+        .initialize_versioned_contract(
+            contract_id.clone(),
+            ClarityVersion::Clarity2,
+            CONTRACT,
+            None,
+            ASTRules::PrecheckSize,
+        )
+        .unwrap();
+
+    let mut session = FuzzSession::new(marf.as_clarity_db(), false, 0x80000000, EPOCH);
+    let config = FuzzConfig::new(42, test_senders(false, 3));
+    let report = fuzz(&target, &config, EPOCH, |calls| {
+        session.run_calls(&target, calls)
+    })
+    .unwrap();
+
+    let counterexample = report.counterexample.expect("the invariant should fail");
+    assert_eq!(
+        counterexample.violation.invariant.as_str(),
+        "invariant-below-limit"
+    );
+    assert_eq!(counterexample.violation.outcome, "returned false");
+    assert!(counterexample.calls.len() <= counterexample.original_calls);
+    // every call is needed, and no amount can be any smaller
+    let amounts: Vec<_> = counterexample
+        .calls
+        .iter()
+        .map(|call| {
+            assert_eq!(call.function.as_str(), "add");
+            match call.args[..] {
+                [Value::UInt(amount)] => amount,
+                _ => panic!("unexpected arguments: {call}"),
+            }
+        })
+        .collect();
+    assert!(amounts.iter().all(|amount| *amount > 0));
+    assert_eq!(amounts.iter().sum::<u128>(), 10);
+
+    // fuzzing discards the writes of every sequence
+    let mut noop = counterexample.calls[0].clone();
+    noop.args = vec![Value::UInt(0)];
+    assert_eq!(session.run_calls(&target, &[noop]).unwrap(), None);
+}
@@ -28,15 +28,31 @@ use crate::vm::errors::Error;
 use crate::vm::types::Value;
 
 mod assets;
+#[cfg(test)]
+mod bindings;
 mod contracts;
+#[cfg(test)]
+mod coverage;
 mod datamaps;
 mod defines;
+#[cfg(test)]
+mod formatter;
+#[cfg(test)]
+mod fuzz;
 mod principals;
+#[cfg(test)]
+mod profiler;
 mod sequences;
 #[cfg(test)]
 mod simple_apply_eval;
+#[cfg(test)]
+mod tracer;
 mod traits;
+#[cfg(test)]
+mod upgrade;
 mod variables;
+#[cfg(test)]
+mod verify;
 
 #[cfg(any(test, feature = "testing"))]
 impl OwnedEnvironment<'_, '_> {
@@ -0,0 +1,172 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use stacks_common::types::StacksEpochId;
+
+use crate::boot_util::boot_code_id;
+use crate::vm::ast::ASTRules;
+use crate::vm::contexts::OwnedEnvironment;
+use crate::vm::costs::ExecutionCost;
+use crate::vm::database::MemoryBackingStore;
+use crate::vm::profiler::{CostDimension, CostProfiler};
+use crate::vm::types::{PrincipalData, QualifiedContractIdentifier};
+use crate::vm::{ClarityVersion, Value};
+
+const CONTRACT: &str = "(define-data-var counter uint u0)
+(define-map balances principal uint)
+(define-private (bump (by uint))
+  (let ((next (+ (var-get counter) by)))
+    (var-set counter next)
+    next))
+(define-public (main)
+  (begin
+    (map-set balances tx-sender u10)
+    (bump u1)
+    (ok (bump u2))))";
+
+const COSTS_CONTRACT: &str =
+    include_str!("../../../../stackslib/src/chainstate/stacks/boot/costs-3.clar");
+
+/// Deploy the boot contracts a cost tracker loads its cost functions from
+fn deploy_cost_contracts(marf: &mut MemoryBackingStore) {
+    let mut db = marf.as_clarity_db();
+    db.begin();
+    db.set_clarity_epoch_version(StacksEpochId::Epoch25)
+        .unwrap();
+    db.commit().unwrap();
+    let mut owned_env = OwnedEnvironment::new(db, StacksEpochId::Epoch25);
+    for (name, source) in [
+        (
+            "cost-voting",
+            "(define-data-var confirmed-proposal-count uint u0)",
+        ),
+        ("costs-3", COSTS_CONTRACT),
+    ] {
+        owned_env
+            .initialize_versioned_contract(
+                boot_code_id(name, false),
+                ClarityVersion::Clarity2,
+                source,
+                None,
+                ASTRules::PrecheckSize,
+            )
+            .unwrap();
+    }
+}
+
+/// Call `main` with the profiler attached, and return the total cost of the call
+fn profile_main(profiler: &mut CostProfiler) -> ExecutionCost {
+    let contract_id = QualifiedContractIdentifier::local("profiled").unwrap();
+    let mut marf = MemoryBackingStore::new();
+    deploy_cost_contracts(&mut marf);
+    let mut owned_env =
+        OwnedEnvironment::new_max_limit(marf.as_clarity_db(), StacksEpochId::Epoch25, false);
+    owned_env
+        .initialize_versioned_contract(
+            contract_id.clone(),
+            ClarityVersion::Clarity2,
+            CONTRACT,
+            None,
+            ASTRules::PrecheckSize,
+        )
+        .unwrap();
+    let before = owned_env.get_cost_total();
+    owned_env.add_eval_hook(profiler);
+    let sender = PrincipalData::parse("S1G2081040G2081040G2081040G208105NK8PE5").unwrap();
+    let (result, ..) = owned_env
+        .execute_transaction(sender, None, contract_id, "main", &[])
+        .unwrap();
+    assert_eq!(result, Value::okay(Value::UInt(3)).unwrap());
+    let mut cost = owned_env.get_cost_total();
+    cost.sub(&before).unwrap();
+    cost
+}
+
+#[test]
+fn test_function_costs() {
+    let mut profiler = CostProfiler::new();
+    let total = profile_main(&mut profiler);
+
+    let functions = profiler.functions();
+    assert_eq!(functions.len(), 2);
+    let main = functions.iter().find(|f| f.function == "main").unwrap();
+    let bump = functions.iter().find(|f| f.function == "bump").unwrap();
+    assert_eq!(main.calls, 1);
+    assert_eq!(bump.calls, 2);
+    assert_eq!(bump.total.write_count, 2);
+    assert_eq!(main.self_cost.write_count, 1);
+
+    // main's total covers everything it called ...
+    let mut expected = main.self_cost.clone();
+    expected.add(&bump.total).unwrap();
+    assert_eq!(main.total, expected);
+    // ... and everything the profiler attributed, which is no more than the
+    //  transaction's cost
+    assert_eq!(profiler.attributed(), &main.total);
+    assert!(!main.total.exceeds(&total));
+    assert!(main.total.runtime > 0);
+
+    let map_set = profiler
+        .expressions()
+        .into_iter()
+        .find(|expression| expression.label == "map-set")
+        .unwrap();
+    assert_eq!(map_set.evaluations, 1);
+    assert_eq!(map_set.total.write_count, 1);
+
+    let json = profiler.to_json(&total);
+    assert_eq!(json["functions"].as_array().unwrap().len(), 2);
+    assert_eq!(json["total"]["runtime"], total.runtime);
+}
+
+#[test]
+fn test_folded_stacks() {
+    let mut profiler = CostProfiler::new();
+    profile_main(&mut profiler);
+
+    let mut folded = vec![];
+    profiler
+        .write_folded(&mut folded, CostDimension::WriteCount)
+        .unwrap();
+    let folded = String::from_utf8(folded).unwrap();
+    let main = "S1G2081040G2081040G2081040G208105NK8PE5.profiled::main";
+    let bump = "S1G2081040G2081040G2081040G208105NK8PE5.profiled::bump";
+    assert_eq!(
+        folded,
+        format!(
+            "{main};bump;{bump};var-set 1
+{main};map-set 1
+{main};ok;bump;{bump};var-set 1
+"
+        )
+    );
+
+    let mut folded = vec![];
+    profiler
+        .write_folded(&mut folded, CostDimension::Runtime)
+        .unwrap();
+    let runtime: u64 = String::from_utf8(folded)
+        .unwrap()
+        .lines()
+        .map(|line| line.rsplit_once(' ').unwrap().1.parse::<u64>().unwrap())
+        .sum();
+    assert_eq!(runtime, profiler.attributed().runtime);
+
+    assert_eq!(
+        "read_length".parse::<CostDimension>(),
+        Ok(CostDimension::ReadLength)
+    );
+    assert!("memory".parse::<CostDimension>().is_err());
+}
@@ -0,0 +1,145 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use serde_json::json;
+use stacks_common::types::StacksEpochId;
+
+use crate::vm::ast::ASTRules;
+use crate::vm::contexts::OwnedEnvironment;
+use crate::vm::database::MemoryBackingStore;
+use crate::vm::tracer::{ExecutionTrace, ExecutionTracer, TraceStep};
+use crate::vm::types::{PrincipalData, QualifiedContractIdentifier};
+use crate::vm::{ClarityVersion, SymbolicExpression, Value};
+
+const TOKEN: &str = "(define-map balances principal uint)
+(define-read-only (balance-of (who principal))
+  (default-to u0 (map-get? balances who)))
+(define-public (mint (who principal) (amount uint))
+  (begin
+    (map-set balances who (+ (balance-of who) amount))
+    (ok amount)))";
+
+const VAULT: &str = "(define-data-var deposits uint u0)
+(define-public (deposit (amount uint))
+  (begin
+    (asserts! (> amount u0) (err u1))
+    (var-set deposits (+ (var-get deposits) amount))
+    (contract-call? .token mint tx-sender amount)))";
+
+const SENDER: &str = "S1G2081040G2081040G2081040G208105NK8PE5";
+
+/// Call `vault.deposit` with the tracer attached, and return its trace
+fn trace_deposit(amount: u128) -> (Value, ExecutionTrace) {
+    let mut tracer = ExecutionTracer::new();
+    let mut marf = MemoryBackingStore::new();
+    let result = {
+        let mut owned_env = OwnedEnvironment::new(marf.as_clarity_db(), StacksEpochId::Epoch25);
+        for (name, source) in [("token", TOKEN), ("vault", VAULT)] {
+            owned_env
+                .initialize_versioned_contract(
+                    QualifiedContractIdentifier::local(name).unwrap(),
+                    ClarityVersion::Clarity2,
+                    source,
+                    None,
+                    ASTRules::PrecheckSize,
+                )
+                .unwrap();
+        }
+        owned_env.add_eval_hook(&mut tracer);
+        let (result, ..) = owned_env
+            .execute_transaction(
+                PrincipalData::parse(SENDER).unwrap(),
+                None,
+                QualifiedContractIdentifier::local("vault").unwrap(),
+                "deposit",
+                &[SymbolicExpression::atom_value(Value::UInt(amount))],
+            )
+            .unwrap();
+        result
+    };
+    (result, tracer.take_trace())
+}
+
+#[test]
+fn test_nested_calls() {
+    let (result, trace) = trace_deposit(5);
+    assert_eq!(result, Value::okay(Value::UInt(5)).unwrap());
+
+    let calls: Vec<_> = trace
+        .calls()
+        .iter()
+        .map(|call| format!("{}.{}", call.contract, call.function))
+        .collect();
+    assert_eq!(
+        calls,
+        vec![
+            format!("{SENDER}.vault.deposit"),
+            format!("{SENDER}.token.mint"),
+            format!("{SENDER}.token.balance-of"),
+        ]
+    );
+
+    let json = serde_json::to_value(&trace).unwrap();
+    let deposit = &json["steps"][0];
+    assert_eq!(deposit["type"], "call");
+    assert_eq!(deposit["args"], json!([{"name": "amount", "value": "5"}]));
+    assert_eq!(deposit["result"], json!({"ok": "5"}));
+    assert_eq!(deposit["sender"], SENDER);
+
+    assert_eq!(deposit["steps"][0]["type"], "read");
+    assert_eq!(deposit["steps"][0]["op"], "var-get");
+    assert_eq!(deposit["steps"][0]["result"], "0");
+    assert_eq!(deposit["steps"][1]["type"], "write");
+    assert_eq!(deposit["steps"][1]["op"], "var-set");
+    assert_eq!(deposit["steps"][1]["value"], "5");
+
+    // the callee sees the vault as its caller
+    let mint = &deposit["steps"][2];
+    assert_eq!(mint["function"], "mint");
+    assert_eq!(mint["caller"], format!("{SENDER}.vault"));
+    assert_eq!(
+        mint["args"],
+        json!([{"name": "who", "value": SENDER}, {"name": "amount", "value": "5"}])
+    );
+    assert_eq!(mint["result"], json!({"ok": "5"}));
+
+    let balance_of = &mint["steps"][0];
+    assert_eq!(balance_of["function"], "balance-of");
+    assert_eq!(balance_of["steps"][0]["op"], "map-get?");
+    assert_eq!(balance_of["steps"][0]["name"], "balances");
+    assert_eq!(balance_of["steps"][0]["key"], SENDER);
+    assert_eq!(balance_of["steps"][0]["result"], json!(null));
+    assert_eq!(balance_of["result"], "0");
+
+    assert_eq!(mint["steps"][1]["op"], "map-set");
+    assert_eq!(mint["steps"][1]["key"], SENDER);
+    assert_eq!(mint["steps"][1]["value"], "5");
+    assert_eq!(mint["steps"][1]["result"], true);
+}
+
+#[test]
+fn test_early_return() {
+    let (result, trace) = trace_deposit(0);
+    assert_eq!(result, Value::error(Value::UInt(1)).unwrap());
+
+    // the call stopped at the failed assertion
+    assert_eq!(trace.steps.len(), 1);
+    let TraceStep::Call(deposit) = &trace.steps[0] else {
+        panic!("Expected a call, got {:?}", trace.steps[0]);
+    };
+    assert_eq!(deposit.result, Some(json!({"err": "1"})));
+    assert!(deposit.error.is_none());
+    assert!(deposit.steps.is_empty());
+}
@@ -0,0 +1,190 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use stacks_common::types::StacksEpochId;
+
+use crate::vm::analysis::contract_interface_builder::ContractInterfaceFunctionAccess;
+use crate::vm::analysis::ContractAnalysis;
+use crate::vm::tooling::mem_type_check;
+use crate::vm::tooling::upgrade::{check_upgrade, Change, MapType, Signature};
+use crate::vm::types::TraitIdentifier;
+use crate::vm::ClarityVersion;
+
+const TOKEN: &str = "(define-read-only (get-name) (ok \"Token\"))
+(define-read-only (get-symbol) (ok \"TKN\"))
+(define-read-only (get-decimals) (ok u6))
+(define-read-only (get-total-supply) (ok u0))
+(define-read-only (get-balance (who principal))
+  (ok (default-to u0 (map-get? balances who))))
+(define-public (transfer (amount uint) (sender principal) (recipient principal) (memo (optional (buff 34))))
+  (ok true))";
+
+fn analysis(source: &str) -> ContractAnalysis {
+    mem_type_check(source, ClarityVersion::Clarity2, StacksEpochId::Epoch25)
+        .unwrap()
+        .1
+}
+
+#[test]
+fn test_upgrade_unchanged() {
+    let source = format!("(define-map balances principal uint)\n{TOKEN}");
+    let report = check_upgrade(&analysis(&source), &analysis(&source));
+    assert!(!report.breaking);
+    assert!(report.changes.is_empty());
+}
+
+#[test]
+fn test_upgrade_changes() {
+    let mut old = analysis(&format!(
+        "(define-map balances principal uint)
+(define-map nonces principal uint)
+(define-read-only (get-token-uri) (ok none))
+(define-public (mint (amount uint)) (ok true))
+(define-public (set-memo (memo (buff 10))) (ok true))
+(define-read-only (get-owner) (ok tx-sender))
+{TOKEN}"
+    ));
+    old.implemented_traits.insert(
+        TraitIdentifier::parse_fully_qualified(
+            "SP3FBR2AGK5H9QBDH3EEN6DF8EK8JY7RX8QJ5SVTE.sip-010-trait-ft-standard.sip-010-trait",
+        )
+        .unwrap(),
+    );
+    let new = analysis(&format!(
+        "(define-map balances principal uint)
+(define-map nonces principal int)
+(define-map allowances principal uint)
+(define-public (mint (amount int)) (ok true))
+(define-public (set-memo (memo (buff 20))) (ok true))
+(define-public (get-owner) (ok tx-sender))
+(define-public (burn (amount uint)) (ok true))
+{TOKEN}"
+    ));
+
+    let report = check_upgrade(&old, &new);
+    let changes: Vec<_> = report
+        .changes
+        .iter()
+        .map(|reported| (reported.breaking, reported.change.clone()))
+        .collect();
+    let public_access = ContractInterfaceFunctionAccess::public;
+    assert_eq!(
+        changes,
+        [
+            (
+                true,
+                Change::FunctionAccessChanged {
+                    name: "get-owner".into(),
+                    old: ContractInterfaceFunctionAccess::read_only,
+                    new: public_access.clone(),
+                }
+            ),
+            (
+                true,
+                Change::FunctionRemoved {
+                    name: "get-token-uri".into(),
+                    access: ContractInterfaceFunctionAccess::read_only,
+                }
+            ),
+            (
+                true,
+                Change::FunctionSignatureChanged {
+                    name: "mint".into(),
+                    access: public_access.clone(),
+                    old: Signature {
+                        args: vec!["uint".into()],
+                        returns: "(response bool UnknownType)".into(),
+                    },
+                    new: Signature {
+                        args: vec!["int".into()],
+                        returns: "(response bool UnknownType)".into(),
+                    },
+                    compatible: false,
+                }
+            ),
+            (
+                false,
+                Change::FunctionSignatureChanged {
+                    name: "set-memo".into(),
+                    access: public_access.clone(),
+                    old: Signature {
+                        args: vec!["(buff 10)".into()],
+                        returns: "(response bool UnknownType)".into(),
+                    },
+                    new: Signature {
+                        args: vec!["(buff 20)".into()],
+                        returns: "(response bool UnknownType)".into(),
+                    },
+                    compatible: true,
+                }
+            ),
+            (
+                false,
+                Change::FunctionAdded {
+                    name: "burn".into(),
+                    access: public_access,
+                }
+            ),
+            (
+                true,
+                Change::MapTypeChanged {
+                    name: "nonces".into(),
+                    old: MapType {
+                        key: "principal".into(),
+                        value: "uint".into(),
+                    },
+                    new: MapType {
+                        key: "principal".into(),
+                        value: "int".into(),
+                    },
+                }
+            ),
+            (
+                false,
+                Change::MapAdded {
+                    name: "allowances".into(),
+                }
+            ),
+            (
+                true,
+                Change::TraitImplementationDropped {
+                    trait_identifier:
+                        "SP3FBR2AGK5H9QBDH3EEN6DF8EK8JY7RX8QJ5SVTE.sip-010-trait-ft-standard.sip-010-trait"
+                            .into(),
+                }
+            ),
+            (
+                true,
+                Change::ConformanceChanged {
+                    standard: "SIP-010".into(),
+                    old: true,
+                    new: false,
+                }
+            ),
+        ]
+    );
+    assert!(report.breaking);
+
+    let json = serde_json::to_value(&report.changes[4]).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "breaking": false,
+            "kind": "function_added",
+            "name": "burn",
+            "access": "public",
+        })
+    );
+}
@@ -0,0 +1,224 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use stacks_common::types::StacksEpochId;
+
+use crate::vm::tooling::fuzz::test_senders;
+use crate::vm::tooling::mem_type_check;
+use crate::vm::tooling::verify::solver::{Outcome, Solver};
+use crate::vm::tooling::verify::term::{ArithOp, CompareOp, Concrete, Sort, Term};
+use crate::vm::tooling::verify::{verify, Property, Verdict, VerificationReport};
+use crate::vm::types::PrincipalData;
+use crate::vm::{ClarityVersion, Value};
+
+const CONTRACT: &str = "(define-constant OWNER 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM)
+(define-map balances principal uint)
+(define-fungible-token tok u1000)
+
+(define-public (withdraw (amount uint))
+  (let ((balance (default-to u0 (map-get? balances tx-sender))))
+    (asserts! (>= balance amount) (err u1))
+    (ok (map-set balances tx-sender (- balance amount)))))
+
+(define-public (unsafe-withdraw (amount uint))
+  (let ((balance (default-to u0 (map-get? balances tx-sender))))
+    (ok (map-set balances tx-sender (- balance amount)))))
+
+(define-public (mint (amount uint))
+  (begin
+    (asserts! (is-eq tx-sender OWNER) (err u2))
+    (ft-mint? tok amount tx-sender)))
+
+(define-public (capped-mint (amount uint))
+  (begin
+    (asserts! (<= amount (- u1000 (ft-get-supply tok))) (err u3))
+    (ft-mint? tok amount tx-sender)))
+
+(define-read-only (ratio (a uint) (b uint))
+  (if (> b u0) (safe-div a b) u0))
+
+(define-private (safe-div (a uint) (b uint))
+  (/ a b))
+
+(define-private (unused (a int))
+  (- a 1))";
+
+fn report(source: &str) -> VerificationReport {
+    let (_, analysis) =
+        mem_type_check(source, ClarityVersion::Clarity2, StacksEpochId::Epoch25).unwrap();
+    verify(&analysis, &test_senders(false, 2))
+}
+
+#[test]
+fn test_verify_contract() {
+    let report = report(CONTRACT);
+    let summary: Vec<_> = report
+        .properties
+        .iter()
+        .map(|property| {
+            let status = match &property.verdict {
+                Verdict::Proved => "proved",
+                Verdict::Refuted { .. } => "refuted",
+                Verdict::Unknown { .. } => "unknown",
+            };
+            (property.function.as_str(), property.property, status)
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("withdraw", Property::Assertion, "refuted"),
+            ("withdraw", Property::Underflow, "proved"),
+            ("unsafe-withdraw", Property::Underflow, "refuted"),
+            ("mint", Property::Assertion, "refuted"),
+            ("mint", Property::MintCap, "refuted"),
+            ("capped-mint", Property::Assertion, "refuted"),
+            ("capped-mint", Property::Underflow, "proved"),
+            ("capped-mint", Property::MintCap, "proved"),
+            ("safe-div", Property::DivisionByZero, "proved"),
+            ("unused", Property::Overflow, "unknown"),
+        ]
+    );
+    assert!(report.refuted());
+
+    let Verdict::Refuted { counterexample } = &report.properties[2].verdict else {
+        panic!("expected a counterexample");
+    };
+    assert_eq!(counterexample.function, "unsafe-withdraw");
+    let Value::UInt(amount) = counterexample.args[0] else {
+        panic!("expected a uint argument");
+    };
+    assert!(amount > 0);
+
+    // minting beyond the cap needs the owner as sender
+    let Verdict::Refuted { counterexample } = &report.properties[4].verdict else {
+        panic!("expected a counterexample");
+    };
+    assert_eq!(
+        counterexample.sender,
+        PrincipalData::parse("ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM").unwrap()
+    );
+    let supply = counterexample
+        .assumptions
+        .iter()
+        .find(|assumption| assumption.expression == "(ft-get-supply tok)")
+        .map(|assumption| assumption.value.clone().expect_u128().unwrap())
+        .unwrap_or(0);
+    let Value::UInt(amount) = counterexample.args[0] else {
+        panic!("expected a uint argument");
+    };
+    assert!(supply <= 1000 && supply + amount > 1000);
+
+    let json = serde_json::to_value(&report.properties[2]).unwrap();
+    assert_eq!(json["status"], "refuted");
+    assert_eq!(json["property"], "underflow");
+    assert_eq!(
+        json["expression"],
+        "(- balance amount)",
+        "{}",
+        serde_json::to_string_pretty(&json).unwrap()
+    );
+    assert_eq!(json["counterexample"]["function"], "unsafe-withdraw");
+}
+
+#[test]
+fn test_verify_paths() {
+    let report = report(
+        "(define-data-var counter uint u0)
+(define-public (count (step uint))
+  (begin
+    (asserts! (and (> step u0) (< step u10)) (err u1))
+    (var-set counter (+ (var-get counter) step))
+    (asserts! (> (var-get counter) u0) (err u2))
+    (match (some step)
+      value (ok (- value u1))
+      (err u3))))",
+    );
+    let verdicts: Vec<_> = report
+        .properties
+        .iter()
+        .map(|property| (property.property, property.verdict.clone()))
+        .collect();
+    assert!(matches!(
+        verdicts[0],
+        (Property::Assertion, Verdict::Refuted { .. })
+    ));
+    // the sum is refuted by a counter near the top of the range
+    match &verdicts[1] {
+        (Property::Overflow, Verdict::Refuted { counterexample }) => {
+            assert_eq!(
+                counterexample.assumptions[0].expression,
+                "(var-get counter)"
+            );
+        }
+        other => panic!("unexpected {other:?}"),
+    }
+    assert_eq!(verdicts[2], (Property::Assertion, Verdict::Proved));
+    assert_eq!(verdicts[3], (Property::Underflow, Verdict::Proved));
+}
+
+#[test]
+fn test_solver() {
+    let principals = test_senders(false, 1);
+    let sorts = [Sort::UInt, Sort::UInt, Sort::Int];
+    let solver = Solver {
+        sorts: &sorts,
+        principals: &principals,
+    };
+    let x = Term::Symbol(0, Sort::UInt);
+    let y = Term::Symbol(1, Sort::UInt);
+    let z = Term::Symbol(2, Sort::Int);
+    let uint = |n| Term::Const(Concrete::UInt(n));
+
+    // x + y <= 10, x >= 6, y >= 5
+    let sum = Term::arith(ArithOp::Add, x.clone(), y.clone());
+    let constraints = [
+        Term::compare(CompareOp::Le, sum.clone(), uint(10)),
+        Term::compare(CompareOp::Ge, x.clone(), uint(6)),
+        Term::compare(CompareOp::Ge, y.clone(), uint(5)),
+    ];
+    assert_eq!(solver.check(&constraints), Outcome::Unsat);
+    assert!(solver.is_unsat(&constraints));
+
+    // x - y is only defined when x >= y
+    let constraints = [
+        Term::compare(
+            CompareOp::Gt,
+            Term::arith(ArithOp::Sub, x.clone(), y.clone()),
+            uint(0),
+        ),
+        Term::compare(CompareOp::Le, x.clone(), y.clone()),
+    ];
+    assert_eq!(solver.check(&constraints), Outcome::Unsat);
+
+    // 2z = 7 has no integer solution
+    let double = Term::arith(ArithOp::Mul, Term::Const(Concrete::Int(2)), z.clone());
+    let constraints = [Term::eq(double.clone(), Term::Const(Concrete::Int(7)))];
+    assert_eq!(solver.check(&constraints), Outcome::Unsat);
+
+    let constraints = [
+        Term::negate(Term::eq(x.clone(), uint(3))),
+        Term::compare(CompareOp::Le, sum, uint(4)),
+        Term::compare(CompareOp::Ge, x.clone(), uint(3)),
+    ];
+    let Outcome::Sat(model) = solver.check(&constraints) else {
+        panic!("expected a model");
+    };
+    for constraint in constraints.iter() {
+        assert_eq!(constraint.eval(&model), Some(Concrete::Bool(true)));
+    }
+    assert_eq!(model[0], Concrete::UInt(4));
+    assert_eq!(model[1], Concrete::UInt(0));
+}
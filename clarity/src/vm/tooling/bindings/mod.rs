@@ -0,0 +1,365 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Typed bindings for calling a contract, generated from its interface.
+//!
+//! `Bindings::from_interface` turns a `ContractInterface` into a language
+//! neutral model: every tuple type becomes a named struct, and every response
+//! type a named two-variant enum. The `rust` and `typescript` emitters render
+//! that model as source code. Only public and read-only functions are bound,
+//! since private functions cannot be called from outside the contract.
+
+pub mod runtime;
+pub mod rust;
+pub mod typescript;
+
+use crate::vm::analysis::contract_interface_builder::{
+    ContractInterface, ContractInterfaceAtomType, ContractInterfaceFunctionAccess,
+};
+
+/// The type of an argument, a result, or a field
+#[derive(Debug, Clone, PartialEq)]
+pub enum BindingType {
+    Int,
+    UInt,
+    Bool,
+    Principal,
+    Buffer,
+    StringAscii,
+    StringUtf8,
+    List(Box<BindingType>),
+    Optional(Box<BindingType>),
+    /// An index into `Bindings::types`
+    Named(usize),
+    /// A trait reference argument, which is passed as a contract principal
+    TraitReference,
+    /// The type of a value which cannot occur, such as the `err` type of a
+    ///  function that always returns `(ok ...)`
+    NoType,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    /// The name of the field in Clarity
+    pub name: String,
+    pub binding_type: BindingType,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NamedTypeKind {
+    /// A tuple, with its fields sorted by name
+    Struct(Vec<Field>),
+    Response {
+        ok: BindingType,
+        err: BindingType,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamedType {
+    /// An `UpperCamelCase` name, unique within the bindings
+    pub name: String,
+    pub kind: NamedTypeKind,
+    /// Values of this type are passed as arguments
+    pub encode: bool,
+    /// Values of this type are returned from functions
+    pub decode: bool,
+    interface_type: ContractInterfaceAtomType,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BindingFunction {
+    /// The name of the function in Clarity
+    pub name: String,
+    pub read_only: bool,
+    pub args: Vec<Field>,
+    pub output: BindingType,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bindings {
+    /// The name of the contract, used to name the generated contract type
+    pub contract_name: String,
+    pub types: Vec<NamedType>,
+    pub functions: Vec<BindingFunction>,
+}
+
+#[derive(Clone, Copy)]
+enum Usage {
+    Encode,
+    Decode,
+}
+
+/// Split a Clarity name into lowercase words
+fn words(name: &str) -> Vec<String> {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_ascii_lowercase())
+        .collect()
+}
+
+/// `UpperCamelCase` for a Clarity name
+pub fn upper_camel_case(name: &str) -> String {
+    let name: String = words(name)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect();
+    match name.chars().next() {
+        Some(first) if first.is_ascii_alphabetic() => name,
+        _ => format!("T{name}"),
+    }
+}
+
+/// `lowerCamelCase` for a Clarity name
+pub fn lower_camel_case(name: &str) -> String {
+    let upper = upper_camel_case(name);
+    let mut chars = upper.chars();
+    chars
+        .next()
+        .map(|first| first.to_ascii_lowercase().to_string() + chars.as_str())
+        .unwrap_or_default()
+}
+
+/// `snake_case` for a Clarity name
+pub fn snake_case(name: &str) -> String {
+    let name = words(name).join("_");
+    match name.chars().next() {
+        Some(first) if first.is_ascii_alphabetic() => name,
+        _ => format!("_{name}"),
+    }
+}
+
+impl Bindings {
+    pub fn from_interface(contract_name: &str, interface: &ContractInterface) -> Bindings {
+        let mut bindings = Bindings {
+            contract_name: contract_name.to_string(),
+            types: vec![],
+            functions: vec![],
+        };
+        for function in interface.functions.iter() {
+            let read_only = match function.access {
+                ContractInterfaceFunctionAccess::public => false,
+                ContractInterfaceFunctionAccess::read_only => true,
+                ContractInterfaceFunctionAccess::private => continue,
+            };
+            let context = upper_camel_case(&function.name);
+            let args = function
+                .args
+                .iter()
+                .map(|arg| Field {
+                    name: arg.name.clone(),
+                    binding_type: bindings.binding_type(
+                        &arg.type_f,
+                        &format!("{context}{}", upper_camel_case(&arg.name)),
+                        Usage::Encode,
+                    ),
+                })
+                .collect();
+            let output_context = match function.outputs.type_f {
+                ContractInterfaceAtomType::tuple(_) => format!("{context}Output"),
+                _ => context,
+            };
+            let output =
+                bindings.binding_type(&function.outputs.type_f, &output_context, Usage::Decode);
+            bindings.functions.push(BindingFunction {
+                name: function.name.clone(),
+                read_only,
+                args,
+                output,
+            });
+        }
+        bindings
+    }
+
+    /// The name of the generated contract type
+    pub fn contract_type_name(&self) -> String {
+        upper_camel_case(&self.contract_name)
+    }
+
+    /// A name for a new type, made unique with a numeric suffix
+    fn unique_name(&self, name: &str) -> String {
+        let contract_type_name = self.contract_type_name();
+        let taken = |name: &str| {
+            name == contract_type_name || self.types.iter().any(|named| named.name == name)
+        };
+        if !taken(name) {
+            return name.to_string();
+        }
+        (2..)
+            .map(|suffix| format!("{name}{suffix}"))
+            .find(|name| !taken(name))
+            .unwrap_or_default()
+    }
+
+    /// Find or create the named type for a tuple or response type
+    fn named_type(
+        &mut self,
+        interface_type: &ContractInterfaceAtomType,
+        name: &str,
+        usage: Usage,
+    ) -> usize {
+        let index = match self
+            .types
+            .iter()
+            .position(|named| &named.interface_type == interface_type)
+        {
+            Some(index) => index,
+            None => {
+                let kind = match interface_type {
+                    ContractInterfaceAtomType::tuple(entries) => NamedTypeKind::Struct(
+                        entries
+                            .iter()
+                            .map(|entry| Field {
+                                name: entry.name.clone(),
+                                binding_type: self.binding_type(
+                                    &entry.type_f,
+                                    &format!("{name}{}", upper_camel_case(&entry.name)),
+                                    usage,
+                                ),
+                            })
+                            .collect(),
+                    ),
+                    ContractInterfaceAtomType::response { ok, error } => NamedTypeKind::Response {
+                        ok: self.binding_type(ok, &format!("{name}Ok"), usage),
+                        err: self.binding_type(error, &format!("{name}Err"), usage),
+                    },
+                    _ => unreachable!("only tuples and responses are named"),
+                };
+                let name = match interface_type {
+                    ContractInterfaceAtomType::response { .. } => {
+                        self.unique_name(&format!("{name}Response"))
+                    }
+                    _ => self.unique_name(name),
+                };
+                self.types.push(NamedType {
+                    name,
+                    kind,
+                    encode: false,
+                    decode: false,
+                    interface_type: interface_type.clone(),
+                });
+                self.types.len() - 1
+            }
+        };
+        self.mark_used(index, usage);
+        index
+    }
+
+    /// Record that a named type, and every named type inside it, is used
+    fn mark_used(&mut self, index: usize, usage: Usage) {
+        let named = &mut self.types[index];
+        let already = match usage {
+            Usage::Encode => std::mem::replace(&mut named.encode, true),
+            Usage::Decode => std::mem::replace(&mut named.decode, true),
+        };
+        if already {
+            return;
+        }
+        let inner: Vec<usize> = match &named.kind {
+            NamedTypeKind::Struct(fields) => fields
+                .iter()
+                .filter_map(|field| field.binding_type.named())
+                .collect(),
+            NamedTypeKind::Response { ok, err } => {
+                ok.named().into_iter().chain(err.named()).collect()
+            }
+        };
+        for index in inner {
+            self.mark_used(index, usage);
+        }
+    }
+
+    fn binding_type(
+        &mut self,
+        interface_type: &ContractInterfaceAtomType,
+        name: &str,
+        usage: Usage,
+    ) -> BindingType {
+        use crate::vm::analysis::contract_interface_builder::ContractInterfaceAtomType::*;
+        match interface_type {
+            none => BindingType::NoType,
+            int128 => BindingType::Int,
+            uint128 => BindingType::UInt,
+            bool => BindingType::Bool,
+            principal => BindingType::Principal,
+            buffer { .. } => BindingType::Buffer,
+            string_ascii { .. } => BindingType::StringAscii,
+            string_utf8 { .. } => BindingType::StringUtf8,
+            list { type_f, .. } => {
+                BindingType::List(Box::new(self.binding_type(type_f, name, usage)))
+            }
+            optional(inner) => {
+                BindingType::Optional(Box::new(self.binding_type(inner, name, usage)))
+            }
+            tuple(_) | response { .. } => {
+                BindingType::Named(self.named_type(interface_type, name, usage))
+            }
+            trait_reference => BindingType::TraitReference,
+        }
+    }
+}
+
+impl NamedType {
+    /// The Clarity type this type stands for, such as `(tuple (a int))`
+    pub fn clarity_type(&self) -> String {
+        clarity_type(&self.interface_type)
+    }
+}
+
+/// Clarity type syntax for an interface type. A type which cannot occur is
+///  shown as `none`.
+fn clarity_type(interface_type: &ContractInterfaceAtomType) -> String {
+    use crate::vm::analysis::contract_interface_builder::ContractInterfaceAtomType::*;
+    match interface_type {
+        none => "none".into(),
+        int128 => "int".into(),
+        uint128 => "uint".into(),
+        bool => "bool".into(),
+        principal => "principal".into(),
+        buffer { length } => format!("(buff {length})"),
+        string_ascii { length } => format!("(string-ascii {length})"),
+        string_utf8 { length } => format!("(string-utf8 {length})"),
+        list { type_f, length } => format!("(list {length} {})", clarity_type(type_f)),
+        optional(inner) => format!("(optional {})", clarity_type(inner)),
+        tuple(entries) => {
+            let entries: Vec<String> = entries
+                .iter()
+                .map(|entry| format!("({} {})", entry.name, clarity_type(&entry.type_f)))
+                .collect();
+            format!("(tuple {})", entries.join(" "))
+        }
+        response { ok, error } => {
+            format!("(response {} {})", clarity_type(ok), clarity_type(error))
+        }
+        trait_reference => "<trait>".into(),
+    }
+}
+
+impl BindingType {
+    /// The outermost named type inside this type
+    fn named(&self) -> Option<usize> {
+        match self {
+            BindingType::Named(index) => Some(*index),
+            BindingType::List(inner) | BindingType::Optional(inner) => inner.named(),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,108 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Helpers called by generated Rust bindings, to keep the generated code
+//! short. These are not meant to be used directly.
+
+use crate::vm::errors::{CheckErrors, Error, InterpreterError};
+use crate::vm::types::{BuffData, CharType, SequenceData, TupleData, UTF8Data};
+use crate::vm::{ClarityName, Value};
+
+/// A Clarity name which is known to be valid
+pub fn name(name: &str) -> Result<ClarityName, Error> {
+    Ok(ClarityName::try_from(name.to_string())?)
+}
+
+pub fn encode_list<T>(
+    items: &[T],
+    encode: impl Fn(&T) -> Result<Value, Error>,
+) -> Result<Value, Error> {
+    let items = items.iter().map(encode).collect::<Result<_, _>>()?;
+    Value::cons_list_unsanitized(items)
+}
+
+pub fn encode_optional<T>(
+    item: Option<&T>,
+    encode: impl Fn(&T) -> Result<Value, Error>,
+) -> Result<Value, Error> {
+    match item {
+        Some(item) => Value::some(encode(item)?),
+        None => Ok(Value::none()),
+    }
+}
+
+pub fn encode_tuple(fields: Vec<(&str, Value)>) -> Result<Value, Error> {
+    let fields = fields
+        .into_iter()
+        .map(|(field, value)| Ok((name(field)?, value)))
+        .collect::<Result<_, Error>>()?;
+    Ok(Value::Tuple(TupleData::from_data(fields)?))
+}
+
+/// There are no values of a type which cannot occur
+pub fn encode_no_type() -> Result<Value, Error> {
+    Err(InterpreterError::Expect("Cannot encode a value of no type".into()).into())
+}
+
+pub fn decode_list<T>(
+    value: Value,
+    decode: impl Fn(Value) -> Result<T, Error>,
+) -> Result<Vec<T>, Error> {
+    value.expect_list()?.into_iter().map(decode).collect()
+}
+
+pub fn decode_optional<T>(
+    value: Value,
+    decode: impl Fn(Value) -> Result<T, Error>,
+) -> Result<Option<T>, Error> {
+    value.expect_optional()?.map(decode).transpose()
+}
+
+pub fn decode_buffer(value: Value) -> Result<Vec<u8>, Error> {
+    match value {
+        Value::Sequence(SequenceData::Buffer(BuffData { data })) => Ok(data),
+        _ => Err(InterpreterError::Expect("Expected buff".into()).into()),
+    }
+}
+
+pub fn decode_utf8(value: Value) -> Result<String, Error> {
+    match value {
+        Value::Sequence(SequenceData::String(CharType::UTF8(UTF8Data { data }))) => {
+            String::from_utf8(data.concat())
+                .map_err(|_| CheckErrors::InvalidCharactersDetected.into())
+        }
+        _ => Err(InterpreterError::Expect("Expected UTF-8 string".into()).into()),
+    }
+}
+
+/// A value of a type which cannot occur is never used
+pub fn decode_no_type(_value: Value) -> Result<(), Error> {
+    Ok(())
+}
+
+/// The fields of a tuple, taken out one by one
+pub struct Fields(TupleData);
+
+impl Fields {
+    pub fn new(value: Value) -> Result<Fields, Error> {
+        Ok(Fields(value.expect_tuple()?))
+    }
+
+    pub fn take(&mut self, field: &str) -> Result<Value, Error> {
+        self.0.data_map.remove(field).ok_or_else(|| {
+            CheckErrors::NoSuchTupleField(field.to_string(), self.0.type_signature.clone()).into()
+        })
+    }
+}
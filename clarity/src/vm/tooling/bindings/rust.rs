@@ -0,0 +1,381 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Renders bindings as a Rust module.
+//!
+//! Public and read-only functions both become methods that build a
+//! `TransactionContractCall`: its `function_args` are also what a read-only
+//! call over RPC takes. Each function gets a `decode_` method for its result.
+
+use std::fmt::Write;
+
+use super::{snake_case, BindingType, Bindings, NamedTypeKind};
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "box", "break", "const", "continue", "crate", "dyn", "else", "enum",
+    "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+    "mut", "pub", "ref", "return", "self", "static", "struct", "super", "trait", "true", "try",
+    "type", "unsafe", "use", "where", "while", "yield",
+];
+
+/// The imports of the generated module. `PrincipalData` is only imported if
+///  it is used.
+const IMPORTS: &[&str] = &[
+    "blockstack_lib::chainstate::stacks::TransactionContractCall",
+    "clarity::types::chainstate::StacksAddress",
+    "clarity::vm::errors::Error",
+    "clarity::vm::tooling::bindings::runtime",
+    "clarity::vm::types::PrincipalData",
+    "clarity::vm::{ContractName, Value}",
+];
+
+/// A `snake_case` identifier for a Clarity name
+fn ident(name: &str) -> String {
+    let ident = snake_case(name);
+    if RUST_KEYWORDS.contains(&ident.as_str()) {
+        format!("{ident}_")
+    } else {
+        ident
+    }
+}
+
+fn rust_type(bindings: &Bindings, binding_type: &BindingType) -> String {
+    match binding_type {
+        BindingType::Int => "i128".into(),
+        BindingType::UInt => "u128".into(),
+        BindingType::Bool => "bool".into(),
+        BindingType::Principal | BindingType::TraitReference => "PrincipalData".into(),
+        BindingType::Buffer => "Vec<u8>".into(),
+        BindingType::StringAscii | BindingType::StringUtf8 => "String".into(),
+        BindingType::List(inner) => format!("Vec<{}>", rust_type(bindings, inner)),
+        BindingType::Optional(inner) => format!("Option<{}>", rust_type(bindings, inner)),
+        BindingType::Named(index) => bindings.types[*index].name.clone(),
+        BindingType::NoType => "()".into(),
+    }
+}
+
+/// An expression encoding `place` as a `Value`, and whether it returns a
+///  `Result`. `place` is a reference if `by_ref` is set.
+fn encode(binding_type: &BindingType, place: &str, by_ref: bool) -> (String, bool) {
+    let copied = if by_ref {
+        format!("*{place}")
+    } else {
+        place.to_string()
+    };
+    let borrowed = if by_ref {
+        place.to_string()
+    } else {
+        format!("&{place}")
+    };
+    match binding_type {
+        BindingType::Int => (format!("Value::Int({copied})"), false),
+        BindingType::UInt => (format!("Value::UInt({copied})"), false),
+        BindingType::Bool => (format!("Value::Bool({copied})"), false),
+        BindingType::Principal | BindingType::TraitReference => {
+            (format!("Value::Principal({place}.clone())"), false)
+        }
+        BindingType::Buffer => (format!("Value::buff_from({place}.clone())"), true),
+        BindingType::StringAscii => (
+            format!("Value::string_ascii_from_bytes({place}.clone().into_bytes())"),
+            true,
+        ),
+        BindingType::StringUtf8 => (
+            format!("Value::string_utf8_from_bytes({place}.clone().into_bytes())"),
+            true,
+        ),
+        BindingType::List(inner) => (
+            format!(
+                "runtime::encode_list({borrowed}, |item| {})",
+                closure_body(encode(inner, "item", true))
+            ),
+            true,
+        ),
+        BindingType::Optional(inner) => (
+            format!(
+                "runtime::encode_optional({place}.as_ref(), |item| {})",
+                closure_body(encode(inner, "item", true))
+            ),
+            true,
+        ),
+        BindingType::Named(_) => (format!("{place}.to_value()"), true),
+        BindingType::NoType => ("runtime::encode_no_type()".into(), true),
+    }
+}
+
+/// An expression decoding the `Value` expression `value`, which returns a
+///  `Result`
+fn decode(bindings: &Bindings, binding_type: &BindingType, value: &str) -> String {
+    match binding_type {
+        BindingType::Int => format!("{value}.expect_i128()"),
+        BindingType::UInt => format!("{value}.expect_u128()"),
+        BindingType::Bool => format!("{value}.expect_bool()"),
+        BindingType::Principal | BindingType::TraitReference => {
+            format!("{value}.expect_principal()")
+        }
+        BindingType::Buffer => format!("runtime::decode_buffer({value})"),
+        BindingType::StringAscii => format!("{value}.expect_ascii()"),
+        BindingType::StringUtf8 => format!("runtime::decode_utf8({value})"),
+        BindingType::List(inner) => format!(
+            "runtime::decode_list({value}, |item| {})",
+            decode(bindings, inner, "item")
+        ),
+        BindingType::Optional(inner) => format!(
+            "runtime::decode_optional({value}, |item| {})",
+            decode(bindings, inner, "item")
+        ),
+        BindingType::Named(index) => {
+            format!("{}::from_value({value})", bindings.types[*index].name)
+        }
+        BindingType::NoType => format!("runtime::decode_no_type({value})"),
+    }
+}
+
+fn closure_body((expr, fallible): (String, bool)) -> String {
+    if fallible {
+        expr
+    } else {
+        format!("Ok({expr})")
+    }
+}
+
+fn try_expr((expr, fallible): (String, bool)) -> String {
+    if fallible {
+        format!("{expr}?")
+    } else {
+        expr
+    }
+}
+
+fn emit_types(bindings: &Bindings, out: &mut String) -> std::fmt::Result {
+    for named in bindings.types.iter() {
+        let name = &named.name;
+        writeln!(out)?;
+        writeln!(out, "/// `{}`", named.clarity_type())?;
+        writeln!(out, "#[derive(Debug, Clone, PartialEq)]")?;
+        match &named.kind {
+            NamedTypeKind::Struct(fields) => {
+                writeln!(out, "pub struct {name} {{")?;
+                for field in fields.iter() {
+                    writeln!(
+                        out,
+                        "    pub {}: {},",
+                        ident(&field.name),
+                        rust_type(bindings, &field.binding_type)
+                    )?;
+                }
+                writeln!(out, "}}")?;
+                writeln!(out)?;
+                writeln!(out, "impl {name} {{")?;
+                if named.encode {
+                    writeln!(out, "    pub fn to_value(&self) -> Result<Value, Error> {{")?;
+                    writeln!(out, "        runtime::encode_tuple(vec![")?;
+                    for field in fields.iter() {
+                        let place = format!("self.{}", ident(&field.name));
+                        let value = try_expr(encode(&field.binding_type, &place, false));
+                        writeln!(out, "            ({:?}, {value}),", field.name)?;
+                    }
+                    writeln!(out, "        ])")?;
+                    writeln!(out, "    }}")?;
+                }
+                if named.decode {
+                    if named.encode {
+                        writeln!(out)?;
+                    }
+                    writeln!(
+                        out,
+                        "    pub fn from_value(value: Value) -> Result<Self, Error> {{"
+                    )?;
+                    writeln!(
+                        out,
+                        "        let mut fields = runtime::Fields::new(value)?;"
+                    )?;
+                    writeln!(out, "        Ok(Self {{")?;
+                    for field in fields.iter() {
+                        let value = format!("fields.take({:?})?", field.name);
+                        writeln!(
+                            out,
+                            "            {}: {}?,",
+                            ident(&field.name),
+                            decode(bindings, &field.binding_type, &value)
+                        )?;
+                    }
+                    writeln!(out, "        }})")?;
+                    writeln!(out, "    }}")?;
+                }
+                writeln!(out, "}}")?;
+            }
+            NamedTypeKind::Response { ok, err } => {
+                writeln!(out, "pub enum {name} {{")?;
+                writeln!(out, "    Ok({}),", rust_type(bindings, ok))?;
+                writeln!(out, "    Err({}),", rust_type(bindings, err))?;
+                writeln!(out, "}}")?;
+                writeln!(out)?;
+                writeln!(out, "impl {name} {{")?;
+                if named.encode {
+                    writeln!(out, "    pub fn to_value(&self) -> Result<Value, Error> {{")?;
+                    writeln!(out, "        match self {{")?;
+                    writeln!(
+                        out,
+                        "            Self::Ok(ok) => Value::okay({}),",
+                        try_expr(encode(ok, "ok", true))
+                    )?;
+                    writeln!(
+                        out,
+                        "            Self::Err(err) => Value::error({}),",
+                        try_expr(encode(err, "err", true))
+                    )?;
+                    writeln!(out, "        }}")?;
+                    writeln!(out, "    }}")?;
+                }
+                if named.decode {
+                    if named.encode {
+                        writeln!(out)?;
+                    }
+                    writeln!(
+                        out,
+                        "    pub fn from_value(value: Value) -> Result<Self, Error> {{"
+                    )?;
+                    writeln!(out, "        Ok(match value.expect_result()? {{")?;
+                    writeln!(
+                        out,
+                        "            Ok(ok) => Self::Ok({}?),",
+                        decode(bindings, ok, "ok")
+                    )?;
+                    writeln!(
+                        out,
+                        "            Err(err) => Self::Err({}?),",
+                        decode(bindings, err, "err")
+                    )?;
+                    writeln!(out, "        }})")?;
+                    writeln!(out, "    }}")?;
+                }
+                writeln!(out, "}}")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn emit_contract(bindings: &Bindings, out: &mut String) -> std::fmt::Result {
+    let contract_type = bindings.contract_type_name();
+    writeln!(out)?;
+    writeln!(
+        out,
+        "/// Calls to the `{}` contract",
+        bindings.contract_name
+    )?;
+    writeln!(out, "#[derive(Debug, Clone, PartialEq)]")?;
+    writeln!(out, "pub struct {contract_type} {{")?;
+    writeln!(out, "    pub address: StacksAddress,")?;
+    writeln!(out, "    pub contract_name: ContractName,")?;
+    writeln!(out, "}}")?;
+    writeln!(out)?;
+    writeln!(out, "impl {contract_type} {{")?;
+    for function in bindings.functions.iter() {
+        let method = ident(&function.name);
+        let params: String = function
+            .args
+            .iter()
+            .map(|arg| {
+                format!(
+                    ", {}: {}",
+                    ident(&arg.name),
+                    rust_type(bindings, &arg.binding_type)
+                )
+            })
+            .collect();
+        let args: Vec<String> = function
+            .args
+            .iter()
+            .map(|arg| try_expr(encode(&arg.binding_type, &ident(&arg.name), false)))
+            .collect();
+        let access = if function.read_only {
+            "read-only"
+        } else {
+            "public"
+        };
+        writeln!(
+            out,
+            "    /// Calls the {access} function `{}`",
+            function.name
+        )?;
+        writeln!(
+            out,
+            "    pub fn {method}(&self{params}) -> Result<TransactionContractCall, Error> {{"
+        )?;
+        writeln!(
+            out,
+            "        self.contract_call({:?}, vec![{}])",
+            function.name,
+            args.join(", ")
+        )?;
+        writeln!(out, "    }}")?;
+        writeln!(out)?;
+        writeln!(out, "    /// Decodes the result of `{}`", function.name)?;
+        writeln!(
+            out,
+            "    pub fn decode_{}(value: Value) -> Result<{}, Error> {{",
+            snake_case(&function.name),
+            rust_type(bindings, &function.output)
+        )?;
+        writeln!(
+            out,
+            "        {}",
+            decode(bindings, &function.output, "value")
+        )?;
+        writeln!(out, "    }}")?;
+        writeln!(out)?;
+    }
+    writeln!(out, "    fn contract_call(")?;
+    writeln!(out, "        &self,")?;
+    writeln!(out, "        function_name: &str,")?;
+    writeln!(out, "        function_args: Vec<Value>,")?;
+    writeln!(out, "    ) -> Result<TransactionContractCall, Error> {{")?;
+    writeln!(out, "        Ok(TransactionContractCall {{")?;
+    writeln!(out, "            address: self.address.clone(),")?;
+    writeln!(
+        out,
+        "            contract_name: self.contract_name.clone(),"
+    )?;
+    writeln!(
+        out,
+        "            function_name: runtime::name(function_name)?,"
+    )?;
+    writeln!(out, "            function_args,")?;
+    writeln!(out, "        }})")?;
+    writeln!(out, "    }}")?;
+    writeln!(out, "}}")
+}
+
+/// Render `bindings` as a Rust module
+pub fn emit_rust(bindings: &Bindings) -> String {
+    let mut body = String::new();
+    emit_types(bindings, &mut body)
+        .and_then(|_| emit_contract(bindings, &mut body))
+        .expect("writing to a String cannot fail");
+
+    let mut out = format!(
+        "// Bindings for the `{}` contract, generated by `clarity-cli bindings`.\n\n",
+        bindings.contract_name
+    );
+    for path in IMPORTS.iter() {
+        if path.ends_with("PrincipalData") && !body.contains("PrincipalData") {
+            continue;
+        }
+        out.push_str(&format!("use {path};\n"));
+    }
+    out.push_str(&body);
+    out
+}
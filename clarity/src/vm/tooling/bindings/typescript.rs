@@ -0,0 +1,469 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Renders bindings as a TypeScript module for `@stacks/transactions`.
+//!
+//! Tuples become interfaces, and responses become unions discriminated by
+//! `ok`. The contract class has a method per function, returning the
+//! options for a contract call, and a static decoder per function result.
+
+use std::fmt::Write;
+
+use super::{lower_camel_case, upper_camel_case, BindingType, Bindings, NamedTypeKind};
+
+const TYPESCRIPT_RESERVED: &[&str] = &[
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "import",
+    "in",
+    "instanceof",
+    "new",
+    "null",
+    "return",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+];
+
+/// Helper functions for decoding, each included only if it is used
+const DECODE_HELPERS: &[(&str, &str)] = &[
+    (
+        "decodeInt",
+        "function decodeInt(value: ClarityValue): bigint {
+  if (value.type !== ClarityType.Int) unexpected('int', value);
+  return BigInt(value.value);
+}",
+    ),
+    (
+        "decodeUInt",
+        "function decodeUInt(value: ClarityValue): bigint {
+  if (value.type !== ClarityType.UInt) unexpected('uint', value);
+  return BigInt(value.value);
+}",
+    ),
+    (
+        "decodeBool",
+        "function decodeBool(value: ClarityValue): boolean {
+  if (value.type === ClarityType.BoolTrue) return true;
+  if (value.type === ClarityType.BoolFalse) return false;
+  unexpected('bool', value);
+}",
+    ),
+    (
+        "decodePrincipal",
+        "function decodePrincipal(value: ClarityValue): string {
+  if (value.type !== ClarityType.PrincipalStandard && value.type !== ClarityType.PrincipalContract) {
+    unexpected('principal', value);
+  }
+  return principalToString(value);
+}",
+    ),
+    (
+        "decodeBuffer",
+        "function decodeBuffer(value: ClarityValue): Uint8Array {
+  if (value.type !== ClarityType.Buffer) unexpected('buff', value);
+  return value.buffer;
+}",
+    ),
+    (
+        "decodeStringAscii",
+        "function decodeStringAscii(value: ClarityValue): string {
+  if (value.type !== ClarityType.StringASCII) unexpected('string-ascii', value);
+  return value.data;
+}",
+    ),
+    (
+        "decodeStringUtf8",
+        "function decodeStringUtf8(value: ClarityValue): string {
+  if (value.type !== ClarityType.StringUTF8) unexpected('string-utf8', value);
+  return value.data;
+}",
+    ),
+    (
+        "decodeList",
+        "function decodeList(value: ClarityValue): ClarityValue[] {
+  if (value.type !== ClarityType.List) unexpected('list', value);
+  return value.list;
+}",
+    ),
+    (
+        "decodeOptional",
+        "function decodeOptional<T>(value: ClarityValue, decode: (item: ClarityValue) => T): T | null {
+  if (value.type === ClarityType.OptionalNone) return null;
+  if (value.type === ClarityType.OptionalSome) return decode(value.value);
+  unexpected('optional', value);
+}",
+    ),
+    (
+        "decodeTuple",
+        "function decodeTuple(value: ClarityValue): Record<string, ClarityValue> {
+  if (value.type !== ClarityType.Tuple) unexpected('tuple', value);
+  return value.data;
+}",
+    ),
+    (
+        "decodeNoType",
+        "function decodeNoType(value: ClarityValue): never {
+  unexpected('no value', value);
+}",
+    ),
+];
+
+const UNEXPECTED: &str = "function unexpected(expected: string, value: ClarityValue): never {
+  throw new Error(`Expected ${expected}, got Clarity type ${value.type}`);
+}";
+
+/// A `lowerCamelCase` identifier for a Clarity name
+fn ident(name: &str) -> String {
+    let ident = lower_camel_case(name);
+    if TYPESCRIPT_RESERVED.contains(&ident.as_str()) {
+        format!("{ident}_")
+    } else {
+        ident
+    }
+}
+
+fn ts_type(bindings: &Bindings, binding_type: &BindingType) -> String {
+    match binding_type {
+        BindingType::Int | BindingType::UInt => "bigint".into(),
+        BindingType::Bool => "boolean".into(),
+        BindingType::Principal | BindingType::TraitReference => "string".into(),
+        BindingType::Buffer => "Uint8Array".into(),
+        BindingType::StringAscii | BindingType::StringUtf8 => "string".into(),
+        BindingType::List(inner) => match inner.as_ref() {
+            BindingType::Optional(_) => format!("({})[]", ts_type(bindings, inner)),
+            _ => format!("{}[]", ts_type(bindings, inner)),
+        },
+        BindingType::Optional(inner) => format!("{} | null", ts_type(bindings, inner)),
+        BindingType::Named(index) => bindings.types[*index].name.clone(),
+        BindingType::NoType => "never".into(),
+    }
+}
+
+/// An expression encoding `place` as a `ClarityValue`
+fn encode(bindings: &Bindings, binding_type: &BindingType, place: &str) -> String {
+    match binding_type {
+        BindingType::Int => format!("Cl.int({place})"),
+        BindingType::UInt => format!("Cl.uint({place})"),
+        BindingType::Bool => format!("Cl.bool({place})"),
+        BindingType::Principal | BindingType::TraitReference => format!("Cl.principal({place})"),
+        BindingType::Buffer => format!("Cl.buffer({place})"),
+        BindingType::StringAscii => format!("Cl.stringAscii({place})"),
+        BindingType::StringUtf8 => format!("Cl.stringUtf8({place})"),
+        BindingType::List(inner) => format!(
+            "Cl.list({place}.map((item) => {}))",
+            encode(bindings, inner, "item")
+        ),
+        BindingType::Optional(inner) => format!(
+            "({place} === null ? Cl.none() : Cl.some({}))",
+            encode(bindings, inner, place)
+        ),
+        BindingType::Named(index) => format!("encode{}({place})", bindings.types[*index].name),
+        BindingType::NoType => "Cl.none()".into(),
+    }
+}
+
+/// An expression decoding the `ClarityValue` expression `value`
+fn decode(bindings: &Bindings, binding_type: &BindingType, value: &str) -> String {
+    match binding_type {
+        BindingType::Int => format!("decodeInt({value})"),
+        BindingType::UInt => format!("decodeUInt({value})"),
+        BindingType::Bool => format!("decodeBool({value})"),
+        BindingType::Principal | BindingType::TraitReference => {
+            format!("decodePrincipal({value})")
+        }
+        BindingType::Buffer => format!("decodeBuffer({value})"),
+        BindingType::StringAscii => format!("decodeStringAscii({value})"),
+        BindingType::StringUtf8 => format!("decodeStringUtf8({value})"),
+        BindingType::List(inner) => format!(
+            "decodeList({value}).map((item) => {})",
+            decode(bindings, inner, "item")
+        ),
+        BindingType::Optional(inner) => format!(
+            "decodeOptional({value}, (item) => {})",
+            decode(bindings, inner, "item")
+        ),
+        BindingType::Named(index) => format!("decode{}({value})", bindings.types[*index].name),
+        BindingType::NoType => format!("decodeNoType({value})"),
+    }
+}
+
+fn emit_types(bindings: &Bindings, out: &mut String) -> std::fmt::Result {
+    for named in bindings.types.iter() {
+        let name = &named.name;
+        writeln!(out)?;
+        writeln!(out, "/** `{}` */", named.clarity_type())?;
+        match &named.kind {
+            NamedTypeKind::Struct(fields) => {
+                writeln!(out, "export interface {name} {{")?;
+                for field in fields.iter() {
+                    writeln!(
+                        out,
+                        "  {}: {};",
+                        lower_camel_case(&field.name),
+                        ts_type(bindings, &field.binding_type)
+                    )?;
+                }
+                writeln!(out, "}}")?;
+                if named.encode {
+                    writeln!(out)?;
+                    writeln!(
+                        out,
+                        "export function encode{name}(value: {name}): ClarityValue {{"
+                    )?;
+                    writeln!(out, "  return Cl.tuple({{")?;
+                    for field in fields.iter() {
+                        let place = format!("value.{}", lower_camel_case(&field.name));
+                        writeln!(
+                            out,
+                            "    '{}': {},",
+                            field.name,
+                            encode(bindings, &field.binding_type, &place)
+                        )?;
+                    }
+                    writeln!(out, "  }});")?;
+                    writeln!(out, "}}")?;
+                }
+                if named.decode {
+                    writeln!(out)?;
+                    writeln!(
+                        out,
+                        "export function decode{name}(value: ClarityValue): {name} {{"
+                    )?;
+                    writeln!(out, "  const data = decodeTuple(value);")?;
+                    writeln!(out, "  return {{")?;
+                    for field in fields.iter() {
+                        let value = format!("data['{}']", field.name);
+                        writeln!(
+                            out,
+                            "    {}: {},",
+                            lower_camel_case(&field.name),
+                            decode(bindings, &field.binding_type, &value)
+                        )?;
+                    }
+                    writeln!(out, "  }};")?;
+                    writeln!(out, "}}")?;
+                }
+            }
+            NamedTypeKind::Response { ok, err } => {
+                writeln!(
+                    out,
+                    "export type {name} = {{ ok: true; value: {} }} | {{ ok: false; value: {} }};",
+                    ts_type(bindings, ok),
+                    ts_type(bindings, err)
+                )?;
+                if named.encode {
+                    writeln!(out)?;
+                    writeln!(
+                        out,
+                        "export function encode{name}(value: {name}): ClarityValue {{"
+                    )?;
+                    writeln!(
+                        out,
+                        "  return value.ok ? Cl.ok({}) : Cl.error({});",
+                        encode(bindings, ok, "value.value"),
+                        encode(bindings, err, "value.value")
+                    )?;
+                    writeln!(out, "}}")?;
+                }
+                if named.decode {
+                    writeln!(out)?;
+                    writeln!(
+                        out,
+                        "export function decode{name}(value: ClarityValue): {name} {{"
+                    )?;
+                    writeln!(out, "  if (value.type === ClarityType.ResponseOk) {{")?;
+                    writeln!(
+                        out,
+                        "    return {{ ok: true, value: {} }};",
+                        decode(bindings, ok, "value.value")
+                    )?;
+                    writeln!(out, "  }}")?;
+                    writeln!(out, "  if (value.type === ClarityType.ResponseErr) {{")?;
+                    writeln!(
+                        out,
+                        "    return {{ ok: false, value: {} }};",
+                        decode(bindings, err, "value.value")
+                    )?;
+                    writeln!(out, "  }}")?;
+                    writeln!(out, "  unexpected('response', value);")?;
+                    writeln!(out, "}}")?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn emit_contract(bindings: &Bindings, out: &mut String) -> std::fmt::Result {
+    writeln!(out)?;
+    writeln!(
+        out,
+        "/** Calls to the `{}` contract */",
+        bindings.contract_name
+    )?;
+    writeln!(out, "export class {} {{", bindings.contract_type_name())?;
+    writeln!(
+        out,
+        "  constructor(readonly contractAddress: string, readonly contractName: string) {{}}"
+    )?;
+    for function in bindings.functions.iter() {
+        let params: Vec<String> = function
+            .args
+            .iter()
+            .map(|arg| {
+                format!(
+                    "{}: {}",
+                    ident(&arg.name),
+                    ts_type(bindings, &arg.binding_type)
+                )
+            })
+            .collect();
+        let args: Vec<String> = function
+            .args
+            .iter()
+            .map(|arg| encode(bindings, &arg.binding_type, &ident(&arg.name)))
+            .collect();
+        let access = if function.read_only {
+            "read-only"
+        } else {
+            "public"
+        };
+        writeln!(out)?;
+        writeln!(
+            out,
+            "  /** Calls the {access} function `{}` */",
+            function.name
+        )?;
+        writeln!(
+            out,
+            "  {}({}): ContractCall {{",
+            ident(&function.name),
+            params.join(", ")
+        )?;
+        writeln!(
+            out,
+            "    return this.contractCall('{}', [{}]);",
+            function.name,
+            args.join(", ")
+        )?;
+        writeln!(out, "  }}")?;
+        writeln!(out)?;
+        writeln!(out, "  /** Decodes the result of `{}` */", function.name)?;
+        writeln!(
+            out,
+            "  static decode{}(value: ClarityValue): {} {{",
+            upper_camel_case(&function.name),
+            ts_type(bindings, &function.output)
+        )?;
+        writeln!(
+            out,
+            "    return {};",
+            decode(bindings, &function.output, "value")
+        )?;
+        writeln!(out, "  }}")?;
+    }
+    writeln!(out)?;
+    writeln!(
+        out,
+        "  private contractCall(functionName: string, functionArgs: ClarityValue[]): ContractCall {{"
+    )?;
+    writeln!(out, "    return {{")?;
+    writeln!(out, "      contractAddress: this.contractAddress,")?;
+    writeln!(out, "      contractName: this.contractName,")?;
+    writeln!(out, "      functionName,")?;
+    writeln!(out, "      functionArgs,")?;
+    writeln!(out, "    }};")?;
+    writeln!(out, "  }}")?;
+    writeln!(out, "}}")
+}
+
+/// Render `bindings` as a TypeScript module
+pub fn emit_typescript(bindings: &Bindings) -> String {
+    let mut body = String::new();
+    emit_types(bindings, &mut body)
+        .and_then(|_| emit_contract(bindings, &mut body))
+        .expect("writing to a String cannot fail");
+
+    let helpers: Vec<&str> = DECODE_HELPERS
+        .iter()
+        .filter(|(name, _)| body.contains(&format!("{name}(")))
+        .map(|(_, helper)| *helper)
+        .collect();
+    let used =
+        |name: &str| body.contains(name) || helpers.iter().any(|helper| helper.contains(name));
+    let imports: Vec<&str> = ["Cl", "ClarityType", "ClarityValue", "principalToString"]
+        .into_iter()
+        .filter(|name| {
+            *name == "ClarityValue" || used(&format!("{name}.")) || used(&format!("{name}("))
+        })
+        .collect();
+
+    let mut out = format!(
+        "// Bindings for the `{}` contract, generated by `clarity-cli bindings`.\n\n",
+        bindings.contract_name
+    );
+    out.push_str(&format!(
+        "import {{ {} }} from '@stacks/transactions';\n\n",
+        imports.join(", ")
+    ));
+    out.push_str(
+        "/** The options for calling a contract function */
+export interface ContractCall {
+  contractAddress: string;
+  contractName: string;
+  functionName: string;
+  functionArgs: ClarityValue[];
+}
+",
+    );
+    if !helpers.is_empty() || body.contains("unexpected(") {
+        out.push('\n');
+        out.push_str(UNEXPECTED);
+        out.push('\n');
+    }
+    for helper in helpers {
+        out.push('\n');
+        out.push_str(helper);
+        out.push('\n');
+    }
+    out.push_str(&body);
+    out
+}
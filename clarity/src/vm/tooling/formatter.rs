@@ -0,0 +1,627 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A source formatter for Clarity contracts.
+//!
+//! The formatter works on the tokens of the v2 lexer, which always carry their
+//! spans, so comments and blank lines are kept where they were written. Lists
+//! which fit within `MAX_WIDTH` are printed on one line; longer ones are broken
+//! with one argument per line. `(tuple (key value) ...)` is rewritten into the
+//! `{key: value, ...}` literal syntax.
+//!
+//! Layout decisions only depend on the expressions, the comments, and which
+//! lines are blank, so formatting is idempotent. Every formatted contract is
+//! parsed again, and must produce the same expressions as the original.
+
+use std::fmt;
+
+use crate::vm::ast::errors::{ParseError, ParseErrors};
+use crate::vm::ast::parser::v2::lexer::token::{PlacedToken, Token};
+use crate::vm::ast::parser::v2::lexer::Lexer;
+use crate::vm::ast::parser::v2::parse;
+use crate::vm::representations::{PreSymbolicExpression, PreSymbolicExpressionType};
+
+/// Lines are broken to fit within this many columns, where possible
+pub const MAX_WIDTH: usize = 80;
+/// The indentation of the arguments of a broken list
+const INDENT: usize = 2;
+
+#[derive(Debug)]
+pub enum FormatError {
+    Parse(ParseError),
+    /// The formatted source does not parse to the same expressions as the
+    ///  original. This is a bug in the formatter.
+    NotEquivalent,
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FormatError::Parse(e) => write!(f, "failed to parse contract: {e}"),
+            FormatError::NotEquivalent => {
+                write!(f, "formatting would change the meaning of the contract")
+            }
+        }
+    }
+}
+
+impl From<ParseError> for FormatError {
+    fn from(e: ParseError) -> Self {
+        FormatError::Parse(e)
+    }
+}
+
+/// The number of arguments a broken call to `name` keeps on its first line
+fn header_args(name: &str) -> usize {
+    match name {
+        "define-public"
+        | "define-private"
+        | "define-read-only"
+        | "define-constant"
+        | "define-data-var"
+        | "define-map"
+        | "define-fungible-token"
+        | "define-non-fungible-token"
+        | "define-trait"
+        | "let"
+        | "if"
+        | "match"
+        | "asserts!"
+        | "unwrap!"
+        | "unwrap-err!"
+        | "var-set"
+        | "map-get?"
+        | "map-set"
+        | "map-insert"
+        | "map-delete"
+        | "get"
+        | "default-to" => 1,
+        "contract-call?" => 2,
+        _ => 0,
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    /// An atom, exactly as it was written
+    Atom(String),
+    List(Vec<Item>),
+    /// The items of a tuple literal are its entries and comments
+    Tuple(Vec<Item>),
+    Entry(Box<Item>, Box<Item>),
+    /// `trailing` comments follow other tokens on the same line
+    Comment {
+        text: String,
+        trailing: bool,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct Item {
+    node: Node,
+    start_line: u32,
+    end_line: u32,
+}
+
+impl Item {
+    fn is_comment(&self) -> bool {
+        matches!(self.node, Node::Comment { .. })
+    }
+}
+
+/// Builds the tree of items from the lexer's tokens
+struct TreeBuilder<'a> {
+    lines: Vec<Vec<char>>,
+    tokens: &'a [PlacedToken],
+    next: usize,
+    /// The line that the last consumed token ended on
+    last_line: u32,
+}
+
+impl TreeBuilder<'_> {
+    /// The source text of the tokens in `first..=last`
+    fn source(&self, first: &PlacedToken, last: &PlacedToken) -> String {
+        let start = first.span.start_column.saturating_sub(1) as usize;
+        let end = last.span.end_column as usize;
+        self.lines
+            .get(first.span.start_line.saturating_sub(1) as usize)
+            .and_then(|line| line.get(start..end.min(line.len())))
+            .map(|chars| chars.iter().collect())
+            .unwrap_or_default()
+    }
+
+    fn is_atom_token(token: &Token) -> bool {
+        !matches!(
+            token,
+            Token::Eof
+                | Token::Whitespace
+                | Token::Lparen
+                | Token::Rparen
+                | Token::Lbrace
+                | Token::Rbrace
+                | Token::Colon
+                | Token::Comma
+                | Token::Comment(_)
+        )
+    }
+
+    /// Read items until the closing token, which is consumed. Returns the
+    ///  items and the line of the closing token.
+    fn items(&mut self, close: &Token) -> (Vec<Item>, u32) {
+        let mut items = vec![];
+        while let Some(token) = self.tokens.get(self.next) {
+            let span = token.span.clone();
+            match &token.token {
+                Token::Whitespace | Token::Colon | Token::Comma => {
+                    self.next += 1;
+                }
+                Token::Eof => break,
+                t if t == close => {
+                    self.next += 1;
+                    self.last_line = span.end_line;
+                    return (items, span.end_line);
+                }
+                Token::Rparen | Token::Rbrace => {
+                    // unbalanced: the parser would have rejected the contract
+                    self.next += 1;
+                }
+                Token::Comment(_) => {
+                    let text = self.source(token, token).trim_end().to_string();
+                    items.push(Item {
+                        node: Node::Comment {
+                            text,
+                            trailing: span.start_line == self.last_line,
+                        },
+                        start_line: span.start_line,
+                        end_line: span.end_line,
+                    });
+                    self.next += 1;
+                    self.last_line = span.end_line;
+                }
+                Token::Lparen => {
+                    self.next += 1;
+                    self.last_line = span.end_line;
+                    let (children, end_line) = self.items(&Token::Rparen);
+                    items.push(Item {
+                        node: Node::List(children),
+                        start_line: span.start_line,
+                        end_line,
+                    });
+                }
+                Token::Lbrace => {
+                    self.next += 1;
+                    self.last_line = span.end_line;
+                    let (children, end_line) = self.items(&Token::Rbrace);
+                    items.push(Item {
+                        node: Node::Tuple(pair_entries(children)),
+                        start_line: span.start_line,
+                        end_line,
+                    });
+                }
+                _ => {
+                    // adjacent atom tokens, such as `'SP000.contract.trait`,
+                    //  form a single atom
+                    let first = self.next;
+                    let mut last = self.next;
+                    while let Some(next) = self.tokens.get(last + 1) {
+                        let prev = &self.tokens[last].span;
+                        if !Self::is_atom_token(&next.token)
+                            || next.span.start_line != prev.end_line
+                            || next.span.start_column != prev.end_column + 1
+                        {
+                            break;
+                        }
+                        last += 1;
+                    }
+                    let text = self.source(&self.tokens[first], &self.tokens[last]);
+                    let end_line = self.tokens[last].span.end_line;
+                    items.push(Item {
+                        node: Node::Atom(text),
+                        start_line: span.start_line,
+                        end_line,
+                    });
+                    self.next = last + 1;
+                    self.last_line = end_line;
+                }
+            }
+        }
+        let end_line = self.last_line;
+        (items, end_line)
+    }
+}
+
+/// Pair up the keys and values of a tuple literal. Comments between a key
+///  and its value are moved after the entry.
+fn pair_entries(items: Vec<Item>) -> Vec<Item> {
+    let mut entries = vec![];
+    let mut key: Option<Item> = None;
+    let mut moved_comments = vec![];
+    for item in items {
+        if item.is_comment() {
+            if key.is_some() {
+                moved_comments.push(item);
+            } else {
+                entries.push(item);
+            }
+            continue;
+        }
+        match key.take() {
+            None => key = Some(item),
+            Some(key) => {
+                entries.push(Item {
+                    start_line: key.start_line,
+                    end_line: item.end_line,
+                    node: Node::Entry(Box::new(key), Box::new(item)),
+                });
+                for mut comment in moved_comments.drain(..) {
+                    if let Node::Comment { trailing, .. } = &mut comment.node {
+                        *trailing = false;
+                    }
+                    entries.push(comment);
+                }
+            }
+        }
+    }
+    entries.extend(key);
+    entries.extend(moved_comments);
+    entries
+}
+
+/// Rewrite `(tuple (key value) ...)` as a tuple literal
+fn normalize(item: &mut Item) {
+    match &mut item.node {
+        Node::List(children) => {
+            children.iter_mut().for_each(normalize);
+            if let Some(entries) = tuple_entries(children) {
+                item.node = Node::Tuple(entries);
+            }
+        }
+        Node::Tuple(children) => children.iter_mut().for_each(normalize),
+        Node::Entry(key, value) => {
+            normalize(key);
+            normalize(value);
+        }
+        Node::Atom(_) | Node::Comment { .. } => {}
+    }
+}
+
+/// The entries of a `(tuple ...)` call, if every argument is a `(key value)` pair
+fn tuple_entries(children: &[Item]) -> Option<Vec<Item>> {
+    let mut args = children.iter().filter(|item| !item.is_comment());
+    match args.next() {
+        Some(Item {
+            node: Node::Atom(head),
+            ..
+        }) if head == "tuple" => {}
+        _ => return None,
+    }
+    let mut entries = vec![];
+    for item in children.iter().skip_while(|item| item.is_comment()).skip(1) {
+        match &item.node {
+            Node::Comment { .. } => entries.push(item.clone()),
+            Node::List(pair) => match &pair[..] {
+                [key @ Item {
+                    node: Node::Atom(_),
+                    ..
+                }, value]
+                    if !value.is_comment() =>
+                {
+                    entries.push(Item {
+                        node: Node::Entry(Box::new(key.clone()), Box::new(value.clone())),
+                        start_line: item.start_line,
+                        end_line: item.end_line,
+                    });
+                }
+                _ => return None,
+            },
+            _ => return None,
+        }
+    }
+    if entries.iter().any(|entry| !entry.is_comment()) {
+        Some(entries)
+    } else {
+        None
+    }
+}
+
+/// The single-line rendering of an item, if it has no comments
+fn flat(item: &Item) -> Option<String> {
+    match &item.node {
+        Node::Atom(text) => Some(text.clone()),
+        Node::Comment { .. } => None,
+        Node::List(children) => {
+            let children = children.iter().map(flat).collect::<Option<Vec<_>>>()?;
+            Some(format!("({})", children.join(" ")))
+        }
+        Node::Tuple(entries) => {
+            let entries = entries.iter().map(flat).collect::<Option<Vec<_>>>()?;
+            Some(format!("{{{}}}", entries.join(", ")))
+        }
+        Node::Entry(key, value) => Some(format!("{}: {}", flat(key)?, flat(value)?)),
+    }
+}
+
+/// Output lines, where the first line starts at the column it was rendered at
+struct Lines {
+    lines: Vec<String>,
+    /// The last line ends in a comment, so nothing else can be added to it
+    commented: bool,
+}
+
+impl Lines {
+    fn new(first: &str) -> Lines {
+        Lines {
+            lines: vec![first.to_string()],
+            commented: false,
+        }
+    }
+
+    fn last_mut(&mut self) -> &mut String {
+        if self.lines.is_empty() {
+            self.lines.push(String::new());
+        }
+        let last = self.lines.len() - 1;
+        &mut self.lines[last]
+    }
+
+    /// The column the next character on the last line would be written at
+    fn column(&self, start_column: usize) -> usize {
+        match self.lines.len() {
+            0 | 1 => start_column + self.lines.first().map_or(0, |line| line.len()),
+            _ => self.lines.last().map_or(0, |line| line.len()),
+        }
+    }
+
+    fn append(&mut self, text: &str) {
+        let mut rendered = text.lines();
+        if let Some(first) = rendered.next() {
+            self.last_mut().push_str(first);
+        }
+        self.lines.extend(rendered.map(str::to_string));
+    }
+
+    fn new_line(&mut self, indent: usize, blank_before: bool) {
+        if blank_before {
+            self.lines.push(String::new());
+        }
+        self.lines.push(" ".repeat(indent));
+        self.commented = false;
+    }
+
+    fn comment(&mut self, text: &str, trailing: bool, indent: usize, blank_before: bool) {
+        if trailing && !self.commented {
+            self.last_mut().push(' ');
+        } else {
+            self.new_line(indent, blank_before);
+        }
+        self.last_mut().push_str(text);
+        self.commented = true;
+    }
+
+    fn finish(self) -> String {
+        self.lines
+            .iter()
+            .map(|line| line.trim_end())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Render `item`, with its first line starting at `column`. Later lines carry
+///  their own indentation.
+fn render(item: &Item, column: usize) -> String {
+    if let Some(flat) = flat(item) {
+        if column + flat.len() <= MAX_WIDTH || matches!(item.node, Node::Atom(_)) {
+            return flat;
+        }
+    }
+    match &item.node {
+        Node::Atom(text) => text.clone(),
+        Node::Comment { text, .. } => text.clone(),
+        Node::List(children) => render_list(children, column),
+        Node::Tuple(entries) => render_tuple(entries, column),
+        Node::Entry(key, value) => render_entry(key, value, column),
+    }
+}
+
+fn render_entry(key: &Item, value: &Item, column: usize) -> String {
+    let key = render(key, column);
+    let value = render(value, column + key.len() + 2);
+    format!("{key}: {value}")
+}
+
+/// Whether there is a blank line between `prev` and `item` in the source
+fn blank_between(prev: Option<&Item>, item: &Item) -> bool {
+    prev.is_some_and(|prev| item.start_line > prev.end_line + 1)
+}
+
+fn render_list(children: &[Item], column: usize) -> String {
+    let mut lines = Lines::new("(");
+    let head = children.iter().find(|item| !item.is_comment());
+    let (child_indent, header) = match head.map(|head| &head.node) {
+        Some(Node::Atom(name)) => (column + INDENT, header_args(name)),
+        _ => (column + 1, 0),
+    };
+
+    let mut prev: Option<&Item> = None;
+    let mut args = 0;
+    for item in children {
+        let blank = blank_between(prev, item);
+        match &item.node {
+            Node::Comment { text, trailing } => {
+                lines.comment(text, *trailing, child_indent, blank);
+            }
+            _ => {
+                if args == 0 {
+                    // the head of the list
+                    if lines.commented {
+                        lines.new_line(column + 1, false);
+                    }
+                } else if args <= header && !lines.commented && !blank {
+                    lines.append(" ");
+                } else {
+                    lines.new_line(child_indent, blank);
+                }
+                let rendered = render(item, lines.column(column));
+                lines.append(&rendered);
+                args += 1;
+            }
+        }
+        prev = Some(item);
+    }
+
+    if lines.commented {
+        lines.new_line(column, false);
+    }
+    lines.append(")");
+    lines.finish()
+}
+
+fn render_tuple(entries: &[Item], column: usize) -> String {
+    let mut lines = Lines::new("{");
+    let indent = column + INDENT;
+    let count = entries.iter().filter(|entry| !entry.is_comment()).count();
+
+    let mut prev: Option<&Item> = None;
+    let mut index = 0;
+    for entry in entries {
+        let blank = blank_between(prev, entry);
+        match &entry.node {
+            Node::Comment { text, trailing } => lines.comment(text, *trailing, indent, blank),
+            _ => {
+                lines.new_line(indent, blank);
+                let rendered = render(entry, indent);
+                lines.append(&rendered);
+                index += 1;
+                if index < count {
+                    lines.append(",");
+                }
+            }
+        }
+        prev = Some(entry);
+    }
+
+    lines.new_line(column, false);
+    lines.append("}");
+    lines.finish()
+}
+
+fn render_top_level(items: &[Item]) -> String {
+    let mut lines = Lines {
+        lines: vec![],
+        commented: false,
+    };
+    let mut prev: Option<&Item> = None;
+    for item in items {
+        let blank = blank_between(prev, item);
+        match &item.node {
+            Node::Comment { text, trailing } if prev.is_some() => {
+                lines.comment(text, *trailing, 0, blank)
+            }
+            Node::Comment { text, .. } => lines.comment(text, false, 0, false),
+            _ => {
+                if prev.is_some() {
+                    lines.new_line(0, blank);
+                } else {
+                    lines.new_line(0, false);
+                }
+                let rendered = render(item, 0);
+                lines.append(&rendered);
+            }
+        }
+        prev = Some(item);
+    }
+    let mut out = lines.finish();
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    out
+}
+
+/// A comparable form of parsed expressions, without comments, ids or spans,
+///  and with tuple literals expanded into `tuple` calls
+#[derive(Debug, PartialEq)]
+enum Canonical {
+    Name(String),
+    Atom(String),
+    List(Vec<Canonical>),
+}
+
+fn canonical(exprs: &[PreSymbolicExpression]) -> Vec<Canonical> {
+    exprs
+        .iter()
+        .filter(|expr| expr.match_comment().is_none())
+        .map(|expr| match &expr.pre_expr {
+            PreSymbolicExpressionType::List(children) => Canonical::List(canonical(children)),
+            PreSymbolicExpressionType::Tuple(children) => {
+                let mut items = vec![Canonical::Name("tuple".into())];
+                let fields = canonical(children);
+                let mut fields = fields.into_iter();
+                while let (Some(key), Some(value)) = (fields.next(), fields.next()) {
+                    items.push(Canonical::List(vec![key, value]));
+                }
+                Canonical::List(items)
+            }
+            PreSymbolicExpressionType::Atom(name) => Canonical::Name(name.to_string()),
+            other => Canonical::Atom(format!("{other:?}")),
+        })
+        .collect()
+}
+
+/// Format a contract in the canonical style
+pub fn format_source(source: &str) -> Result<String, FormatError> {
+    let original = parse(source)?;
+
+    let tokens = {
+        let mut lexer =
+            Lexer::new(source, false).map_err(|e| ParseError::new(ParseErrors::Lexer(e)))?;
+        let mut tokens = vec![];
+        loop {
+            let token = lexer
+                .read_token()
+                .map_err(|e| ParseError::new(ParseErrors::Lexer(e)))?;
+            let eof = token.token == Token::Eof;
+            tokens.push(token);
+            if eof {
+                break;
+            }
+        }
+        tokens
+    };
+
+    let mut builder = TreeBuilder {
+        lines: source
+            .split('\n')
+            .map(|line| line.chars().collect())
+            .collect(),
+        tokens: &tokens,
+        next: 0,
+        last_line: 0,
+    };
+    let (mut items, _) = builder.items(&Token::Eof);
+    items.iter_mut().for_each(normalize);
+    let formatted = render_top_level(&items);
+
+    let reparsed = parse(&formatted).map_err(|_| FormatError::NotEquivalent)?;
+    if canonical(&original) != canonical(&reparsed) {
+        return Err(FormatError::NotEquivalent);
+    }
+    Ok(formatted)
+}
+
+/// Whether `source` is already formatted in the canonical style
+pub fn is_formatted(source: &str) -> Result<bool, FormatError> {
+    Ok(format_source(source)? == source)
+}
@@ -0,0 +1,733 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Property-based fuzzing of a contract's public functions.
+//!
+//! A `FuzzTarget` is read from a contract's interface. Its public functions
+//! are called in random sequences, with random well-typed arguments, and its
+//! invariants -- read-only functions named `invariant-*`, which take no
+//! arguments and return a `bool` -- are checked after every call. When an
+//! invariant does not hold, the failing sequence is shrunk, by dropping calls
+//! and simplifying arguments, to a smaller sequence which breaks the same
+//! invariant.
+
+use std::fmt;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use stacks_common::types::StacksEpochId;
+use stacks_common::util::hash::to_hex;
+
+use crate::vm::analysis::contract_interface_builder::{
+    ContractInterface, ContractInterfaceAtomType, ContractInterfaceFunctionAccess,
+};
+use crate::vm::contexts::OwnedEnvironment;
+use crate::vm::database::ClarityDatabase;
+use crate::vm::errors::{Error, InterpreterError};
+use crate::vm::representations::{ClarityName, SymbolicExpression};
+use crate::vm::types::{
+    BuffData, CharType, ListData, PrincipalData, QualifiedContractIdentifier, SequenceData,
+    SequenceSubtype, StandardPrincipalData, StringSubtype, TupleData, TypeSignature,
+};
+use crate::vm::Value;
+
+/// Read-only functions whose names start with this are invariants
+pub const INVARIANT_PREFIX: &str = "invariant-";
+
+/// Generated sequences are at most this long, regardless of their type's
+///  maximum length
+const MAX_GENERATED_LENGTH: u32 = 32;
+
+/// A public function, with the types of its arguments
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzFunction {
+    pub name: ClarityName,
+    pub args: Vec<TypeSignature>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzTarget {
+    pub contract: QualifiedContractIdentifier,
+    pub functions: Vec<FuzzFunction>,
+    pub invariants: Vec<ClarityName>,
+    /// Public functions which take arguments no value can be generated for,
+    ///  such as trait references
+    pub skipped: Vec<ClarityName>,
+}
+
+impl FuzzTarget {
+    pub fn from_interface(
+        contract: QualifiedContractIdentifier,
+        interface: &ContractInterface,
+    ) -> Result<FuzzTarget, String> {
+        let mut functions = vec![];
+        let mut invariants = vec![];
+        let mut skipped = vec![];
+        for function in interface.functions.iter() {
+            let name = ClarityName::try_from(function.name.clone()).map_err(|e| e.to_string())?;
+            match function.access {
+                ContractInterfaceFunctionAccess::public => {
+                    let args: Option<Vec<_>> = function
+                        .args
+                        .iter()
+                        .map(|arg| arg.type_f.to_type_signature())
+                        .collect();
+                    match args {
+                        Some(args) => functions.push(FuzzFunction { name, args }),
+                        None => skipped.push(name),
+                    }
+                }
+                ContractInterfaceFunctionAccess::read_only
+                    if function.name.starts_with(INVARIANT_PREFIX) =>
+                {
+                    if !function.args.is_empty()
+                        || function.outputs.type_f != ContractInterfaceAtomType::bool
+                    {
+                        return Err(format!(
+                            "invariant `{name}` must take no arguments and return a bool"
+                        ));
+                    }
+                    invariants.push(name);
+                }
+                _ => {}
+            }
+        }
+        if functions.is_empty() {
+            return Err(format!("{contract} has no public functions to call"));
+        }
+        if invariants.is_empty() {
+            return Err(format!(
+                "{contract} has no invariants: define read-only functions named `{INVARIANT_PREFIX}*`"
+            ));
+        }
+        Ok(FuzzTarget {
+            contract,
+            functions,
+            invariants,
+            skipped,
+        })
+    }
+}
+
+/// A call to a public function
+#[derive(Debug, Clone, PartialEq)]
+pub struct Call {
+    pub function: ClarityName,
+    pub sender: PrincipalData,
+    pub args: Vec<Value>,
+}
+
+impl fmt::Display for Call {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}", self.function)?;
+        for arg in self.args.iter() {
+            write!(f, " {}", clarity_literal(arg))?;
+        }
+        write!(f, ") as {}", self.sender)
+    }
+}
+
+/// An invariant which did not hold
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    /// The index of the call after which the invariant was checked
+    pub after_call: usize,
+    pub invariant: ClarityName,
+    /// What the invariant returned instead of `true`
+    pub outcome: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "`{}` {} after call {}",
+            self.invariant,
+            self.outcome,
+            self.after_call + 1
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzConfig {
+    /// How many call sequences to generate
+    pub runs: u32,
+    /// The maximum length of a call sequence
+    pub max_calls: u32,
+    pub seed: u64,
+    /// The maximum number of sequences to run while shrinking a failure
+    pub max_shrink_runs: u32,
+    /// The principals calls are sent from
+    pub senders: Vec<PrincipalData>,
+}
+
+impl FuzzConfig {
+    pub fn new(seed: u64, senders: Vec<PrincipalData>) -> FuzzConfig {
+        FuzzConfig {
+            runs: 100,
+            max_calls: 20,
+            seed,
+            max_shrink_runs: 1000,
+            senders,
+        }
+    }
+}
+
+/// `count` distinct standard principals to send calls from
+pub fn test_senders(mainnet: bool, count: u8) -> Vec<PrincipalData> {
+    // single-sig address versions
+    let version = if mainnet { 22 } else { 26 };
+    (1..=count)
+        .filter_map(|i| StandardPrincipalData::new(version, [i; 20]).ok())
+        .map(PrincipalData::Standard)
+        .collect()
+}
+
+/// A failing call sequence, after shrinking
+#[derive(Debug, Clone, PartialEq)]
+pub struct Counterexample {
+    pub calls: Vec<Call>,
+    pub violation: Violation,
+    /// The number of calls in the sequence before it was shrunk
+    pub original_calls: usize,
+    pub shrink_runs: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzReport {
+    /// The number of sequences run, not counting shrinking
+    pub runs: u32,
+    pub calls: u64,
+    pub counterexample: Option<Counterexample>,
+}
+
+/// Render a value as Clarity source which evaluates to it
+pub fn clarity_literal(value: &Value) -> String {
+    fn escape_ascii(c: u8, out: &mut String) {
+        match c {
+            b'"' => out.push_str("\\\""),
+            b'\\' => out.push_str("\\\\"),
+            b'\n' => out.push_str("\\n"),
+            b'\t' => out.push_str("\\t"),
+            b'\r' => out.push_str("\\r"),
+            0 => out.push_str("\\0"),
+            c => out.push(c as char),
+        }
+    }
+
+    fn join(values: &[Value]) -> String {
+        values
+            .iter()
+            .map(|value| format!(" {}", clarity_literal(value)))
+            .collect()
+    }
+
+    match value {
+        Value::Int(n) => n.to_string(),
+        Value::UInt(n) => format!("u{n}"),
+        Value::Bool(b) => b.to_string(),
+        Value::Principal(principal) => format!("'{principal}"),
+        Value::CallableContract(callable) => format!("'{}", callable.contract_identifier),
+        Value::Sequence(SequenceData::Buffer(buff)) => format!("0x{}", to_hex(&buff.data)),
+        Value::Sequence(SequenceData::String(CharType::ASCII(string))) => {
+            let mut out = "\"".to_string();
+            for c in string.data.iter() {
+                escape_ascii(*c, &mut out);
+            }
+            out.push('"');
+            out
+        }
+        Value::Sequence(SequenceData::String(CharType::UTF8(string))) => {
+            let mut out = "u\"".to_string();
+            for c in string.data.iter() {
+                match c.as_slice() {
+                    [c] => escape_ascii(*c, &mut out),
+                    bytes => {
+                        let code_point = std::str::from_utf8(bytes)
+                            .ok()
+                            .and_then(|c| c.chars().next())
+                            .map(u32::from)
+                            .unwrap_or(0xfffd);
+                        out.push_str(&format!("\\u{{{code_point:x}}}"));
+                    }
+                }
+            }
+            out.push('"');
+            out
+        }
+        Value::Sequence(SequenceData::List(list)) => format!("(list{})", join(&list.data)),
+        Value::Tuple(tuple) => {
+            let fields: Vec<_> = tuple
+                .data_map
+                .iter()
+                .map(|(name, value)| format!("{name}: {}", clarity_literal(value)))
+                .collect();
+            format!("{{{}}}", fields.join(", "))
+        }
+        Value::Optional(optional) => match &optional.data {
+            Some(value) => format!("(some {})", clarity_literal(value)),
+            None => "none".to_string(),
+        },
+        Value::Response(response) if response.committed => {
+            format!("(ok {})", clarity_literal(&response.data))
+        }
+        Value::Response(response) => format!("(err {})", clarity_literal(&response.data)),
+    }
+}
+
+/// Generates random values of a given type
+pub struct ValueGenerator<R: Rng> {
+    rng: R,
+    /// The principals that principal values are drawn from
+    principals: Vec<PrincipalData>,
+    epoch: StacksEpochId,
+}
+
+impl<R: Rng> ValueGenerator<R> {
+    pub fn new(rng: R, principals: Vec<PrincipalData>, epoch: StacksEpochId) -> ValueGenerator<R> {
+        ValueGenerator {
+            rng,
+            principals,
+            epoch,
+        }
+    }
+
+    /// A sequence length, biased towards the empty and the longest sequences
+    fn length(&mut self, max: u32) -> usize {
+        let max = max.min(MAX_GENERATED_LENGTH);
+        let length = match self.rng.gen_range(0..4) {
+            0 => 0,
+            1 => max,
+            _ => self.rng.gen_range(0..=max),
+        };
+        length as usize
+    }
+
+    fn int(&mut self) -> i128 {
+        match self.rng.gen_range(0..8) {
+            0 => 0,
+            1 => 1,
+            2 => -1,
+            3 => i128::MAX,
+            4 => i128::MIN,
+            5 | 6 => self.rng.gen_range(-1000..=1000),
+            _ => self.rng.gen(),
+        }
+    }
+
+    fn uint(&mut self) -> u128 {
+        match self.rng.gen_range(0..6) {
+            0 => 0,
+            1 => 1,
+            2 => u128::MAX,
+            3 | 4 => self.rng.gen_range(0..=1000),
+            _ => self.rng.gen(),
+        }
+    }
+
+    fn utf8_char(&mut self) -> char {
+        if self.rng.gen_bool(0.5) {
+            return self.rng.gen_range(0x20u8..=0x7e) as char;
+        }
+        let c: char = self.rng.gen();
+        if c.is_control() {
+            '?'
+        } else {
+            c
+        }
+    }
+
+    pub fn generate(&mut self, ty: &TypeSignature) -> Result<Value, String> {
+        use crate::vm::types::TypeSignature::*;
+
+        let value = match ty {
+            IntType => Value::Int(self.int()),
+            UIntType => Value::UInt(self.uint()),
+            BoolType => Value::Bool(self.rng.gen()),
+            PrincipalType => {
+                if self.principals.is_empty() {
+                    return Err("no principals to generate principal values from".into());
+                }
+                let index = self.rng.gen_range(0..self.principals.len());
+                Value::Principal(self.principals[index].clone())
+            }
+            SequenceType(SequenceSubtype::BufferType(max)) => {
+                let length = self.length(max.into());
+                let data = (0..length).map(|_| self.rng.gen()).collect();
+                Value::buff_from(data).map_err(|e| e.to_string())?
+            }
+            SequenceType(SequenceSubtype::StringType(StringSubtype::ASCII(max))) => {
+                let length = self.length(max.into());
+                let data = (0..length)
+                    .map(|_| self.rng.gen_range(0x20u8..=0x7e))
+                    .collect();
+                Value::string_ascii_from_bytes(data).map_err(|e| e.to_string())?
+            }
+            SequenceType(SequenceSubtype::StringType(StringSubtype::UTF8(max))) => {
+                let length = self.length(max.into());
+                let string: String = (0..length).map(|_| self.utf8_char()).collect();
+                Value::string_utf8_from_bytes(string.into_bytes()).map_err(|e| e.to_string())?
+            }
+            SequenceType(SequenceSubtype::ListType(list_type)) => {
+                let length = self.length(list_type.get_max_len());
+                let items = (0..length)
+                    .map(|_| self.generate(list_type.get_list_item_type()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Value::list_with_type(&self.epoch, items, list_type.clone())
+                    .map_err(|e| e.to_string())?
+            }
+            TupleType(tuple_type) => {
+                let fields = tuple_type
+                    .get_type_map()
+                    .iter()
+                    .map(|(name, ty)| Ok((name.clone(), self.generate(ty)?)))
+                    .collect::<Result<Vec<_>, String>>()?;
+                Value::Tuple(TupleData::from_data(fields).map_err(|e| e.to_string())?)
+            }
+            OptionalType(inner) => {
+                if self.rng.gen_range(0..4) == 0 {
+                    Value::none()
+                } else {
+                    Value::some(self.generate(inner)?).map_err(|e| e.to_string())?
+                }
+            }
+            ResponseType(inner) => {
+                let (ok_type, err_type) = inner.as_ref();
+                if self.rng.gen_bool(0.5) {
+                    Value::okay(self.generate(ok_type)?).map_err(|e| e.to_string())?
+                } else {
+                    Value::error(self.generate(err_type)?).map_err(|e| e.to_string())?
+                }
+            }
+            NoType | CallableType(_) | ListUnionType(_) | TraitReferenceType(_) => {
+                return Err(format!("cannot generate values of type {ty}"));
+            }
+        };
+        Ok(value)
+    }
+
+    fn call(&mut self, function: &FuzzFunction, senders: &[PrincipalData]) -> Result<Call, String> {
+        let args = function
+            .args
+            .iter()
+            .map(|ty| self.generate(ty))
+            .collect::<Result<_, _>>()?;
+        let sender = senders[self.rng.gen_range(0..senders.len())].clone();
+        Ok(Call {
+            function: function.name.clone(),
+            sender,
+            args,
+        })
+    }
+}
+
+/// Simpler values of the same type as `value`, simplest first
+pub fn shrink_value(value: &Value) -> Vec<Value> {
+    /// The empty sequence, the first half, and all but the last element
+    fn shorter<T: Clone + PartialEq>(items: &[T]) -> Vec<Vec<T>> {
+        if items.is_empty() {
+            return vec![];
+        }
+        let mut candidates = vec![vec![], items[..items.len() / 2].to_vec()];
+        candidates.push(items[..items.len() - 1].to_vec());
+        candidates.dedup();
+        candidates
+    }
+
+    let mut candidates = vec![];
+    match value {
+        Value::Int(n) if *n != 0 => {
+            candidates.extend([0, n / 2, n - n.signum()].map(Value::Int));
+        }
+        Value::UInt(n) if *n != 0 => {
+            candidates.extend([0, n / 2, n - 1].map(Value::UInt));
+        }
+        Value::Bool(true) => candidates.push(Value::Bool(false)),
+        Value::Sequence(SequenceData::Buffer(buff)) => {
+            for data in shorter(&buff.data) {
+                candidates.push(Value::Sequence(SequenceData::Buffer(BuffData { data })));
+            }
+        }
+        Value::Sequence(SequenceData::String(CharType::ASCII(string))) => {
+            for data in shorter(&string.data) {
+                let mut string = string.clone();
+                string.data = data;
+                candidates.push(Value::Sequence(SequenceData::String(CharType::ASCII(
+                    string,
+                ))));
+            }
+        }
+        Value::Sequence(SequenceData::String(CharType::UTF8(string))) => {
+            for data in shorter(&string.data) {
+                let mut string = string.clone();
+                string.data = data;
+                candidates.push(Value::Sequence(SequenceData::String(CharType::UTF8(
+                    string,
+                ))));
+            }
+        }
+        Value::Sequence(SequenceData::List(list)) => {
+            let mut lists = shorter(&list.data);
+            for (index, item) in list.data.iter().enumerate() {
+                for simpler in shrink_value(item) {
+                    let mut data = list.data.clone();
+                    data[index] = simpler;
+                    lists.push(data);
+                }
+            }
+            candidates.extend(lists.into_iter().map(|data| {
+                Value::Sequence(SequenceData::List(ListData {
+                    data,
+                    type_signature: list.type_signature.clone(),
+                }))
+            }));
+        }
+        Value::Tuple(tuple) => {
+            for (name, field) in tuple.data_map.iter() {
+                for simpler in shrink_value(field) {
+                    let mut fields: Vec<_> = tuple
+                        .data_map
+                        .iter()
+                        .map(|(name, value)| (name.clone(), value.clone()))
+                        .collect();
+                    if let Some(entry) = fields.iter_mut().find(|(n, _)| n == name) {
+                        entry.1 = simpler;
+                    }
+                    if let Ok(tuple) = TupleData::from_data(fields) {
+                        candidates.push(Value::Tuple(tuple));
+                    }
+                }
+            }
+        }
+        Value::Optional(optional) => {
+            if let Some(inner) = &optional.data {
+                candidates.push(Value::none());
+                candidates.extend(
+                    shrink_value(inner)
+                        .into_iter()
+                        .filter_map(|simpler| Value::some(simpler).ok()),
+                );
+            }
+        }
+        Value::Response(response) => {
+            candidates.extend(
+                shrink_value(&response.data)
+                    .into_iter()
+                    .filter_map(|simpler| {
+                        if response.committed {
+                            Value::okay(simpler).ok()
+                        } else {
+                            Value::error(simpler).ok()
+                        }
+                    }),
+            );
+        }
+        _ => {}
+    }
+    candidates.retain(|candidate| candidate != value);
+    candidates.dedup();
+    candidates
+}
+
+/// Runs call sequences against a database, discarding the writes of each
+///  sequence before the next one runs
+pub struct FuzzSession<'a> {
+    db: Option<ClarityDatabase<'a>>,
+    mainnet: bool,
+    chain_id: u32,
+    epoch: StacksEpochId,
+}
+
+impl<'a> FuzzSession<'a> {
+    pub fn new(
+        db: ClarityDatabase<'a>,
+        mainnet: bool,
+        chain_id: u32,
+        epoch: StacksEpochId,
+    ) -> FuzzSession<'a> {
+        FuzzSession {
+            db: Some(db),
+            mainnet,
+            chain_id,
+            epoch,
+        }
+    }
+
+    fn check_calls(
+        env: &mut OwnedEnvironment,
+        target: &FuzzTarget,
+        calls: &[Call],
+    ) -> Option<Violation> {
+        for (index, call) in calls.iter().enumerate() {
+            let args: Vec<_> = call
+                .args
+                .iter()
+                .cloned()
+                .map(SymbolicExpression::atom_value)
+                .collect();
+            // calls which abort are part of the sequence, as they would be on chain
+            let _ = env.execute_transaction(
+                call.sender.clone(),
+                None,
+                target.contract.clone(),
+                &call.function,
+                &args,
+            );
+            for invariant in target.invariants.iter() {
+                let result = env.execute_in_env(call.sender.clone(), None, None, |env| {
+                    env.execute_contract(&target.contract, invariant, &[], true)
+                });
+                let outcome = match result {
+                    Ok((Value::Bool(true), ..)) => continue,
+                    Ok((value, ..)) => format!("returned {value}"),
+                    Err(e) => format!("failed: {e}"),
+                };
+                return Some(Violation {
+                    after_call: index,
+                    invariant: invariant.clone(),
+                    outcome,
+                });
+            }
+        }
+        None
+    }
+
+    /// Run `calls`, checking every invariant after each one, and return the
+    ///  first invariant which did not hold
+    pub fn run_calls(
+        &mut self,
+        target: &FuzzTarget,
+        calls: &[Call],
+    ) -> Result<Option<Violation>, Error> {
+        let mut db = self
+            .db
+            .take()
+            .ok_or_else(|| InterpreterError::Expect("Fuzz session lost its database".into()))?;
+        db.begin();
+        let mut env = OwnedEnvironment::new_free(self.mainnet, self.chain_id, db, self.epoch);
+        let violation = Self::check_calls(&mut env, target, calls);
+        let (mut db, _) = env.destruct().ok_or_else(|| {
+            InterpreterError::Expect("Fuzzed environment was left with uncommitted data".into())
+        })?;
+        db.roll_back()?;
+        self.db = Some(db);
+        Ok(violation)
+    }
+}
+
+/// Shrink a failing sequence, keeping only changes which break the same invariant
+fn shrink<F>(
+    mut calls: Vec<Call>,
+    mut violation: Violation,
+    max_runs: u32,
+    run: &mut F,
+) -> Result<Counterexample, String>
+where
+    F: FnMut(&[Call]) -> Result<Option<Violation>, Error>,
+{
+    let original_calls = calls.len();
+    let mut runs = 0;
+    calls.truncate(violation.after_call + 1);
+
+    'shrinking: while runs < max_runs {
+        let mut candidates = vec![];
+        for index in 0..calls.len() {
+            let mut candidate = calls.clone();
+            candidate.remove(index);
+            candidates.push(candidate);
+        }
+        for (index, call) in calls.iter().enumerate() {
+            for (arg_index, arg) in call.args.iter().enumerate() {
+                for simpler in shrink_value(arg) {
+                    let mut candidate = calls.clone();
+                    candidate[index].args[arg_index] = simpler;
+                    candidates.push(candidate);
+                }
+            }
+        }
+
+        for candidate in candidates {
+            if runs >= max_runs {
+                break;
+            }
+            runs += 1;
+            match run(&candidate).map_err(|e| e.to_string())? {
+                Some(candidate_violation)
+                    if candidate_violation.invariant == violation.invariant =>
+                {
+                    calls = candidate;
+                    calls.truncate(candidate_violation.after_call + 1);
+                    violation = candidate_violation;
+                    continue 'shrinking;
+                }
+                _ => {}
+            }
+        }
+        // no candidate breaks the invariant: the sequence is minimal
+        break;
+    }
+
+    Ok(Counterexample {
+        calls,
+        violation,
+        original_calls,
+        shrink_runs: runs,
+    })
+}
+
+/// Run random call sequences with `run`, until one breaks an invariant or
+///  `config.runs` sequences have passed. A failing sequence is shrunk.
+pub fn fuzz<F>(
+    target: &FuzzTarget,
+    config: &FuzzConfig,
+    epoch: StacksEpochId,
+    mut run: F,
+) -> Result<FuzzReport, String>
+where
+    F: FnMut(&[Call]) -> Result<Option<Violation>, Error>,
+{
+    if config.senders.is_empty() {
+        return Err("no senders to call the contract from".into());
+    }
+    let mut principals = config.senders.clone();
+    principals.push(PrincipalData::Contract(target.contract.clone()));
+    let mut generator =
+        ValueGenerator::new(ChaCha8Rng::seed_from_u64(config.seed), principals, epoch);
+
+    let mut report = FuzzReport {
+        runs: 0,
+        calls: 0,
+        counterexample: None,
+    };
+    for _ in 0..config.runs {
+        let length = generator.rng.gen_range(1..=config.max_calls.max(1));
+        let calls = (0..length)
+            .map(|_| {
+                let index = generator.rng.gen_range(0..target.functions.len());
+                generator.call(&target.functions[index], &config.senders)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        report.runs += 1;
+        report.calls += calls.len() as u64;
+        if let Some(violation) = run(&calls).map_err(|e| e.to_string())? {
+            report.counterexample =
+                Some(shrink(calls, violation, config.max_shrink_runs, &mut run)?);
+            break;
+        }
+    }
+    Ok(report)
+}
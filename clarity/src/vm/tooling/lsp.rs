@@ -0,0 +1,797 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A minimal Language Server Protocol implementation for Clarity.
+//!
+//! The server speaks JSON-RPC over stdio and is driven by the same parser and
+//! analysis passes that the node uses, so the diagnostics shown in an editor
+//! are exactly the ones a deploy would produce. Expressions are placed in the
+//! document with a `SpanLocator`, so this works whether or not the build
+//! tracks expression spans.
+
+use std::io::{self, BufRead, Write};
+
+use hashbrown::HashMap;
+use serde_json::{json, Value as JsonValue};
+use stacks_common::types::StacksEpochId;
+
+use crate::vm::analysis::{run_analysis_with_diagnostics, ContractAnalysis};
+use crate::vm::ast::build_ast_with_diagnostics;
+use crate::vm::ast::span_locator::SpanLocator;
+use crate::vm::database::MemoryBackingStore;
+use crate::vm::diagnostic::{Diagnostic, Level};
+use crate::vm::docs::{make_api_reference, make_define_reference, make_keyword_reference};
+use crate::vm::functions::define::{DefineFunctions, DefineFunctionsParsed};
+use crate::vm::functions::NativeFunctions;
+use crate::vm::representations::{Span, SymbolicExpression};
+use crate::vm::types::{FunctionType, QualifiedContractIdentifier};
+use crate::vm::variables::NativeVariables;
+use crate::vm::ClarityVersion;
+
+/// JSON-RPC error code for an unknown method
+const METHOD_NOT_FOUND: i64 = -32601;
+/// JSON-RPC error code for malformed request parameters
+const INVALID_PARAMS: i64 = -32602;
+
+/// LSP `CompletionItemKind` values used by this server
+const COMPLETION_KIND_FUNCTION: u32 = 3;
+const COMPLETION_KIND_VARIABLE: u32 = 6;
+const COMPLETION_KIND_KEYWORD: u32 = 14;
+
+/// The result of running the parser and the analysis passes over a contract.
+pub struct CheckedContract {
+    /// The (possibly partial) AST of the contract. Present even if parsing failed.
+    pub expressions: Vec<SymbolicExpression>,
+    /// The analysis, if the contract parsed. Carries a populated `type_map`, and
+    /// is incomplete if any pass reported an error.
+    pub analysis: Option<ContractAnalysis>,
+    pub diagnostics: Vec<Diagnostic>,
+    /// Where the expressions are in the source
+    pub spans: SpanLocator,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CompletionItem {
+    pub label: String,
+    pub kind: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub documentation: Option<String>,
+}
+
+/// A top-level `define-*` form found in a contract
+struct Definition<'a> {
+    name: String,
+    kind: DefineFunctions,
+    expr: &'a SymbolicExpression,
+}
+
+/// Parse and analyze `source` with the type checker, trait checker, read-only
+/// checker and lint checker, collecting every diagnostic they produce.
+pub fn check_contract(
+    source: &str,
+    clarity_version: ClarityVersion,
+    epoch: StacksEpochId,
+) -> CheckedContract {
+    let contract_identifier = QualifiedContractIdentifier::transient();
+    let (ast, mut diagnostics, success) = build_ast_with_diagnostics(
+        &contract_identifier,
+        source,
+        &mut (),
+        clarity_version,
+        epoch,
+    );
+    let expressions = ast.expressions;
+    let spans = SpanLocator::new(source, &expressions);
+    if !success {
+        return CheckedContract {
+            expressions,
+            analysis: None,
+            diagnostics,
+            spans,
+        };
+    }
+
+    let mut marf = MemoryBackingStore::new();
+    let (analysis, analysis_diagnostics) = run_analysis_with_diagnostics(
+        &contract_identifier,
+        &expressions,
+        &mut marf.as_analysis_db(),
+        epoch,
+        clarity_version,
+        &spans,
+    );
+    diagnostics.extend(analysis_diagnostics);
+
+    CheckedContract {
+        expressions,
+        analysis: Some(analysis),
+        diagnostics,
+        spans,
+    }
+}
+
+/// Does `span` cover the 1-based `line` and `column`? Spans are inclusive.
+fn span_contains(span: &Span, line: u32, column: u32) -> bool {
+    if *span == Span::zero() {
+        return false;
+    }
+    (span.start_line, span.start_column) <= (line, column)
+        && (line, column) <= (span.end_line, span.end_column)
+}
+
+/// Find the innermost expression at `line`/`column`, along with its enclosing
+/// expressions (outermost first).
+fn find_expression_path(
+    contract: &CheckedContract,
+    line: u32,
+    column: u32,
+) -> Vec<&SymbolicExpression> {
+    let mut path = vec![];
+    let mut frontier = &contract.expressions[..];
+    while let Some(expr) = frontier
+        .iter()
+        .find(|expr| span_contains(&contract.spans.span(expr), line, column))
+    {
+        path.push(expr);
+        frontier = match expr.match_list() {
+            Some(children) => children,
+            None => break,
+        };
+    }
+    path
+}
+
+fn collect_definitions(exprs: &[SymbolicExpression]) -> Vec<Definition<'_>> {
+    let mut definitions = vec![];
+    for expr in exprs.iter() {
+        let Some(define_type) = expr
+            .match_list()
+            .and_then(|list| list.first())
+            .and_then(|head| head.match_atom())
+            .and_then(|name| DefineFunctions::lookup_by_name(name))
+        else {
+            continue;
+        };
+        let name = match DefineFunctionsParsed::try_parse(expr).ok().flatten() {
+            Some(DefineFunctionsParsed::Constant { name, .. })
+            | Some(DefineFunctionsParsed::NonFungibleToken { name, .. })
+            | Some(DefineFunctionsParsed::BoundedFungibleToken { name, .. })
+            | Some(DefineFunctionsParsed::UnboundedFungibleToken { name })
+            | Some(DefineFunctionsParsed::Map { name, .. })
+            | Some(DefineFunctionsParsed::PersistedVariable { name, .. })
+            | Some(DefineFunctionsParsed::Trait { name, .. })
+            | Some(DefineFunctionsParsed::UseTrait { name, .. }) => name.to_string(),
+            Some(DefineFunctionsParsed::PrivateFunction { signature, .. })
+            | Some(DefineFunctionsParsed::ReadOnlyFunction { signature, .. })
+            | Some(DefineFunctionsParsed::PublicFunction { signature, .. }) => {
+                match signature.first().and_then(|name| name.match_atom()) {
+                    Some(name) => name.to_string(),
+                    None => continue,
+                }
+            }
+            Some(DefineFunctionsParsed::ImplTrait { .. }) | None => continue,
+        };
+        definitions.push(Definition {
+            name,
+            kind: define_type,
+            expr,
+        });
+    }
+    definitions
+}
+
+fn format_function_type(name: &str, function_type: &FunctionType) -> String {
+    match function_type {
+        FunctionType::Fixed(function) => {
+            let args: Vec<_> = function
+                .args
+                .iter()
+                .map(|arg| format!("({} {})", arg.name, arg.signature))
+                .collect();
+            if args.is_empty() {
+                format!("({name}) -> {}", function.returns)
+            } else {
+                format!("({name} {}) -> {}", args.join(" "), function.returns)
+            }
+        }
+        other => format!("{name}: {other:?}"),
+    }
+}
+
+/// Describe a user-defined name from the contract analysis
+fn describe_definition(analysis: &ContractAnalysis, name: &str) -> Option<String> {
+    if let Some(function_type) = analysis
+        .public_function_types
+        .iter()
+        .find(|(fn_name, _)| fn_name.as_str() == name)
+        .map(|(_, t)| t)
+    {
+        return Some(format!(
+            "public function\n\n`{}`",
+            format_function_type(name, function_type)
+        ));
+    }
+    if let Some(function_type) = analysis
+        .read_only_function_types
+        .iter()
+        .find(|(fn_name, _)| fn_name.as_str() == name)
+        .map(|(_, t)| t)
+    {
+        return Some(format!(
+            "read-only function\n\n`{}`",
+            format_function_type(name, function_type)
+        ));
+    }
+    if let Some(function_type) = analysis
+        .private_function_types
+        .iter()
+        .find(|(fn_name, _)| fn_name.as_str() == name)
+        .map(|(_, t)| t)
+    {
+        return Some(format!(
+            "private function\n\n`{}`",
+            format_function_type(name, function_type)
+        ));
+    }
+    if let Some((_, (key_type, value_type))) = analysis
+        .map_types
+        .iter()
+        .find(|(map_name, _)| map_name.as_str() == name)
+    {
+        return Some(format!("map `{name}`: `{key_type}` -> `{value_type}`"));
+    }
+    if let Some((_, data_type)) = analysis
+        .persisted_variable_types
+        .iter()
+        .find(|(var_name, _)| var_name.as_str() == name)
+    {
+        return Some(format!("data-var `{name}`: `{data_type}`"));
+    }
+    if let Some((_, data_type)) = analysis
+        .variable_types
+        .iter()
+        .find(|(var_name, _)| var_name.as_str() == name)
+    {
+        return Some(format!("constant `{name}`: `{data_type}`"));
+    }
+    if let Some((_, asset_type)) = analysis
+        .non_fungible_tokens
+        .iter()
+        .find(|(token_name, _)| token_name.as_str() == name)
+    {
+        return Some(format!("non-fungible token `{name}`: `{asset_type}`"));
+    }
+    if analysis
+        .fungible_tokens
+        .iter()
+        .any(|token_name| token_name.as_str() == name)
+    {
+        return Some(format!("fungible token `{name}`"));
+    }
+    None
+}
+
+/// Produce hover text for the 1-based `line` and `column` of a checked contract.
+/// Native functions and keywords are described from the API reference,
+/// user definitions from the contract analysis, and any other expression by
+/// the type inferred for it in `ContractAnalysis.type_map`.
+pub fn hover(
+    contract: &CheckedContract,
+    clarity_version: ClarityVersion,
+    line: u32,
+    column: u32,
+) -> Option<String> {
+    let path = find_expression_path(contract, line, column);
+    let expr = *path.last()?;
+
+    if let Some(name) = expr.match_atom() {
+        if let Some(function) = NativeFunctions::lookup_by_name_at_version(name, &clarity_version) {
+            let api = make_api_reference(&function);
+            return Some(format!(
+                "`{}`\n\n{}\n\n{}",
+                api.signature, api.output_type, api.description
+            ));
+        }
+        if let Some(define_type) = DefineFunctions::lookup_by_name(name) {
+            let api = make_define_reference(&define_type);
+            return Some(format!("`{}`\n\n{}", api.signature, api.description));
+        }
+        if let Some(keyword) = NativeVariables::lookup_by_name_at_version(name, &clarity_version)
+            .and_then(|variable| make_keyword_reference(&variable))
+        {
+            return Some(format!(
+                "`{}`: `{}`\n\n{}",
+                keyword.name, keyword.output_type, keyword.description
+            ));
+        }
+    }
+
+    let analysis = contract.analysis.as_ref()?;
+    if let Some(name) = expr.match_atom() {
+        if let Some(description) = describe_definition(analysis, name) {
+            return Some(description);
+        }
+    }
+
+    analysis
+        .type_map
+        .as_ref()?
+        .get_type_expected(expr)
+        .map(|type_sig| format!("`{type_sig}`"))
+}
+
+/// Find the `define-*` form that declares the name at the 1-based `line` and `column`.
+pub fn definition(contract: &CheckedContract, line: u32, column: u32) -> Option<Span> {
+    let path = find_expression_path(contract, line, column);
+    let name = path.last()?.match_atom()?;
+    collect_definitions(&contract.expressions)
+        .into_iter()
+        .find(|definition| definition.name == name.as_str())
+        .map(|definition| contract.spans.span(definition.expr))
+}
+
+/// List the native functions, keywords and `define-*` forms available in
+/// `clarity_version`, followed by the names the contract itself defines.
+pub fn completions(
+    contract: Option<&CheckedContract>,
+    clarity_version: ClarityVersion,
+) -> Vec<CompletionItem> {
+    let mut items = vec![];
+    for function in NativeFunctions::ALL.iter() {
+        if NativeFunctions::lookup_by_name_at_version(function.get_name_str(), &clarity_version)
+            .is_none()
+        {
+            continue;
+        }
+        let api = make_api_reference(function);
+        items.push(CompletionItem {
+            label: api.name,
+            kind: COMPLETION_KIND_FUNCTION,
+            detail: Some(api.signature),
+            documentation: Some(api.description),
+        });
+    }
+    for variable in NativeVariables::ALL.iter() {
+        if NativeVariables::lookup_by_name_at_version(variable.get_name_str(), &clarity_version)
+            .is_none()
+        {
+            continue;
+        }
+        if let Some(keyword) = make_keyword_reference(variable) {
+            items.push(CompletionItem {
+                label: keyword.name.to_string(),
+                kind: COMPLETION_KIND_VARIABLE,
+                detail: Some(keyword.output_type.to_string()),
+                documentation: Some(keyword.description.to_string()),
+            });
+        }
+    }
+    for define_type in DefineFunctions::ALL.iter() {
+        let api = make_define_reference(define_type);
+        items.push(CompletionItem {
+            label: api.name,
+            kind: COMPLETION_KIND_KEYWORD,
+            detail: Some(api.signature),
+            documentation: Some(api.description),
+        });
+    }
+    if let Some(contract) = contract {
+        for definition in collect_definitions(&contract.expressions) {
+            let kind = match definition.kind {
+                DefineFunctions::PrivateFunction
+                | DefineFunctions::PublicFunction
+                | DefineFunctions::ReadOnlyFunction => COMPLETION_KIND_FUNCTION,
+                _ => COMPLETION_KIND_VARIABLE,
+            };
+            let detail = contract
+                .analysis
+                .as_ref()
+                .and_then(|analysis| describe_definition(analysis, &definition.name));
+            items.push(CompletionItem {
+                label: definition.name,
+                kind,
+                detail,
+                documentation: None,
+            });
+        }
+    }
+    items
+}
+
+/// Convert an inclusive, 1-based Clarity span into an LSP range
+fn span_to_range(span: &Span) -> JsonValue {
+    json!({
+        "start": {
+            "line": span.start_line.saturating_sub(1),
+            "character": span.start_column.saturating_sub(1),
+        },
+        "end": {
+            "line": span.end_line.saturating_sub(1),
+            "character": span.end_column,
+        },
+    })
+}
+
+fn diagnostic_to_json(diagnostic: &Diagnostic) -> JsonValue {
+    let severity = match diagnostic.level {
+        Level::Error => 1,
+        Level::Warning => 2,
+        Level::Note => 3,
+    };
+    let range = diagnostic
+        .spans
+        .first()
+        .map(span_to_range)
+        .unwrap_or_else(|| span_to_range(&Span::zero()));
+    let message = match &diagnostic.suggestion {
+        Some(suggestion) => format!("{}\n{}", diagnostic.message, suggestion),
+        None => diagnostic.message.clone(),
+    };
+    json!({
+        "range": range,
+        "severity": severity,
+        "source": "clarity",
+        "message": message,
+    })
+}
+
+/// Read one `Content-Length` framed JSON-RPC message. Returns `Ok(None)` at EOF.
+pub fn read_message<R: BufRead>(input: &mut R) -> io::Result<Option<JsonValue>> {
+    let mut content_length = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(len) = header.strip_prefix("Content-Length:") {
+            content_length = Some(
+                len.trim()
+                    .parse::<usize>()
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{e}")))?,
+            );
+        }
+    }
+    let content_length = content_length.ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidData, "missing Content-Length header")
+    })?;
+    let mut body = vec![0u8; content_length];
+    input.read_exact(&mut body)?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Write one `Content-Length` framed JSON-RPC message
+pub fn write_message<W: Write>(output: &mut W, message: &JsonValue) -> io::Result<()> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    output.flush()
+}
+
+/// LSP position (0-based) to Clarity line and column (1-based)
+fn position_from_params(params: &JsonValue) -> Option<(u32, u32)> {
+    let position = params.get("position")?;
+    let line = u32::try_from(position.get("line")?.as_u64()?).ok()?;
+    let character = u32::try_from(position.get("character")?.as_u64()?).ok()?;
+    Some((line + 1, character + 1))
+}
+
+fn document_uri(params: &JsonValue) -> Option<&str> {
+    params.get("textDocument")?.get("uri")?.as_str()
+}
+
+pub struct LanguageServer {
+    clarity_version: ClarityVersion,
+    epoch: StacksEpochId,
+    documents: HashMap<String, CheckedContract>,
+    shutdown_requested: bool,
+    exited: bool,
+}
+
+impl LanguageServer {
+    pub fn new(clarity_version: ClarityVersion, epoch: StacksEpochId) -> LanguageServer {
+        LanguageServer {
+            clarity_version,
+            epoch,
+            documents: HashMap::new(),
+            shutdown_requested: false,
+            exited: false,
+        }
+    }
+
+    /// Serve requests read from `input` until the client sends `exit` or closes the stream.
+    pub fn run<R: BufRead, W: Write>(&mut self, input: &mut R, output: &mut W) -> io::Result<()> {
+        while !self.exited {
+            let Some(message) = read_message(input)? else {
+                break;
+            };
+            for reply in self.handle_message(&message) {
+                write_message(output, &reply)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn has_exited(&self) -> bool {
+        self.exited
+    }
+
+    /// Was `shutdown` received before `exit`? Clients use this to pick an exit code.
+    pub fn shutdown_requested(&self) -> bool {
+        self.shutdown_requested
+    }
+
+    /// Handle a single JSON-RPC message, returning the responses and
+    /// notifications to send back to the client.
+    pub fn handle_message(&mut self, message: &JsonValue) -> Vec<JsonValue> {
+        let Some(method) = message.get("method").and_then(|m| m.as_str()) else {
+            // a response to a server-initiated request; we never send any
+            return vec![];
+        };
+        let params = message.get("params").cloned().unwrap_or(JsonValue::Null);
+        let id = message.get("id").cloned();
+
+        let result = match method {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    // full document sync
+                    "textDocumentSync": 1,
+                    "hoverProvider": true,
+                    "definitionProvider": true,
+                    "completionProvider": {},
+                },
+                "serverInfo": { "name": "clarity-lsp" },
+            })),
+            "shutdown" => {
+                self.shutdown_requested = true;
+                Ok(JsonValue::Null)
+            }
+            "exit" => {
+                self.exited = true;
+                return vec![];
+            }
+            "textDocument/didOpen" => {
+                let uri = document_uri(&params);
+                let text = params
+                    .get("textDocument")
+                    .and_then(|doc| doc.get("text"))
+                    .and_then(|text| text.as_str());
+                return match (uri, text) {
+                    (Some(uri), Some(text)) => vec![self.update_document(uri, text)],
+                    _ => vec![],
+                };
+            }
+            "textDocument/didChange" => {
+                let uri = document_uri(&params);
+                // full sync: the last change carries the whole document
+                let text = params
+                    .get("contentChanges")
+                    .and_then(|changes| changes.as_array())
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change.get("text"))
+                    .and_then(|text| text.as_str());
+                return match (uri, text) {
+                    (Some(uri), Some(text)) => vec![self.update_document(uri, text)],
+                    _ => vec![],
+                };
+            }
+            "textDocument/didClose" => {
+                let Some(uri) = document_uri(&params) else {
+                    return vec![];
+                };
+                self.documents.remove(uri);
+                return vec![json!({
+                    "jsonrpc": "2.0",
+                    "method": "textDocument/publishDiagnostics",
+                    "params": { "uri": uri, "diagnostics": [] },
+                })];
+            }
+            "textDocument/hover" => self.handle_hover(&params),
+            "textDocument/definition" => self.handle_definition(&params),
+            "textDocument/completion" => {
+                let contract = document_uri(&params).and_then(|uri| self.documents.get(uri));
+                Ok(
+                    serde_json::to_value(completions(contract, self.clarity_version))
+                        .unwrap_or(JsonValue::Null),
+                )
+            }
+            _ => Err((METHOD_NOT_FOUND, format!("Unhandled method: {method}"))),
+        };
+
+        // notifications never get a reply
+        let Some(id) = id else {
+            return vec![];
+        };
+        let reply = match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": code, "message": message },
+            }),
+        };
+        vec![reply]
+    }
+
+    /// Re-check a document and build its `publishDiagnostics` notification
+    fn update_document(&mut self, uri: &str, text: &str) -> JsonValue {
+        let contract = check_contract(text, self.clarity_version, self.epoch);
+        let diagnostics: Vec<_> = contract
+            .diagnostics
+            .iter()
+            .map(diagnostic_to_json)
+            .collect();
+        self.documents.insert(uri.to_string(), contract);
+        json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": { "uri": uri, "diagnostics": diagnostics },
+        })
+    }
+
+    fn handle_hover(&self, params: &JsonValue) -> Result<JsonValue, (i64, String)> {
+        let (uri, (line, column)) = document_uri(params)
+            .zip(position_from_params(params))
+            .ok_or_else(|| (INVALID_PARAMS, "Expected textDocument and position".into()))?;
+        let Some(contract) = self.documents.get(uri) else {
+            return Ok(JsonValue::Null);
+        };
+        Ok(match hover(contract, self.clarity_version, line, column) {
+            Some(text) => json!({ "contents": { "kind": "markdown", "value": text } }),
+            None => JsonValue::Null,
+        })
+    }
+
+    fn handle_definition(&self, params: &JsonValue) -> Result<JsonValue, (i64, String)> {
+        let (uri, (line, column)) = document_uri(params)
+            .zip(position_from_params(params))
+            .ok_or_else(|| (INVALID_PARAMS, "Expected textDocument and position".into()))?;
+        let Some(contract) = self.documents.get(uri) else {
+            return Ok(JsonValue::Null);
+        };
+        Ok(match definition(contract, line, column) {
+            Some(span) => json!({ "uri": uri, "range": span_to_range(&span) }),
+            None => JsonValue::Null,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTRACT: &str = "(define-data-var counter int 0)
+(define-public (increment (by int))
+  (begin
+    (var-set counter (+ (var-get counter) by))
+    (ok (var-get counter))))
+";
+
+    fn server() -> LanguageServer {
+        LanguageServer::new(ClarityVersion::Clarity2, StacksEpochId::Epoch25)
+    }
+
+    fn open(server: &mut LanguageServer, text: &str) -> JsonValue {
+        let mut replies = server.handle_message(&json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": {
+                "textDocument": { "uri": "file:///test.clar", "languageId": "clarity", "version": 1, "text": text }
+            }
+        }));
+        assert_eq!(replies.len(), 1);
+        replies.pop().unwrap()
+    }
+
+    #[test]
+    fn test_initialize_and_shutdown() {
+        let mut server = server();
+        let replies = server.handle_message(
+            &json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }),
+        );
+        assert_eq!(replies[0]["id"], 1);
+        assert_eq!(replies[0]["result"]["capabilities"]["hoverProvider"], true);
+
+        let replies =
+            server.handle_message(&json!({ "jsonrpc": "2.0", "id": 2, "method": "bogus" }));
+        assert_eq!(replies[0]["error"]["code"], METHOD_NOT_FOUND);
+
+        server.handle_message(&json!({ "jsonrpc": "2.0", "id": 3, "method": "shutdown" }));
+        assert!(server.shutdown_requested());
+        assert!(server
+            .handle_message(&json!({ "jsonrpc": "2.0", "method": "exit" }))
+            .is_empty());
+        assert!(server.has_exited());
+    }
+
+    #[test]
+    fn test_publish_diagnostics() {
+        let mut server = server();
+        let notification = open(&mut server, CONTRACT);
+        assert_eq!(notification["method"], "textDocument/publishDiagnostics");
+        assert!(notification["params"]["diagnostics"]
+            .as_array()
+            .unwrap()
+            .is_empty());
+
+        let notification = open(&mut server, "(define-public (foo) (ok (+ 1 u1)))");
+        let diagnostics = notification["params"]["diagnostics"].as_array().unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0]["severity"], 1);
+        assert!(diagnostics[0]["message"]
+            .as_str()
+            .unwrap()
+            .contains("expecting expression of type"));
+
+        let notification = open(&mut server, "(define-public (foo) (ok 1)");
+        let diagnostics = notification["params"]["diagnostics"].as_array().unwrap();
+        assert!(!diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_completions_gated_by_version() {
+        let items = completions(None, ClarityVersion::Clarity1);
+        assert!(items.iter().any(|item| item.label == "map-get?"));
+        assert!(items.iter().any(|item| item.label == "define-public"));
+        assert!(!items.iter().any(|item| item.label == "stx-transfer-memo?"));
+        assert!(!items.iter().any(|item| item.label == "tenure-height"));
+
+        let items = completions(None, ClarityVersion::Clarity3);
+        assert!(items.iter().any(|item| item.label == "stx-transfer-memo?"));
+        assert!(items.iter().any(|item| item.label == "tenure-height"));
+        assert!(!items.iter().any(|item| item.label == "block-height"));
+
+        let contract = check_contract(CONTRACT, ClarityVersion::Clarity2, StacksEpochId::Epoch25);
+        let items = completions(Some(&contract), ClarityVersion::Clarity2);
+        let increment = items.iter().find(|item| item.label == "increment").unwrap();
+        assert_eq!(increment.kind, COMPLETION_KIND_FUNCTION);
+        assert!(increment.detail.as_ref().unwrap().contains("(by int)"));
+        assert!(items.iter().any(|item| item.label == "counter"));
+    }
+
+    #[test]
+    fn test_read_write_message() {
+        let message = json!({ "jsonrpc": "2.0", "id": 1, "method": "shutdown" });
+        let mut buffer = vec![];
+        write_message(&mut buffer, &message).unwrap();
+        let mut input = io::Cursor::new(buffer);
+        assert_eq!(read_message(&mut input).unwrap(), Some(message));
+        assert_eq!(read_message(&mut input).unwrap(), None);
+    }
+
+    #[test]
+    fn test_hover_and_definition() {
+        let contract = check_contract(CONTRACT, ClarityVersion::Clarity2, StacksEpochId::Epoch25);
+
+        // `var-get` on line 4
+        let text = hover(&contract, ClarityVersion::Clarity2, 4, 26).unwrap();
+        assert!(text.contains("var-get"));
+
+        // `by` on line 4
+        let text = hover(&contract, ClarityVersion::Clarity2, 4, 43).unwrap();
+        assert_eq!(text, "`int`");
+
+        // `counter` on line 4 jumps to the `define-data-var` on line 1
+        let span = definition(&contract, 4, 15).unwrap();
+        assert_eq!(span.start_line, 1);
+        assert_eq!(span.start_column, 1);
+    }
+}
@@ -1,3 +1,10 @@
+pub mod bindings;
+pub mod formatter;
+pub mod fuzz;
+pub mod lsp;
+pub mod upgrade;
+pub mod verify;
+
 use stacks_common::types::StacksEpochId;
 
 use super::analysis::ContractAnalysis;
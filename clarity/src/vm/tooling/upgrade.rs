@@ -0,0 +1,350 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Compares two versions of a contract, reporting the changes which could
+//! break its callers.
+//!
+//! A changed function signature is compatible if every argument type of the
+//! new version admits the old one, and the old return type admits the new
+//! one: every call which type checked against the old version then type
+//! checks against the new one.
+
+use std::collections::BTreeMap;
+
+use stacks_common::types::StacksEpochId;
+
+use super::mem_type_check;
+use crate::vm::analysis::contract_interface_builder::ContractInterfaceFunctionAccess;
+use crate::vm::analysis::ContractAnalysis;
+use crate::vm::types::{FunctionSignature, FunctionType, TraitIdentifier, TypeSignature};
+use crate::vm::{ClarityName, ClarityVersion};
+
+const SIP_010_TRAIT: &str = "(define-trait sip-010-trait
+  (
+    (transfer (uint principal principal (optional (buff 34))) (response bool uint))
+    (get-name () (response (string-ascii 32) uint))
+    (get-symbol () (response (string-ascii 32) uint))
+    (get-decimals () (response uint uint))
+    (get-balance (principal) (response uint uint))
+    (get-total-supply () (response uint uint))
+    (get-token-uri () (response (optional (string-utf8 256)) uint))
+  )
+)";
+
+const SIP_009_TRAIT: &str = "(define-trait nft-trait
+  (
+    (get-last-token-id () (response uint uint))
+    (get-token-uri (uint) (response (optional (string-ascii 256)) uint))
+    (get-owner (uint) (response (optional principal) uint))
+    (transfer (uint principal principal) (response bool uint))
+  )
+)";
+
+/// The token standards whose conformance is compared: the standard's name,
+///  its mainnet trait, and the trait's definition
+const STANDARDS: [(&str, &str, &str); 2] = [
+    (
+        "SIP-010",
+        "SP3FBR2AGK5H9QBDH3EEN6DF8EK8JY7RX8QJ5SVTE.sip-010-trait-ft-standard.sip-010-trait",
+        SIP_010_TRAIT,
+    ),
+    (
+        "SIP-009",
+        "SP2PABAF9FTAJYNFZH93XENAJ8FVY99RRM50D2JG9.nft-trait.nft-trait",
+        SIP_009_TRAIT,
+    ),
+];
+
+/// A function's argument and return types
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Signature {
+    pub args: Vec<String>,
+    pub returns: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MapType {
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Change {
+    FunctionAdded {
+        name: String,
+        access: ContractInterfaceFunctionAccess,
+    },
+    FunctionRemoved {
+        name: String,
+        access: ContractInterfaceFunctionAccess,
+    },
+    /// A function moved between public and read-only
+    FunctionAccessChanged {
+        name: String,
+        old: ContractInterfaceFunctionAccess,
+        new: ContractInterfaceFunctionAccess,
+    },
+    FunctionSignatureChanged {
+        name: String,
+        access: ContractInterfaceFunctionAccess,
+        old: Signature,
+        new: Signature,
+        /// Every call to the old signature is a valid call to the new one
+        compatible: bool,
+    },
+    MapAdded {
+        name: String,
+    },
+    MapRemoved {
+        name: String,
+    },
+    MapTypeChanged {
+        name: String,
+        old: MapType,
+        new: MapType,
+    },
+    TraitImplementationAdded {
+        trait_identifier: String,
+    },
+    TraitImplementationDropped {
+        trait_identifier: String,
+    },
+    /// The contract started or stopped conforming to a token standard
+    ConformanceChanged {
+        standard: String,
+        old: bool,
+        new: bool,
+    },
+}
+
+impl Change {
+    /// Whether the change can break callers of the old version
+    pub fn is_breaking(&self) -> bool {
+        match self {
+            Change::FunctionAdded { .. }
+            | Change::MapAdded { .. }
+            | Change::TraitImplementationAdded { .. } => false,
+            Change::FunctionRemoved { .. }
+            | Change::FunctionAccessChanged { .. }
+            | Change::MapRemoved { .. }
+            | Change::MapTypeChanged { .. }
+            | Change::TraitImplementationDropped { .. } => true,
+            Change::FunctionSignatureChanged { compatible, .. } => !compatible,
+            Change::ConformanceChanged { old, new, .. } => *old && !*new,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReportedChange {
+    pub breaking: bool,
+    #[serde(flatten)]
+    pub change: Change,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UpgradeReport {
+    pub breaking: bool,
+    pub changes: Vec<ReportedChange>,
+}
+
+impl UpgradeReport {
+    fn new(changes: Vec<Change>) -> UpgradeReport {
+        let changes: Vec<_> = changes
+            .into_iter()
+            .map(|change| ReportedChange {
+                breaking: change.is_breaking(),
+                change,
+            })
+            .collect();
+        UpgradeReport {
+            breaking: changes.iter().any(|change| change.breaking),
+            changes,
+        }
+    }
+}
+
+fn signature(function: &FunctionType) -> Signature {
+    match function {
+        FunctionType::Fixed(fixed) => Signature {
+            args: fixed
+                .args
+                .iter()
+                .map(|arg| arg.signature.to_string())
+                .collect(),
+            returns: fixed.returns.to_string(),
+        },
+        // only native functions have other function types
+        other => Signature {
+            args: vec![],
+            returns: format!("{other:?}"),
+        },
+    }
+}
+
+/// Whether every call to `old` is a valid call to `new`
+fn is_compatible(epoch: &StacksEpochId, old: &FunctionType, new: &FunctionType) -> bool {
+    let (FunctionType::Fixed(old), FunctionType::Fixed(new)) = (old, new) else {
+        return old == new;
+    };
+    let admits = |wider: &TypeSignature, narrower: &TypeSignature| {
+        wider.admits_type(epoch, narrower).unwrap_or(false)
+    };
+    old.args.len() == new.args.len()
+        && old
+            .args
+            .iter()
+            .zip(new.args.iter())
+            .all(|(old_arg, new_arg)| admits(&new_arg.signature, &old_arg.signature))
+        && admits(&old.returns, &new.returns)
+}
+
+/// The callable functions of a contract
+fn functions(
+    analysis: &ContractAnalysis,
+) -> BTreeMap<&ClarityName, (ContractInterfaceFunctionAccess, &FunctionType)> {
+    let public = analysis
+        .public_function_types
+        .iter()
+        .map(|(name, function)| (name, (ContractInterfaceFunctionAccess::public, function)));
+    let read_only = analysis
+        .read_only_function_types
+        .iter()
+        .map(|(name, function)| (name, (ContractInterfaceFunctionAccess::read_only, function)));
+    public.chain(read_only).collect()
+}
+
+fn compare_functions(old: &ContractAnalysis, new: &ContractAnalysis, changes: &mut Vec<Change>) {
+    let old_functions = functions(old);
+    let new_functions = functions(new);
+    for (name, (old_access, old_function)) in old_functions.iter() {
+        let Some((new_access, new_function)) = new_functions.get(name) else {
+            changes.push(Change::FunctionRemoved {
+                name: name.to_string(),
+                access: old_access.clone(),
+            });
+            continue;
+        };
+        if old_access != new_access {
+            changes.push(Change::FunctionAccessChanged {
+                name: name.to_string(),
+                old: old_access.clone(),
+                new: new_access.clone(),
+            });
+        }
+        let (old_signature, new_signature) = (signature(old_function), signature(new_function));
+        if old_signature != new_signature {
+            changes.push(Change::FunctionSignatureChanged {
+                name: name.to_string(),
+                access: new_access.clone(),
+                old: old_signature,
+                new: new_signature,
+                compatible: is_compatible(&new.epoch, old_function, new_function),
+            });
+        }
+    }
+    for (name, (access, _)) in new_functions.iter() {
+        if !old_functions.contains_key(name) {
+            changes.push(Change::FunctionAdded {
+                name: name.to_string(),
+                access: access.clone(),
+            });
+        }
+    }
+}
+
+fn compare_maps(old: &ContractAnalysis, new: &ContractAnalysis, changes: &mut Vec<Change>) {
+    let map_type = |(key, value): &(TypeSignature, TypeSignature)| MapType {
+        key: key.to_string(),
+        value: value.to_string(),
+    };
+    for (name, old_types) in old.map_types.iter() {
+        match new.map_types.get(name) {
+            None => changes.push(Change::MapRemoved {
+                name: name.to_string(),
+            }),
+            Some(new_types) if new_types != old_types => changes.push(Change::MapTypeChanged {
+                name: name.to_string(),
+                old: map_type(old_types),
+                new: map_type(new_types),
+            }),
+            Some(_) => {}
+        }
+    }
+    for name in new.map_types.keys() {
+        if !old.map_types.contains_key(name) {
+            changes.push(Change::MapAdded {
+                name: name.to_string(),
+            });
+        }
+    }
+}
+
+fn compare_traits(old: &ContractAnalysis, new: &ContractAnalysis, changes: &mut Vec<Change>) {
+    for dropped in old.implemented_traits.difference(&new.implemented_traits) {
+        changes.push(Change::TraitImplementationDropped {
+            trait_identifier: dropped.to_string(),
+        });
+    }
+    for added in new.implemented_traits.difference(&old.implemented_traits) {
+        changes.push(Change::TraitImplementationAdded {
+            trait_identifier: added.to_string(),
+        });
+    }
+}
+
+/// A standard's trait, and its definition
+fn standard_trait(
+    identifier: &str,
+    source: &str,
+) -> Option<(TraitIdentifier, BTreeMap<ClarityName, FunctionSignature>)> {
+    let identifier = TraitIdentifier::parse_fully_qualified(identifier).ok()?;
+    let (_, analysis) =
+        mem_type_check(source, ClarityVersion::Clarity2, StacksEpochId::Epoch21).ok()?;
+    let definition = analysis.defined_traits.get(&identifier.name)?.clone();
+    Some((identifier, definition))
+}
+
+fn compare_conformance(old: &ContractAnalysis, new: &ContractAnalysis, changes: &mut Vec<Change>) {
+    for (standard, identifier, source) in STANDARDS {
+        let Some((identifier, definition)) = standard_trait(identifier, source) else {
+            continue;
+        };
+        let conforms = |analysis: &ContractAnalysis| {
+            analysis
+                .check_trait_compliance(&analysis.epoch, &identifier, &definition)
+                .is_ok()
+        };
+        let (old_conforms, new_conforms) = (conforms(old), conforms(new));
+        if old_conforms != new_conforms {
+            changes.push(Change::ConformanceChanged {
+                standard: standard.to_string(),
+                old: old_conforms,
+                new: new_conforms,
+            });
+        }
+    }
+}
+
+/// Compare the old and new versions of a contract
+pub fn check_upgrade(old: &ContractAnalysis, new: &ContractAnalysis) -> UpgradeReport {
+    let mut changes = vec![];
+    compare_functions(old, new, &mut changes);
+    compare_maps(old, new, &mut changes);
+    compare_traits(old, new, &mut changes);
+    compare_conformance(old, new, &mut changes);
+    UpgradeReport::new(changes)
+}
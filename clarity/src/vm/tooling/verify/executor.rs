@@ -0,0 +1,1404 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Symbolic execution of a contract's functions.
+//!
+//! Each public and read-only function is run with symbols for its arguments
+//! and for the chain state it reads, forking at every branch whose condition
+//! depends on them. Private functions are inlined at their call sites. At
+//! every operation which can fail, the executor records an obligation: the
+//! path condition, conjoined with the condition under which the operation
+//! fails. The operation then fails exactly when its obligation is
+//! satisfiable.
+//!
+//! Values the executor does not model (sequences, the results of calls to
+//! other contracts, most native functions) are replaced by fresh symbols of
+//! the type the type checker inferred for them, which over-approximates the
+//! behaviour of the function: a proof is a proof for any value they take,
+//! but a counterexample may depend on the values assumed for them.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use super::solver::Solver;
+use super::term::{ArithOp, CompareOp, Concrete, Sort, Term};
+use super::{source, Property};
+use crate::vm::analysis::ContractAnalysis;
+use crate::vm::functions::define::DefineFunctionsParsed;
+use crate::vm::representations::{ClarityName, SymbolicExpression, SymbolicExpressionType};
+use crate::vm::types::signatures::CallableSubtype;
+use crate::vm::types::{FixedFunction, FunctionType, PrincipalData, TypeSignature, Value};
+
+/// The most branches of one function the executor explores. Exploration
+///  beyond this is cut short, and the function is marked incomplete.
+const MAX_FORKS: usize = 256;
+
+/// The symbolic value of a Clarity expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum SymValue {
+    Scalar(Term),
+    Optional {
+        is_some: Term,
+        value: Box<SymValue>,
+    },
+    Response {
+        is_ok: Term,
+        ok: Box<SymValue>,
+        err: Box<SymValue>,
+    },
+    Tuple(BTreeMap<ClarityName, SymValue>),
+    /// A value which is not modelled
+    Opaque,
+}
+
+impl SymValue {
+    pub fn from_value(value: &Value) -> SymValue {
+        if let Some(c) = Concrete::from_value(value) {
+            return SymValue::Scalar(Term::Const(c));
+        }
+        match value {
+            Value::CallableContract(callable) => {
+                SymValue::Scalar(Term::Const(Concrete::Principal(PrincipalData::Contract(
+                    callable.contract_identifier.clone(),
+                ))))
+            }
+            Value::Optional(optional) => SymValue::Optional {
+                is_some: Term::bool(optional.data.is_some()),
+                value: Box::new(
+                    optional
+                        .data
+                        .as_deref()
+                        .map_or(SymValue::Opaque, SymValue::from_value),
+                ),
+            },
+            Value::Response(response) => {
+                let data = SymValue::from_value(&response.data);
+                let (ok, err) = if response.committed {
+                    (data, SymValue::Opaque)
+                } else {
+                    (SymValue::Opaque, data)
+                };
+                SymValue::Response {
+                    is_ok: Term::bool(response.committed),
+                    ok: Box::new(ok),
+                    err: Box::new(err),
+                }
+            }
+            Value::Tuple(tuple) => SymValue::Tuple(
+                tuple
+                    .data_map
+                    .iter()
+                    .map(|(name, value)| (name.clone(), SymValue::from_value(value)))
+                    .collect(),
+            ),
+            _ => SymValue::Opaque,
+        }
+    }
+
+    fn as_term(&self) -> Option<&Term> {
+        match self {
+            SymValue::Scalar(term) => Some(term),
+            _ => None,
+        }
+    }
+
+    /// The test of an optional or response, and the values of its two cases
+    fn cases(&self) -> Option<(&Term, &SymValue, &SymValue)> {
+        match self {
+            SymValue::Optional { is_some, value } => Some((is_some, value, &SymValue::Opaque)),
+            SymValue::Response { is_ok, ok, err } => Some((is_ok, ok, err)),
+            _ => None,
+        }
+    }
+
+    /// The condition under which two values are equal, if it can be expressed
+    fn equals(&self, other: &SymValue) -> Option<Term> {
+        match (self, other) {
+            (SymValue::Scalar(a), SymValue::Scalar(b)) => Some(Term::eq(a.clone(), b.clone())),
+            (SymValue::Tuple(a), SymValue::Tuple(b)) if a.len() == b.len() => {
+                let mut fields = vec![];
+                for (name, a) in a.iter() {
+                    fields.push(a.equals(b.get(name)?)?);
+                }
+                Some(Term::and(fields))
+            }
+            _ => {
+                let ((test_a, yes_a, no_a), (test_b, yes_b, no_b)) =
+                    (self.cases()?, other.cases()?);
+                let same_case = Term::eq(test_a.clone(), test_b.clone());
+                let if_yes = Term::or(vec![Term::negate(test_a.clone()), yes_a.equals(yes_b)?]);
+                // the value of `none` carries nothing to compare
+                let if_no = match self {
+                    SymValue::Optional { .. } => Term::bool(true),
+                    _ => Term::or(vec![test_a.clone(), no_a.equals(no_b)?]),
+                };
+                Some(Term::and(vec![same_case, if_yes, if_no]))
+            }
+        }
+    }
+}
+
+/// A symbol of the executor
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymbolInfo {
+    /// The Clarity expression whose value the symbol stands for
+    pub description: String,
+    pub sort: Sort,
+    /// Whether the symbol is (a part of) an input of the transaction, rather
+    ///  than of the chain state
+    pub input: bool,
+}
+
+/// A public or read-only function, once explored
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub function: ClarityName,
+    pub args: Vec<(TypeSignature, SymValue)>,
+    pub sender: Term,
+    /// Whether every path through the function was explored
+    pub complete: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Obligation {
+    /// The id of the expression which can fail
+    pub site: u64,
+    /// The index of the entry whose exploration reached the site
+    pub entry: usize,
+    /// The path condition, and the condition under which the site fails
+    pub constraints: Vec<Term>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Path {
+    constraints: Vec<Term>,
+    vars: HashMap<ClarityName, SymValue>,
+    supplies: HashMap<ClarityName, Term>,
+    /// Values read from, or written to, maps since they were last modified:
+    ///  `(map, key, value)`
+    map_entries: Vec<(ClarityName, SymValue, SymValue)>,
+}
+
+impl Path {
+    fn assume(&mut self, condition: Term) {
+        if condition.as_bool() != Some(true) {
+            self.constraints.push(condition);
+        }
+    }
+
+    fn with(mut self, condition: Term) -> Path {
+        self.assume(condition);
+        self
+    }
+
+    fn forget_map(&mut self, map: &ClarityName) {
+        self.map_entries.retain(|(name, ..)| name != map);
+    }
+}
+
+/// How the evaluation of an expression ended on a path
+#[derive(Debug, Clone)]
+enum Flow {
+    Value(SymValue),
+    /// An early return from the enclosing function, e.g. by `asserts!`
+    Return(SymValue),
+    /// A runtime error, which aborts the transaction
+    Abort,
+}
+
+type Outcomes = Vec<(Path, Flow)>;
+
+fn value(path: Path, value: SymValue) -> Outcomes {
+    vec![(path, Flow::Value(value))]
+}
+
+#[derive(Clone)]
+struct Frame {
+    locals: HashMap<ClarityName, SymValue>,
+    sender: Term,
+    caller: Term,
+}
+
+fn zero(sort: Sort) -> Term {
+    match sort {
+        Sort::UInt => Term::Const(Concrete::UInt(0)),
+        _ => Term::Const(Concrete::Int(0)),
+    }
+}
+
+/// The condition under which `a op b` fails at runtime, if it can
+fn failure(op: ArithOp, a: &Term, b: &Term) -> Option<Term> {
+    let (a, b) = (a.clone(), b.clone());
+    let sort = a.sort();
+    let gt = |x: Term, y: Term| Term::compare(CompareOp::Gt, x, y);
+    let lt = |x: Term, y: Term| Term::compare(CompareOp::Lt, x, y);
+    let int = |i: i128| Term::Const(Concrete::Int(i));
+    match (op, sort) {
+        (ArithOp::Div | ArithOp::Mod, _) => Some(Term::eq(b, zero(sort))),
+        (ArithOp::Sub, Sort::UInt) => Some(lt(a, b)),
+        (ArithOp::Add, Sort::UInt) => Some(gt(
+            a,
+            Term::arith(ArithOp::Sub, Term::Const(Concrete::UInt(u128::MAX)), b),
+        )),
+        (ArithOp::Mul, Sort::UInt) => Some(Term::and(vec![
+            gt(b.clone(), zero(sort)),
+            gt(
+                a,
+                Term::arith(ArithOp::Div, Term::Const(Concrete::UInt(u128::MAX)), b),
+            ),
+        ])),
+        (ArithOp::Add, Sort::Int) => Some(Term::or(vec![
+            Term::and(vec![
+                gt(b.clone(), int(0)),
+                gt(
+                    a.clone(),
+                    Term::arith(ArithOp::Sub, int(i128::MAX), b.clone()),
+                ),
+            ]),
+            Term::and(vec![
+                lt(b.clone(), int(0)),
+                lt(a, Term::arith(ArithOp::Sub, int(i128::MIN), b)),
+            ]),
+        ])),
+        (ArithOp::Sub, Sort::Int) => Some(Term::or(vec![
+            Term::and(vec![
+                lt(b.clone(), int(0)),
+                gt(
+                    a.clone(),
+                    Term::arith(ArithOp::Add, int(i128::MAX), b.clone()),
+                ),
+            ]),
+            Term::and(vec![
+                gt(b.clone(), int(0)),
+                lt(a, Term::arith(ArithOp::Add, int(i128::MIN), b)),
+            ]),
+        ])),
+        _ => None,
+    }
+}
+
+pub struct Executor<'a> {
+    analysis: &'a ContractAnalysis,
+    functions: HashMap<ClarityName, (&'a FixedFunction, &'a SymbolicExpression)>,
+    constants: HashMap<ClarityName, SymValue>,
+    /// The maximum supply of each bounded fungible token
+    caps: HashMap<ClarityName, u128>,
+    principals: Vec<PrincipalData>,
+    pub symbols: Vec<SymbolInfo>,
+    pub entries: Vec<Entry>,
+    pub obligations: Vec<Obligation>,
+    /// Private functions passed to `map`, `filter` or `fold`, which are not
+    ///  inlined
+    pub indirect: HashSet<ClarityName>,
+    /// Functions inlined at a call site
+    pub called: HashSet<ClarityName>,
+    /// The index of the entry being explored
+    entry: Option<usize>,
+    /// The chain state at the start of the entry being explored
+    state: HashMap<String, SymValue>,
+    forks: usize,
+    complete: bool,
+}
+
+impl<'a> Executor<'a> {
+    /// `principals` are the values tried for principal symbols; it must not
+    ///  be empty
+    pub fn new(analysis: &'a ContractAnalysis, principals: Vec<PrincipalData>) -> Executor<'a> {
+        let mut executor = Executor {
+            analysis,
+            functions: HashMap::new(),
+            constants: HashMap::new(),
+            caps: HashMap::new(),
+            principals,
+            symbols: vec![],
+            entries: vec![],
+            obligations: vec![],
+            indirect: HashSet::new(),
+            called: HashSet::new(),
+            entry: None,
+            state: HashMap::new(),
+            forks: 0,
+            complete: true,
+        };
+
+        for expr in analysis.expressions.iter() {
+            match DefineFunctionsParsed::try_parse(expr).ok().flatten() {
+                Some(DefineFunctionsParsed::PrivateFunction { signature, body })
+                | Some(DefineFunctionsParsed::ReadOnlyFunction { signature, body })
+                | Some(DefineFunctionsParsed::PublicFunction { signature, body }) => {
+                    let Some(name) = signature.first().and_then(|name| name.match_atom()) else {
+                        continue;
+                    };
+                    if let Some(FunctionType::Fixed(function)) = analysis
+                        .private_function_types
+                        .get(name)
+                        .or_else(|| analysis.read_only_function_types.get(name))
+                        .or_else(|| analysis.public_function_types.get(name))
+                    {
+                        executor.functions.insert(name.clone(), (function, body));
+                    }
+                }
+                Some(DefineFunctionsParsed::Constant { name, value }) => {
+                    let constant = executor.evaluate_constant(value);
+                    executor.constants.insert(name.clone(), constant);
+                }
+                Some(DefineFunctionsParsed::BoundedFungibleToken { name, max_supply }) => {
+                    if let SymValue::Scalar(Term::Const(Concrete::UInt(cap))) =
+                        executor.evaluate_constant(max_supply)
+                    {
+                        executor.caps.insert(name.clone(), cap);
+                    }
+                }
+                _ => {}
+            }
+        }
+        executor
+    }
+
+    /// The property checked at an expression, if it can fail
+    pub fn property(&self, expr: &SymbolicExpression) -> Option<Property> {
+        let list = expr.match_list()?;
+        let name = list.first()?.match_atom()?;
+        let expr_type = || {
+            self.analysis
+                .type_map
+                .as_ref()
+                .and_then(|type_map| type_map.get_type_expected(expr))
+        };
+        match name.as_str() {
+            "asserts!" => Some(Property::Assertion),
+            "/" | "mod" => Some(Property::DivisionByZero),
+            "-" => match expr_type()? {
+                TypeSignature::UIntType => Some(Property::Underflow),
+                TypeSignature::IntType => Some(Property::Overflow),
+                _ => None,
+            },
+            "+" => Some(Property::Overflow),
+            "*" => match expr_type()? {
+                TypeSignature::UIntType => Some(Property::Overflow),
+                _ => None,
+            },
+            "ft-mint?" => {
+                let token = list.get(1)?.match_atom()?;
+                self.caps.contains_key(token).then_some(Property::MintCap)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn is_function(&self, name: &ClarityName) -> bool {
+        self.functions.contains_key(name)
+    }
+
+    fn evaluate_constant(&mut self, expr: &SymbolicExpression) -> SymValue {
+        let frame = Frame {
+            locals: HashMap::new(),
+            sender: Term::Const(Concrete::Principal(self.principals[0].clone())),
+            caller: Term::Const(Concrete::Principal(self.principals[0].clone())),
+        };
+        match self.eval(expr, &frame, Path::default()).as_slice() {
+            [(_, Flow::Value(value))] => value.clone(),
+            _ => SymValue::Opaque,
+        }
+    }
+
+    /// Explore every path through a public or read-only function
+    pub fn explore(&mut self, name: &ClarityName) {
+        let Some((function, body)) = self.functions.get(name).cloned() else {
+            return;
+        };
+        self.entry = Some(self.entries.len());
+        self.state.clear();
+        self.forks = 0;
+        self.complete = true;
+
+        let sender = self.symbol("tx-sender".into(), Sort::Principal, true);
+        let mut frame = Frame {
+            locals: HashMap::new(),
+            caller: sender.clone(),
+            sender: sender.clone(),
+        };
+        let mut args = vec![];
+        for arg in function.args.iter() {
+            let arg_value = self.fresh(&arg.signature, arg.name.to_string(), true);
+            frame.locals.insert(arg.name.clone(), arg_value.clone());
+            args.push((arg.signature.clone(), arg_value));
+        }
+
+        let mut path = Path::default();
+        let mut tokens: Vec<_> = self.analysis.fungible_tokens.iter().collect();
+        tokens.sort();
+        for token in tokens {
+            let supply = self.supply(&path, token);
+            if let Some(cap) = self.caps.get(token) {
+                path.assume(Term::compare(
+                    CompareOp::Le,
+                    supply,
+                    Term::Const(Concrete::UInt(*cap)),
+                ));
+            }
+        }
+        self.eval(body, &frame, path);
+
+        self.entries.push(Entry {
+            function: name.clone(),
+            args,
+            sender,
+            complete: self.complete,
+        });
+        self.entry = None;
+    }
+
+    fn symbol(&mut self, description: String, sort: Sort, input: bool) -> Term {
+        self.symbols.push(SymbolInfo {
+            description,
+            sort,
+            input,
+        });
+        Term::Symbol(self.symbols.len() - 1, sort)
+    }
+
+    /// A fresh value of a type, made of new symbols
+    fn fresh(&mut self, ty: &TypeSignature, description: String, input: bool) -> SymValue {
+        let sort = match ty {
+            TypeSignature::IntType => Sort::Int,
+            TypeSignature::UIntType => Sort::UInt,
+            TypeSignature::BoolType => Sort::Bool,
+            TypeSignature::PrincipalType
+            | TypeSignature::CallableType(CallableSubtype::Principal(_)) => Sort::Principal,
+            TypeSignature::OptionalType(inner) => {
+                let is_some = self.symbol(format!("(is-some {description})"), Sort::Bool, input);
+                let inner = self.fresh(inner, format!("(unwrap-panic {description})"), input);
+                return SymValue::Optional {
+                    is_some,
+                    value: Box::new(inner),
+                };
+            }
+            TypeSignature::ResponseType(inner) => {
+                let is_ok = self.symbol(format!("(is-ok {description})"), Sort::Bool, input);
+                let ok = self.fresh(&inner.0, format!("(unwrap-panic {description})"), input);
+                let err = self.fresh(&inner.1, format!("(unwrap-err-panic {description})"), input);
+                return SymValue::Response {
+                    is_ok,
+                    ok: Box::new(ok),
+                    err: Box::new(err),
+                };
+            }
+            TypeSignature::TupleType(tuple) => {
+                let fields = tuple
+                    .get_type_map()
+                    .iter()
+                    .map(|(name, ty)| {
+                        let field = self.fresh(ty, format!("(get {name} {description})"), input);
+                        (name.clone(), field)
+                    })
+                    .collect();
+                return SymValue::Tuple(fields);
+            }
+            _ => return SymValue::Opaque,
+        };
+        SymValue::Scalar(self.symbol(description, sort, input))
+    }
+
+    /// A fresh value of the type of `expr`
+    fn fresh_for(&mut self, expr: &SymbolicExpression) -> SymValue {
+        let ty = self
+            .analysis
+            .type_map
+            .as_ref()
+            .and_then(|type_map| type_map.get_type_expected(expr))
+            .cloned();
+        match ty {
+            Some(ty) => self.fresh(&ty, source(expr), false),
+            None => SymValue::Opaque,
+        }
+    }
+
+    /// A value of the chain state at the start of the transaction
+    fn state(&mut self, description: String, ty: &TypeSignature) -> SymValue {
+        if let Some(value) = self.state.get(&description) {
+            return value.clone();
+        }
+        let value = self.fresh(ty, description.clone(), false);
+        self.state.insert(description, value.clone());
+        value
+    }
+
+    fn supply(&mut self, path: &Path, token: &ClarityName) -> Term {
+        if let Some(supply) = path.supplies.get(token) {
+            return supply.clone();
+        }
+        match self.state(format!("(ft-get-supply {token})"), &TypeSignature::UIntType) {
+            SymValue::Scalar(supply) => supply,
+            _ => unreachable!("a uint is a scalar"),
+        }
+    }
+
+    fn feasible(&self, constraints: &[Term]) -> bool {
+        let sorts: Vec<_> = self.symbols.iter().map(|symbol| symbol.sort).collect();
+        let solver = Solver {
+            sorts: &sorts,
+            principals: &self.principals,
+        };
+        !solver.is_unsat(constraints)
+    }
+
+    /// The paths on which `condition` is true and false, if they are feasible
+    fn fork(&mut self, path: Path, condition: &Term) -> (Option<Path>, Option<Path>) {
+        match condition.as_bool() {
+            Some(true) => return (Some(path), None),
+            Some(false) => return (None, Some(path)),
+            None => {}
+        }
+        let if_true = path.clone().with(condition.clone());
+        let if_false = path.with(Term::negate(condition.clone()));
+        let if_true = self.feasible(&if_true.constraints).then_some(if_true);
+        let if_false = self.feasible(&if_false.constraints).then_some(if_false);
+        if if_true.is_some() && if_false.is_some() {
+            if self.forks >= MAX_FORKS {
+                self.complete = false;
+                return (if_true, None);
+            }
+            self.forks += 1;
+        }
+        (if_true, if_false)
+    }
+
+    /// Record an obligation for the site, unless the failure is impossible
+    fn record(&mut self, site: Option<u64>, path: &Path, failure: &Term) {
+        let (Some(site), Some(entry)) = (site, self.entry) else {
+            return;
+        };
+        if failure.as_bool() == Some(false) {
+            return;
+        }
+        let mut constraints = path.constraints.clone();
+        constraints.push(failure.clone());
+        self.obligations.push(Obligation {
+            site,
+            entry,
+            constraints,
+        });
+    }
+
+    /// Record an obligation for an operation which aborts the transaction
+    ///  when it fails, and continue on the path where it succeeds. Returns
+    ///  `false` if the operation always fails.
+    fn require(&mut self, site: Option<u64>, path: &mut Path, failure: Term) -> bool {
+        self.record(site, path, &failure);
+        let succeeds = Term::negate(failure);
+        if succeeds.as_bool() == Some(false) {
+            return false;
+        }
+        path.assume(succeeds);
+        true
+    }
+
+    /// The boolean term of a value, or a fresh symbol if it is not modelled
+    fn condition(&mut self, value: &SymValue, expr: &SymbolicExpression) -> Term {
+        match value.as_term() {
+            Some(term) => term.clone(),
+            None => self.symbol(source(expr), Sort::Bool, false),
+        }
+    }
+
+    /// Continue each path on which evaluation produced a value
+    fn then(
+        &mut self,
+        outcomes: Outcomes,
+        mut f: impl FnMut(&mut Self, Path, SymValue) -> Outcomes,
+    ) -> Outcomes {
+        let mut result = vec![];
+        for (path, flow) in outcomes {
+            match flow {
+                Flow::Value(v) => result.extend(f(self, path, v)),
+                exit => result.push((path, exit)),
+            }
+        }
+        result
+    }
+
+    /// Evaluate expressions in sequence, and continue each path on which
+    ///  they all produced a value
+    fn then_all(
+        &mut self,
+        exprs: &[SymbolicExpression],
+        frame: &Frame,
+        path: Path,
+        mut f: impl FnMut(&mut Self, Path, Vec<SymValue>) -> Outcomes,
+    ) -> Outcomes {
+        let mut states = vec![(path, vec![])];
+        let mut result = vec![];
+        for expr in exprs.iter() {
+            let mut next = vec![];
+            for (path, values) in states {
+                for (path, flow) in self.eval(expr, frame, path) {
+                    match flow {
+                        Flow::Value(v) => {
+                            let mut values = values.clone();
+                            values.push(v);
+                            next.push((path, values));
+                        }
+                        exit => result.push((path, exit)),
+                    }
+                }
+            }
+            states = next;
+        }
+        for (path, values) in states {
+            result.extend(f(self, path, values));
+        }
+        result
+    }
+
+    fn eval(&mut self, expr: &SymbolicExpression, frame: &Frame, path: Path) -> Outcomes {
+        match &expr.expr {
+            SymbolicExpressionType::AtomValue(v) | SymbolicExpressionType::LiteralValue(v) => {
+                value(path, SymValue::from_value(v))
+            }
+            SymbolicExpressionType::Atom(name) => {
+                let v = self.lookup(name, frame);
+                value(path, v)
+            }
+            SymbolicExpressionType::List(list) => self.eval_list(expr, list, frame, path),
+            SymbolicExpressionType::Field(_) | SymbolicExpressionType::TraitReference(..) => {
+                value(path, SymValue::Opaque)
+            }
+        }
+    }
+
+    fn lookup(&mut self, name: &ClarityName, frame: &Frame) -> SymValue {
+        if let Some(v) = frame.locals.get(name).or_else(|| self.constants.get(name)) {
+            return v.clone();
+        }
+        match name.as_str() {
+            "tx-sender" => SymValue::Scalar(frame.sender.clone()),
+            "contract-caller" => SymValue::Scalar(frame.caller.clone()),
+            "true" => SymValue::Scalar(Term::bool(true)),
+            "false" => SymValue::Scalar(Term::bool(false)),
+            "none" => SymValue::Optional {
+                is_some: Term::bool(false),
+                value: Box::new(SymValue::Opaque),
+            },
+            "block-height"
+            | "burn-block-height"
+            | "stacks-block-height"
+            | "tenure-height"
+            | "chain-id"
+            | "stx-liquid-supply" => self.state(name.to_string(), &TypeSignature::UIntType),
+            "is-in-mainnet" | "is-in-regtest" => {
+                self.state(name.to_string(), &TypeSignature::BoolType)
+            }
+            _ => {
+                // a function passed to `map`, `filter` or `fold`
+                if self.functions.contains_key(name) {
+                    self.indirect.insert(name.clone());
+                }
+                SymValue::Opaque
+            }
+        }
+    }
+
+    fn eval_list(
+        &mut self,
+        expr: &SymbolicExpression,
+        list: &[SymbolicExpression],
+        frame: &Frame,
+        path: Path,
+    ) -> Outcomes {
+        let Some((name, args)) = list
+            .split_first()
+            .and_then(|(head, args)| Some((head.match_atom()?, args)))
+        else {
+            return value(path, SymValue::Opaque);
+        };
+        if let Some((function, body)) = self.functions.get(name).cloned() {
+            self.called.insert(name.clone());
+            return self.then_all(args, frame, path, |this, path, values| {
+                this.call(function, body, frame, path, values)
+            });
+        }
+
+        let site = self.property(expr).map(|_| expr.id);
+        match (name.as_str(), args) {
+            ("+" | "-" | "*" | "/" | "mod", _) => {
+                let op = match name.as_str() {
+                    "+" => ArithOp::Add,
+                    "-" => ArithOp::Sub,
+                    "*" => ArithOp::Mul,
+                    "/" => ArithOp::Div,
+                    _ => ArithOp::Mod,
+                };
+                self.then_all(args, frame, path, |this, path, values| {
+                    this.arithmetic(expr, site, op, path, values)
+                })
+            }
+            ("<" | "<=" | ">" | ">=", [_, _]) => {
+                let op = match name.as_str() {
+                    "<" => CompareOp::Lt,
+                    "<=" => CompareOp::Le,
+                    ">" => CompareOp::Gt,
+                    _ => CompareOp::Ge,
+                };
+                self.then_all(args, frame, path, |this, path, values| {
+                    match (values[0].as_term(), values[1].as_term()) {
+                        (Some(a), Some(b)) if matches!(a.sort(), Sort::Int | Sort::UInt) => {
+                            let result = Term::compare(op, a.clone(), b.clone());
+                            value(path, SymValue::Scalar(result))
+                        }
+                        _ => {
+                            let result = this.fresh_for(expr);
+                            value(path, result)
+                        }
+                    }
+                })
+            }
+            ("is-eq", [_, ..]) => self.then_all(args, frame, path, |this, path, values| {
+                let equal: Option<Vec<_>> =
+                    values[1..].iter().map(|v| values[0].equals(v)).collect();
+                let result = match equal {
+                    Some(equal) => SymValue::Scalar(Term::and(equal)),
+                    None => this.fresh_for(expr),
+                };
+                value(path, result)
+            }),
+            ("not", [arg]) => {
+                let outcomes = self.eval(arg, frame, path);
+                self.then(outcomes, |this, path, v| {
+                    let condition = this.condition(&v, arg);
+                    value(path, SymValue::Scalar(Term::negate(condition)))
+                })
+            }
+            ("and", _) => self.eval_logic(true, args, frame, path),
+            ("or", _) => self.eval_logic(false, args, frame, path),
+            ("if", [condition, then_branch, else_branch]) => {
+                let outcomes = self.eval(condition, frame, path);
+                self.then(outcomes, |this, path, v| {
+                    let condition = this.condition(&v, condition);
+                    let (if_true, if_false) = this.fork(path, &condition);
+                    let mut result = vec![];
+                    if let Some(path) = if_true {
+                        result.extend(this.eval(then_branch, frame, path));
+                    }
+                    if let Some(path) = if_false {
+                        result.extend(this.eval(else_branch, frame, path));
+                    }
+                    result
+                })
+            }
+            ("begin", [_, ..]) => self.then_all(args, frame, path, |_, path, mut values| {
+                let last = values.pop().unwrap_or(SymValue::Opaque);
+                value(path, last)
+            }),
+            ("let", [bindings, body @ ..]) => self.eval_let(bindings, body, frame, path),
+            ("asserts!", [condition, thrown]) => {
+                let outcomes = self.eval(condition, frame, path);
+                self.then(outcomes, |this, path, v| {
+                    let condition = this.condition(&v, condition);
+                    this.record(site, &path, &Term::negate(condition.clone()));
+                    let (if_true, if_false) = this.fork(path, &condition);
+                    let mut result = vec![];
+                    if let Some(path) = if_true {
+                        result.push((path, Flow::Value(SymValue::Scalar(Term::bool(true)))));
+                    }
+                    if let Some(path) = if_false {
+                        result.extend(this.throw(thrown, frame, path));
+                    }
+                    result
+                })
+            }
+            ("unwrap!" | "unwrap-err!", [input, thrown]) => {
+                let success = name.as_str() == "unwrap!";
+                let outcomes = self.eval(input, frame, path);
+                self.then(outcomes, |this, path, v| {
+                    this.unwrap(expr, &v, success, path, |this, path| {
+                        this.throw(thrown, frame, path)
+                    })
+                })
+            }
+            ("unwrap-panic" | "unwrap-err-panic", [input]) => {
+                let success = name.as_str() == "unwrap-panic";
+                let outcomes = self.eval(input, frame, path);
+                self.then(outcomes, |this, path, v| {
+                    this.unwrap(expr, &v, success, path, |_, path| vec![(path, Flow::Abort)])
+                })
+            }
+            ("try!", [input]) => {
+                let outcomes = self.eval(input, frame, path);
+                self.then(outcomes, |this, path, v| {
+                    let failed = match &v {
+                        SymValue::Optional { .. } => SymValue::Optional {
+                            is_some: Term::bool(false),
+                            value: Box::new(SymValue::Opaque),
+                        },
+                        SymValue::Response { err, .. } => SymValue::Response {
+                            is_ok: Term::bool(false),
+                            ok: Box::new(SymValue::Opaque),
+                            err: err.clone(),
+                        },
+                        _ => SymValue::Opaque,
+                    };
+                    this.unwrap(expr, &v, true, path, |_, path| {
+                        vec![(path, Flow::Return(failed.clone()))]
+                    })
+                })
+            }
+            ("match", [input, some_name, some_branch, none_branch]) => {
+                let outcomes = self.eval(input, frame, path);
+                self.then(outcomes, |this, path, v| {
+                    this.eval_match(
+                        &v,
+                        (some_name, some_branch),
+                        (None, none_branch),
+                        frame,
+                        path,
+                    )
+                })
+            }
+            ("match", [input, ok_name, ok_branch, err_name, err_branch]) => {
+                let outcomes = self.eval(input, frame, path);
+                self.then(outcomes, |this, path, v| {
+                    this.eval_match(
+                        &v,
+                        (ok_name, ok_branch),
+                        (Some(err_name), err_branch),
+                        frame,
+                        path,
+                    )
+                })
+            }
+            ("ok" | "err" | "some", [arg]) => {
+                let outcomes = self.eval(arg, frame, path);
+                self.then(outcomes, |_, path, v| {
+                    let v = Box::new(v);
+                    let result = match name.as_str() {
+                        "some" => SymValue::Optional {
+                            is_some: Term::bool(true),
+                            value: v,
+                        },
+                        "ok" => SymValue::Response {
+                            is_ok: Term::bool(true),
+                            ok: v,
+                            err: Box::new(SymValue::Opaque),
+                        },
+                        _ => SymValue::Response {
+                            is_ok: Term::bool(false),
+                            ok: Box::new(SymValue::Opaque),
+                            err: v,
+                        },
+                    };
+                    value(path, result)
+                })
+            }
+            ("default-to", [_, _]) => self.then_all(args, frame, path, |this, path, values| {
+                let Some((is_some, v, _)) = values[1].cases() else {
+                    let result = this.fresh_for(expr);
+                    return value(path, result);
+                };
+                let (is_some, v) = (is_some.clone(), v.clone());
+                let (if_some, if_none) = this.fork(path, &is_some);
+                let mut result = vec![];
+                if let Some(path) = if_some {
+                    result.push((path, Flow::Value(v)));
+                }
+                if let Some(path) = if_none {
+                    result.push((path, Flow::Value(values[0].clone())));
+                }
+                result
+            }),
+            ("is-some" | "is-ok" | "is-none" | "is-err", [arg]) => {
+                let positive = matches!(name.as_str(), "is-some" | "is-ok");
+                let outcomes = self.eval(arg, frame, path);
+                self.then(outcomes, |this, path, v| {
+                    let result = match v.cases() {
+                        Some((test, ..)) if positive => SymValue::Scalar(test.clone()),
+                        Some((test, ..)) => SymValue::Scalar(Term::negate(test.clone())),
+                        None => this.fresh_for(expr),
+                    };
+                    value(path, result)
+                })
+            }
+            ("tuple", _) => {
+                let mut names = vec![];
+                let mut values = vec![];
+                for pair in args.iter() {
+                    match pair.match_list() {
+                        Some([name, v]) if name.match_atom().is_some() => {
+                            names.extend(name.match_atom().cloned());
+                            values.push(v.clone());
+                        }
+                        _ => return value(path, SymValue::Opaque),
+                    }
+                }
+                self.then_all(&values, frame, path, |_, path, values| {
+                    let fields = names.iter().cloned().zip(values).collect();
+                    value(path, SymValue::Tuple(fields))
+                })
+            }
+            ("get", [field, tuple]) => {
+                let Some(field) = field.match_atom() else {
+                    return value(path, SymValue::Opaque);
+                };
+                let outcomes = self.eval(tuple, frame, path);
+                self.then(outcomes, |this, path, v| {
+                    let result = match v {
+                        SymValue::Tuple(fields) => {
+                            fields.get(field).cloned().unwrap_or(SymValue::Opaque)
+                        }
+                        SymValue::Optional { is_some, value } => match *value {
+                            SymValue::Tuple(fields) => SymValue::Optional {
+                                is_some,
+                                value: Box::new(
+                                    fields.get(field).cloned().unwrap_or(SymValue::Opaque),
+                                ),
+                            },
+                            _ => this.fresh_for(expr),
+                        },
+                        _ => this.fresh_for(expr),
+                    };
+                    value(path, result)
+                })
+            }
+            ("merge", [_, _]) => self.then_all(args, frame, path, |this, path, values| {
+                let result = match (&values[0], &values[1]) {
+                    (SymValue::Tuple(a), SymValue::Tuple(b)) => {
+                        let mut merged = a.clone();
+                        merged.extend(b.iter().map(|(name, v)| (name.clone(), v.clone())));
+                        SymValue::Tuple(merged)
+                    }
+                    _ => this.fresh_for(expr),
+                };
+                value(path, result)
+            }),
+            ("var-get", [var]) => {
+                let Some(var) = var.match_atom() else {
+                    return value(path, SymValue::Opaque);
+                };
+                let result = match path.vars.get(var) {
+                    Some(v) => v.clone(),
+                    None => match self.analysis.persisted_variable_types.get(var) {
+                        Some(ty) => self.state(format!("(var-get {var})"), ty),
+                        None => SymValue::Opaque,
+                    },
+                };
+                value(path, result)
+            }
+            ("var-set", [var, new_value]) => {
+                let Some(var) = var.match_atom() else {
+                    return value(path, SymValue::Opaque);
+                };
+                let outcomes = self.eval(new_value, frame, path);
+                self.then(outcomes, |_, mut path, v| {
+                    path.vars.insert(var.clone(), v);
+                    value(path, SymValue::Scalar(Term::bool(true)))
+                })
+            }
+            ("map-get?", [map, key]) => {
+                let Some(map) = map.match_atom() else {
+                    return value(path, SymValue::Opaque);
+                };
+                let outcomes = self.eval(key, frame, path);
+                self.then(outcomes, |this, mut path, key| {
+                    let known = path
+                        .map_entries
+                        .iter()
+                        .find(|(name, k, _)| name == map && *k == key && key != SymValue::Opaque);
+                    if let Some((.., v)) = known {
+                        let v = v.clone();
+                        return value(path, v);
+                    }
+                    let result = this.fresh_for(expr);
+                    path.map_entries.push((map.clone(), key, result.clone()));
+                    value(path, result)
+                })
+            }
+            ("map-set" | "map-insert" | "map-delete", [map, ..]) => {
+                let Some(map) = map.match_atom() else {
+                    return value(path, SymValue::Opaque);
+                };
+                self.then_all(&args[1..], frame, path, |this, mut path, mut values| {
+                    // another key may be the same as this one
+                    path.forget_map(map);
+                    let key = values.remove(0);
+                    let result = match (name.as_str(), values.pop()) {
+                        ("map-set", Some(v)) => {
+                            let entry = SymValue::Optional {
+                                is_some: Term::bool(true),
+                                value: Box::new(v),
+                            };
+                            path.map_entries.push((map.clone(), key, entry));
+                            SymValue::Scalar(Term::bool(true))
+                        }
+                        ("map-delete", None) => {
+                            let entry = SymValue::Optional {
+                                is_some: Term::bool(false),
+                                value: Box::new(SymValue::Opaque),
+                            };
+                            path.map_entries.push((map.clone(), key, entry));
+                            this.fresh_for(expr)
+                        }
+                        _ => this.fresh_for(expr),
+                    };
+                    value(path, result)
+                })
+            }
+            ("ft-get-supply", [token]) => {
+                let Some(token) = token.match_atom() else {
+                    return value(path, SymValue::Opaque);
+                };
+                let supply = self.supply(&path, token);
+                value(path, SymValue::Scalar(supply))
+            }
+            ("ft-get-balance", [token, _]) => {
+                let Some(token) = token.match_atom() else {
+                    return value(path, SymValue::Opaque);
+                };
+                self.then_all(&args[1..], frame, path, |this, mut path, _| {
+                    let balance = this.fresh_for(expr);
+                    if let SymValue::Scalar(balance) = &balance {
+                        // no balance exceeds the supply
+                        let supply = this.supply(&path, token);
+                        path.assume(Term::compare(CompareOp::Le, balance.clone(), supply));
+                    }
+                    value(path, balance)
+                })
+            }
+            ("ft-mint?" | "ft-burn?", [token, _, _]) => {
+                let Some(token) = token.match_atom() else {
+                    return value(path, SymValue::Opaque);
+                };
+                let mint = name.as_str() == "ft-mint?";
+                self.then_all(&args[1..], frame, path, |this, path, values| {
+                    match values[0].as_term() {
+                        Some(amount) => {
+                            let amount = amount.clone();
+                            this.token_supply_change(site, token, mint, amount, path)
+                        }
+                        None => {
+                            let result = this.fresh_for(expr);
+                            value(path, result)
+                        }
+                    }
+                })
+            }
+            ("as-contract", [inner]) => {
+                let contract = Term::Const(Concrete::Principal(PrincipalData::Contract(
+                    self.analysis.contract_identifier.clone(),
+                )));
+                let frame = Frame {
+                    locals: frame.locals.clone(),
+                    sender: contract.clone(),
+                    caller: contract,
+                };
+                self.eval(inner, &frame, path)
+            }
+            ("print", [arg]) => self.eval(arg, frame, path),
+            _ => {
+                // evaluate the arguments for their checks, but assume nothing
+                //  about the result
+                self.then_all(args, frame, path, |this, path, _| {
+                    let result = this.fresh_for(expr);
+                    value(path, result)
+                })
+            }
+        }
+    }
+
+    fn arithmetic(
+        &mut self,
+        expr: &SymbolicExpression,
+        site: Option<u64>,
+        op: ArithOp,
+        mut path: Path,
+        values: Vec<SymValue>,
+    ) -> Outcomes {
+        let terms: Option<Vec<Term>> = values.iter().map(|v| v.as_term().cloned()).collect();
+        let Some(mut terms) = terms.filter(|terms| !terms.is_empty()) else {
+            let result = self.fresh_for(expr);
+            return value(path, result);
+        };
+        if op == ArithOp::Sub && terms.len() == 1 {
+            terms.insert(0, zero(terms[0].sort()));
+        }
+        let mut result = terms[0].clone();
+        for operand in terms[1..].iter() {
+            if let Some(failure) = failure(op, &result, operand) {
+                if !self.require(site, &mut path, failure) {
+                    return vec![(path, Flow::Abort)];
+                }
+            }
+            result = Term::arith(op, result, operand.clone());
+        }
+        value(path, SymValue::Scalar(result))
+    }
+
+    /// The result of `ft-mint?` or `ft-burn?`
+    fn token_supply_change(
+        &mut self,
+        site: Option<u64>,
+        token: &ClarityName,
+        mint: bool,
+        amount: Term,
+        path: Path,
+    ) -> Outcomes {
+        let supply = self.supply(&path, token);
+        let error = |code| SymValue::Response {
+            is_ok: Term::bool(false),
+            ok: Box::new(SymValue::Opaque),
+            err: Box::new(SymValue::Scalar(Term::Const(Concrete::UInt(code)))),
+        };
+        let success = SymValue::Response {
+            is_ok: Term::bool(true),
+            ok: Box::new(SymValue::Scalar(Term::bool(true))),
+            err: Box::new(SymValue::Opaque),
+        };
+        let positive = Term::compare(CompareOp::Gt, amount.clone(), zero(Sort::UInt));
+        let mut result = vec![];
+        if mint {
+            let (if_positive, if_zero) = self.fork(path, &positive);
+            if let Some(path) = if_zero {
+                result.push((path, Flow::Value(error(1))));
+            }
+            if let Some(mut path) = if_positive {
+                let exceeds = match self.caps.get(token) {
+                    Some(cap) => Term::compare(
+                        CompareOp::Gt,
+                        amount.clone(),
+                        Term::arith(
+                            ArithOp::Sub,
+                            Term::Const(Concrete::UInt(*cap)),
+                            supply.clone(),
+                        ),
+                    ),
+                    None => {
+                        failure(ArithOp::Add, &supply, &amount).unwrap_or_else(|| Term::bool(false))
+                    }
+                };
+                if self.require(site, &mut path, exceeds) {
+                    let supply = Term::arith(ArithOp::Add, supply, amount);
+                    path.supplies.insert(token.clone(), supply);
+                    result.push((path, Flow::Value(success)));
+                } else {
+                    result.push((path, Flow::Abort));
+                }
+            }
+        } else {
+            // a burn can also fail for want of a balance, so its failure
+            //  tells nothing about the amount
+            let burned = path.clone().with(Term::and(vec![
+                positive,
+                Term::compare(CompareOp::Le, amount.clone(), supply.clone()),
+            ]));
+            if self.feasible(&burned.constraints) {
+                let mut burned = burned;
+                let supply = Term::arith(ArithOp::Sub, supply, amount);
+                burned.supplies.insert(token.clone(), supply);
+                result.push((burned, Flow::Value(success)));
+            }
+            let code = self.symbol(format!("(ft-burn? {token})"), Sort::UInt, false);
+            let failed = SymValue::Response {
+                is_ok: Term::bool(false),
+                ok: Box::new(SymValue::Opaque),
+                err: Box::new(SymValue::Scalar(code)),
+            };
+            result.push((path, Flow::Value(failed)));
+        }
+        result
+    }
+
+    /// Evaluate `thrown`, and return its value from the enclosing function
+    fn throw(&mut self, thrown: &SymbolicExpression, frame: &Frame, path: Path) -> Outcomes {
+        let outcomes = self.eval(thrown, frame, path);
+        self.then(outcomes, |_, path, v| vec![(path, Flow::Return(v))])
+    }
+
+    /// Take the `success` (some or ok) or failure (err) case of a value,
+    ///  running `otherwise` on the path where it is in the other case
+    fn unwrap(
+        &mut self,
+        expr: &SymbolicExpression,
+        v: &SymValue,
+        success: bool,
+        path: Path,
+        mut otherwise: impl FnMut(&mut Self, Path) -> Outcomes,
+    ) -> Outcomes {
+        let Some((test, yes, no)) = v.cases() else {
+            let result = self.fresh_for(expr);
+            let mut outcomes = value(path.clone(), result);
+            outcomes.extend(otherwise(self, path));
+            return outcomes;
+        };
+        let (test, taken) = if success {
+            (test.clone(), yes.clone())
+        } else {
+            (Term::negate(test.clone()), no.clone())
+        };
+        let (if_taken, if_not) = self.fork(path, &test);
+        let mut result = vec![];
+        if let Some(path) = if_taken {
+            result.push((path, Flow::Value(taken)));
+        }
+        if let Some(path) = if_not {
+            result.extend(otherwise(self, path));
+        }
+        result
+    }
+
+    /// `(match input name branch [other-name] other-branch)`
+    fn eval_match(
+        &mut self,
+        v: &SymValue,
+        (name, branch): (&SymbolicExpression, &SymbolicExpression),
+        (other_name, other_branch): (Option<&SymbolicExpression>, &SymbolicExpression),
+        frame: &Frame,
+        path: Path,
+    ) -> Outcomes {
+        let (test, yes, no) = match v.cases() {
+            Some((test, yes, no)) => (test.clone(), yes.clone(), no.clone()),
+            None => (
+                self.symbol("(match)".into(), Sort::Bool, false),
+                SymValue::Opaque,
+                SymValue::Opaque,
+            ),
+        };
+        let bind = |name: Option<&SymbolicExpression>, v: SymValue| {
+            let mut frame = frame.clone();
+            if let Some(name) = name.and_then(|name| name.match_atom()) {
+                frame.locals.insert(name.clone(), v);
+            }
+            frame
+        };
+        let (if_yes, if_no) = self.fork(path, &test);
+        let mut result = vec![];
+        if let Some(path) = if_yes {
+            result.extend(self.eval(branch, &bind(Some(name), yes), path));
+        }
+        if let Some(path) = if_no {
+            result.extend(self.eval(other_branch, &bind(other_name, no), path));
+        }
+        result
+    }
+
+    fn eval_let(
+        &mut self,
+        bindings: &SymbolicExpression,
+        body: &[SymbolicExpression],
+        frame: &Frame,
+        path: Path,
+    ) -> Outcomes {
+        let Some(bindings) = bindings.match_list() else {
+            return value(path, SymValue::Opaque);
+        };
+        let mut states = vec![(path, frame.clone())];
+        let mut result = vec![];
+        for binding in bindings.iter() {
+            let Some([name, bound]) = binding.match_list() else {
+                continue;
+            };
+            let Some(name) = name.match_atom() else {
+                continue;
+            };
+            let mut next = vec![];
+            for (path, frame) in states {
+                for (path, flow) in self.eval(bound, &frame, path) {
+                    match flow {
+                        Flow::Value(v) => {
+                            let mut frame = frame.clone();
+                            frame.locals.insert(name.clone(), v);
+                            next.push((path, frame));
+                        }
+                        exit => result.push((path, exit)),
+                    }
+                }
+            }
+            states = next;
+        }
+        for (path, frame) in states {
+            result.extend(self.then_all(body, &frame, path, |_, path, mut values| {
+                let last = values.pop().unwrap_or(SymValue::Opaque);
+                value(path, last)
+            }));
+        }
+        result
+    }
+
+    /// `and` and `or` short-circuit, so each argument is only evaluated on
+    ///  the paths where the ones before it did not decide the result
+    fn eval_logic(
+        &mut self,
+        is_and: bool,
+        args: &[SymbolicExpression],
+        frame: &Frame,
+        path: Path,
+    ) -> Outcomes {
+        let mut result = vec![];
+        let mut pending = vec![path];
+        for arg in args.iter() {
+            let mut next = vec![];
+            for path in pending {
+                for (path, flow) in self.eval(arg, frame, path) {
+                    let Flow::Value(v) = flow else {
+                        result.push((path, flow));
+                        continue;
+                    };
+                    let condition = self.condition(&v, arg);
+                    let (if_true, if_false) = self.fork(path, &condition);
+                    let (decided, undecided) = if is_and {
+                        (if_false, if_true)
+                    } else {
+                        (if_true, if_false)
+                    };
+                    if let Some(path) = decided {
+                        let decided = SymValue::Scalar(Term::bool(!is_and));
+                        result.push((path, Flow::Value(decided)));
+                    }
+                    next.extend(undecided);
+                }
+            }
+            pending = next;
+        }
+        for path in pending {
+            result.push((path, Flow::Value(SymValue::Scalar(Term::bool(is_and)))));
+        }
+        result
+    }
+
+    /// Inline a call to a function of the contract
+    fn call(
+        &mut self,
+        function: &FixedFunction,
+        body: &SymbolicExpression,
+        frame: &Frame,
+        path: Path,
+        values: Vec<SymValue>,
+    ) -> Outcomes {
+        let callee = Frame {
+            locals: function
+                .args
+                .iter()
+                .map(|arg| arg.name.clone())
+                .zip(values)
+                .collect(),
+            sender: frame.sender.clone(),
+            caller: frame.caller.clone(),
+        };
+        self.eval(body, &callee, path)
+            .into_iter()
+            .map(|(path, flow)| match flow {
+                Flow::Return(v) => (path, Flow::Value(v)),
+                flow => (path, flow),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,409 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Proves or refutes, for every operation of a contract which can fail,
+//! that it never fails: that `asserts!` conditions always hold, that
+//! arithmetic never underflows, overflows or divides by zero, and that
+//! `ft-mint?` never exceeds a token's maximum supply.
+//!
+//! The contract's public and read-only functions are executed symbolically
+//! (see `executor`), and the condition under which each operation fails is
+//! decided by a small solver (see `solver`). A refuted property comes with a
+//! counterexample: a call, with arguments and a sender, which makes the
+//! operation fail, given the values it assumes for the chain state.
+//!
+//! The analysis needs the type of every expression, so the contract must be
+//! analysed with a type map (`build_type_map`).
+
+use std::collections::BTreeSet;
+
+use serde::Serializer;
+
+use self::executor::{Executor, Obligation, SymValue};
+use self::solver::{Outcome, Solver};
+use self::term::Concrete;
+use super::fuzz::clarity_literal;
+use crate::vm::analysis::ContractAnalysis;
+use crate::vm::functions::define::DefineFunctionsParsed;
+use crate::vm::representations::{ClarityName, SymbolicExpression, SymbolicExpressionType};
+use crate::vm::types::{
+    PrincipalData, SequenceSubtype, StringSubtype, TupleData, TypeSignature, Value,
+};
+
+pub mod executor;
+pub mod solver;
+pub mod term;
+
+/// The ways an operation can fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Property {
+    /// The condition of an `asserts!` is false
+    Assertion,
+    /// A uint subtraction goes below zero
+    Underflow,
+    /// An addition, a subtraction of ints or a multiplication of uints
+    ///  leaves the range of its type
+    Overflow,
+    DivisionByZero,
+    /// An `ft-mint?` takes a token's supply beyond its maximum
+    MintCap,
+}
+
+/// A value of the chain state which a counterexample depends on
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Assumption {
+    pub expression: String,
+    #[serde(serialize_with = "serialize_literal")]
+    pub value: Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Counterexample {
+    /// The public or read-only function to call
+    pub function: String,
+    #[serde(serialize_with = "serialize_principal")]
+    pub sender: PrincipalData,
+    #[serde(serialize_with = "serialize_literals")]
+    pub args: Vec<Value>,
+    pub assumptions: Vec<Assumption>,
+}
+
+impl Counterexample {
+    /// The arguments as Clarity source, as `clarity-cli execute` takes them
+    pub fn arg_literals(&self) -> Vec<String> {
+        self.args.iter().map(clarity_literal).collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum Verdict {
+    /// The operation never fails
+    Proved,
+    Refuted {
+        counterexample: Counterexample,
+    },
+    Unknown {
+        reason: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PropertyReport {
+    pub property: Property,
+    /// The function containing the operation
+    pub function: String,
+    pub expression: String,
+    /// Only known when the contract was parsed with spans
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+    #[serde(flatten)]
+    pub verdict: Verdict,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VerificationReport {
+    pub properties: Vec<PropertyReport>,
+}
+
+impl VerificationReport {
+    pub fn refuted(&self) -> bool {
+        self.properties
+            .iter()
+            .any(|report| matches!(report.verdict, Verdict::Refuted { .. }))
+    }
+}
+
+fn serialize_literal<S: Serializer>(value: &Value, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(&clarity_literal(value))
+}
+
+fn serialize_literals<S: Serializer>(values: &[Value], s: S) -> Result<S::Ok, S::Error> {
+    s.collect_seq(values.iter().map(clarity_literal))
+}
+
+fn serialize_principal<S: Serializer>(principal: &PrincipalData, s: S) -> Result<S::Ok, S::Error> {
+    s.collect_str(principal)
+}
+
+/// Render an expression as Clarity source
+pub fn source(expr: &SymbolicExpression) -> String {
+    match &expr.expr {
+        SymbolicExpressionType::AtomValue(value) | SymbolicExpressionType::LiteralValue(value) => {
+            clarity_literal(value)
+        }
+        SymbolicExpressionType::Atom(name) => name.to_string(),
+        SymbolicExpressionType::List(list) => {
+            let items: Vec<_> = list.iter().map(source).collect();
+            format!("({})", items.join(" "))
+        }
+        SymbolicExpressionType::Field(field) => format!("'{field}"),
+        SymbolicExpressionType::TraitReference(name, _) => format!("<{name}>"),
+    }
+}
+
+/// The operations which can fail, in source order
+fn collect_sites<'a>(
+    executor: &Executor,
+    function: &ClarityName,
+    expr: &'a SymbolicExpression,
+    sites: &mut Vec<(ClarityName, &'a SymbolicExpression, Property)>,
+) {
+    if let Some(property) = executor.property(expr) {
+        sites.push((function.clone(), expr, property));
+    }
+    for child in expr.match_list().unwrap_or_default() {
+        collect_sites(executor, function, child, sites);
+    }
+}
+
+/// A value of a type which the executor did not model
+fn default_value(ty: &TypeSignature, senders: &[PrincipalData]) -> Value {
+    match ty {
+        TypeSignature::IntType => Value::Int(0),
+        TypeSignature::UIntType => Value::UInt(0),
+        TypeSignature::BoolType => Value::Bool(false),
+        TypeSignature::PrincipalType | TypeSignature::CallableType(_) => {
+            Value::Principal(senders[0].clone())
+        }
+        TypeSignature::SequenceType(SequenceSubtype::BufferType(_)) => {
+            Value::buff_from(vec![]).unwrap_or_else(|_| Value::none())
+        }
+        TypeSignature::SequenceType(SequenceSubtype::StringType(StringSubtype::ASCII(_))) => {
+            Value::string_ascii_from_bytes(vec![]).unwrap_or_else(|_| Value::none())
+        }
+        TypeSignature::SequenceType(SequenceSubtype::StringType(StringSubtype::UTF8(_))) => {
+            Value::string_utf8_from_bytes(vec![]).unwrap_or_else(|_| Value::none())
+        }
+        TypeSignature::SequenceType(SequenceSubtype::ListType(_)) => {
+            Value::cons_list_unsanitized(vec![]).unwrap_or_else(|_| Value::none())
+        }
+        TypeSignature::OptionalType(_) => Value::none(),
+        TypeSignature::ResponseType(inner) => {
+            Value::okay(default_value(&inner.0, senders)).unwrap_or_else(|_| Value::none())
+        }
+        TypeSignature::TupleType(tuple) => {
+            let fields = tuple
+                .get_type_map()
+                .iter()
+                .map(|(name, ty)| (name.clone(), default_value(ty, senders)))
+                .collect();
+            TupleData::from_data(fields)
+                .map(Value::from)
+                .unwrap_or_else(|_| Value::none())
+        }
+        _ => Value::none(),
+    }
+}
+
+/// The value of a symbolic value of type `ty` under a model
+fn concretize(
+    value: &SymValue,
+    ty: &TypeSignature,
+    model: &[Concrete],
+    senders: &[PrincipalData],
+) -> Option<Value> {
+    match (value, ty) {
+        (SymValue::Scalar(term), _) => term.eval(model).map(|c| c.to_value()),
+        (SymValue::Optional { is_some, value }, TypeSignature::OptionalType(inner)) => {
+            match is_some.eval(model)? {
+                Concrete::Bool(true) => {
+                    let inner = concretize(value, inner, model, senders)
+                        .unwrap_or_else(|| default_value(inner, senders));
+                    Value::some(inner).ok()
+                }
+                _ => Some(Value::none()),
+            }
+        }
+        (SymValue::Response { is_ok, ok, err }, TypeSignature::ResponseType(inner)) => {
+            let (committed, data, data_type) = match is_ok.eval(model)? {
+                Concrete::Bool(true) => (true, ok, &inner.0),
+                _ => (false, err, &inner.1),
+            };
+            let data = concretize(data, data_type, model, senders)
+                .unwrap_or_else(|| default_value(data_type, senders));
+            if committed {
+                Value::okay(data).ok()
+            } else {
+                Value::error(data).ok()
+            }
+        }
+        (SymValue::Tuple(fields), TypeSignature::TupleType(tuple)) => {
+            let data = tuple
+                .get_type_map()
+                .iter()
+                .map(|(name, ty)| {
+                    let field = fields
+                        .get(name)
+                        .and_then(|field| concretize(field, ty, model, senders))
+                        .unwrap_or_else(|| default_value(ty, senders));
+                    (name.clone(), field)
+                })
+                .collect();
+            TupleData::from_data(data).map(Value::from).ok()
+        }
+        _ => None,
+    }
+}
+
+fn counterexample(
+    executor: &Executor,
+    obligation: &Obligation,
+    model: &[Concrete],
+    senders: &[PrincipalData],
+) -> Counterexample {
+    let entry = &executor.entries[obligation.entry];
+    let args = entry
+        .args
+        .iter()
+        .map(|(ty, value)| {
+            concretize(value, ty, model, senders).unwrap_or_else(|| default_value(ty, senders))
+        })
+        .collect();
+    let sender = match entry.sender.eval(model) {
+        Some(Concrete::Principal(sender)) => sender,
+        _ => senders[0].clone(),
+    };
+
+    let mut symbols = BTreeSet::new();
+    for constraint in obligation.constraints.iter() {
+        constraint.for_each_symbol(&mut |index, _| {
+            symbols.insert(index);
+        });
+    }
+    let assumptions = symbols
+        .into_iter()
+        .filter(|index| !executor.symbols[*index].input)
+        .map(|index| Assumption {
+            expression: executor.symbols[index].description.clone(),
+            value: model[index].to_value(),
+        })
+        .collect();
+
+    Counterexample {
+        function: entry.function.to_string(),
+        sender,
+        args,
+        assumptions,
+    }
+}
+
+fn verdict(
+    executor: &Executor,
+    solver: &Solver,
+    senders: &[PrincipalData],
+    function: &ClarityName,
+    site: &SymbolicExpression,
+) -> Verdict {
+    let obligations: Vec<_> = executor
+        .obligations
+        .iter()
+        .filter(|obligation| obligation.site == site.id)
+        .collect();
+    let mut undecided = false;
+    for obligation in obligations.iter() {
+        match solver.check(&obligation.constraints) {
+            Outcome::Sat(model) => {
+                return Verdict::Refuted {
+                    counterexample: counterexample(executor, obligation, &model, senders),
+                }
+            }
+            Outcome::Unknown => undecided = true,
+            Outcome::Unsat => {}
+        }
+    }
+
+    let unknown = |reason: String| Verdict::Unknown { reason };
+    if undecided {
+        return unknown("the solver could not decide whether it can fail".into());
+    }
+    let incomplete = executor.entries.iter().any(|entry| {
+        !entry.complete
+            && (entry.function == *function
+                || obligations.iter().any(|obligation| {
+                    executor.entries[obligation.entry].function == entry.function
+                }))
+    });
+    if incomplete {
+        return unknown("there are too many paths to explore them all".into());
+    }
+    if executor.indirect.contains(function) {
+        return unknown(format!(
+            "`{function}` is passed to `map`, `filter` or `fold`, whose calls are not explored"
+        ));
+    }
+    let is_entry = executor
+        .entries
+        .iter()
+        .any(|entry| entry.function == *function);
+    if !is_entry && !executor.called.contains(function) {
+        return unknown(format!(
+            "`{function}` is never called from a public or read-only function"
+        ));
+    }
+    Verdict::Proved
+}
+
+/// Check every operation of a contract which can fail. Principal symbols
+///  take their values from `senders` (which must not be empty) and from the
+///  principals the contract mentions.
+pub fn verify(analysis: &ContractAnalysis, senders: &[PrincipalData]) -> VerificationReport {
+    let mut executor = Executor::new(analysis, senders.to_vec());
+    let mut sites = vec![];
+    let mut entries = vec![];
+    for expr in analysis.expressions.iter() {
+        let (signature, body, entry) = match DefineFunctionsParsed::try_parse(expr).ok().flatten() {
+            Some(DefineFunctionsParsed::PrivateFunction { signature, body }) => {
+                (signature, body, false)
+            }
+            Some(DefineFunctionsParsed::ReadOnlyFunction { signature, body })
+            | Some(DefineFunctionsParsed::PublicFunction { signature, body }) => {
+                (signature, body, true)
+            }
+            _ => continue,
+        };
+        let Some(name) = signature.first().and_then(|name| name.match_atom()) else {
+            continue;
+        };
+        if !executor.is_function(name) {
+            continue;
+        }
+        collect_sites(&executor, name, body, &mut sites);
+        if entry {
+            entries.push(name.clone());
+        }
+    }
+    for name in entries.iter() {
+        executor.explore(name);
+    }
+
+    let sorts: Vec<_> = executor.symbols.iter().map(|symbol| symbol.sort).collect();
+    let solver = Solver {
+        sorts: &sorts,
+        principals: senders,
+    };
+    let properties = sites
+        .into_iter()
+        .map(|(function, site, property)| PropertyReport {
+            property,
+            verdict: verdict(&executor, &solver, senders, &function, site),
+            function: function.to_string(),
+            expression: source(site),
+            line: Some(site.span().start_line).filter(|line| *line > 0),
+        })
+        .collect();
+    VerificationReport { properties }
+}
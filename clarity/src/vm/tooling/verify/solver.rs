@@ -0,0 +1,509 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A small decision procedure for conjunctions of terms.
+//!
+//! The conjunction is put in disjunctive normal form, and the integer
+//! literals of each case are read as linear inequalities, in which every
+//! subterm that is not linear (a product of two symbols, a quotient) is an
+//! opaque variable. Fourier-Motzkin elimination then decides whether the
+//! inequalities have a solution. It is exact over the rationals, and so
+//! sound (though incomplete) for proving that a case has no integer
+//! solution; when every case has none, the conjunction is unsatisfiable.
+//!
+//! Otherwise, a satisfying assignment is searched for among values built
+//! from the constants of the conjunction, and checked by evaluating the
+//! conjunction. If neither a proof nor an assignment is found, the answer
+//! is unknown.
+
+use std::collections::BTreeMap;
+
+use hashbrown::HashSet;
+
+use super::term::{ArithOp, CompareOp, Concrete, Sort, Term};
+use crate::vm::types::PrincipalData;
+
+/// The most cases the disjunctive normal form may have before the solver
+///  gives up on proving unsatisfiability
+const MAX_CASES: usize = 256;
+/// The most inequalities elimination may produce before it gives up
+const MAX_INEQUALITIES: usize = 2048;
+/// The most assignments evaluated while searching for a model
+const MAX_EVALUATIONS: usize = 4096;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Unsat,
+    /// A value for every symbol, which satisfies the conjunction
+    Sat(Vec<Concrete>),
+    Unknown,
+}
+
+pub struct Solver<'a> {
+    /// The sort of every symbol, by index
+    pub sorts: &'a [Sort],
+    /// The principals tried as values of principal symbols, besides the
+    ///  constants of the conjunction. Must not be empty.
+    pub principals: &'a [PrincipalData],
+}
+
+impl Solver<'_> {
+    /// Whether the conjunction of `constraints` is certainly unsatisfiable.
+    /// Cheaper than `check()`, as it never searches for a model.
+    pub fn is_unsat(&self, constraints: &[Term]) -> bool {
+        let conjunction = Term::and(constraints.to_vec());
+        match dnf(&conjunction, true) {
+            Some(cases) => cases.iter().all(|case| is_infeasible(case)),
+            None => false,
+        }
+    }
+
+    /// Decide whether the conjunction of `constraints` is satisfiable
+    pub fn check(&self, constraints: &[Term]) -> Outcome {
+        let conjunction = Term::and(constraints.to_vec());
+        let mut evaluations = 0;
+        match dnf(&conjunction, true) {
+            Some(cases) => {
+                let feasible: Vec<_> = cases.iter().filter(|case| !is_infeasible(case)).collect();
+                if feasible.is_empty() {
+                    return Outcome::Unsat;
+                }
+                for case in feasible.iter() {
+                    if let Some(model) = self.search(&conjunction, case, &mut evaluations) {
+                        return Outcome::Sat(model);
+                    }
+                }
+            }
+            None => {
+                let literals = [conjunction.clone()];
+                if let Some(model) = self.search(&conjunction, &literals, &mut evaluations) {
+                    return Outcome::Sat(model);
+                }
+            }
+        }
+        Outcome::Unknown
+    }
+
+    fn default_value(&self, sort: Sort) -> Concrete {
+        match sort {
+            Sort::Int => Concrete::Int(0),
+            Sort::UInt => Concrete::UInt(0),
+            Sort::Bool => Concrete::Bool(false),
+            Sort::Principal => Concrete::Principal(self.principals[0].clone()),
+        }
+    }
+
+    /// Search for an assignment satisfying `conjunction`, trying the values
+    ///  suggested by one of its cases
+    fn search(
+        &self,
+        conjunction: &Term,
+        case: &[Term],
+        evaluations: &mut usize,
+    ) -> Option<Vec<Concrete>> {
+        let mut constants = vec![];
+        for literal in case.iter() {
+            literal.for_each_const(&mut |c| {
+                if !constants.contains(c) {
+                    constants.push(c.clone())
+                }
+            });
+        }
+        let mut symbols = vec![];
+        conjunction.for_each_symbol(&mut |index, _| {
+            if !symbols.contains(&index) {
+                symbols.push(index);
+            }
+        });
+
+        let candidates: Vec<Vec<Concrete>> = symbols
+            .iter()
+            .map(|index| self.candidates(self.sorts[*index], &constants))
+            .collect();
+
+        let mut model: Vec<_> = self
+            .sorts
+            .iter()
+            .map(|sort| self.default_value(*sort))
+            .collect();
+        let mut try_choice = |choice: &[usize], evaluations: &mut usize| {
+            *evaluations += 1;
+            for (i, index) in symbols.iter().enumerate() {
+                model[*index] = candidates[i][choice[i]].clone();
+            }
+            match conjunction.eval(&model) {
+                Some(Concrete::Bool(true)) => Some(model.clone()),
+                _ => None,
+            }
+        };
+
+        // first the most likely value of every symbol, then every variation
+        //  of one symbol, and then pseudo-random assignments
+        let mut choice = vec![0; symbols.len()];
+        if let Some(model) = try_choice(&choice, evaluations) {
+            return Some(model);
+        }
+        for i in 0..choice.len() {
+            for value in 1..candidates[i].len() {
+                if *evaluations >= MAX_EVALUATIONS {
+                    return None;
+                }
+                choice[i] = value;
+                if let Some(model) = try_choice(&choice, evaluations) {
+                    return Some(model);
+                }
+            }
+            choice[i] = 0;
+        }
+        let mut seed: u64 = 0x9e37_79b9_7f4a_7c15;
+        while *evaluations < MAX_EVALUATIONS && !choice.is_empty() {
+            for (i, value) in choice.iter_mut().enumerate() {
+                seed = seed
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                *value = ((seed >> 33) as usize) % candidates[i].len();
+            }
+            if let Some(model) = try_choice(&choice, evaluations) {
+                return Some(model);
+            }
+        }
+        None
+    }
+
+    /// The values tried for a symbol of a sort, most promising first
+    fn candidates(&self, sort: Sort, constants: &[Concrete]) -> Vec<Concrete> {
+        let mut values = vec![];
+        match sort {
+            Sort::Int | Sort::UInt => {
+                let integers: Vec<_> = constants.iter().filter_map(Concrete::as_i128).collect();
+                let mut tried = vec![0, 1];
+                for constant in integers.iter() {
+                    tried.push(*constant);
+                    tried.extend(constant.checked_add(1));
+                    tried.extend(constant.checked_sub(1));
+                }
+                // sums and differences of constants solve literals relating
+                //  two symbols to a constant
+                for a in integers.iter().take(8) {
+                    for b in integers.iter().take(8) {
+                        tried.extend(a.checked_add(*b));
+                        tried.extend(a.checked_sub(*b));
+                        tried.extend(a.checked_sub(*b).and_then(|d| d.checked_add(1)));
+                    }
+                }
+                tried.push(2);
+                values.extend(tried.into_iter().filter_map(|i| Concrete::integer(sort, i)));
+                match sort {
+                    Sort::Int => values.extend([
+                        Concrete::Int(-1),
+                        Concrete::Int(i128::MAX),
+                        Concrete::Int(i128::MIN),
+                    ]),
+                    _ => values.push(Concrete::UInt(u128::MAX)),
+                }
+                values.extend(constants.iter().filter(|c| c.sort() == sort).cloned());
+            }
+            Sort::Bool => values.extend([Concrete::Bool(true), Concrete::Bool(false)]),
+            Sort::Principal => {
+                values.extend(constants.iter().filter(|c| c.sort() == sort).cloned());
+                values.extend(self.principals.iter().cloned().map(Concrete::Principal));
+            }
+        }
+        let mut seen = HashSet::new();
+        values.retain(|value| seen.insert(value.clone()));
+        values
+    }
+}
+
+/// The disjunctive normal form of `term` (or of its negation), as a list of
+///  cases, each a conjunction of literals. `None` if there are too many cases.
+fn dnf(term: &Term, positive: bool) -> Option<Vec<Vec<Term>>> {
+    let product = |terms: &[Term], positive: bool| {
+        let mut cases = vec![vec![]];
+        for term in terms.iter() {
+            let alternatives = dnf(term, positive)?;
+            let mut next = vec![];
+            for case in cases.iter() {
+                for alternative in alternatives.iter() {
+                    let mut combined: Vec<Term> = Vec::clone(case);
+                    combined.extend(alternative.iter().cloned());
+                    next.push(combined);
+                }
+            }
+            if next.len() > MAX_CASES {
+                return None;
+            }
+            cases = next;
+        }
+        Some(cases)
+    };
+    let union = |terms: &[Term], positive: bool| {
+        let mut cases = vec![];
+        for term in terms.iter() {
+            cases.extend(dnf(term, positive)?);
+            if cases.len() > MAX_CASES {
+                return None;
+            }
+        }
+        Some(cases)
+    };
+    match term {
+        Term::Const(Concrete::Bool(b)) => Some(if *b == positive { vec![vec![]] } else { vec![] }),
+        Term::And(terms) if positive => product(terms, true),
+        Term::And(terms) => union(terms, false),
+        Term::Or(terms) if positive => union(terms, true),
+        Term::Or(terms) => product(terms, false),
+        Term::Not(inner) => dnf(inner, !positive),
+        Term::Eq(a, b) if !positive && matches!(a.sort(), Sort::Int | Sort::UInt) => Some(vec![
+            vec![Term::compare(CompareOp::Lt, (**a).clone(), (**b).clone())],
+            vec![Term::compare(CompareOp::Gt, (**a).clone(), (**b).clone())],
+        ]),
+        literal if positive => Some(vec![vec![literal.clone()]]),
+        literal => Some(vec![vec![Term::negate(literal.clone())]]),
+    }
+}
+
+/// A linear combination of variables plus a constant. Variables are the
+///  indexes of the opaque subterms of a case.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+struct Linear {
+    coefficients: BTreeMap<usize, i128>,
+    constant: i128,
+}
+
+impl Linear {
+    fn constant(constant: i128) -> Linear {
+        Linear {
+            coefficients: BTreeMap::new(),
+            constant,
+        }
+    }
+
+    fn scale(&self, factor: i128) -> Option<Linear> {
+        let mut coefficients = BTreeMap::new();
+        for (variable, coefficient) in self.coefficients.iter() {
+            coefficients.insert(*variable, coefficient.checked_mul(factor)?);
+        }
+        Some(Linear {
+            coefficients,
+            constant: self.constant.checked_mul(factor)?,
+        })
+    }
+
+    fn add(&self, other: &Linear) -> Option<Linear> {
+        let mut sum = self.clone();
+        for (variable, coefficient) in other.coefficients.iter() {
+            let entry = sum.coefficients.entry(*variable).or_insert(0);
+            *entry = entry.checked_add(*coefficient)?;
+            if *entry == 0 {
+                sum.coefficients.remove(variable);
+            }
+        }
+        sum.constant = sum.constant.checked_add(other.constant)?;
+        Some(sum)
+    }
+
+    fn sub(&self, other: &Linear) -> Option<Linear> {
+        self.add(&other.scale(-1)?)
+    }
+
+    /// Divide the inequality `self <= 0` by the greatest common divisor of
+    ///  its coefficients, rounding its constant up, which is exact for
+    ///  integer variables
+    fn normalize(mut self) -> Linear {
+        let divisor = self
+            .coefficients
+            .values()
+            .fold(0u128, |g, c| gcd(g, c.unsigned_abs()));
+        if divisor > 1 {
+            let divisor = divisor as i128;
+            for coefficient in self.coefficients.values_mut() {
+                *coefficient /= divisor;
+            }
+            self.constant = self.constant.div_euclid(divisor)
+                + i128::from(self.constant.rem_euclid(divisor) != 0);
+        }
+        self
+    }
+}
+
+fn gcd(a: u128, b: u128) -> u128 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// The linear inequalities (each `expr <= 0`) implied by a case
+struct Inequalities {
+    variables: Vec<Term>,
+    inequalities: Vec<Linear>,
+}
+
+impl Inequalities {
+    fn variable(&mut self, term: &Term) -> Linear {
+        let index = match self.variables.iter().position(|variable| variable == term) {
+            Some(index) => index,
+            None => {
+                self.variables.push(term.clone());
+                // unsigned subterms are never negative
+                if term.sort() == Sort::UInt {
+                    let mut negated = Linear::default();
+                    negated.coefficients.insert(self.variables.len() - 1, -1);
+                    self.inequalities.push(negated);
+                }
+                self.variables.len() - 1
+            }
+        };
+        let mut linear = Linear::default();
+        linear.coefficients.insert(index, 1);
+        linear
+    }
+
+    /// Read an integer term as a linear combination. A term only appears in a
+    ///  literal which holds if it evaluates, and an unsigned subtraction only
+    ///  evaluates if its result is not negative.
+    fn linearize(&mut self, term: &Term) -> Option<Linear> {
+        match term {
+            Term::Const(c) => Some(Linear::constant(c.as_i128()?)),
+            Term::Arith(ArithOp::Add, a, b) => self.linearize(a)?.add(&self.linearize(b)?),
+            Term::Arith(ArithOp::Sub, a, b) => {
+                let difference = self.linearize(a)?.sub(&self.linearize(b)?)?;
+                if term.sort() == Sort::UInt {
+                    self.inequalities.extend(difference.scale(-1));
+                }
+                Some(difference)
+            }
+            Term::Arith(ArithOp::Mul, a, b) => match (&**a, &**b) {
+                (Term::Const(c), other) | (other, Term::Const(c)) => {
+                    self.linearize(other)?.scale(c.as_i128()?)
+                }
+                _ => Some(self.variable(term)),
+            },
+            _ => Some(self.variable(term)),
+        }
+    }
+
+    /// Add the inequalities of `a op b`, or nothing if it is not linear
+    fn compare(&mut self, op: CompareOp, a: &Term, b: &Term) {
+        let (Some(a), Some(b)) = (self.linearize(a), self.linearize(b)) else {
+            return;
+        };
+        let inequality = match op {
+            CompareOp::Le => a.sub(&b),
+            CompareOp::Lt => a.sub(&b).and_then(|d| d.add(&Linear::constant(1))),
+            CompareOp::Ge => b.sub(&a),
+            CompareOp::Gt => b.sub(&a).and_then(|d| d.add(&Linear::constant(1))),
+        };
+        self.inequalities.extend(inequality);
+    }
+}
+
+/// Whether a case certainly has no solution
+fn is_infeasible(case: &[Term]) -> bool {
+    // a literal and its complement
+    for literal in case.iter() {
+        if let Term::Not(inner) = literal {
+            if case.contains(inner) {
+                return true;
+            }
+        }
+    }
+
+    let mut system = Inequalities {
+        variables: vec![],
+        inequalities: vec![],
+    };
+    for literal in case.iter() {
+        match literal {
+            Term::Compare(op, a, b) => system.compare(*op, a, b),
+            Term::Eq(a, b) if matches!(a.sort(), Sort::Int | Sort::UInt) => {
+                system.compare(CompareOp::Le, a, b);
+                system.compare(CompareOp::Ge, a, b);
+            }
+            _ => {}
+        }
+    }
+    eliminate(system.inequalities)
+}
+
+/// Fourier-Motzkin elimination: whether the inequalities have no solution
+fn eliminate(inequalities: Vec<Linear>) -> bool {
+    let mut inequalities: Vec<Linear> = inequalities
+        .into_iter()
+        .map(Linear::normalize)
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    loop {
+        if inequalities
+            .iter()
+            .any(|inequality| inequality.coefficients.is_empty() && inequality.constant > 0)
+        {
+            return true;
+        }
+        inequalities.retain(|inequality| !inequality.coefficients.is_empty());
+
+        // eliminate the variable which produces the fewest inequalities
+        let mut counts: BTreeMap<usize, (usize, usize)> = BTreeMap::new();
+        for inequality in inequalities.iter() {
+            for (variable, coefficient) in inequality.coefficients.iter() {
+                let count = counts.entry(*variable).or_default();
+                if *coefficient > 0 {
+                    count.0 += 1;
+                } else {
+                    count.1 += 1;
+                }
+            }
+        }
+        let Some((&variable, _)) = counts
+            .iter()
+            .min_by_key(|(_, (upper, lower))| upper * lower)
+        else {
+            return false;
+        };
+
+        let mut next = HashSet::new();
+        let (mut upper, mut lower) = (vec![], vec![]);
+        for inequality in inequalities.into_iter() {
+            match inequality.coefficients.get(&variable).copied() {
+                Some(c) if c > 0 => upper.push((c, inequality)),
+                Some(c) => lower.push((c, inequality)),
+                None => {
+                    next.insert(inequality);
+                }
+            }
+        }
+        for (a, upper) in upper.iter() {
+            for (b, lower) in lower.iter() {
+                // a * x + ... <= 0 and b * x + ... <= 0 with b < 0; an
+                //  inequality which overflows is dropped, which only
+                //  weakens the system
+                let combined = upper
+                    .scale(-*b)
+                    .zip(lower.scale(*a))
+                    .and_then(|(u, l)| u.add(&l));
+                if let Some(combined) = combined {
+                    next.insert(combined.normalize());
+                }
+            }
+        }
+        if next.len() > MAX_INEQUALITIES {
+            return false;
+        }
+        inequalities = next.into_iter().collect();
+    }
+}
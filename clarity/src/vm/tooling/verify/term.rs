@@ -0,0 +1,322 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The constraint language of the symbolic executor: terms over the
+//! integer, boolean and principal values of a function.
+
+use crate::vm::types::{PrincipalData, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Sort {
+    Int,
+    UInt,
+    Bool,
+    Principal,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Concrete {
+    Int(i128),
+    UInt(u128),
+    Bool(bool),
+    Principal(PrincipalData),
+}
+
+impl Concrete {
+    pub fn sort(&self) -> Sort {
+        match self {
+            Concrete::Int(_) => Sort::Int,
+            Concrete::UInt(_) => Sort::UInt,
+            Concrete::Bool(_) => Sort::Bool,
+            Concrete::Principal(_) => Sort::Principal,
+        }
+    }
+
+    pub fn from_value(value: &Value) -> Option<Concrete> {
+        match value {
+            Value::Int(i) => Some(Concrete::Int(*i)),
+            Value::UInt(u) => Some(Concrete::UInt(*u)),
+            Value::Bool(b) => Some(Concrete::Bool(*b)),
+            Value::Principal(p) => Some(Concrete::Principal(p.clone())),
+            _ => None,
+        }
+    }
+
+    pub fn to_value(&self) -> Value {
+        match self {
+            Concrete::Int(i) => Value::Int(*i),
+            Concrete::UInt(u) => Value::UInt(*u),
+            Concrete::Bool(b) => Value::Bool(*b),
+            Concrete::Principal(p) => Value::Principal(p.clone()),
+        }
+    }
+
+    /// The value of an integer constant, if it fits an `i128`
+    pub fn as_i128(&self) -> Option<i128> {
+        match self {
+            Concrete::Int(i) => Some(*i),
+            Concrete::UInt(u) => i128::try_from(*u).ok(),
+            _ => None,
+        }
+    }
+
+    /// The integer constant of `sort` with the value `i`, if it is in range
+    pub fn integer(sort: Sort, i: i128) -> Option<Concrete> {
+        match sort {
+            Sort::Int => Some(Concrete::Int(i)),
+            Sort::UInt => u128::try_from(i).ok().map(Concrete::UInt),
+            Sort::Bool | Sort::Principal => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArithOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CompareOp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl CompareOp {
+    pub fn negate(self) -> CompareOp {
+        match self {
+            CompareOp::Lt => CompareOp::Ge,
+            CompareOp::Le => CompareOp::Gt,
+            CompareOp::Gt => CompareOp::Le,
+            CompareOp::Ge => CompareOp::Lt,
+        }
+    }
+
+    fn holds<T: Ord>(self, a: T, b: T) -> bool {
+        match self {
+            CompareOp::Lt => a < b,
+            CompareOp::Le => a <= b,
+            CompareOp::Gt => a > b,
+            CompareOp::Ge => a >= b,
+        }
+    }
+}
+
+/// A term is built by the smart constructors below, which fold constants,
+/// so that a term is only compound if it depends on a symbol.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Term {
+    Const(Concrete),
+    /// The index of a symbol in the executor's symbol table
+    Symbol(usize, Sort),
+    Arith(ArithOp, Box<Term>, Box<Term>),
+    Compare(CompareOp, Box<Term>, Box<Term>),
+    Eq(Box<Term>, Box<Term>),
+    Not(Box<Term>),
+    And(Vec<Term>),
+    Or(Vec<Term>),
+}
+
+impl Term {
+    pub fn bool(b: bool) -> Term {
+        Term::Const(Concrete::Bool(b))
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Term::Const(Concrete::Bool(b)) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn sort(&self) -> Sort {
+        match self {
+            Term::Const(c) => c.sort(),
+            Term::Symbol(_, sort) => *sort,
+            Term::Arith(_, a, _) => a.sort(),
+            Term::Compare(..) | Term::Eq(..) | Term::Not(_) | Term::And(_) | Term::Or(_) => {
+                Sort::Bool
+            }
+        }
+    }
+
+    pub fn arith(op: ArithOp, a: Term, b: Term) -> Term {
+        let term = Term::Arith(op, Box::new(a), Box::new(b));
+        match term.fold() {
+            Some(c) => Term::Const(c),
+            // an operation on constants which fails at runtime stays symbolic,
+            //  and so never evaluates
+            None => term,
+        }
+    }
+
+    pub fn compare(op: CompareOp, a: Term, b: Term) -> Term {
+        let term = Term::Compare(op, Box::new(a), Box::new(b));
+        term.fold().map(Term::Const).unwrap_or(term)
+    }
+
+    pub fn eq(a: Term, b: Term) -> Term {
+        if a == b {
+            return Term::bool(true);
+        }
+        let term = Term::Eq(Box::new(a), Box::new(b));
+        term.fold().map(Term::Const).unwrap_or(term)
+    }
+
+    pub fn negate(a: Term) -> Term {
+        match a {
+            Term::Const(Concrete::Bool(b)) => Term::bool(!b),
+            Term::Not(inner) => *inner,
+            Term::Compare(op, a, b) => Term::Compare(op.negate(), a, b),
+            other => Term::Not(Box::new(other)),
+        }
+    }
+
+    pub fn and(terms: Vec<Term>) -> Term {
+        let mut conjuncts = vec![];
+        for term in terms {
+            match term {
+                Term::Const(Concrete::Bool(true)) => {}
+                Term::Const(Concrete::Bool(false)) => return Term::bool(false),
+                Term::And(inner) => conjuncts.extend(inner),
+                other => conjuncts.push(other),
+            }
+        }
+        match conjuncts.len() {
+            0 => Term::bool(true),
+            1 => conjuncts.remove(0),
+            _ => Term::And(conjuncts),
+        }
+    }
+
+    pub fn or(terms: Vec<Term>) -> Term {
+        let mut disjuncts = vec![];
+        for term in terms {
+            match term {
+                Term::Const(Concrete::Bool(false)) => {}
+                Term::Const(Concrete::Bool(true)) => return Term::bool(true),
+                Term::Or(inner) => disjuncts.extend(inner),
+                other => disjuncts.push(other),
+            }
+        }
+        match disjuncts.len() {
+            0 => Term::bool(false),
+            1 => disjuncts.remove(0),
+            _ => Term::Or(disjuncts),
+        }
+    }
+
+    fn fold(&self) -> Option<Concrete> {
+        self.eval(&[])
+    }
+
+    /// Evaluate the term under an assignment of its symbols, as the Clarity
+    /// runtime would. Returns `None` if a symbol is unassigned, or if an
+    /// operation fails at runtime (overflow, underflow, division by zero).
+    pub fn eval(&self, model: &[Concrete]) -> Option<Concrete> {
+        match self {
+            Term::Const(c) => Some(c.clone()),
+            Term::Symbol(index, _) => model.get(*index).cloned(),
+            Term::Arith(op, a, b) => match (a.eval(model)?, b.eval(model)?) {
+                (Concrete::Int(a), Concrete::Int(b)) => {
+                    let result = match op {
+                        ArithOp::Add => a.checked_add(b),
+                        ArithOp::Sub => a.checked_sub(b),
+                        ArithOp::Mul => a.checked_mul(b),
+                        ArithOp::Div => a.checked_div(b),
+                        ArithOp::Mod => a.checked_rem(b),
+                    };
+                    result.map(Concrete::Int)
+                }
+                (Concrete::UInt(a), Concrete::UInt(b)) => {
+                    let result = match op {
+                        ArithOp::Add => a.checked_add(b),
+                        ArithOp::Sub => a.checked_sub(b),
+                        ArithOp::Mul => a.checked_mul(b),
+                        ArithOp::Div => a.checked_div(b),
+                        ArithOp::Mod => a.checked_rem(b),
+                    };
+                    result.map(Concrete::UInt)
+                }
+                _ => None,
+            },
+            Term::Compare(op, a, b) => match (a.eval(model)?, b.eval(model)?) {
+                (Concrete::Int(a), Concrete::Int(b)) => Some(Concrete::Bool(op.holds(a, b))),
+                (Concrete::UInt(a), Concrete::UInt(b)) => Some(Concrete::Bool(op.holds(a, b))),
+                _ => None,
+            },
+            Term::Eq(a, b) => Some(Concrete::Bool(a.eval(model)? == b.eval(model)?)),
+            Term::Not(a) => match a.eval(model)? {
+                Concrete::Bool(b) => Some(Concrete::Bool(!b)),
+                _ => None,
+            },
+            // `and` and `or` short-circuit, so that a guard protects the
+            //  evaluation of the terms after it
+            Term::And(terms) => {
+                for term in terms {
+                    if term.eval(model)? == Concrete::Bool(false) {
+                        return Some(Concrete::Bool(false));
+                    }
+                }
+                Some(Concrete::Bool(true))
+            }
+            Term::Or(terms) => {
+                for term in terms {
+                    if term.eval(model)? == Concrete::Bool(true) {
+                        return Some(Concrete::Bool(true));
+                    }
+                }
+                Some(Concrete::Bool(false))
+            }
+        }
+    }
+
+    /// Call `f` on every constant in the term
+    pub fn for_each_const(&self, f: &mut impl FnMut(&Concrete)) {
+        match self {
+            Term::Const(c) => f(c),
+            Term::Symbol(..) => {}
+            Term::Arith(_, a, b) | Term::Compare(_, a, b) | Term::Eq(a, b) => {
+                a.for_each_const(f);
+                b.for_each_const(f);
+            }
+            Term::Not(a) => a.for_each_const(f),
+            Term::And(terms) | Term::Or(terms) => {
+                terms.iter().for_each(|term| term.for_each_const(f))
+            }
+        }
+    }
+
+    /// Call `f` on every symbol in the term
+    pub fn for_each_symbol(&self, f: &mut impl FnMut(usize, Sort)) {
+        match self {
+            Term::Const(_) => {}
+            Term::Symbol(index, sort) => f(*index, *sort),
+            Term::Arith(_, a, b) | Term::Compare(_, a, b) | Term::Eq(a, b) => {
+                a.for_each_symbol(f);
+                b.for_each_symbol(f);
+            }
+            Term::Not(a) => a.for_each_symbol(f),
+            Term::And(terms) | Term::Or(terms) => {
+                terms.iter().for_each(|term| term.for_each_symbol(f))
+            }
+        }
+    }
+}
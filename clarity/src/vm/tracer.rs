@@ -0,0 +1,330 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! An `EvalHook` which records a structured trace of an execution: the
+//! nested calls of user-defined functions, with their arguments and return
+//! values, and the data-var and map accesses made from each of them.
+//!
+//! A call is recognized by the evaluation of a function's body, so it is
+//! traced whether it was made from the same contract, by `contract-call?`, or
+//! as the entry point of a transaction. Values are written in the JSON
+//! encoding of `Value::to_json`.
+
+use std::collections::HashMap;
+
+use super::functions::NativeFunctions;
+use super::EvalHook;
+use crate::vm::contexts::{Environment, LocalContext};
+use crate::vm::errors::{Error, ShortReturnType};
+use crate::vm::types::QualifiedContractIdentifier;
+use crate::vm::{ClarityName, SymbolicExpression, Value};
+
+/// A call of a user-defined function
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CallTrace {
+    pub contract: String,
+    pub function: String,
+    pub sender: Option<String>,
+    pub caller: Option<String>,
+    pub args: Vec<TracedArgument>,
+    /// The returned value, unless the call failed with a runtime error
+    pub result: Option<serde_json::Value>,
+    pub error: Option<String>,
+    /// What the call did, in order
+    pub steps: Vec<TraceStep>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TracedArgument {
+    pub name: String,
+    pub value: serde_json::Value,
+}
+
+/// A data-var or map access
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StorageAccess {
+    /// The function which made the access, e.g. `map-set`
+    pub op: String,
+    pub contract: String,
+    /// The name of the data-var or map
+    pub name: String,
+    pub key: Option<serde_json::Value>,
+    /// The value being written
+    pub value: Option<serde_json::Value>,
+    pub result: Option<serde_json::Value>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TraceStep {
+    Call(CallTrace),
+    Read(StorageAccess),
+    Write(StorageAccess),
+}
+
+/// The steps of an execution which were not made from within a call, such
+///  as the top-level expressions of a contract being deployed
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ExecutionTrace {
+    pub steps: Vec<TraceStep>,
+}
+
+impl ExecutionTrace {
+    /// Every call in the trace, outermost first
+    pub fn calls(&self) -> Vec<&CallTrace> {
+        fn collect<'a>(steps: &'a [TraceStep], calls: &mut Vec<&'a CallTrace>) {
+            for step in steps {
+                if let TraceStep::Call(call) = step {
+                    calls.push(call);
+                    collect(&call.steps, calls);
+                }
+            }
+        }
+        let mut calls = vec![];
+        collect(&self.steps, &mut calls);
+        calls
+    }
+}
+
+/// An expression which is being evaluated
+struct Frame {
+    id: u64,
+    /// Set if the expression is the body of a call
+    is_call: bool,
+    /// Set if the expression accesses storage: the accessing function, the
+    ///  name of the data-var or map, and the values of the arguments
+    ///  evaluated so far
+    access: Option<(NativeFunctions, String, Vec<Value>)>,
+}
+
+pub struct ExecutionTracer {
+    stack: Vec<Frame>,
+    /// The calls which have begun but not finished, innermost last
+    calls: Vec<CallTrace>,
+    trace: ExecutionTrace,
+    /// The names of each contract's functions, by the expression id of their bodies
+    function_bodies: HashMap<QualifiedContractIdentifier, HashMap<u64, ClarityName>>,
+}
+
+impl Default for ExecutionTracer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn json_of_result(res: &Result<Value, Error>) -> (Option<serde_json::Value>, Option<String>) {
+    match res {
+        Ok(value) => (Some(value.to_json()), None),
+        // an early return from a function body is the function's result
+        Err(Error::ShortReturn(ShortReturnType::ExpectedValue(value)))
+        | Err(Error::ShortReturn(ShortReturnType::AssertionFailed(value))) => {
+            (Some(value.to_json()), None)
+        }
+        Err(e) => (None, Some(e.to_string())),
+    }
+}
+
+impl ExecutionTracer {
+    pub fn new() -> ExecutionTracer {
+        ExecutionTracer {
+            stack: vec![],
+            calls: vec![],
+            trace: ExecutionTrace::default(),
+            function_bodies: HashMap::new(),
+        }
+    }
+
+    /// Take the trace recorded so far, and start a new one
+    pub fn take_trace(&mut self) -> ExecutionTrace {
+        self.stack.clear();
+        self.calls.clear();
+        // the same contract name may be deployed with other code on another fork
+        self.function_bodies.clear();
+        std::mem::take(&mut self.trace)
+    }
+
+    fn push_step(&mut self, step: TraceStep) {
+        match self.calls.last_mut() {
+            Some(call) => call.steps.push(step),
+            None => self.trace.steps.push(step),
+        }
+    }
+
+    /// The function whose body is `expr`, if any
+    fn lookup_function_name(
+        &mut self,
+        env: &Environment,
+        expr: &SymbolicExpression,
+    ) -> Option<ClarityName> {
+        let functions = &env.contract_context.functions;
+        let bodies = self
+            .function_bodies
+            .entry(env.contract_context.contract_identifier.clone())
+            .or_default();
+        // a contract being deployed gains functions as it is evaluated
+        if bodies.len() != functions.len() {
+            *bodies = functions
+                .iter()
+                .map(|(name, function)| (function.get_body().id, name.clone()))
+                .collect();
+        }
+        bodies.get(&expr.id).cloned()
+    }
+
+    fn begin(&mut self, env: &mut Environment, context: &LocalContext, expr: &SymbolicExpression) {
+        let function = self
+            .lookup_function_name(env, expr)
+            .and_then(|name| Some((name.clone(), env.contract_context.functions.get(&name)?)));
+        if let Some((name, function)) = &function {
+            let args = function
+                .get_arguments()
+                .iter()
+                .map(|arg| {
+                    let value = match context.variables.get(arg) {
+                        Some(value) => value.to_json(),
+                        None => context
+                            .callable_contracts
+                            .get(arg)
+                            .map(|callable| {
+                                serde_json::Value::String(callable.contract_identifier.to_string())
+                            })
+                            .unwrap_or(serde_json::Value::Null),
+                    };
+                    TracedArgument {
+                        name: arg.to_string(),
+                        value,
+                    }
+                })
+                .collect();
+            self.calls.push(CallTrace {
+                contract: env.contract_context.contract_identifier.to_string(),
+                function: name.to_string(),
+                sender: env.sender.as_ref().map(|p| p.to_string()),
+                caller: env.caller.as_ref().map(|p| p.to_string()),
+                args,
+                result: None,
+                error: None,
+                steps: vec![],
+            });
+        }
+
+        let access = expr.match_list().and_then(|list| {
+            let function = NativeFunctions::lookup_by_name(list.first()?.match_atom()?)?;
+            match function {
+                NativeFunctions::FetchVar
+                | NativeFunctions::SetVar
+                | NativeFunctions::FetchEntry
+                | NativeFunctions::SetEntry
+                | NativeFunctions::InsertEntry
+                | NativeFunctions::DeleteEntry => {
+                    let name = list.get(1)?.match_atom()?.to_string();
+                    Some((function, name, vec![]))
+                }
+                _ => None,
+            }
+        });
+
+        self.stack.push(Frame {
+            id: expr.id,
+            is_call: function.is_some(),
+            access,
+        });
+    }
+
+    fn finish(
+        &mut self,
+        env: &mut Environment,
+        expr: &SymbolicExpression,
+        res: &Result<Value, Error>,
+    ) {
+        // expressions which fail before they are applied skip `did_finish_eval`
+        let frame = loop {
+            let Some(frame) = self.stack.pop() else {
+                return;
+            };
+            if frame.id == expr.id {
+                break frame;
+            }
+            if frame.is_call {
+                self.finish_call(res);
+            }
+        };
+
+        if let Some((function, name, args)) = frame.access {
+            let (result, error) = json_of_result(res);
+            let (key, value) = match function {
+                NativeFunctions::FetchVar => (None, None),
+                NativeFunctions::SetVar => (None, args.first()),
+                NativeFunctions::FetchEntry | NativeFunctions::DeleteEntry => (args.first(), None),
+                _ => (args.first(), args.get(1)),
+            };
+            let access = StorageAccess {
+                op: function.get_name(),
+                contract: env.contract_context.contract_identifier.to_string(),
+                name,
+                key: key.map(Value::to_json),
+                value: value.map(Value::to_json),
+                result,
+                error,
+            };
+            self.push_step(match function {
+                NativeFunctions::FetchVar | NativeFunctions::FetchEntry => TraceStep::Read(access),
+                _ => TraceStep::Write(access),
+            });
+        }
+
+        if frame.is_call {
+            self.finish_call(res);
+        }
+
+        if let (Ok(value), Some(parent)) = (res, self.stack.last_mut()) {
+            if let Some((_, _, args)) = parent.access.as_mut() {
+                args.push(value.clone());
+            }
+        }
+    }
+
+    fn finish_call(&mut self, res: &Result<Value, Error>) {
+        let Some(mut call) = self.calls.pop() else {
+            return;
+        };
+        (call.result, call.error) = json_of_result(res);
+        self.push_step(TraceStep::Call(call));
+    }
+}
+
+impl EvalHook for ExecutionTracer {
+    fn will_begin_eval(
+        &mut self,
+        env: &mut Environment,
+        context: &LocalContext,
+        expr: &SymbolicExpression,
+    ) {
+        self.begin(env, context, expr);
+    }
+
+    fn did_finish_eval(
+        &mut self,
+        env: &mut Environment,
+        _context: &LocalContext,
+        expr: &SymbolicExpression,
+        res: &Result<Value, Error>,
+    ) {
+        self.finish(env, expr, res);
+    }
+
+    fn did_complete(&mut self, _result: Result<&mut crate::vm::ExecutionResult, String>) {}
+}
@@ -29,8 +29,8 @@ use crate::vm::errors::{CheckErrors, Error as ClarityError, IncomparableError, I
 use crate::vm::representations::{ClarityName, ContractName, MAX_STRING_LEN};
 use crate::vm::types::{
     BufferLength, CallableData, CharType, OptionalData, PrincipalData, QualifiedContractIdentifier,
-    SequenceData, SequenceSubtype, StandardPrincipalData, StringSubtype, TupleData, TypeSignature,
-    Value, BOUND_VALUE_SERIALIZATION_BYTES, MAX_TYPE_DEPTH, MAX_VALUE_SIZE,
+    ResponseData, SequenceData, SequenceSubtype, StandardPrincipalData, StringSubtype, TupleData,
+    TypeSignature, Value, BOUND_VALUE_SERIALIZATION_BYTES, MAX_TYPE_DEPTH, MAX_VALUE_SIZE,
 };
 
 /// Errors that may occur in serialization or deserialization
@@ -45,6 +45,12 @@ pub enum SerializationError {
     BadTypeError(CheckErrors),
     DeserializationError(String),
     DeserializeExpected(TypeSignature),
+    /// A JSON value did not match the expected type. `path` locates it
+    ///   within the input, e.g. `$.recipients[2].amount`.
+    JsonDeserializationError {
+        path: String,
+        message: String,
+    },
     LeftoverBytesInDeserialization,
     SerializationError(String),
     UnexpectedSerialization,
@@ -91,6 +97,9 @@ impl std::fmt::Display for SerializationError {
                 "Deserialization expected the type of the input to be: {}",
                 e
             ),
+            SerializationError::JsonDeserializationError { path, message } => {
+                write!(f, "JSON deserialization error at {}: {}", path, message)
+            }
             SerializationError::UnexpectedSerialization => {
                 write!(f, "The serializer handled an input in an unexpected way")
             }
@@ -1331,6 +1340,287 @@ impl Value {
     }
 }
 
+fn json_error(path: &str, message: impl Into<String>) -> SerializationError {
+    SerializationError::JsonDeserializationError {
+        path: path.to_string(),
+        message: message.into(),
+    }
+}
+
+/// The JSON representation of Clarity values is lossless, but only decodable
+///  given the expected type:
+///   - `int` and `uint` are decimal strings, so that 128-bit values survive
+///     JSON parsers which read numbers as doubles
+///   - `bool` is a JSON boolean
+///   - buffers are `0x`-prefixed hex strings
+///   - `string-ascii` and `string-utf8` are JSON strings
+///   - principals (and trait references) are strings in their Clarity
+///     literal form, without the leading `'`
+///   - tuples are objects, and lists are arrays
+///   - `none` is `null`, and `(some v)` is the representation of `v`, or
+///     `{"some": v}` if `v` is itself an optional
+///   - responses are `{"ok": v}` or `{"err": v}`
+impl Value {
+    pub fn to_json(&self) -> serde_json::Value {
+        use serde_json::Value as Json;
+        match self {
+            Value::Int(i) => Json::String(i.to_string()),
+            Value::UInt(u) => Json::String(u.to_string()),
+            Value::Bool(b) => Json::Bool(*b),
+            Value::Sequence(SequenceData::Buffer(buffer)) => {
+                Json::String(format!("0x{}", to_hex(&buffer.data)))
+            }
+            Value::Sequence(SequenceData::String(CharType::ASCII(string))) => {
+                Json::String(String::from_utf8_lossy(&string.data).into_owned())
+            }
+            Value::Sequence(SequenceData::String(CharType::UTF8(string))) => {
+                Json::String(String::from_utf8_lossy(&string.data.concat()).into_owned())
+            }
+            Value::Sequence(SequenceData::List(list)) => {
+                Json::Array(list.data.iter().map(Value::to_json).collect())
+            }
+            Value::Principal(principal) => Json::String(principal.to_string()),
+            Value::CallableContract(CallableData {
+                contract_identifier,
+                ..
+            }) => Json::String(contract_identifier.to_string()),
+            Value::Tuple(tuple) => Json::Object(
+                tuple
+                    .data_map
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), value.to_json()))
+                    .collect(),
+            ),
+            Value::Optional(OptionalData { data: None }) => Json::Null,
+            Value::Optional(OptionalData { data: Some(inner) }) => match inner.as_ref() {
+                Value::Optional(_) => {
+                    let mut wrapper = serde_json::Map::new();
+                    wrapper.insert("some".into(), inner.to_json());
+                    Json::Object(wrapper)
+                }
+                inner => inner.to_json(),
+            },
+            Value::Response(ResponseData { committed, data }) => {
+                let mut wrapper = serde_json::Map::new();
+                let key = if *committed { "ok" } else { "err" };
+                wrapper.insert(key.into(), data.to_json());
+                Json::Object(wrapper)
+            }
+        }
+    }
+
+    /// Decode the JSON representation of a value of the `expected` type.
+    /// Errors locate the offending part of the input with a path from `$`.
+    pub fn from_json(
+        json: &serde_json::Value,
+        expected: &TypeSignature,
+    ) -> Result<Value, SerializationError> {
+        Value::inner_from_json(json, expected, "$", 0)
+    }
+
+    fn inner_from_json(
+        json: &serde_json::Value,
+        expected: &TypeSignature,
+        path: &str,
+        depth: u8,
+    ) -> Result<Value, SerializationError> {
+        use serde_json::Value as Json;
+
+        if depth > MAX_TYPE_DEPTH {
+            return Err(CheckErrors::TypeSignatureTooDeep.into());
+        }
+        let construct = |value: Result<Value, ClarityError>| {
+            value.map_err(|e| json_error(path, format!("invalid {}: {}", expected, e)))
+        };
+        let expect_string = |what: &str| {
+            json.as_str()
+                .ok_or_else(|| json_error(path, format!("expected {} for {}", what, expected)))
+        };
+
+        match expected {
+            TypeSignature::IntType => expect_string("a decimal string")?
+                .parse::<i128>()
+                .map(Value::Int)
+                .map_err(|_| json_error(path, "expected an int as a decimal string")),
+            TypeSignature::UIntType => expect_string("a decimal string")?
+                .parse::<u128>()
+                .map(Value::UInt)
+                .map_err(|_| json_error(path, "expected a uint as a decimal string")),
+            TypeSignature::BoolType => json
+                .as_bool()
+                .map(Value::Bool)
+                .ok_or_else(|| json_error(path, "expected a boolean")),
+            TypeSignature::SequenceType(SequenceSubtype::BufferType(max_len)) => {
+                let hex = expect_string("a hex string")?;
+                let bytes = hex_bytes(hex.strip_prefix("0x").unwrap_or(hex))
+                    .map_err(|_| json_error(path, "expected a buffer as a hex string"))?;
+                if bytes.len() > u32::from(max_len) as usize {
+                    return Err(json_error(
+                        path,
+                        format!("{} bytes do not fit in {}", bytes.len(), expected),
+                    ));
+                }
+                construct(Value::buff_from(bytes))
+            }
+            TypeSignature::SequenceType(SequenceSubtype::StringType(StringSubtype::ASCII(
+                max_len,
+            ))) => {
+                let string = expect_string("a string")?;
+                if string.len() > u32::from(max_len) as usize {
+                    return Err(json_error(
+                        path,
+                        format!("{} characters do not fit in {}", string.len(), expected),
+                    ));
+                }
+                construct(Value::string_ascii_from_bytes(string.as_bytes().to_vec()))
+            }
+            TypeSignature::SequenceType(SequenceSubtype::StringType(StringSubtype::UTF8(
+                max_len,
+            ))) => {
+                let string = expect_string("a string")?;
+                let len = string.chars().count();
+                if len > u32::from(max_len) as usize {
+                    return Err(json_error(
+                        path,
+                        format!("{} characters do not fit in {}", len, expected),
+                    ));
+                }
+                construct(Value::string_utf8_from_bytes(string.as_bytes().to_vec()))
+            }
+            TypeSignature::SequenceType(SequenceSubtype::ListType(list_type)) => {
+                let items = json.as_array().ok_or_else(|| {
+                    json_error(path, format!("expected an array for {}", expected))
+                })?;
+                if items.len() > list_type.get_max_len() as usize {
+                    return Err(json_error(
+                        path,
+                        format!("{} items do not fit in {}", items.len(), expected),
+                    ));
+                }
+                let items = items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, item)| {
+                        Value::inner_from_json(
+                            item,
+                            list_type.get_list_item_type(),
+                            &format!("{}[{}]", path, i),
+                            depth + 1,
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                construct(Value::list_with_type(
+                    &DESERIALIZATION_TYPE_CHECK_EPOCH,
+                    items,
+                    list_type.clone(),
+                ))
+            }
+            TypeSignature::PrincipalType
+            | TypeSignature::CallableType(_)
+            | TypeSignature::ListUnionType(_)
+            | TypeSignature::TraitReferenceType(_) => {
+                let principal = PrincipalData::parse(expect_string("a principal")?)
+                    .map_err(|e| json_error(path, format!("invalid principal: {}", e)))?;
+                if !matches!(expected, TypeSignature::PrincipalType)
+                    && !matches!(principal, PrincipalData::Contract(_))
+                {
+                    return Err(json_error(
+                        path,
+                        format!("expected a contract principal for {}", expected),
+                    ));
+                }
+                Ok(Value::Principal(principal))
+            }
+            TypeSignature::TupleType(tuple_type) => {
+                let fields = json.as_object().ok_or_else(|| {
+                    json_error(path, format!("expected an object for {}", expected))
+                })?;
+                if let Some(unknown) = fields
+                    .keys()
+                    .find(|key| !tuple_type.get_type_map().contains_key(key.as_str()))
+                {
+                    return Err(json_error(path, format!("unexpected field `{}`", unknown)));
+                }
+                let data = tuple_type
+                    .get_type_map()
+                    .iter()
+                    .map(|(name, field_type)| {
+                        let field = fields
+                            .get(name.as_str())
+                            .ok_or_else(|| json_error(path, format!("missing field `{}`", name)))?;
+                        let value = Value::inner_from_json(
+                            field,
+                            field_type,
+                            &format!("{}.{}", path, name),
+                            depth + 1,
+                        )?;
+                        Ok((name.clone(), value))
+                    })
+                    .collect::<Result<Vec<_>, SerializationError>>()?;
+                construct(TupleData::from_data(data).map(Value::from))
+            }
+            TypeSignature::OptionalType(inner_type) => match (json, inner_type.as_ref()) {
+                (Json::Null, _) => Ok(Value::none()),
+                (json, TypeSignature::OptionalType(_)) => {
+                    let inner = single_field(json, &["some"])
+                        .ok_or_else(|| json_error(path, "expected null or {\"some\": ...}"))?
+                        .1;
+                    let inner = Value::inner_from_json(
+                        inner,
+                        inner_type,
+                        &format!("{}.some", path),
+                        depth + 1,
+                    )?;
+                    construct(Value::some(inner))
+                }
+                (json, inner_type) => {
+                    let inner = Value::inner_from_json(json, inner_type, path, depth + 1)?;
+                    construct(Value::some(inner))
+                }
+            },
+            TypeSignature::ResponseType(response_type) => {
+                let (key, inner) = single_field(json, &["ok", "err"])
+                    .ok_or_else(|| json_error(path, "expected {\"ok\": ...} or {\"err\": ...}"))?;
+                let committed = key == "ok";
+                let inner_type = if committed {
+                    &response_type.0
+                } else {
+                    &response_type.1
+                };
+                let inner = Value::inner_from_json(
+                    inner,
+                    inner_type,
+                    &format!("{}.{}", path, key),
+                    depth + 1,
+                )?;
+                construct(if committed {
+                    Value::okay(inner)
+                } else {
+                    Value::error(inner)
+                })
+            }
+            TypeSignature::NoType => Err(json_error(
+                path,
+                "cannot decode a value of an indeterminate type",
+            )),
+        }
+    }
+}
+
+/// The key and value of an object with exactly one field, which is one of `keys`
+fn single_field<'a>(
+    json: &'a serde_json::Value,
+    keys: &[&str],
+) -> Option<(&'a str, &'a serde_json::Value)> {
+    let object = json.as_object()?;
+    if object.len() != 1 {
+        return None;
+    }
+    let (key, value) = object.iter().next()?;
+    keys.contains(&key.as_str())
+        .then_some((key.as_str(), value))
+}
+
 impl ClaritySerializable for u32 {
     fn serialize(&self) -> String {
         to_hex(&self.to_be_bytes())
@@ -1373,6 +1663,7 @@ pub mod tests {
 
     use rstest::rstest;
     use rstest_reuse::{self, *};
+    use serde_json::json;
     use stacks_common::types::StacksEpochId;
 
     use super::super::*;
@@ -2210,4 +2501,145 @@ pub mod tests {
         test_bad_expectation(contract_p2, TypeSignature::BoolType);
         test_bad_expectation(standard_p, TypeSignature::BoolType);
     }
+
+    #[test]
+    fn test_json_round_trip() {
+        let principal = PrincipalData::parse("SM2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQVX8X0G").unwrap();
+        let contract =
+            PrincipalData::parse("SM2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQVX8X0G.foo").unwrap();
+        let tuple = Value::from(
+            TupleData::from_data(vec![
+                ("amount".into(), Value::UInt(u128::MAX)),
+                ("memo".into(), Value::buff_from(vec![0xde, 0xad]).unwrap()),
+                ("owner".into(), Value::Principal(principal)),
+            ])
+            .unwrap(),
+        );
+        let tuple_json = json!({
+            "amount": u128::MAX.to_string(),
+            "memo": "0xdead",
+            "owner": "SM2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQVX8X0G",
+        });
+        let cases = [
+            (Value::Int(i128::MIN), json!(i128::MIN.to_string())),
+            (Value::Bool(true), json!(true)),
+            (
+                Value::string_ascii_from_bytes(b"hello".to_vec()).unwrap(),
+                json!("hello"),
+            ),
+            (
+                Value::string_utf8_from_bytes("héllo ☃".as_bytes().to_vec()).unwrap(),
+                json!("héllo ☃"),
+            ),
+            (tuple.clone(), tuple_json.clone()),
+            (
+                Value::cons_list_unsanitized(vec![tuple.clone(), tuple]).unwrap(),
+                json!([tuple_json.clone(), tuple_json]),
+            ),
+            (
+                Value::Principal(contract),
+                json!("SM2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQVX8X0G.foo"),
+            ),
+            (Value::none(), json!(null)),
+            (Value::some(Value::Int(1)).unwrap(), json!("1")),
+            (
+                Value::some(Value::some(Value::none()).unwrap()).unwrap(),
+                json!({"some": {"some": null}}),
+            ),
+            (
+                Value::okay(Value::some(Value::UInt(2)).unwrap()).unwrap(),
+                json!({"ok": "2"}),
+            ),
+            (Value::err_none(), json!({"err": null})),
+        ];
+        for (value, expected_json) in cases {
+            let json = value.to_json();
+            assert_eq!(json, expected_json);
+            let expected_type = TypeSignature::type_of(&value).unwrap();
+            assert_eq!(Value::from_json(&json, &expected_type).unwrap(), value);
+        }
+
+        // the nesting of optionals is given by the type
+        let nested =
+            TypeSignature::new_option(TypeSignature::new_option(TypeSignature::IntType).unwrap())
+                .unwrap();
+        assert_eq!(
+            Value::from_json(&json!(null), &nested).unwrap(),
+            Value::none()
+        );
+        assert_eq!(
+            Value::from_json(&json!({"some": null}), &nested).unwrap(),
+            Value::some(Value::none()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_json_errors() {
+        let tuple_type = TypeSignature::TupleType(
+            TupleTypeSignature::try_from(vec![
+                (
+                    "amounts".into(),
+                    TypeSignature::list_of(TypeSignature::UIntType, 2).unwrap(),
+                ),
+                ("memo".into(), buff_type(2)),
+            ])
+            .unwrap(),
+        );
+        let error = |json: serde_json::Value, expected: &TypeSignature| match Value::from_json(
+            &json, expected,
+        )
+        .unwrap_err()
+        {
+            SerializationError::JsonDeserializationError { path, message } => (path, message),
+            other => panic!("unexpected error {other:?}"),
+        };
+
+        let (path, message) = error(json!({"amounts": ["1", "-2"], "memo": "0x00"}), &tuple_type);
+        assert_eq!(path, "$.amounts[1]");
+        assert_eq!(message, "expected a uint as a decimal string");
+
+        let (path, _) = error(json!({"amounts": [1], "memo": "0x00"}), &tuple_type);
+        assert_eq!(path, "$.amounts[0]");
+
+        let (path, message) = error(
+            json!({"amounts": ["1", "2", "3"], "memo": "0x00"}),
+            &tuple_type,
+        );
+        assert_eq!(path, "$.amounts");
+        assert_eq!(message, "3 items do not fit in (list 2 uint)");
+
+        let (path, message) = error(json!({"amounts": [], "memo": "0x000000"}), &tuple_type);
+        assert_eq!(path, "$.memo");
+        assert_eq!(message, "3 bytes do not fit in (buff 2)");
+
+        let (_, message) = error(json!({"amounts": []}), &tuple_type);
+        assert_eq!(message, "missing field `memo`");
+        let (_, message) = error(
+            json!({"amounts": [], "memo": "0x", "extra": true}),
+            &tuple_type,
+        );
+        assert_eq!(message, "unexpected field `extra`");
+
+        let response_type =
+            TypeSignature::new_response(TypeSignature::BoolType, TypeSignature::IntType).unwrap();
+        let (path, _) = error(json!({"err": "x"}), &response_type);
+        assert_eq!(path, "$.err");
+        let (path, _) = error(json!({"ok": true, "err": "1"}), &response_type);
+        assert_eq!(path, "$");
+
+        let ascii_type = TypeSignature::SequenceType(SequenceSubtype::StringType(
+            StringSubtype::ASCII(10_u32.try_into().unwrap()),
+        ));
+        let (_, message) = error(json!("héllo"), &ascii_type);
+        assert!(
+            message.starts_with("invalid (string-ascii 10)"),
+            "{message}"
+        );
+
+        let (_, message) = error(
+            json!("SM2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQVX8X0G"),
+            &TypeSignature::IntType,
+        );
+        assert_eq!(message, "expected an int as a decimal string");
+    }
 }
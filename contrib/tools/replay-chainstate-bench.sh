@@ -0,0 +1,81 @@
+#!/bin/bash
+
+# Compares the throughput of `stacks-inspect replay-chainstate` between two revisions, by
+# replaying the same chainstate with a release build of each into a fresh chainstate directory.
+
+exit_error() {
+   printf "$1" >&2
+   exit 1
+}
+
+for cmd in git cargo date mktemp bc stat; do
+   which $cmd >/dev/null 2>&1 || exit_error "Missing command: $cmd\n"
+done
+
+base_rev="$1"
+old_chainstate="$2"
+old_sortition_db="$3"
+old_burnchain_db="$4"
+new_burnchain_db="$5"
+runs="${6:-3}"
+
+if [ -z "$base_rev" ] || [ -z "$old_chainstate" ] || [ -z "$old_sortition_db" ] \
+   || [ -z "$old_burnchain_db" ] || [ -z "$new_burnchain_db" ]; then
+   exit_error "Usage: $0 BASE_REV OLD_CHAINSTATE_PATH OLD_SORTITION_DB_PATH OLD_BURNCHAIN_DB_PATH NEW_BURNCHAIN_DB_PATH [RUNS]\n"
+fi
+
+set -euo pipefail
+
+repo_dir="$(git rev-parse --show-toplevel)"
+work_dir="$(mktemp -d)"
+trap 'git -C "$repo_dir" worktree remove --force "$work_dir/base" >/dev/null 2>&1; rm -rf "$work_dir"' EXIT
+
+build() {
+   local src_dir="$1"
+   (cd "$src_dir" && cargo build --release --bin stacks-inspect >&2)
+   echo "$src_dir/target/release/stacks-inspect"
+}
+
+# replays with `$1` into a fresh chainstate directory, logging to `$work_dir/$2.log`, and prints
+# the wall-clock seconds of the replay and the size in bytes of the Clarity side store it wrote
+replay() {
+   local bin="$1"
+   local log="$work_dir/$2.log"
+   local new_chainstate="$work_dir/new-chainstate"
+   rm -rf "$new_chainstate"
+   local start="$(date +%s.%N)"
+   "$bin" replay-chainstate "$old_chainstate" "$old_sortition_db" "$old_burnchain_db" \
+      "$new_chainstate" "$new_burnchain_db" >"$log" 2>&1 \
+      || exit_error "Replay with $bin failed, see $log\n"
+   local end="$(date +%s.%N)"
+   echo "$(echo "$end - $start" | bc -l) $(stat -c %s "$new_chainstate/vm/clarity/marf.sqlite")"
+}
+
+# prints the blocks/s which a replay logged, or `-` if its build doesn't report it
+blocks_per_sec() {
+   sed -n 's/^Processed .*(\([0-9.]*\) blocks\/s)$/\1/p' "$1" | tail -n 1 | grep . || echo "-"
+}
+
+git -C "$repo_dir" worktree add --detach "$work_dir/base" "$base_rev" >&2
+base_bin="$(build "$work_dir/base")"
+head_bin="$(build "$repo_dir")"
+
+base_total=0
+head_total=0
+for run in $(seq 1 "$runs"); do
+   base_out="$(replay "$base_bin" "base-$run")"
+   head_out="$(replay "$head_bin" "head-$run")"
+   read -r base_secs base_bytes <<<"$base_out"
+   read -r head_secs head_bytes <<<"$head_out"
+   printf "run %d: %s %.3fs (%s blocks/s, side store %d bytes), HEAD %.3fs (%s blocks/s, side store %d bytes)\n" \
+      "$run" "$base_rev" "$base_secs" "$(blocks_per_sec "$work_dir/base-$run.log")" "$base_bytes" \
+      "$head_secs" "$(blocks_per_sec "$work_dir/head-$run.log")" "$head_bytes"
+   base_total="$(echo "$base_total + $base_secs" | bc -l)"
+   head_total="$(echo "$head_total + $head_secs" | bc -l)"
+done
+
+printf "mean: %s %.3fs, HEAD %.3fs, speedup %.3fx; side store %d bytes -> %d bytes\n" "$base_rev" \
+   "$(echo "$base_total / $runs" | bc -l)" \
+   "$(echo "$head_total / $runs" | bc -l)" \
+   "$(echo "$base_total / $head_total" | bc -l)" \
+   "$base_bytes" "$head_bytes"
@@ -0,0 +1,666 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Stateless verification of MARF Merkle proofs.
+//!
+//! The RPC endpoints which take `?proof=1` return a value along with a proof that it is stored
+//! in the MARF of a block. Checking such a proof only needs the proof, the MARF key and value,
+//! the root hash of the block's trie (its header's `state_index_root`), and a way to find the
+//! block of each ancestor trie root which the proof passes through. This crate decodes proofs
+//! from their wire format and checks them, without any chainstate, database or node types, so
+//! light clients can use it on its own.
+//!
+//! A MARF key is hashed into its 32-byte path with SHA-512/256, and the value is committed to
+//! as the SHA-512/256 hash of its stored form (see `marf_path` and `marf_value`).
+
+extern crate sha2;
+
+use std::collections::HashMap;
+use std::{error, fmt};
+
+use sha2::{Digest, Sha512_256};
+
+#[cfg(test)]
+mod tests;
+
+/// Length of a trie hash, a MARF path, or a block ID
+pub const TRIE_HASH_ENCODED_SIZE: usize = 32;
+/// Length of the data stored in a MARF leaf
+pub const MARF_VALUE_ENCODED_SIZE: usize = 40;
+
+/// Node IDs, as the MARF encodes them
+const NODE_ID_EMPTY: u8 = 0;
+const NODE_ID_LEAF: u8 = 1;
+
+/// Proof item type bytes, as the MARF encodes them
+const PROOF_TYPE_NODE4: u8 = 0;
+const PROOF_TYPE_NODE16: u8 = 1;
+const PROOF_TYPE_NODE48: u8 = 2;
+const PROOF_TYPE_NODE256: u8 = 3;
+const PROOF_TYPE_LEAF: u8 = 4;
+const PROOF_TYPE_SHUNT: u8 = 5;
+
+pub type TrieHash = [u8; TRIE_HASH_ENCODED_SIZE];
+pub type MARFValue = [u8; MARF_VALUE_ENCODED_SIZE];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// The proof bytes are malformed
+    DeserializeError(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::DeserializeError(ref s) => fmt::Display::fmt(s, f),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::DeserializeError(_) => None,
+        }
+    }
+}
+
+/// A child pointer of a node in a proof. Back-pointers carry the ID of the block whose trie
+/// holds the child; other pointers carry all zeros.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProofTriePtr {
+    pub id: u8,
+    pub chr: u8,
+    pub back_block: TrieHash,
+}
+
+/// An interior node of a proof
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProofTrieNode {
+    pub id: u8,
+    pub path: Vec<u8>,
+    pub ptrs: Vec<ProofTriePtr>,
+}
+
+/// The leaf a proof starts from
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProofTrieLeaf {
+    pub path: Vec<u8>,
+    pub data: MARFValue,
+}
+
+/// One item of a proof
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProofItem {
+    /// A node of a segment proof: the path character of the child which the proof passes
+    /// through, the node, and the hashes of its other children. A node with `n` children has
+    /// `n - 1` hashes.
+    Node(u8, ProofTrieNode, Vec<TrieHash>),
+    /// The leaf at the start of the proof, with its path character
+    Leaf(u8, ProofTrieLeaf),
+    /// A shunt proof entry, linking a trie root to its ancestors' roots
+    Shunt(i64, Vec<TrieHash>),
+}
+
+/// A MARF Merkle proof, from the leaf back to the root of the trie it was made at
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarfProof(pub Vec<ProofItem>);
+
+fn sha512_256(parts: &[&[u8]]) -> TrieHash {
+    let mut hasher = Sha512_256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    let mut hash = [0u8; TRIE_HASH_ENCODED_SIZE];
+    hash.copy_from_slice(hasher.finalize().as_slice());
+    hash
+}
+
+/// The MARF path of a key
+pub fn marf_path(key: &str) -> TrieHash {
+    sha512_256(&[key.as_bytes()])
+}
+
+/// The MARF leaf data which commits to a value, given the value's stored form. For Clarity
+/// state, that's the hex of the value's consensus serialization, without an `0x` prefix.
+pub fn marf_value(value: &str) -> MARFValue {
+    let mut data = [0u8; MARF_VALUE_ENCODED_SIZE];
+    data[..TRIE_HASH_ENCODED_SIZE].copy_from_slice(&sha512_256(&[value.as_bytes()]));
+    data
+}
+
+fn leaf_hash(leaf: &ProofTrieLeaf) -> TrieHash {
+    sha512_256(&[
+        &[NODE_ID_LEAF, leaf.path.len() as u8],
+        &leaf.path,
+        &leaf.data,
+    ])
+}
+
+fn node_hash(node: &ProofTrieNode, child_hashes: &[TrieHash]) -> TrieHash {
+    let mut hasher = Sha512_256::new();
+    hasher.update([node.id]);
+    for ptr in node.ptrs.iter() {
+        hasher.update([ptr.id, ptr.chr]);
+        hasher.update(ptr.back_block);
+    }
+    hasher.update([node.path.len() as u8]);
+    hasher.update(&node.path);
+    for child_hash in child_hashes {
+        hasher.update(child_hash);
+    }
+    let mut hash = [0u8; TRIE_HASH_ENCODED_SIZE];
+    hash.copy_from_slice(hasher.finalize().as_slice());
+    hash
+}
+
+/// Reads the MARF's wire encoding: big-endian integers, and `u32`-length-prefixed lists
+struct ProofReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> ProofReader<'a> {
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.bytes.len() < len {
+            return Err(Error::DeserializeError(format!(
+                "Proof is truncated: needed {} more bytes, but only {} remain",
+                len,
+                self.bytes.len()
+            )));
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        let mut array = [0u8; N];
+        array.copy_from_slice(self.read_bytes(N)?);
+        Ok(array)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, Error> {
+        Ok(self.read_array::<1>()?[0])
+    }
+
+    fn read_u32(&mut self) -> Result<u32, Error> {
+        Ok(u32::from_be_bytes(self.read_array()?))
+    }
+
+    fn read_i64(&mut self) -> Result<i64, Error> {
+        Ok(i64::from_be_bytes(self.read_array()?))
+    }
+
+    fn read_list<T, F>(&mut self, mut read_item: F) -> Result<Vec<T>, Error>
+    where
+        F: FnMut(&mut Self) -> Result<T, Error>,
+    {
+        let len = self.read_u32()? as usize;
+        // every item takes at least a byte, so a bogus length can't allocate much
+        let mut items = Vec::with_capacity(len.min(self.bytes.len()));
+        for _ in 0..len {
+            items.push(read_item(self)?);
+        }
+        Ok(items)
+    }
+
+    fn read_ptr(&mut self) -> Result<ProofTriePtr, Error> {
+        Ok(ProofTriePtr {
+            id: self.read_u8()?,
+            chr: self.read_u8()?,
+            back_block: self.read_array()?,
+        })
+    }
+
+    fn read_node(&mut self, num_hashes: usize) -> Result<ProofItem, Error> {
+        let chr = self.read_u8()?;
+        let id = self.read_u8()?;
+        let path = self.read_list(Self::read_u8)?;
+        let ptrs = self.read_list(Self::read_ptr)?;
+        let mut hashes = Vec::with_capacity(num_hashes);
+        for _ in 0..num_hashes {
+            hashes.push(self.read_array()?);
+        }
+        Ok(ProofItem::Node(
+            chr,
+            ProofTrieNode { id, path, ptrs },
+            hashes,
+        ))
+    }
+
+    fn read_item(&mut self) -> Result<ProofItem, Error> {
+        match self.read_u8()? {
+            PROOF_TYPE_NODE4 => self.read_node(3),
+            PROOF_TYPE_NODE16 => self.read_node(15),
+            PROOF_TYPE_NODE48 => self.read_node(47),
+            PROOF_TYPE_NODE256 => self.read_node(255),
+            PROOF_TYPE_LEAF => {
+                let chr = self.read_u8()?;
+                let path = self.read_list(Self::read_u8)?;
+                let data = self.read_array()?;
+                Ok(ProofItem::Leaf(chr, ProofTrieLeaf { path, data }))
+            }
+            PROOF_TYPE_SHUNT => {
+                let idx = self.read_i64()?;
+                let hashes = self.read_list(Self::read_array)?;
+                Ok(ProofItem::Shunt(idx, hashes))
+            }
+            type_byte => Err(Error::DeserializeError(format!(
+                "Bad type byte in MARF proof: {type_byte}"
+            ))),
+        }
+    }
+}
+
+fn write_list_len(bytes: &mut Vec<u8>, len: usize) {
+    bytes.extend_from_slice(&(len as u32).to_be_bytes());
+}
+
+/// The index of the first shunt proof entry in `proof` at or after `start`, or `proof.len()`
+fn next_shunt(proof: &[ProofItem], start: usize) -> usize {
+    proof[start.min(proof.len())..]
+        .iter()
+        .position(|item| matches!(item, ProofItem::Shunt(..)))
+        .map(|offset| start + offset)
+        .unwrap_or(proof.len())
+}
+
+impl MarfProof {
+    /// Decode a proof from its wire encoding
+    pub fn from_bytes(bytes: &[u8]) -> Result<MarfProof, Error> {
+        let mut reader = ProofReader { bytes };
+        let items = reader.read_list(ProofReader::read_item)?;
+        if !reader.bytes.is_empty() {
+            return Err(Error::DeserializeError(format!(
+                "{} trailing bytes after MARF proof",
+                reader.bytes.len()
+            )));
+        }
+        Ok(MarfProof(items))
+    }
+
+    /// Decode a proof from the hex of its wire encoding, as the RPC endpoints return it (an `0x`
+    /// prefix is allowed)
+    pub fn from_hex(hex: &str) -> Result<MarfProof, Error> {
+        let hex = hex.strip_prefix("0x").unwrap_or(hex);
+        let bytes = hex
+            .as_bytes()
+            .chunks(2)
+            .enumerate()
+            .map(|(i, digits)| {
+                std::str::from_utf8(digits)
+                    .ok()
+                    .filter(|digits| digits.len() == 2)
+                    .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+                    .ok_or_else(|| {
+                        Error::DeserializeError(format!("Bad hex in MARF proof at byte {i}"))
+                    })
+            })
+            .collect::<Result<Vec<u8>, Error>>()?;
+        MarfProof::from_bytes(&bytes)
+    }
+
+    /// Encode this proof in its wire encoding
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        write_list_len(&mut bytes, self.0.len());
+        for item in self.0.iter() {
+            match item {
+                ProofItem::Node(chr, node, hashes) => {
+                    let type_byte = match hashes.len() {
+                        3 => PROOF_TYPE_NODE4,
+                        15 => PROOF_TYPE_NODE16,
+                        47 => PROOF_TYPE_NODE48,
+                        _ => PROOF_TYPE_NODE256,
+                    };
+                    bytes.extend_from_slice(&[type_byte, *chr, node.id]);
+                    write_list_len(&mut bytes, node.path.len());
+                    bytes.extend_from_slice(&node.path);
+                    write_list_len(&mut bytes, node.ptrs.len());
+                    for ptr in node.ptrs.iter() {
+                        bytes.extend_from_slice(&[ptr.id, ptr.chr]);
+                        bytes.extend_from_slice(&ptr.back_block);
+                    }
+                    for hash in hashes.iter() {
+                        bytes.extend_from_slice(hash);
+                    }
+                }
+                ProofItem::Leaf(chr, leaf) => {
+                    bytes.extend_from_slice(&[PROOF_TYPE_LEAF, *chr]);
+                    write_list_len(&mut bytes, leaf.path.len());
+                    bytes.extend_from_slice(&leaf.path);
+                    bytes.extend_from_slice(&leaf.data);
+                }
+                ProofItem::Shunt(idx, hashes) => {
+                    bytes.push(PROOF_TYPE_SHUNT);
+                    bytes.extend_from_slice(&idx.to_be_bytes());
+                    write_list_len(&mut bytes, hashes.len());
+                    for hash in hashes.iter() {
+                        bytes.extend_from_slice(hash);
+                    }
+                }
+            }
+        }
+        bytes
+    }
+
+    fn next_shunt_hash(hash: &TrieHash, idx: i64, hashes: &[TrieHash]) -> Option<TrieHash> {
+        if idx == 0 {
+            return None;
+        }
+        let mut all_hashes: Vec<&[u8]> = Vec::with_capacity(hashes.len() + 1);
+        let mut rest = hashes.iter();
+        for i in 0..hashes.len() + 1 {
+            if idx - 1 == (i as i64) {
+                all_hashes.push(hash);
+            } else {
+                all_hashes.push(rest.next()?);
+            }
+        }
+        Some(sha512_256(&all_hashes))
+    }
+
+    /// Verify the head of a shunt proof, which links the root node hash of the first segment
+    /// proof to the root hash of its trie
+    fn verify_shunt_proof_head(node_root_hash: &TrieHash, head: &ProofItem) -> Option<TrieHash> {
+        let ProofItem::Shunt(0, hashes) = head else {
+            return None;
+        };
+        if hashes.is_empty() {
+            // a trie with no ancestors: its root hash is just its root node's hash
+            return Some(*node_root_hash);
+        }
+        let mut all_hashes: Vec<&[u8]> = Vec::with_capacity(hashes.len() + 1);
+        all_hashes.push(node_root_hash);
+        all_hashes.extend(hashes.iter().map(|hash| &hash[..]));
+        Some(sha512_256(&all_hashes))
+    }
+
+    /// Verify the tail of a shunt proof, given the back-pointer's trie root hash. Calculates the
+    /// root hash of the next segment proof's trie.
+    fn verify_shunt_proof_tail(initial_hash: &TrieHash, tail: &[ProofItem]) -> Option<TrieHash> {
+        let mut hash = *initial_hash;
+        for item in tail.iter() {
+            let ProofItem::Shunt(idx, hashes) = item else {
+                return None;
+            };
+            hash = MarfProof::next_shunt_hash(&hash, *idx, hashes)?;
+        }
+        Some(hash)
+    }
+
+    /// Verify a shunt junction, where a shunt proof tail and a segment proof meet. Returns the
+    /// root hash of the junction's trie.
+    fn verify_shunt_proof_junction(
+        node_root_hash: &TrieHash,
+        penultimate_trie_hash: &TrieHash,
+        junction: &ProofItem,
+    ) -> Option<TrieHash> {
+        let ProofItem::Shunt(idx, hashes) = junction else {
+            return None;
+        };
+        if *idx == 0 {
+            return None;
+        }
+        let mut all_hashes: Vec<&[u8]> = Vec::with_capacity(hashes.len() + 2);
+        all_hashes.push(node_root_hash);
+        let mut rest = hashes.iter();
+        for i in 0..hashes.len() + 1 {
+            if *idx - 1 == (i as i64) {
+                all_hashes.push(penultimate_trie_hash);
+            } else {
+                all_hashes.push(rest.next()?);
+            }
+        }
+        Some(sha512_256(&all_hashes))
+    }
+
+    /// Find the hash of a segment proof node, given the hash of the child the proof passes through
+    fn get_segment_proof_hash(
+        node: &ProofTrieNode,
+        hash: &TrieHash,
+        chr: u8,
+        hashes: &[TrieHash],
+    ) -> Option<TrieHash> {
+        if node.ptrs.len() != hashes.len() + 1 {
+            return None;
+        }
+        let mut all_hashes = Vec::with_capacity(node.ptrs.len());
+        let mut rest = hashes.iter();
+        for child_ptr in node.ptrs.iter() {
+            if child_ptr.id != NODE_ID_EMPTY && child_ptr.chr == chr {
+                all_hashes.push(*hash);
+            } else {
+                all_hashes.push(*rest.next()?);
+            }
+        }
+        Some(node_hash(node, &all_hashes))
+    }
+
+    /// Given a segment proof and the hash of its deepest node, calculate the hash of the
+    /// segment's root node
+    fn verify_segment_proof(segment: &[ProofItem], node_hash: &TrieHash) -> Option<TrieHash> {
+        let mut hash = *node_hash;
+        for item in segment.iter() {
+            hash = match item {
+                // the leaf has no child hashes to check
+                ProofItem::Leaf(_, leaf) => leaf_hash(leaf),
+                ProofItem::Node(chr, node, hashes) => {
+                    MarfProof::get_segment_proof_hash(node, &hash, *chr, hashes)?
+                }
+                ProofItem::Shunt(..) => return None,
+            };
+        }
+        Some(hash)
+    }
+
+    /// Given a segment proof, extract the path prefix it encodes
+    fn get_segment_proof_path_prefix(segment: &[ProofItem]) -> Option<Vec<u8>> {
+        let mut path_parts = vec![];
+        for item in segment.iter() {
+            match item {
+                ProofItem::Leaf(_, leaf) => {
+                    path_parts.push(leaf.path.clone());
+                }
+                ProofItem::Node(chr, node, _) => {
+                    path_parts.push(vec![*chr]);
+                    path_parts.push(node.path.clone());
+                }
+                ProofItem::Shunt(..) => return None,
+            }
+        }
+        Some(path_parts.into_iter().rev().flatten().collect())
+    }
+
+    /// Check that a proof is well-formed:
+    /// * it starts with a leaf;
+    /// * it alternates between segment and shunt proofs, and ends with a shunt proof;
+    /// * the first segment proof's path is `expected_path`, and each later segment proof's path
+    ///   is a prefix of the one before it.
+    fn is_proof_well_formed(proof: &[ProofItem], expected_path: &TrieHash) -> bool {
+        if !matches!(proof.first(), Some(ProofItem::Leaf(..))) {
+            return false;
+        }
+
+        let mut i = 0;
+        let mut path_bytes = vec![];
+        while i < proof.len() {
+            let j = next_shunt(proof, i + 1);
+            let Some(segment_path) = MarfProof::get_segment_proof_path_prefix(&proof[i..j]) else {
+                return false;
+            };
+            if i == 0 {
+                if segment_path[..] != expected_path[..] {
+                    return false;
+                }
+                path_bytes = segment_path;
+            } else if !path_bytes.starts_with(&segment_path) {
+                return false;
+            }
+
+            // skip the shunt proof which must follow
+            i = j;
+            if i >= proof.len() {
+                return false;
+            }
+            i += proof[i..]
+                .iter()
+                .take_while(|item| matches!(item, ProofItem::Shunt(..)))
+                .count();
+        }
+        true
+    }
+
+    /// Check that this proof shows `value` to be stored at `path` in the trie whose root hash
+    /// is `root_hash`. `block_for_root` must give the ID of the block whose trie has a given
+    /// root hash; it is only asked about roots calculated from the proof, so a verifier can
+    /// answer it from block headers which it already trusts, and learn them as it goes.
+    pub fn verify_with<F>(
+        &self,
+        path: &TrieHash,
+        value: &MARFValue,
+        root_hash: &TrieHash,
+        mut block_for_root: F,
+    ) -> bool
+    where
+        F: FnMut(&TrieHash) -> Option<TrieHash>,
+    {
+        let proof = &self.0[..];
+        if !MarfProof::is_proof_well_formed(proof, path) {
+            return false;
+        }
+
+        // the proof must be for this value
+        let ProofItem::Leaf(_, leaf) = &proof[0] else {
+            return false;
+        };
+        if leaf.data != *value {
+            return false;
+        }
+
+        // the first segment proof, up to the root of the trie holding the leaf
+        let mut i = 0;
+        let mut j = next_shunt(proof, i + 1);
+        let Some(node_root_hash) = MarfProof::verify_segment_proof(&proof[i..j], &leaf_hash(leaf))
+        else {
+            return false;
+        };
+        i = j;
+        if i >= proof.len() {
+            return false;
+        }
+
+        let Some(mut trie_hash) = MarfProof::verify_shunt_proof_head(&node_root_hash, &proof[i])
+        else {
+            return false;
+        };
+        i += 1;
+        if i >= proof.len() {
+            // no further shunts
+            return *root_hash == trie_hash;
+        }
+
+        // the next node hash is the ID of the block whose trie has this root
+        let Some(mut node_hash) = block_for_root(&trie_hash) else {
+            return false;
+        };
+        if matches!(proof[i], ProofItem::Shunt(..)) {
+            return false;
+        }
+
+        while i < proof.len() {
+            j = next_shunt(proof, i + 1);
+            let Some(next_node_root_hash) =
+                MarfProof::verify_segment_proof(&proof[i..j], &node_hash)
+            else {
+                return false;
+            };
+            i = j;
+            if i >= proof.len() {
+                return false;
+            }
+
+            // the shunt proof tail runs up to, but not including, the junction
+            j = i + proof[i..]
+                .iter()
+                .take_while(|item| matches!(item, ProofItem::Shunt(idx, _) if *idx != 0))
+                .count();
+            if j == i {
+                return false;
+            }
+            j -= 1;
+
+            let Some(penultimate_trie_hash) =
+                MarfProof::verify_shunt_proof_tail(&trie_hash, &proof[i..j])
+            else {
+                return false;
+            };
+            i = j;
+
+            let Some(next_trie_hash) = MarfProof::verify_shunt_proof_junction(
+                &next_node_root_hash,
+                &penultimate_trie_hash,
+                &proof[i],
+            ) else {
+                return false;
+            };
+            trie_hash = next_trie_hash;
+            node_hash = match block_for_root(&trie_hash) {
+                Some(block_id) => block_id,
+                None => return false,
+            };
+            i += 1;
+
+            if trie_hash == *root_hash {
+                break;
+            }
+        }
+
+        *root_hash == trie_hash
+    }
+
+    /// Like `verify_with`, but with the blocks of all the trie roots known up front
+    pub fn verify(
+        &self,
+        path: &TrieHash,
+        value: &MARFValue,
+        root_hash: &TrieHash,
+        root_to_block: &HashMap<TrieHash, TrieHash>,
+    ) -> bool {
+        self.verify_with(path, value, root_hash, |root| {
+            root_to_block.get(root).cloned()
+        })
+    }
+
+    /// Check that this proof shows `value`, the stored form of a value, to be stored under the
+    /// MARF key `key`. See `verify_with`.
+    pub fn verify_entry<F>(
+        &self,
+        key: &str,
+        value: &str,
+        root_hash: &TrieHash,
+        block_for_root: F,
+    ) -> bool
+    where
+        F: FnMut(&TrieHash) -> Option<TrieHash>,
+    {
+        self.verify_with(
+            &marf_path(key),
+            &marf_value(value),
+            root_hash,
+            block_for_root,
+        )
+    }
+}
@@ -0,0 +1,155 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+
+use crate::*;
+
+const NODE_ID_NODE256: u8 = 5;
+
+/// A proof of `key` => `value` in a trie whose root is a Node256 holding only the leaf, with
+/// the given ancestor hashes in its shunt proof head. Returns the proof and the trie's root hash.
+fn single_trie_proof(key: &str, value: &str, ancestors: Vec<TrieHash>) -> (MarfProof, TrieHash) {
+    let path = marf_path(key);
+    let leaf = ProofTrieLeaf {
+        path: path[1..].to_vec(),
+        data: marf_value(value),
+    };
+    let ptrs = (0..=255u8)
+        .map(|chr| ProofTriePtr {
+            id: if chr == path[0] {
+                NODE_ID_LEAF
+            } else {
+                NODE_ID_EMPTY
+            },
+            chr,
+            back_block: [0u8; 32],
+        })
+        .collect();
+    let root = ProofTrieNode {
+        id: NODE_ID_NODE256,
+        path: vec![],
+        ptrs,
+    };
+
+    let mut child_hashes = vec![[0u8; 32]; 256];
+    child_hashes[path[0] as usize] = leaf_hash(&leaf);
+    let node_root_hash = node_hash(&root, &child_hashes);
+    let root_hash = if ancestors.is_empty() {
+        node_root_hash
+    } else {
+        let mut all_hashes: Vec<&[u8]> = vec![&node_root_hash];
+        all_hashes.extend(ancestors.iter().map(|hash| &hash[..]));
+        sha512_256(&all_hashes)
+    };
+
+    let proof = MarfProof(vec![
+        ProofItem::Leaf(0, leaf),
+        ProofItem::Node(path[0], root, vec![[0u8; 32]; 255]),
+        ProofItem::Shunt(0, ancestors),
+    ]);
+    (proof, root_hash)
+}
+
+#[test]
+fn test_marf_path_of_empty_key() {
+    // the SHA-512/256 of no data, which the MARF special-cases
+    assert_eq!(
+        marf_path(""),
+        [
+            0xc6, 0x72, 0xb8, 0xd1, 0xef, 0x56, 0xed, 0x28, 0xab, 0x87, 0xc3, 0x62, 0x2c, 0x51,
+            0x14, 0x06, 0x9b, 0xdd, 0x3a, 0xd7, 0xb8, 0xf9, 0x73, 0x74, 0x98, 0xd0, 0xc0, 0x1e,
+            0xce, 0xf0, 0x96, 0x7a,
+        ]
+    );
+    assert_eq!(marf_value("")[..32], marf_path("")[..]);
+    assert_eq!(marf_value("")[32..], [0u8; 8]);
+}
+
+#[test]
+fn test_verify_single_trie_proof() {
+    let no_blocks = |_root: &TrieHash| -> Option<TrieHash> { None };
+    let (proof, root_hash) = single_trie_proof("some-key", "0a01", vec![]);
+    assert!(proof.verify_entry("some-key", "0a01", &root_hash, no_blocks));
+    assert!(proof.verify(
+        &marf_path("some-key"),
+        &marf_value("0a01"),
+        &root_hash,
+        &HashMap::new()
+    ));
+
+    // wrong value, wrong key, wrong root
+    assert!(!proof.verify_entry("some-key", "0a02", &root_hash, no_blocks));
+    assert!(!proof.verify_entry("other-key", "0a01", &root_hash, no_blocks));
+    assert!(!proof.verify_entry("some-key", "0a01", &[1u8; 32], no_blocks));
+
+    // a tampered sibling hash changes the root
+    let mut tampered = proof.clone();
+    if let ProofItem::Node(_, _, hashes) = &mut tampered.0[1] {
+        hashes[7] = [0xff; 32];
+    }
+    assert!(!tampered.verify_entry("some-key", "0a01", &root_hash, no_blocks));
+
+    // the proof must end in a shunt proof
+    let mut truncated = proof.clone();
+    truncated.0.pop();
+    assert!(!truncated.verify_entry("some-key", "0a01", &root_hash, no_blocks));
+
+    // a node whose hashes don't match its children is rejected, not a panic
+    let mut misshapen = proof.clone();
+    if let ProofItem::Node(_, _, hashes) = &mut misshapen.0[1] {
+        hashes.pop();
+    }
+    assert!(!misshapen.verify_entry("some-key", "0a01", &root_hash, no_blocks));
+}
+
+#[test]
+fn test_verify_proof_with_ancestors() {
+    let ancestors = vec![[1u8; 32], [2u8; 32]];
+    let (proof, root_hash) = single_trie_proof("some-key", "0a01", ancestors);
+    assert!(proof.verify_entry("some-key", "0a01", &root_hash, |_| None));
+
+    let (no_ancestors, _) = single_trie_proof("some-key", "0a01", vec![]);
+    assert!(!no_ancestors.verify_entry("some-key", "0a01", &root_hash, |_| None));
+}
+
+#[test]
+fn test_proof_codec() {
+    let (proof, _) = single_trie_proof("some-key", "0a01", vec![[3u8; 32]]);
+    let bytes = proof.to_bytes();
+    assert_eq!(MarfProof::from_bytes(&bytes).unwrap(), proof);
+
+    let hex: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+    assert_eq!(MarfProof::from_hex(&hex).unwrap(), proof);
+    assert_eq!(MarfProof::from_hex(&format!("0x{hex}")).unwrap(), proof);
+
+    // item count, then the leaf's type byte, path character, and path length
+    assert_eq!(bytes[..4], 3u32.to_be_bytes());
+    assert_eq!(bytes[4..6], [PROOF_TYPE_LEAF, 0]);
+    assert_eq!(bytes[6..10], 31u32.to_be_bytes());
+
+    assert!(MarfProof::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert!(MarfProof::from_bytes(&trailing).is_err());
+    let mut bad_type = bytes.clone();
+    bad_type[4] = 6;
+    assert!(MarfProof::from_bytes(&bad_type).is_err());
+    assert!(MarfProof::from_hex("0x0").is_err());
+    assert!(MarfProof::from_hex("zz").is_err());
+
+    // a huge length prefix fails on the missing items, rather than allocating for them
+    assert!(MarfProof::from_bytes(&u32::MAX.to_be_bytes()).is_err());
+}
@@ -3097,6 +3097,24 @@ impl SortitionDB {
         Ok(ret)
     }
 
+    /// Get the index block hashes of the PoX anchor blocks of every preprocessed reward set
+    pub fn get_pox_anchor_block_ids(conn: &DBConn) -> Result<HashSet<StacksBlockId>, db_error> {
+        let qry = "SELECT DISTINCT consensus_hash FROM snapshots WHERE winning_stacks_block_hash = ?1 AND sortition = 1";
+        let mut anchors = HashSet::new();
+        for (_, rc_info) in SortitionDB::get_all_preprocessed_reward_sets(conn)? {
+            let Some((block_hash, _)) = rc_info.selected_anchor_block() else {
+                continue;
+            };
+            let consensus_hashes: Vec<ConsensusHash> = query_rows(conn, qry, &[block_hash])?;
+            anchors.extend(
+                consensus_hashes
+                    .iter()
+                    .map(|consensus_hash| StacksBlockId::new(consensus_hash, block_hash)),
+            );
+        }
+        Ok(anchors)
+    }
+
     /// Get the height of a consensus hash, even if it's not on the canonical PoX fork.
     #[cfg_attr(test, mutants::skip)]
     pub fn get_consensus_hash_height(&self, ch: &ConsensusHash) -> Result<Option<u64>, db_error> {
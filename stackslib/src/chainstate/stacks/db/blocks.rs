@@ -141,6 +141,23 @@ pub enum MemPoolRejection {
         principal: PrincipalData,
         is_origin: bool,
     },
+    /// No transaction in the mempool has `missing_nonce`, which the account must use before it
+    /// can use `actual_nonce`
+    NonceGap {
+        missing_nonce: u64,
+        actual_nonce: u64,
+        principal: PrincipalData,
+        is_origin: bool,
+    },
+    /// The account already has `max_txs` transactions pending in the mempool
+    TooManyPendingTxs {
+        max_txs: u64,
+        principal: PrincipalData,
+        is_origin: bool,
+    },
+    /// The mempool is full, and the transaction's estimated fee rate is not high enough to evict
+    /// the transactions it would need room from
+    MemPoolFull,
     BadTransactionVersion,
     TransferRecipientIsSender(PrincipalData),
     TransferAmountMustBePositive,
@@ -240,6 +257,42 @@ impl MemPoolRejection {
                     }),
                 ),
             ),
+            NonceGap {
+                missing_nonce,
+                actual_nonce,
+                principal,
+                is_origin,
+            } => (
+                "NonceGap",
+                Some(
+                    json!({"message": "Nonce leaves a gap in the account's pending transactions",
+                                "missing": missing_nonce,
+                                "actual": actual_nonce,
+                                "principal": principal.to_string(),
+                                "is_origin": is_origin
+                    }),
+                ),
+            ),
+            TooManyPendingTxs {
+                max_txs,
+                principal,
+                is_origin,
+            } => (
+                "TooManyPendingTransactions",
+                Some(
+                    json!({"message": "Account has too many transactions pending in mempool",
+                                "max": max_txs,
+                                "principal": principal.to_string(),
+                                "is_origin": is_origin
+                    }),
+                ),
+            ),
+            MemPoolFull => (
+                "MempoolFull",
+                Some(
+                    json!({"message": "Mempool is full and the transaction's fee rate is too low to evict others"}),
+                ),
+            ),
             BadTransactionVersion => ("BadTransactionVersion", None),
             FailedToValidate(e) => (
                 "SignatureValidation",
@@ -4570,10 +4623,16 @@ impl StacksChainState {
         let mut burns = 0u128;
         let mut receipts = vec![];
         for tx in block_txs.iter() {
+            if let Some(tracer) = clarity_tx.tx_tracer() {
+                tracer.begin_transaction();
+            }
             let (tx_fee, mut tx_receipt) =
                 StacksChainState::process_transaction(clarity_tx, tx, false, ast_rules)?;
             fees = fees.checked_add(u128::from(tx_fee)).expect("Fee overflow");
             tx_receipt.tx_index = tx_index;
+            if let Some(tracer) = clarity_tx.tx_tracer() {
+                tracer.finish_transaction(&tx_receipt);
+            }
             burns = burns
                 .checked_add(tx_receipt.stx_burned)
                 .expect("Burns overflow");
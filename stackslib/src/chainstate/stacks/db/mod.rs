@@ -80,14 +80,15 @@ use crate::clarity_vm::clarity::{
 };
 use crate::clarity_vm::database::marf::MarfedKV;
 use crate::clarity_vm::database::HeadersDBConn;
+use crate::clarity_vm::trace::TxTracer;
 use crate::core::*;
 use crate::monitoring;
 use crate::net::atlas::BNS_CHARS_REGEX;
 use crate::net::Error as net_error;
 use crate::util_lib::boot::{boot_code_acc, boot_code_addr, boot_code_id, boot_code_tx_auth};
 use crate::util_lib::db::{
-    query_count, query_row, tx_begin_immediate, tx_busy_handler, DBConn, DBTx, Error as db_error,
-    FromColumn, FromRow, IndexDBConn, IndexDBTx,
+    query_count, query_row, query_row_columns, tx_begin_immediate, tx_busy_handler, DBConn, DBTx,
+    Error as db_error, FromColumn, FromRow, IndexDBConn, IndexDBTx,
 };
 
 pub mod accounts;
@@ -524,6 +525,11 @@ impl<'a, 'b> ClarityTx<'a, 'b> {
         self.block.block_limit()
     }
 
+    /// The tracer of this block's transactions, if they are being traced
+    pub fn tx_tracer(&mut self) -> Option<&mut TxTracer> {
+        self.block.tx_tracer()
+    }
+
     /// Run `todo` in this ClarityTx with `new_tracker`.
     /// Returns the result of `todo` and the `new_tracker`
     pub fn with_temporary_cost_tracker<F, R>(
@@ -676,7 +682,7 @@ impl<'a> DerefMut for ChainstateTx<'a> {
     }
 }
 
-pub const CHAINSTATE_VERSION: &str = "8";
+pub const CHAINSTATE_VERSION: &str = "9";
 
 const CHAINSTATE_INITIAL_SCHEMA: &[&str] = &[
     "PRAGMA foreign_keys = ON;",
@@ -875,6 +881,15 @@ const CHAINSTATE_SCHEMA_3: &[&str] = &[
     "#,
 ];
 
+const CHAINSTATE_SCHEMA_9: &[&str] = &[
+    // schema version 9: the Clarity side store holds serialized values as blobs rather than
+    // hex text. Older nodes fail to read those rows, so this version keeps them from opening
+    // the chainstate at all.
+    r#"
+    UPDATE db_config SET version = "9";
+    "#,
+];
+
 const CHAINSTATE_INDEXES: &[&str] = &[
     "CREATE INDEX IF NOT EXISTS index_block_hash_to_primary_key ON block_headers(index_block_hash,consensus_hash,block_hash);",
     "CREATE INDEX IF NOT EXISTS block_headers_hash_index ON block_headers(block_hash,block_height);",
@@ -1126,6 +1141,14 @@ impl StacksChainState {
                         tx.execute_batch(cmd)?;
                     }
                 }
+                "8" => {
+                    info!(
+                        "Migrating chainstate schema from version 8 to 9: Clarity values stored as bytes"
+                    );
+                    for cmd in CHAINSTATE_SCHEMA_9.iter() {
+                        tx.execute_batch(cmd)?;
+                    }
+                }
                 _ => {
                     error!(
                         "Invalid chain state database: expected version = {}, got {}",
@@ -2531,6 +2554,18 @@ impl StacksChainState {
         Ok(ret)
     }
 
+    /// Get the index block hashes of the blocks which processed a transaction.
+    /// Only works if the node logs its transactions (`STACKS_TRANSACTION_LOG=1`), and may
+    /// include blocks which are not on the canonical fork.
+    pub fn get_index_block_hashes_for_tx(
+        conn: &Connection,
+        txid: &Txid,
+    ) -> Result<Vec<StacksBlockId>, Error> {
+        let sql = "SELECT index_block_hash FROM transactions WHERE txid = ?1";
+        let args = params![txid];
+        Ok(query_row_columns(conn, sql, args, "index_block_hash")?)
+    }
+
     /// Store all on-burnchain STX operations' txids by index block hash.
     /// `index_block_hash` is the tenure-start block.
     /// * For epoch 2.x, this is simply the block ID
@@ -22,8 +22,9 @@ use std::io::{BufWriter, Cursor, Read, Seek, SeekFrom, Write};
 use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::time::SystemTime;
-use std::{cmp, env, error, fmt, fs, io, os};
+use std::{cmp, env, error, fmt, fs, io, os, ptr, slice};
 
 use rusqlite::types::{FromSql, ToSql};
 use rusqlite::{
@@ -68,15 +69,128 @@ pub struct TrieFileRAM {
     trie_offsets: TrieIdOffsets,
 }
 
+/// A read-only memory mapping of a prefix of a file
+#[cfg(unix)]
+struct MmapRegion {
+    ptr: *mut libc::c_void,
+    len: usize,
+}
+
+// The mapping is read-only and owned by exactly one `TrieFileMmap`, so it can move between
+// threads along with its MARF.
+#[cfg(unix)]
+unsafe impl Send for MmapRegion {}
+
+#[cfg(unix)]
+impl MmapRegion {
+    /// Map the first `len` bytes of `fd`.  `len` must be non-zero.
+    fn map(fd: &fs::File, len: usize) -> io::Result<MmapRegion> {
+        use std::os::unix::io::AsRawFd;
+        // SAFETY: the mapping is private to this struct and unmapped on drop.  The blobs file is
+        // only ever appended to, so the mapped bytes stay valid while the mapping exists.
+        let ptr = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                len,
+                libc::PROT_READ,
+                libc::MAP_SHARED,
+                fd.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(MmapRegion { ptr, len })
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        // SAFETY: `ptr` points to `len` readable bytes for as long as `self` lives
+        unsafe { slice::from_raw_parts(self.ptr as *const u8, self.len) }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for MmapRegion {
+    fn drop(&mut self) {
+        // SAFETY: `ptr` and `len` came from a successful `mmap()` which was not unmapped yet
+        unsafe {
+            libc::munmap(self.ptr, self.len);
+        }
+    }
+}
+
+/// Handle to a memory-mapped, append-only flat file containing Trie blobs.
+///
+/// Tries are always appended to the end of the file, and never overwritten, so a mapping of the
+/// file stays valid as it grows; reads past the mapped prefix remap the file.  Node reads are
+/// served from the mapping and from an in-RAM table of trie offsets, which is loaded from the DB
+/// in bulk, so reading a node costs neither a system call nor a DB query.  The file layout is the
+/// same as `TrieFileDisk`'s, so a MARF can switch between the two.
+#[cfg(unix)]
+pub struct TrieFileMmap {
+    fd: fs::File,
+    path: String,
+    readonly: bool,
+    map: Option<MmapRegion>,
+    /// Length of the file, as last seen or written by this handle
+    len: u64,
+    /// Read/write cursor
+    pos: u64,
+    trie_offsets: TrieIdOffsets,
+    /// Highest block ID whose offset was bulk-loaded from the DB
+    max_loaded_block_id: Option<u32>,
+}
+
+/// How a MARF's external trie blobs are stored
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrieFileBackend {
+    /// In a flat file, read with `seek()` and `read()`
+    Disk,
+    /// In a flat file which is memory-mapped for reads, and only ever appended to.  On platforms
+    /// without `mmap()`, this is the same as `Disk`.
+    Mmap,
+    /// In RAM, regardless of the DB path, so nothing outlives the MARF handle (for testing)
+    RAM,
+}
+
+impl FromStr for TrieFileBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<TrieFileBackend, String> {
+        match s {
+            "disk" => Ok(TrieFileBackend::Disk),
+            "mmap" => Ok(TrieFileBackend::Mmap),
+            "ram" => Ok(TrieFileBackend::RAM),
+            _ => Err(format!(
+                "Unknown trie blob backend '{s}': expected 'disk', 'mmap' or 'ram'"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for TrieFileBackend {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TrieFileBackend::Disk => write!(f, "disk"),
+            TrieFileBackend::Mmap => write!(f, "mmap"),
+            TrieFileBackend::RAM => write!(f, "ram"),
+        }
+    }
+}
+
 /// This is flat-file storage for a MARF's tries.  All tries are stored as contiguous byte arrays
 /// within a larger byte array.  The variants differ in how those bytes are backed.  The `RAM`
-/// variant stores data in RAM in a byte buffer, and the `Disk` variant stores data in a flat file
-/// on disk.  This structure is used to support external trie blobs, so that the tries don't need
-/// to be stored in sqlite blobs (which incurs a sqlite paging overhead).  This is useful for when
-/// the tries are too big to fit into a single page, such as the Stacks chainstate.
+/// variant stores data in RAM in a byte buffer, the `Disk` variant stores data in a flat file
+/// on disk, and the `Mmap` variant stores data in a flat file on disk which it reads through a
+/// memory mapping.  This structure is used to support external trie blobs, so that the tries don't
+/// need to be stored in sqlite blobs (which incurs a sqlite paging overhead).  This is useful for
+/// when the tries are too big to fit into a single page, such as the Stacks chainstate.
 pub enum TrieFile {
     RAM(TrieFileRAM),
     Disk(TrieFileDisk),
+    #[cfg(unix)]
+    Mmap(TrieFileMmap),
 }
 
 impl TrieFile {
@@ -94,6 +208,36 @@ impl TrieFile {
         }))
     }
 
+    /// Make a new memory-mapped TrieFile
+    #[cfg(unix)]
+    fn new_mmap(path: &str, readonly: bool) -> Result<TrieFile, Error> {
+        let fd = OpenOptions::new()
+            .read(true)
+            .write(!readonly)
+            .create(!readonly)
+            .open(path)?;
+        let len = fd.metadata()?.len();
+        Ok(TrieFile::Mmap(TrieFileMmap {
+            fd,
+            path: path.to_string(),
+            readonly,
+            map: None,
+            len,
+            pos: 0,
+            trie_offsets: TrieIdOffsets::new(),
+            max_loaded_block_id: None,
+        }))
+    }
+
+    /// Memory-mapping is not available, so use a plain disk-backed TrieFile
+    #[cfg(not(unix))]
+    fn new_mmap(path: &str, readonly: bool) -> Result<TrieFile, Error> {
+        warn!(
+            "Memory-mapped trie blobs are not supported on this platform; reading them from disk"
+        );
+        TrieFile::new_disk(path, readonly)
+    }
+
     /// Make a new RAM-backed TrieFile
     fn new_ram(readonly: bool) -> TrieFile {
         TrieFile::RAM(TrieFileRAM {
@@ -128,18 +272,44 @@ impl TrieFile {
         match self {
             TrieFile::RAM(_) => ":memory:".to_string(),
             TrieFile::Disk(ref disk) => disk.path.clone(),
+            #[cfg(unix)]
+            TrieFile::Mmap(ref mmap) => mmap.path.clone(),
         }
     }
 
-    /// Instantiate a TrieFile, given the associated DB path.
+    /// Which backend stores this TrieFile's blobs?
+    pub fn backend(&self) -> TrieFileBackend {
+        match self {
+            TrieFile::RAM(_) => TrieFileBackend::RAM,
+            TrieFile::Disk(_) => TrieFileBackend::Disk,
+            #[cfg(unix)]
+            TrieFile::Mmap(_) => TrieFileBackend::Mmap,
+        }
+    }
+
+    /// Instantiate a disk-backed TrieFile, given the associated DB path.
     /// If path is ':memory:', then it'll be an in-RAM TrieFile.
     /// Otherwise, it'll be stored as `$db_path.blobs`.
     pub fn from_db_path(path: &str, readonly: bool) -> Result<TrieFile, Error> {
-        if path == ":memory:" {
-            Ok(TrieFile::new_ram(readonly))
-        } else {
-            let blob_path = format!("{}.blobs", path);
-            TrieFile::new_disk(&blob_path, readonly)
+        TrieFile::from_db_path_with_backend(path, readonly, TrieFileBackend::Disk)
+    }
+
+    /// Instantiate a TrieFile with the given backend, given the associated DB path.
+    /// If path is ':memory:' or the backend is `RAM`, then it'll be an in-RAM TrieFile.
+    /// Otherwise, it'll be stored as `$db_path.blobs`.
+    pub fn from_db_path_with_backend(
+        path: &str,
+        readonly: bool,
+        backend: TrieFileBackend,
+    ) -> Result<TrieFile, Error> {
+        if path == ":memory:" || backend == TrieFileBackend::RAM {
+            return Ok(TrieFile::new_ram(readonly));
+        }
+        let blob_path = format!("{}.blobs", path);
+        match backend {
+            TrieFileBackend::Disk => TrieFile::new_disk(&blob_path, readonly),
+            TrieFileBackend::Mmap => TrieFile::new_mmap(&blob_path, readonly),
+            TrieFileBackend::RAM => unreachable!(),
         }
     }
 
@@ -354,6 +524,8 @@ impl TrieFile {
         let offset_opt = match self {
             TrieFile::RAM(ref ram) => ram.trie_offsets.get(&block_id),
             TrieFile::Disk(ref disk) => disk.trie_offsets.get(&block_id),
+            #[cfg(unix)]
+            TrieFile::Mmap(ref mut mmap) => return mmap.get_trie_offset(db, block_id),
         };
         match offset_opt {
             Some(offset) => Ok(*offset),
@@ -362,6 +534,8 @@ impl TrieFile {
                 match self {
                     TrieFile::RAM(ref mut ram) => ram.trie_offsets.insert(block_id, offset),
                     TrieFile::Disk(ref mut disk) => disk.trie_offsets.insert(block_id, offset),
+                    #[cfg(unix)]
+                    TrieFile::Mmap(ref mut mmap) => mmap.trie_offsets.insert(block_id, offset),
                 };
                 Ok(offset)
             }
@@ -452,19 +626,72 @@ impl TrieFile {
     /// Append a serialized trie to the TrieFile.
     /// Returns the offset at which it was appended.
     pub fn append_trie_blob(&mut self, db: &Connection, buf: &[u8]) -> Result<u64, Error> {
-        let offset = trie_sql::get_external_blobs_length(db)?;
+        let offset = if self.backend() == TrieFileBackend::Mmap {
+            // never overwrite bytes which a mapping may be reading, even if no trie refers to
+            // them (e.g. because the transaction which wrote them was rolled back)
+            self.seek(SeekFrom::End(0))?
+        } else {
+            trie_sql::get_external_blobs_length(db)?
+        };
         test_debug!("Write trie of {} bytes at {}", buf.len(), offset);
         self.seek(SeekFrom::Start(offset))?;
         self.write_all(buf)?;
         self.flush()?;
 
-        if let TrieFile::Disk(ref mut data) = self {
-            data.fd.sync_data()?;
+        match self {
+            TrieFile::Disk(ref mut data) => data.fd.sync_data()?,
+            #[cfg(unix)]
+            TrieFile::Mmap(ref mut data) => data.fd.sync_data()?,
+            TrieFile::RAM(_) => {}
         }
         Ok(offset)
     }
 }
 
+#[cfg(unix)]
+impl TrieFileMmap {
+    /// Determine the offset of a trie from the in-RAM offset table.  On a miss, load the offsets
+    /// of all tries added to the DB since the last load, and failing that, of this trie alone
+    /// (e.g. if its row was rewritten).
+    fn get_trie_offset(&mut self, db: &Connection, block_id: u32) -> Result<u64, Error> {
+        if let Some(offset) = self.trie_offsets.get(&block_id) {
+            return Ok(*offset);
+        }
+        let offsets = trie_sql::get_external_trie_offsets_after(db, self.max_loaded_block_id)?;
+        for (loaded_block_id, offset) in offsets.into_iter() {
+            self.max_loaded_block_id = Some(
+                self.max_loaded_block_id
+                    .map_or(loaded_block_id, |max| cmp::max(max, loaded_block_id)),
+            );
+            self.trie_offsets.insert(loaded_block_id, offset);
+        }
+        if let Some(offset) = self.trie_offsets.get(&block_id) {
+            return Ok(*offset);
+        }
+        let (offset, _length) = trie_sql::get_external_trie_offset_length(db, block_id)?;
+        self.trie_offsets.insert(block_id, offset);
+        Ok(offset)
+    }
+
+    /// Make sure that the first `end` bytes of the file are mapped, if the file is that long.
+    /// The file may have grown since it was last mapped, by this handle or by another.
+    fn ensure_mapped(&mut self, end: u64) -> io::Result<()> {
+        let mapped_len = self.map.as_ref().map(|map| map.len as u64).unwrap_or(0);
+        if end <= mapped_len {
+            return Ok(());
+        }
+        self.len = cmp::max(self.len, self.fd.metadata()?.len());
+        if self.len <= mapped_len {
+            return Ok(());
+        }
+        let len = usize::try_from(self.len).map_err(|_| {
+            io::Error::new(io::ErrorKind::Other, "Trie blobs file is too big to map")
+        })?;
+        self.map = Some(MmapRegion::map(&self.fd, len)?);
+        Ok(())
+    }
+}
+
 /// Boilerplate Write implementation for TrieFileDisk.  Plumbs through to the inner fd.
 impl Write for TrieFileDisk {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
@@ -487,12 +714,37 @@ impl Write for TrieFileRAM {
     }
 }
 
+/// Write implementation for TrieFileMmap.  Writes go to the file at the cursor, and are seen by
+/// the mapping once it is extended over them.
+#[cfg(unix)]
+impl Write for TrieFileMmap {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.readonly {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "Trie blobs file is read-only",
+            ));
+        }
+        self.fd.seek(SeekFrom::Start(self.pos))?;
+        let written = self.fd.write(buf)?;
+        self.pos += written as u64;
+        self.len = cmp::max(self.len, self.pos);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.fd.flush()
+    }
+}
+
 /// Boilerplate Write implementation for TrieFile enum.  Plumbs through to the inner struct.
 impl Write for TrieFile {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         match self {
             TrieFile::RAM(ref mut ram) => ram.write(buf),
             TrieFile::Disk(ref mut disk) => disk.write(buf),
+            #[cfg(unix)]
+            TrieFile::Mmap(ref mut mmap) => mmap.write(buf),
         }
     }
 
@@ -500,6 +752,8 @@ impl Write for TrieFile {
         match self {
             TrieFile::RAM(ref mut ram) => ram.flush(),
             TrieFile::Disk(ref mut disk) => disk.flush(),
+            #[cfg(unix)]
+            TrieFile::Mmap(ref mut mmap) => mmap.flush(),
         }
     }
 }
@@ -518,12 +772,31 @@ impl Read for TrieFileRAM {
     }
 }
 
+/// Read implementation for TrieFileMmap.  Copies out of the mapping at the cursor.
+#[cfg(unix)]
+impl Read for TrieFileMmap {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.ensure_mapped(self.pos.saturating_add(buf.len() as u64))?;
+        let Some(map) = self.map.as_ref() else {
+            return Ok(0);
+        };
+        let bytes = map.as_slice();
+        let start = cmp::min(usize::try_from(self.pos).unwrap_or(usize::MAX), bytes.len());
+        let count = cmp::min(buf.len(), bytes.len() - start);
+        buf[..count].copy_from_slice(&bytes[start..start + count]);
+        self.pos += count as u64;
+        Ok(count)
+    }
+}
+
 /// Boilerplate Read implementation for TrieFile enum.  Plumbs through to the inner struct.
 impl Read for TrieFile {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         match self {
             TrieFile::RAM(ref mut ram) => ram.read(buf),
             TrieFile::Disk(ref mut disk) => disk.read(buf),
+            #[cfg(unix)]
+            TrieFile::Mmap(ref mut mmap) => mmap.read(buf),
         }
     }
 }
@@ -542,11 +815,39 @@ impl Seek for TrieFileRAM {
     }
 }
 
+/// Seek implementation for TrieFileMmap.  Only moves the cursor; seeking to the end finds the
+/// current length of the file, which other handles may have appended to.
+#[cfg(unix)]
+impl Seek for TrieFileMmap {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let (base, delta) = match pos {
+            SeekFrom::Start(offset) => {
+                self.pos = offset;
+                return Ok(offset);
+            }
+            SeekFrom::End(delta) => {
+                self.len = cmp::max(self.len, self.fd.metadata()?.len());
+                (self.len, delta)
+            }
+            SeekFrom::Current(delta) => (self.pos, delta),
+        };
+        self.pos = base.checked_add_signed(delta).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })?;
+        Ok(self.pos)
+    }
+}
+
 impl Seek for TrieFile {
     fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
         match self {
             TrieFile::RAM(ref mut ram) => ram.seek(pos),
             TrieFile::Disk(ref mut disk) => disk.seek(pos),
+            #[cfg(unix)]
+            TrieFile::Mmap(ref mut mmap) => mmap.seek(pos),
         }
     }
 }
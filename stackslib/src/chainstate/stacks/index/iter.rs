@@ -0,0 +1,222 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Iteration over the leaves of a MARF.
+//!
+//! The leaves reachable from a block's trie are the MARF's entries as of that block.
+//! `TrieLeafIter` walks them depth-first, following back-pointers into the tries of
+//! ancestor blocks, and yields them in ascending path order. The walk may be bounded to
+//! a range of paths, in which case the subtries outside of the range are not read.
+
+use stacks_common::types::chainstate::{TrieHash, TRIEHASH_ENCODED_SIZE};
+
+use crate::chainstate::stacks::index::node::{is_backptr, TrieNodeID, TrieNodeType, TriePtr};
+use crate::chainstate::stacks::index::storage::TrieStorageConnection;
+use crate::chainstate::stacks::index::trie::Trie;
+use crate::chainstate::stacks::index::{Error, MarfTrieId, TrieLeaf};
+
+/// An intermediate node on the current path of the walk
+struct TrieLeafIterFrame<T> {
+    /// The block whose trie holds the node
+    block_hash: T,
+    block_id: u32,
+    /// The path bytes leading up to, and including, the node's own path
+    prefix: Vec<u8>,
+    /// The node's non-empty children, in ascending order of their path byte
+    children: Vec<TriePtr>,
+    /// The index of the next child to visit
+    next: usize,
+}
+
+/// Iterator over the (path, leaf) pairs reachable from a block's trie, in ascending path
+/// order. Only paths strictly after `after` and up to and including `until` are visited.
+///
+/// The iterator leaves the storage connection opened to whichever block it read last;
+/// callers which need the open block preserved must restore it themselves, as
+/// `MARF::get_leaves` does.
+pub struct TrieLeafIter<'a, 'b, T: MarfTrieId> {
+    storage: &'b mut TrieStorageConnection<'a, T>,
+    after: Option<TrieHash>,
+    until: Option<TrieHash>,
+    stack: Vec<TrieLeafIterFrame<T>>,
+}
+
+impl<'a, 'b, T: MarfTrieId> TrieLeafIter<'a, 'b, T> {
+    /// Start a walk of the trie of `block_hash`.
+    /// Returns NotFoundError if the block is not in the MARF, and PrunedError if its trie has
+    /// been pruned.
+    pub fn new(
+        storage: &'b mut TrieStorageConnection<'a, T>,
+        block_hash: &T,
+        after: Option<&TrieHash>,
+        until: Option<&TrieHash>,
+    ) -> Result<TrieLeafIter<'a, 'b, T>, Error> {
+        storage.open_block(block_hash)?;
+        storage.check_open_block_not_pruned()?;
+        let block_id = storage.get_cur_block_identifier()?;
+        let root = Trie::read_root_nohash(storage)?;
+
+        let mut iter = TrieLeafIter {
+            storage,
+            after: after.cloned(),
+            until: until.cloned(),
+            stack: vec![],
+        };
+        iter.push_frame(block_hash.clone(), block_id, vec![], &root);
+        Ok(iter)
+    }
+
+    fn push_frame(&mut self, block_hash: T, block_id: u32, prefix: Vec<u8>, node: &TrieNodeType) {
+        let mut children: Vec<_> = node
+            .ptrs()
+            .iter()
+            .filter(|ptr| ptr.id() != TrieNodeID::Empty as u8)
+            .cloned()
+            .collect();
+        children.sort_by_key(|ptr| ptr.chr());
+        self.stack.push(TrieLeafIterFrame {
+            block_hash,
+            block_id,
+            prefix,
+            children,
+            next: 0,
+        });
+    }
+
+    /// Does every path starting with `prefix` come at or before `after`?
+    fn before_range(&self, prefix: &[u8]) -> bool {
+        match self.after.as_ref() {
+            Some(after) => {
+                let after = after.as_bytes();
+                if prefix.len() < after.len() {
+                    prefix < &after[..prefix.len()]
+                } else {
+                    prefix <= &after[..]
+                }
+            }
+            None => false,
+        }
+    }
+
+    /// Does every path starting with `prefix` come after `until`?
+    fn after_range(&self, prefix: &[u8]) -> bool {
+        match self.until.as_ref() {
+            Some(until) => {
+                let until = until.as_bytes();
+                prefix[..] > until[..prefix.len().min(until.len())]
+            }
+            None => false,
+        }
+    }
+
+    /// Read the node `ptr` points to from the trie of `block_hash`, following it to an
+    /// ancestor's trie if it is a back-pointer. Returns the node and the block it was read
+    /// from, against which the node's own pointers are resolved.
+    fn read_child(
+        &mut self,
+        block_hash: &T,
+        block_id: u32,
+        ptr: &TriePtr,
+    ) -> Result<(TrieNodeType, T, u32), Error> {
+        if is_backptr(ptr.id()) {
+            let back_block_hash = self
+                .storage
+                .get_block_from_local_id(ptr.back_block())?
+                .clone();
+            self.storage
+                .open_block_known_id(&back_block_hash, ptr.back_block())?;
+            let node = self.storage.read_nodetype_nohash(&ptr.from_backptr())?;
+            Ok((node, back_block_hash, ptr.back_block()))
+        } else {
+            self.storage.open_block_known_id(block_hash, block_id)?;
+            let node = self.storage.read_nodetype_nohash(ptr)?;
+            Ok((node, block_hash.clone(), block_id))
+        }
+    }
+
+    fn next_leaf(&mut self) -> Result<Option<(TrieHash, TrieLeaf)>, Error> {
+        loop {
+            let Some(frame) = self.stack.last_mut() else {
+                return Ok(None);
+            };
+            let Some(ptr) = frame.children.get(frame.next).cloned() else {
+                self.stack.pop();
+                continue;
+            };
+            frame.next += 1;
+
+            let mut prefix = frame.prefix.clone();
+            prefix.push(ptr.chr());
+            let (block_hash, block_id) = (frame.block_hash.clone(), frame.block_id);
+
+            if self.before_range(&prefix) {
+                continue;
+            }
+            if self.after_range(&prefix) {
+                // every remaining child on the stack comes later still
+                self.stack.clear();
+                return Ok(None);
+            }
+
+            let (node, node_block_hash, node_block_id) =
+                self.read_child(&block_hash, block_id, &ptr)?;
+            prefix.extend_from_slice(node.path_bytes());
+            if prefix.len() > TRIEHASH_ENCODED_SIZE {
+                return Err(Error::CorruptionError(format!(
+                    "Path of {} bytes is longer than a TrieHash",
+                    prefix.len()
+                )));
+            }
+            if self.before_range(&prefix) {
+                continue;
+            }
+            if self.after_range(&prefix) {
+                self.stack.clear();
+                return Ok(None);
+            }
+
+            match node {
+                TrieNodeType::Leaf(leaf) => {
+                    let path = TrieHash::from_bytes(&prefix).ok_or_else(|| {
+                        Error::CorruptionError(format!(
+                            "Leaf path is {} bytes, not {}",
+                            prefix.len(),
+                            TRIEHASH_ENCODED_SIZE
+                        ))
+                    })?;
+                    return Ok(Some((path, leaf)));
+                }
+                node => {
+                    self.push_frame(node_block_hash, node_block_id, prefix, &node);
+                }
+            }
+        }
+    }
+}
+
+impl<T: MarfTrieId> Iterator for TrieLeafIter<'_, '_, T> {
+    type Item = Result<(TrieHash, TrieLeaf), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_leaf() {
+            Ok(leaf_opt) => leaf_opt.map(Ok),
+            Err(e) => {
+                // don't try to walk past a broken node
+                self.stack.clear();
+                Some(Err(e))
+            }
+        }
+    }
+}
@@ -26,6 +26,8 @@ use stacks_common::util::hash::Sha512Trunc256Sum;
 use stacks_common::util::log;
 
 use crate::chainstate::stacks::index::bits::{get_leaf_hash, get_node_hash, read_root_hash};
+use crate::chainstate::stacks::index::file::TrieFileBackend;
+use crate::chainstate::stacks::index::iter::TrieLeafIter;
 use crate::chainstate::stacks::index::node::{
     clear_backptr, is_backptr, set_backptr, CursorError, TrieCursor, TrieNode, TrieNode16,
     TrieNode256, TrieNode4, TrieNode48, TrieNodeID, TrieNodeType, TriePtr, TRIEPTR_SIZE,
@@ -69,6 +71,8 @@ pub struct MARFOpenOpts {
     pub cache_strategy: String,
     /// store trie blobs externally from the DB, in a flat file
     pub external_blobs: bool,
+    /// how external trie blobs are stored, if `external_blobs` is set
+    pub blob_backend: TrieFileBackend,
     /// unconditionally do a DB migration (used for testing)
     pub force_db_migrate: bool,
 }
@@ -79,6 +83,7 @@ impl MARFOpenOpts {
             hash_calculation_mode: TrieHashCalculationMode::Deferred,
             cache_strategy: "noop".to_string(),
             external_blobs: false,
+            blob_backend: TrieFileBackend::Disk,
             force_db_migrate: false,
         }
     }
@@ -92,6 +97,7 @@ impl MARFOpenOpts {
             hash_calculation_mode,
             cache_strategy: cache_strategy.to_string(),
             external_blobs,
+            blob_backend: TrieFileBackend::Disk,
             force_db_migrate: false,
         }
     }
@@ -109,6 +115,12 @@ impl MARFOpenOpts {
             MARFOpenOpts::new(TrieHashCalculationMode::Deferred, "everything", true),
         ]
     }
+
+    /// Store external trie blobs with the given backend
+    pub fn with_blob_backend(mut self, blob_backend: TrieFileBackend) -> MARFOpenOpts {
+        self.blob_backend = blob_backend;
+        self
+    }
 }
 
 ///
@@ -185,6 +197,18 @@ pub trait MarfConnection<T: MarfTrieId> {
         })
     }
 
+    /// Get up to `limit` entries of the MARF as of `block_hash`, in ascending path order,
+    /// whose paths come after `after` and no later than `until`.
+    fn get_leaves(
+        &mut self,
+        block_hash: &T,
+        after: Option<&TrieHash>,
+        until: Option<&TrieHash>,
+        limit: usize,
+    ) -> Result<Vec<(TrieHash, MARFValue)>, Error> {
+        self.with_conn(|c| MARF::get_leaves(c, block_hash, after, until, limit))
+    }
+
     fn get_block_at_height(&mut self, height: u32, tip: &T) -> Result<Option<T>, Error> {
         self.with_conn(|c| MARF::get_block_at_height(c, height, tip))
     }
@@ -198,6 +222,16 @@ pub trait MarfConnection<T: MarfTrieId> {
         self.with_conn(|c| c.get_root_hash_at(block_hash))
     }
 
+    /// Has the trie of a block been pruned?  Only the root hash of a pruned trie can be read.
+    fn is_block_pruned(&mut self, block_hash: &T) -> Result<bool, Error> {
+        self.with_conn(|c| c.is_block_pruned(block_hash))
+    }
+
+    /// Has any trie in this MARF been pruned?  Proofs cannot be made across a pruned trie.
+    fn has_pruned_blocks(&mut self) -> Result<bool, Error> {
+        self.with_conn(|c| c.has_pruned_blocks())
+    }
+
     /// Check if a block can open successfully, i.e.,
     ///   it's a known block, the storage system isn't issueing IOErrors, _and_ it's in the same fork
     ///   as the current block
@@ -1039,6 +1073,7 @@ impl<T: MarfTrieId> MARF<T> {
         storage.open_block(block_hash).inspect_err(|_e| {
             test_debug!("Failed to open block {block_hash:?}: {_e:?}");
         })?;
+        storage.check_open_block_not_pruned()?;
 
         // a NotFoundError _here_ means that the key doesn't exist in this view
         let (cursor, node) = MARF::walk(storage, block_hash, path)
@@ -1227,6 +1262,35 @@ impl<T: MarfTrieId> MARF<T> {
         result.map(|option_result| option_result.map(|leaf| leaf.data))
     }
 
+    /// Load up to `limit` of the MARF's entries as of `block_hash`, in ascending path order,
+    /// whose paths come after `after` and no later than `until`. Pass the last path of a
+    /// page as `after` to get the next one.
+    pub fn get_leaves(
+        storage: &mut TrieStorageConnection<T>,
+        block_hash: &T,
+        after: Option<&TrieHash>,
+        until: Option<&TrieHash>,
+        limit: usize,
+    ) -> Result<Vec<(TrieHash, MARFValue)>, Error> {
+        let (cur_block_hash, cur_block_id) = storage.get_cur_block_and_id();
+
+        let result = TrieLeafIter::new(storage, block_hash, after, until).and_then(|iter| {
+            iter.take(limit)
+                .map(|leaf_res| leaf_res.map(|(path, leaf)| (path, leaf.data)))
+                .collect()
+        });
+
+        // restore
+        storage
+            .open_block_maybe_id(&cur_block_hash, cur_block_id)
+            .inspect_err(|e| {
+                warn!("Failed to re-open {cur_block_hash} {cur_block_id:?}: {e:?}");
+                warn!("Result of failed leaf scan from {after:?}: {result:?}");
+            })?;
+
+        result
+    }
+
     pub fn get_block_height_miner_tip(
         storage: &mut TrieStorageConnection<T>,
         block_hash: &T,
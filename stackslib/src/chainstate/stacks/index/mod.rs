@@ -31,13 +31,16 @@ use crate::util_lib::db::Error as db_error;
 pub mod bits;
 pub mod cache;
 pub mod file;
+pub mod iter;
 pub mod marf;
 pub mod node;
 pub mod profile;
 pub mod proofs;
+pub mod prune;
 pub mod storage;
 pub mod trie;
 pub mod trie_sql;
+pub mod verify;
 
 #[cfg(test)]
 pub mod test;
@@ -258,6 +261,7 @@ pub enum Error {
     CursorError(node::CursorError),
     RestoreMarfBlockError(Box<Error>),
     NonMatchingForks([u8; 32], [u8; 32]),
+    PrunedError,
 }
 
 impl From<io::Error> for Error {
@@ -323,6 +327,7 @@ impl fmt::Display for Error {
             Error::RequestedIdentifierForExtensionTrie => {
                 write!(f, "BUG: MARF requested the identifier for a RAM trie")
             }
+            Error::PrunedError => write!(f, "The block's trie has been pruned"),
         }
     }
 }
@@ -509,3 +509,140 @@ impl TrieBenchmark {
 
     pub fn marf_find_backptr_node_finish(&mut self) {}
 }
+
+#[cfg(test)]
+pub mod test {
+    use std::fs;
+    use std::time::Instant;
+
+    use stacks_common::types::chainstate::{BlockHeaderHash, TrieHash};
+
+    use crate::chainstate::stacks::index::cache::test::make_test_insert_data;
+    use crate::chainstate::stacks::index::file::TrieFileBackend;
+    use crate::chainstate::stacks::index::marf::{MARFOpenOpts, MarfConnection, MARF};
+    use crate::chainstate::stacks::index::storage::TrieHashCalculationMode;
+    use crate::chainstate::stacks::index::{MARFValue, MarfTrieId};
+
+    /// How quickly a MARF commits blocks and looks up keys, with a given trie blob backend
+    #[derive(Debug, Clone)]
+    pub struct TrieFileBackendBenchmark {
+        pub backend: TrieFileBackend,
+        pub num_blocks: u64,
+        /// Total number of nanoseconds spent building and committing blocks
+        pub total_commit_time_ns: u128,
+        pub num_gets: u64,
+        /// Total number of nanoseconds spent in `MARF::get_by_key()`
+        pub total_get_time_ns: u128,
+        /// Root hash of the last block, which must be the same for every backend
+        pub root_hash: TrieHash,
+    }
+
+    impl TrieFileBackendBenchmark {
+        pub fn blocks_per_sec(&self) -> f64 {
+            self.num_blocks as f64 / (self.total_commit_time_ns.max(1) as f64 / 1e9)
+        }
+
+        pub fn mean_get_ns(&self) -> u128 {
+            self.total_get_time_ns / u128::from(self.num_gets.max(1))
+        }
+    }
+
+    /// Commit `data` to a fresh MARF whose trie blobs are stored with `backend`, one block per
+    /// entry, then look up every key at the last block through a newly-opened handle.
+    pub fn bench_trie_file_backend(
+        test_name: &str,
+        backend: TrieFileBackend,
+        data: &[Vec<(String, MARFValue)>],
+    ) -> TrieFileBackendBenchmark {
+        let test_dir = format!("/tmp/stacks-marf-tests/{}", test_name);
+        if fs::metadata(&test_dir).is_ok() {
+            fs::remove_dir_all(&test_dir).unwrap();
+        }
+        fs::create_dir_all(&test_dir).unwrap();
+        let test_file = format!("{}/marf-{}.sqlite", &test_dir, backend);
+
+        let marf_opts = MARFOpenOpts::new(TrieHashCalculationMode::Deferred, "noop", true)
+            .with_blob_backend(backend);
+        let mut marf: MARF<BlockHeaderHash> =
+            MARF::from_path(&test_file, marf_opts.clone()).unwrap();
+
+        let mut last_block_header = BlockHeaderHash::sentinel();
+        let start = Instant::now();
+        for (i, block_data) in data.iter().enumerate() {
+            let mut block_hash_bytes = [0u8; 32];
+            block_hash_bytes[0..8].copy_from_slice(&(i as u64).to_be_bytes());
+            let block_header = BlockHeaderHash(block_hash_bytes);
+
+            marf.begin(&last_block_header, &block_header).unwrap();
+            let keys: Vec<_> = block_data.iter().map(|(k, _)| k.clone()).collect();
+            let values = block_data.iter().map(|(_, v)| v.clone()).collect();
+            marf.insert_batch(&keys, values).unwrap();
+            marf.commit().unwrap();
+            last_block_header = block_header;
+        }
+        let total_commit_time_ns = start.elapsed().as_nanos();
+        let root_hash = marf.get_root_hash_at(&last_block_header).unwrap();
+
+        // an in-RAM backend does not outlive its handle
+        let mut reader = if backend == TrieFileBackend::RAM {
+            marf
+        } else {
+            MARF::from_path(&test_file, marf_opts).unwrap()
+        };
+
+        let mut num_gets = 0;
+        let start = Instant::now();
+        for block_data in data.iter() {
+            for (key, value) in block_data.iter() {
+                let read = reader
+                    .with_conn(|c| MARF::get_by_key(c, &last_block_header, key))
+                    .unwrap();
+                assert_eq!(read.as_ref(), Some(value));
+                num_gets += 1;
+            }
+        }
+        let total_get_time_ns = start.elapsed().as_nanos();
+
+        TrieFileBackendBenchmark {
+            backend,
+            num_blocks: data.len() as u64,
+            total_commit_time_ns,
+            num_gets,
+            total_get_time_ns,
+            root_hash,
+        }
+    }
+
+    #[test]
+    fn test_trie_file_backends() {
+        let data = make_test_insert_data(128, 128);
+        let mut benches = vec![];
+        for backend in [
+            TrieFileBackend::Disk,
+            TrieFileBackend::Mmap,
+            TrieFileBackend::RAM,
+        ] {
+            benches.push(bench_trie_file_backend(
+                "test_trie_file_backends",
+                backend,
+                &data,
+            ));
+        }
+
+        for bench in benches.iter() {
+            eprintln!(
+                "{}: {:.1} blocks/s committed; {} ns per get_by_key ({} gets)",
+                bench.backend,
+                bench.blocks_per_sec(),
+                bench.mean_get_ns(),
+                bench.num_gets
+            );
+            eprintln!("{:#?}", bench);
+        }
+
+        // the backend only decides where the bytes go
+        for bench in benches.iter() {
+            assert_eq!(bench.root_hash, benches[0].root_hash);
+        }
+    }
+}
@@ -27,11 +27,9 @@ use stacks_common::codec::{read_next, Error as codec_error, StacksMessageCodec};
 use stacks_common::types::chainstate::{
     BlockHeaderHash, TrieHash, BLOCK_HEADER_HASH_ENCODED_SIZE, TRIEHASH_ENCODED_SIZE,
 };
-use stacks_common::util::hash::to_hex;
+use stacks_common::util::hash::{hex_bytes, to_hex};
 
-use crate::chainstate::stacks::index::bits::{
-    get_leaf_hash, get_node_hash, read_root_hash, write_path_to_bytes,
-};
+use crate::chainstate::stacks::index::bits::{get_node_hash, read_root_hash, write_path_to_bytes};
 use crate::chainstate::stacks::index::marf::MARF;
 use crate::chainstate::stacks::index::node::{
     clear_backptr, is_backptr, set_backptr, ConsensusSerializable, CursorError, TrieCursor,
@@ -367,6 +365,20 @@ impl<T: MarfTrieId> TrieMerkleProof<T> {
         to_hex(&marf_proof)
     }
 
+    /// Decode a proof from its hex encoding, as `to_hex` writes it (an `0x` prefix is allowed)
+    pub fn from_hex(hex: &str) -> Result<Self, codec_error> {
+        let bytes = hex_bytes(hex.strip_prefix("0x").unwrap_or(hex))
+            .map_err(|e| codec_error::DeserializeError(format!("Invalid proof hex: {e:?}")))?;
+        let mut cursor = &bytes[..];
+        let proof = TrieMerkleProof::consensus_deserialize(&mut cursor)?;
+        if !cursor.is_empty() {
+            return Err(codec_error::DeserializeError(
+                "Trailing bytes after proof".to_string(),
+            ));
+        }
+        Ok(proof)
+    }
+
     fn make_proof_hashes(
         node: &TrieNodeType,
         all_hashes: &[TrieHash],
@@ -664,173 +676,6 @@ impl<T: MarfTrieId> TrieMerkleProof<T> {
         Ok(proof)
     }
 
-    fn next_shunt_hash(hash: &TrieHash, idx: i64, hashes: &[TrieHash]) -> Option<TrieHash> {
-        let mut all_hashes = Vec::with_capacity(hashes.len() + 1);
-        let mut hash_idx = 0;
-        for i in 0..hashes.len() + 1 {
-            if idx == 0 {
-                trace!("Intermediate shunt proof entry must have idx > 0");
-                return None;
-            }
-
-            if idx - 1 == (i as i64) {
-                all_hashes.push(hash.clone());
-            } else {
-                if hash_idx >= hashes.len() {
-                    trace!(
-                        "Invalid proof: hash_idx = {}, hashes.len() = {}",
-                        hash_idx,
-                        hashes.len()
-                    );
-                    return None;
-                }
-                all_hashes.push(hashes[hash_idx].clone());
-                hash_idx += 1;
-            }
-        }
-        trace!("Shunt proof node: idx={}, all_hashes={:?}", idx, all_hashes);
-        let next_hash = TrieHash::from_data_array(&all_hashes);
-        Some(next_hash)
-    }
-
-    /// Verify the head of a shunt proof
-    fn verify_shunt_proof_head(
-        node_root_hash: &TrieHash,
-        shunt_proof_head: &TrieMerkleProofType<T>,
-    ) -> Option<TrieHash> {
-        // ancestor hashes are always the first item
-        let hash = match shunt_proof_head {
-            TrieMerkleProofType::Shunt((ref idx, ref hashes)) => {
-                if *idx != 0 {
-                    trace!("First shunt proof entry must have idx == 0");
-                    return None;
-                }
-
-                if hashes.is_empty() {
-                    // special case -- if this shunt proof has no hashes (i.e. this is a leaf from the first
-                    // block), then we can safely skip this step
-                    trace!(
-                        "Special case for a 0-ancestor node: hash is just the trie hash: {:?}",
-                        node_root_hash
-                    );
-                    node_root_hash.clone()
-                } else {
-                    let mut all_hashes = Vec::with_capacity(hashes.len() + 1);
-                    all_hashes.push(node_root_hash.clone());
-                    for h in hashes {
-                        all_hashes.push(h.clone());
-                    }
-                    let ret = TrieHash::from_data_array(&all_hashes);
-                    trace!(
-                        "Shunt proof head: hash = {:?}, all_hashes = {:?}",
-                        &ret,
-                        &all_hashes
-                    );
-                    ret
-                }
-            }
-            _ => {
-                trace!("Shunt proof head is not a shunt proof node");
-                return None;
-            }
-        };
-
-        Some(hash)
-    }
-
-    /// Verify the tail of a shunt proof, given the backptr root hash.
-    /// Calculate the root hash of the next segment proof.
-    fn verify_shunt_proof_tail(
-        initial_hash: &TrieHash,
-        shunt_proof: &[TrieMerkleProofType<T>],
-    ) -> Option<TrieHash> {
-        let mut hash = initial_hash.clone();
-
-        // walk subsequent legs of a shunt proof, except for the last (since we need the next
-        // segment proof for that)
-        for i in 0..shunt_proof.len() {
-            let proof_node = &shunt_proof[i];
-            hash = match proof_node {
-                TrieMerkleProofType::Shunt((ref idx, ref hashes)) => {
-                    if *idx == 0 {
-                        trace!("Invalid shunt proof tail: idx == 0");
-                        return None;
-                    }
-
-                    match TrieMerkleProof::<T>::next_shunt_hash(&hash, *idx, hashes) {
-                        Some(h) => h,
-                        None => {
-                            return None;
-                        }
-                    }
-                }
-                _ => {
-                    trace!("Shunt proof item is not a shunt proof node");
-                    return None;
-                }
-            };
-        }
-        Some(hash)
-    }
-
-    /// Verify a shunt juncture, where a shunt proof tail and a segment proof meet.
-    /// Returns the hash of the root of the junction
-    fn verify_shunt_proof_junction(
-        node_root_hash: &TrieHash,
-        penultimate_trie_hash: &TrieHash,
-        shunt_proof_junction: &TrieMerkleProofType<T>,
-    ) -> Option<TrieHash> {
-        // at the juncture, we include the node root hash (from the subsequent segment proof) as
-        // the first hash, and include the penultimate trie hash in its idx
-        let hash = match shunt_proof_junction {
-            TrieMerkleProofType::Shunt((ref idx, ref hashes)) => {
-                if *idx == 0 {
-                    trace!("Shunt proof junction entry must not have idx == 0");
-                    return None;
-                }
-
-                let mut all_hashes = Vec::with_capacity(hashes.len() + 1);
-                let mut hash_idx = 0;
-
-                all_hashes.push(node_root_hash.clone());
-
-                for i in 0..hashes.len() + 1 {
-                    if *idx - 1 == (i as i64) {
-                        all_hashes.push(penultimate_trie_hash.clone());
-                    } else {
-                        if hash_idx >= hashes.len() {
-                            trace!(
-                                "ran out of hashes: hash_idx = {}, hashes.len() = {}",
-                                hash_idx,
-                                hashes.len()
-                            );
-                            return None;
-                        }
-
-                        all_hashes.push(hashes[hash_idx].clone());
-                        hash_idx += 1;
-                    }
-                }
-
-                trace!(
-                    "idx = {}, hashes = {:?}, penultimate = {:?}, node root = {:?}",
-                    *idx,
-                    hashes,
-                    penultimate_trie_hash,
-                    node_root_hash
-                );
-                trace!("Shunt proof junction: all_hashes = {:?}", &all_hashes);
-                TrieHash::from_data_array(&all_hashes)
-            }
-            _ => {
-                trace!("Shunt proof junction is not a shunt proof node");
-                return None;
-            }
-        };
-
-        Some(hash)
-    }
-
     /// Given a list of non-backptr ptrs and a root block header hash, calculate a Merkle proof.
     fn make_segment_proof(
         storage: &mut TrieStorageConnection<T>,
@@ -876,483 +721,6 @@ impl<T: MarfTrieId> TrieMerkleProof<T> {
         Ok(proof_segment)
     }
 
-    /// Given a node in a segment proof, find the hash
-    fn get_segment_proof_hash(
-        node: &ProofTrieNode<T>,
-        hash: &TrieHash,
-        chr: u8,
-        hashes: &[TrieHash],
-        count: usize,
-    ) -> Option<TrieHash> {
-        let mut all_hashes = vec![];
-        let mut ih = 0;
-
-        assert!(node.ptrs().len() == count);
-        assert!(count > 0 && hashes.len() == count - 1);
-
-        for child_ptr in node.ptrs() {
-            if child_ptr.id != TrieNodeID::Empty as u8 && child_ptr.chr == chr {
-                all_hashes.push(hash.clone());
-            } else if ih >= hashes.len() {
-                trace!("verify_get_hash: {} >= {}", ih, hashes.len());
-                return None;
-            } else {
-                all_hashes.push(hashes[ih].clone());
-                ih += 1;
-            }
-        }
-        if all_hashes.len() != count {
-            trace!("verify_get_hash: {} != {}", all_hashes.len(), count);
-            return None;
-        }
-
-        Some(get_node_hash(node, &all_hashes, &mut ()))
-    }
-
-    /// Given a segment proof, the deepest node's hash, and the hash of the trie root, verify that
-    /// the segment proof is well-formed.
-    /// If so, calculate the root hash of the segment and return it.
-    fn verify_segment_proof(
-        proof: &[TrieMerkleProofType<T>],
-        node_hash: &TrieHash,
-    ) -> Option<TrieHash> {
-        let mut hash = node_hash.clone();
-        for i in 0..proof.len() {
-            let hash_opt = match proof[i] {
-                TrieMerkleProofType::Leaf((ref _chr, ref node)) => {
-                    // special case the leaf hash -- it doesn't
-                    //   have any child hashes to check.
-                    Some(get_leaf_hash(node))
-                }
-                TrieMerkleProofType::Node4((ref chr, ref node, ref hashes)) => {
-                    TrieMerkleProof::get_segment_proof_hash(node, &hash, *chr, hashes, 4)
-                }
-                TrieMerkleProofType::Node16((ref chr, ref node, ref hashes)) => {
-                    TrieMerkleProof::get_segment_proof_hash(node, &hash, *chr, hashes, 16)
-                }
-                TrieMerkleProofType::Node48((ref chr, ref node, ref hashes)) => {
-                    TrieMerkleProof::get_segment_proof_hash(node, &hash, *chr, hashes, 48)
-                }
-                TrieMerkleProofType::Node256((ref chr, ref node, ref hashes)) => {
-                    TrieMerkleProof::get_segment_proof_hash(node, &hash, *chr, hashes, 256)
-                }
-                _ => {
-                    trace!("Invalid proof -- encountered a non-node proof type");
-                    return None;
-                }
-            };
-            hash = match hash_opt {
-                None => {
-                    return None;
-                }
-                Some(h) => h,
-            };
-        }
-
-        trace!("verify segment: calculated root hash = {:?}", hash);
-        Some(hash)
-    }
-
-    /// Given a segment proof, extract the path prefix it encodes
-    fn get_segment_proof_path_prefix(segment_proof: &[TrieMerkleProofType<T>]) -> Option<Vec<u8>> {
-        let mut path_parts = vec![];
-        for proof_node in segment_proof {
-            match proof_node {
-                TrieMerkleProofType::Leaf((ref _chr, ref node)) => {
-                    // path_parts.push(vec![*chr]);
-                    path_parts.push(node.path.clone());
-                }
-                TrieMerkleProofType::Node4((ref chr, ref node, _)) => {
-                    path_parts.push(vec![*chr]);
-                    path_parts.push(node.path.clone());
-                }
-                TrieMerkleProofType::Node16((ref chr, ref node, _)) => {
-                    path_parts.push(vec![*chr]);
-                    path_parts.push(node.path.clone());
-                }
-                TrieMerkleProofType::Node48((ref chr, ref node, _)) => {
-                    path_parts.push(vec![*chr]);
-                    path_parts.push(node.path.clone());
-                }
-                TrieMerkleProofType::Node256((ref chr, ref node, _)) => {
-                    path_parts.push(vec![*chr]);
-                    path_parts.push(node.path.clone());
-                }
-                _ => {
-                    trace!("Not a valid segment proof: got a non-node proof node");
-                    return None;
-                }
-            }
-        }
-
-        let mut path = vec![];
-        for i in 0..path_parts.len() {
-            let idx = path_parts.len() - 1 - i;
-            path.extend_from_slice(&path_parts[idx]);
-        }
-        Some(path)
-    }
-
-    /// Verify that a proof is well-formed:
-    /// * it must have the same number of segment and shunt proofs
-    /// * segment proof i+1 must be a prefix of segment proof i
-    /// * segment proof 0 must end in a leaf
-    /// * all segment proofs must end in a Node256 (a root)
-    fn is_proof_well_formed(proof: &[TrieMerkleProofType<T>], expected_path: &TrieHash) -> bool {
-        if proof.is_empty() {
-            trace!("Proof is empty");
-            return false;
-        }
-
-        match proof[0] {
-            TrieMerkleProofType::Leaf(_) => {}
-            _ => {
-                trace!("First proof node is not a leaf");
-                return false;
-            }
-        }
-
-        // must be alternating segment and shunt proofs
-        let mut i = 0;
-        let mut path_bytes = vec![];
-
-        while i < proof.len() {
-            // next segment proof
-            let mut j = i + 1;
-            while j < proof.len() {
-                match proof[j] {
-                    TrieMerkleProofType::Shunt(_) => {
-                        break;
-                    }
-                    _ => {
-                        j += 1;
-                    }
-                }
-            }
-
-            let segment_proof = &proof[i..j];
-
-            if i == 0 {
-                // detect the path
-                path_bytes = match TrieMerkleProof::get_segment_proof_path_prefix(segment_proof) {
-                    Some(bytes) => bytes,
-                    None => {
-                        trace!("Failed to get the path from the proof");
-                        return false;
-                    }
-                };
-
-                // first path bytes must be the expected TrieHash
-                if expected_path.as_bytes().to_vec() != path_bytes {
-                    trace!(
-                        "Invalid proof -- path bytes {:?} differs from the expected path {:?}",
-                        &path_bytes,
-                        expected_path
-                    );
-                    return false;
-                }
-            } else {
-                // make sure that this segment proof is a prefix of the last
-                let new_path_bytes =
-                    match TrieMerkleProof::get_segment_proof_path_prefix(segment_proof) {
-                        Some(bytes) => bytes,
-                        None => {
-                            trace!("Failed to et the path prefix from the proof");
-                            return false;
-                        }
-                    };
-
-                if path_bytes.len() < new_path_bytes.len() {
-                    trace!("Segment proof path is {}, which is longer than the previous segment proof length {}", path_bytes.len(), new_path_bytes.len());
-                    trace!("path_bytes: {:?}", &path_bytes);
-                    trace!("new path bytes: {:?}", &new_path_bytes);
-                    return false;
-                }
-
-                for i in 0..new_path_bytes.len() {
-                    if path_bytes[i] != new_path_bytes[i] {
-                        trace!(
-                            "Segment path {:?} is not a prefix of previous segment path {:?}",
-                            &new_path_bytes,
-                            &path_bytes
-                        );
-                        return false;
-                    }
-                }
-            }
-
-            // next shunt proof
-            i = j;
-            if i >= proof.len() {
-                trace!("Proof is incomplete -- must end with a shunt proof");
-                return false;
-            }
-
-            j = i + 1;
-            while j < proof.len() {
-                match proof[j] {
-                    TrieMerkleProofType::Shunt(_) => {
-                        j += 1;
-                    }
-                    _ => {
-                        break;
-                    }
-                }
-            }
-
-            // end of shunt proof
-            i = j;
-        }
-
-        true
-    }
-
-    /// Given a value and the root hash from which this proof was
-    /// (supposedly) generated go and verify whether or not it is consistent with the root hash.
-    /// For the proof validation to work, the verifier needs to know which Trie roots correspond to
-    /// which block headers.  This can be calculated and verified independently from the blockchain
-    /// headers.
-    /// NOTE: Trie root hashes are globally unique by design, even if they represent the same contents, so the root_to_block map is bijective with high probability.
-    pub fn verify_proof(
-        proof: &[TrieMerkleProofType<T>],
-        path: &TrieHash,
-        value: &MARFValue,
-        root_hash: &TrieHash,
-        root_to_block: &HashMap<TrieHash, T>,
-    ) -> bool {
-        if !TrieMerkleProof::is_proof_well_formed(proof, path) {
-            test_debug!("Invalid proof -- proof is not well-formed");
-            return false;
-        }
-
-        let (mut node_hash, node_data) = match proof[0] {
-            TrieMerkleProofType::Leaf((_, ref node)) => (get_leaf_hash(node), node.data.clone()),
-            _ => unreachable!(),
-        };
-
-        // proof must be for this value
-        if node_data != *value {
-            test_debug!(
-                "Invalid proof -- not for value hash {:?}",
-                value.to_value_hash()
-            );
-            return false;
-        }
-
-        let mut i = 0;
-
-        // verify the very first segment proof
-        let mut j = i + 1;
-        while j < proof.len() {
-            match proof[j] {
-                TrieMerkleProofType::Shunt(_) => {
-                    break;
-                }
-                _ => {
-                    j += 1;
-                }
-            }
-        }
-
-        trace!("verify segment proof in range {}..{}", i, j);
-        let node_root_hash = match TrieMerkleProof::verify_segment_proof(&proof[i..j], &node_hash) {
-            Some(h) => h,
-            None => {
-                test_debug!("Unable to verify segment proof in range {}...{}", i, j);
-                return false;
-            }
-        };
-
-        i = j;
-        if i >= proof.len() {
-            test_debug!(
-                "Proof is too short -- needed at least one shunt proof for the first segment"
-            );
-            return false;
-        }
-
-        // verify the very first shunt proof head.
-        trace!("verify shunt proof head at {}: {:?}", i, &proof[i]);
-        let mut trie_hash =
-            match TrieMerkleProof::verify_shunt_proof_head(&node_root_hash, &proof[i]) {
-                Some(h) => h,
-                None => {
-                    test_debug!(
-                        "Unable to verify shunt proof head at {}: {:?}",
-                        i,
-                        &proof[i]
-                    );
-                    return false;
-                }
-            };
-        trace!("shunt proof head hash: {:?}", &trie_hash);
-
-        i += 1;
-        if i >= proof.len() {
-            // done -- no further shunts
-            test_debug!("Verify proof: {:?} =?= {:?}", root_hash, &trie_hash);
-            return *root_hash == trie_hash;
-        }
-
-        // next node hash is the hash of the block from which its root came
-        node_hash = match root_to_block.get(&trie_hash) {
-            Some(bhh) => {
-                trace!("Block hash for {:?} is {:?}", &trie_hash, bhh);
-
-                // safe because block header hashes are 32 bytes long
-                TrieHash(bhh.clone().to_bytes())
-            }
-            None => {
-                test_debug!("Trie hash not found in root-to-block map: {:?}", &trie_hash);
-                trace!("root-to-block map: {:?}", &root_to_block);
-                return false;
-            }
-        };
-
-        // next proof item should be part of a segment proof
-        if let TrieMerkleProofType::Shunt(_) = proof[i] {
-            test_debug!(
-                "Malformed proof -- exepcted segment proof following first shunt proof head at {}",
-                i
-            );
-            return false;
-        }
-
-        while i < proof.len() {
-            // find the next segment proof
-            j = i + 1;
-            while j < proof.len() {
-                match proof[j] {
-                    TrieMerkleProofType::Shunt(_) => {
-                        break;
-                    }
-                    _ => {
-                        j += 1;
-                    }
-                }
-            }
-
-            trace!("verify segment proof in range {}..{}", i, j);
-            let next_node_root_hash =
-                match TrieMerkleProof::verify_segment_proof(&proof[i..j], &node_hash) {
-                    Some(h) => h,
-                    None => {
-                        test_debug!("Unable to verify segment proof in range {}..{}", i, j);
-                        return false;
-                    }
-                };
-
-            i = j;
-            if i >= proof.len() {
-                test_debug!("Proof to short -- no shunt proof tail");
-                return false;
-            }
-
-            // find the tail end
-            j = i;
-            while j < proof.len() {
-                match proof[j] {
-                    TrieMerkleProofType::Shunt((ref idx, _)) => {
-                        if *idx == 0 {
-                            break;
-                        }
-                        j += 1;
-                    }
-                    _ => {
-                        break;
-                    }
-                }
-            }
-            j -= 1;
-
-            if j < i {
-                test_debug!("Proof is malformed -- no tail or junction proof");
-                return false;
-            }
-
-            trace!(
-                "verify shunt proof tail in range {}..{} initial hash = {:?}: {:?}",
-                i,
-                j,
-                &trie_hash,
-                &proof[i..j]
-            );
-            let penultimate_trie_hash =
-                match TrieMerkleProof::verify_shunt_proof_tail(&trie_hash, &proof[i..j]) {
-                    Some(h) => h,
-                    None => {
-                        test_debug!("Unable to verify shunt proof tail");
-                        return false;
-                    }
-                };
-            trace!(
-                "verify shunt proof tail in range {}..{}: penultimate trie hash is {:?}",
-                i,
-                j,
-                &penultimate_trie_hash
-            );
-
-            i = j;
-            if i >= proof.len() {
-                test_debug!("Proof to short -- no junction proof");
-                return false;
-            }
-
-            trace!("verify shunt junction proof at {} next_node_root_hash = {:?} penultimate hash = {:?}: {:?}", i, &next_node_root_hash, &penultimate_trie_hash, &proof[i]);
-            let next_trie_hash = match TrieMerkleProof::verify_shunt_proof_junction(
-                &next_node_root_hash,
-                &penultimate_trie_hash,
-                &proof[i],
-            ) {
-                Some(h) => h,
-                None => {
-                    test_debug!("Unable to verify shunt junction proof at {} next_node_root_hash = {:?} penultimate hash = {:?}: {:?}", i, &next_node_root_hash, &penultimate_trie_hash, &proof[i]);
-                    return false;
-                }
-            };
-
-            // next node hash is the hash of the block from which its root came
-            trie_hash = next_trie_hash;
-            node_hash = match root_to_block.get(&trie_hash) {
-                Some(bhh) => {
-                    trace!("Block hash for {:?} is {:?}", &trie_hash, bhh);
-
-                    // safe because block header hashes are 32 bytes long
-                    TrieHash(bhh.clone().to_bytes())
-                }
-                None => {
-                    test_debug!("Trie hash not found in root-to-block map: {:?}", &trie_hash);
-                    test_debug!("root-to-block map: {:?}", &root_to_block);
-                    return false;
-                }
-            };
-
-            i += 1;
-
-            if trie_hash == *root_hash {
-                trace!(
-                    "Appeared to find the root hash early, with the remaining proof:\n{:?}",
-                    &proof[i..]
-                );
-                break;
-            }
-        }
-
-        test_debug!("Verify proof: {:?} =?= {:?}", root_hash, &trie_hash);
-        *root_hash == trie_hash
-    }
-
-    /// Verify this proof
-    pub fn verify(
-        &self,
-        path: &TrieHash,
-        marf_value: &MARFValue,
-        root_hash: &TrieHash,
-        root_to_block: &HashMap<TrieHash, T>,
-    ) -> bool {
-        TrieMerkleProof::<T>::verify_proof(&self.0, path, marf_value, root_hash, root_to_block)
-    }
-
     /// Walk down the trie pointed to by s until we reach a backptr or a leaf
     fn walk_to_leaf_or_backptr(
         storage: &mut TrieStorageConnection<T>,
@@ -1444,6 +812,8 @@ impl<T: MarfTrieId> TrieMerkleProof<T> {
 
         loop {
             storage.open_block(&block_header)?;
+            // a pruned trie no longer has the sibling hashes a segment proof needs
+            storage.check_open_block_not_pruned()?;
 
             trace!(
                 "Walk {:?} path {:?} to leaf or backptr",
@@ -0,0 +1,368 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pruning of historical tries from a MARF's blobs file.
+//!
+//! A block's trie only holds the nodes which changed in that block, and reaches the rest of the
+//! state through back-pointers into its ancestors' tries, so the blobs file keeps every trie
+//! ever written. Pruning keeps the tries of the most recent blocks, and of any named anchor
+//! blocks, in full. Every other trie is compacted down to:
+//!
+//! * its header and root node, since the root hashes of later blocks commit to its root hash;
+//! * the nodes which a retained trie still reaches through back-pointers.
+//!
+//! The compacted tries are packed into a new blobs file, and every pointer into them is
+//! rewritten to the node's new offset. A node's hash does not depend on where it is stored,
+//! so no hash changes.
+//!
+//! A pruned block's state can no longer be read: lookups, leaf scans and extensions from it
+//! fail with `Error::PrunedError`. Unconfirmed and mined tries are dropped, and rebuilt as
+//! needed. Pruning replaces the blobs file, so nothing else may have the MARF open while it
+//! runs.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{BufReader, BufWriter, Cursor, Read, Seek, SeekFrom, Write};
+use std::{fs, io};
+
+use rusqlite::Connection;
+use stacks_common::types::chainstate::TrieHash;
+
+use crate::chainstate::stacks::index::bits::{
+    get_node_byte_len, read_nodetype_at_head, write_nodetype_bytes,
+};
+use crate::chainstate::stacks::index::file::TrieFile;
+use crate::chainstate::stacks::index::marf::{MARFOpenOpts, MarfConnection, MARF};
+use crate::chainstate::stacks::index::node::{
+    clear_backptr, is_backptr, TrieNodeID, TrieNodeType, TriePtr,
+};
+use crate::chainstate::stacks::index::storage::{TrieHashCalculationMode, TrieStorageConnection};
+use crate::chainstate::stacks::index::{trie_sql, Error, MarfTrieId};
+use crate::util_lib::db::tx_begin_immediate;
+
+/// What a prune did
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MARFPruneStats {
+    /// Tries kept in full
+    pub retained_tries: u64,
+    /// Tries which were compacted, including those pruned by earlier prunes
+    pub pruned_tries: u64,
+    /// Nodes of pruned tries which are still reachable, and so were kept
+    pub kept_nodes: u64,
+    /// Size of the blobs file before pruning
+    pub blobs_size_before: u64,
+    /// Size of the blobs file after pruning
+    pub blobs_size_after: u64,
+}
+
+/// Where a confirmed trie is stored in the blobs file
+struct TrieBlob<T> {
+    block_hash: T,
+    offset: u64,
+    length: u64,
+}
+
+fn root_ptr<T: MarfTrieId>() -> TriePtr {
+    TriePtr::new(
+        TrieNodeID::Node256 as u8,
+        0,
+        TrieStorageConnection::<T>::root_ptr_disk(),
+    )
+}
+
+/// Read a node and its hash from the trie stored at `trie_offset`
+fn read_node<F: Read + Seek>(
+    f: &mut F,
+    trie_offset: u64,
+    ptr: &TriePtr,
+) -> Result<(TrieNodeType, TrieHash), Error> {
+    f.seek(SeekFrom::Start(trie_offset + u64::from(ptr.ptr())))?;
+    read_nodetype_at_head(f, clear_backptr(ptr.id()))
+}
+
+/// Point the children of `node`, a node of the trie of `block_id`, at the new offsets of any
+/// which live in pruned tries
+fn relocate_ptrs(
+    node: &mut TrieNodeType,
+    block_id: u32,
+    relocations: &HashMap<u32, HashMap<u32, u32>>,
+) -> Result<(), Error> {
+    for ptr in node.ptrs_mut().iter_mut() {
+        if ptr.id() == TrieNodeID::Empty as u8 {
+            continue;
+        }
+        let target_block_id = if is_backptr(ptr.id()) {
+            ptr.back_block()
+        } else {
+            block_id
+        };
+        let Some(relocation) = relocations.get(&target_block_id) else {
+            // a retained trie
+            continue;
+        };
+        ptr.ptr = *relocation.get(&ptr.ptr()).ok_or_else(|| {
+            Error::CorruptionError(format!(
+                "No node at {} in the trie of block {} is reachable",
+                ptr.ptr(),
+                target_block_id
+            ))
+        })?;
+    }
+    Ok(())
+}
+
+/// Swap in the pruned blobs file of a prune whose trie offsets were committed, if it has not
+/// been swapped in already.  Called whenever the MARF at `db_path` is opened, since the blobs
+/// file does not match the committed offsets until it is.
+pub fn finish_pruned_blobs_swap(
+    conn: &Connection,
+    db_path: &str,
+    readonly: bool,
+) -> Result<(), Error> {
+    if db_path == ":memory:" || !trie_sql::is_pruned_blobs_swap_pending(conn)? {
+        return Ok(());
+    }
+    let blobs_path = format!("{db_path}.blobs");
+    let pruned_path = format!("{blobs_path}.pruned");
+    if fs::metadata(&pruned_path).is_ok() {
+        info!("Swapping in pruned blobs file {pruned_path}");
+        fs::rename(&pruned_path, &blobs_path)?;
+    }
+    if !readonly {
+        trie_sql::set_pruned_blobs_swap_pending(conn, false)?;
+    }
+    Ok(())
+}
+
+/// Prune the MARF at `db_path`, keeping in full the tries of the `keep_recent` most recent
+/// blocks as of `tip` (on any fork), and of the blocks in `anchors`. The tip is always kept.
+///
+/// The MARF must store its tries in a blobs file, and must not be open anywhere else.
+pub fn prune_marf<T: MarfTrieId>(
+    db_path: &str,
+    tip: &T,
+    keep_recent: u32,
+    anchors: &HashSet<T>,
+) -> Result<MARFPruneStats, Error> {
+    if !TrieFile::exists(db_path)? {
+        return Err(Error::IOError(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{db_path} does not store its tries in a blobs file"),
+        )));
+    }
+    let open_opts = MARFOpenOpts::new(TrieHashCalculationMode::Deferred, "noop", true);
+    let mut marf: MARF<T> = MARF::from_path(db_path, open_opts)?;
+
+    let blobs_path = format!("{db_path}.blobs");
+    let mut stats = MARFPruneStats {
+        blobs_size_before: fs::metadata(&blobs_path)?.len(),
+        ..MARFPruneStats::default()
+    };
+
+    if marf.is_block_pruned(tip)? {
+        return Err(Error::PrunedError);
+    }
+    let tip_height = marf
+        .get_block_height(tip, tip)?
+        .ok_or(Error::NotFoundError)?;
+    let min_height = (tip_height + 1).saturating_sub(keep_recent.max(1));
+
+    // decide which tries to keep while every unpruned trie can still be read
+    let already_pruned = trie_sql::get_pruned_blocks(marf.sqlite_conn())?;
+    let mut tries = HashMap::new();
+    let mut order = vec![];
+    let mut retained = HashSet::new();
+    for (block_id, block_hash, offset, length) in
+        trie_sql::get_external_trie_blobs::<T>(marf.sqlite_conn())?
+    {
+        if !already_pruned.contains(&block_id) {
+            let height = marf
+                .get_block_height(&block_hash, &block_hash)?
+                .ok_or_else(|| {
+                    Error::CorruptionError(format!("No block height for {block_hash}"))
+                })?;
+            if height >= min_height || anchors.contains(&block_hash) {
+                retained.insert(block_id);
+            }
+        } else if anchors.contains(&block_hash) {
+            warn!("Anchor block {block_hash} was already pruned");
+        }
+        order.push(block_id);
+        tries.insert(
+            block_id,
+            TrieBlob {
+                block_hash,
+                offset,
+                length,
+            },
+        );
+    }
+    let mut conn = marf.into_sqlite_conn();
+
+    let trie_blob = |block_id: u32| {
+        tries.get(&block_id).ok_or_else(|| {
+            Error::CorruptionError(format!("No confirmed trie with block ID {block_id}"))
+        })
+    };
+    let mut blobs = BufReader::new(fs::File::open(&blobs_path)?);
+
+    // find the nodes of pruned tries which retained tries reach, and the nodes of retained tries
+    // which point to them
+    let mut reachable: HashMap<u32, BTreeMap<u32, u8>> = HashMap::new();
+    let mut pointing: HashMap<u32, Vec<TriePtr>> = HashMap::new();
+    let mut pending = vec![];
+    for block_id in order.iter().filter(|block_id| retained.contains(block_id)) {
+        let trie = trie_blob(*block_id)?;
+        let mut frontier = vec![root_ptr::<T>()];
+        while let Some(ptr) = frontier.pop() {
+            let (node, _) = read_node(&mut blobs, trie.offset, &ptr)?;
+            let mut points_to_pruned = false;
+            for child in node.ptrs().iter() {
+                if child.id() == TrieNodeID::Empty as u8 {
+                    continue;
+                }
+                if is_backptr(child.id()) {
+                    if !retained.contains(&child.back_block()) {
+                        pending.push((child.back_block(), child.from_backptr()));
+                        points_to_pruned = true;
+                    }
+                } else if child.id() != TrieNodeID::Leaf as u8 {
+                    frontier.push(child.clone());
+                }
+            }
+            if points_to_pruned {
+                pointing.entry(*block_id).or_default().push(ptr);
+            }
+        }
+    }
+    while let Some((block_id, ptr)) = pending.pop() {
+        let trie = trie_blob(block_id)?;
+        let nodes = reachable.entry(block_id).or_default();
+        if nodes.insert(ptr.ptr(), ptr.id()).is_some() || ptr.id() == TrieNodeID::Leaf as u8 {
+            continue;
+        }
+        let (node, _) = read_node(&mut blobs, trie.offset, &ptr)?;
+        for child in node.ptrs().iter() {
+            if child.id() == TrieNodeID::Empty as u8 {
+                continue;
+            }
+            if is_backptr(child.id()) {
+                if !retained.contains(&child.back_block()) {
+                    pending.push((child.back_block(), child.from_backptr()));
+                }
+            } else {
+                pending.push((block_id, child.clone()));
+            }
+        }
+    }
+
+    // lay out each pruned trie as its header and root node, followed by its reachable nodes
+    let root_offset = TrieStorageConnection::<T>::root_ptr_disk();
+    let mut relocations: HashMap<u32, HashMap<u32, u32>> = HashMap::new();
+    for block_id in order.iter().filter(|block_id| !retained.contains(block_id)) {
+        let trie = trie_blob(*block_id)?;
+        let (root, _) = read_node(&mut blobs, trie.offset, &root_ptr::<T>())?;
+        let mut next_offset = root_offset + get_node_byte_len(&root) as u32;
+        let mut relocation = HashMap::new();
+        for (offset, id) in reachable.get(block_id).into_iter().flatten() {
+            let (node, _) = read_node(&mut blobs, trie.offset, &TriePtr::new(*id, 0, *offset))?;
+            relocation.insert(*offset, next_offset);
+            next_offset += get_node_byte_len(&node) as u32;
+        }
+        relocations.insert(*block_id, relocation);
+    }
+
+    // write out the new blobs file
+    let pruned_path = format!("{blobs_path}.pruned");
+    let mut pruned_blobs = BufWriter::new(fs::File::create(&pruned_path)?);
+    let mut new_offsets = vec![];
+    let mut new_offset = 0u64;
+    for block_id in order.iter() {
+        let trie = trie_blob(*block_id)?;
+        let mut buf = vec![];
+        if retained.contains(block_id) {
+            buf.resize(usize::try_from(trie.length).expect("Trie blob too big"), 0);
+            blobs.seek(SeekFrom::Start(trie.offset))?;
+            blobs.read_exact(&mut buf)?;
+
+            // pointers are fixed-size, so nodes can be rewritten in place
+            let mut cursor = Cursor::new(buf);
+            for ptr in pointing.get(block_id).into_iter().flatten() {
+                let (mut node, hash) = read_node(&mut cursor, 0, ptr)?;
+                relocate_ptrs(&mut node, *block_id, &relocations)?;
+                cursor.seek(SeekFrom::Start(u64::from(ptr.ptr())))?;
+                write_nodetype_bytes(&mut cursor, &node, hash)?;
+            }
+            buf = cursor.into_inner();
+            stats.retained_tries += 1;
+        } else {
+            let (root, _) = read_node(&mut blobs, trie.offset, &root_ptr::<T>())?;
+            let header_len = root_offset as usize + get_node_byte_len(&root);
+            buf.resize(header_len, 0);
+            blobs.seek(SeekFrom::Start(trie.offset))?;
+            blobs.read_exact(&mut buf)?;
+
+            // the root's children are no longer reachable, but only its hash is ever read again
+            let mut cursor = Cursor::new(buf);
+            cursor.seek(SeekFrom::End(0))?;
+            for (offset, id) in reachable.get(block_id).into_iter().flatten() {
+                let (mut node, hash) =
+                    read_node(&mut blobs, trie.offset, &TriePtr::new(*id, 0, *offset))?;
+                relocate_ptrs(&mut node, *block_id, &relocations)?;
+                write_nodetype_bytes(&mut cursor, &node, hash)?;
+                stats.kept_nodes += 1;
+            }
+            buf = cursor.into_inner();
+            stats.pruned_tries += 1;
+        }
+        pruned_blobs.write_all(&buf)?;
+        new_offsets.push((*block_id, new_offset, buf.len() as u64));
+        new_offset += buf.len() as u64;
+    }
+    pruned_blobs
+        .into_inner()
+        .map_err(|e| Error::IOError(e.into_error()))?
+        .sync_all()?;
+    drop(blobs);
+
+    // commit the new offsets before swapping in the new blobs file.  If the swap is interrupted,
+    // the next open of the MARF finishes it.
+    let tx = tx_begin_immediate(&mut conn)?;
+    for (block_id, offset, length) in new_offsets.iter() {
+        let trie = trie_blob(*block_id)?;
+        trie_sql::update_external_trie_blob(&tx, &trie.block_hash, *offset, *length, *block_id)?;
+    }
+    let pruned: Vec<u32> = order
+        .iter()
+        .filter(|block_id| !retained.contains(block_id))
+        .cloned()
+        .collect();
+    trie_sql::set_pruned_blocks(&tx, &pruned)?;
+    trie_sql::drop_scratch_tries(&tx)?;
+    trie_sql::set_pruned_blobs_swap_pending(&tx, true)?;
+    tx.commit()?;
+
+    finish_pruned_blobs_swap(&conn, db_path, false)?;
+
+    stats.blobs_size_after = new_offset;
+    info!(
+        "Pruned MARF {db_path}: kept {} tries, compacted {} tries to {} nodes, shrank blobs from {} to {} bytes",
+        stats.retained_tries,
+        stats.pruned_tries,
+        stats.kept_nodes,
+        stats.blobs_size_before,
+        stats.blobs_size_after
+    );
+    Ok(stats)
+}
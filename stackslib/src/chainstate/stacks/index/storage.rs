@@ -51,7 +51,7 @@ use crate::chainstate::stacks::index::node::{
 use crate::chainstate::stacks::index::profile::TrieBenchmark;
 use crate::chainstate::stacks::index::trie::Trie;
 use crate::chainstate::stacks::index::{
-    trie_sql, BlockMap, ClarityMarfTrieId, Error, MarfTrieId, TrieHasher, TrieLeaf,
+    prune, trie_sql, BlockMap, ClarityMarfTrieId, Error, MarfTrieId, TrieHasher, TrieLeaf,
 };
 use crate::util_lib::db::{
     sql_pragma, sqlite_open, tx_begin_immediate, tx_busy_handler, Error as db_error,
@@ -1260,6 +1260,9 @@ pub struct TrieStorageTransientData<T: MarfTrieId> {
 
     /// Does this trie represent unconfirmed state?
     unconfirmed: bool,
+
+    /// IDs of the blocks whose tries have been pruned.  Loaded on first use.
+    pruned_blocks: Option<HashSet<u32>>,
 }
 
 // disk-backed Trie.
@@ -1407,8 +1410,16 @@ impl<T: MarfTrieId> TrieFileStorage<T> {
             trie_sql::create_tables_if_needed(&mut db)?;
         }
 
+        if marf_opts.external_blobs {
+            prune::finish_pruned_blobs_swap(&db, &db_path, readonly)?;
+        }
+
         let mut blobs = if marf_opts.external_blobs {
-            Some(TrieFile::from_db_path(&db_path, readonly)?)
+            Some(TrieFile::from_db_path_with_backend(
+                &db_path,
+                readonly,
+                marf_opts.blob_backend,
+            )?)
         } else {
             None
         };
@@ -1429,7 +1440,10 @@ impl<T: MarfTrieId> TrieFileStorage<T> {
         debug!(
             "Opened TrieFileStorage {}; external blobs: {}",
             db_path,
-            blobs.is_some()
+            blobs
+                .as_ref()
+                .map(|blobs| blobs.backend().to_string())
+                .unwrap_or("none".to_string())
         );
 
         let cache = TrieCache::new(&marf_opts.cache_strategy);
@@ -1460,6 +1474,7 @@ impl<T: MarfTrieId> TrieFileStorage<T> {
 
                 readonly,
                 unconfirmed,
+                pruned_blocks: None,
             },
 
             // used in testing in order to short-circuit block-height lookups
@@ -1514,10 +1529,13 @@ impl<T: MarfTrieId> TrieFileStorage<T> {
     pub fn reopen_readonly(&self) -> Result<TrieFileStorage<T>, Error> {
         let db = marf_sqlite_open(&self.db_path, OpenFlags::SQLITE_OPEN_READ_ONLY, false)?;
         let cache = TrieCache::default();
-        let blobs = if self.blobs.is_some() {
-            Some(TrieFile::from_db_path(&self.db_path, true)?)
-        } else {
-            None
+        let blobs = match self.blobs.as_ref() {
+            Some(blobs) => Some(TrieFile::from_db_path_with_backend(
+                &self.db_path,
+                true,
+                blobs.backend(),
+            )?),
+            None => None,
         };
 
         trace!("Make read-only view of TrieFileStorage: {}", &self.db_path);
@@ -1549,6 +1567,7 @@ impl<T: MarfTrieId> TrieFileStorage<T> {
 
                 readonly: true,
                 unconfirmed: self.unconfirmed(),
+                pruned_blocks: None,
             },
 
             // used in testing in order to short-circuit block-height lookups
@@ -1578,10 +1597,13 @@ impl<'a, T: MarfTrieId> TrieStorageTransaction<'a, T> {
     ///  _does not_ preserve the cur_block/open tip
     pub fn reopen_readonly(&self) -> Result<TrieFileStorage<T>, Error> {
         let db = marf_sqlite_open(&self.db_path, OpenFlags::SQLITE_OPEN_READ_ONLY, false)?;
-        let blobs = if self.blobs.is_some() {
-            Some(TrieFile::from_db_path(self.db_path, true)?)
-        } else {
-            None
+        let blobs = match self.blobs.as_ref() {
+            Some(blobs) => Some(TrieFile::from_db_path_with_backend(
+                self.db_path,
+                true,
+                blobs.backend(),
+            )?),
+            None => None,
         };
 
         trace!(
@@ -1618,6 +1640,7 @@ impl<'a, T: MarfTrieId> TrieStorageTransaction<'a, T> {
 
                 readonly: true,
                 unconfirmed: self.unconfirmed(),
+                pruned_blocks: None,
             },
 
             // used in testing in order to short-circuit block-height lookups
@@ -2248,6 +2271,50 @@ impl<T: MarfTrieId> TrieStorageConnection<'_, T> {
         self.data.cur_block_id.ok_or_else(|| Error::NotOpenedError)
     }
 
+    /// Has the trie of the given block been pruned?  A block which is not in the MARF has not.
+    pub fn is_block_pruned(&mut self, bhh: &T) -> Result<bool, Error> {
+        match self.get_block_id_caching(bhh) {
+            Ok(block_id) => self.is_block_id_pruned(block_id),
+            Err(Error::NotFoundError) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Has any trie in this MARF been pruned?
+    pub fn has_pruned_blocks(&mut self) -> Result<bool, Error> {
+        if self.data.pruned_blocks.is_none() {
+            self.data.pruned_blocks = Some(trie_sql::get_pruned_blocks(&self.db)?);
+        }
+        Ok(self
+            .data
+            .pruned_blocks
+            .as_ref()
+            .is_some_and(|pruned| !pruned.is_empty()))
+    }
+
+    fn is_block_id_pruned(&mut self, block_id: u32) -> Result<bool, Error> {
+        if self.data.pruned_blocks.is_none() {
+            self.data.pruned_blocks = Some(trie_sql::get_pruned_blocks(&self.db)?);
+        }
+        Ok(self
+            .data
+            .pruned_blocks
+            .as_ref()
+            .is_some_and(|pruned| pruned.contains(&block_id)))
+    }
+
+    /// Fail with PrunedError if the currently-open block's trie has been pruned.  Only the root
+    /// hash of a pruned trie can be read, so this must be checked before walking it.
+    pub fn check_open_block_not_pruned(&mut self) -> Result<(), Error> {
+        let Some(block_id) = self.data.cur_block_id else {
+            return Ok(());
+        };
+        if self.is_block_id_pruned(block_id)? {
+            return Err(Error::PrunedError);
+        }
+        Ok(())
+    }
+
     /// Get the currently-open block hash
     pub fn get_cur_block(&self) -> T {
         self.data.cur_block.clone()
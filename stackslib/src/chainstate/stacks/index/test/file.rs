@@ -15,6 +15,7 @@
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::fs;
+use std::io::Write;
 
 use rusqlite::{Connection, OpenFlags};
 
@@ -78,6 +79,70 @@ fn test_load_store_trie_blob() {
     assert_eq!(buf, vec![10, 20, 30, 40, 50]);
 }
 
+#[test]
+fn test_load_store_trie_blob_mmap() {
+    let mut db = setup_db("test_load_store_trie_blob_mmap");
+    let path = db_path("test_load_store_trie_blob_mmap");
+    let blobs_path = format!("{}.blobs", &path);
+    if fs::metadata(&blobs_path).is_ok() {
+        fs::remove_file(&blobs_path).unwrap();
+    }
+    trie_sql::migrate_tables_if_needed::<BlockHeaderHash>(&mut db).unwrap();
+
+    let mut blobs =
+        TrieFile::from_db_path_with_backend(&path, false, TrieFileBackend::Mmap).unwrap();
+    assert_eq!(blobs.backend(), TrieFileBackend::Mmap);
+    blobs
+        .store_trie_blob::<BlockHeaderHash>(&db, &BlockHeaderHash([0x01; 32]), &[1, 2, 3, 4, 5])
+        .unwrap();
+
+    // a reader opened now must remap to see tries appended later
+    let mut reader =
+        TrieFile::from_db_path_with_backend(&path, true, TrieFileBackend::Mmap).unwrap();
+    let block_id = trie_sql::get_block_identifier(&db, &BlockHeaderHash([0x01; 32])).unwrap();
+    assert_eq!(
+        reader.read_trie_blob(&db, block_id).unwrap(),
+        vec![1, 2, 3, 4, 5]
+    );
+
+    blobs
+        .store_trie_blob::<BlockHeaderHash>(
+            &db,
+            &BlockHeaderHash([0x02; 32]),
+            &[10, 20, 30, 40, 50],
+        )
+        .unwrap();
+    let block_id = trie_sql::get_block_identifier(&db, &BlockHeaderHash([0x02; 32])).unwrap();
+    assert_eq!(reader.get_trie_offset(&db, block_id).unwrap(), 5);
+    assert_eq!(
+        reader.read_trie_blob(&db, block_id).unwrap(),
+        vec![10, 20, 30, 40, 50]
+    );
+
+    // readers cannot write
+    assert!(reader
+        .store_trie_blob::<BlockHeaderHash>(&db, &BlockHeaderHash([0x03; 32]), &[1])
+        .is_err());
+
+    // bytes which no trie refers to are never overwritten
+    fs::OpenOptions::new()
+        .append(true)
+        .open(&blobs_path)
+        .unwrap()
+        .write_all(&[0xff; 3])
+        .unwrap();
+    blobs
+        .store_trie_blob::<BlockHeaderHash>(&db, &BlockHeaderHash([0x04; 32]), &[7, 8, 9])
+        .unwrap();
+    let block_id = trie_sql::get_block_identifier(&db, &BlockHeaderHash([0x04; 32])).unwrap();
+    assert_eq!(reader.get_trie_offset(&db, block_id).unwrap(), 13);
+    assert_eq!(reader.read_trie_blob(&db, block_id).unwrap(), vec![7, 8, 9]);
+
+    // the disk backend reads the same file
+    let mut disk = TrieFile::from_db_path(&path, true).unwrap();
+    assert_eq!(disk.read_trie_blob(&db, block_id).unwrap(), vec![7, 8, 9]);
+}
+
 #[test]
 fn test_migrate_existing_trie_blobs() {
     let test_file = "/tmp/test_migrate_existing_trie_blobs.sqlite";
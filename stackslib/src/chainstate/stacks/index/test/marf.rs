@@ -2268,3 +2268,98 @@ fn test_marf_unconfirmed() {
         assert!(false);
     }
 }
+
+#[test]
+fn marf_get_leaves() {
+    let marf_opts = MARFOpenOpts::default();
+    let mut m: MARF<BlockHeaderHash> = MARF::from_path(":memory:", marf_opts).unwrap();
+
+    let block_0 = BlockHeaderHash([0; 32]);
+    let block_1 = BlockHeaderHash([1; 32]);
+    let block_2 = BlockHeaderHash([2; 32]);
+    let fork_1 = BlockHeaderHash([3; 32]);
+
+    let insert = |m: &mut MARF<BlockHeaderHash>, keys: &[String], value: &str| {
+        let values = keys.iter().map(|_| MARFValue::from_value(value)).collect();
+        m.insert_batch(keys, values).unwrap();
+    };
+    let keys: Vec<String> = (0..300).map(|i| format!("key-{i}")).collect();
+
+    m.begin(&BlockHeaderHash::sentinel(), &block_0).unwrap();
+    insert(&mut m, &keys[..100], "block-0");
+    m.commit().unwrap();
+
+    // leaves come from both this trie and, through back-pointers, its ancestor
+    m.begin(&block_0, &block_1).unwrap();
+    insert(&mut m, &keys[100..200], "block-1");
+    insert(&mut m, &keys[..10], "block-1");
+    m.commit().unwrap();
+
+    m.begin(&block_1, &block_2).unwrap();
+    insert(&mut m, &keys[200..], "block-2");
+    m.commit().unwrap();
+
+    m.begin(&block_0, &fork_1).unwrap();
+    insert(&mut m, &["fork-key".to_string()], "fork-1");
+    m.commit().unwrap();
+
+    let leaves = m.get_leaves(&block_2, None, None, usize::MAX).unwrap();
+    assert!(leaves.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    for (path, value) in leaves.iter() {
+        assert_eq!(
+            m.get_from_hash(&block_2, path).unwrap().as_ref(),
+            Some(value)
+        );
+    }
+    for (i, key) in keys.iter().enumerate() {
+        let path = TrieHash::from_key(key);
+        let value = leaves.iter().find(|(p, _)| p == &path).unwrap().1.clone();
+        let expected = match i {
+            0..=9 => "block-1",
+            10..=99 => "block-0",
+            100..=199 => "block-1",
+            _ => "block-2",
+        };
+        assert_eq!(value, MARFValue::from_value(expected));
+    }
+    let fork_path = TrieHash::from_key("fork-key");
+    assert!(leaves.iter().all(|(path, _)| path != &fork_path));
+
+    // the leaves of an ancestor are those which it held
+    let leaves_0 = m.get_leaves(&block_0, None, None, usize::MAX).unwrap();
+    assert!(leaves_0.len() < leaves.len());
+    assert!(leaves_0
+        .iter()
+        .all(|(path, _)| path != &TrieHash::from_key(&keys[100])));
+
+    // paging from the last path of each page visits every leaf once
+    let mut paged = vec![];
+    loop {
+        let after = paged
+            .last()
+            .map(|(path, _): &(TrieHash, MARFValue)| path.clone());
+        let page = m.get_leaves(&block_2, after.as_ref(), None, 7).unwrap();
+        if page.is_empty() {
+            break;
+        }
+        paged.extend(page);
+    }
+    assert_eq!(paged, leaves);
+
+    // range scans only return paths in the range
+    let after = leaves[10].0.clone();
+    let until = leaves[20].0.clone();
+    let range = m
+        .get_leaves(&block_2, Some(&after), Some(&until), usize::MAX)
+        .unwrap();
+    assert_eq!(range, leaves[11..=20].to_vec());
+
+    // the open block is left as it was, even if the scan fails
+    let cur_block = m.borrow_storage_backend().get_cur_block();
+    m.get_leaves(&block_0, None, None, 1).unwrap();
+    assert_eq!(m.borrow_storage_backend().get_cur_block(), cur_block);
+    assert!(m
+        .get_leaves(&BlockHeaderHash([9; 32]), None, None, 1)
+        .is_err());
+    assert_eq!(m.borrow_storage_backend().get_cur_block(), cur_block);
+}
@@ -39,6 +39,7 @@ pub mod file;
 pub mod marf;
 pub mod node;
 pub mod proofs;
+pub mod prune;
 pub mod storage;
 pub mod trie;
 
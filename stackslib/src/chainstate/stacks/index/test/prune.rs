@@ -0,0 +1,242 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::HashSet;
+use std::fs;
+
+use super::*;
+use crate::chainstate::stacks::index::prune::*;
+use crate::chainstate::stacks::index::{trie_sql, Error, MarfTrieId};
+use crate::util_lib::db::sqlite_open;
+
+fn open_marf(path: &str) -> MARF<BlockHeaderHash> {
+    let marf_opts = MARFOpenOpts::new(TrieHashCalculationMode::Deferred, "noop", true);
+    MARF::from_path(path, marf_opts).unwrap()
+}
+
+fn insert_block(
+    m: &mut MARF<BlockHeaderHash>,
+    parent: &BlockHeaderHash,
+    block: &BlockHeaderHash,
+    height: usize,
+) {
+    let mut keys = vec![format!("key-{height}"), "shared".to_string()];
+    let mut values = vec![
+        MARFValue::from_value(&format!("value-{height}")),
+        MARFValue::from_value(&format!("shared-{height}")),
+    ];
+    for i in 0..50 {
+        keys.push(format!("bulk-{height}-{i}"));
+        values.push(MARFValue::from_value(&format!("bulk-{height}-{i}")));
+    }
+    m.begin(parent, block).unwrap();
+    m.insert_batch(&keys, values).unwrap();
+    m.commit().unwrap();
+}
+
+fn check_block(m: &mut MARF<BlockHeaderHash>, block: &BlockHeaderHash, height: usize) {
+    for i in 0..=height {
+        assert_eq!(
+            m.get(block, &format!("key-{i}")).unwrap(),
+            Some(MARFValue::from_value(&format!("value-{i}")))
+        );
+        assert_eq!(
+            m.get(block, &format!("bulk-{i}-7")).unwrap(),
+            Some(MARFValue::from_value(&format!("bulk-{i}-7")))
+        );
+    }
+    assert_eq!(
+        m.get(block, "shared").unwrap(),
+        Some(MARFValue::from_value(&format!("shared-{height}")))
+    );
+}
+
+/// Start a fresh MARF at `path` with a chain of `len` blocks, returning the blocks
+fn build_chain(path: &str, len: u8) -> Vec<BlockHeaderHash> {
+    let blobs_path = format!("{path}.blobs");
+    let pruned_path = format!("{blobs_path}.pruned");
+    for file in [path, &blobs_path, &pruned_path] {
+        if fs::metadata(file).is_ok() {
+            fs::remove_file(file).unwrap();
+        }
+    }
+
+    let blocks: Vec<_> = (0..len).map(|i| BlockHeaderHash([i + 1; 32])).collect();
+    let mut m = open_marf(path);
+    let mut parent = BlockHeaderHash::sentinel();
+    for (height, block) in blocks.iter().enumerate() {
+        insert_block(&mut m, &parent, block, height);
+        parent = block.clone();
+    }
+    blocks
+}
+
+#[test]
+fn marf_prune() {
+    let path = "/tmp/marf_prune.sqlite";
+    let blobs_path = format!("{path}.blobs");
+    for file in [path, &blobs_path] {
+        if fs::metadata(file).is_ok() {
+            fs::remove_file(file).unwrap();
+        }
+    }
+
+    let blocks: Vec<_> = (0..20).map(|i| BlockHeaderHash([i + 1; 32])).collect();
+    let fork = BlockHeaderHash([0xff; 32]);
+    let tip = blocks[19].clone();
+
+    let mut m = open_marf(path);
+    let mut parent = BlockHeaderHash::sentinel();
+    for (height, block) in blocks.iter().enumerate() {
+        insert_block(&mut m, &parent, block, height);
+        parent = block.clone();
+    }
+    insert_block(&mut m, &blocks[3], &fork, 4);
+
+    let root_hashes: Vec<_> = blocks
+        .iter()
+        .chain([&fork])
+        .map(|block| m.get_root_hash_at(block).unwrap())
+        .collect();
+    let leaves = m.get_leaves(&tip, None, None, usize::MAX).unwrap();
+    drop(m);
+
+    let anchors = HashSet::from([blocks[10].clone()]);
+    let stats = prune_marf(path, &tip, 5, &anchors).unwrap();
+    assert_eq!(stats.retained_tries, 6);
+    assert_eq!(stats.pruned_tries, 15);
+    assert!(stats.blobs_size_after < stats.blobs_size_before);
+    assert_eq!(
+        fs::metadata(&blobs_path).unwrap().len(),
+        stats.blobs_size_after
+    );
+
+    // retained tries are intact, even where they reach into pruned tries
+    let mut m = open_marf(path);
+    for height in [10, 15, 16, 17, 18, 19] {
+        assert!(!m.is_block_pruned(&blocks[height]).unwrap());
+        check_block(&mut m, &blocks[height], height);
+    }
+    assert_eq!(m.get_leaves(&tip, None, None, usize::MAX).unwrap(), leaves);
+
+    // pruned tries can't be read, but their root hashes are kept
+    for block in blocks[..10].iter().chain(&blocks[11..15]).chain([&fork]) {
+        assert!(m.is_block_pruned(block).unwrap());
+        assert!(matches!(m.get(block, "key-0"), Err(Error::PrunedError)));
+        assert!(matches!(
+            m.get_leaves(block, None, None, 1),
+            Err(Error::PrunedError)
+        ));
+    }
+    let pruned_root_hashes: Vec<_> = blocks
+        .iter()
+        .chain([&fork])
+        .map(|block| m.get_root_hash_at(block).unwrap())
+        .collect();
+    assert_eq!(pruned_root_hashes, root_hashes);
+    assert!(!m.is_block_pruned(&BlockHeaderHash([0xee; 32])).unwrap());
+
+    // the chain can still grow, but not from a pruned block
+    let next = BlockHeaderHash([0xaa; 32]);
+    insert_block(&mut m, &tip, &next, 20);
+    check_block(&mut m, &next, 20);
+    assert!(matches!(
+        m.begin(&blocks[3], &BlockHeaderHash([0xab; 32])),
+        Err(Error::PrunedError)
+    ));
+    drop(m);
+
+    // pruning again drops the nodes only the old anchor reached
+    let stats = prune_marf(path, &next, 2, &HashSet::new()).unwrap();
+    assert_eq!(stats.retained_tries, 2);
+    assert_eq!(stats.pruned_tries, 20);
+    let mut m = open_marf(path);
+    check_block(&mut m, &next, 20);
+    check_block(&mut m, &tip, 19);
+    assert!(m.is_block_pruned(&blocks[10]).unwrap());
+}
+
+#[test]
+fn marf_prune_proofs() {
+    let path = "/tmp/marf_prune_proofs.sqlite";
+    let blocks = build_chain(path, 10);
+    let tip = blocks[9].clone();
+
+    let mut m = open_marf(path);
+    assert!(!m.has_pruned_blocks().unwrap());
+    let root_to_block = m
+        .borrow_storage_backend()
+        .read_root_to_block_table()
+        .unwrap();
+    drop(m);
+
+    prune_marf(path, &tip, 3, &HashSet::new()).unwrap();
+    let mut m = open_marf(path);
+    assert!(m.has_pruned_blocks().unwrap());
+
+    // proofs which only pass through retained tries still verify against the tip
+    let tip_root_hash = m.get_root_hash_at(&tip).unwrap();
+    for key in ["key-9", "key-8", "key-7", "shared"] {
+        let (value, proof) = m.get_with_proof(&tip, key).unwrap().unwrap();
+        assert!(proof.verify(
+            &TrieHash::from_key(key),
+            &value,
+            &tip_root_hash,
+            &root_to_block
+        ));
+    }
+
+    // proofs which would pass through a pruned trie are refused, rather than left incomplete
+    for key in ["key-0", "key-6", "bulk-3-7"] {
+        assert_eq!(
+            m.get(&tip, key).unwrap(),
+            Some(MARFValue::from_value(&key.replacen("key", "value", 1)))
+        );
+        assert!(matches!(
+            m.get_with_proof(&tip, key),
+            Err(Error::PrunedError)
+        ));
+    }
+}
+
+#[test]
+fn marf_prune_interrupted_swap() {
+    let path = "/tmp/marf_prune_interrupted_swap.sqlite";
+    let blobs_path = format!("{path}.blobs");
+    let pruned_path = format!("{blobs_path}.pruned");
+    let blocks = build_chain(path, 10);
+    let tip = blocks[9].clone();
+    let unpruned_path = format!("{blobs_path}.unpruned");
+    fs::copy(&blobs_path, &unpruned_path).unwrap();
+
+    prune_marf(path, &tip, 3, &HashSet::new()).unwrap();
+    assert!(fs::metadata(&pruned_path).is_err());
+
+    // put things back as they were between committing the new offsets and swapping the files
+    fs::rename(&blobs_path, &pruned_path).unwrap();
+    fs::rename(&unpruned_path, &blobs_path).unwrap();
+    let conn = sqlite_open(path, rusqlite::OpenFlags::SQLITE_OPEN_READ_WRITE, false).unwrap();
+    trie_sql::set_pruned_blobs_swap_pending(&conn, true).unwrap();
+    drop(conn);
+
+    // opening the MARF finishes the swap
+    let mut m = open_marf(path);
+    assert!(fs::metadata(&pruned_path).is_err());
+    assert!(!trie_sql::is_pruned_blobs_swap_pending(m.sqlite_conn()).unwrap());
+    for height in 7..10 {
+        check_block(&mut m, &blocks[height], height);
+    }
+    assert!(m.is_block_pruned(&blocks[6]).unwrap());
+}
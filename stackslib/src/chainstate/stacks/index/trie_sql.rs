@@ -50,7 +50,7 @@ use crate::chainstate::stacks::index::node::{
 use crate::chainstate::stacks::index::storage::{TrieFileStorage, TrieStorageConnection};
 use crate::chainstate::stacks::index::{trie_sql, BlockMap, Error, MarfTrieId, TrieLeaf};
 use crate::util_lib::db::{
-    query_count, query_row, query_rows, sql_pragma, tx_begin_immediate, u64_to_sql,
+    query_count, query_row, query_rows, sql_pragma, tx_begin_immediate, u64_to_sql, FromColumn,
 };
 
 static SQL_MARF_DATA_TABLE: &str = "
@@ -97,6 +97,13 @@ INSERT OR REPLACE INTO schema_version (version) VALUES (2);
 INSERT OR REPLACE INTO migrated_version (version) VALUES (1);
 ";
 
+// created by the first prune, rather than by a schema migration, since migrating the schema
+// re-exports the trie blobs
+static SQL_MARF_PRUNED_TABLE: &str = "
+CREATE TABLE IF NOT EXISTS pruned_blocks (block_id INTEGER PRIMARY KEY);
+CREATE TABLE IF NOT EXISTS pruned_blobs_swap (pending INTEGER NOT NULL);
+";
+
 pub static SQL_MARF_SCHEMA_VERSION: u64 = 2;
 
 pub fn create_tables_if_needed(conn: &mut Connection) -> Result<(), Error> {
@@ -520,6 +527,23 @@ pub fn get_external_trie_offset_length(
     Ok((offset, length))
 }
 
+/// Get the block IDs and blobs file offsets of all confirmed tries whose block IDs are higher than
+/// `after`, or of all confirmed tries if `after` is `None`.
+pub fn get_external_trie_offsets_after(
+    conn: &Connection,
+    after: Option<u32>,
+) -> Result<Vec<(u32, u64)>, Error> {
+    let qry = "SELECT block_id, external_offset FROM marf_data WHERE unconfirmed = 0 AND block_id > ?1 ORDER BY block_id";
+    let after = after.map(i64::from).unwrap_or(-1);
+    let mut stmt = conn.prepare(qry)?;
+    let rows = stmt.query_and_then(params![after], |row| -> Result<(u32, u64), Error> {
+        let block_id: u32 = row.get("block_id")?;
+        let offset = u64::from_column(row, "external_offset")?;
+        Ok((block_id, offset))
+    })?;
+    rows.collect()
+}
+
 /// Get the offset of a trie blob in the blobs file, given its block header hash.
 pub fn get_external_trie_offset_length_by_bhh<T: MarfTrieId>(
     conn: &Connection,
@@ -539,6 +563,96 @@ pub fn get_external_blobs_length(conn: &Connection) -> Result<u64, Error> {
     Ok(max_len)
 }
 
+/// Get the block IDs, block hashes, and blobs file offsets and lengths of all confirmed tries, in
+/// the order in which they are stored in the blobs file.
+pub fn get_external_trie_blobs<T: MarfTrieId>(
+    conn: &Connection,
+) -> Result<Vec<(u32, T, u64, u64)>, Error> {
+    let qry = "SELECT block_id, block_hash, external_offset, external_length FROM marf_data WHERE unconfirmed = 0 ORDER BY external_offset";
+    let mut stmt = conn.prepare(qry)?;
+    let rows = stmt.query_and_then(NO_PARAMS, |row| -> Result<(u32, T, u64, u64), Error> {
+        let block_id: u32 = row.get("block_id")?;
+        let block_hash: T = row.get("block_hash")?;
+        let offset = u64::from_column(row, "external_offset")?;
+        let length = u64::from_column(row, "external_length")?;
+        Ok((block_id, block_hash, offset, length))
+    })?;
+    rows.collect()
+}
+
+/// Get the block IDs of all tries which have been pruned
+pub fn get_pruned_blocks(conn: &Connection) -> Result<HashSet<u32>, Error> {
+    let has_table = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'pruned_blocks'",
+            NO_PARAMS,
+            |_row| Ok(()),
+        )
+        .optional()?
+        .is_some();
+    if !has_table {
+        return Ok(HashSet::new());
+    }
+
+    let mut stmt = conn.prepare("SELECT block_id FROM pruned_blocks")?;
+    let rows = stmt.query_and_then(NO_PARAMS, |row| -> Result<u32, Error> {
+        Ok(row.get("block_id")?)
+    })?;
+    rows.collect()
+}
+
+/// Record that the tries of the given blocks have been pruned
+pub fn set_pruned_blocks(conn: &Connection, block_ids: &[u32]) -> Result<(), Error> {
+    conn.execute_batch(SQL_MARF_PRUNED_TABLE)?;
+    for block_id in block_ids.iter() {
+        conn.execute(
+            "INSERT OR IGNORE INTO pruned_blocks (block_id) VALUES (?1)",
+            params![block_id],
+        )?;
+    }
+    Ok(())
+}
+
+/// Record whether the pruned blobs file has yet to replace the blobs file.  The new trie offsets
+/// are only valid for the pruned blobs file, so this is set in the same transaction as them.
+pub fn set_pruned_blobs_swap_pending(conn: &Connection, pending: bool) -> Result<(), Error> {
+    conn.execute_batch(SQL_MARF_PRUNED_TABLE)?;
+    conn.execute("DELETE FROM pruned_blobs_swap", NO_PARAMS)?;
+    if pending {
+        conn.execute(
+            "INSERT INTO pruned_blobs_swap (pending) VALUES (1)",
+            NO_PARAMS,
+        )?;
+    }
+    Ok(())
+}
+
+/// Has a prune committed its trie offsets without swapping in its pruned blobs file?
+pub fn is_pruned_blobs_swap_pending(conn: &Connection) -> Result<bool, Error> {
+    let has_table = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'pruned_blobs_swap'",
+            NO_PARAMS,
+            |_row| Ok(()),
+        )
+        .optional()?
+        .is_some();
+    if !has_table {
+        return Ok(false);
+    }
+    Ok(conn
+        .query_row("SELECT 1 FROM pruned_blobs_swap", NO_PARAMS, |_row| Ok(()))
+        .optional()?
+        .is_some())
+}
+
+/// Drop every unconfirmed and mined trie.  These are scratch space, and are rebuilt as needed.
+pub fn drop_scratch_tries(conn: &Connection) -> Result<(), Error> {
+    conn.execute("DELETE FROM marf_data WHERE unconfirmed = 1", NO_PARAMS)?;
+    conn.execute("DELETE FROM mined_blocks", NO_PARAMS)?;
+    Ok(())
+}
+
 /// Do we have a partially-migrated database?
 /// Either all tries have offset and length 0, or they all don't.  If we have a mixture, then we're
 /// corrupted.
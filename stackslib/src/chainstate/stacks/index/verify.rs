@@ -0,0 +1,113 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Verification of MARF Merkle proofs.
+//!
+//! Proofs are made from a MARF's storage (see `proofs`), but are checked by the `libmarfproof`
+//! crate, which light clients can use without any chainstate or database. A proof is checked
+//! in its wire encoding, so the node and light clients check exactly the same bytes.
+
+use std::collections::HashMap;
+
+use libmarfproof::MarfProof;
+use stacks_common::codec::StacksMessageCodec;
+use stacks_common::types::chainstate::TrieHash;
+
+use crate::chainstate::stacks::index::{
+    ClarityMarfTrieId, MARFValue, MarfTrieId, TrieMerkleProof, TrieMerkleProofType,
+};
+
+impl<T: MarfTrieId> TrieMerkleProof<T> {
+    /// Given a value and the root hash from which this proof was
+    /// (supposedly) generated go and verify whether or not it is consistent with the root hash.
+    /// For the proof validation to work, the verifier needs to know which Trie roots correspond to
+    /// which block headers.  This can be calculated and verified independently from the blockchain
+    /// headers.
+    /// NOTE: Trie root hashes are globally unique by design, even if they represent the same contents, so the root_to_block map is bijective with high probability.
+    pub fn verify_proof(
+        proof: &[TrieMerkleProofType<T>],
+        path: &TrieHash,
+        value: &MARFValue,
+        root_hash: &TrieHash,
+        root_to_block: &HashMap<TrieHash, T>,
+    ) -> bool {
+        TrieMerkleProof::verify_proof_with(proof, path, value, root_hash, |trie_hash| {
+            root_to_block.get(trie_hash).cloned()
+        })
+    }
+
+    /// Like `verify_proof`, but asks `block_for_root` for the block whose trie has a given root
+    /// hash, so a verifier which learns block headers as it goes need not know them all up front.
+    pub fn verify_proof_with<F>(
+        proof: &[TrieMerkleProofType<T>],
+        path: &TrieHash,
+        value: &MARFValue,
+        root_hash: &TrieHash,
+        mut block_for_root: F,
+    ) -> bool
+    where
+        F: FnMut(&TrieHash) -> Option<T>,
+    {
+        let mut bytes = vec![];
+        (proof.len() as u32)
+            .consensus_serialize(&mut bytes)
+            .expect("Write error on memory buffer");
+        for item in proof.iter() {
+            item.consensus_serialize(&mut bytes)
+                .expect("Write error on memory buffer");
+        }
+        let proof = match MarfProof::from_bytes(&bytes) {
+            Ok(proof) => proof,
+            Err(e) => {
+                test_debug!("Invalid proof -- failed to decode: {e}");
+                return false;
+            }
+        };
+        proof.verify_with(&path.0, &value.0, &root_hash.0, |trie_hash| {
+            block_for_root(&TrieHash(*trie_hash)).map(|block| block.to_bytes())
+        })
+    }
+
+    /// Verify this proof
+    pub fn verify(
+        &self,
+        path: &TrieHash,
+        marf_value: &MARFValue,
+        root_hash: &TrieHash,
+        root_to_block: &HashMap<TrieHash, T>,
+    ) -> bool {
+        TrieMerkleProof::<T>::verify_proof(&self.0, path, marf_value, root_hash, root_to_block)
+    }
+
+    /// Verify this proof, looking up the blocks of ancestor tries with `block_for_root`
+    pub fn verify_with<F>(
+        &self,
+        path: &TrieHash,
+        marf_value: &MARFValue,
+        root_hash: &TrieHash,
+        block_for_root: F,
+    ) -> bool
+    where
+        F: FnMut(&TrieHash) -> Option<T>,
+    {
+        TrieMerkleProof::<T>::verify_proof_with(
+            &self.0,
+            path,
+            marf_value,
+            root_hash,
+            block_for_root,
+        )
+    }
+}
@@ -1409,7 +1409,7 @@ impl<'a> StacksMicroblockBuilder<'a> {
         self.runtime.considered.replace(considered);
         self.runtime.num_mined = num_txs;
 
-        mem_pool.drop_txs(&invalidated_txs)?;
+        mem_pool.drop_txs(&invalidated_txs, MemPoolDropReason::TOO_EXPENSIVE)?;
         event_dispatcher.mempool_txs_dropped(
             invalidated_txs,
             None,
@@ -2537,7 +2537,7 @@ impl StacksBlockBuilder {
             loop_result
         };
 
-        mempool.drop_txs(&invalidated_txs)?;
+        mempool.drop_txs(&invalidated_txs, MemPoolDropReason::TOO_EXPENSIVE)?;
 
         if let Some(observer) = event_observer {
             observer.mempool_txs_dropped(invalidated_txs, None, MemPoolDropReason::TOO_EXPENSIVE);
@@ -20,7 +20,25 @@ use std::path::PathBuf;
 use std::str::FromStr;
 use std::{env, fs, io, process};
 
+use clarity::vm::ast::span_locator::SpanLocator;
 use clarity::vm::coverage::CoverageReporter;
+use clarity::vm::debugger::cli::{parse_breakpoint, CommandLineFrontend};
+use clarity::vm::debugger::dap::DapFrontend;
+use clarity::vm::debugger::{Breakpoints, DebugFrontend, Debugger, Paused, Resume};
+use clarity::vm::profiler::{CostDimension, CostProfiler};
+use clarity::vm::tooling::bindings::rust::emit_rust;
+use clarity::vm::tooling::bindings::typescript::emit_typescript;
+use clarity::vm::tooling::bindings::Bindings;
+use clarity::vm::tooling::formatter::format_source;
+use clarity::vm::tooling::fuzz::{
+    clarity_literal, fuzz, test_senders, Counterexample, FuzzConfig, FuzzSession, FuzzTarget,
+};
+use clarity::vm::tooling::lsp::LanguageServer;
+use clarity::vm::tooling::upgrade::check_upgrade;
+use clarity::vm::tooling::verify::{
+    verify, Counterexample as VerifyCounterexample, Verdict as VerifyVerdict,
+};
+use clarity::vm::EvalHook;
 use lazy_static::lazy_static;
 use rand::Rng;
 use rusqlite::types::ToSql;
@@ -46,7 +64,9 @@ use crate::chainstate::stacks::boot::{
 };
 use crate::chainstate::stacks::index::storage::TrieFileStorage;
 use crate::chainstate::stacks::index::{ClarityMarfTrieId, MarfTrieId};
-use crate::clarity::vm::analysis::contract_interface_builder::build_contract_interface;
+use crate::clarity::vm::analysis::contract_interface_builder::{
+    build_contract_interface, ContractInterface,
+};
 use crate::clarity::vm::analysis::errors::{CheckError, CheckResult};
 use crate::clarity::vm::analysis::{AnalysisDatabase, ContractAnalysis};
 use crate::clarity::vm::ast::{build_ast_with_rules, ASTRules};
@@ -55,8 +75,11 @@ use crate::clarity::vm::costs::{ExecutionCost, LimitedCostTracker};
 use crate::clarity::vm::database::{
     BurnStateDB, ClarityDatabase, HeadersDB, STXBalance, SqliteConnection, NULL_BURN_STATE_DB,
 };
+use crate::clarity::vm::diagnostic::{Diagnostic, Level};
 use crate::clarity::vm::errors::{Error, InterpreterResult, RuntimeErrorType};
-use crate::clarity::vm::types::{OptionalData, PrincipalData, QualifiedContractIdentifier};
+use crate::clarity::vm::types::{
+    FixedFunction, FunctionType, OptionalData, PrincipalData, QualifiedContractIdentifier,
+};
 use crate::clarity::vm::{
     analysis, ast, eval_all, ClarityVersion, ContractContext, ContractName, SymbolicExpression,
     SymbolicExpressionType, Value,
@@ -121,7 +144,15 @@ where command is one of:
   eval_raw           to typecheck and evaluate an expression without a contract or database context.
   repl               to typecheck and evaluate expressions in a stdin/stdout loop.
   execute            to execute a public function of a defined contract.
+  debug              to step through a public function call in a debugger, without committing it.
+  fuzz               to call a contract's public functions with random arguments, checking its invariants.
+  fmt                to format contracts in the canonical style, or check that they are with --check.
+  bindings           to generate typed Rust or TypeScript bindings from a contract interface.
+  check_upgrade      to report the changes in a new version of a contract which can break its callers.
+  verify             to prove or refute that a contract's assertions and arithmetic can never fail.
   generate_address   to generate a random Stacks public address for testing purposes.
+  lsp                to run a Clarity language server over stdin/stdout.
+  make_lcov          to produce line and branch coverage reports from a coverage folder.
 ",
         invoked_by
     );
@@ -230,6 +261,25 @@ fn run_analysis<C: ClarityStorage>(
     header_db: &CLIHeadersDB,
     marf_kv: &mut C,
     save_contract: bool,
+) -> Result<ContractAnalysis, (CheckError, LimitedCostTracker)> {
+    // no type map data is used in the clarity_cli, except by `verify`
+    run_analysis_with_type_map(
+        contract_identifier,
+        expressions,
+        header_db,
+        marf_kv,
+        save_contract,
+        false,
+    )
+}
+
+fn run_analysis_with_type_map<C: ClarityStorage>(
+    contract_identifier: &QualifiedContractIdentifier,
+    expressions: &mut [SymbolicExpression],
+    header_db: &CLIHeadersDB,
+    marf_kv: &mut C,
+    save_contract: bool,
+    build_type_map: bool,
 ) -> Result<ContractAnalysis, (CheckError, LimitedCostTracker)> {
     let mainnet = header_db.is_mainnet();
     let clarity_version = ClarityVersion::default_for_epoch(DEFAULT_CLI_EPOCH);
@@ -253,9 +303,82 @@ fn run_analysis<C: ClarityStorage>(
         cost_track,
         DEFAULT_CLI_EPOCH,
         clarity_version,
-        // no type map data is used in the clarity_cli
+        build_type_map,
+    )
+}
+
+/// Analyse one version of a contract for `check_upgrade`, without saving it.
+/// A failure is returned as the command's result.
+fn analyse_version<C: ClarityStorage>(
+    contract_identifier: &QualifiedContractIdentifier,
+    file: &str,
+    source: &str,
+    header_db: &CLIHeadersDB,
+    marf_kv: &mut C,
+) -> Result<ContractAnalysis, serde_json::Value> {
+    let mut ast = friendly_expect(
+        parse(contract_identifier, source, ClarityVersion::Clarity2),
+        &format!("Failed to parse program: {}", file),
+    );
+    run_analysis(contract_identifier, &mut ast, header_db, marf_kv, false).map_err(|(e, _)| {
+        json!({
+            "message": format!("Checks failed for {}.", file),
+            "error": {
+                "analysis": serde_json::to_value(&e.diagnostic).unwrap(),
+            },
+        })
+    })
+}
+
+/// Analyse a contract for `verify`, with the type of every expression.
+/// A failure is returned as the command's result.
+fn analyse_with_types<C: ClarityStorage>(
+    contract_identifier: &QualifiedContractIdentifier,
+    file: &str,
+    source: &str,
+    header_db: &CLIHeadersDB,
+    marf_kv: &mut C,
+) -> Result<ContractAnalysis, serde_json::Value> {
+    let mut ast = friendly_expect(
+        parse(contract_identifier, source, ClarityVersion::Clarity2),
+        &format!("Failed to parse program: {}", file),
+    );
+    run_analysis_with_type_map(
+        contract_identifier,
+        &mut ast,
+        header_db,
+        marf_kv,
         false,
+        true,
     )
+    .map_err(|(e, _)| {
+        json!({
+            "message": format!("Checks failed for {}.", file),
+            "error": {
+                "analysis": serde_json::to_value(&e.diagnostic).unwrap(),
+            },
+        })
+    })
+}
+
+/// Collect every analysis error and lint warning for a contract. This is only
+/// used for reporting: `run_analysis()` still decides whether the contract passes.
+fn run_analysis_diagnostics<C: ClarityStorage>(
+    contract_identifier: &QualifiedContractIdentifier,
+    expressions: &[SymbolicExpression],
+    source_code: &str,
+    marf_kv: &mut C,
+) -> Vec<Diagnostic> {
+    let clarity_version = ClarityVersion::default_for_epoch(DEFAULT_CLI_EPOCH);
+    let (_, diagnostics) = analysis::run_analysis_with_diagnostics(
+        contract_identifier,
+        expressions,
+        &mut marf_kv.get_analysis_db(),
+        DEFAULT_CLI_EPOCH,
+        clarity_version,
+        &SpanLocator::new(source_code, expressions),
+    );
+    diagnostics
 }
 
 fn create_or_open_db(path: &String) -> Connection {
@@ -414,6 +537,23 @@ fn with_env_costs<F, R>(
     coverage: Option<&mut CoverageReporter>,
     f: F,
 ) -> (R, ExecutionCost)
+where
+    F: FnOnce(&mut OwnedEnvironment) -> R,
+{
+    let hooks: Vec<&mut dyn EvalHook> = match coverage {
+        Some(coverage) => vec![coverage],
+        None => vec![],
+    };
+    with_env_costs_and_hooks(mainnet, header_db, marf, hooks, f)
+}
+
+fn with_env_costs_and_hooks<F, R>(
+    mainnet: bool,
+    header_db: &CLIHeadersDB,
+    marf: &mut WritableMarfStore,
+    hooks: Vec<&mut dyn EvalHook>,
+    f: F,
+) -> (R, ExecutionCost)
 where
     F: FnOnce(&mut OwnedEnvironment) -> R,
 {
@@ -437,8 +577,8 @@ where
         cost_track,
         DEFAULT_CLI_EPOCH,
     );
-    if let Some(coverage) = coverage {
-        vm_env.add_eval_hook(coverage);
+    for hook in hooks {
+        vm_env.add_eval_hook(hook);
     }
     let result = f(&mut vm_env);
     let cost = vm_env.get_cost_total();
@@ -473,6 +613,71 @@ pub fn vm_execute(program: &str, clarity_version: ClarityVersion) -> Result<Opti
     })
 }
 
+/// Parse the Clarity values passed as arguments to a public function
+fn parse_function_arguments(arguments: &[String]) -> Vec<SymbolicExpression> {
+    arguments
+        .iter()
+        .map(|argument| {
+            let clarity_version = ClarityVersion::default_for_epoch(DEFAULT_CLI_EPOCH);
+            let argument_parsed = friendly_expect(
+                vm_execute(argument, clarity_version),
+                &format!("Error parsing argument \"{}\"", argument),
+            );
+            let argument_value = friendly_expect_opt(
+                argument_parsed,
+                &format!("Failed to parse a value from the argument: {}", argument),
+            );
+            SymbolicExpression::atom_value(argument_value)
+        })
+        .collect()
+}
+
+/// Decode arguments in the JSON representation of Clarity values, against the
+///  signature of a public function
+fn parse_json_function_arguments<C: ClarityStorage>(
+    marf_kv: &mut C,
+    contract_identifier: &QualifiedContractIdentifier,
+    function: &str,
+    arguments: &[String],
+) -> Vec<SymbolicExpression> {
+    let function_type = friendly_expect(
+        marf_kv.get_analysis_db().execute(|db| {
+            db.get_public_function_type(contract_identifier, function, &DEFAULT_CLI_EPOCH)
+        }),
+        "Failed to load the contract.",
+    );
+    let Some(FunctionType::Fixed(FixedFunction { args, .. })) = function_type else {
+        eprintln!(
+            "No public function `{}` in {}",
+            function, contract_identifier
+        );
+        panic_test!();
+    };
+    if args.len() != arguments.len() {
+        eprintln!(
+            "`{}` expects {} arguments, but {} were given",
+            function,
+            args.len(),
+            arguments.len()
+        );
+        panic_test!();
+    }
+    args.iter()
+        .zip(arguments)
+        .map(|(arg, argument)| {
+            let json: serde_json::Value = friendly_expect(
+                serde_json::from_str(argument),
+                &format!("Error parsing argument \"{}\" as JSON", argument),
+            );
+            let argument_value = friendly_expect(
+                Value::from_json(&json, &arg.signature),
+                &format!("Error decoding argument `{}`", arg.name),
+            );
+            SymbolicExpression::atom_value(argument_value)
+        })
+        .collect()
+}
+
 fn save_coverage(
     coverage_folder: Option<String>,
     coverage: Option<CoverageReporter>,
@@ -494,6 +699,173 @@ fn save_coverage(
     }
 }
 
+/// The cost profiling options of `eval` and `execute`
+struct ProfileOptions {
+    /// Add the per-function and per-expression cost tables to the output
+    json: bool,
+    /// Write the self costs, in one dimension, as folded stacks to this file
+    folded: Option<(String, CostDimension)>,
+}
+
+impl ProfileOptions {
+    fn consume(argv: &mut Vec<String>) -> ProfileOptions {
+        let json = matches!(consume_arg(argv, &["--profile"], false), Ok(Some(_)));
+        let folded_file = friendly_expect(
+            consume_arg(argv, &["--folded"], true),
+            "Expected a file name after --folded",
+        );
+        let dimension = friendly_expect(
+            consume_arg(argv, &["--folded_cost"], true),
+            "Expected a cost dimension after --folded_cost",
+        )
+        .map(|dimension| friendly_expect(dimension.parse(), "Invalid --folded_cost"))
+        .unwrap_or(CostDimension::Runtime);
+        ProfileOptions {
+            json,
+            folded: folded_file.map(|file| (file, dimension)),
+        }
+    }
+
+    fn profiler(&self) -> Option<CostProfiler> {
+        if self.json || self.folded.is_some() {
+            Some(CostProfiler::new())
+        } else {
+            None
+        }
+    }
+}
+
+fn eval_hooks<'a>(
+    coverage: Option<&'a mut CoverageReporter>,
+    profiler: Option<&'a mut CostProfiler>,
+) -> Vec<&'a mut dyn EvalHook> {
+    let mut hooks: Vec<&mut dyn EvalHook> = vec![];
+    if let Some(coverage) = coverage {
+        hooks.push(coverage);
+    }
+    if let Some(profiler) = profiler {
+        hooks.push(profiler);
+    }
+    hooks
+}
+
+fn save_profile(
+    result: &mut serde_json::Value,
+    options: &ProfileOptions,
+    profiler: Option<CostProfiler>,
+    cost: &ExecutionCost,
+) {
+    let Some(profiler) = profiler else {
+        return;
+    };
+    if options.json {
+        result["profile"] = profiler.to_json(cost);
+    }
+    if let Some((ref folded_file, dimension)) = options.folded {
+        let mut output = friendly_expect(
+            fs::File::create(folded_file),
+            &format!("Failed to create {}", folded_file),
+        );
+        friendly_expect(
+            profiler.write_folded(&mut output, dimension),
+            &format!("Failed to write {}", folded_file),
+        );
+    }
+}
+
+/// Quote `arg` for a POSIX shell
+fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+/// The `clarity-cli` commands which replay a fuzzing counterexample, and then
+///  evaluate the invariant it breaks
+fn fuzz_reproducer(
+    invoked_by: &str,
+    vm_filename: &str,
+    contract_identifier: &QualifiedContractIdentifier,
+    counterexample: &Counterexample,
+) -> Vec<String> {
+    let mut commands: Vec<_> = counterexample
+        .calls
+        .iter()
+        .map(|call| {
+            let mut command = format!(
+                "{} execute {} {} {} {}",
+                invoked_by,
+                shell_quote(vm_filename),
+                contract_identifier,
+                call.function,
+                call.sender
+            );
+            for arg in call.args.iter() {
+                command.push(' ');
+                command.push_str(&shell_quote(&clarity_literal(arg)));
+            }
+            command
+        })
+        .collect();
+    commands.push(format!(
+        "echo '({})' | {} eval_at_chaintip {} {}",
+        counterexample.violation.invariant,
+        invoked_by,
+        contract_identifier,
+        shell_quote(vm_filename)
+    ));
+    commands
+}
+
+/// The `clarity-cli` command which replays a verification counterexample:
+///  `execute` for a public function, and `eval_at_chaintip` for a read-only one
+fn verify_reproducer(
+    invoked_by: &str,
+    vm_filename: &str,
+    contract_identifier: &str,
+    read_only: bool,
+    counterexample: &VerifyCounterexample,
+) -> String {
+    let args = counterexample.arg_literals();
+    if read_only {
+        let call = format!("({} {})", counterexample.function, args.join(" "));
+        format!(
+            "echo {} | {} eval_at_chaintip {} {}",
+            shell_quote(&call),
+            invoked_by,
+            contract_identifier,
+            vm_filename
+        )
+    } else {
+        let mut command = format!(
+            "{} execute {} {} {} {}",
+            invoked_by,
+            vm_filename,
+            contract_identifier,
+            counterexample.function,
+            counterexample.sender
+        );
+        for arg in args.iter() {
+            command.push(' ');
+            command.push_str(&shell_quote(arg));
+        }
+        command
+    }
+}
+
+/// The front-end driving a `clarity-cli debug` session
+enum DebugSession<'a> {
+    CommandLine(CommandLineFrontend<io::StdinLock<'a>, io::Stdout>),
+    Dap(DapFrontend<io::StdinLock<'a>, io::Stdout>),
+}
+
+impl DebugFrontend for DebugSession<'_> {
+    fn paused(&mut self, paused: &mut Paused) -> Resume {
+        match self {
+            DebugSession::CommandLine(frontend) => frontend.paused(paused),
+            DebugSession::Dap(frontend) => frontend.paused(paused),
+        }
+    }
+}
+
 struct CLIHeadersDB {
     db_path: String,
     conn: Connection,
@@ -776,7 +1148,7 @@ impl HeadersDB for CLIHeadersDB {
 fn get_eval_input(invoked_by: &str, args: &[String]) -> EvalInput {
     if args.len() < 3 || args.len() > 4 {
         eprintln!(
-            "Usage: {} {} [--costs] [contract-identifier] (program.clar) [vm-state.db]",
+            "Usage: {} {} [--costs] [--profile] [--folded FILE [--folded_cost DIMENSION]] [contract-identifier] (program.clar) [vm-state.db]",
             invoked_by, args[0]
         );
         panic_test!();
@@ -1114,7 +1486,7 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) -> (i32, Option<serde_j
         "check" => {
             if args.len() < 2 {
                 eprintln!(
-                    "Usage: {} {} [program-file.clar] [--contract_id CONTRACT_ID] [--output_analysis] [--costs] [--testnet] (vm-state.db)",
+                    "Usage: {} {} [program-file.clar] [--contract_id CONTRACT_ID] [--output_analysis] [--costs] [--testnet] [--deny_warnings] (vm-state.db)",
                     invoked_by, args[0]
                 );
                 panic_test!();
@@ -1144,6 +1516,10 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) -> (i32, Option<serde_j
                 };
 
             let costs = matches!(consume_arg(&mut argv, &["--costs"], false), Ok(Some(_)));
+            let deny_warnings = matches!(
+                consume_arg(&mut argv, &["--deny_warnings"], false),
+                Ok(Some(_))
+            );
 
             // NOTE: ignored if we're using a DB
             let mut testnet_given = false;
@@ -1192,7 +1568,9 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) -> (i32, Option<serde_j
                     let result = at_chaintip(&argv[2], marf_kv, |mut marf| {
                         let result =
                             run_analysis(&contract_id, &mut ast, &header_db, &mut marf, false);
-                        (marf, result)
+                        let diagnostics =
+                            run_analysis_diagnostics(&contract_id, &ast, &content, &mut marf);
+                        (marf, (result, diagnostics))
                     });
                     result
                 } else {
@@ -1200,15 +1578,20 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) -> (i32, Option<serde_j
                     let mut analysis_marf = MemoryBackingStore::new();
 
                     install_boot_code(&header_db, &mut analysis_marf);
-                    run_analysis(
+                    let result = run_analysis(
                         &contract_id,
                         &mut ast,
                         &header_db,
                         &mut analysis_marf,
                         false,
-                    )
+                    );
+                    let diagnostics =
+                        run_analysis_diagnostics(&contract_id, &ast, &content, &mut analysis_marf);
+                    (result, diagnostics)
                 }
             };
+            let (contract_analysis_res, diagnostics) = contract_analysis_res;
+            let has_warnings = diagnostics.iter().any(|d| d.level == Level::Warning);
 
             let mut contract_analysis = match contract_analysis_res {
                 Ok(contract_analysis) => contract_analysis,
@@ -1217,7 +1600,8 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) -> (i32, Option<serde_j
                         "message": "Checks failed.",
                         "error": {
                             "analysis": serde_json::to_value(&e.diagnostic).unwrap(),
-                        }
+                        },
+                        "diagnostics": serde_json::to_value(&diagnostics).unwrap(),
                     });
                     add_costs(&mut result, costs, cost_tracker.get_total());
                     return (1, Some(result));
@@ -1225,7 +1609,8 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) -> (i32, Option<serde_j
             };
 
             let mut result = json!({
-                "message": "Checks passed."
+                "message": if has_warnings { "Checks passed with warnings." } else { "Checks passed." },
+                "diagnostics": serde_json::to_value(&diagnostics).unwrap(),
             });
 
             add_costs(
@@ -1239,7 +1624,11 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) -> (i32, Option<serde_j
                     serde_json::to_value(&build_contract_interface(&contract_analysis).unwrap())
                         .unwrap();
             }
-            (0, Some(result))
+            if has_warnings && deny_warnings {
+                (2, Some(result))
+            } else {
+                (0, Some(result))
+            }
         }
         "repl" => {
             let mut argv = args.to_vec();
@@ -1373,6 +1762,7 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) -> (i32, Option<serde_j
             let mut argv = args.to_vec();
 
             let costs = matches!(consume_arg(&mut argv, &["--costs"], false), Ok(Some(_)));
+            let profile = ProfileOptions::consume(&mut argv);
 
             let evalInput = get_eval_input(invoked_by, &argv);
             let vm_filename = if argv.len() == 3 { &argv[2] } else { &argv[3] };
@@ -1388,9 +1778,14 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) -> (i32, Option<serde_j
                 ClarityVersion::Clarity2,
             );
 
+            let mut profiler = profile.profiler();
             let (_, _, result_and_cost) = in_block(header_db, marf_kv, |header_db, mut marf| {
-                let result_and_cost =
-                    with_env_costs(mainnet, &header_db, &mut marf, None, |vm_env| {
+                let result_and_cost = with_env_costs_and_hooks(
+                    mainnet,
+                    &header_db,
+                    &mut marf,
+                    eval_hooks(None, profiler.as_mut()),
+                    |vm_env| {
                         vm_env
                             .get_exec_environment(None, None, &placeholder_context)
                             .eval_read_only_with_rules(
@@ -1398,7 +1793,8 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) -> (i32, Option<serde_j
                                 &evalInput.content,
                                 ASTRules::PrecheckSize,
                             )
-                    });
+                    },
+                );
                 (header_db, marf, result_and_cost)
             });
 
@@ -1410,6 +1806,7 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) -> (i32, Option<serde_j
                     });
 
                     add_serialized_output(&mut result_json, result);
+                    save_profile(&mut result_json, &profile, profiler, &cost);
                     add_costs(&mut result_json, costs, cost);
 
                     (0, Some(result_json))
@@ -1422,6 +1819,7 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) -> (i32, Option<serde_j
                         "success": false,
                     });
 
+                    save_profile(&mut result_json, &profile, profiler, &cost);
                     add_costs(&mut result_json, costs, cost);
 
                     (1, Some(result_json))
@@ -1433,6 +1831,7 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) -> (i32, Option<serde_j
 
             let costs = matches!(consume_arg(&mut argv, &["--costs"], false), Ok(Some(_)));
             let coverage_folder = consume_arg(&mut argv, &["--c"], true).unwrap_or(None);
+            let profile = ProfileOptions::consume(&mut argv);
 
             let evalInput = get_eval_input(invoked_by, &argv);
             let vm_filename = if argv.len() == 3 { &argv[2] } else { &argv[3] };
@@ -1453,12 +1852,13 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) -> (i32, Option<serde_j
             } else {
                 None
             };
+            let mut profiler = profile.profiler();
             let result_and_cost = at_chaintip(vm_filename, marf_kv, |mut marf| {
-                let result_and_cost = with_env_costs(
+                let result_and_cost = with_env_costs_and_hooks(
                     mainnet,
                     &header_db,
                     &mut marf,
-                    coverage.as_mut(),
+                    eval_hooks(coverage.as_mut(), profiler.as_mut()),
                     |vm_env| {
                         vm_env
                             .get_exec_environment(None, None, &placeholder_context)
@@ -1483,6 +1883,7 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) -> (i32, Option<serde_j
                     });
 
                     add_serialized_output(&mut result_json, result);
+                    save_profile(&mut result_json, &profile, profiler, &cost);
                     add_costs(&mut result_json, costs, cost);
 
                     (0, Some(result_json))
@@ -1496,6 +1897,7 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) -> (i32, Option<serde_j
                         "success": false,
                     });
 
+                    save_profile(&mut result_json, &profile, profiler, &cost);
                     add_costs(&mut result_json, costs, cost);
 
                     (1, Some(result_json))
@@ -1729,19 +2131,21 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) -> (i32, Option<serde_j
 
             let costs = matches!(consume_arg(&mut argv, &["--costs"], false), Ok(Some(_)));
             let assets = matches!(consume_arg(&mut argv, &["--assets"], false), Ok(Some(_)));
+            let json_args = matches!(consume_arg(&mut argv, &["--json"], false), Ok(Some(_)));
+            let profile = ProfileOptions::consume(&mut argv);
 
             if argv.len() < 5 {
-                eprintln!("Usage: {} {} [--costs] [--assets] [vm-state.db] [contract-identifier] [public-function-name] [sender-address] [args...]", invoked_by, argv[0]);
+                eprintln!("Usage: {} {} [--costs] [--assets] [--json] [--profile] [--folded FILE [--folded_cost DIMENSION]] [vm-state.db] [contract-identifier] [public-function-name] [sender-address] [args...]", invoked_by, argv[0]);
+                eprintln!("   With --json, args are in the JSON representation of Clarity values (e.g. '\"100\"' for u100),");
+                eprintln!(
+                    "   decoded against the function's signature, rather than Clarity literals."
+                );
                 panic_test!();
             }
 
             let vm_filename = &argv[1];
             let header_db =
                 friendly_expect(CLIHeadersDB::resume(vm_filename), "Failed to open CLI DB");
-            let marf_kv = friendly_expect(
-                MarfedKV::open(vm_filename, None, None),
-                "Failed to open VM database.",
-            );
             let mainnet = header_db.is_mainnet();
             let contract_identifier = friendly_expect(
                 QualifiedContractIdentifier::parse(&argv[2]),
@@ -1760,33 +2164,42 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) -> (i32, Option<serde_j
                 }
             };
 
-            let arguments: Vec<_> = argv[5..]
-                .iter()
-                .map(|argument| {
-                    let clarity_version = ClarityVersion::default_for_epoch(DEFAULT_CLI_EPOCH);
-                    let argument_parsed = friendly_expect(
-                        vm_execute(argument, clarity_version),
-                        &format!("Error parsing argument \"{}\"", argument),
-                    );
-                    let argument_value = friendly_expect_opt(
-                        argument_parsed,
-                        &format!("Failed to parse a value from the argument: {}", argument),
+            let marf_kv = friendly_expect(
+                MarfedKV::open(vm_filename, None, None),
+                "Failed to open VM database.",
+            );
+            let (arguments, marf_kv) = if json_args {
+                // decoded before the block is begun, so that a bad argument leaves the chain tip
+                let arguments = at_chaintip(vm_filename, marf_kv, |mut marf| {
+                    let arguments = parse_json_function_arguments(
+                        &mut marf,
+                        &contract_identifier,
+                        tx_name,
+                        &argv[5..],
                     );
-                    SymbolicExpression::atom_value(argument_value)
-                })
-                .collect();
+                    (marf, arguments)
+                });
+                let marf_kv = friendly_expect(
+                    MarfedKV::open(vm_filename, None, None),
+                    "Failed to open VM database.",
+                );
+                (arguments, marf_kv)
+            } else {
+                (parse_function_arguments(&argv[5..]), marf_kv)
+            };
 
             let mut coverage = if coverage_folder.is_some() {
                 Some(CoverageReporter::new())
             } else {
                 None
             };
+            let mut profiler = profile.profiler();
             let (_, _, result_and_cost) = in_block(header_db, marf_kv, |header_db, mut marf| {
-                let result_and_cost = with_env_costs(
+                let result_and_cost = with_env_costs_and_hooks(
                     mainnet,
                     &header_db,
                     &mut marf,
-                    coverage.as_mut(),
+                    eval_hooks(coverage.as_mut(), profiler.as_mut()),
                     |vm_env| {
                         vm_env.execute_transaction(
                             sender,
@@ -1813,6 +2226,7 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) -> (i32, Option<serde_j
                             });
 
                             add_serialized_output(&mut result, *data.data);
+                            save_profile(&mut result, &profile, profiler, &cost);
                             add_costs(&mut result, costs, cost);
                             add_assets(&mut result, assets, asset_map);
 
@@ -1832,6 +2246,7 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) -> (i32, Option<serde_j
                                 "success": false,
                             });
 
+                            save_profile(&mut result, &profile, profiler, &cost);
                             add_costs(&mut result, costs, cost);
                             add_serialized_output(&mut result, *data.data);
                             add_assets(&mut result, assets, asset_map);
@@ -1849,23 +2264,43 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) -> (i32, Option<serde_j
                         (1, Some(result))
                     }
                 }
-                (Err(error), ..) => {
-                    let result = json!({
+                (Err(error), cost) => {
+                    let mut result = json!({
                         "error": {
                             "runtime": "Transaction execution error.",
                             "error": serde_json::to_value(&format!("{}", error)).unwrap()
                         },
                         "success": false,
                     });
+                    save_profile(&mut result, &profile, profiler, &cost);
                     (1, Some(result))
                 }
             }
         }
         "make_lcov" => {
+            let mut argv = args.to_vec();
+            let cobertura_output_file = consume_arg(&mut argv, &["--cobertura"], true)
+                .unwrap_or_else(|e| {
+                    eprintln!("--cobertura: {e}");
+                    panic_test!();
+                });
+            let html_output_file = consume_arg(&mut argv, &["--html"], true).unwrap_or_else(|e| {
+                eprintln!("--html: {e}");
+                panic_test!();
+            });
+
+            if argv.len() < 3 {
+                eprintln!(
+                    "Usage: {} {} [--cobertura FILE] [--html FILE] [coverage-folder] [lcov-output-file]",
+                    invoked_by, argv[0]
+                );
+                panic_test!();
+            }
+
             let mut register_files = vec![];
             let mut coverage_files = vec![];
-            let coverage_folder = &args[1];
-            let lcov_output_file = &args[2];
+            let coverage_folder = &argv[1];
+            let lcov_output_file = &argv[2];
             for folder_entry in
                 fs::read_dir(coverage_folder).expect("Failed to read the coverage folder")
             {
@@ -1882,8 +2317,616 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) -> (i32, Option<serde_j
             }
             CoverageReporter::produce_lcov(lcov_output_file, &register_files, &coverage_files)
                 .expect("Failed to produce an lcov output");
+            if let Some(ref cobertura_output_file) = cobertura_output_file {
+                CoverageReporter::produce_cobertura(
+                    cobertura_output_file,
+                    &register_files,
+                    &coverage_files,
+                )
+                .expect("Failed to produce a Cobertura output");
+            }
+            if let Some(ref html_output_file) = html_output_file {
+                CoverageReporter::produce_html(html_output_file, &register_files, &coverage_files)
+                    .expect("Failed to produce an HTML output");
+            }
+            (0, None)
+        }
+        "debug" => {
+            let mut argv = args.to_vec();
+            let dap = matches!(consume_arg(&mut argv, &["--dap"], false), Ok(Some(_)));
+            let mut breakpoint_specs = vec![];
+            while let Ok(Some(spec)) = consume_arg(&mut argv, &["--break"], true) {
+                breakpoint_specs.push(spec);
+            }
+
+            if argv.len() < 5 {
+                eprintln!("Usage: {} {} [--dap] [--break [CONTRACT:]LINE]... [vm-state.db] [contract-identifier] [public-function-name] [sender-address] [args...]", invoked_by, argv[0]);
+                panic_test!();
+            }
+
+            let vm_filename = &argv[1];
+            let header_db =
+                friendly_expect(CLIHeadersDB::resume(vm_filename), "Failed to open CLI DB");
+            let marf_kv = friendly_expect(
+                MarfedKV::open(vm_filename, None, None),
+                "Failed to open VM database.",
+            );
+            let mainnet = header_db.is_mainnet();
+            let contract_identifier = friendly_expect(
+                QualifiedContractIdentifier::parse(&argv[2]),
+                "Failed to parse contract identifier.",
+            );
+            let tx_name = &argv[3];
+            let sender = friendly_expect(
+                PrincipalData::parse_standard_principal(&argv[4]),
+                &format!("Unexpected result parsing sender: {}", &argv[4]),
+            );
+            let arguments = parse_function_arguments(&argv[5..]);
+
+            let mut breakpoints = Breakpoints::default();
+            for spec in breakpoint_specs.iter() {
+                let (contract, line) = friendly_expect(
+                    parse_breakpoint(spec, Some(&contract_identifier)),
+                    &format!("Invalid breakpoint '{}'", spec),
+                );
+                friendly_expect(
+                    breakpoints.add(contract, line),
+                    &format!("Cannot set breakpoint '{}'", spec),
+                );
+            }
+
+            let stdin = io::stdin();
+            let mut debugger = if dap {
+                let mut frontend = DapFrontend::new(stdin.lock(), io::stdout());
+                let options = friendly_expect(
+                    frontend.configure(&mut breakpoints),
+                    "Debug adapter I/O failure",
+                );
+                let Some(options) = options else {
+                    // the client disconnected before the program ran
+                    return (0, None);
+                };
+                let mut debugger = Debugger::new(DebugSession::Dap(frontend));
+                if options.stop_on_entry {
+                    debugger.stop_on_entry();
+                }
+                debugger
+            } else {
+                let frontend = CommandLineFrontend::new(stdin.lock(), io::stdout());
+                let mut debugger = Debugger::new(DebugSession::CommandLine(frontend));
+                debugger.stop_on_entry();
+                debugger
+            };
+            *debugger.breakpoints_mut() = breakpoints;
+
+            // the transaction is evaluated at the chain tip, and never committed
+            let result = at_chaintip(vm_filename, marf_kv, |mut marf| {
+                let (result, _cost) = with_env_costs_and_hooks(
+                    mainnet,
+                    &header_db,
+                    &mut marf,
+                    vec![&mut debugger],
+                    |vm_env| {
+                        vm_env.execute_transaction(
+                            PrincipalData::Standard(sender),
+                            None,
+                            contract_identifier,
+                            tx_name,
+                            &arguments,
+                        )
+                    },
+                );
+                (marf, result)
+            });
+
+            let (exit_code, result) = match result {
+                Ok((Value::Response(data), ..)) => (
+                    0,
+                    json!({
+                        "output": serde_json::to_value(&data.data).unwrap(),
+                        "committed": data.committed,
+                        "success": true,
+                    }),
+                ),
+                Ok((value, ..)) => (
+                    1,
+                    json!({
+                        "error": {
+                            "runtime": "Expected a ResponseType result from transaction.",
+                            "output": serde_json::to_value(&value).unwrap()
+                        },
+                        "success": false,
+                    }),
+                ),
+                Err(error) => (
+                    1,
+                    json!({
+                        "error": {
+                            "runtime": "Transaction execution error.",
+                            "error": serde_json::to_value(&format!("{}", error)).unwrap()
+                        },
+                        "success": false,
+                    }),
+                ),
+            };
+
+            if let DebugSession::Dap(frontend) = debugger.frontend_mut() {
+                // stdout carries the protocol, so the result is sent as program output
+                friendly_expect(
+                    frontend.finish(&result.to_string(), exit_code),
+                    "Debug adapter I/O failure",
+                );
+                (exit_code, None)
+            } else {
+                (exit_code, Some(result))
+            }
+        }
+        "fuzz" => {
+            let mut argv = args.to_vec();
+            let mut parse_count = |name: &str| -> Option<u64> {
+                match consume_arg(&mut argv, &[name], true) {
+                    Ok(Some(count)) => Some(friendly_expect(
+                        count.parse(),
+                        &format!("Invalid value for {}: {}", name, count),
+                    )),
+                    Ok(None) => None,
+                    Err(e) => {
+                        eprintln!("{}: {}", name, e);
+                        panic_test!();
+                    }
+                }
+            };
+            let runs = parse_count("--runs");
+            let max_calls = parse_count("--calls");
+            let seed = parse_count("--seed").unwrap_or_else(|| rand::thread_rng().gen());
+
+            if argv.len() < 3 {
+                eprintln!("Usage: {} {} [--runs N] [--calls N] [--seed N] [vm-state.db] [contract-identifier]", invoked_by, argv[0]);
+                panic_test!();
+            }
+
+            let vm_filename = &argv[1];
+            let header_db =
+                friendly_expect(CLIHeadersDB::resume(vm_filename), "Failed to open CLI DB");
+            let marf_kv = friendly_expect(
+                MarfedKV::open(vm_filename, None, None),
+                "Failed to open VM database.",
+            );
+            let mainnet = header_db.is_mainnet();
+            let contract_identifier = friendly_expect(
+                QualifiedContractIdentifier::parse(&argv[2]),
+                "Failed to parse contract identifier.",
+            );
+
+            let mut config = FuzzConfig::new(seed, test_senders(mainnet, 3));
+            if let Some(runs) = runs {
+                config.runs = friendly_expect(u32::try_from(runs), "Too many runs");
+            }
+            if let Some(max_calls) = max_calls {
+                config.max_calls = friendly_expect(u32::try_from(max_calls), "Too many calls");
+            }
+
+            // every call sequence is rolled back, and so is the chain tip
+            let (target, report) = at_chaintip(vm_filename, marf_kv, |mut marf| {
+                let analysis = friendly_expect_opt(
+                    friendly_expect(
+                        marf.as_analysis_db().execute(|db| {
+                            db.load_contract(&contract_identifier, &DEFAULT_CLI_EPOCH)
+                        }),
+                        "Failed to load contract analysis.",
+                    ),
+                    &format!("No such contract: {}", contract_identifier),
+                );
+                let interface = friendly_expect(
+                    build_contract_interface(&analysis),
+                    "Failed to build contract interface.",
+                );
+                let target = friendly_expect(
+                    FuzzTarget::from_interface(contract_identifier.clone(), &interface),
+                    "Cannot fuzz contract.",
+                );
+                let mut session = FuzzSession::new(
+                    marf.as_clarity_db(&header_db, &NULL_BURN_STATE_DB),
+                    mainnet,
+                    default_chain_id(mainnet),
+                    DEFAULT_CLI_EPOCH,
+                );
+                let report = friendly_expect(
+                    fuzz(&target, &config, DEFAULT_CLI_EPOCH, |calls| {
+                        session.run_calls(&target, calls)
+                    }),
+                    "Fuzzing failed.",
+                );
+                drop(session);
+                (marf, (target, report))
+            });
+
+            let mut result = json!({
+                "seed": seed,
+                "runs": report.runs,
+                "calls": report.calls,
+                "skipped": target.skipped.iter().map(|name| name.to_string()).collect::<Vec<_>>(),
+            });
+            match report.counterexample {
+                None => {
+                    result["message"] = json!("All invariants held.");
+                    result["success"] = json!(true);
+                    (0, Some(result))
+                }
+                Some(counterexample) => {
+                    let calls: Vec<_> = counterexample
+                        .calls
+                        .iter()
+                        .map(|call| {
+                            json!({
+                                "function": call.function.to_string(),
+                                "sender": call.sender.to_string(),
+                                "args": call.args.iter().map(clarity_literal).collect::<Vec<_>>(),
+                            })
+                        })
+                        .collect();
+                    result["message"] = json!(format!("Invariant {}", counterexample.violation));
+                    result["counterexample"] = json!({
+                        "invariant": counterexample.violation.invariant.to_string(),
+                        "outcome": counterexample.violation.outcome,
+                        "calls": calls,
+                        "original_calls": counterexample.original_calls,
+                        "shrink_runs": counterexample.shrink_runs,
+                        "reproducer": fuzz_reproducer(
+                            invoked_by,
+                            vm_filename,
+                            &contract_identifier,
+                            &counterexample,
+                        ),
+                    });
+                    result["success"] = json!(false);
+                    (1, Some(result))
+                }
+            }
+        }
+        "fmt" => {
+            let mut argv = args.to_vec();
+            let check = matches!(consume_arg(&mut argv, &["--check"], false), Ok(Some(_)));
+
+            if argv.len() < 2 {
+                eprintln!(
+                    "Usage: {} {} [--check] [program-file.clar|-]...",
+                    invoked_by, argv[0]
+                );
+                panic_test!();
+            }
+
+            let mut changed = vec![];
+            for file in argv[1..].iter() {
+                let content = if file == "-" {
+                    let mut buffer = String::new();
+                    friendly_expect(
+                        io::stdin().read_to_string(&mut buffer),
+                        "Error reading from stdin.",
+                    );
+                    buffer
+                } else {
+                    friendly_expect(
+                        fs::read_to_string(file),
+                        &format!("Error reading file: {}", file),
+                    )
+                };
+                let formatted = match format_source(&content) {
+                    Ok(formatted) => formatted,
+                    Err(error) => {
+                        let result = json!({
+                            "error": {
+                                "file": file,
+                                "format": error.to_string(),
+                            },
+                            "success": false,
+                        });
+                        return (1, Some(result));
+                    }
+                };
+                if file == "-" && !check {
+                    print!("{}", formatted);
+                }
+                if formatted == content {
+                    continue;
+                }
+                changed.push(file.clone());
+                if !check && file != "-" {
+                    friendly_expect(
+                        fs::write(file, formatted),
+                        &format!("Error writing file: {}", file),
+                    );
+                }
+            }
+
+            if argv[1..] == ["-"] && !check {
+                // stdout carries the formatted contract
+                (0, None)
+            } else if check {
+                let success = changed.is_empty();
+                let result = json!({
+                    "unformatted": changed,
+                    "success": success,
+                });
+                (if success { 0 } else { 1 }, Some(result))
+            } else {
+                let result = json!({
+                    "formatted": changed,
+                    "success": true,
+                });
+                (0, Some(result))
+            }
+        }
+        "check_upgrade" => {
+            let mut argv = args.to_vec();
+            // NOTE: ignored if we're using a DB
+            let mainnet = !matches!(consume_arg(&mut argv, &["--testnet"], false), Ok(Some(_)));
+
+            if argv.len() < 3 {
+                eprintln!(
+                    "Usage: {} {} [--testnet] [old-program.clar|old-contract-identifier] [new-program.clar] (vm-state.db)",
+                    invoked_by, argv[0]
+                );
+                eprintln!("   Given a database, the old version may be a deployed contract.");
+                panic_test!();
+            }
+
+            let read_source = |file: &str| {
+                friendly_expect(
+                    fs::read_to_string(file),
+                    &format!("Error reading file: {}", file),
+                )
+            };
+            let (old_file, new_file) = (&argv[1], &argv[2]);
+            let new_source = read_source(new_file);
+
+            let analyses = if let Some(vm_filename) = argv.get(3) {
+                let header_db =
+                    friendly_expect(CLIHeadersDB::resume(vm_filename), "Failed to open CLI DB");
+                let marf_kv = friendly_expect(
+                    MarfedKV::open(vm_filename, None, None),
+                    "Failed to open VM database.",
+                );
+                // an old version which is not a file is a deployed contract,
+                //  and the new version replaces it
+                let deployed = if fs::metadata(old_file).is_ok() {
+                    None
+                } else {
+                    Some(friendly_expect(
+                        QualifiedContractIdentifier::parse(old_file),
+                        &format!("Error reading file: {}", old_file),
+                    ))
+                };
+                let contract_id = deployed
+                    .clone()
+                    .unwrap_or_else(QualifiedContractIdentifier::transient);
+
+                at_chaintip(vm_filename, marf_kv, |mut marf| {
+                    let old = match &deployed {
+                        Some(deployed) => Ok(friendly_expect_opt(
+                            friendly_expect(
+                                marf.as_analysis_db()
+                                    .execute(|db| db.load_contract(deployed, &DEFAULT_CLI_EPOCH)),
+                                "Failed to load contract analysis.",
+                            ),
+                            &format!("No such contract: {}", deployed),
+                        )),
+                        None => analyse_version(
+                            &contract_id,
+                            old_file,
+                            &read_source(old_file),
+                            &header_db,
+                            &mut marf,
+                        ),
+                    };
+                    let new =
+                        analyse_version(&contract_id, new_file, &new_source, &header_db, &mut marf);
+                    (marf, old.and_then(|old| Ok((old, new?))))
+                })
+            } else {
+                let header_db = CLIHeadersDB::new_memory(mainnet);
+                let mut marf = MemoryBackingStore::new();
+                install_boot_code(&header_db, &mut marf);
+                let contract_id = QualifiedContractIdentifier::transient();
+
+                let old_source = read_source(old_file);
+                analyse_version(&contract_id, old_file, &old_source, &header_db, &mut marf)
+                    .and_then(|old| {
+                        let new = analyse_version(
+                            &contract_id,
+                            new_file,
+                            &new_source,
+                            &header_db,
+                            &mut marf,
+                        )?;
+                        Ok((old, new))
+                    })
+            };
+            let (old, new) = match analyses {
+                Ok(analyses) => analyses,
+                Err(result) => return (1, Some(result)),
+            };
+
+            let report = check_upgrade(&old, &new);
+            let result = friendly_expect(
+                serde_json::to_value(&report),
+                "Failed to serialize the upgrade report.",
+            );
+            (if report.breaking { 1 } else { 0 }, Some(result))
+        }
+        "verify" => {
+            let mut argv = args.to_vec();
+            // NOTE: ignored if we're using a DB
+            let mainnet = !matches!(consume_arg(&mut argv, &["--testnet"], false), Ok(Some(_)));
+
+            if argv.len() != 2 && argv.len() != 4 {
+                eprintln!(
+                    "Usage: {} {} [--testnet] [program-file.clar] (vm-state.db contract-identifier)",
+                    invoked_by, argv[0]
+                );
+                eprintln!("   Given a database, the contract is checked against it, and counterexamples are");
+                eprintln!("   replayed against the contract deployed with the identifier.");
+                panic_test!();
+            }
+
+            let file = &argv[1];
+            let source = friendly_expect(
+                fs::read_to_string(file),
+                &format!("Error reading file: {}", file),
+            );
+            let (analysis, mainnet, vm_filename, contract) =
+                if let [_, _, vm_filename, contract] = &argv[..] {
+                    let contract_id = friendly_expect(
+                        QualifiedContractIdentifier::parse(contract),
+                        &format!("Error parsing contract identifier '{}'", contract),
+                    );
+                    let header_db =
+                        friendly_expect(CLIHeadersDB::resume(vm_filename), "Failed to open CLI DB");
+                    let marf_kv = friendly_expect(
+                        MarfedKV::open(vm_filename, None, None),
+                        "Failed to open VM database.",
+                    );
+                    let mainnet = header_db.is_mainnet();
+                    let analysis = at_chaintip(vm_filename, marf_kv, |mut marf| {
+                        let analysis =
+                            analyse_with_types(&contract_id, file, &source, &header_db, &mut marf);
+                        (marf, analysis)
+                    });
+                    (
+                        analysis,
+                        mainnet,
+                        shell_quote(vm_filename),
+                        contract.to_string(),
+                    )
+                } else {
+                    let header_db = CLIHeadersDB::new_memory(mainnet);
+                    let mut marf = MemoryBackingStore::new();
+                    install_boot_code(&header_db, &mut marf);
+                    let contract_id = QualifiedContractIdentifier::transient();
+                    let analysis =
+                        analyse_with_types(&contract_id, file, &source, &header_db, &mut marf);
+                    (
+                        analysis,
+                        mainnet,
+                        "<vm-state.db>".to_string(),
+                        "<contract-identifier>".to_string(),
+                    )
+                };
+            let analysis = match analysis {
+                Ok(analysis) => analysis,
+                Err(result) => return (1, Some(result)),
+            };
+
+            let report = verify(&analysis, &test_senders(mainnet, 2));
+            let mut result = friendly_expect(
+                serde_json::to_value(&report),
+                "Failed to serialize the verification report.",
+            );
+            for (property, json) in report
+                .properties
+                .iter()
+                .zip(result["properties"].as_array_mut().into_iter().flatten())
+            {
+                if let VerifyVerdict::Refuted { counterexample } = &property.verdict {
+                    let read_only = analysis
+                        .read_only_function_types
+                        .keys()
+                        .any(|name| name.as_str() == counterexample.function);
+                    json["counterexample"]["replay"] = json!(verify_reproducer(
+                        invoked_by,
+                        &vm_filename,
+                        &contract,
+                        read_only,
+                        counterexample,
+                    ));
+                }
+            }
+            (if report.refuted() { 1 } else { 0 }, Some(result))
+        }
+        "bindings" => {
+            let mut argv = args.to_vec();
+            let typescript = matches!(
+                consume_arg(&mut argv, &["--typescript"], false),
+                Ok(Some(_))
+            );
+            let name = consume_arg(&mut argv, &["--name"], true);
+
+            let (name, file) = match (name, &argv[1..]) {
+                (Ok(name), [file]) => (name, file),
+                _ => {
+                    eprintln!(
+                        "Usage: {} {} [--typescript] [--name CONTRACT-NAME] [contract-interface.json|-]",
+                        invoked_by, argv[0]
+                    );
+                    eprintln!("   The interface is the JSON served by /v2/contracts/interface.");
+                    panic_test!();
+                }
+            };
+            let content = if file == "-" {
+                let mut buffer = String::new();
+                friendly_expect(
+                    io::stdin().read_to_string(&mut buffer),
+                    "Error reading from stdin.",
+                );
+                buffer
+            } else {
+                friendly_expect(
+                    fs::read_to_string(file),
+                    &format!("Error reading file: {}", file),
+                )
+            };
+            let interface: ContractInterface = friendly_expect(
+                serde_json::from_str(&content),
+                "Error parsing the contract interface.",
+            );
+            let name = name.unwrap_or_else(|| {
+                PathBuf::from(file)
+                    .file_stem()
+                    .filter(|_| file != "-")
+                    .and_then(OsStr::to_str)
+                    .unwrap_or("contract")
+                    .to_string()
+            });
+
+            let bindings = Bindings::from_interface(&name, &interface);
+            if typescript {
+                print!("{}", emit_typescript(&bindings));
+            } else {
+                print!("{}", emit_rust(&bindings));
+            }
             (0, None)
         }
+        "lsp" => {
+            let mut argv = args.to_vec();
+            let clarity_version = match consume_arg(&mut argv, &["--clarity_version"], true) {
+                Ok(Some(version)) => friendly_expect(
+                    ClarityVersion::from_str(&version),
+                    &format!("Invalid Clarity version '{}'", &version),
+                ),
+                Ok(None) => ClarityVersion::default_for_epoch(DEFAULT_CLI_EPOCH),
+                Err(_) => {
+                    eprintln!(
+                        "Usage: {} {} [--clarity_version VERSION]",
+                        invoked_by, argv[0]
+                    );
+                    panic_test!();
+                }
+            };
+
+            let mut server = LanguageServer::new(clarity_version, DEFAULT_CLI_EPOCH);
+            let stdin = io::stdin();
+            friendly_expect(
+                server.run(&mut stdin.lock(), &mut io::stdout()),
+                "Language server I/O failure",
+            );
+
+            // per the LSP spec, exiting without a prior `shutdown` is an error
+            if server.shutdown_requested() {
+                (0, None)
+            } else {
+                (1, None)
+            }
+        }
         _ => {
             print_usage(invoked_by);
             (1, None)
@@ -1895,6 +2938,8 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) -> (i32, Option<serde_j
 mod test {
     use std::path::Path;
 
+    use clarity::vm::tooling::mem_type_check;
+    use clarity::vm::ClarityVersion;
     use stacks_common::util::cargo_workspace;
 
     use super::*;
@@ -2271,6 +3316,60 @@ mod test {
         assert!(result["costs"] != json!(null));
     }
 
+    #[test]
+    fn test_check_diagnostics() {
+        let contract_path = format!("/tmp/contract_{}.clar", rand::thread_rng().gen::<i32>());
+
+        eprintln!("check contract with warnings");
+        fs::write(
+            &contract_path,
+            "(define-private (unused) true) (define-public (foo) (ok true))",
+        )
+        .unwrap();
+        let invoked = invoke_command("test", &["check".to_string(), contract_path.clone()]);
+
+        let exit = invoked.0;
+        let result = invoked.1.unwrap();
+
+        assert_eq!(exit, 0);
+        let diagnostics = result["diagnostics"].as_array().unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0]["level"], "Warning");
+        assert_eq!(
+            diagnostics[0]["spans"],
+            json!([{ "start_line": 1, "start_column": 1, "end_line": 1, "end_column": 30 }])
+        );
+
+        eprintln!("check contract with warnings denied");
+        let invoked = invoke_command(
+            "test",
+            &[
+                "check".to_string(),
+                "--deny_warnings".to_string(),
+                contract_path.clone(),
+            ],
+        );
+        assert_eq!(invoked.0, 2);
+
+        eprintln!("check contract with several errors");
+        fs::write(
+            &contract_path,
+            "(define-read-only (a) (+ 1 u1)) (define-read-only (b) (- true))",
+        )
+        .unwrap();
+        let invoked = invoke_command("test", &["check".to_string(), contract_path.clone()]);
+
+        let exit = invoked.0;
+        let result = invoked.1.unwrap();
+
+        assert_eq!(exit, 1);
+        assert!(result["error"]["analysis"] != json!(null));
+        let diagnostics = result["diagnostics"].as_array().unwrap();
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics.iter().all(|d| d["level"] == "Error"));
+        assert!(diagnostics.iter().all(|d| d["spans"][0]["start_line"] == 1));
+    }
+
     #[test]
     fn test_assets() {
         let db_name = format!("/tmp/db_{}", rand::thread_rng().gen::<i32>());
@@ -2363,4 +3462,94 @@ mod test {
                 })
         );
     }
+
+    /// A contract whose functions take and return every kind of value the bindings support
+    const BINDINGS_CONTRACT: &str = "(define-trait token-trait ((get-balance (principal) (response uint uint))))
+(define-public (transfer
+    (amount uint)
+    (recipient principal)
+    (memo (optional (buff 34)))
+    (meta {tag: (string-ascii 10), notes: (list 3 (string-utf8 20))}))
+  (ok {amount: amount, recipient: recipient}))
+(define-public (swap (token <token-trait>) (type int) (legs (list 4 {to: principal, fee: (optional uint)})))
+  (if (> type 0) (ok (list true false)) (err \"negative\")))
+(define-read-only (get-balance (who principal))
+  (if (is-eq who tx-sender) (ok u1) (err u2)))
+(define-read-only (get-memo) (some 0x0102))
+(define-read-only (get-type) (ok {kind: 1}))";
+
+    fn bindings_fixture() -> Bindings {
+        let (_, analysis) = mem_type_check(
+            BINDINGS_CONTRACT,
+            ClarityVersion::Clarity2,
+            StacksEpochId::Epoch25,
+        )
+        .unwrap();
+        let interface = build_contract_interface(&analysis).unwrap();
+        Bindings::from_interface("token-vault", &interface)
+    }
+
+    /// The generated Rust must build, without warnings, against this crate and `clarity`. This
+    /// builds a crate of its own, so it is left to the bindings CI job.
+    #[test]
+    #[ignore]
+    fn test_rust_bindings_compile() {
+        let dir = env::temp_dir().join(format!(
+            "clarity_cli_bindings_{}",
+            rand::thread_rng().gen::<u32>()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("token_vault.rs");
+        fs::write(
+            &path,
+            format!(
+                "#![deny(warnings)]\n#![allow(dead_code)]\n\n{}\nfn main() {{}}\n",
+                emit_rust(&bindings_fixture())
+            ),
+        )
+        .unwrap();
+
+        trybuild::TestCases::new().pass(&path);
+    }
+
+    /// The generated TypeScript must type-check against `@stacks/transactions`. This needs the
+    /// packages of `contrib/core-contract-tests` to be installed (`npm ci`), which include
+    /// `typescript`.
+    #[test]
+    #[ignore]
+    fn test_typescript_bindings_typecheck() {
+        let node_modules = cargo_workspace("contrib/core-contract-tests/node_modules");
+        let dir = env::temp_dir().join(format!(
+            "clarity_cli_bindings_{}",
+            rand::thread_rng().gen::<u32>()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("token-vault.ts");
+        fs::write(&path, emit_typescript(&bindings_fixture())).unwrap();
+
+        let output = process::Command::new(node_modules.join(".bin/tsc"))
+            .args([
+                "--noEmit",
+                "--strict",
+                "--noUnusedLocals",
+                "--noUnusedParameters",
+                "--target",
+                "es2020",
+                "--moduleResolution",
+                "node",
+                "--skipLibCheck",
+                // resolve `@stacks/transactions` from the installed packages
+                "--baseUrl",
+            ])
+            .arg(&node_modules)
+            .arg(&path)
+            .output()
+            .expect("Failed to run tsc; run `npm ci` in contrib/core-contract-tests");
+        assert!(
+            output.status.success(),
+            "tsc rejected the bindings:\n{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
 }
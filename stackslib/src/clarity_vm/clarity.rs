@@ -25,8 +25,8 @@ pub use clarity::vm::clarity::{ClarityConnection, Error};
 use clarity::vm::contexts::{AssetMap, Environment, OwnedEnvironment};
 use clarity::vm::costs::{CostTracker, ExecutionCost, LimitedCostTracker};
 use clarity::vm::database::{
-    BurnStateDB, ClarityDatabase, HeadersDB, RollbackWrapper, RollbackWrapperPersistedLog,
-    STXBalance, SqliteConnection, NULL_BURN_STATE_DB, NULL_HEADER_DB,
+    BurnStateDB, ClarityBackingStore, ClarityDatabase, HeadersDB, RollbackWrapper,
+    RollbackWrapperPersistedLog, STXBalance, SqliteConnection, NULL_BURN_STATE_DB, NULL_HEADER_DB,
 };
 use clarity::vm::errors::Error as InterpreterError;
 use clarity::vm::representations::SymbolicExpression;
@@ -63,6 +63,7 @@ use crate::chainstate::stacks::{
     TransactionVersion,
 };
 use crate::clarity_vm::database::marf::{MarfedKV, ReadOnlyMarfStore, WritableMarfStore};
+use crate::clarity_vm::trace::{TxTraceConfig, TxTracer};
 use crate::core::{StacksEpoch, StacksEpochId, FIRST_STACKS_BLOCK_ID, GENESIS_EPOCH};
 use crate::util_lib::boot::{boot_code_acc, boot_code_addr, boot_code_id, boot_code_tx_auth};
 use crate::util_lib::db::Error as DatabaseError;
@@ -94,6 +95,9 @@ pub struct ClarityInstance {
     datastore: MarfedKV,
     mainnet: bool,
     chain_id: u32,
+    /// If set, the transactions which `process_block_transactions` runs in blocks
+    ///  begun with `begin_block` are traced
+    tx_trace: Option<TxTraceConfig>,
 }
 
 ///
@@ -123,6 +127,7 @@ pub struct ClarityBlockConnection<'a, 'b> {
     mainnet: bool,
     chain_id: u32,
     epoch: StacksEpochId,
+    tracer: Option<TxTracer>,
 }
 
 ///
@@ -132,13 +137,14 @@ pub struct ClarityBlockConnection<'a, 'b> {
 ///   rollback the transaction by dropping this struct.
 pub struct ClarityTransactionConnection<'a, 'b> {
     log: Option<RollbackWrapperPersistedLog>,
-    store: &'a mut WritableMarfStore<'b>,
+    store: &'a mut (dyn ClarityBackingStore + 'b),
     header_db: &'a dyn HeadersDB,
     burn_state_db: &'a dyn BurnStateDB,
     cost_track: &'a mut Option<LimitedCostTracker>,
     mainnet: bool,
     chain_id: u32,
     epoch: StacksEpochId,
+    tracer: &'a mut Option<TxTracer>,
 }
 
 pub struct ClarityReadOnlyConnection<'a> {
@@ -191,6 +197,7 @@ impl ClarityBlockConnection<'_, '_> {
             mainnet: false,
             chain_id: CHAIN_ID_TESTNET,
             epoch,
+            tracer: None,
         }
     }
 
@@ -219,6 +226,11 @@ impl ClarityBlockConnection<'_, '_> {
         }
     }
 
+    /// The tracer of this block's transactions, if they are being traced
+    pub fn tx_tracer(&mut self) -> Option<&mut TxTracer> {
+        self.tracer.as_mut()
+    }
+
     /// Returns the block limit for the block being created.
     pub fn block_limit(&self) -> Option<ExecutionCost> {
         match self.cost_track {
@@ -249,9 +261,16 @@ impl ClarityInstance {
             datastore,
             mainnet,
             chain_id,
+            tx_trace: None,
         }
     }
 
+    /// Trace the transactions of the blocks begun from now on, as selected by `config`,
+    ///  or stop tracing them if `config` is `None`
+    pub fn set_tx_trace_config(&mut self, config: Option<TxTraceConfig>) {
+        self.tx_trace = config;
+    }
+
     pub fn with_marf<F, R>(&mut self, f: F) -> R
     where
         F: FnOnce(&mut MARF<StacksBlockId>) -> R,
@@ -317,6 +336,7 @@ impl ClarityInstance {
             mainnet: self.mainnet,
             chain_id: self.chain_id,
             epoch: epoch.epoch_id,
+            tracer: self.tx_trace.clone().map(TxTracer::new),
         }
     }
 
@@ -341,6 +361,7 @@ impl ClarityInstance {
             mainnet: self.mainnet,
             chain_id: self.chain_id,
             epoch,
+            tracer: None,
         }
     }
 
@@ -367,6 +388,7 @@ impl ClarityInstance {
             mainnet: self.mainnet,
             chain_id: self.chain_id,
             epoch,
+            tracer: None,
         };
 
         let use_mainnet = self.mainnet;
@@ -463,6 +485,7 @@ impl ClarityInstance {
             mainnet: self.mainnet,
             chain_id: self.chain_id,
             epoch,
+            tracer: None,
         };
 
         let use_mainnet = self.mainnet;
@@ -571,6 +594,7 @@ impl ClarityInstance {
             mainnet: self.mainnet,
             chain_id: self.chain_id,
             epoch: epoch.epoch_id,
+            tracer: None,
         }
     }
 
@@ -678,6 +702,45 @@ impl ClarityConnection for ClarityBlockConnection<'_, '_> {
     }
 }
 
+impl<'a> ClarityReadOnlyConnection<'a> {
+    /// Run `todo` as a transaction on top of this connection's chain tip, with its costs
+    /// limited to `cost_limit`. The transaction is never committed: its writes stay in the
+    /// edit log and are thrown away, so the backing store is only ever read.
+    pub fn as_simulated_transaction<F, R>(
+        &mut self,
+        mainnet: bool,
+        chain_id: u32,
+        cost_limit: ExecutionCost,
+        todo: F,
+    ) -> Result<R, Error>
+    where
+        F: FnOnce(&mut ClarityTransactionConnection) -> R,
+    {
+        let epoch = self.epoch;
+        let cost_track = self
+            .with_clarity_db_readonly(|clarity_db| {
+                LimitedCostTracker::new_mid_block(mainnet, chain_id, cost_limit, clarity_db, epoch)
+            })
+            .map_err(|e| Error::Interpreter(e.into()))?;
+        let mut cost_track = Some(cost_track);
+        let mut tracer = None;
+        let mut log = RollbackWrapperPersistedLog::new();
+        log.nest();
+        let mut tx = ClarityTransactionConnection {
+            store: &mut self.datastore,
+            cost_track: &mut cost_track,
+            header_db: self.header_db,
+            burn_state_db: self.burn_state_db,
+            log: Some(log),
+            mainnet,
+            chain_id,
+            epoch,
+            tracer: &mut tracer,
+        };
+        Ok(todo(&mut tx))
+    }
+}
+
 impl ClarityConnection for ClarityReadOnlyConnection<'_> {
     /// Do something with ownership of the underlying DB that involves only reading.
     fn with_clarity_db_readonly_owned<F, R>(&mut self, to_do: F) -> R
@@ -1568,6 +1631,7 @@ impl<'a> ClarityBlockConnection<'a, '_> {
     pub fn start_transaction_processing<'c>(&'c mut self) -> ClarityTransactionConnection<'c, 'a> {
         let store = &mut self.datastore;
         let cost_track = &mut self.cost_track;
+        let tracer = &mut self.tracer;
         let header_db = self.header_db;
         let burn_state_db = self.burn_state_db;
         let mainnet = self.mainnet;
@@ -1583,6 +1647,7 @@ impl<'a> ClarityBlockConnection<'a, '_> {
             mainnet,
             chain_id,
             epoch: self.epoch,
+            tracer,
         }
     }
 
@@ -1727,6 +1792,9 @@ impl TransactionConnection for ClarityTransactionConnection<'_, '_> {
                     cost_track,
                     self.epoch,
                 );
+                if let Some(hook) = self.tracer.as_mut().and_then(TxTracer::eval_hook) {
+                    vm_env.add_eval_hook(hook);
+                }
                 let result = to_do(&mut vm_env);
                 let (mut db, cost_track) = vm_env
                     .destruct()
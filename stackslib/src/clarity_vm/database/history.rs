@@ -0,0 +1,272 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! An index of the blocks which wrote each contract data key.
+//!
+//! Whenever a block writes a data-var, map entry or token balance to the
+//! Clarity MARF, the key is recorded in the `data_key_history` table of the
+//! MARF's side store, along with the block and its height. The rows of a
+//! block are renamed and dropped alongside its metadata, so the table only
+//! describes blocks which were processed.
+//!
+//! The index is built as blocks are processed, and is not backfilled: a
+//! node only knows the history of the blocks it processed since it was
+//! upgraded. The `data_key_history_info` table records whether the index
+//! was created along with the side store, and so covers every block.
+
+use clarity::vm::database::SqliteConnection;
+use clarity::vm::errors::{InterpreterError, InterpreterResult};
+use rusqlite::{params, Connection, OptionalExtension};
+use stacks_common::types::chainstate::StacksBlockId;
+use stacks_common::types::sqlite::NO_PARAMS;
+
+use crate::chainstate::stacks::index::marf::{MarfConnection, MARF};
+use crate::chainstate::stacks::index::Error as MARFError;
+use crate::util_lib::db::Error as DBError;
+
+const SQL_FAIL_MESSAGE: &str = "PANIC: SQL Failure in Smart Contract VM.";
+
+const DATA_KEY_HISTORY_SCHEMA: &[&str] = &[
+    r#"
+    CREATE TABLE IF NOT EXISTS data_key_history(
+        key TEXT NOT NULL,
+        blockhash TEXT NOT NULL,
+        block_height INTEGER NOT NULL,
+        PRIMARY KEY(key, blockhash)
+    );"#,
+    "CREATE INDEX IF NOT EXISTS data_key_history_by_height ON data_key_history(key, block_height);",
+    "CREATE INDEX IF NOT EXISTS data_key_history_by_block ON data_key_history(blockhash);",
+    r#"
+    CREATE TABLE IF NOT EXISTS data_key_history_info(
+        complete INTEGER NOT NULL
+    );"#,
+];
+
+/// One change of a data key
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DataKeyChange {
+    pub block_height: u32,
+    pub index_block_hash: StacksBlockId,
+    /// The hex-serialized value which the block left
+    pub data: String,
+}
+
+/// Is this the key of contract data, i.e. a data-var, map entry or token balance?
+pub fn is_data_key(key: &str) -> bool {
+    key.starts_with("vm::")
+}
+
+/// Does the side store have the history tables?
+pub fn has_history_table(conn: &Connection) -> bool {
+    conn.query_row(
+        "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'data_key_history_info'",
+        NO_PARAMS,
+        |_row| Ok(()),
+    )
+    .is_ok()
+}
+
+/// Create the history tables, if they do not exist yet. `complete` says whether the side
+///  store is new, so that the history will cover every block.
+pub fn initialize_history(conn: &Connection, complete: bool) -> InterpreterResult<()> {
+    for cmd in DATA_KEY_HISTORY_SCHEMA {
+        conn.execute_batch(cmd).map_err(|e| {
+            error!("Failed to create the data key history table: {:?}", &e);
+            InterpreterError::DBError(SQL_FAIL_MESSAGE.into())
+        })?;
+    }
+    conn.execute(
+        "INSERT INTO data_key_history_info (complete)
+         SELECT ?1 WHERE NOT EXISTS (SELECT 1 FROM data_key_history_info)",
+        params![complete],
+    )
+    .map_err(|e| {
+        error!("Failed to initialize the data key history: {:?}", &e);
+        InterpreterError::DBError(SQL_FAIL_MESSAGE.into())
+    })?;
+    Ok(())
+}
+
+/// Does the history cover every block, or only those processed since the node was upgraded?
+pub fn is_history_complete(conn: &Connection) -> Result<bool, DBError> {
+    let complete = conn
+        .query_row(
+            "SELECT complete FROM data_key_history_info",
+            NO_PARAMS,
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(complete.unwrap_or(false))
+}
+
+/// Record that the block `bhh`, at `block_height`, wrote `keys`
+pub fn record_data_key_writes<'a>(
+    conn: &Connection,
+    bhh: &StacksBlockId,
+    block_height: u32,
+    keys: impl Iterator<Item = &'a String>,
+) -> InterpreterResult<()> {
+    let mut stmt = conn
+        .prepare_cached(
+            "INSERT OR IGNORE INTO data_key_history (key, blockhash, block_height) VALUES (?, ?, ?)",
+        )
+        .map_err(|e| {
+            error!("Failed to prepare the data key history insert: {:?}", &e);
+            InterpreterError::DBError(SQL_FAIL_MESSAGE.into())
+        })?;
+    for key in keys.filter(|key| is_data_key(key)) {
+        if let Err(e) = stmt.execute(params![key, bhh, block_height]) {
+            error!("Failed to record the write of {} by {}: {:?}", key, bhh, &e);
+            return Err(InterpreterError::DBError(SQL_FAIL_MESSAGE.into()).into());
+        }
+    }
+    Ok(())
+}
+
+/// Move the writes recorded for `from` to `to`, as `commit_metadata_to` does
+pub fn commit_history_to(
+    conn: &Connection,
+    from: &StacksBlockId,
+    to: &StacksBlockId,
+) -> InterpreterResult<()> {
+    if let Err(e) = conn.execute(
+        "UPDATE data_key_history SET blockhash = ? WHERE blockhash = ?",
+        params![to, from],
+    ) {
+        error!("Failed to update history of {} to {}: {:?}", from, to, &e);
+        return Err(InterpreterError::DBError(SQL_FAIL_MESSAGE.into()).into());
+    }
+    Ok(())
+}
+
+/// Drop the writes recorded for `from`, as `drop_metadata` does
+pub fn drop_history(conn: &Connection, from: &StacksBlockId) -> InterpreterResult<()> {
+    if let Err(e) = conn.execute(
+        "DELETE FROM data_key_history WHERE blockhash = ?",
+        params![from],
+    ) {
+        error!("Failed to drop history of {}: {:?}", from, &e);
+        return Err(InterpreterError::DBError(SQL_FAIL_MESSAGE.into()).into());
+    }
+    Ok(())
+}
+
+/// The height of `block`, if it is `tip` or one of its ancestors
+pub(crate) fn block_height_of(
+    marf: &mut MARF<StacksBlockId>,
+    block: &StacksBlockId,
+    tip: &StacksBlockId,
+) -> Result<Option<u32>, DBError> {
+    match marf.get_block_height_of(block, tip) {
+        Ok(height) => Ok(height),
+        Err(MARFError::NotFoundError) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// The ancestor of `tip` at `block_height`, if `tip` is at least that high
+fn block_at_height(
+    marf: &mut MARF<StacksBlockId>,
+    block_height: u32,
+    tip: &StacksBlockId,
+) -> Result<Option<StacksBlockId>, DBError> {
+    match marf.get_block_at_height(block_height, tip) {
+        Ok(block) => Ok(block),
+        Err(MARFError::NotFoundError) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Get up to `limit` of the highest heights below `below_height` at which any fork wrote `key`
+fn get_write_heights(
+    conn: &Connection,
+    key: &str,
+    below_height: u32,
+    limit: usize,
+) -> Result<Vec<u32>, DBError> {
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT block_height FROM data_key_history
+         WHERE key = ?1 AND block_height < ?2 ORDER BY block_height DESC LIMIT ?3",
+    )?;
+    let limit = i64::try_from(limit).map_err(|_| DBError::ParseError)?;
+    let rows = stmt.query_map(params![key, below_height, limit], |row| row.get(0))?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+/// Did `block` write `key`?
+fn block_wrote_key(conn: &Connection, key: &str, block: &StacksBlockId) -> Result<bool, DBError> {
+    let mut stmt =
+        conn.prepare("SELECT 1 FROM data_key_history WHERE key = ?1 AND blockhash = ?2")?;
+    Ok(stmt.exists(params![key, block])?)
+}
+
+/// Get up to `limit` changes of `key` in the fork of `tip`, newest first, from the blocks
+///  below `before_height` if given. Returns `None` if `tip` is not in the MARF.
+pub fn get_data_key_changes(
+    marf: &mut MARF<StacksBlockId>,
+    tip: &StacksBlockId,
+    key: &str,
+    before_height: Option<u32>,
+    limit: usize,
+) -> Result<Option<Vec<DataKeyChange>>, DBError> {
+    if block_height_of(marf, tip, tip)?.is_none() {
+        return Ok(None);
+    }
+
+    // Page through the heights at which any fork wrote the key, and keep the writes by the
+    // ancestor of `tip` at each height. Each page is only as long as the changes still wanted.
+    let mut writes: Vec<(StacksBlockId, u32)> = vec![];
+    let mut below_height = before_height.unwrap_or(u32::MAX);
+    while writes.len() < limit {
+        let heights =
+            get_write_heights(marf.sqlite_conn(), key, below_height, limit - writes.len())?;
+        let Some(lowest) = heights.last() else {
+            break;
+        };
+        below_height = *lowest;
+        for block_height in heights {
+            let Some(block) = block_at_height(marf, block_height, tip)? else {
+                continue;
+            };
+            if block_wrote_key(marf.sqlite_conn(), key, &block)? {
+                writes.push((block, block_height));
+            }
+        }
+    }
+
+    let mut changes = vec![];
+    for (block, block_height) in writes {
+        let marf_value = match marf.get(&block, key) {
+            Ok(Some(marf_value)) => marf_value,
+            Ok(None) | Err(MARFError::NotFoundError) => {
+                error!("Data key history names a block which did not write the key";
+                    "key" => key,
+                    "block" => %block
+                );
+                return Err(DBError::Corruption);
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let data = SqliteConnection::get(marf.sqlite_conn(), &marf_value.to_hex())
+            .map_err(|e| DBError::Other(e.to_string()))?
+            .ok_or(DBError::Corruption)?;
+        changes.push(DataKeyChange {
+            block_height,
+            index_block_hash: block,
+            data: format!("0x{}", data),
+        });
+    }
+    Ok(Some(changes))
+}
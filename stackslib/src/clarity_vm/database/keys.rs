@@ -0,0 +1,149 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Listing the contract data under a tip.
+//!
+//! The MARF only stores the hash of each key, as the path of its leaf, so it cannot name
+//! its own keys. The data key history names every data-var, map entry and token balance
+//! which a block wrote, though, so the keys under a map or token are listed from it in key
+//! order, and each one's value is read from the MARF of the tip. Keys which only other
+//! forks wrote have no value there, and are skipped.
+//!
+//! The history only covers the blocks a node processed since it was upgraded, so a scan
+//! on a node which had chainstate before then misses the keys which have not been written
+//! since. `DataKeyScan::complete` says whether that can happen.
+
+use clarity::vm::database::SqliteConnection;
+use rusqlite::{params, Connection};
+use stacks_common::types::chainstate::StacksBlockId;
+
+use crate::chainstate::stacks::index::marf::{MarfConnection, MARF};
+use crate::clarity_vm::database::history::{block_height_of, is_history_complete};
+use crate::util_lib::db::Error as DBError;
+
+/// How many keys are loaded at a time while scanning
+const DATA_KEY_SCAN_BATCH: usize = 1024;
+
+/// One page of a scan of the contract data under a tip
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataKeyScan {
+    /// The matching keys, and their hex-serialized values, in key order
+    pub entries: Vec<(String, String)>,
+    /// The key to resume the scan after, if it did not reach the last key
+    pub next: Option<String>,
+    /// Could the scan see every key? If not, keys not written since the node was upgraded
+    ///  are missing.
+    pub complete: bool,
+}
+
+/// The least string which is greater than every key starting with `prefix`
+fn prefix_end(prefix: &str) -> String {
+    // keys are ASCII, and prefixes end in a separator, so incrementing the last byte will
+    // not carry
+    let mut end = prefix.as_bytes().to_vec();
+    if let Some(last) = end.last_mut() {
+        *last += 1;
+    }
+    String::from_utf8(end).expect("FATAL: data key prefix is not ASCII")
+}
+
+/// Get up to `limit` of the keys which any fork wrote between `after` and `before`
+fn get_written_keys(
+    conn: &Connection,
+    after: &str,
+    before: &str,
+    limit: usize,
+) -> Result<Vec<String>, DBError> {
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT key FROM data_key_history
+         WHERE key > ?1 AND key < ?2 ORDER BY key LIMIT ?3",
+    )?;
+    let limit = i64::try_from(limit).map_err(|_| DBError::ParseError)?;
+    let rows = stmt.query_map(params![after, before, limit], |row| row.get(0))?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+/// Get up to `limit` of the data keys which start with one of `prefixes` and have a value
+///  at `tip`, along with their values, in key order from after the key `after`. At most
+///  `max_scan` keys are visited, so a page may hold fewer than `limit` entries even though
+///  the scan has not reached the end; `DataKeyScan::next` is set whenever there may be more.
+/// Returns `None` if `tip` is not in the MARF.
+pub fn scan_data_keys(
+    marf: &mut MARF<StacksBlockId>,
+    tip: &StacksBlockId,
+    prefixes: &[String],
+    after: Option<&str>,
+    limit: usize,
+    max_scan: usize,
+) -> Result<Option<DataKeyScan>, DBError> {
+    if block_height_of(marf, tip, tip)?.is_none() {
+        return Ok(None);
+    }
+    let complete = is_history_complete(marf.sqlite_conn())?;
+
+    let mut prefixes = prefixes.to_vec();
+    prefixes.sort();
+    let mut entries = vec![];
+    let mut scanned = 0;
+    for prefix in prefixes.iter() {
+        let end = prefix_end(prefix);
+        // no key equals its prefix, so starting after the prefix skips nothing
+        let mut cursor = match after {
+            Some(after) if after >= end.as_str() => continue,
+            Some(after) if after > prefix.as_str() => after.to_string(),
+            _ => prefix.clone(),
+        };
+        loop {
+            if scanned >= max_scan {
+                return Ok(Some(DataKeyScan {
+                    entries,
+                    next: Some(cursor),
+                    complete,
+                }));
+            }
+            let batch = DATA_KEY_SCAN_BATCH.min(max_scan - scanned);
+            let keys = get_written_keys(marf.sqlite_conn(), &cursor, &end, batch)?;
+            let end_of_prefix = keys.len() < batch;
+
+            for key in keys {
+                scanned += 1;
+                cursor = key.clone();
+                let Some(marf_value) = marf.get(tip, &key)? else {
+                    continue;
+                };
+                let data = SqliteConnection::get(marf.sqlite_conn(), &marf_value.to_hex())
+                    .map_err(|e| DBError::Other(e.to_string()))?
+                    .ok_or(DBError::Corruption)?;
+                entries.push((key, data));
+                if entries.len() >= limit {
+                    return Ok(Some(DataKeyScan {
+                        entries,
+                        next: Some(cursor),
+                        complete,
+                    }));
+                }
+            }
+
+            if end_of_prefix {
+                break;
+            }
+        }
+    }
+    Ok(Some(DataKeyScan {
+        entries,
+        next: None,
+        complete,
+    }))
+}
@@ -9,7 +9,7 @@ use clarity::vm::database::sqlite::{
 };
 use clarity::vm::database::{
     BurnStateDB, ClarityBackingStore, ClarityDatabase, HeadersDB, SpecialCaseHandler,
-    SqliteConnection,
+    SqliteConnection, StoredValue,
 };
 use clarity::vm::errors::{
     IncomparableError, InterpreterError, InterpreterResult, RuntimeErrorType,
@@ -23,6 +23,7 @@ use crate::chainstate::stacks::index::marf::{MARFOpenOpts, MarfConnection, MarfT
 use crate::chainstate::stacks::index::{
     ClarityMarfTrieId, Error, MARFValue, MarfTrieId, TrieMerkleProof,
 };
+use crate::clarity_vm::database::history;
 use crate::clarity_vm::special::handle_contract_call_special_cases;
 use crate::core::{FIRST_BURNCHAIN_CONSENSUS_HASH, FIRST_STACKS_BLOCK_HASH};
 use crate::util_lib::db::{Error as DatabaseError, IndexDBConn};
@@ -67,7 +68,8 @@ impl MarfedKV {
                 .map_err(|err| InterpreterError::MarfFailure(err.to_string()))?
         };
 
-        if SqliteConnection::check_schema(marf.sqlite_conn()).is_ok() {
+        let schema_ok = SqliteConnection::check_schema(marf.sqlite_conn()).is_ok();
+        if schema_ok && history::has_history_table(marf.sqlite_conn()) {
             // no need to initialize
             return Ok(marf);
         }
@@ -76,7 +78,12 @@ impl MarfedKV {
             .storage_tx()
             .map_err(|err| InterpreterError::DBError(err.to_string()))?;
 
-        SqliteConnection::initialize_conn(&tx)?;
+        if !schema_ok {
+            SqliteConnection::initialize_conn(&tx)?;
+        }
+        // added after the rest of the side store, so existing databases gain it on open,
+        //  and then only know of the blocks processed from then on
+        history::initialize_history(&tx, !schema_ok)?;
         tx.commit()
             .map_err(|err| InterpreterError::SqliteError(IncomparableError { err }))?;
 
@@ -310,6 +317,19 @@ impl ClarityBackingStore for ReadOnlyMarfStore<'_> {
 
     /// Sets the chain tip at which queries will happen.  Used for `(at-block ..)`
     fn set_block_hash(&mut self, bhh: StacksBlockId) -> InterpreterResult<StacksBlockId> {
+        // a node which pruned its MARF can't read historical state, but must not crash when a
+        //  read-only call asks for it
+        let pruned = self.marf.is_block_pruned(&bhh).map_err(|e| {
+            InterpreterError::MarfFailure(format!(
+                "Failed to check whether block {bhh} has been pruned: {e}"
+            ))
+        })?;
+        if pruned {
+            return Err(InterpreterError::MarfFailure(format!(
+                "The state of block {bhh} has been pruned"
+            ))
+            .into());
+        }
         self.marf
             .check_ancestor_block_hash(&bhh)
             .map_err(|e| match e {
@@ -475,6 +495,28 @@ impl ClarityBackingStore for ReadOnlyMarfStore<'_> {
             .transpose()
     }
 
+    fn get_data_bytes(&mut self, key: &str) -> InterpreterResult<Option<Vec<u8>>> {
+        trace!("MarfedKV get_data_bytes: {:?} tip={}", key, &self.chain_tip);
+        self.marf
+            .get(&self.chain_tip, key)
+            .or_else(|e| match e {
+                Error::NotFoundError => Ok(None),
+                _ => Err(e),
+            })
+            .map_err(|_| InterpreterError::Expect("ERROR: Unexpected MARF Failure on GET".into()))?
+            .map(|marf_value| {
+                let side_key = marf_value.to_hex();
+                SqliteConnection::get_bytes(self.get_side_store(), &side_key)?.ok_or_else(|| {
+                    InterpreterError::Expect(format!(
+                        "ERROR: MARF contained value_hash not found in side storage: {}",
+                        side_key
+                    ))
+                    .into()
+                })
+            })
+            .transpose()
+    }
+
     fn get_data_from_path(&mut self, hash: &TrieHash) -> InterpreterResult<Option<String>> {
         trace!("MarfedKV get_from_hash: {:?} tip={}", hash, &self.chain_tip);
         self.marf
@@ -505,7 +547,7 @@ impl ClarityBackingStore for ReadOnlyMarfStore<'_> {
             .transpose()
     }
 
-    fn put_all_data(&mut self, _items: Vec<(String, String)>) -> InterpreterResult<()> {
+    fn put_all_data(&mut self, _items: Vec<(String, StoredValue)>) -> InterpreterResult<()> {
         error!("Attempted to commit changes to read-only MARF");
         panic!("BUG: attempted commit to read-only MARF");
     }
@@ -564,6 +606,7 @@ impl WritableMarfStore<'_> {
     pub fn rollback_unconfirmed(self) -> InterpreterResult<()> {
         debug!("Drop unconfirmed MARF trie {}", &self.chain_tip);
         SqliteConnection::drop_metadata(self.marf.sqlite_tx(), &self.chain_tip)?;
+        history::drop_history(self.marf.sqlite_tx(), &self.chain_tip)?;
         self.marf.drop_unconfirmed();
         Ok(())
     }
@@ -571,6 +614,7 @@ impl WritableMarfStore<'_> {
     pub fn commit_to(self, final_bhh: &StacksBlockId) -> InterpreterResult<()> {
         debug!("commit_to({})", final_bhh);
         SqliteConnection::commit_metadata_to(self.marf.sqlite_tx(), &self.chain_tip, final_bhh)?;
+        history::commit_history_to(self.marf.sqlite_tx(), &self.chain_tip, final_bhh)?;
 
         let _ = self.marf.commit_to(final_bhh).map_err(|e| {
             error!("Failed to commit to MARF block {}: {:?}", &final_bhh, &e);
@@ -608,6 +652,7 @@ impl WritableMarfStore<'_> {
         //    _if_ for some reason, we do want to be able to access that mined chain state in the future,
         //    we should probably commit the data to a different table which does not have uniqueness constraints.
         SqliteConnection::drop_metadata(self.marf.sqlite_tx(), &self.chain_tip)?;
+        history::drop_history(self.marf.sqlite_tx(), &self.chain_tip)?;
         let _ = self.marf.commit_mined(will_move_to).map_err(|e| {
             error!(
                 "Failed to commit to mined MARF block {}: {:?}",
@@ -684,6 +729,28 @@ impl ClarityBackingStore for WritableMarfStore<'_> {
             .transpose()
     }
 
+    fn get_data_bytes(&mut self, key: &str) -> InterpreterResult<Option<Vec<u8>>> {
+        trace!("MarfedKV get_data_bytes: {:?} tip={}", key, &self.chain_tip);
+        self.marf
+            .get(&self.chain_tip, key)
+            .or_else(|e| match e {
+                Error::NotFoundError => Ok(None),
+                _ => Err(e),
+            })
+            .map_err(|_| InterpreterError::Expect("ERROR: Unexpected MARF Failure on GET".into()))?
+            .map(|marf_value| {
+                let side_key = marf_value.to_hex();
+                SqliteConnection::get_bytes(self.marf.sqlite_tx(), &side_key)?.ok_or_else(|| {
+                    InterpreterError::Expect(format!(
+                        "ERROR: MARF contained value_hash not found in side storage: {}",
+                        side_key
+                    ))
+                    .into()
+                })
+            })
+            .transpose()
+    }
+
     fn get_data_from_path(&mut self, hash: &TrieHash) -> InterpreterResult<Option<String>> {
         trace!("MarfedKV get_from_hash: {:?} tip={}", hash, &self.chain_tip);
         self.marf
@@ -824,16 +891,24 @@ impl ClarityBackingStore for WritableMarfStore<'_> {
         }
     }
 
-    fn put_all_data(&mut self, items: Vec<(String, String)>) -> InterpreterResult<()> {
+    fn put_all_data(&mut self, items: Vec<(String, StoredValue)>) -> InterpreterResult<()> {
         let mut keys = Vec::new();
         let mut values = Vec::new();
         for (key, value) in items.into_iter() {
-            trace!("MarfedKV put '{}' = '{}'", &key, &value);
-            let marf_value = MARFValue::from_value(&value);
+            trace!("MarfedKV put '{}' = '{:?}'", &key, &value);
+            let marf_value = MARFValue::from_value(&value.to_serialized());
             SqliteConnection::put(self.get_side_store(), &marf_value.to_hex(), &value)?;
             keys.push(key);
             values.push(marf_value);
         }
+        let open_tip = self.get_open_chain_tip();
+        let open_tip_height = self.get_open_chain_tip_height();
+        history::record_data_key_writes(
+            self.marf.sqlite_tx(),
+            &open_tip,
+            open_tip_height,
+            keys.iter(),
+        )?;
         self.marf
             .insert_batch(&keys, values)
             .map_err(|_| InterpreterError::Expect("ERROR: Unexpected MARF Failure".into()).into())
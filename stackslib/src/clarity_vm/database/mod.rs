@@ -9,7 +9,7 @@ use clarity::vm::database::sqlite::{
 };
 use clarity::vm::database::{
     BurnStateDB, ClarityBackingStore, ClarityDatabase, HeadersDB, SpecialCaseHandler,
-    SqliteConnection, NULL_BURN_STATE_DB, NULL_HEADER_DB,
+    SqliteConnection, StoredValue, NULL_BURN_STATE_DB, NULL_HEADER_DB,
 };
 use clarity::vm::errors::{InterpreterResult, RuntimeErrorType};
 use clarity::vm::types::{PrincipalData, QualifiedContractIdentifier, TupleData};
@@ -40,6 +40,8 @@ use crate::clarity_vm::special::handle_contract_call_special_cases;
 use crate::core::{StacksEpoch, StacksEpochId};
 use crate::util_lib::db::{DBConn, Error as DBError, FromColumn, FromRow};
 
+pub mod history;
+pub mod keys;
 pub mod marf;
 
 pub trait GetTenureStartId {
@@ -1231,6 +1233,10 @@ impl ClarityBackingStore for MemoryBackingStore {
         SqliteConnection::get(self.get_side_store(), key)
     }
 
+    fn get_data_bytes(&mut self, key: &str) -> InterpreterResult<Option<Vec<u8>>> {
+        SqliteConnection::get_bytes(self.get_side_store(), key)
+    }
+
     fn get_data_from_path(&mut self, hash: &TrieHash) -> InterpreterResult<Option<String>> {
         SqliteConnection::get(self.get_side_store(), hash.to_string().as_str())
     }
@@ -1277,7 +1283,7 @@ impl ClarityBackingStore for MemoryBackingStore {
         Some(&handle_contract_call_special_cases)
     }
 
-    fn put_all_data(&mut self, items: Vec<(String, String)>) -> InterpreterResult<()> {
+    fn put_all_data(&mut self, items: Vec<(String, StoredValue)>) -> InterpreterResult<()> {
         for (key, value) in items.into_iter() {
             SqliteConnection::put(self.get_side_store(), &key, &value)?;
         }
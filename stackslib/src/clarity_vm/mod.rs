@@ -3,6 +3,12 @@ pub mod clarity;
 
 pub mod special;
 
+/// Execution traces of processed transactions
+pub mod trace;
+
+/// Verification of the MARF proofs of Clarity state
+pub mod proofs;
+
 /// Stacks blockchain specific Clarity database implementations and wrappers
 pub mod database;
 
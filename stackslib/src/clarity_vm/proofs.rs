@@ -0,0 +1,114 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Verification of the MARF proofs of Clarity state.
+//!
+//! The RPC endpoints which take `?proof=1` return the stored form of a piece of Clarity state
+//! with a proof that it is in the MARF of the requested block. A client which trusts a block's
+//! `index_root` (the root hash of its MARF trie) can check such a proof here, without any
+//! chainstate: the client derives the MARF key itself, so a node can't answer for a different key.
+//! The proof itself is checked by `libmarfproof`, which needs no Clarity types.
+
+use clarity::vm::database::{ClarityDatabase, ClarityDeserializable, STXBalance, StoreType};
+use clarity::vm::types::{PrincipalData, QualifiedContractIdentifier};
+use clarity::vm::{ClarityName, Value};
+use libmarfproof::MarfProof;
+use stacks_common::types::chainstate::{StacksBlockId, TrieHash};
+
+/// A piece of Clarity state which can be proven
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClarityStateKey {
+    DataVar {
+        contract: QualifiedContractIdentifier,
+        var_name: ClarityName,
+    },
+    MapEntry {
+        contract: QualifiedContractIdentifier,
+        map_name: ClarityName,
+        key: Value,
+    },
+    StxBalance(PrincipalData),
+}
+
+/// A decoded piece of proven Clarity state
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClarityStateValue {
+    /// A data-var value, or a map entry as an `(optional ..)`
+    Value(Value),
+    StxBalance(STXBalance),
+}
+
+impl ClarityStateKey {
+    /// The key under which this state is stored in the Clarity MARF
+    pub fn marf_key(&self) -> Result<String, String> {
+        match self {
+            ClarityStateKey::DataVar { contract, var_name } => Ok(
+                ClarityDatabase::make_key_for_trip(contract, StoreType::Variable, var_name),
+            ),
+            ClarityStateKey::MapEntry {
+                contract,
+                map_name,
+                key,
+            } => ClarityDatabase::make_key_for_data_map_entry(contract, map_name, key)
+                .map_err(|e| format!("Failed to serialize map key: {e:?}")),
+            ClarityStateKey::StxBalance(principal) => {
+                Ok(ClarityDatabase::make_key_for_account_balance(principal))
+            }
+        }
+    }
+
+    /// The MARF path of this state, as `/v2/clarity/marf/:marf_key_hash` takes it
+    pub fn marf_path(&self) -> Result<TrieHash, String> {
+        Ok(TrieHash::from_key(&self.marf_key()?))
+    }
+
+    /// Decode the stored form of this state
+    pub fn decode_data(&self, data: &str) -> Result<ClarityStateValue, String> {
+        let data = data.strip_prefix("0x").unwrap_or(data);
+        match self {
+            ClarityStateKey::DataVar { .. } | ClarityStateKey::MapEntry { .. } => {
+                Value::try_deserialize_hex_untyped(data)
+                    .map(ClarityStateValue::Value)
+                    .map_err(|e| format!("Failed to decode Clarity value: {e:?}"))
+            }
+            ClarityStateKey::StxBalance(_) => STXBalance::deserialize(data)
+                .map(ClarityStateValue::StxBalance)
+                .map_err(|e| format!("Failed to decode STX balance: {e:?}")),
+        }
+    }
+
+    /// Check that `proof` shows `data`, the stored form of this state (hex, with or without an
+    ///  `0x` prefix), to be in the MARF trie whose root hash is `index_root`.
+    /// `block_for_root` must give the index block hash of each ancestor trie root the proof
+    ///  passes through; it is only asked about roots computed from the proof, so a client can
+    ///  answer it from block headers it already trusts.
+    pub fn verify<F>(
+        &self,
+        data: &str,
+        proof: &MarfProof,
+        index_root: &TrieHash,
+        mut block_for_root: F,
+    ) -> Result<bool, String>
+    where
+        F: FnMut(&TrieHash) -> Option<StacksBlockId>,
+    {
+        let data = data.strip_prefix("0x").unwrap_or(data);
+        Ok(
+            proof.verify_entry(&self.marf_key()?, data, &index_root.0, |root| {
+                block_for_root(&TrieHash(*root)).map(|block| block.0)
+            }),
+        )
+    }
+}
@@ -0,0 +1,133 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use clarity::vm::ast::ASTRules;
+use clarity::vm::contexts::OwnedEnvironment;
+use clarity::vm::database::{ClarityDatabase, StoreType};
+use clarity::vm::test_util::{TEST_BURN_STATE_DB, TEST_HEADER_DB};
+use clarity::vm::types::{PrincipalData, QualifiedContractIdentifier, Value};
+use stacks_common::types::chainstate::StacksBlockId;
+use stacks_common::types::StacksEpochId;
+
+use crate::chainstate::stacks::index::ClarityMarfTrieId;
+use crate::clarity_vm::database::history::{get_data_key_changes, DataKeyChange};
+use crate::clarity_vm::database::marf::MarfedKV;
+
+const COUNTER: &str = "(define-data-var counter int 0)
+(define-public (incr)
+  (begin
+    (var-set counter (+ (var-get counter) 1))
+    (ok (var-get counter))))";
+
+fn contract_id() -> QualifiedContractIdentifier {
+    QualifiedContractIdentifier::local("counter").unwrap()
+}
+
+/// Process a block on top of `parent` which calls `incr` `count` times
+fn incr_block(marf_kv: &mut MarfedKV, parent: u8, block: u8, count: usize) {
+    let mut store = marf_kv.begin(&StacksBlockId([parent; 32]), &StacksBlockId([block; 32]));
+    let mut owned_env = OwnedEnvironment::new(
+        store.as_clarity_db(&TEST_HEADER_DB, &TEST_BURN_STATE_DB),
+        StacksEpochId::Epoch25,
+    );
+    let sender = PrincipalData::parse("SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR").unwrap();
+    for _ in 0..count {
+        owned_env
+            .execute_transaction(sender.clone(), None, contract_id(), "incr", &[])
+            .unwrap();
+    }
+    store.test_commit();
+}
+
+fn change(block_height: u32, block: u8, value: i128) -> DataKeyChange {
+    DataKeyChange {
+        block_height,
+        index_block_hash: StacksBlockId([block; 32]),
+        data: format!("0x{}", Value::Int(value).serialize_to_hex().unwrap()),
+    }
+}
+
+#[test]
+fn test_data_var_history() {
+    let mut marf_kv = MarfedKV::temporary();
+    {
+        let mut store = marf_kv.begin(&StacksBlockId::sentinel(), &StacksBlockId([0; 32]));
+        store
+            .as_clarity_db(&TEST_HEADER_DB, &TEST_BURN_STATE_DB)
+            .initialize();
+        store.test_commit();
+    }
+    {
+        let mut store = marf_kv.begin(&StacksBlockId([0; 32]), &StacksBlockId([1; 32]));
+        let mut owned_env = OwnedEnvironment::new(
+            store.as_clarity_db(&TEST_HEADER_DB, &TEST_BURN_STATE_DB),
+            StacksEpochId::Epoch25,
+        );
+        owned_env
+            .initialize_contract(contract_id(), COUNTER, None, ASTRules::PrecheckSize)
+            .unwrap();
+        store.test_commit();
+    }
+
+    // 1 -> 2 -> 4, and 1 -> 3; 5 does not touch the counter
+    incr_block(&mut marf_kv, 1, 2, 1);
+    incr_block(&mut marf_kv, 1, 3, 2);
+    incr_block(&mut marf_kv, 2, 4, 1);
+    incr_block(&mut marf_kv, 4, 5, 0);
+
+    let key = ClarityDatabase::make_key_for_trip(&contract_id(), StoreType::Variable, "counter");
+    let marf = marf_kv.get_marf();
+
+    let changes = get_data_key_changes(marf, &StacksBlockId([5; 32]), &key, None, 10)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        changes,
+        vec![change(3, 4, 2), change(2, 2, 1), change(1, 1, 0)]
+    );
+
+    // the other fork has its own history
+    let changes = get_data_key_changes(marf, &StacksBlockId([3; 32]), &key, None, 10)
+        .unwrap()
+        .unwrap();
+    assert_eq!(changes, vec![change(2, 3, 2), change(1, 1, 0)]);
+
+    // pages
+    let changes = get_data_key_changes(marf, &StacksBlockId([5; 32]), &key, None, 2)
+        .unwrap()
+        .unwrap();
+    assert_eq!(changes, vec![change(3, 4, 2), change(2, 2, 1)]);
+    let changes = get_data_key_changes(marf, &StacksBlockId([5; 32]), &key, Some(2), 2)
+        .unwrap()
+        .unwrap();
+    assert_eq!(changes, vec![change(1, 1, 0)]);
+
+    // a page skips the heights at which only the other fork wrote the key
+    let changes = get_data_key_changes(marf, &StacksBlockId([3; 32]), &key, None, 1)
+        .unwrap()
+        .unwrap();
+    assert_eq!(changes, vec![change(2, 3, 2)]);
+    let changes = get_data_key_changes(marf, &StacksBlockId([5; 32]), &key, Some(3), 1)
+        .unwrap()
+        .unwrap();
+    assert_eq!(changes, vec![change(2, 2, 1)]);
+
+    // unknown tip
+    assert!(
+        get_data_key_changes(marf, &StacksBlockId([9; 32]), &key, None, 10)
+            .unwrap()
+            .is_none()
+    );
+}
@@ -0,0 +1,248 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+
+use clarity::vm::database::{ClarityBackingStore, StoredValue};
+use rand::Rng;
+use stacks_common::types::chainstate::StacksBlockId;
+use stacks_common::types::sqlite::NO_PARAMS;
+use stacks_common::util::hash::to_hex;
+
+use crate::chainstate::stacks::index::marf::MarfConnection;
+use crate::chainstate::stacks::index::ClarityMarfTrieId;
+use crate::clarity_vm::database::keys::scan_data_keys;
+use crate::clarity_vm::database::marf::MarfedKV;
+
+fn map_prefix(map: &str) -> String {
+    format!("vm::S1G2081040G2081040G2081040G208105NK8PE5.test::0::{map}::")
+}
+
+fn map_key(map: &str, i: usize) -> String {
+    format!("{}{i:02x}", map_prefix(map))
+}
+
+fn put_block(
+    marf_kv: &mut MarfedKV,
+    parent: &StacksBlockId,
+    block: &StacksBlockId,
+    items: Vec<(String, StoredValue)>,
+) {
+    let mut store = marf_kv.begin(parent, block);
+    store.put_all_data(items).unwrap();
+    store.test_commit();
+}
+
+#[test]
+fn test_scan_data_keys() {
+    let mut marf_kv = MarfedKV::temporary();
+    let mut items = vec![("not-a-data-key".to_string(), StoredValue::Bytes(vec![0]))];
+    for i in 0..50 {
+        items.push((map_key("a", i), StoredValue::Bytes(vec![i as u8])));
+        items.push((map_key("b", i), StoredValue::Bytes(vec![i as u8])));
+    }
+    put_block(
+        &mut marf_kv,
+        &StacksBlockId::sentinel(),
+        &StacksBlockId([1; 32]),
+        items,
+    );
+    put_block(
+        &mut marf_kv,
+        &StacksBlockId([1; 32]),
+        &StacksBlockId([2; 32]),
+        vec![(map_key("a", 0), StoredValue::Bytes(vec![0xff]))],
+    );
+    // a sibling of the tip
+    put_block(
+        &mut marf_kv,
+        &StacksBlockId([1; 32]),
+        &StacksBlockId([4; 32]),
+        vec![(map_key("a", 99), StoredValue::Bytes(vec![0x99]))],
+    );
+
+    let marf = marf_kv.get_marf();
+    let prefixes = vec![map_prefix("a")];
+    let expected: HashMap<_, _> = (0..50)
+        .map(|i| {
+            let data = if i == 0 {
+                "ff".into()
+            } else {
+                format!("{i:02x}")
+            };
+            (map_key("a", i), data)
+        })
+        .collect();
+
+    // one scan finds every entry under the prefix, as of the tip
+    let tip = StacksBlockId([2; 32]);
+    let scan = scan_data_keys(marf, &tip, &prefixes, None, 1000, usize::MAX)
+        .unwrap()
+        .unwrap();
+    assert!(scan.next.is_none());
+    assert!(scan.complete);
+    assert_eq!(
+        scan.entries.iter().cloned().collect::<HashMap<_, _>>(),
+        expected
+    );
+
+    // and so does following the cursor through short scans
+    let mut entries = HashMap::new();
+    let mut cursor = None;
+    let mut pages = 0;
+    loop {
+        let scan = scan_data_keys(marf, &tip, &prefixes, cursor.as_deref(), 7, 20)
+            .unwrap()
+            .unwrap();
+        assert!(scan.entries.len() <= 7);
+        for (key, data) in scan.entries {
+            assert!(entries.insert(key, data).is_none());
+        }
+        pages += 1;
+        match scan.next {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+    assert!(pages >= 50 / 7);
+    assert_eq!(entries, expected);
+
+    // the parent only has what it wrote
+    let scan = scan_data_keys(marf, &StacksBlockId([1; 32]), &prefixes, None, 1000, 1000)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        scan.entries
+            .iter()
+            .find(|(key, _)| key == &map_key("a", 0))
+            .map(|(_, data)| data.as_str()),
+        Some("00")
+    );
+
+    // the sibling has its own key, which the tip does not
+    let scan = scan_data_keys(marf, &StacksBlockId([4; 32]), &prefixes, None, 1000, 1000)
+        .unwrap()
+        .unwrap();
+    assert_eq!(scan.entries.len(), 51);
+    assert_eq!(
+        scan.entries.last(),
+        Some(&(map_key("a", 99), "99".to_string()))
+    );
+
+    assert!(
+        scan_data_keys(marf, &StacksBlockId([3; 32]), &prefixes, None, 1000, 1000)
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn test_scan_data_keys_prefixes() {
+    let mut marf_kv = MarfedKV::temporary();
+    let mut items = vec![];
+    for i in 0..50 {
+        items.push((map_key("a", i), StoredValue::Bytes(vec![i as u8])));
+        items.push((map_key("b", i), StoredValue::Bytes(vec![i as u8])));
+        items.push((map_key("c", i), StoredValue::Bytes(vec![i as u8])));
+    }
+    let tip = StacksBlockId([1; 32]);
+    put_block(&mut marf_kv, &StacksBlockId::sentinel(), &tip, items);
+
+    // the pages run through the prefixes in order, and skip the others
+    let marf = marf_kv.get_marf();
+    let prefixes = vec![map_prefix("c"), map_prefix("a")];
+    let scan = scan_data_keys(marf, &tip, &prefixes, None, 60, 1000)
+        .unwrap()
+        .unwrap();
+    assert_eq!(scan.entries.len(), 60);
+    assert_eq!(scan.entries[49].0, map_key("a", 49));
+    assert_eq!(scan.entries[50].0, map_key("c", 0));
+    assert_eq!(scan.next, Some(map_key("c", 9)));
+
+    let scan = scan_data_keys(marf, &tip, &prefixes, scan.next.as_deref(), 60, 1000)
+        .unwrap()
+        .unwrap();
+    assert_eq!(scan.entries.len(), 40);
+    assert_eq!(scan.entries[0].0, map_key("c", 10));
+    assert!(scan.next.is_none());
+
+    // a scan which stops early resumes where it stopped
+    let scan = scan_data_keys(marf, &tip, &prefixes, None, 60, 10)
+        .unwrap()
+        .unwrap();
+    assert_eq!(scan.entries.len(), 10);
+    assert_eq!(scan.next, Some(map_key("a", 9)));
+}
+
+#[test]
+fn test_scan_data_keys_upgraded() {
+    let path = format!(
+        "/tmp/stacks-node-tests/unit-tests-keys/{}",
+        to_hex(&rand::thread_rng().gen::<[u8; 32]>())
+    );
+    let mut marf_kv = MarfedKV::open(&path, None, None).unwrap();
+    let mut items = vec![];
+    for i in 0..10 {
+        items.push((map_key("a", i), StoredValue::Bytes(vec![i as u8])));
+    }
+    put_block(
+        &mut marf_kv,
+        &StacksBlockId::sentinel(),
+        &StacksBlockId([1; 32]),
+        items,
+    );
+
+    // a side store from before the data key history
+    marf_kv
+        .get_marf()
+        .sqlite_conn()
+        .execute_batch("DROP TABLE data_key_history_info; DELETE FROM data_key_history;")
+        .unwrap();
+    drop(marf_kv);
+
+    let mut marf_kv = MarfedKV::open(&path, None, None).unwrap();
+    put_block(
+        &mut marf_kv,
+        &StacksBlockId([1; 32]),
+        &StacksBlockId([2; 32]),
+        vec![(map_key("a", 3), StoredValue::Bytes(vec![0xff]))],
+    );
+
+    // only the key written since is known, and the scan says so
+    let scan = scan_data_keys(
+        marf_kv.get_marf(),
+        &StacksBlockId([2; 32]),
+        &[map_prefix("a")],
+        None,
+        100,
+        100,
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(scan.entries, vec![(map_key("a", 3), "ff".to_string())]);
+    assert!(!scan.complete);
+    assert_eq!(
+        marf_kv
+            .get_marf()
+            .sqlite_conn()
+            .query_row(
+                "SELECT COUNT(*) FROM data_key_history_info",
+                NO_PARAMS,
+                |row| { row.get::<_, i64>(0) }
+            )
+            .unwrap(),
+        1
+    );
+}
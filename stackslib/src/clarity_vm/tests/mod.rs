@@ -21,5 +21,9 @@ pub mod costs;
 pub mod epoch_switch;
 pub mod events;
 pub mod forking;
+pub mod history;
+pub mod keys;
 pub mod large_contract;
+pub mod proofs;
+pub mod side_store;
 pub mod simple_tests;
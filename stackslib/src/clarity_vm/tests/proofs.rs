@@ -0,0 +1,146 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+
+use clarity::vm::database::{ClarityBackingStore, ClaritySerializable, STXBalance, StoredValue};
+use clarity::vm::types::{PrincipalData, QualifiedContractIdentifier};
+use clarity::vm::Value;
+use libmarfproof::MarfProof;
+use stacks_common::types::chainstate::{StacksBlockId, TrieHash};
+use stacks_common::util::hash::to_hex;
+
+use crate::chainstate::stacks::index::marf::MarfConnection;
+use crate::chainstate::stacks::index::ClarityMarfTrieId;
+use crate::clarity_vm::database::marf::MarfedKV;
+use crate::clarity_vm::proofs::{ClarityStateKey, ClarityStateValue};
+
+fn get_with_proof(
+    marf_kv: &mut MarfedKV,
+    tip: &StacksBlockId,
+    key: &ClarityStateKey,
+) -> (String, MarfProof) {
+    let mut store = marf_kv.begin_read_only(Some(tip));
+    let (data, proof) = store
+        .get_data_with_proof(&key.marf_key().unwrap())
+        .unwrap()
+        .unwrap();
+    let proof = MarfProof::from_hex(&to_hex(&proof)).unwrap();
+    (data, proof)
+}
+
+#[test]
+fn test_verify_clarity_state_proofs() {
+    let contract =
+        QualifiedContractIdentifier::parse("S1G2081040G2081040G2081040G208105NK8PE5.test").unwrap();
+    let data_var = ClarityStateKey::DataVar {
+        contract: contract.clone(),
+        var_name: "counter".into(),
+    };
+    let map_entry = ClarityStateKey::MapEntry {
+        contract: contract.clone(),
+        map_name: "entries".into(),
+        key: Value::Int(7),
+    };
+    let other_map_entry = ClarityStateKey::MapEntry {
+        contract,
+        map_name: "entries".into(),
+        key: Value::Int(8),
+    };
+    let principal = PrincipalData::parse("S1G2081040G2081040G2081040G208105NK8PE5").unwrap();
+    let balance = ClarityStateKey::StxBalance(principal);
+
+    let map_value = Value::some(Value::UInt(1)).unwrap();
+    let stx_balance = STXBalance::Unlocked { amount: 1000 };
+
+    // the map entry and the balance are written long before the tip, so their proofs pass
+    //  through ancestor tries
+    let blocks: Vec<_> = (1..=10).map(|i| StacksBlockId([i; 32])).collect();
+    let tip = blocks[9].clone();
+    let mut marf_kv = MarfedKV::temporary();
+    let mut parent = StacksBlockId::sentinel();
+    for (i, block) in blocks.iter().enumerate() {
+        let mut items = vec![(format!("filler-{i}"), StoredValue::Bytes(vec![0]))];
+        if i == 0 {
+            items.push((
+                data_var.marf_key().unwrap(),
+                StoredValue::Bytes(Value::UInt(42).serialize_to_vec().unwrap()),
+            ));
+            items.push((
+                map_entry.marf_key().unwrap(),
+                StoredValue::Bytes(map_value.serialize_to_vec().unwrap()),
+            ));
+            items.push((balance.marf_key().unwrap(), stx_balance.serialize_stored()));
+        }
+        if block == &tip {
+            items.push((
+                data_var.marf_key().unwrap(),
+                StoredValue::Bytes(Value::UInt(43).serialize_to_vec().unwrap()),
+            ));
+        }
+        let mut store = marf_kv.begin(&parent, block);
+        store.put_all_data(items).unwrap();
+        store.test_commit();
+        parent = block.clone();
+    }
+
+    let root_to_block: HashMap<TrieHash, StacksBlockId> = blocks
+        .iter()
+        .map(|block| {
+            let root = marf_kv.get_marf().get_root_hash_at(block).unwrap();
+            (root, block.clone())
+        })
+        .collect();
+    let index_root = marf_kv.get_marf().get_root_hash_at(&tip).unwrap();
+    let lookup = |root: &TrieHash| root_to_block.get(root).cloned();
+
+    let (data, proof) = get_with_proof(&mut marf_kv, &tip, &data_var);
+    assert!(data_var.verify(&data, &proof, &index_root, lookup).unwrap());
+    assert_eq!(
+        data_var.decode_data(&data).unwrap(),
+        ClarityStateValue::Value(Value::UInt(43))
+    );
+    let stale = Value::UInt(42).serialize_to_hex().unwrap();
+    assert!(!data_var
+        .verify(&stale, &proof, &index_root, lookup)
+        .unwrap());
+
+    let (data, proof) = get_with_proof(&mut marf_kv, &tip, &map_entry);
+    assert!(map_entry
+        .verify(&format!("0x{data}"), &proof, &index_root, lookup)
+        .unwrap());
+    assert_eq!(
+        map_entry.decode_data(&data).unwrap(),
+        ClarityStateValue::Value(map_value)
+    );
+    // the proof is for one key only
+    assert!(!other_map_entry
+        .verify(&data, &proof, &index_root, lookup)
+        .unwrap());
+    // ancestor tries must be known
+    assert!(!map_entry
+        .verify(&data, &proof, &index_root, |_| None)
+        .unwrap());
+    // and the proof only holds at the root it was made at
+    let old_root = marf_kv.get_marf().get_root_hash_at(&blocks[8]).unwrap();
+    assert!(!map_entry.verify(&data, &proof, &old_root, lookup).unwrap());
+
+    let (data, proof) = get_with_proof(&mut marf_kv, &tip, &balance);
+    assert!(balance.verify(&data, &proof, &index_root, lookup).unwrap());
+    assert_eq!(
+        balance.decode_data(&data).unwrap(),
+        ClarityStateValue::StxBalance(stx_balance)
+    );
+}
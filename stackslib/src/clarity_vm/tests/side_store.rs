@@ -0,0 +1,146 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use clarity::vm::database::{ClarityBackingStore, SqliteConnection, StoredValue};
+use clarity::vm::types::Value;
+use rusqlite::{params, Connection};
+use stacks_common::types::chainstate::StacksBlockId;
+use stacks_common::types::sqlite::NO_PARAMS;
+use stacks_common::util::hash::hex_bytes;
+
+use crate::chainstate::stacks::index::ClarityMarfTrieId;
+use crate::clarity_vm::database::marf::MarfedKV;
+
+fn value_type(conn: &Connection, key: &str) -> String {
+    conn.query_row(
+        "SELECT typeof(value) FROM data_table WHERE key = ?",
+        params![key],
+        |row| row.get(0),
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_side_store_values() {
+    let conn = SqliteConnection::memory().unwrap();
+    let value = Value::UInt(1).serialize_to_hex().unwrap();
+
+    // serialized values are stored as bytes, and read back either way
+    let bytes = StoredValue::Bytes(hex_bytes(&value).unwrap());
+    SqliteConnection::put(&conn, "value", &bytes).unwrap();
+    assert_eq!(value_type(&conn, "value"), "blob");
+    assert_eq!(
+        SqliteConnection::get(&conn, "value").unwrap(),
+        Some(value.clone())
+    );
+    assert_eq!(
+        SqliteConnection::get_bytes(&conn, "value").unwrap(),
+        Some(hex_bytes(&value).unwrap())
+    );
+
+    // text is stored verbatim, even when it looks like hex
+    for text in ["ABCD", "abc", "00ff", "not hex"] {
+        SqliteConnection::put(&conn, text, &StoredValue::Text(text.into())).unwrap();
+        assert_eq!(value_type(&conn, text), "text");
+        assert_eq!(
+            SqliteConnection::get(&conn, text).unwrap().as_deref(),
+            Some(text)
+        );
+    }
+    assert!(SqliteConnection::get_bytes(&conn, "not hex").is_err());
+
+    assert_eq!(SqliteConnection::get(&conn, "missing").unwrap(), None);
+    assert_eq!(SqliteConnection::get_bytes(&conn, "missing").unwrap(), None);
+}
+
+#[test]
+fn test_compact_data_table() {
+    let conn = SqliteConnection::memory().unwrap();
+    let values: Vec<_> = (0..3)
+        .map(|i| Value::Int(i).serialize_to_hex().unwrap())
+        .collect();
+
+    // rows as they were written before values were stored as bytes
+    for (i, value) in values.iter().enumerate() {
+        conn.execute(
+            "INSERT INTO data_table (key, value) VALUES (?, ?)",
+            params![format!("key-{i}"), value],
+        )
+        .unwrap();
+        if i == 0 {
+            conn.execute(
+                "INSERT INTO data_table (key, value) VALUES ('text', 'not hex')",
+                NO_PARAMS,
+            )
+            .unwrap();
+        }
+    }
+    for (i, value) in values.iter().enumerate() {
+        let key = format!("key-{i}");
+        assert_eq!(value_type(&conn, &key), "text");
+        assert_eq!(
+            SqliteConnection::get_bytes(&conn, &key).unwrap(),
+            Some(hex_bytes(value).unwrap())
+        );
+    }
+
+    // each call picks up after the last row the previous one looked at
+    let (compacted, last_rowid) = SqliteConnection::compact_data_table(&conn, 0, 2).unwrap();
+    assert_eq!(compacted, 2);
+    assert_eq!(value_type(&conn, "key-2"), "text");
+    let (compacted, last_rowid) =
+        SqliteConnection::compact_data_table(&conn, last_rowid, 2).unwrap();
+    assert_eq!(compacted, 1);
+    let (compacted, _) = SqliteConnection::compact_data_table(&conn, last_rowid, 2).unwrap();
+    assert_eq!(compacted, 0);
+
+    for (i, value) in values.iter().enumerate() {
+        let key = format!("key-{i}");
+        assert_eq!(value_type(&conn, &key), "blob");
+        assert_eq!(
+            SqliteConnection::get(&conn, &key).unwrap(),
+            Some(value.clone())
+        );
+    }
+    assert_eq!(value_type(&conn, "text"), "text");
+    assert_eq!(
+        SqliteConnection::get(&conn, "text").unwrap().as_deref(),
+        Some("not hex")
+    );
+}
+
+#[test]
+fn test_marf_store_bytes() {
+    let mut marf_kv = MarfedKV::temporary();
+    let value = Value::UInt(1).serialize_to_hex().unwrap();
+    {
+        let mut store = marf_kv.begin(&StacksBlockId::sentinel(), &StacksBlockId([1; 32]));
+        store
+            .put_all_data(vec![(
+                "vm::key".into(),
+                StoredValue::Bytes(hex_bytes(&value).unwrap()),
+            )])
+            .unwrap();
+        store.test_commit();
+    }
+
+    let mut store = marf_kv.begin_read_only(Some(&StacksBlockId([1; 32])));
+    assert_eq!(store.get_data("vm::key").unwrap(), Some(value.clone()));
+    assert_eq!(
+        store.get_data_bytes("vm::key").unwrap(),
+        Some(hex_bytes(&value).unwrap())
+    );
+    assert_eq!(store.get_data_bytes("vm::missing").unwrap(), None);
+}
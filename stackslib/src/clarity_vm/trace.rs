@@ -0,0 +1,127 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Execution traces of the transactions in processed blocks.
+//!
+//! When a `ClarityInstance` has a `TxTraceConfig`, each block connection it
+//! begins carries an `ExecutionTracer`. It only evaluates while block processing
+//! has a transaction open with `begin_transaction`, so blocks assembled by the
+//! miner are not traced. The traces of the transactions selected by the config
+//! are written to `<output_dir>/<txid>.json`.
+
+use std::fs;
+use std::path::PathBuf;
+
+use clarity::vm::tracer::{ExecutionTrace, ExecutionTracer};
+use clarity::vm::types::ResponseData;
+use clarity::vm::Value;
+use serde_json::json;
+
+use crate::chainstate::stacks::events::StacksTransactionReceipt;
+
+/// Where, and for which transactions, to write execution traces
+#[derive(Debug, Clone, PartialEq)]
+pub struct TxTraceConfig {
+    pub output_dir: PathBuf,
+    /// Only keep the traces of transactions which failed: those which
+    ///  returned an `err`, hit a runtime error, or were aborted by a post-condition
+    pub failed_only: bool,
+}
+
+/// Traces the transactions of a block, one at a time
+pub struct TxTracer {
+    config: TxTraceConfig,
+    tracer: ExecutionTracer,
+    /// Is a transaction being traced?
+    in_transaction: bool,
+}
+
+/// Did the transaction fail?
+pub fn is_failed_transaction(receipt: &StacksTransactionReceipt) -> bool {
+    receipt.post_condition_aborted
+        || receipt.vm_error.is_some()
+        || matches!(
+            receipt.result,
+            Value::Response(ResponseData {
+                committed: false,
+                ..
+            })
+        )
+}
+
+/// The trace of a transaction, along with the outcome from its receipt
+pub fn tx_trace_to_json(
+    receipt: &StacksTransactionReceipt,
+    trace: &ExecutionTrace,
+) -> serde_json::Value {
+    json!({
+        "txid": receipt.transaction.txid(),
+        "tx_index": receipt.tx_index,
+        "result": receipt.result.to_json(),
+        "post_condition_aborted": receipt.post_condition_aborted,
+        "vm_error": receipt.vm_error,
+        "steps": trace.steps,
+    })
+}
+
+impl TxTracer {
+    pub fn new(config: TxTraceConfig) -> TxTracer {
+        TxTracer {
+            config,
+            tracer: ExecutionTracer::new(),
+            in_transaction: false,
+        }
+    }
+
+    /// The hook to evaluate with, if a transaction is being traced
+    pub fn eval_hook(&mut self) -> Option<&mut ExecutionTracer> {
+        if self.in_transaction {
+            Some(&mut self.tracer)
+        } else {
+            None
+        }
+    }
+
+    /// Start tracing a transaction of the block
+    pub fn begin_transaction(&mut self) {
+        self.tracer.take_trace();
+        self.in_transaction = true;
+    }
+
+    /// Write out the trace of the transaction which produced `receipt`, if
+    ///  the config selects it. Failing to write a trace is logged, and does
+    ///  not interrupt block processing.
+    pub fn finish_transaction(&mut self, receipt: &StacksTransactionReceipt) {
+        let trace = self.tracer.take_trace();
+        self.in_transaction = false;
+        if self.config.failed_only && !is_failed_transaction(receipt) {
+            return;
+        }
+        let txid = receipt.transaction.txid();
+        let path = self.config.output_dir.join(format!("{txid}.json"));
+        let written = fs::create_dir_all(&self.config.output_dir).and_then(|_| {
+            let json = tx_trace_to_json(receipt, &trace);
+            fs::write(&path, serde_json::to_vec_pretty(&json)?)
+        });
+        match written {
+            Ok(()) => debug!("Wrote execution trace"; "txid" => %txid, "path" => %path.display()),
+            Err(e) => warn!("Failed to write execution trace";
+                "txid" => %txid,
+                "path" => %path.display(),
+                "err" => %e
+            ),
+        }
+    }
+}
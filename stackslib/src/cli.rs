@@ -47,6 +47,7 @@ use crate::chainstate::stacks::db::{StacksBlockHeaderTypes, StacksChainState, St
 use crate::chainstate::stacks::miner::*;
 use crate::chainstate::stacks::{Error as ChainstateError, *};
 use crate::clarity_vm::clarity::ClarityInstance;
+use crate::clarity_vm::trace::TxTraceConfig;
 use crate::config::{Config, ConfigFile, DEFAULT_MAINNET_CONFIG};
 use crate::core::*;
 use crate::cost_estimates::metrics::UnitMetric;
@@ -127,13 +128,30 @@ pub fn command_replay_block(argv: &[String], conf: Option<&Config>) {
     let print_help_and_exit = || -> ! {
         let n = &argv[0];
         eprintln!("Usage:");
-        eprintln!("  {n} <database-path>");
-        eprintln!("  {n} <database-path> prefix <index-block-hash-prefix>");
-        eprintln!("  {n} <database-path> index-range <start-block> <end-block>");
-        eprintln!("  {n} <database-path> range <start-block> <end-block>");
-        eprintln!("  {n} <database-path> <first|last> <block-count>");
+        eprintln!("  {n} [--trace <output-dir>] <database-path>");
+        eprintln!("  {n} [--trace <output-dir>] <database-path> prefix <index-block-hash-prefix>");
+        eprintln!(
+            "  {n} [--trace <output-dir>] <database-path> index-range <start-block> <end-block>"
+        );
+        eprintln!("  {n} [--trace <output-dir>] <database-path> range <start-block> <end-block>");
+        eprintln!("  {n} [--trace <output-dir>] <database-path> <first|last> <block-count>");
+        eprintln!();
+        eprintln!("With --trace, the execution trace of each replayed transaction is written");
+        eprintln!("to <output-dir>/<txid>.json");
         process::exit(1);
     };
+    let mut argv = argv.to_vec();
+    let tx_trace = argv.iter().position(|arg| arg == "--trace").map(|i| {
+        let output_dir = argv
+            .get(i + 1)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| print_help_and_exit());
+        argv.drain(i..i + 2);
+        TxTraceConfig {
+            output_dir,
+            failed_only: false,
+        }
+    });
     let start = Instant::now();
     let db_path = argv.get(1).unwrap_or_else(|| print_help_and_exit());
     let mode = argv.get(2).map(String::as_str);
@@ -191,7 +209,7 @@ pub fn command_replay_block(argv: &[String], conf: Option<&Config>) {
         if i % 100 == 0 {
             println!("Checked {i}...");
         }
-        replay_staging_block(db_path, index_block_hash, conf);
+        replay_staging_block(db_path, index_block_hash, conf, tx_trace.as_ref());
     }
     println!("Finished. run_time_seconds = {}", start.elapsed().as_secs());
 }
@@ -205,13 +223,30 @@ pub fn command_replay_block_nakamoto(argv: &[String], conf: Option<&Config>) {
     let print_help_and_exit = || -> ! {
         let n = &argv[0];
         eprintln!("Usage:");
-        eprintln!("  {n} <database-path>");
-        eprintln!("  {n} <database-path> prefix <index-block-hash-prefix>");
-        eprintln!("  {n} <database-path> index-range <start-block> <end-block>");
-        eprintln!("  {n} <database-path> range <start-block> <end-block>");
-        eprintln!("  {n} <database-path> <first|last> <block-count>");
+        eprintln!("  {n} [--trace <output-dir>] <database-path>");
+        eprintln!("  {n} [--trace <output-dir>] <database-path> prefix <index-block-hash-prefix>");
+        eprintln!(
+            "  {n} [--trace <output-dir>] <database-path> index-range <start-block> <end-block>"
+        );
+        eprintln!("  {n} [--trace <output-dir>] <database-path> range <start-block> <end-block>");
+        eprintln!("  {n} [--trace <output-dir>] <database-path> <first|last> <block-count>");
+        eprintln!();
+        eprintln!("With --trace, the execution trace of each replayed transaction is written");
+        eprintln!("to <output-dir>/<txid>.json");
         process::exit(1);
     };
+    let mut argv = argv.to_vec();
+    let tx_trace = argv.iter().position(|arg| arg == "--trace").map(|i| {
+        let output_dir = argv
+            .get(i + 1)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| print_help_and_exit());
+        argv.drain(i..i + 2);
+        TxTraceConfig {
+            output_dir,
+            failed_only: false,
+        }
+    });
     let start = Instant::now();
     let db_path = argv.get(1).unwrap_or_else(|| print_help_and_exit());
     let mode = argv.get(2).map(String::as_str);
@@ -279,7 +314,7 @@ pub fn command_replay_block_nakamoto(argv: &[String], conf: Option<&Config>) {
         if i % 100 == 0 {
             println!("Checked {i}...");
         }
-        replay_naka_staging_block(db_path, index_block_hash, conf);
+        replay_naka_staging_block(db_path, index_block_hash, conf, tx_trace.as_ref());
     }
     println!("Finished. run_time_seconds = {}", start.elapsed().as_secs());
 }
@@ -551,7 +586,12 @@ pub fn command_try_mine(argv: &[String], conf: Option<&Config>) {
 }
 
 /// Fetch and process a `StagingBlock` from database and call `replay_block()` to validate
-fn replay_staging_block(db_path: &str, index_block_hash_hex: &str, conf: Option<&Config>) {
+fn replay_staging_block(
+    db_path: &str,
+    index_block_hash_hex: &str,
+    conf: Option<&Config>,
+    tx_trace: Option<&TxTraceConfig>,
+) {
     let block_id = StacksBlockId::from_hex(index_block_hash_hex).unwrap();
     let chain_state_path = format!("{db_path}/chainstate/");
     let sort_db_path = format!("{db_path}/burnchain/sortition");
@@ -587,6 +627,7 @@ fn replay_staging_block(db_path: &str, index_block_hash_hex: &str, conf: Option<
     let (mut chainstate_tx, clarity_instance) = chainstate
         .chainstate_tx_begin()
         .expect("Failed to start chainstate tx");
+    clarity_instance.set_tx_trace_config(tx_trace.cloned());
     let mut next_staging_block =
         StacksChainState::load_staging_block_info(&chainstate_tx.tx, &block_id)
             .expect("Failed to load staging block data")
@@ -833,7 +874,12 @@ fn replay_block(
 }
 
 /// Fetch and process a NakamotoBlock from database and call `replay_block_nakamoto()` to validate
-fn replay_naka_staging_block(db_path: &str, index_block_hash_hex: &str, conf: &Config) {
+fn replay_naka_staging_block(
+    db_path: &str,
+    index_block_hash_hex: &str,
+    conf: &Config,
+    tx_trace: Option<&TxTraceConfig>,
+) {
     let block_id = StacksBlockId::from_hex(index_block_hash_hex).unwrap();
     let chain_state_path = format!("{db_path}/chainstate/");
     let sort_db_path = format!("{db_path}/burnchain/sortition");
@@ -865,7 +911,7 @@ fn replay_naka_staging_block(db_path: &str, index_block_hash_hex: &str, conf: &C
         .get_nakamoto_block(&block_id)
         .unwrap()
         .unwrap();
-    replay_block_nakamoto(&mut sortdb, &mut chainstate, &block, block_size).unwrap();
+    replay_block_nakamoto(&mut sortdb, &mut chainstate, &block, block_size, tx_trace).unwrap();
 }
 
 fn replay_block_nakamoto(
@@ -873,6 +919,7 @@ fn replay_block_nakamoto(
     stacks_chain_state: &mut StacksChainState,
     block: &NakamotoBlock,
     block_size: u64,
+    tx_trace: Option<&TxTraceConfig>,
 ) -> Result<(), ChainstateError> {
     // find corresponding snapshot
     let next_ready_block_snapshot =
@@ -923,6 +970,7 @@ fn replay_block_nakamoto(
             ChainstateError::NoSuchBlockError
         })?;
     let (mut chainstate_tx, clarity_instance) = stacks_chain_state.chainstate_tx_begin()?;
+    clarity_instance.set_tx_trace_config(tx_trace.cloned());
 
     // find parent header
     let Some(parent_header_info) =
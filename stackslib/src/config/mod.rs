@@ -41,10 +41,12 @@ use crate::burnchains::bitcoin::BitcoinNetworkType;
 use crate::burnchains::{Burnchain, MagicBytes, PoxConstants, BLOCKSTACK_MAGIC_MAINNET};
 use crate::chainstate::nakamoto::signer_set::NakamotoSigners;
 use crate::chainstate::stacks::boot::MINERS_NAME;
+use crate::chainstate::stacks::index::file::TrieFileBackend;
 use crate::chainstate::stacks::index::marf::MARFOpenOpts;
 use crate::chainstate::stacks::index::storage::TrieHashCalculationMode;
 use crate::chainstate::stacks::miner::{BlockBuilderSettings, MinerStatus};
 use crate::chainstate::stacks::MAX_BLOCK_LEN;
+use crate::clarity_vm::trace::TxTraceConfig;
 use crate::config::chain_data::MinerStats;
 use crate::core::mempool::{MemPoolWalkSettings, MemPoolWalkTxTypes};
 use crate::core::{
@@ -53,6 +55,7 @@ use crate::core::{
     BITCOIN_TESTNET_STACKS_25_REORGED_HEIGHT, CHAIN_ID_MAINNET, CHAIN_ID_TESTNET,
     PEER_VERSION_MAINNET, PEER_VERSION_TESTNET, STACKS_EPOCHS_REGTEST, STACKS_EPOCHS_TESTNET,
 };
+use crate::cost_estimates::fee_confirm_time::ConfirmTimeFeeRateEstimator;
 use crate::cost_estimates::fee_medians::WeightedMedianFeeRateEstimator;
 use crate::cost_estimates::fee_rate_fuzzer::FeeRateFuzzer;
 use crate::cost_estimates::fee_scalar::ScalarFeeRateEstimator;
@@ -588,13 +591,16 @@ impl Config {
             .make_cost_metric()
             .unwrap_or_else(|| Box::new(UnitMetric));
 
-        MemPoolDB::open(
+        let mut mempool = MemPoolDB::open(
             self.is_mainnet(),
             self.burnchain.chain_id,
             &self.get_chainstate_path_str(),
             cost_estimator,
             metric,
-        )
+        )?;
+        mempool.max_size_bytes = self.connection_options.mempool_max_size_bytes;
+        mempool.max_txs_per_address = self.connection_options.mempool_max_txs_per_address;
+        Ok(mempool)
     }
 
     /// Load up a Burnchain and apply config settings to it.
@@ -1097,6 +1103,7 @@ impl Config {
                 filter_origins: miner_config.filter_origins,
                 tenure_cost_limit_per_block_percentage: miner_config
                     .tenure_cost_limit_per_block_percentage,
+                consider_packages: miner_config.consider_tx_packages,
             },
             miner_status,
             confirm_microblocks: false,
@@ -1139,6 +1146,7 @@ impl Config {
                 filter_origins: miner_config.filter_origins,
                 tenure_cost_limit_per_block_percentage: miner_config
                     .tenure_cost_limit_per_block_percentage,
+                consider_packages: miner_config.consider_tx_packages,
             },
             miner_status,
             confirm_microblocks: true,
@@ -1659,6 +1667,9 @@ pub struct NodeConfig {
     pub mock_mining: bool,
     /// Where to output blocks from mock mining
     pub mock_mining_output_dir: Option<PathBuf>,
+    /// If set, trace the transactions of processed blocks, and write the
+    /// execution traces of those which fail to `<dir>/<txid>.json`
+    pub tx_trace_output_dir: Option<PathBuf>,
     pub mine_microblocks: bool,
     pub microblock_frequency: u64,
     pub max_microblocks: u64,
@@ -1674,6 +1685,9 @@ pub struct NodeConfig {
     pub prometheus_bind: Option<String>,
     pub marf_cache_strategy: Option<String>,
     pub marf_defer_hashing: bool,
+    /// How the Clarity state MARF stores its trie blobs: "disk" (the default), or "mmap" to
+    /// read them through a memory mapping of the blobs file
+    pub marf_blob_backend: TrieFileBackend,
     pub pox_sync_sample_secs: u64,
     pub use_test_genesis_chainstate: Option<bool>,
     pub always_use_affirmation_maps: bool,
@@ -1700,6 +1714,7 @@ pub enum CostEstimatorName {
 pub enum FeeEstimatorName {
     ScalarFeeRate,
     FuzzedWeightedMedianFeeRate,
+    ConfirmTimeFeeRate,
 }
 
 #[derive(Clone, Debug)]
@@ -1741,6 +1756,8 @@ impl FeeEstimatorName {
             FeeEstimatorName::ScalarFeeRate
         } else if &s.to_lowercase() == "fuzzed_weighted_median_fee_rate" {
             FeeEstimatorName::FuzzedWeightedMedianFeeRate
+        } else if &s.to_lowercase() == "confirm_time_fee_rate" {
+            FeeEstimatorName::ConfirmTimeFeeRate
         } else {
             panic!("Bad fee estimator name supplied in configuration file: {s}");
         }
@@ -1769,6 +1786,9 @@ pub struct FeeEstimationConfig {
     /// If using WeightedMedianFeeRateEstimator, the window size to use. See comments on
     /// WeightedMedianFeeRateEstimator.
     pub fee_rate_window_size: u64,
+    /// If using ConfirmTimeFeeRateEstimator, the number of blocks to learn confirmation times
+    /// from. See comments on ConfirmTimeFeeRateEstimator.
+    pub fee_confirm_time_window_size: u64,
 }
 
 impl Default for FeeEstimationConfig {
@@ -1780,6 +1800,7 @@ impl Default for FeeEstimationConfig {
             log_error: false,
             fee_rate_fuzzer_fraction: 0.1f64,
             fee_rate_window_size: 5u64,
+            fee_confirm_time_window_size: 100u64,
         }
     }
 }
@@ -1794,6 +1815,7 @@ impl From<FeeEstimationConfigFile> for FeeEstimationConfig {
                 log_error: false,
                 fee_rate_fuzzer_fraction: 0f64,
                 fee_rate_window_size: 0u64,
+                fee_confirm_time_window_size: 0u64,
             };
         }
         let cost_estimator = f
@@ -1816,6 +1838,7 @@ impl From<FeeEstimationConfigFile> for FeeEstimationConfig {
             log_error,
             fee_rate_fuzzer_fraction: f.fee_rate_fuzzer_fraction.unwrap_or(0.1f64),
             fee_rate_window_size: f.fee_rate_window_size.unwrap_or(5u64),
+            fee_confirm_time_window_size: f.fee_confirm_time_window_size.unwrap_or(100u64),
         }
     }
 }
@@ -1852,6 +1875,15 @@ impl Config {
             FeeEstimatorName::FuzzedWeightedMedianFeeRate => self
                 .estimation
                 .make_fuzzed_weighted_median_fee_estimator(self.get_estimates_path(), metric),
+            FeeEstimatorName::ConfirmTimeFeeRate => {
+                let mempool_path = MemPoolDB::db_path(&self.get_chainstate_path_str())
+                    .expect("FATAL: failed to construct mempool DB path");
+                self.estimation.make_confirm_time_fee_estimator(
+                    self.get_estimates_path(),
+                    PathBuf::from(mempool_path),
+                    metric,
+                )
+            }
         };
 
         Some(fee_estimator)
@@ -1913,6 +1945,30 @@ impl FeeEstimationConfig {
             panic!("BUG: Expected to configure a weighted median fee estimator");
         }
     }
+
+    pub fn make_confirm_time_fee_estimator<CM: CostMetric + 'static>(
+        &self,
+        mut estimates_path: PathBuf,
+        mempool_path: PathBuf,
+        metric: CM,
+    ) -> Box<dyn FeeEstimator> {
+        if let Some(FeeEstimatorName::ConfirmTimeFeeRate) = self.fee_estimator.as_ref() {
+            estimates_path.push("fee_estimator_confirm_time.sqlite");
+            Box::new(
+                ConfirmTimeFeeRateEstimator::open(
+                    &estimates_path,
+                    &mempool_path,
+                    metric,
+                    self.fee_confirm_time_window_size
+                        .try_into()
+                        .expect("Configured confirmation time window size out of bounds."),
+                )
+                .expect("Error opening fee estimator"),
+            )
+        } else {
+            panic!("BUG: Expected to configure a confirmation time fee estimator");
+        }
+    }
 }
 
 impl Default for NodeConfig {
@@ -1949,6 +2005,7 @@ impl Default for NodeConfig {
             stacker: false,
             mock_mining: false,
             mock_mining_output_dir: None,
+            tx_trace_output_dir: None,
             mine_microblocks: true,
             microblock_frequency: 30_000,
             max_microblocks: u16::MAX as u64,
@@ -1958,6 +2015,7 @@ impl Default for NodeConfig {
             prometheus_bind: None,
             marf_cache_strategy: None,
             marf_defer_hashing: true,
+            marf_blob_backend: TrieFileBackend::Disk,
             pox_sync_sample_secs: 30,
             use_test_genesis_chainstate: None,
             always_use_affirmation_maps: true,
@@ -2114,6 +2172,17 @@ impl NodeConfig {
             self.marf_cache_strategy.as_deref().unwrap_or("noop"),
             false,
         )
+        .with_blob_backend(self.marf_blob_backend)
+    }
+
+    /// How the chains coordinator traces the transactions of the blocks it processes
+    pub fn get_tx_trace_config(&self) -> Option<TxTraceConfig> {
+        self.tx_trace_output_dir
+            .as_ref()
+            .map(|output_dir| TxTraceConfig {
+                output_dir: output_dir.clone(),
+                failed_only: true,
+            })
     }
 }
 
@@ -2186,6 +2255,10 @@ pub struct MinerConfig {
     pub tenure_extend_cost_threshold: u64,
     /// Define the timeout to apply while waiting for signers responses, based on the amount of rejections
     pub block_rejection_timeout_steps: HashMap<u32, Duration>,
+    /// Whether to weigh each chain of transactions from one account by its aggregate fee rate
+    /// when walking the mempool, so that higher-fee transactions can pull in the lower-fee ones
+    /// they depend on.
+    pub consider_tx_packages: bool,
 }
 
 impl Default for MinerConfig {
@@ -2234,6 +2307,7 @@ impl Default for MinerConfig {
                 rejections_timeouts_default_map.insert(30, Duration::from_secs(0));
                 rejections_timeouts_default_map
             },
+            consider_tx_packages: false,
         }
     }
 }
@@ -2288,6 +2362,9 @@ pub struct ConnectionOptionsFile {
     pub reject_blocks_pushed: Option<bool>,
     pub stackerdb_hint_replicas: Option<String>,
     pub block_proposal_max_age_secs: Option<u64>,
+    pub mempool_reject_nonce_gaps: Option<bool>,
+    pub mempool_max_size_bytes: Option<u64>,
+    pub mempool_max_txs_per_address: Option<u64>,
 }
 
 impl ConnectionOptionsFile {
@@ -2439,6 +2516,11 @@ impl ConnectionOptionsFile {
             block_proposal_max_age_secs: self
                 .block_proposal_max_age_secs
                 .unwrap_or(DEFAULT_BLOCK_PROPOSAL_MAX_AGE_SECS),
+            mempool_reject_nonce_gaps: self
+                .mempool_reject_nonce_gaps
+                .unwrap_or(default.mempool_reject_nonce_gaps),
+            mempool_max_size_bytes: self.mempool_max_size_bytes,
+            mempool_max_txs_per_address: self.mempool_max_txs_per_address,
             ..default
         })
     }
@@ -2461,6 +2543,7 @@ pub struct NodeConfigFile {
     pub stacker: Option<bool>,
     pub mock_mining: Option<bool>,
     pub mock_mining_output_dir: Option<String>,
+    pub tx_trace_output_dir: Option<String>,
     pub mine_microblocks: Option<bool>,
     pub microblock_frequency: Option<u64>,
     pub max_microblocks: Option<u64>,
@@ -2470,6 +2553,7 @@ pub struct NodeConfigFile {
     pub prometheus_bind: Option<String>,
     pub marf_cache_strategy: Option<String>,
     pub marf_defer_hashing: Option<bool>,
+    pub marf_blob_backend: Option<String>,
     pub pox_sync_sample_secs: Option<u64>,
     pub use_test_genesis_chainstate: Option<bool>,
     pub always_use_affirmation_maps: Option<bool>,
@@ -2523,6 +2607,7 @@ impl NodeConfigFile {
                 .unwrap_or_else(|e| {
                     panic!("Failed to construct PathBuf from node.mock_mining_output_dir: {e}")
                 }),
+            tx_trace_output_dir: self.tx_trace_output_dir.map(PathBuf::from),
             mine_microblocks: self
                 .mine_microblocks
                 .unwrap_or(default_node_config.mine_microblocks),
@@ -2546,6 +2631,12 @@ impl NodeConfigFile {
             marf_defer_hashing: self
                 .marf_defer_hashing
                 .unwrap_or(default_node_config.marf_defer_hashing),
+            marf_blob_backend: self
+                .marf_blob_backend
+                .map(|backend| backend.parse())
+                .transpose()
+                .map_err(|e| format!("node.marf_blob_backend: {e}"))?
+                .unwrap_or(default_node_config.marf_blob_backend),
             pox_sync_sample_secs: self
                 .pox_sync_sample_secs
                 .unwrap_or(default_node_config.pox_sync_sample_secs),
@@ -2595,6 +2686,7 @@ pub struct FeeEstimationConfigFile {
     pub log_error: Option<bool>,
     pub fee_rate_fuzzer_fraction: Option<f64>,
     pub fee_rate_window_size: Option<u64>,
+    pub fee_confirm_time_window_size: Option<u64>,
 }
 
 #[derive(Clone, Deserialize, Default, Debug)]
@@ -2632,6 +2724,7 @@ pub struct MinerConfigFile {
     pub tenure_timeout_secs: Option<u64>,
     pub tenure_extend_cost_threshold: Option<u64>,
     pub block_rejection_timeout_steps: Option<HashMap<String, u64>>,
+    pub consider_tx_packages: Option<bool>,
 }
 
 impl MinerConfigFile {
@@ -2792,7 +2885,8 @@ impl MinerConfigFile {
                 } else{
                     miner_default_config.block_rejection_timeout_steps
                 }
-            }
+            },
+            consider_tx_packages: self.consider_tx_packages.unwrap_or(miner_default_config.consider_tx_packages),
         })
     }
 }
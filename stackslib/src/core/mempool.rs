@@ -15,7 +15,7 @@
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::cmp::{self, Ordering};
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, BinaryHeap, HashMap, HashSet, VecDeque};
 use std::hash::Hasher;
 use std::io::{Read, Write};
 use std::ops::{Deref, DerefMut};
@@ -53,12 +53,13 @@ use crate::chainstate::stacks::index::Error as MarfError;
 use crate::chainstate::stacks::miner::TransactionEvent;
 use crate::chainstate::stacks::{
     Error as ChainstateError, StacksBlock, StacksMicroblock, StacksTransaction, TransactionPayload,
+    MAX_BLOCK_LEN,
 };
 use crate::clarity_vm::clarity::ClarityConnection;
 use crate::core::{
     ExecutionCost, StacksEpochId, FIRST_BURNCHAIN_CONSENSUS_HASH, FIRST_STACKS_BLOCK_HASH,
 };
-use crate::cost_estimates::metrics::{CostMetric, UnitMetric};
+use crate::cost_estimates::metrics::{CostMetric, UnitMetric, PROPORTION_RESOLUTION};
 use crate::cost_estimates::{CostEstimator, EstimatorError, UnitEstimator};
 use crate::monitoring::increment_stx_mempool_gc;
 use crate::net::api::postblock_proposal::{BlockValidateOk, BlockValidateReject};
@@ -94,6 +95,17 @@ pub const BLOOM_COUNTER_DEPTH: usize = 2;
 pub const DEFAULT_BLACKLIST_TIMEOUT: u64 = 24 * 60 * 60 * 2;
 pub const DEFAULT_BLACKLIST_MAX_SIZE: u64 = 134217728; // 2**27 -- the blacklist table can reach at most 4GB at 128 bytes per record
 
+// how many dropped and replaced transactions will the mempool remember the fate of?
+pub const DEFAULT_TX_HISTORY_MAX_SIZE: u64 = 65536;
+
+// how long is a summary of the mempool served before the mempool is summarized again?
+// summarizing the mempool reads every transaction in it.
+pub const MEMPOOL_STATS_MAX_AGE_SECS: u64 = 60;
+
+// how many transactions will a full mempool look through to make room for a new one?  this bounds
+// the work a single submission can cause while the mempool is locked.
+pub const MEMPOOL_EVICTION_SCAN_LIMIT: u64 = 1024;
+
 // maximum many tx tags we'll send before sending a bloom filter instead.
 // The parameter choice here is due to performance -- calculating a tag set can be slower than just
 // loading the bloom filter, even though the bloom filter is larger.
@@ -349,12 +361,14 @@ impl MemPoolAdmitter {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub enum MemPoolDropReason {
     REPLACE_ACROSS_FORK,
     REPLACE_BY_FEE,
     STALE_COLLECT,
     TOO_EXPENSIVE,
     PROBLEMATIC,
+    MEMPOOL_FULL,
 }
 
 pub struct ConsiderTransaction {
@@ -380,6 +394,23 @@ impl std::fmt::Display for MemPoolDropReason {
             MemPoolDropReason::REPLACE_ACROSS_FORK => write!(f, "ReplaceAcrossFork"),
             MemPoolDropReason::REPLACE_BY_FEE => write!(f, "ReplaceByFee"),
             MemPoolDropReason::PROBLEMATIC => write!(f, "Problematic"),
+            MemPoolDropReason::MEMPOOL_FULL => write!(f, "MempoolFull"),
+        }
+    }
+}
+
+impl std::str::FromStr for MemPoolDropReason {
+    type Err = db_error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "StaleGarbageCollect" => Ok(MemPoolDropReason::STALE_COLLECT),
+            "TooExpensive" => Ok(MemPoolDropReason::TOO_EXPENSIVE),
+            "ReplaceAcrossFork" => Ok(MemPoolDropReason::REPLACE_ACROSS_FORK),
+            "ReplaceByFee" => Ok(MemPoolDropReason::REPLACE_BY_FEE),
+            "Problematic" => Ok(MemPoolDropReason::PROBLEMATIC),
+            "MempoolFull" => Ok(MemPoolDropReason::MEMPOOL_FULL),
+            _ => Err(db_error::ParseError),
         }
     }
 }
@@ -440,6 +471,50 @@ pub struct MemPoolTxInfoPartial {
     pub sponsor_nonce: u64,
 }
 
+impl MemPoolTxInfoPartial {
+    /// The accounts whose nonces this transaction uses, with the nonce it uses of each
+    fn accounts(&self) -> Vec<(&StacksAddress, u64)> {
+        let mut accounts = vec![(&self.origin_address, self.origin_nonce)];
+        if self.sponsor_address != self.origin_address {
+            accounts.push((&self.sponsor_address, self.sponsor_nonce));
+        }
+        accounts
+    }
+}
+
+/// Tells which mempool transactions have been mined, i.e. use a nonce below that of their origin
+/// or sponsor at the chain tip, as reckoned by `account_nonce`.  Mined transactions stay in the
+/// mempool until they are garbage-collected.  Each account's nonce is looked up only once.
+struct MinedTxFilter<F> {
+    account_nonce: F,
+    account_nonces: HashMap<StacksAddress, u64>,
+}
+
+impl<F> MinedTxFilter<F>
+where
+    F: FnMut(&StacksAddress) -> u64,
+{
+    fn new(account_nonce: F) -> Self {
+        Self {
+            account_nonce,
+            account_nonces: HashMap::new(),
+        }
+    }
+
+    fn is_mined(&mut self, tx: &MemPoolTxInfoPartial) -> bool {
+        let Self {
+            account_nonce,
+            account_nonces,
+        } = self;
+        tx.accounts().into_iter().any(|(address, nonce)| {
+            let account_nonce = *account_nonces
+                .entry(address.clone())
+                .or_insert_with(|| account_nonce(address));
+            nonce < account_nonce
+        })
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct MemPoolTxMetadata {
     pub txid: Txid,
@@ -481,6 +556,465 @@ impl MemPoolTxMetadata {
     }
 }
 
+/// A transaction waiting in the mempool on one of an account's nonces
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemPoolPendingTx {
+    pub txid: Txid,
+    /// The account's nonce which this transaction uses
+    pub nonce: u64,
+    /// Whether the account is the transaction's origin, rather than its sponsor
+    pub is_origin: bool,
+    pub tx_fee: u64,
+    pub fee_rate: Option<f64>,
+}
+
+/// A transaction which left the mempool without being mined
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemPoolTxHistoryEntry {
+    pub txid: Txid,
+    pub origin_address: StacksAddress,
+    pub origin_nonce: u64,
+    pub reason: MemPoolDropReason,
+    /// The transaction which took its place, if it was replaced
+    pub replaced_by: Option<Txid>,
+    pub drop_time: u64,
+}
+
+/// The transactions to remove from a full mempool to make room for a new one
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MemPoolEviction {
+    /// Pending transactions which pay less than the new one, dropped as `MempoolFull`
+    pub evicted: Vec<Txid>,
+    /// Transactions which were mined, and are garbage-collected early
+    pub mined: Vec<Txid>,
+}
+
+/// Where a pending transaction stands among the rest of the mempool.
+/// Ranks start at 1, for the best-paying transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemPoolTxRank {
+    /// Rank by fee paid per byte
+    pub fee_per_byte_rank: u64,
+    /// Rank by estimated fee rate, among the transactions which have one
+    pub fee_rate_rank: Option<u64>,
+    pub fee_rate: Option<f64>,
+}
+
+/// The transactions waiting in the mempool on an account's nonces, in nonce order
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemPoolNonceChain {
+    /// The account's nonce at the chain tip
+    pub account_nonce: u64,
+    pub txs: Vec<MemPoolPendingTx>,
+}
+
+impl MemPoolNonceChain {
+    /// The lowest nonce, from the account's nonce up, which no pending transaction uses.
+    /// This is the nonce the account's next transaction should have.
+    pub fn next_nonce(&self) -> u64 {
+        let mut next_nonce = self.account_nonce;
+        for tx in self.txs.iter() {
+            if tx.nonce == next_nonce {
+                next_nonce += 1;
+            } else if tx.nonce > next_nonce {
+                break;
+            }
+        }
+        next_nonce
+    }
+
+    /// The missing nonce which keeps some of the pending transactions from being mined, if any
+    pub fn first_gap(&self) -> Option<u64> {
+        let next_nonce = self.next_nonce();
+        self.txs
+            .iter()
+            .any(|tx| tx.nonce > next_nonce)
+            .then_some(next_nonce)
+    }
+
+    /// Can a pending transaction with this nonce be mined, once those before it are?
+    pub fn is_executable(&self, nonce: u64) -> bool {
+        self.account_nonce <= nonce && nonce < self.next_nonce()
+    }
+
+    /// The aggregate fee rate of the pending transactions from the account's nonce up to and
+    ///  including `nonce` -- the package a miner must include to include the transaction with
+    ///  `nonce`.  None if the package can't be mined, or some of its fee rates are unknown.
+    pub fn package_fee_rate(&self, nonce: u64) -> Option<f64> {
+        if !self.is_executable(nonce) {
+            return None;
+        }
+        let mut fees = 0u64;
+        let mut weight = 0.0;
+        for tx in self.txs.iter().filter(|tx| tx.nonce <= nonce) {
+            weight += package_weight(tx.tx_fee, tx.fee_rate?)?;
+            fees = fees.saturating_add(tx.tx_fee);
+        }
+        Some(fees as f64 / weight)
+    }
+}
+
+/// Transactions in the mempool whose fee rates fall in one bucket of a histogram
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemPoolFeeRateBucket {
+    /// The lowest fee rate in the bucket.  Buckets start at 0, 1, 2, 5, 10, 20, 50, and so on.
+    pub min_fee_rate: u64,
+    pub tx_count: u64,
+    pub total_bytes: u64,
+    /// The estimated number of blocks needed to mine the transactions in this bucket and in
+    ///  all of the buckets paying more
+    pub blocks_to_clear: u64,
+}
+
+/// A histogram of the transactions in the mempool by fee rate, highest bucket first
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct MemPoolFeeRateHistogram {
+    pub buckets: Vec<MemPoolFeeRateBucket>,
+    /// Transactions which have no fee rate yet, and so are in no bucket
+    pub unestimated_tx_count: u64,
+}
+
+/// The number of transactions in the mempool of each `MemPoolWalkTxTypes`
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct MemPoolTxTypeCounts {
+    pub token_transfer: u64,
+    pub smart_contract: u64,
+    pub contract_call: u64,
+    /// Transactions of any other type
+    pub other: u64,
+}
+
+/// Histograms of the transactions in the mempool by fee per unit of each dimension of the block
+/// limit, where a block holds `PROPORTION_RESOLUTION` units of each.  A transaction estimated to
+/// use none of a dimension is in none of its buckets.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct MemPoolCostHistograms {
+    pub runtime: MemPoolFeeRateHistogram,
+    pub read_count: MemPoolFeeRateHistogram,
+    pub read_length: MemPoolFeeRateHistogram,
+    pub write_count: MemPoolFeeRateHistogram,
+    pub write_length: MemPoolFeeRateHistogram,
+}
+
+/// A summary of the transactions in the mempool which have not been mined
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct MemPoolStats {
+    /// When the mempool was summarized, in seconds since the epoch
+    pub timestamp: u64,
+    pub tx_count: u64,
+    pub total_bytes: u64,
+    /// How long ago the oldest transaction was received, in seconds
+    pub oldest_tx_age_secs: Option<u64>,
+    pub tx_types: MemPoolTxTypeCounts,
+    /// Transactions by fee per byte, where a block holds `MAX_BLOCK_LEN` bytes
+    pub fee_per_byte: MemPoolFeeRateHistogram,
+    /// Transactions by the fee rates of the mempool's cost metric, where a block holds as much
+    ///  as the metric scores a full block
+    pub fee_rate: MemPoolFeeRateHistogram,
+    /// Transactions by fee per unit of each dimension of their estimated execution cost
+    pub fee_rate_by_cost: MemPoolCostHistograms,
+}
+
+/// The lower bound of the histogram bucket holding `fee_rate`
+fn fee_rate_bucket(fee_rate: f64) -> u64 {
+    // also catches NaN
+    if !(fee_rate >= 1.0) {
+        return 0;
+    }
+    // saturates for rates too high for a u64
+    let fee_rate = fee_rate as u64;
+    let mut decade: u64 = 1;
+    while let Some(next_decade) = decade.checked_mul(10).filter(|next| *next <= fee_rate) {
+        decade = next_decade;
+    }
+    let step = match fee_rate / decade {
+        0..=1 => 1,
+        2..=4 => 2,
+        _ => 5,
+    };
+    step * decade
+}
+
+/// Accumulates a `MemPoolFeeRateHistogram`, weighing each transaction by how much of a block it
+/// takes up
+#[derive(Default)]
+struct FeeRateHistogramBuilder {
+    /// (tx count, total bytes, total weight), by bucket
+    buckets: BTreeMap<u64, (u64, u64, f64)>,
+    unestimated_tx_count: u64,
+}
+
+impl FeeRateHistogramBuilder {
+    fn add(&mut self, fee_rate: Option<f64>, length: u64, weight: f64) {
+        let Some(fee_rate) = fee_rate else {
+            self.unestimated_tx_count += 1;
+            return;
+        };
+        let bucket = self
+            .buckets
+            .entry(fee_rate_bucket(fee_rate))
+            .or_insert((0, 0, 0.0));
+        bucket.0 += 1;
+        bucket.1 = bucket.1.saturating_add(length);
+        bucket.2 += weight;
+    }
+
+    /// Finish the histogram, given the total weight a block can hold
+    fn build(self, block_capacity: f64) -> MemPoolFeeRateHistogram {
+        let block_capacity = block_capacity.max(1.0);
+        let mut total_weight = 0.0;
+        let buckets = self
+            .buckets
+            .into_iter()
+            .rev()
+            .map(|(min_fee_rate, (tx_count, total_bytes, weight))| {
+                total_weight += weight;
+                MemPoolFeeRateBucket {
+                    min_fee_rate,
+                    tx_count,
+                    total_bytes,
+                    blocks_to_clear: (total_weight / block_capacity).ceil() as u64,
+                }
+            })
+            .collect();
+        MemPoolFeeRateHistogram {
+            buckets,
+            unestimated_tx_count: self.unestimated_tx_count,
+        }
+    }
+}
+
+/// The shares of each dimension of `block_limit` which `cost` takes up, in
+/// `PROPORTION_RESOLUTION`ths, in the order of `MemPoolCostHistograms`.  None for a dimension
+/// which it uses none of, or which the block limit has none of.
+fn cost_shares(cost: &ExecutionCost, block_limit: &ExecutionCost) -> [Option<f64>; 5] {
+    let share = |used: u64, limit: u64| {
+        (used > 0 && limit > 0).then(|| used as f64 * PROPORTION_RESOLUTION as f64 / limit as f64)
+    };
+    [
+        share(cost.runtime, block_limit.runtime),
+        share(cost.read_count, block_limit.read_count),
+        share(cost.read_length, block_limit.read_length),
+        share(cost.write_count, block_limit.write_count),
+        share(cost.write_length, block_limit.write_length),
+    ]
+}
+
+/// The weight of a transaction in a package, i.e. its fee over its fee rate.
+/// None for free transactions, which can't be weighed this way.
+fn package_weight(tx_fee: u64, fee_rate: f64) -> Option<f64> {
+    (tx_fee > 0 && fee_rate > 0.0).then(|| tx_fee as f64 / fee_rate)
+}
+
+/// Score the transactions pending on one account's nonces, given as (nonce, fee, fee rate) in
+/// nonce order, for a package-aware mempool walk.
+///
+/// Only the run of transactions on consecutive nonces from the account's nonce at the chain tip
+/// can be mined, so only they are scored, and the scores are returned in their order.  A
+/// transaction's package is it and up to `MAXIMUM_MEMPOOL_TX_CHAINING - 1` of the transactions
+/// which use the account's following nonces.  It scores the best aggregate fee rate of any such
+/// package, so a low-fee transaction is lifted by the higher-fee ones which depend on it
+/// (child-pays-for-parent), capped by the score of its predecessor, so a package is walked in
+/// nonce order.
+fn package_scores(account_nonce: u64, chain: &[(u64, u64, Option<f64>)]) -> Vec<f64> {
+    let max_package_len = usize::try_from(MAXIMUM_MEMPOOL_TX_CHAINING)
+        .expect("FATAL: chaining limit does not fit into usize");
+    // a package can't span a missing nonce
+    let run_len = chain
+        .iter()
+        .zip(account_nonce..)
+        .take_while(|((nonce, ..), expected_nonce)| nonce == expected_nonce)
+        .count();
+    let run = &chain[..run_len];
+
+    let mut scores = Vec::with_capacity(run.len());
+    let mut cap = f64::INFINITY;
+    for k in 0..run.len() {
+        let mut best = run[k].2.unwrap_or(0.0);
+        let mut package_fees = 0u64;
+        let mut package_cost = 0.0;
+        for (_, fee, fee_rate) in run[k..].iter().take(max_package_len) {
+            let Some(weight) = fee_rate.and_then(|rate| package_weight(*fee, rate)) else {
+                break;
+            };
+            package_fees = package_fees.saturating_add(*fee);
+            package_cost += weight;
+            best = best.max(package_fees as f64 / package_cost);
+        }
+        cap = cap.min(best);
+        scores.push(cap);
+    }
+    scores
+}
+
+/// A candidate in a package-aware mempool walk.  Candidates are ordered by package score, and
+/// then by lowest nonces.
+struct PackageCandidate {
+    score: f64,
+    candidate: MemPoolTxInfoPartial,
+}
+
+impl Ord for PackageCandidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (ours, theirs) = (&self.candidate, &other.candidate);
+        self.score
+            .total_cmp(&other.score)
+            .then_with(|| theirs.origin_nonce.cmp(&ours.origin_nonce))
+            .then_with(|| theirs.sponsor_nonce.cmp(&ours.sponsor_nonce))
+    }
+}
+
+impl PartialOrd for PackageCandidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for PackageCandidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for PackageCandidate {}
+
+/// Orders the transactions which have a fee rate by their package scores (see `package_scores`)
+/// during a mempool walk.
+///
+/// Scores are computed as the walk goes: an account's pending transactions are loaded and scored
+/// only once the fee-rate order reaches one of them.  No package scores more than the best fee
+/// rate in it, so once the fee-rate order has reached rate `r`, no account which is yet to be
+/// loaded can score more than `r`, and any candidate already scored at `r` or more can be walked.
+struct PackageCandidates {
+    /// Scored candidates which are yet to be walked
+    scored: BinaryHeap<PackageCandidate>,
+    /// The next transaction in fee-rate order, whose fee rate bounds the scores yet to be computed
+    lookahead: Option<MemPoolTxInfoPartial>,
+    /// Whether the fee-rate order has been read to the end
+    exhausted: bool,
+    /// Accounts whose pending transactions have been scored
+    loaded_accounts: HashSet<StacksAddress>,
+    /// Transactions which have been scored as part of an account's package
+    packaged_txids: HashSet<Txid>,
+    /// Transactions which have been walked
+    walked_txids: HashSet<Txid>,
+}
+
+impl PackageCandidates {
+    fn new() -> Self {
+        Self {
+            scored: BinaryHeap::new(),
+            lookahead: None,
+            exhausted: false,
+            loaded_accounts: HashSet::new(),
+            packaged_txids: HashSet::new(),
+            walked_txids: HashSet::new(),
+        }
+    }
+
+    /// Get the next candidate to walk.  `fee_iterator` yields the transactions which have a fee
+    /// rate, in fee-rate order, and `account_nonce` gets an account's nonce at the chain tip.
+    fn next<F>(
+        &mut self,
+        conn: &DBConn,
+        fee_iterator: &mut Rows,
+        mut account_nonce: F,
+    ) -> Result<Option<MemPoolTxInfoPartial>, db_error>
+    where
+        F: FnMut(&StacksAddress) -> u64,
+    {
+        loop {
+            if self.lookahead.is_none() && !self.exhausted {
+                self.lookahead = fee_iterator
+                    .next()
+                    .map_err(Error::SqliteError)?
+                    .map(MemPoolTxInfoPartial::from_row)
+                    .transpose()?;
+                self.exhausted = self.lookahead.is_none();
+            }
+            let bound = self
+                .lookahead
+                .as_ref()
+                .and_then(|tx| tx.fee_rate)
+                .unwrap_or(f64::NEG_INFINITY);
+            if let Some(next) = self.scored.peek() {
+                if next.score >= bound {
+                    let next = self.scored.pop().expect("FATAL: peeked candidate is gone");
+                    // a sponsored transaction is in two accounts' packages, and is walked at the
+                    // better of its scores
+                    if self.walked_txids.insert(next.candidate.txid.clone()) {
+                        return Ok(Some(next.candidate));
+                    }
+                    continue;
+                }
+            }
+
+            let Some(tx) = self.lookahead.take() else {
+                return Ok(None);
+            };
+            for address in [&tx.origin_address, &tx.sponsor_address] {
+                if self.loaded_accounts.insert(address.clone()) {
+                    let nonce = account_nonce(address);
+                    self.load_account(conn, address, nonce)?;
+                }
+            }
+            // a transaction which can't be mined yet is walked at its own fee rate
+            if !self.packaged_txids.contains(&tx.txid) {
+                self.scored.push(PackageCandidate {
+                    score: tx.fee_rate.unwrap_or(0.0),
+                    candidate: tx,
+                });
+            }
+        }
+    }
+
+    /// Score the transactions pending on `address`'s nonces, from its nonce at the chain tip up.
+    fn load_account(
+        &mut self,
+        conn: &DBConn,
+        address: &StacksAddress,
+        account_nonce: u64,
+    ) -> Result<(), db_error> {
+        let sql = "
+            SELECT txid, origin_nonce, origin_address, sponsor_nonce, sponsor_address, fee_rate, tx_fee,
+                origin_nonce AS nonce
+            FROM mempool
+            WHERE origin_address = ?1 AND origin_nonce >= ?2
+            UNION ALL
+            SELECT txid, origin_nonce, origin_address, sponsor_nonce, sponsor_address, fee_rate, tx_fee,
+                sponsor_nonce AS nonce
+            FROM mempool
+            WHERE sponsor_address = ?1 AND origin_address != ?1 AND sponsor_nonce >= ?2
+            ORDER BY nonce ASC
+            ";
+        let args = params![address.to_string(), u64_to_sql(account_nonce)?];
+        let mut stmt = conn.prepare(sql).map_err(Error::SqliteError)?;
+        let mut rows = stmt.query(args).map_err(Error::SqliteError)?;
+        let mut chain = vec![];
+        let mut candidates = vec![];
+        while let Some(row) = rows.next().map_err(Error::SqliteError)? {
+            let candidate = MemPoolTxInfoPartial::from_row(row)?;
+            chain.push((
+                u64::from_column(row, "nonce")?,
+                u64::from_column(row, "tx_fee")?,
+                candidate.fee_rate,
+            ));
+            candidates.push(candidate);
+        }
+
+        let scores = package_scores(account_nonce, &chain);
+        for (candidate, score) in candidates.into_iter().zip(scores) {
+            // transactions without a fee rate are walked separately
+            if candidate.fee_rate.is_none() {
+                continue;
+            }
+            self.packaged_txids.insert(candidate.txid.clone());
+            self.scored.push(PackageCandidate { score, candidate });
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum MemPoolWalkTxTypes {
     TokenTransfer,
@@ -512,6 +1046,16 @@ impl MemPoolWalkTxTypes {
     pub fn only(selected: &[MemPoolWalkTxTypes]) -> HashSet<MemPoolWalkTxTypes> {
         selected.iter().copied().collect()
     }
+
+    /// The type of a transaction with this payload, if it is one a mempool walk filters on
+    pub fn from_payload(payload: &TransactionPayload) -> Option<MemPoolWalkTxTypes> {
+        match payload {
+            TransactionPayload::TokenTransfer(..) => Some(MemPoolWalkTxTypes::TokenTransfer),
+            TransactionPayload::SmartContract(..) => Some(MemPoolWalkTxTypes::SmartContract),
+            TransactionPayload::ContractCall(..) => Some(MemPoolWalkTxTypes::ContractCall),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -535,6 +1079,9 @@ pub struct MemPoolWalkSettings {
     /// What percentage of the remaining cost limit should we consume before stopping the walk
     /// None means we consume the entire cost limit ASAP
     pub tenure_cost_limit_per_block_percentage: Option<u8>,
+    /// Whether to walk transactions with a fee rate in order of the aggregate fee rate of the
+    /// nonce chains they start, rather than of their own fee rate (see `package_scores`)
+    pub consider_packages: bool,
 }
 
 impl Default for MemPoolWalkSettings {
@@ -547,6 +1094,7 @@ impl Default for MemPoolWalkSettings {
             txs_to_consider: MemPoolWalkTxTypes::all(),
             filter_origins: HashSet::new(),
             tenure_cost_limit_per_block_percentage: None,
+            consider_packages: false,
         }
     }
 }
@@ -560,6 +1108,7 @@ impl MemPoolWalkSettings {
             txs_to_consider: MemPoolWalkTxTypes::all(),
             filter_origins: HashSet::new(),
             tenure_cost_limit_per_block_percentage: None,
+            consider_packages: false,
         }
     }
 }
@@ -644,6 +1193,45 @@ impl FromRow<MemPoolTxInfoPartial> for MemPoolTxInfoPartial {
     }
 }
 
+impl FromRow<MemPoolTxHistoryEntry> for MemPoolTxHistoryEntry {
+    fn from_row(row: &Row) -> Result<MemPoolTxHistoryEntry, db_error> {
+        let txid = Txid::from_column(row, "txid")?;
+        let origin_address = StacksAddress::from_column(row, "origin_address")?;
+        let origin_nonce = u64::from_column(row, "origin_nonce")?;
+        let reason_str: String = row.get("reason")?;
+        let reason = reason_str.parse()?;
+        let replaced_by: Option<Txid> = row.get("replaced_by")?;
+        let drop_time = u64::from_column(row, "drop_time")?;
+
+        Ok(MemPoolTxHistoryEntry {
+            txid,
+            origin_address,
+            origin_nonce,
+            reason,
+            replaced_by,
+            drop_time,
+        })
+    }
+}
+
+impl FromRow<MemPoolPendingTx> for MemPoolPendingTx {
+    fn from_row(row: &Row) -> Result<MemPoolPendingTx, db_error> {
+        let txid = Txid::from_column(row, "txid")?;
+        let nonce = u64::from_column(row, "nonce")?;
+        let is_origin: bool = row.get("is_origin")?;
+        let tx_fee = u64::from_column(row, "tx_fee")?;
+        let fee_rate: Option<f64> = row.get("fee_rate")?;
+
+        Ok(MemPoolPendingTx {
+            txid,
+            nonce,
+            is_origin,
+            tx_fee,
+            fee_rate,
+        })
+    }
+}
+
 impl FromRow<(u64, u64)> for (u64, u64) {
     fn from_row(row: &Row) -> Result<(u64, u64), db_error> {
         let t1: i64 = row.get_unwrap(0);
@@ -803,6 +1391,70 @@ const MEMPOOL_SCHEMA_7_TIME_ESTIMATES: &[&str] = &[
     "#,
 ];
 
+const MEMPOOL_SCHEMA_8_TX_HISTORY: &[&str] = &[
+    r#"
+    -- Transactions which left the mempool without being mined, and why.
+    -- `drop_time` indicates when the entry was created. This is used to bound the size of the table.
+    CREATE TABLE IF NOT EXISTS tx_history(
+        txid TEXT PRIMARY KEY NOT NULL,
+        origin_address TEXT NOT NULL,
+        origin_nonce INTEGER NOT NULL,
+        -- the `MemPoolDropReason`
+        reason TEXT NOT NULL,
+        -- the transaction which replaced this one, if any
+        replaced_by TEXT,
+        drop_time INTEGER NOT NULL
+    );
+    "#,
+    r#"
+    INSERT INTO schema_version (version) VALUES (8)
+    "#,
+];
+
+const MEMPOOL_SCHEMA_9_SIZE: &[&str] = &[
+    r#"
+    -- The total length of the transactions in the mempool
+    CREATE TABLE IF NOT EXISTS mempool_size(
+        size INTEGER NOT NULL
+    );
+    "#,
+    r#"
+    -- Maintain the total length of the mempool's transactions
+    CREATE TRIGGER IF NOT EXISTS mempool_size_inc
+    AFTER INSERT ON mempool
+    BEGIN
+        UPDATE mempool_size SET size = size + NEW.length;
+    END
+    "#,
+    r#"
+    -- `INSERT OR REPLACE` does not fire delete triggers for the rows it replaces, so take their
+    -- lengths off before inserting
+    CREATE TRIGGER IF NOT EXISTS mempool_size_replace
+    BEFORE INSERT ON mempool
+    BEGIN
+        UPDATE mempool_size SET size = size - (
+            SELECT IFNULL(SUM(length), 0) FROM mempool
+            WHERE txid = NEW.txid
+                OR (origin_address = NEW.origin_address AND origin_nonce = NEW.origin_nonce)
+                OR (sponsor_address = NEW.sponsor_address AND sponsor_nonce = NEW.sponsor_nonce)
+        );
+    END
+    "#,
+    r#"
+    CREATE TRIGGER IF NOT EXISTS mempool_size_dec
+    AFTER DELETE ON mempool
+    BEGIN
+        UPDATE mempool_size SET size = size - OLD.length;
+    END
+    "#,
+    r#"
+    INSERT INTO mempool_size (size) SELECT IFNULL(SUM(length), 0) FROM mempool
+    "#,
+    r#"
+    INSERT INTO schema_version (version) VALUES (9)
+    "#,
+];
+
 const MEMPOOL_INDEXES: &[&str] = &[
     "CREATE INDEX IF NOT EXISTS by_txid ON mempool(txid);",
     "CREATE INDEX IF NOT EXISTS by_height ON mempool(height);",
@@ -810,11 +1462,13 @@ const MEMPOOL_INDEXES: &[&str] = &[
     "CREATE INDEX IF NOT EXISTS by_sponsor ON mempool(sponsor_address, sponsor_nonce);",
     "CREATE INDEX IF NOT EXISTS by_origin ON mempool(origin_address, origin_nonce);",
     "CREATE INDEX IF NOT EXISTS by_timestamp ON mempool(accept_time);",
+    "CREATE INDEX IF NOT EXISTS by_fee_rate ON mempool(fee_rate);",
     "CREATE INDEX IF NOT EXISTS by_chaintip ON mempool(consensus_hash,block_header_hash);",
     "CREATE INDEX IF NOT EXISTS fee_by_txid ON fee_estimates(txid);",
     "CREATE INDEX IF NOT EXISTS by_ordered_hashed_txid ON randomized_txids(hashed_txid ASC);",
     "CREATE INDEX IF NOT EXISTS by_hashed_txid ON randomized_txids(txid,hashed_txid);",
     "CREATE INDEX IF NOT EXISTS by_arrival_time_desc ON tx_blacklist(arrival_time DESC);",
+    "CREATE INDEX IF NOT EXISTS by_drop_time_desc ON tx_history(drop_time DESC);",
 ];
 
 pub struct MemPoolDB {
@@ -827,6 +1481,16 @@ pub struct MemPoolDB {
     metric: Box<dyn CostMetric>,
     pub blacklist_timeout: u64,
     pub blacklist_max_size: u64,
+    /// If set, the most bytes of transactions the mempool holds.  Once it is full, a new
+    /// transaction gets in only by evicting ones which pay a lower fee rate.
+    pub max_size_bytes: Option<u64>,
+    /// If set, the most pending transactions an address may have in the mempool, as either
+    /// origin or sponsor
+    pub max_txs_per_address: Option<u64>,
+    /// The most dropped and replaced transactions to remember the fate of
+    pub tx_history_max_size: u64,
+    /// The last summary of the mempool, served until it is `MEMPOOL_STATS_MAX_AGE_SECS` old
+    stats_snapshot: Option<MemPoolStats>,
 }
 
 pub struct MemPoolTx<'a> {
@@ -1291,6 +1955,12 @@ impl MemPoolDB {
                     MemPoolDB::instantiate_schema_7(tx)?;
                 }
                 7 => {
+                    MemPoolDB::instantiate_tx_history(tx)?;
+                }
+                8 => {
+                    MemPoolDB::instantiate_mempool_size(tx)?;
+                }
+                9 => {
                     break;
                 }
                 _ => {
@@ -1377,6 +2047,25 @@ impl MemPoolDB {
         Ok(())
     }
 
+    /// Add the dropped transaction history table
+    #[cfg_attr(test, mutants::skip)]
+    fn instantiate_tx_history(tx: &DBTx) -> Result<(), db_error> {
+        for sql_exec in MEMPOOL_SCHEMA_8_TX_HISTORY {
+            tx.execute_batch(sql_exec)?;
+        }
+
+        Ok(())
+    }
+
+    /// Add the running total of the mempool's size
+    fn instantiate_mempool_size(tx: &DBTx) -> Result<(), db_error> {
+        for sql_exec in MEMPOOL_SCHEMA_9_SIZE {
+            tx.execute_batch(sql_exec)?;
+        }
+
+        Ok(())
+    }
+
     #[cfg_attr(test, mutants::skip)]
     pub fn db_path(chainstate_root_path: &str) -> Result<String, db_error> {
         let mut path = PathBuf::from(chainstate_root_path);
@@ -1439,6 +2128,10 @@ impl MemPoolDB {
             metric,
             blacklist_timeout: DEFAULT_BLACKLIST_TIMEOUT,
             blacklist_max_size: DEFAULT_BLACKLIST_MAX_SIZE,
+            max_size_bytes: None,
+            max_txs_per_address: None,
+            tx_history_max_size: DEFAULT_TX_HISTORY_MAX_SIZE,
+            stats_snapshot: None,
         })
     }
 
@@ -1596,6 +2289,10 @@ impl MemPoolDB {
     /// were skipped on the first pass, but become valid after some lower
     /// fee-rate transactions are considered.
     ///
+    /// If `settings.consider_packages` is set, the fee-rate order is that of
+    /// each transaction's package score instead (see `package_scores`), so a
+    /// chain of transactions from one account is weighed as a whole.
+    ///
     /// The size of the candidate cache and the nonce cache are configurable
     /// in the settings struct. This method is interruptable -- in the
     /// `settings` struct, the caller may choose how long to spend iterating
@@ -1659,6 +2356,10 @@ impl MemPoolDB {
             .query(NO_PARAMS)
             .map_err(Error::SqliteError)?;
 
+        // when considering packages, the transactions with a fee rate are walked in order of their
+        // package scores instead
+        let mut package_candidates = settings.consider_packages.then(PackageCandidates::new);
+
         let stop_reason = loop {
             if start_time.elapsed().as_millis() > settings.max_walk_time_ms as u128 {
                 debug!("Mempool iteration deadline exceeded";
@@ -1680,23 +2381,44 @@ impl MemPoolDB {
                     // randomly selecting from either the null fee-rate transactions
                     // or those with fee-rate estimates.
                     let opt_tx = if start_with_no_estimate {
-                        null_iterator.next().map_err(Error::SqliteError)?
+                        null_iterator
+                            .next()
+                            .map_err(Error::SqliteError)?
+                            .map(MemPoolTxInfoPartial::from_row)
+                            .transpose()?
                     } else {
-                        fee_iterator.next().map_err(Error::SqliteError)?
+                        Self::next_fee_candidate(
+                            &self.db,
+                            &mut fee_iterator,
+                            package_candidates.as_mut(),
+                            &mut nonce_cache,
+                            clarity_tx,
+                            &mut retry_store,
+                            settings.nonce_cache_size,
+                        )?
                     };
                     match opt_tx {
-                        Some(row) => (MemPoolTxInfoPartial::from_row(row)?, start_with_no_estimate),
+                        Some(tx) => (tx, start_with_no_estimate),
                         None => {
                             // If the selected iterator is empty, check the other
                             match if start_with_no_estimate {
-                                fee_iterator.next().map_err(Error::SqliteError)?
+                                Self::next_fee_candidate(
+                                    &self.db,
+                                    &mut fee_iterator,
+                                    package_candidates.as_mut(),
+                                    &mut nonce_cache,
+                                    clarity_tx,
+                                    &mut retry_store,
+                                    settings.nonce_cache_size,
+                                )?
                             } else {
-                                null_iterator.next().map_err(Error::SqliteError)?
+                                null_iterator
+                                    .next()
+                                    .map_err(Error::SqliteError)?
+                                    .map(MemPoolTxInfoPartial::from_row)
+                                    .transpose()?
                             } {
-                                Some(row) => (
-                                    MemPoolTxInfoPartial::from_row(row)?,
-                                    !start_with_no_estimate,
-                                ),
+                                Some(tx) => (tx, !start_with_no_estimate),
                                 None => {
                                     debug!("No more transactions to consider in mempool");
                                     break MempoolIterationStopReason::NoMoreCandidates;
@@ -1781,26 +2503,13 @@ impl MemPoolDB {
                 }
             };
 
-            let (tx_type, do_consider) = match &tx_info.tx.payload {
-                TransactionPayload::TokenTransfer(..) => (
-                    "TokenTransfer".to_string(),
-                    settings
-                        .txs_to_consider
-                        .contains(&MemPoolWalkTxTypes::TokenTransfer),
-                ),
-                TransactionPayload::SmartContract(..) => (
-                    "SmartContract".to_string(),
-                    settings
-                        .txs_to_consider
-                        .contains(&MemPoolWalkTxTypes::SmartContract),
+            let walk_tx_type = MemPoolWalkTxTypes::from_payload(&tx_info.tx.payload);
+            let (tx_type, do_consider) = match walk_tx_type {
+                Some(tx_type) => (
+                    format!("{tx_type:?}"),
+                    settings.txs_to_consider.contains(&tx_type),
                 ),
-                TransactionPayload::ContractCall(..) => (
-                    "ContractCall".to_string(),
-                    settings
-                        .txs_to_consider
-                        .contains(&MemPoolWalkTxTypes::ContractCall),
-                ),
-                _ => ("".to_string(), true),
+                None => ("".to_string(), true),
             };
             if !do_consider {
                 debug!("Will skip mempool tx, since it does not have an acceptable type";
@@ -1920,6 +2629,34 @@ impl MemPoolDB {
         Ok((total_considered, stop_reason))
     }
 
+    /// Get the next transaction which has a fee rate to walk: from `fee_iterator` in fee-rate
+    /// order, or in package-score order if `package_candidates` is given, in which case account
+    /// nonces are read through `nonce_cache`.
+    fn next_fee_candidate<C: ClarityConnection>(
+        conn: &DBConn,
+        fee_iterator: &mut Rows,
+        package_candidates: Option<&mut PackageCandidates>,
+        nonce_cache: &mut NonceCache,
+        clarity_tx: &mut C,
+        retry_store: &mut HashMap<StacksAddress, u64>,
+        nonce_cache_size: u64,
+    ) -> Result<Option<MemPoolTxInfoPartial>, db_error> {
+        let Some(package_candidates) = package_candidates else {
+            return fee_iterator
+                .next()
+                .map_err(Error::SqliteError)?
+                .map(MemPoolTxInfoPartial::from_row)
+                .transpose();
+        };
+        package_candidates.next(conn, fee_iterator, |address| {
+            let (nonce, retry_store_nonce) = nonce_cache.get(address, clarity_tx, conn);
+            if retry_store_nonce {
+                Self::save_nonce_for_retry(retry_store, nonce_cache_size, address.clone(), nonce);
+            }
+            nonce
+        })
+    }
+
     pub fn conn(&self) -> &DBConn {
         &self.db
     }
@@ -1942,6 +2679,87 @@ impl MemPoolDB {
         query_row(conn, "SELECT * FROM mempool WHERE txid = ?1", params![txid])
     }
 
+    /// Get the time at which a transaction was accepted into the mempool, if it is still there
+    pub fn get_tx_accept_time(conn: &DBConn, txid: &Txid) -> Result<Option<u64>, db_error> {
+        query_row(
+            conn,
+            "SELECT accept_time FROM mempool WHERE txid = ?1",
+            params![txid],
+        )
+    }
+
+    /// Get the transactions accepted into the mempool after `since` which have an estimated fee
+    /// rate, as (txid, fee rate, accept time)
+    pub fn get_estimated_txs_since(
+        conn: &DBConn,
+        since: u64,
+    ) -> Result<Vec<(Txid, f64, u64)>, db_error> {
+        let sql = "SELECT txid, fee_rate, accept_time FROM mempool
+                   WHERE accept_time > ?1 AND fee_rate IS NOT NULL";
+        let mut stmt = conn.prepare(sql)?;
+        let mut rows = stmt.query(params![u64_to_sql(since)?])?;
+        let mut txs = vec![];
+        while let Some(row) = rows.next()? {
+            let txid = Txid::from_column(row, "txid")?;
+            let fee_rate: f64 = row.get("fee_rate")?;
+            let accept_time = u64::from_column(row, "accept_time")?;
+            txs.push((txid, fee_rate, accept_time));
+        }
+        Ok(txs)
+    }
+
+    /// Rank a pending transaction against the rest of the mempool, by fee per byte and by
+    /// estimated fee rate.  Transactions which have been mined, by `account_nonce`'s reckoning of
+    /// accounts' nonces at the chain tip, are not counted against it.
+    pub fn get_tx_rank<F>(
+        conn: &DBConn,
+        txid: &Txid,
+        account_nonce: F,
+    ) -> Result<Option<MemPoolTxRank>, db_error>
+    where
+        F: FnMut(&StacksAddress) -> u64,
+    {
+        let sql = "SELECT CAST(tx_fee AS REAL) / length, fee_rate FROM mempool WHERE txid = ?1";
+        let Some((fee_per_byte, fee_rate)) = conn
+            .query_row(sql, params![txid], |row| {
+                Ok((row.get::<_, f64>(0)?, row.get::<_, Option<f64>>(1)?))
+            })
+            .optional()?
+        else {
+            return Ok(None);
+        };
+
+        let mut mined_filter = MinedTxFilter::new(account_nonce);
+        let mut fee_per_byte_rank = 1;
+        let mut fee_rate_rank = 1;
+        let sql = "SELECT txid, origin_address, origin_nonce, sponsor_address, sponsor_nonce,
+                          fee_rate, CAST(tx_fee AS REAL) / length > ?1 AS pays_more_per_byte
+                   FROM mempool
+                   WHERE CAST(tx_fee AS REAL) / length > ?1 OR fee_rate > ?2";
+        let mut stmt = conn.prepare(sql)?;
+        let mut rows = stmt.query(params![fee_per_byte, fee_rate])?;
+        while let Some(row) = rows.next()? {
+            let other = MemPoolTxInfoPartial::from_row(row)?;
+            if mined_filter.is_mined(&other) {
+                continue;
+            }
+            let pays_more_per_byte: bool = row.get("pays_more_per_byte")?;
+            if pays_more_per_byte {
+                fee_per_byte_rank += 1;
+            }
+            if let (Some(other_rate), Some(fee_rate)) = (other.fee_rate, fee_rate) {
+                if other_rate > fee_rate {
+                    fee_rate_rank += 1;
+                }
+            }
+        }
+        Ok(Some(MemPoolTxRank {
+            fee_per_byte_rank,
+            fee_rate_rank: fee_rate.map(|_| fee_rate_rank),
+            fee_rate,
+        }))
+    }
+
     /// Get all transactions across all tips
     #[cfg(test)]
     pub fn get_all_txs(conn: &DBConn) -> Result<Vec<MemPoolTxInfo>, db_error> {
@@ -1983,6 +2801,419 @@ impl MemPoolDB {
         Ok(rows)
     }
 
+    /// Summarize the transactions in the mempool which have not been mined, by
+    /// `account_nonce`'s reckoning of accounts' nonces at the chain tip.  Blocks are taken to be
+    /// `block_limit` and `MAX_BLOCK_LEN` bytes big when estimating how many it takes to clear each
+    /// fee rate, and transactions' costs are estimated as of `epoch_id`.
+    pub fn get_stats<F>(
+        &self,
+        block_limit: &ExecutionCost,
+        epoch_id: &StacksEpochId,
+        account_nonce: F,
+    ) -> Result<MemPoolStats, db_error>
+    where
+        F: FnMut(&StacksAddress) -> u64,
+    {
+        let now = get_epoch_time_secs();
+        let mut stats = MemPoolStats {
+            timestamp: now,
+            ..MemPoolStats::default()
+        };
+        let mut fee_per_byte = FeeRateHistogramBuilder::default();
+        let mut fee_rate = FeeRateHistogramBuilder::default();
+        let mut fee_rate_by_cost: [FeeRateHistogramBuilder; 5] = Default::default();
+
+        let mut mined_filter = MinedTxFilter::new(account_nonce);
+        let sql = "SELECT txid, origin_address, origin_nonce, sponsor_address, sponsor_nonce,
+                          tx_fee, length, fee_rate, accept_time, tx
+                   FROM mempool";
+        let mut stmt = self.conn().prepare(sql)?;
+        let mut rows = stmt.query(NO_PARAMS)?;
+        while let Some(row) = rows.next()? {
+            if mined_filter.is_mined(&MemPoolTxInfoPartial::from_row(row)?) {
+                // waiting to be garbage-collected
+                continue;
+            }
+
+            let tx_fee = u64::from_column(row, "tx_fee")?;
+            let length = u64::from_column(row, "length")?;
+            let tx_fee_rate: Option<f64> = row.get("fee_rate")?;
+            let accept_time = u64::from_column(row, "accept_time")?;
+            let tx_bytes: Vec<u8> = row.get("tx")?;
+
+            stats.tx_count += 1;
+            stats.total_bytes = stats.total_bytes.saturating_add(length);
+            let age = now.saturating_sub(accept_time);
+            stats.oldest_tx_age_secs = Some(stats.oldest_tx_age_secs.unwrap_or(0).max(age));
+
+            let tx = StacksTransaction::consensus_deserialize(&mut &tx_bytes[..]).ok();
+            let tx_type = tx
+                .as_ref()
+                .and_then(|tx| MemPoolWalkTxTypes::from_payload(&tx.payload));
+            match tx_type {
+                Some(MemPoolWalkTxTypes::TokenTransfer) => stats.tx_types.token_transfer += 1,
+                Some(MemPoolWalkTxTypes::SmartContract) => stats.tx_types.smart_contract += 1,
+                Some(MemPoolWalkTxTypes::ContractCall) => stats.tx_types.contract_call += 1,
+                None => stats.tx_types.other += 1,
+            }
+
+            let length_f64 = length.max(1) as f64;
+            fee_per_byte.add(Some(tx_fee as f64 / length_f64), length, length as f64);
+            let weight = tx_fee_rate
+                .and_then(|rate| package_weight(tx_fee, rate))
+                .unwrap_or_else(|| self.metric.from_len(length) as f64);
+            fee_rate.add(tx_fee_rate, length, weight);
+
+            let cost = tx.and_then(|tx| {
+                self.cost_estimator
+                    .estimate_cost(&tx.payload, epoch_id)
+                    .ok()
+            });
+            match cost {
+                Some(cost) => {
+                    let shares = cost_shares(&cost, block_limit);
+                    for (histogram, share) in fee_rate_by_cost.iter_mut().zip(shares) {
+                        if let Some(share) = share {
+                            histogram.add(Some(tx_fee as f64 / share), length, share);
+                        }
+                    }
+                }
+                None => {
+                    for histogram in fee_rate_by_cost.iter_mut() {
+                        histogram.add(None, length, 0.0);
+                    }
+                }
+            }
+        }
+
+        let block_len = u64::from(MAX_BLOCK_LEN);
+        stats.fee_per_byte = fee_per_byte.build(block_len as f64);
+        let block_cost = self
+            .metric
+            .from_cost_and_len(block_limit, block_limit, block_len);
+        stats.fee_rate = fee_rate.build(block_cost as f64);
+        let [runtime, read_count, read_length, write_count, write_length] =
+            fee_rate_by_cost.map(|histogram| histogram.build(PROPORTION_RESOLUTION as f64));
+        stats.fee_rate_by_cost = MemPoolCostHistograms {
+            runtime,
+            read_count,
+            read_length,
+            write_count,
+            write_length,
+        };
+        Ok(stats)
+    }
+
+    /// Get a summary of the transactions in the mempool which have not been mined as of the
+    /// Stacks block `tip`.  Summarizing the mempool reads every transaction in it, so the last
+    /// summary is served until it is `MEMPOOL_STATS_MAX_AGE_SECS` old.  A fresh summary is also
+    /// reported to the monitoring gauges.
+    pub fn get_stats_snapshot(
+        &mut self,
+        chainstate: &mut StacksChainState,
+        sortdb: &SortitionDB,
+        tip: &StacksBlockId,
+        block_limit: &ExecutionCost,
+        epoch_id: &StacksEpochId,
+    ) -> Result<MemPoolStats, ChainstateError> {
+        let now = get_epoch_time_secs();
+        if let Some(stats) = self.stats_snapshot.as_ref() {
+            if now < stats.timestamp.saturating_add(MEMPOOL_STATS_MAX_AGE_SECS) {
+                return Ok(stats.clone());
+            }
+        }
+
+        let sort_handle = sortdb.index_handle_at_block(chainstate, tip)?;
+        let stats = chainstate.maybe_read_only_clarity_tx(&sort_handle, tip, |clarity_tx| {
+            self.get_stats(block_limit, epoch_id, |address| {
+                StacksChainState::get_nonce(clarity_tx, &address.clone().into())
+            })
+        })?;
+        let stats = match stats {
+            Some(stats) => stats?,
+            // accounts are taken to be unused if the tip's state is not available
+            None => self.get_stats(block_limit, epoch_id, |_| 0)?,
+        };
+        monitoring::set_mempool_stats(&stats);
+        self.stats_snapshot = Some(stats.clone());
+        Ok(stats)
+    }
+
+    /// Get the transactions pending on `address`'s nonces, as origin or sponsor, from its nonce
+    /// at the chain tip (`account_nonce`) up.
+    pub fn get_nonce_chain(
+        conn: &DBConn,
+        address: &StacksAddress,
+        account_nonce: u64,
+    ) -> Result<MemPoolNonceChain, db_error> {
+        let sql = "SELECT txid, origin_nonce AS nonce, 1 AS is_origin, tx_fee, fee_rate FROM mempool
+                   WHERE origin_address = ?1 AND origin_nonce >= ?2
+                   UNION ALL
+                   SELECT txid, sponsor_nonce AS nonce, 0 AS is_origin, tx_fee, fee_rate FROM mempool
+                   WHERE sponsor_address = ?1 AND origin_address != ?1 AND sponsor_nonce >= ?2
+                   ORDER BY nonce ASC, is_origin DESC";
+        let args = params![address.to_string(), u64_to_sql(account_nonce)?];
+        let txs = query_rows(conn, sql, args)?;
+        Ok(MemPoolNonceChain { account_nonce, txs })
+    }
+
+    /// Check that `tx` does not skip any of its origin's or sponsor's nonces, given their nonces
+    /// at the chain tip.  Every nonce from the account's up to the transaction's must be used by a
+    /// pending transaction, or else `tx` can't be mined until one arrives.
+    pub fn check_nonce_gaps(
+        conn: &DBConn,
+        tx: &StacksTransaction,
+        origin_account_nonce: u64,
+        sponsor_account_nonce: Option<u64>,
+    ) -> Result<(), MemPoolRejection> {
+        let mut accounts = vec![(
+            tx.origin_address(),
+            tx.get_origin_nonce(),
+            origin_account_nonce,
+            true,
+        )];
+        if let (Some(address), Some(nonce), Some(account_nonce)) = (
+            tx.sponsor_address(),
+            tx.get_sponsor_nonce(),
+            sponsor_account_nonce,
+        ) {
+            accounts.push((address, nonce, account_nonce, false));
+        }
+
+        for (address, nonce, account_nonce, is_origin) in accounts {
+            let next_nonce = Self::get_nonce_chain(conn, &address, account_nonce)?.next_nonce();
+            if nonce > next_nonce {
+                return Err(MemPoolRejection::NonceGap {
+                    missing_nonce: next_nonce,
+                    actual_nonce: nonce,
+                    principal: address.into(),
+                    is_origin,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that neither `tx`'s origin nor its sponsor already has `max_txs` transactions
+    /// pending in the mempool, given their nonces at the chain tip.  A transaction which replaces
+    /// a pending one takes no more room, so it always passes.
+    pub fn check_pending_tx_limit(
+        conn: &DBConn,
+        tx: &StacksTransaction,
+        origin_account_nonce: u64,
+        sponsor_account_nonce: Option<u64>,
+        max_txs: u64,
+    ) -> Result<(), MemPoolRejection> {
+        let mut accounts = vec![(
+            tx.origin_address(),
+            tx.get_origin_nonce(),
+            origin_account_nonce,
+            true,
+        )];
+        if let (Some(address), Some(nonce), Some(account_nonce)) = (
+            tx.sponsor_address(),
+            tx.get_sponsor_nonce(),
+            sponsor_account_nonce,
+        ) {
+            accounts.push((address, nonce, account_nonce, false));
+        }
+
+        for (address, nonce, account_nonce, is_origin) in accounts {
+            let chain = Self::get_nonce_chain(conn, &address, account_nonce)?;
+            let is_replacement = chain
+                .txs
+                .iter()
+                .any(|pending| pending.nonce == nonce && pending.is_origin == is_origin);
+            if !is_replacement && chain.txs.len() as u64 >= max_txs {
+                return Err(MemPoolRejection::TooManyPendingTxs {
+                    max_txs,
+                    principal: address.into(),
+                    is_origin,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Find the transactions to evict so that a new `length`-byte transaction with the estimated
+    /// `fee_rate` fits in a mempool of at most `max_size_bytes`.  `replaced` are the transactions
+    /// the new one replaces, whose room it takes over.
+    ///
+    /// Transactions which have been mined, by `account_nonce`'s reckoning of accounts' nonces at
+    /// the chain tip, stay in the mempool until garbage collection but take up room, so they are
+    /// removed first, whatever they paid.  Pending transactions with a lower estimated fee rate,
+    /// or none, are evicted lowest first, from the top of their accounts' nonce chains down, since
+    /// evicting any other would leave a gap which keeps the transactions above it from being
+    /// mined.
+    ///
+    /// Transactions are considered in fee-rate order, and at most `MEMPOOL_EVICTION_SCAN_LIMIT` of
+    /// them.  Fails with `MemPoolFull` if they would still not make enough room.
+    pub fn find_txs_to_evict<F>(
+        conn: &DBConn,
+        fee_rate: Option<f64>,
+        length: u64,
+        replaced: &[MemPoolTxMetadata],
+        max_size_bytes: u64,
+        account_nonce: F,
+    ) -> Result<MemPoolEviction, MemPoolRejection>
+    where
+        F: FnMut(&StacksAddress) -> u64,
+    {
+        let size = query_int(conn, "SELECT size FROM mempool_size", NO_PARAMS)?;
+        let size = replaced.iter().fold(size as u64, |size, replaced_tx| {
+            size.saturating_sub(replaced_tx.len)
+        });
+        let Some(excess) = size
+            .saturating_add(length)
+            .checked_sub(max_size_bytes)
+            .filter(|excess| *excess > 0)
+        else {
+            return Ok(MemPoolEviction::default());
+        };
+        // transactions without an estimate are the last a miner considers
+        let rate_of = |candidate: &MemPoolTxInfoPartial| -> f64 {
+            candidate.fee_rate.unwrap_or(f64::NEG_INFINITY)
+        };
+        // a new transaction without an estimate pays less than any other
+        let pays_less = |candidate: &MemPoolTxInfoPartial| -> bool {
+            fee_rate.is_some_and(|fee_rate| rate_of(candidate) < fee_rate)
+        };
+
+        let mut mined_filter = MinedTxFilter::new(account_nonce);
+
+        let sql = "SELECT txid, origin_nonce, origin_address, sponsor_nonce, sponsor_address, fee_rate, length
+                   FROM mempool
+                   ORDER BY fee_rate ASC
+                   LIMIT ?1";
+        let mut stmt = conn.prepare(sql).map_err(db_error::SqliteError)?;
+        let mut rows = stmt
+            .query(params![u64_to_sql(MEMPOOL_EVICTION_SCAN_LIMIT)?])
+            .map_err(db_error::SqliteError)?;
+        // the next transaction in fee-rate order
+        let mut lookahead = None;
+        let mut rows_done = false;
+        // transactions which were exposed at the top of a nonce chain by evictions
+        let mut exposed: Vec<(MemPoolTxInfoPartial, u64)> = vec![];
+
+        let mut eviction = MemPoolEviction::default();
+        let mut removed_txids = HashSet::new();
+        let mut freed = 0u64;
+        let mut scanned = 0u64;
+        loop {
+            if lookahead.is_none() && !rows_done {
+                lookahead = match rows.next().map_err(db_error::SqliteError)? {
+                    Some(row) => Some((
+                        MemPoolTxInfoPartial::from_row(row)?,
+                        u64::from_column(row, "length")?,
+                    )),
+                    None => None,
+                };
+                rows_done = lookahead.is_none();
+            }
+            let lowest_exposed = exposed
+                .iter()
+                .enumerate()
+                .min_by(|(_, (a, _)), (_, (b, _))| rate_of(a).total_cmp(&rate_of(b)))
+                .map(|(i, _)| i);
+            let take_exposed = match (lowest_exposed, lookahead.as_ref()) {
+                (Some(i), Some((next, _))) => rate_of(&exposed[i].0) < rate_of(next),
+                (Some(_), None) => true,
+                (None, _) => false,
+            };
+            let next = if take_exposed {
+                lowest_exposed.map(|i| exposed.swap_remove(i))
+            } else {
+                lookahead.take()
+            };
+            let Some((candidate, tx_len)) = next else {
+                return Err(MemPoolRejection::MemPoolFull);
+            };
+            scanned += 1;
+            if scanned > MEMPOOL_EVICTION_SCAN_LIMIT {
+                return Err(MemPoolRejection::MemPoolFull);
+            }
+
+            if removed_txids.contains(&candidate.txid)
+                || replaced
+                    .iter()
+                    .any(|replaced_tx| replaced_tx.txid == candidate.txid)
+            {
+                continue;
+            }
+            if mined_filter.is_mined(&candidate) {
+                eviction.mined.push(candidate.txid.clone());
+            } else if pays_less(&candidate)
+                && Self::is_nonce_chain_top(conn, &candidate, &removed_txids)?
+            {
+                eviction.evicted.push(candidate.txid.clone());
+                for (address, nonce) in candidate.accounts() {
+                    if let Some((below, below_len)) =
+                        Self::get_nonce_chain_predecessor(conn, address, nonce)?
+                    {
+                        if pays_less(&below) {
+                            exposed.push((below, below_len));
+                        }
+                    }
+                }
+            } else {
+                continue;
+            }
+
+            freed = freed.saturating_add(tx_len);
+            removed_txids.insert(candidate.txid);
+            if freed >= excess {
+                return Ok(eviction);
+            }
+        }
+    }
+
+    /// Is `candidate` at the top of its accounts' nonce chains, once the transactions in
+    /// `removed` are gone?
+    fn is_nonce_chain_top(
+        conn: &DBConn,
+        candidate: &MemPoolTxInfoPartial,
+        removed: &HashSet<Txid>,
+    ) -> Result<bool, db_error> {
+        let sql = "SELECT txid FROM mempool WHERE origin_address = ?1 AND origin_nonce > ?2
+                   UNION ALL
+                   SELECT txid FROM mempool WHERE sponsor_address = ?1 AND sponsor_nonce > ?2";
+        for (address, nonce) in candidate.accounts() {
+            let args = params![address.to_string(), u64_to_sql(nonce)?];
+            let above: Vec<Txid> = query_rows(conn, sql, args)?;
+            if above.iter().any(|txid| !removed.contains(txid)) {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Get the transaction, and its length, which uses the highest of `address`'s nonces below
+    /// `nonce`, as origin or sponsor.
+    fn get_nonce_chain_predecessor(
+        conn: &DBConn,
+        address: &StacksAddress,
+        nonce: u64,
+    ) -> Result<Option<(MemPoolTxInfoPartial, u64)>, db_error> {
+        let sql = "SELECT txid, origin_nonce, origin_address, sponsor_nonce, sponsor_address, fee_rate, length,
+                       origin_nonce AS nonce
+                   FROM mempool WHERE origin_address = ?1 AND origin_nonce < ?2
+                   UNION ALL
+                   SELECT txid, origin_nonce, origin_address, sponsor_nonce, sponsor_address, fee_rate, length,
+                       sponsor_nonce AS nonce
+                   FROM mempool WHERE sponsor_address = ?1 AND sponsor_nonce < ?2
+                   ORDER BY nonce DESC LIMIT 1";
+        let args = params![address.to_string(), u64_to_sql(nonce)?];
+        let mut stmt = conn.prepare(sql).map_err(db_error::SqliteError)?;
+        let mut rows = stmt.query(args).map_err(db_error::SqliteError)?;
+        let Some(row) = rows.next().map_err(db_error::SqliteError)? else {
+            return Ok(None);
+        };
+        Ok(Some((
+            MemPoolTxInfoPartial::from_row(row)?,
+            u64::from_column(row, "length")?,
+        )))
+    }
+
     /// Get a transaction's metadata, given address and nonce, and whether the address is used as a sponsor or an origin.
     /// Faster than getting the MemPoolTxInfo, since no deserialization will be needed.
     /// Used to see if there exists a transaction with this info, so as to implement replace-by-fee
@@ -2163,6 +3394,16 @@ impl MemPoolDB {
             prior_tx.as_ref().map(|tx| tx.txid.clone()),
         )?;
 
+        if let Some(prior_tx) = prior_tx.as_ref().filter(|prior_tx| prior_tx.txid != txid) {
+            MemPoolDB::inner_record_tx_history(
+                tx,
+                &[prior_tx.txid.clone()],
+                &replace_reason,
+                Some(&txid),
+                get_epoch_time_secs(),
+            )?;
+        }
+
         let sql = "INSERT OR REPLACE INTO mempool (
             txid,
             origin_address,
@@ -2198,6 +3439,10 @@ impl MemPoolDB {
 
         tx.update_mempool_pager(&txid)?;
 
+        // the transaction is back, so whatever happened to it before no longer holds
+        tx.execute("DELETE FROM tx_history WHERE txid = ?1", params![txid])
+            .map_err(|e| MemPoolRejection::DBError(db_error::SqliteError(e)))?;
+
         // broadcast drop event if a tx is being replaced
         if let (Some(prior_tx), Some(event_observer)) = (prior_tx, event_observer) {
             event_observer.mempool_txs_dropped(vec![prior_tx.txid], Some(txid), replace_reason);
@@ -2207,12 +3452,19 @@ impl MemPoolDB {
     }
 
     /// Garbage-collect the mempool according to the behavior specified in `behavior`.
-    pub fn garbage_collect(
+    /// `account_nonce` gets an account's nonce at the chain tip, so that mined transactions are
+    /// not remembered as dropped.
+    pub fn garbage_collect<F>(
         &mut self,
         chain_height: u64,
         behavior: &MempoolCollectionBehavior,
         event_observer: Option<&dyn MemPoolEventDispatcher>,
-    ) -> Result<(), db_error> {
+        account_nonce: F,
+    ) -> Result<(), db_error>
+    where
+        F: FnMut(&StacksAddress) -> u64,
+    {
+        let tx_history_max_size = self.tx_history_max_size;
         let tx = self.tx_begin()?;
         match behavior {
             MempoolCollectionBehavior::ByStacksHeight => {
@@ -2221,33 +3473,44 @@ impl MemPoolDB {
                 let Some(min_height) = chain_height.checked_sub(MEMPOOL_MAX_TRANSACTION_AGE) else {
                     return Ok(());
                 };
-                Self::garbage_collect_by_coinbase_height(&tx, min_height, event_observer)?;
+                Self::garbage_collect_by_coinbase_height(
+                    &tx,
+                    min_height,
+                    event_observer,
+                    account_nonce,
+                )?;
             }
             MempoolCollectionBehavior::ByReceiveTime => {
                 Self::garbage_collect_by_time(
                     &tx,
                     &MEMPOOL_NAKAMOTO_MAX_TRANSACTION_AGE,
                     event_observer,
+                    account_nonce,
                 )?;
             }
         };
+        Self::garbage_collect_tx_history(&tx, tx_history_max_size)?;
         tx.commit()
     }
 
     /// Garbage-collect the mempool. Remove transactions that were accepted more than `age` ago.
-    /// The granularity of this check is in seconds.
-    pub fn garbage_collect_by_time(
+    /// The granularity of this check is in seconds.  `account_nonce` gets an account's nonce at
+    /// the chain tip, to tell which of them were mined.
+    pub fn garbage_collect_by_time<F>(
         tx: &MemPoolTx,
         age: &Duration,
         event_observer: Option<&dyn MemPoolEventDispatcher>,
-    ) -> Result<(), db_error> {
+        account_nonce: F,
+    ) -> Result<(), db_error>
+    where
+        F: FnMut(&StacksAddress) -> u64,
+    {
         let threshold_time = get_epoch_time_secs().saturating_sub(age.as_secs());
         let args = params![u64_to_sql(threshold_time)?];
-        if let Some(event_observer) = event_observer {
-            let sql = "SELECT txid FROM mempool WHERE accept_time < ?1";
-            let txids = query_rows(tx, sql, args)?;
-            event_observer.mempool_txs_dropped(txids, None, MemPoolDropReason::STALE_COLLECT);
-        }
+        let sql = "SELECT txid, origin_address, origin_nonce, sponsor_address, sponsor_nonce
+                   FROM mempool WHERE accept_time < ?1";
+        let collected = query_rows(tx, sql, args)?;
+        Self::record_collected_txs(tx, collected, event_observer, account_nonce)?;
 
         let sql = "DELETE FROM mempool WHERE accept_time < ?1";
 
@@ -2258,18 +3521,21 @@ impl MemPoolDB {
 
     /// Garbage-collect the mempool.  Remove transactions that were received `min_coinbase_height`
     ///  blocks ago.
-    pub fn garbage_collect_by_coinbase_height(
+    /// `account_nonce` gets an account's nonce at the chain tip, to tell which of them were mined.
+    pub fn garbage_collect_by_coinbase_height<F>(
         tx: &MemPoolTx,
         min_coinbase_height: u64,
         event_observer: Option<&dyn MemPoolEventDispatcher>,
-    ) -> Result<(), db_error> {
+        account_nonce: F,
+    ) -> Result<(), db_error>
+    where
+        F: FnMut(&StacksAddress) -> u64,
+    {
         let args = params![u64_to_sql(min_coinbase_height)?];
-
-        if let Some(event_observer) = event_observer {
-            let sql = "SELECT txid FROM mempool WHERE height < ?1";
-            let txids = query_rows(tx, sql, args)?;
-            event_observer.mempool_txs_dropped(txids, None, MemPoolDropReason::STALE_COLLECT);
-        }
+        let sql = "SELECT txid, origin_address, origin_nonce, sponsor_address, sponsor_nonce
+                   FROM mempool WHERE height < ?1";
+        let collected = query_rows(tx, sql, args)?;
+        Self::record_collected_txs(tx, collected, event_observer, account_nonce)?;
 
         let sql = "DELETE FROM mempool WHERE height < ?1";
 
@@ -2278,16 +3544,74 @@ impl MemPoolDB {
         Ok(())
     }
 
+    /// Report the transactions about to be garbage-collected to the observer, and remember the
+    /// ones which were not mined as dropped.  Mined transactions did not drop out of the mempool,
+    /// so they are left out of its history.
+    fn record_collected_txs<F>(
+        tx: &MemPoolTx,
+        collected: Vec<MemPoolTxInfoPartial>,
+        event_observer: Option<&dyn MemPoolEventDispatcher>,
+        account_nonce: F,
+    ) -> Result<(), db_error>
+    where
+        F: FnMut(&StacksAddress) -> u64,
+    {
+        let mut mined_filter = MinedTxFilter::new(account_nonce);
+        let dropped: Vec<_> = collected
+            .iter()
+            .filter(|collected_tx| !mined_filter.is_mined(collected_tx))
+            .map(|collected_tx| collected_tx.txid.clone())
+            .collect();
+        Self::inner_record_tx_history(
+            tx,
+            &dropped,
+            &MemPoolDropReason::STALE_COLLECT,
+            None,
+            get_epoch_time_secs(),
+        )?;
+
+        if let Some(event_observer) = event_observer {
+            let txids = collected
+                .into_iter()
+                .map(|collected_tx| collected_tx.txid)
+                .collect();
+            event_observer.mempool_txs_dropped(txids, None, MemPoolDropReason::STALE_COLLECT);
+        }
+        Ok(())
+    }
+
     #[cfg(test)]
     pub fn clear_before_coinbase_height(
         &mut self,
         min_coinbase_height: u64,
     ) -> Result<(), db_error> {
         let tx = self.tx_begin()?;
-        MemPoolDB::garbage_collect_by_coinbase_height(&tx, min_coinbase_height, None)?;
+        MemPoolDB::garbage_collect_by_coinbase_height(&tx, min_coinbase_height, None, |_| 0)?;
         tx.commit()
     }
 
+    /// Get the nonces of `tx`'s origin and sponsor at the given chain tip.  Accounts are taken to
+    /// be unused if the tip's state is not available.
+    pub fn get_account_nonces(
+        chainstate: &mut StacksChainState,
+        sortdb: &SortitionDB,
+        block_id: &StacksBlockId,
+        tx: &StacksTransaction,
+    ) -> Result<(u64, Option<u64>), MemPoolRejection> {
+        let sort_handle = sortdb.index_handle_at_block(chainstate, block_id)?;
+        let account_nonces = chainstate
+            .maybe_read_only_clarity_tx(&sort_handle, block_id, |clarity_tx| {
+                let origin_nonce =
+                    StacksChainState::get_nonce(clarity_tx, &tx.origin_address().into());
+                let sponsor_nonce = tx
+                    .sponsor_address()
+                    .map(|sponsor| StacksChainState::get_nonce(clarity_tx, &sponsor.into()));
+                (origin_nonce, sponsor_nonce)
+            })
+            .map_err(MemPoolRejection::FailedToValidate)?;
+        Ok(account_nonces.unwrap_or((0, tx.sponsor_address().map(|_| 0))))
+    }
+
     /// Submit a transaction to the mempool at a particular chain tip.
     fn tx_submit(
         mempool_tx: &mut MemPoolTx,
@@ -2299,6 +3623,8 @@ impl MemPoolDB {
         do_admission_checks: bool,
         event_observer: Option<&dyn MemPoolEventDispatcher>,
         fee_rate_estimate: Option<f64>,
+        max_size_bytes: Option<u64>,
+        max_txs_per_address: Option<u64>,
     ) -> Result<(), MemPoolRejection> {
         test_debug!(
             "Mempool submit {} at {}/{}",
@@ -2354,6 +3680,68 @@ impl MemPoolDB {
                 .will_admit_tx(chainstate, sortdb, tx, len)?;
         }
 
+        if let Some(max_txs) = max_txs_per_address {
+            let (origin_account_nonce, sponsor_account_nonce) =
+                MemPoolDB::get_account_nonces(chainstate, sortdb, &block_id, tx)?;
+            MemPoolDB::check_pending_tx_limit(
+                mempool_tx,
+                tx,
+                origin_account_nonce,
+                sponsor_account_nonce,
+                max_txs,
+            )?;
+        }
+
+        let eviction = if let Some(max_size_bytes) = max_size_bytes {
+            let mut replaced = vec![];
+            if let Some(prior_tx) = MemPoolDB::get_tx_metadata_by_address(
+                mempool_tx,
+                true,
+                &origin_address,
+                origin_nonce,
+            )? {
+                replaced.push(prior_tx);
+            }
+            if let Some(prior_tx) = MemPoolDB::get_tx_metadata_by_address(
+                mempool_tx,
+                false,
+                &sponsor_address,
+                sponsor_nonce,
+            )? {
+                if !replaced.iter().any(|tx| tx.txid == prior_tx.txid) {
+                    replaced.push(prior_tx);
+                }
+            }
+            let conn: &DBConn = mempool_tx;
+            let sort_handle = sortdb.index_handle_at_block(chainstate, &block_id)?;
+            let eviction = chainstate
+                .maybe_read_only_clarity_tx(&sort_handle, &block_id, |clarity_tx| {
+                    MemPoolDB::find_txs_to_evict(
+                        conn,
+                        fee_rate_estimate,
+                        len,
+                        &replaced,
+                        max_size_bytes,
+                        |address| StacksChainState::get_nonce(clarity_tx, &address.clone().into()),
+                    )
+                })
+                .map_err(MemPoolRejection::FailedToValidate)?;
+            match eviction {
+                Some(eviction) => eviction?,
+                // accounts are taken to be unused if the tip's state is not available
+                None => MemPoolDB::find_txs_to_evict(
+                    conn,
+                    fee_rate_estimate,
+                    len,
+                    &replaced,
+                    max_size_bytes,
+                    |_| 0,
+                )?,
+            }
+        } else {
+            MemPoolEviction::default()
+        };
+
         MemPoolDB::try_add_tx(
             mempool_tx,
             chainstate,
@@ -2378,6 +3766,44 @@ impl MemPoolDB {
             )
             .map_err(db_error::from)?;
 
+        if !eviction.mined.is_empty() {
+            info!("Mempool is full, collecting {} mined transaction(s)", eviction.mined.len();
+                  "new_txid" => %txid,
+                  "max_size_bytes" => ?max_size_bytes);
+            // mined transactions did not drop out of the mempool, so they are not remembered as
+            // dropped, but observers learn of them as they would from garbage collection
+            let sql = "DELETE FROM mempool WHERE txid = ?";
+            for mined_txid in eviction.mined.iter() {
+                mempool_tx
+                    .execute(sql, params![mined_txid])
+                    .map_err(db_error::from)?;
+            }
+            if let Some(event_observer) = event_observer {
+                event_observer.mempool_txs_dropped(
+                    eviction.mined,
+                    None,
+                    MemPoolDropReason::STALE_COLLECT,
+                );
+            }
+        }
+        if !eviction.evicted.is_empty() {
+            info!("Mempool is full, evicting {} transaction(s)", eviction.evicted.len();
+                  "new_txid" => %txid,
+                  "max_size_bytes" => ?max_size_bytes);
+            MemPoolDB::inner_drop_txs(
+                mempool_tx,
+                &eviction.evicted,
+                &MemPoolDropReason::MEMPOOL_FULL,
+            )?;
+            if let Some(event_observer) = event_observer {
+                event_observer.mempool_txs_dropped(
+                    eviction.evicted,
+                    None,
+                    MemPoolDropReason::MEMPOOL_FULL,
+                );
+            }
+        }
+
         if let Err(e) = monitoring::mempool_accepted(&txid, &chainstate.root_path) {
             warn!("Failed to monitor TX receive: {:?}", e; "txid" => %txid);
         }
@@ -2415,6 +3841,8 @@ impl MemPoolDB {
             test_debug!("Transaction {} is temporarily blacklisted", &tx.txid());
             return Err(MemPoolRejection::TemporarilyBlacklisted);
         }
+        let max_size_bytes = self.max_size_bytes;
+        let max_txs_per_address = self.max_txs_per_address;
 
         let estimator_result = cost_estimates::estimate_fee_rate(
             tx,
@@ -2447,6 +3875,8 @@ impl MemPoolDB {
             true,
             event_observer,
             fee_rate,
+            max_size_bytes,
+            max_txs_per_address,
         )?;
         mempool_tx.commit().map_err(MemPoolRejection::DBError)?;
         Ok(())
@@ -2477,6 +3907,8 @@ impl MemPoolDB {
             false,
             event_observer,
             fee_estimate,
+            None,
+            None,
         )?;
         mempool_tx.commit().map_err(MemPoolRejection::DBError)?;
         Ok(())
@@ -2536,6 +3968,8 @@ impl MemPoolDB {
             false,
             None,
             fee_rate,
+            None,
+            None,
         )?;
         mempool_tx.commit().map_err(MemPoolRejection::DBError)?;
         Ok(())
@@ -2614,9 +4048,50 @@ impl MemPoolDB {
         )
     }
 
+    /// Remember why transactions are leaving the mempool, so their fate can be looked up later.
+    /// Must be called while the transactions are still in the mempool.
+    fn inner_record_tx_history(
+        tx: &DBTx<'_>,
+        txids: &[Txid],
+        reason: &MemPoolDropReason,
+        replaced_by: Option<&Txid>,
+        now: u64,
+    ) -> Result<(), db_error> {
+        let sql = "INSERT OR REPLACE INTO tx_history
+                   (txid, origin_address, origin_nonce, reason, replaced_by, drop_time)
+                   SELECT txid, origin_address, origin_nonce, ?2, ?3, ?4 FROM mempool WHERE txid = ?1";
+        for txid in txids.iter() {
+            let args = params![txid, reason.to_string(), replaced_by, u64_to_sql(now)?];
+            tx.execute(sql, args)?;
+        }
+        Ok(())
+    }
+
+    /// Bound the size of the dropped transaction history, forgetting the oldest entries first
+    pub fn garbage_collect_tx_history(tx: &DBTx<'_>, max_size: u64) -> Result<(), db_error> {
+        let sql = "DELETE FROM tx_history WHERE txid IN (
+                       SELECT txid FROM tx_history ORDER BY drop_time DESC LIMIT -1 OFFSET ?1)";
+        tx.execute(sql, params![u64_to_sql(max_size)?])?;
+        Ok(())
+    }
+
+    /// What happened to a transaction which left the mempool without being mined, if we remember
+    pub fn get_tx_history(
+        conn: &DBConn,
+        txid: &Txid,
+    ) -> Result<Option<MemPoolTxHistoryEntry>, db_error> {
+        let sql = "SELECT * FROM tx_history WHERE txid = ?1";
+        query_row(conn, sql, params![txid])
+    }
+
     /// Inner code body for dropping transactions.
     /// Note that the bloom filter will *NOT* be updated.  That's the caller's job, if desired.
-    fn inner_drop_txs(tx: &DBTx<'_>, txids: &[Txid]) -> Result<(), db_error> {
+    fn inner_drop_txs(
+        tx: &DBTx<'_>,
+        txids: &[Txid],
+        reason: &MemPoolDropReason,
+    ) -> Result<(), db_error> {
+        MemPoolDB::inner_record_tx_history(tx, txids, reason, None, get_epoch_time_secs())?;
         let sql = "DELETE FROM mempool WHERE txid = ?";
         for txid in txids.iter() {
             tx.execute(sql, &[txid])?;
@@ -2626,9 +4101,9 @@ impl MemPoolDB {
 
     /// Drop transactions from the mempool.  Does not update the bloom filter, thereby ensuring that
     /// these transactions will still show up as present to the mempool sync logic.
-    pub fn drop_txs(&mut self, txids: &[Txid]) -> Result<(), db_error> {
+    pub fn drop_txs(&mut self, txids: &[Txid], reason: MemPoolDropReason) -> Result<(), db_error> {
         let mempool_tx = self.tx_begin()?;
-        MemPoolDB::inner_drop_txs(&mempool_tx, txids)?;
+        MemPoolDB::inner_drop_txs(&mempool_tx, txids, &reason)?;
         mempool_tx.commit()?;
         Ok(())
     }
@@ -2658,7 +4133,7 @@ impl MemPoolDB {
         let blacklist_max_size = self.blacklist_max_size;
 
         let mempool_tx = self.tx_begin()?;
-        MemPoolDB::inner_drop_txs(&mempool_tx, txids)?;
+        MemPoolDB::inner_drop_txs(&mempool_tx, txids, &MemPoolDropReason::PROBLEMATIC)?;
         MemPoolDB::inner_blacklist_txs(&mempool_tx, txids, now)?;
         MemPoolDB::garbage_collect_tx_blacklist(
             &mempool_tx,
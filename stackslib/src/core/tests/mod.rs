@@ -33,6 +33,7 @@ use stacks_common::types::chainstate::{
     BlockHeaderHash, BurnchainHeaderHash, StacksAddress, StacksBlockId, StacksWorkScore, TrieHash,
     VRFSeed,
 };
+use stacks_common::types::sqlite::NO_PARAMS;
 use stacks_common::types::{MempoolCollectionBehavior, StacksEpochId};
 use stacks_common::util::hash::{hex_bytes, to_hex, Hash160, *};
 use stacks_common::util::secp256k1::{MessageSignature, *};
@@ -61,14 +62,16 @@ use crate::chainstate::stacks::{
     C32_ADDRESS_VERSION_TESTNET_SINGLESIG,
 };
 use crate::core::mempool::{
-    db_get_all_nonces, MemPoolSyncData, MemPoolWalkSettings, MemPoolWalkTxTypes, TxTag,
-    BLOOM_COUNTER_DEPTH, BLOOM_COUNTER_ERROR_RATE, MAX_BLOOM_COUNTER_TXS,
+    db_get_all_nonces, MemPoolDropReason, MemPoolEviction, MemPoolSyncData, MemPoolTxMetadata,
+    MemPoolWalkSettings, MemPoolWalkTxTypes, TxTag, BLOOM_COUNTER_DEPTH, BLOOM_COUNTER_ERROR_RATE,
+    MAX_BLOOM_COUNTER_TXS,
 };
 use crate::core::{FIRST_BURNCHAIN_CONSENSUS_HASH, FIRST_STACKS_BLOCK_HASH};
+use crate::cost_estimates::metrics::PROPORTION_RESOLUTION;
 use crate::net::Error as NetError;
 use crate::util_lib::bloom::test::setup_bloom_counter;
 use crate::util_lib::bloom::*;
-use crate::util_lib::db::{tx_begin_immediate, DBConn, FromRow};
+use crate::util_lib::db::{query_int, tx_begin_immediate, DBConn, FromRow};
 use crate::util_lib::strings::StacksString;
 
 const FOO_CONTRACT: &str = "(define-public (foo) (ok 1))
@@ -161,6 +164,70 @@ pub fn make_block(
     (block_consensus, block_hash)
 }
 
+/// The height at which `add_test_txs` adds transactions
+const TEST_TX_HEIGHT: u64 = 10;
+
+/// A testnet STX transfer from `pk`'s account
+fn make_test_transfer(pk: &StacksPrivateKey, nonce: u64, fee: u64) -> StacksTransaction {
+    let addr = StacksAddress::new(1, Hash160([0xff; 20])).unwrap();
+    let mut tx = StacksTransaction {
+        version: TransactionVersion::Testnet,
+        chain_id: 0x80000000,
+        auth: TransactionAuth::from_p2pkh(pk).unwrap(),
+        anchor_mode: TransactionAnchorMode::Any,
+        post_condition_mode: TransactionPostConditionMode::Allow,
+        post_conditions: vec![],
+        payload: TransactionPayload::TokenTransfer(
+            addr.to_account_principal(),
+            123,
+            TokenTransferMemo([0u8; 34]),
+        ),
+    };
+    tx.set_tx_fee(fee);
+    tx.set_origin_nonce(nonce);
+    tx
+}
+
+/// Add each transaction to the mempool at `TEST_TX_HEIGHT`, with the given estimated fee rate,
+/// or with none to leave it for `MemPoolDB::estimate_tx_rates`
+fn add_test_txs(
+    mempool: &mut MemPoolDB,
+    chainstate: &mut StacksChainState,
+    txs: &[(StacksTransaction, Option<f64>)],
+) {
+    let mut mempool_tx = mempool.tx_begin().unwrap();
+    for (tx, fee_rate) in txs.iter() {
+        let origin_addr = tx.origin_address();
+        let origin_nonce = tx.get_origin_nonce();
+        MemPoolDB::try_add_tx(
+            &mut mempool_tx,
+            chainstate,
+            &ConsensusHash([0x1 + (TEST_TX_HEIGHT as u8); 20]),
+            &BlockHeaderHash([0x2 + (TEST_TX_HEIGHT as u8); 32]),
+            false, // don't resolve the above chain tip since it doesn't exist
+            tx.txid(),
+            tx.serialize_to_vec(),
+            tx.get_tx_fee(),
+            TEST_TX_HEIGHT,
+            &origin_addr,
+            origin_nonce,
+            &origin_addr,
+            origin_nonce,
+            None,
+        )
+        .unwrap();
+        if fee_rate.is_some() {
+            mempool_tx
+                .execute(
+                    "UPDATE mempool SET fee_rate = ? WHERE txid = ?",
+                    params![fee_rate, tx.txid()],
+                )
+                .unwrap();
+        }
+    }
+    mempool_tx.commit().unwrap();
+}
+
 #[test]
 fn mempool_walk_over_fork() {
     let mut chainstate =
@@ -1254,6 +1321,133 @@ fn test_iterate_candidates_concurrent_write_lock() {
     }
 }
 
+#[test]
+/// A low-fee transaction is walked ahead of a mid-fee one if a high-fee transaction depends on it
+/// and `consider_packages` is set, but not once it has been mined, and a nonce gap is reported as
+/// such.
+fn test_iterate_candidates_packages() {
+    let mut chainstate =
+        instantiate_chainstate_with_balances(false, 0x80000000, function_name!(), vec![]);
+    let chainstate_path = chainstate_path(function_name!());
+    let mut mempool = MemPoolDB::open_test(false, 0x80000000, &chainstate_path).unwrap();
+    let b_1 = make_block(
+        &mut chainstate,
+        ConsensusHash([0x1; 20]),
+        &(
+            FIRST_BURNCHAIN_CONSENSUS_HASH.clone(),
+            FIRST_STACKS_BLOCK_HASH.clone(),
+        ),
+        1,
+        1,
+    );
+    let b_2 = make_block(&mut chainstate, ConsensusHash([0x2; 20]), &b_1, 2, 2);
+
+    let pk_a = StacksPrivateKey::random();
+    let pk_b = StacksPrivateKey::random();
+    let addr_a = make_test_transfer(&pk_a, 0, 0).origin_address();
+
+    // A's nonce 1 pays for its nonce 0, so their package rate (10100 / 200) beats B's
+    let txs = [
+        (make_test_transfer(&pk_a, 0, 100), Some(1.0)),
+        (make_test_transfer(&pk_a, 1, 10000), Some(100.0)),
+        (make_test_transfer(&pk_b, 0, 1000), Some(10.0)),
+    ];
+    add_test_txs(&mut mempool, &mut chainstate, &txs);
+    let txids: Vec<_> = txs.iter().map(|(tx, _)| tx.txid()).collect();
+
+    let walk = |mempool: &mut MemPoolDB, chainstate: &mut StacksChainState, consider_packages| {
+        let mut mempool_settings = MemPoolWalkSettings::default();
+        mempool_settings.consider_no_estimate_tx_prob = 0;
+        mempool_settings.consider_packages = consider_packages;
+        let mut considered = vec![];
+        chainstate.with_read_only_clarity_tx(
+            &TEST_BURN_STATE_DB,
+            &StacksBlockHeader::make_index_block_hash(&b_2.0, &b_2.1),
+            |clarity_conn| {
+                mempool
+                    .iterate_candidates::<_, ChainstateError, _>(
+                        clarity_conn,
+                        &mut vec![],
+                        mempool_settings,
+                        |_, available_tx, _| {
+                            considered.push(available_tx.tx.tx.txid());
+                            // the walk is read-only, so report each one skipped
+                            Ok(Some(
+                                TransactionResult::skipped(
+                                    &available_tx.tx.tx,
+                                    "event not relevant to test".to_string(),
+                                )
+                                .convert_to_event(),
+                            ))
+                        },
+                    )
+                    .unwrap();
+            },
+        );
+        considered
+    };
+
+    // skipped transactions don't advance their accounts' nonces, so A's nonce 1 is never
+    // considered; by fee rate alone, B's transaction comes first
+    assert_eq!(
+        walk(&mut mempool, &mut chainstate, false),
+        vec![txids[2].clone(), txids[0].clone()]
+    );
+    // while A's package comes first as a whole
+    assert_eq!(
+        walk(&mut mempool, &mut chainstate, true),
+        vec![txids[0].clone(), txids[2].clone()]
+    );
+
+    let chain = MemPoolDB::get_nonce_chain(mempool.conn(), &addr_a, 0).unwrap();
+    assert_eq!(chain.next_nonce(), 2);
+    assert_eq!(chain.first_gap(), None);
+    assert_eq!(chain.package_fee_rate(0), Some(1.0));
+    assert_eq!(chain.package_fee_rate(1), Some(10100.0 / 200.0));
+
+    // A's nonce 2 is missing
+    let mut gap_tx = make_test_transfer(&pk_a, 3, 100);
+    match MemPoolDB::check_nonce_gaps(mempool.conn(), &gap_tx, 0, None) {
+        Err(MemPoolRejection::NonceGap {
+            missing_nonce,
+            actual_nonce,
+            is_origin,
+            ..
+        }) => {
+            assert_eq!(missing_nonce, 2);
+            assert_eq!(actual_nonce, 3);
+            assert!(is_origin);
+        }
+        res => panic!("Expected a nonce gap, got {res:?}"),
+    }
+    gap_tx.set_origin_nonce(2);
+    MemPoolDB::check_nonce_gaps(mempool.conn(), &gap_tx, 0, None).unwrap();
+    // once the account's nonce passes the pending transactions, there's no gap either
+    gap_tx.set_origin_nonce(5);
+    MemPoolDB::check_nonce_gaps(mempool.conn(), &gap_tx, 5, None).unwrap();
+
+    // packages start from the account's nonce: once A's nonce 0 is used, its nonce 1 no longer
+    // pays for it, so it is walked ahead of B's transaction, which now beats the old package rate
+    mempool
+        .conn()
+        .execute(
+            "INSERT OR REPLACE INTO nonces (address, nonce) VALUES (?1, ?2)",
+            params![addr_a.to_string(), 1],
+        )
+        .unwrap();
+    mempool
+        .conn()
+        .execute(
+            "UPDATE mempool SET fee_rate = ? WHERE txid = ?",
+            params![Some(60.0), &txids[2]],
+        )
+        .unwrap();
+    assert_eq!(
+        walk(&mut mempool, &mut chainstate, true),
+        vec![txids[1].clone(), txids[2].clone()]
+    );
+}
+
 #[test]
 fn mempool_do_not_replace_tx() {
     let mut chainstate =
@@ -1602,12 +1796,12 @@ fn mempool_db_load_store_replace_tx(#[case] behavior: MempoolCollectionBehavior)
     let mempool_tx = mempool.tx_begin().unwrap();
     match behavior {
         MempoolCollectionBehavior::ByStacksHeight => {
-            MemPoolDB::garbage_collect_by_coinbase_height(&mempool_tx, 101, None)
+            MemPoolDB::garbage_collect_by_coinbase_height(&mempool_tx, 101, None, |_| 0)
         }
         MempoolCollectionBehavior::ByReceiveTime => {
             let test_max_age = Duration::from_secs(1);
             std::thread::sleep(2 * test_max_age);
-            MemPoolDB::garbage_collect_by_time(&mempool_tx, &test_max_age, None)
+            MemPoolDB::garbage_collect_by_time(&mempool_tx, &test_max_age, None, |_| 0)
         }
     }
     .unwrap();
@@ -2626,6 +2820,383 @@ fn test_drop_and_blacklist_txs_by_size() {
     assert_eq!(num_blacklisted, 5);
 }
 
+#[test]
+fn test_mempool_size_limits() {
+    let mut chainstate = instantiate_chainstate(false, 0x80000000, function_name!());
+    let chainstate_path = chainstate_path(function_name!());
+    let mut mempool = MemPoolDB::open_test(false, 0x80000000, &chainstate_path).unwrap();
+
+    let pk_a = StacksPrivateKey::random();
+    let pk_b = StacksPrivateKey::random();
+
+    // account A has nonces 0 through 2 pending, and account B has nonce 0 pending
+    let txs = vec![
+        make_test_transfer(&pk_a, 0, 1000),
+        make_test_transfer(&pk_a, 1, 3000),
+        make_test_transfer(&pk_a, 2, 2000),
+        make_test_transfer(&pk_b, 0, 4000),
+    ];
+    let tx_len = txs[0].serialize_to_vec().len() as u64;
+    assert!(txs
+        .iter()
+        .all(|tx| tx.serialize_to_vec().len() as u64 == tx_len));
+    let unestimated: Vec<_> = txs.iter().map(|tx| (tx.clone(), None)).collect();
+    add_test_txs(&mut mempool, &mut chainstate, &unestimated);
+    // the mempool's unit estimator and metric make each fee rate the fee
+    assert_eq!(
+        mempool
+            .estimate_tx_rates(100, &ExecutionCost::max_value(), &StacksEpochId::latest())
+            .unwrap(),
+        4
+    );
+    assert_eq!(
+        query_int(mempool.conn(), "SELECT size FROM mempool_size", NO_PARAMS).unwrap() as u64,
+        4 * tx_len
+    );
+
+    // per-address limit
+    let next_tx = make_test_transfer(&pk_a, 3, 1000);
+    match MemPoolDB::check_pending_tx_limit(mempool.conn(), &next_tx, 0, None, 3).unwrap_err() {
+        MemPoolRejection::TooManyPendingTxs {
+            max_txs, is_origin, ..
+        } => {
+            assert_eq!(max_txs, 3);
+            assert!(is_origin);
+        }
+        e => panic!("unexpected rejection {e:?}"),
+    }
+    MemPoolDB::check_pending_tx_limit(mempool.conn(), &next_tx, 0, None, 4).unwrap();
+    // transactions below the account's nonce are no longer pending
+    MemPoolDB::check_pending_tx_limit(mempool.conn(), &next_tx, 1, None, 3).unwrap();
+    // a replacement takes no more room
+    let replacement_tx = make_test_transfer(&pk_a, 1, 5000);
+    MemPoolDB::check_pending_tx_limit(mempool.conn(), &replacement_tx, 0, None, 3).unwrap();
+
+    // size limit: the lowest fee-rate transactions are evicted first, but only from the top of
+    // their accounts' nonce chains, so A's nonce 0 goes last
+    let txids: Vec<_> = txs.iter().map(|tx| tx.txid()).collect();
+    let conn = mempool.conn();
+    let unused = |_: &StacksAddress| 0;
+    let evict = |fee_rate, length, replaced: &[MemPoolTxMetadata], max_size_bytes| {
+        MemPoolDB::find_txs_to_evict(conn, fee_rate, length, replaced, max_size_bytes, unused)
+    };
+    assert_eq!(
+        evict(Some(1500.0), tx_len, &[], 5 * tx_len).unwrap(),
+        MemPoolEviction::default()
+    );
+    assert_eq!(
+        evict(Some(2500.0), tx_len, &[], 4 * tx_len)
+            .unwrap()
+            .evicted,
+        vec![txids[2].clone()]
+    );
+    assert_eq!(
+        evict(Some(3500.0), tx_len, &[], 3 * tx_len)
+            .unwrap()
+            .evicted,
+        vec![txids[2].clone(), txids[1].clone()]
+    );
+    assert_eq!(
+        evict(Some(3500.0), tx_len, &[], 2 * tx_len)
+            .unwrap()
+            .evicted,
+        vec![txids[2].clone(), txids[1].clone(), txids[0].clone()]
+    );
+    // only transactions with a lower fee rate can be evicted, and A's nonce 1 protects its nonce 0
+    assert!(matches!(
+        evict(Some(2500.0), tx_len, &[], 3 * tx_len),
+        Err(MemPoolRejection::MemPoolFull)
+    ));
+    assert!(matches!(
+        evict(Some(1500.0), tx_len, &[], 3 * tx_len),
+        Err(MemPoolRejection::MemPoolFull)
+    ));
+    assert!(matches!(
+        evict(Some(5000.0), 5 * tx_len, &[], 4 * tx_len),
+        Err(MemPoolRejection::MemPoolFull)
+    ));
+    // a transaction without an estimate can't evict any
+    assert!(matches!(
+        evict(None, tx_len, &[], 4 * tx_len),
+        Err(MemPoolRejection::MemPoolFull)
+    ));
+    // mined transactions are removed first, whatever they paid, so they don't keep new ones out
+    let addr_a = txs[0].origin_address();
+    let eviction =
+        MemPoolDB::find_txs_to_evict(conn, Some(3500.0), tx_len, &[], 2 * tx_len, |address| {
+            if *address == addr_a {
+                1
+            } else {
+                0
+            }
+        })
+        .unwrap();
+    assert_eq!(eviction.mined, vec![txids[0].clone()]);
+    assert_eq!(eviction.evicted, vec![txids[2].clone(), txids[1].clone()]);
+    let addr_b = txs[3].origin_address();
+    let eviction = MemPoolDB::find_txs_to_evict(conn, None, tx_len, &[], 4 * tx_len, |address| {
+        if *address == addr_b {
+            1
+        } else {
+            0
+        }
+    })
+    .unwrap();
+    assert_eq!(eviction.mined, vec![txids[3].clone()]);
+    assert!(eviction.evicted.is_empty());
+    // a replaced transaction makes room for its replacement
+    let replaced = MemPoolDB::get_tx_metadata_by_address(conn, true, &txs[3].origin_address(), 0)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        evict(Some(5000.0), tx_len, &[replaced], 4 * tx_len).unwrap(),
+        MemPoolEviction::default()
+    );
+}
+
+#[test]
+fn test_mempool_tx_history() {
+    let mut chainstate = instantiate_chainstate(false, 0x80000000, function_name!());
+    let chainstate_path = chainstate_path(function_name!());
+    let mut mempool = MemPoolDB::open_test(false, 0x80000000, &chainstate_path).unwrap();
+
+    let pk = StacksPrivateKey::random();
+    let make_tx = |nonce, fee| make_test_transfer(&pk, nonce, fee);
+    let add_tx =
+        |mempool: &mut MemPoolDB, chainstate: &mut StacksChainState, tx: StacksTransaction| {
+            add_test_txs(mempool, chainstate, &[(tx, None)]);
+        };
+
+    let txs = vec![make_tx(0, 1000), make_tx(1, 2000), make_tx(2, 3000)];
+    for tx in txs.iter() {
+        add_tx(&mut mempool, &mut chainstate, tx.clone());
+    }
+    let txids: Vec<_> = txs.iter().map(|tx| tx.txid()).collect();
+
+    // pending transactions are ranked by what they pay
+    let rank = MemPoolDB::get_tx_rank(mempool.conn(), &txids[2], |_| 0)
+        .unwrap()
+        .unwrap();
+    assert_eq!(rank.fee_per_byte_rank, 1);
+    assert_eq!(rank.fee_rate_rank, None);
+    let rank = MemPoolDB::get_tx_rank(mempool.conn(), &txids[0], |_| 0)
+        .unwrap()
+        .unwrap();
+    assert_eq!(rank.fee_per_byte_rank, 3);
+    // mined transactions don't count against it
+    let rank = MemPoolDB::get_tx_rank(mempool.conn(), &txids[0], |_| 2)
+        .unwrap()
+        .unwrap();
+    assert_eq!(rank.fee_per_byte_rank, 2);
+    assert!(
+        MemPoolDB::get_tx_rank(mempool.conn(), &Txid([0x21; 32]), |_| 0)
+            .unwrap()
+            .is_none()
+    );
+    assert!(MemPoolDB::get_tx_history(mempool.conn(), &txids[0])
+        .unwrap()
+        .is_none());
+
+    // replaced by fee
+    let replacement_tx = make_tx(0, 5000);
+    add_tx(&mut mempool, &mut chainstate, replacement_tx.clone());
+    let entry = MemPoolDB::get_tx_history(mempool.conn(), &txids[0])
+        .unwrap()
+        .unwrap();
+    assert_eq!(entry.reason, MemPoolDropReason::REPLACE_BY_FEE);
+    assert_eq!(entry.replaced_by, Some(replacement_tx.txid()));
+    assert_eq!(entry.origin_address, txs[0].origin_address());
+    assert_eq!(entry.origin_nonce, 0);
+    assert!(
+        MemPoolDB::get_tx_history(mempool.conn(), &replacement_tx.txid())
+            .unwrap()
+            .is_none()
+    );
+
+    // dropped by the miner, and forgotten once it is back in the mempool
+    mempool
+        .drop_txs(&txids[1..2], MemPoolDropReason::TOO_EXPENSIVE)
+        .unwrap();
+    let entry = MemPoolDB::get_tx_history(mempool.conn(), &txids[1])
+        .unwrap()
+        .unwrap();
+    assert_eq!(entry.reason, MemPoolDropReason::TOO_EXPENSIVE);
+    assert_eq!(entry.replaced_by, None);
+    add_tx(&mut mempool, &mut chainstate, txs[1].clone());
+    assert!(MemPoolDB::get_tx_history(mempool.conn(), &txids[1])
+        .unwrap()
+        .is_none());
+
+    // dropped and blacklisted
+    mempool.drop_and_blacklist_txs(&txids[2..3]).unwrap();
+    let entry = MemPoolDB::get_tx_history(mempool.conn(), &txids[2])
+        .unwrap()
+        .unwrap();
+    assert_eq!(entry.reason, MemPoolDropReason::PROBLEMATIC);
+    assert!(mempool.is_tx_blacklisted(&txids[2]).unwrap());
+
+    // the history is bounded, and the oldest entries go first
+    let mempool_tx = mempool.tx_begin().unwrap();
+    mempool_tx
+        .execute(
+            "UPDATE tx_history SET drop_time = 1 WHERE txid = ?1",
+            params![txids[0]],
+        )
+        .unwrap();
+    MemPoolDB::garbage_collect_tx_history(&mempool_tx, 2).unwrap();
+    assert!(MemPoolDB::get_tx_history(&mempool_tx, &txids[0])
+        .unwrap()
+        .is_some());
+    MemPoolDB::garbage_collect_tx_history(&mempool_tx, 1).unwrap();
+    assert!(MemPoolDB::get_tx_history(&mempool_tx, &txids[0])
+        .unwrap()
+        .is_none());
+    assert!(MemPoolDB::get_tx_history(&mempool_tx, &txids[2])
+        .unwrap()
+        .is_some());
+
+    // garbage collection only remembers the transactions which were not mined
+    MemPoolDB::garbage_collect_by_coinbase_height(&mempool_tx, TEST_TX_HEIGHT + 1, None, |_| 1)
+        .unwrap();
+    assert!(
+        MemPoolDB::get_tx_history(&mempool_tx, &replacement_tx.txid())
+            .unwrap()
+            .is_none()
+    );
+    let entry = MemPoolDB::get_tx_history(&mempool_tx, &txids[1])
+        .unwrap()
+        .unwrap();
+    assert_eq!(entry.reason, MemPoolDropReason::STALE_COLLECT);
+    mempool_tx.commit().unwrap();
+}
+
+#[test]
+fn test_mempool_stats() {
+    let mut chainstate = instantiate_chainstate(false, 0x80000000, function_name!());
+    let chainstate_path = chainstate_path(function_name!());
+    let mut mempool = MemPoolDB::open_test(false, 0x80000000, &chainstate_path).unwrap();
+
+    let epoch_id = StacksEpochId::Epoch21;
+    let stats = mempool
+        .get_stats(&ExecutionCost::max_value(), &epoch_id, |_| 0)
+        .unwrap();
+    assert_eq!(stats.tx_count, 0);
+    assert_eq!(stats.oldest_tx_age_secs, None);
+    assert!(stats.fee_per_byte.buckets.is_empty());
+
+    // fees per byte of 1, 3, 3 and 60, and all but the last have been estimated
+    let fee_rates = [1.5, 7.0, 7.0];
+    let txs: Vec<_> = [1, 3, 3, 60]
+        .into_iter()
+        .map(|fee_per_byte| {
+            let mut tx = make_test_transfer(&StacksPrivateKey::random(), 0, 0);
+            tx.set_tx_fee(fee_per_byte * tx.serialize_to_vec().len() as u64);
+            tx
+        })
+        .collect();
+    let entries: Vec<_> = txs
+        .iter()
+        .enumerate()
+        .map(|(i, tx)| (tx.clone(), fee_rates.get(i).copied()))
+        .collect();
+    add_test_txs(&mut mempool, &mut chainstate, &entries);
+
+    let tx_len = txs[0].serialize_to_vec().len() as u64;
+    let stats = mempool
+        .get_stats(&ExecutionCost::max_value(), &epoch_id, |_| 0)
+        .unwrap();
+    assert_eq!(stats.tx_count, 4);
+    assert_eq!(stats.total_bytes, 4 * tx_len);
+    assert!(stats.oldest_tx_age_secs.unwrap() < 60);
+    assert_eq!(stats.tx_types.token_transfer, 4);
+    assert_eq!(stats.tx_types.other, 0);
+
+    // highest bucket first; a block holds far more than all of these bytes
+    let buckets: Vec<_> = stats
+        .fee_per_byte
+        .buckets
+        .iter()
+        .map(|bucket| (bucket.min_fee_rate, bucket.tx_count, bucket.total_bytes))
+        .collect();
+    assert_eq!(
+        buckets,
+        vec![(50, 1, tx_len), (2, 2, 2 * tx_len), (1, 1, tx_len)]
+    );
+    assert!(stats
+        .fee_per_byte
+        .buckets
+        .iter()
+        .all(|bucket| bucket.blocks_to_clear == 1));
+    assert_eq!(stats.fee_per_byte.unestimated_tx_count, 0);
+
+    // the unit metric scores a whole block as 1, and each transaction by its fee over its rate
+    let buckets: Vec<_> = stats
+        .fee_rate
+        .buckets
+        .iter()
+        .map(|bucket| (bucket.min_fee_rate, bucket.tx_count, bucket.blocks_to_clear))
+        .collect();
+    let weights: Vec<_> = txs
+        .iter()
+        .zip(fee_rates)
+        .map(|(tx, fee_rate)| tx.get_tx_fee() as f64 / fee_rate)
+        .collect();
+    assert_eq!(
+        buckets,
+        vec![
+            (5, 2, (weights[1] + weights[2]).ceil() as u64),
+            (1, 1, (weights[0] + weights[1] + weights[2]).ceil() as u64),
+        ]
+    );
+    assert_eq!(stats.fee_rate.unestimated_tx_count, 1);
+
+    // the unit estimator costs each transaction 1 of each dimension, which is a third of this
+    // block limit's write count, and none of its runtime
+    let block_limit = ExecutionCost {
+        write_length: PROPORTION_RESOLUTION,
+        write_count: 3,
+        read_length: PROPORTION_RESOLUTION,
+        read_count: PROPORTION_RESOLUTION,
+        runtime: 0,
+    };
+    let stats = mempool.get_stats(&block_limit, &epoch_id, |_| 0).unwrap();
+    let by_cost = &stats.fee_rate_by_cost;
+    assert!(by_cost.runtime.buckets.is_empty());
+    assert_eq!(by_cost.runtime.unestimated_tx_count, 0);
+    for histogram in [
+        &by_cost.read_count,
+        &by_cost.read_length,
+        &by_cost.write_length,
+    ] {
+        let counts: Vec<_> = histogram
+            .buckets
+            .iter()
+            .map(|bucket| (bucket.tx_count, bucket.blocks_to_clear))
+            .collect();
+        assert_eq!(counts, vec![(1, 1), (2, 1), (1, 1)]);
+        assert_eq!(histogram.unestimated_tx_count, 0);
+    }
+    let counts: Vec<_> = by_cost
+        .write_count
+        .buckets
+        .iter()
+        .map(|bucket| (bucket.tx_count, bucket.blocks_to_clear))
+        .collect();
+    // only the highest-paying transaction pays at least 1 per ten-thousandth of the write count
+    assert_eq!(counts, vec![(1, 1), (3, 2)]);
+
+    // a transaction whose nonce has been used is not counted
+    let mined_addr = txs[3].origin_address();
+    let stats = mempool
+        .get_stats(&ExecutionCost::max_value(), &epoch_id, |address| {
+            u64::from(*address == mined_addr)
+        })
+        .unwrap();
+    assert_eq!(stats.tx_count, 3);
+    assert_eq!(stats.total_bytes, 3 * tx_len);
+    assert_eq!(stats.fee_per_byte.buckets[0].min_fee_rate, 2);
+}
+
 #[test]
 fn test_filter_txs_by_type() {
     let mut chainstate = instantiate_chainstate(false, 0x80000000, function_name!());
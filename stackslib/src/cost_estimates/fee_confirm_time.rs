@@ -0,0 +1,410 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use clarity::types::sqlite::NO_PARAMS;
+use clarity::vm::costs::ExecutionCost;
+use rusqlite::{
+    params, Connection, Error as SqliteError, OpenFlags, OptionalExtension,
+    Transaction as SqlTransaction,
+};
+use stacks_common::util::get_epoch_time_secs;
+
+use super::fee_medians::fee_rate_and_weight_from_receipt;
+use super::metrics::CostMetric;
+use super::{EstimatorError, FeeEstimator, FeeRateEstimate};
+use crate::burnchains::Txid;
+use crate::chainstate::stacks::db::StacksEpochReceipt;
+use crate::chainstate::stacks::events::TransactionOrigin;
+use crate::core::mempool::MemPoolDB;
+use crate::util_lib::db::{sqlite_open, table_exists, tx_begin_immediate_sqlite};
+
+const CREATE_TABLES: &[&str] = &[
+    "
+CREATE TABLE IF NOT EXISTS confirm_time_fee_estimator_blocks (
+    block_key INTEGER PRIMARY KEY AUTOINCREMENT,
+    block_time INTEGER NOT NULL
+)",
+    "
+CREATE TABLE IF NOT EXISTS confirm_time_fee_estimator (
+    block_key INTEGER NOT NULL,
+    fee_rate NUMBER NOT NULL,
+    blocks_waited INTEGER NOT NULL
+)",
+    "CREATE INDEX IF NOT EXISTS confirm_time_fee_estimator_by_block_key ON confirm_time_fee_estimator(block_key)",
+    "
+CREATE TABLE IF NOT EXISTS confirm_time_fee_estimator_mined (
+    block_key INTEGER NOT NULL,
+    txid TEXT NOT NULL
+)",
+    "CREATE INDEX IF NOT EXISTS confirm_time_fee_estimator_mined_by_txid ON confirm_time_fee_estimator_mined(txid)",
+    "CREATE INDEX IF NOT EXISTS confirm_time_fee_estimator_mined_by_block_key ON confirm_time_fee_estimator_mined(block_key)",
+    "
+CREATE TABLE IF NOT EXISTS confirm_time_fee_estimator_unconfirmed (
+    fee_rate NUMBER NOT NULL,
+    blocks_waited INTEGER NOT NULL
+)",
+];
+
+/// Confirmations are judged in groups of this many transactions, so that a single slow or
+/// fast transaction does not swing the estimate.
+pub const CONFIRMATION_SAMPLE_GROUP: usize = 20;
+
+/// The (blocks, confidence) targets answered as the high, middle and low values of
+/// `get_rate_estimates()`.
+pub const RATE_ESTIMATE_TARGETS: [(u64, f64); 3] = [(1, 0.95), (3, 0.95), (10, 0.95)];
+
+/// FeeRateEstimator which learns from how long transactions waited to be confirmed, rather than
+/// from the fee rates alone:
+///
+/// 1) Each block the estimator is notified of is recorded with the time it was processed.
+/// 2) Each transaction in the block which is still in the mempool is recorded with its fee rate
+///    and the number of blocks it waited for, i.e. the blocks processed after its mempool
+///    `accept_time`, counting the one it confirmed in.
+/// 3) The transactions still waiting in the mempool are recorded with the fee rate the mempool
+///    estimated for them and the number of blocks they have waited so far.  They are replaced
+///    with the mempool's waiting transactions at each block.
+/// 4) To answer "which fee rate confirms within N blocks with P confidence", the transactions of
+///    the last `window_size` blocks are sorted by fee rate, highest first, and walked in groups
+///    of `CONFIRMATION_SAMPLE_GROUP`. A transaction which confirmed within N blocks counts as a
+///    success, and one which confirmed later, or which is still waiting after more than N
+///    blocks, as a failure.  A transaction which has waited N blocks or fewer and has not
+///    confirmed yet is not counted.  The estimate is the lowest fee rate of the last group in
+///    which at least P of the transactions succeeded.
+///
+/// Transactions this node never saw in its mempool carry no waiting time, and are skipped, as are
+/// waiting transactions which arrived before the window, since they may have been mined in a
+/// block which has left it.  Waiting times are capped at `window_size` blocks.
+pub struct ConfirmTimeFeeRateEstimator<M: CostMetric> {
+    db: Connection,
+    /// Path to the mempool DB, where the transactions' arrival times are looked up.
+    mempool_path: PathBuf,
+    /// Read-only connection to the mempool DB, opened the first time it is needed.
+    mempool: Option<Connection>,
+    /// We only look back `window_size` blocks when estimating.
+    window_size: u32,
+    /// Use this cost metric in fee rate calculations.
+    metric: M,
+}
+
+impl<M: CostMetric> ConfirmTimeFeeRateEstimator<M> {
+    /// Open a fee rate estimator at the given db path. Creates if not existent.
+    pub fn open(
+        p: &Path,
+        mempool_path: &Path,
+        metric: M,
+        window_size: u32,
+    ) -> Result<Self, SqliteError> {
+        let mut db = sqlite_open(
+            p,
+            OpenFlags::SQLITE_OPEN_CREATE | OpenFlags::SQLITE_OPEN_READ_WRITE,
+            false,
+        )?;
+
+        // check if the db needs to be instantiated regardless of whether or not
+        //  it was newly created: the db itself may be shared with other fee estimators,
+        //  which would not have created the necessary table for this estimator.
+        let tx = tx_begin_immediate_sqlite(&mut db)?;
+        Self::instantiate_db(&tx)?;
+        tx.commit()?;
+
+        Ok(Self {
+            db,
+            mempool_path: mempool_path.to_path_buf(),
+            mempool: None,
+            metric,
+            window_size,
+        })
+    }
+
+    /// Check if the SQL database was already created. Necessary to avoid races if
+    ///  different threads open an estimator at the same time.
+    fn db_already_instantiated(tx: &SqlTransaction) -> Result<bool, SqliteError> {
+        table_exists(tx, "confirm_time_fee_estimator_unconfirmed")
+    }
+
+    fn instantiate_db(tx: &SqlTransaction) -> Result<(), SqliteError> {
+        if !Self::db_already_instantiated(tx)? {
+            for sql in CREATE_TABLES {
+                tx.execute(sql, NO_PARAMS)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the connection to the mempool DB, opening it if it is not open yet.
+    fn mempool_conn(&mut self) -> Result<&Connection, EstimatorError> {
+        let mempool = match self.mempool.take() {
+            Some(mempool) => mempool,
+            None => sqlite_open(&self.mempool_path, OpenFlags::SQLITE_OPEN_READ_ONLY, false)
+                .map_err(EstimatorError::SqliteError)?,
+        };
+        Ok(self.mempool.insert(mempool))
+    }
+
+    /// Look up the mempool arrival times of the block's transactions, and the transactions still
+    /// waiting in the mempool which arrived after `since`, as (txid, fee rate, accept time).
+    /// Transactions which are no longer in the mempool are left out.
+    fn load_mempool_txs(
+        &mut self,
+        receipt: &StacksEpochReceipt,
+        since: u64,
+    ) -> Result<(HashMap<Txid, u64>, Vec<(Txid, f64, u64)>), EstimatorError> {
+        let mempool = self.mempool_conn()?;
+        let mut accept_times = HashMap::new();
+        for tx_receipt in receipt.tx_receipts.iter() {
+            let TransactionOrigin::Stacks(ref tx) = tx_receipt.transaction else {
+                continue;
+            };
+            let txid = tx.txid();
+            match MemPoolDB::get_tx_accept_time(mempool, &txid) {
+                Ok(Some(accept_time)) => {
+                    accept_times.insert(txid, accept_time);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    warn!("Failed to load mempool arrival time"; "txid" => %txid, "err" => ?e);
+                }
+            }
+        }
+        let waiting = MemPoolDB::get_estimated_txs_since(mempool, since).unwrap_or_else(|e| {
+            warn!("Failed to load waiting mempool transactions"; "err" => ?e);
+            vec![]
+        });
+        Ok((accept_times, waiting))
+    }
+
+    /// The processing time of the oldest block in the window, if any
+    fn get_window_start(conn: &Connection) -> Result<Option<u64>, SqliteError> {
+        conn.query_row(
+            "SELECT MIN(block_time) FROM confirm_time_fee_estimator_blocks",
+            NO_PARAMS,
+            |row| row.get(0),
+        )
+    }
+
+    /// Record a block processed at `block_time`, and how long each of its transactions in
+    /// `accept_times` waited to be confirmed in it.  `waiting` are the transactions still waiting
+    /// in the mempool, as (txid, fee rate, accept time), which replace those recorded before.
+    pub fn record_confirmations(
+        &mut self,
+        receipt: &StacksEpochReceipt,
+        block_limit: &ExecutionCost,
+        accept_times: &HashMap<Txid, u64>,
+        waiting: &[(Txid, f64, u64)],
+        block_time: u64,
+    ) -> Result<(), EstimatorError> {
+        let tx = tx_begin_immediate_sqlite(&mut self.db).map_err(EstimatorError::SqliteError)?;
+
+        tx.execute(
+            "INSERT INTO confirm_time_fee_estimator_blocks (block_time) VALUES (?)",
+            params![block_time],
+        )
+        .map_err(EstimatorError::SqliteError)?;
+        let block_key = tx.last_insert_rowid();
+
+        let mut num_recorded = 0;
+        for tx_receipt in receipt.tx_receipts.iter() {
+            let TransactionOrigin::Stacks(ref stacks_tx) = tx_receipt.transaction else {
+                continue;
+            };
+            let txid = stacks_tx.txid();
+            // so that it is not taken to be waiting while the mempool still holds it
+            tx.execute(
+                "INSERT INTO confirm_time_fee_estimator_mined (block_key, txid) VALUES (?, ?)",
+                params![block_key, txid],
+            )
+            .map_err(EstimatorError::SqliteError)?;
+            let Some(accept_time) = accept_times.get(&txid) else {
+                continue;
+            };
+            let Some(fee_rate_and_weight) =
+                fee_rate_and_weight_from_receipt(&self.metric, tx_receipt, block_limit)
+            else {
+                continue;
+            };
+            // the blocks processed since this transaction arrived, including this one
+            let blocks_waited: u64 = tx
+                .query_row(
+                    "SELECT COUNT(*) FROM confirm_time_fee_estimator_blocks
+                     WHERE block_time > ? OR block_key = ?",
+                    params![accept_time, block_key],
+                    |row| row.get(0),
+                )
+                .map_err(EstimatorError::SqliteError)?;
+            tx.execute(
+                "INSERT INTO confirm_time_fee_estimator (block_key, fee_rate, blocks_waited)
+                 VALUES (?, ?, ?)",
+                params![block_key, fee_rate_and_weight.fee_rate, blocks_waited],
+            )
+            .map_err(EstimatorError::SqliteError)?;
+            num_recorded += 1;
+        }
+
+        // forget everything older than the window
+        for table in [
+            "confirm_time_fee_estimator_blocks",
+            "confirm_time_fee_estimator",
+            "confirm_time_fee_estimator_mined",
+        ] {
+            let sql = format!(
+                "DELETE FROM {table}
+                 WHERE block_key <= (
+                    SELECT MAX(block_key) - ?
+                    FROM confirm_time_fee_estimator_blocks )"
+            );
+            tx.execute(&sql, params![self.window_size])
+                .map_err(EstimatorError::SqliteError)?;
+        }
+
+        // a transaction which arrived before the window may have been mined in a block that
+        // has left it, so only those which arrived since are known to be still waiting
+        tx.execute(
+            "DELETE FROM confirm_time_fee_estimator_unconfirmed",
+            NO_PARAMS,
+        )
+        .map_err(EstimatorError::SqliteError)?;
+        let window_start = Self::get_window_start(&tx)
+            .map_err(EstimatorError::SqliteError)?
+            .unwrap_or(block_time);
+        let mut num_waiting = 0;
+        for (txid, fee_rate, accept_time) in waiting.iter() {
+            if *accept_time <= window_start {
+                continue;
+            }
+            let mined = tx
+                .query_row(
+                    "SELECT 1 FROM confirm_time_fee_estimator_mined WHERE txid = ?",
+                    params![txid],
+                    |_row| Ok(()),
+                )
+                .optional()
+                .map_err(EstimatorError::SqliteError)?
+                .is_some();
+            if mined {
+                continue;
+            }
+            let blocks_waited: u64 = tx
+                .query_row(
+                    "SELECT COUNT(*) FROM confirm_time_fee_estimator_blocks WHERE block_time > ?",
+                    params![accept_time],
+                    |row| row.get(0),
+                )
+                .map_err(EstimatorError::SqliteError)?;
+            tx.execute(
+                "INSERT INTO confirm_time_fee_estimator_unconfirmed (fee_rate, blocks_waited)
+                 VALUES (?, ?)",
+                params![fee_rate, blocks_waited],
+            )
+            .map_err(EstimatorError::SqliteError)?;
+            num_waiting += 1;
+        }
+        tx.commit().map_err(EstimatorError::SqliteError)?;
+
+        debug!("Recorded transaction confirmation times for new block";
+               "num_recorded" => num_recorded,
+               "num_waiting" => num_waiting,
+               "block_time" => block_time);
+        Ok(())
+    }
+
+    fn get_rate_estimate_for_target_from_sql(
+        conn: &Connection,
+        target_blocks: u64,
+        confidence: f64,
+    ) -> Result<f64, EstimatorError> {
+        if target_blocks == 0 || !(confidence > 0f64 && confidence <= 1f64) {
+            return Err(EstimatorError::NoEstimateAvailable);
+        }
+
+        // (fee rate, whether it confirmed within the target), leaving out the transactions
+        // still waiting which might yet
+        let sql =
+            "SELECT fee_rate, blocks_waited <= ?1 AS succeeded FROM confirm_time_fee_estimator
+                   UNION ALL
+                   SELECT fee_rate, 0 AS succeeded FROM confirm_time_fee_estimator_unconfirmed
+                   WHERE blocks_waited > ?1";
+        let mut stmt = conn.prepare(sql).map_err(EstimatorError::SqliteError)?;
+        let mut observations = stmt
+            .query_and_then::<_, SqliteError, _, _>(params![target_blocks], |row| {
+                let fee_rate: f64 = row.get("fee_rate")?;
+                let succeeded: bool = row.get("succeeded")?;
+                Ok((fee_rate, succeeded))
+            })
+            .map_err(EstimatorError::SqliteError)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(EstimatorError::SqliteError)?;
+
+        // Sort from the highest fee rate down. For float values that do not compare easily,
+        // treat them as equals.
+        observations.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(Ordering::Equal));
+
+        let mut estimate = None;
+        for group in observations.chunks_exact(CONFIRMATION_SAMPLE_GROUP) {
+            let num_confirmed = group.iter().filter(|(_, succeeded)| *succeeded).count();
+            if (num_confirmed as f64) < confidence * (group.len() as f64) {
+                break;
+            }
+            estimate = group.last().map(|(fee_rate, _)| *fee_rate);
+        }
+
+        estimate.ok_or(EstimatorError::NoEstimateAvailable)
+    }
+}
+
+impl<M: CostMetric> FeeEstimator for ConfirmTimeFeeRateEstimator<M> {
+    fn notify_block(
+        &mut self,
+        receipt: &StacksEpochReceipt,
+        block_limit: &ExecutionCost,
+    ) -> Result<(), EstimatorError> {
+        let block_time = get_epoch_time_secs();
+        // the waiting transactions which can still be in the window after this block
+        let since = Self::get_window_start(&self.db)
+            .map_err(EstimatorError::SqliteError)?
+            .unwrap_or(block_time);
+        let (accept_times, waiting) = self.load_mempool_txs(receipt, since).unwrap_or_else(|e| {
+            warn!("Failed to open mempool to load transaction arrival times"; "err" => ?e);
+            (HashMap::new(), vec![])
+        });
+        self.record_confirmations(receipt, block_limit, &accept_times, &waiting, block_time)
+    }
+
+    /// Estimate each of `RATE_ESTIMATE_TARGETS`.  A target without an estimate takes that of the
+    /// nearest tighter target which has one, or failing that, of the nearest looser target.
+    fn get_rate_estimates(&self) -> Result<FeeRateEstimate, EstimatorError> {
+        let mut estimates = vec![];
+        for (target_blocks, confidence) in RATE_ESTIMATE_TARGETS {
+            match self.get_rate_estimate_for_target(target_blocks, confidence) {
+                Ok(estimate) => estimates.push(Some(estimate)),
+                Err(EstimatorError::NoEstimateAvailable) => estimates.push(None),
+                Err(e) => return Err(e),
+            }
+        }
+        let fallback = |i: usize| {
+            estimates[..i]
+                .iter()
+                .rev()
+                .chain(estimates[i + 1..].iter())
+                .find_map(|estimate| *estimate)
+        };
+        let estimate = |i: usize| {
+            estimates[i]
+                .or_else(|| fallback(i))
+                .ok_or(EstimatorError::NoEstimateAvailable)
+        };
+        let high = estimate(0)?;
+        let middle = estimate(1)?.min(high);
+        let low = estimate(2)?.min(middle);
+        Ok(FeeRateEstimate { high, middle, low })
+    }
+
+    fn get_rate_estimate_for_target(
+        &self,
+        target_blocks: u64,
+        confidence: f64,
+    ) -> Result<f64, EstimatorError> {
+        Self::get_rate_estimate_for_target_from_sql(&self.db, target_blocks, confidence)
+    }
+}
@@ -86,4 +86,14 @@ impl<T: FeeEstimator> FeeEstimator for FeeRateFuzzer<T> {
         let underlying_estimate = self.underlying.get_rate_estimates()?;
         Ok(self.fuzz_estimate(underlying_estimate))
     }
+
+    /// Just passes the request straight to `underlying`.
+    fn get_rate_estimate_for_target(
+        &self,
+        target_blocks: u64,
+        confidence: f64,
+    ) -> Result<f64, EstimatorError> {
+        self.underlying
+            .get_rate_estimate_for_target(target_blocks, confidence)
+    }
 }
@@ -14,6 +14,7 @@ use crate::chainstate::stacks::db::StacksEpochReceipt;
 use crate::chainstate::stacks::events::{StacksTransactionReceipt, TransactionOrigin};
 use crate::chainstate::stacks::{StacksBlock, TransactionPayload};
 
+pub mod fee_confirm_time;
 pub mod fee_medians;
 pub mod fee_rate_fuzzer;
 pub mod fee_scalar;
@@ -49,6 +50,16 @@ pub trait FeeEstimator {
     ) -> Result<(), EstimatorError>;
     /// Get the current estimates for fee rate
     fn get_rate_estimates(&self) -> Result<FeeRateEstimate, EstimatorError>;
+    /// Get the fee rate a transaction should pay to be confirmed within `target_blocks` blocks
+    ///  of its arrival in the mempool, with probability `confidence` (in `(0, 1]`).
+    ///  Estimators which do not track confirmation times have no such estimate.
+    fn get_rate_estimate_for_target(
+        &self,
+        _target_blocks: u64,
+        _confidence: f64,
+    ) -> Result<f64, EstimatorError> {
+        Err(EstimatorError::NoEstimateAvailable)
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
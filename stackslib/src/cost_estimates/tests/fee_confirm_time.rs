@@ -0,0 +1,371 @@
+use std::collections::HashMap;
+use std::env;
+
+use clarity::vm::costs::ExecutionCost;
+use clarity::vm::Value;
+use rand::Rng;
+use stacks_common::types::chainstate::StacksAddress;
+use stacks_common::util::hash::{to_hex, Hash160};
+
+use crate::burnchains::Txid;
+use crate::chainstate::stacks::events::{StacksTransactionReceipt, TransactionOrigin};
+use crate::chainstate::stacks::{
+    StacksTransaction, TransactionAuth, TransactionContractCall, TransactionPayload,
+    TransactionSpendingCondition, TransactionVersion,
+};
+use crate::cost_estimates::fee_confirm_time::ConfirmTimeFeeRateEstimator;
+use crate::cost_estimates::fee_medians::fee_rate_and_weight_from_receipt;
+use crate::cost_estimates::metrics::{CostMetric, ProportionalDotProduct};
+use crate::cost_estimates::tests::common::*;
+use crate::cost_estimates::{EstimatorError, FeeEstimator, FeeRateEstimate};
+
+fn instantiate_test_db<CM: CostMetric>(m: CM) -> ConfirmTimeFeeRateEstimator<CM> {
+    let mut path = env::temp_dir();
+    let random_bytes = rand::thread_rng().gen::<[u8; 32]>();
+    path.push(&format!("fee_db_{}.sqlite", &to_hex(&random_bytes)[0..8]));
+    let mut mempool_path = env::temp_dir();
+    mempool_path.push(&format!("mempool_{}.sqlite", &to_hex(&random_bytes)[0..8]));
+
+    let window_size = 10;
+    ConfirmTimeFeeRateEstimator::open(&path, &mempool_path, m, window_size)
+        .expect("Test failure: could not open fee rate DB")
+}
+
+fn make_dummy_cc_tx(fee: u64, nonce: u64) -> StacksTransactionReceipt {
+    let mut tx = StacksTransaction::new(
+        TransactionVersion::Mainnet,
+        TransactionAuth::Standard(TransactionSpendingCondition::new_initial_sighash()),
+        TransactionPayload::ContractCall(TransactionContractCall {
+            address: StacksAddress::new(0, Hash160([0; 20])).unwrap(),
+            contract_name: "cc-dummy".into(),
+            function_name: "func-name".into(),
+            function_args: vec![],
+        }),
+    );
+    tx.set_tx_fee(fee);
+    tx.set_origin_nonce(nonce);
+    StacksTransactionReceipt::from_contract_call(
+        tx,
+        vec![],
+        Value::okay(Value::Bool(true)).unwrap(),
+        0,
+        ExecutionCost {
+            write_length: 0,
+            write_count: 0,
+            read_length: 0,
+            read_count: 0,
+            runtime: 10,
+        },
+    )
+}
+
+fn receipt_txid(receipt: &StacksTransactionReceipt) -> Txid {
+    match receipt.transaction {
+        TransactionOrigin::Stacks(ref tx) => tx.txid(),
+        TransactionOrigin::Burn(_) => panic!("Test failure: expected a Stacks transaction"),
+    }
+}
+
+const block_limit: ExecutionCost = ExecutionCost {
+    write_length: 100,
+    write_count: 100,
+    read_length: 100,
+    read_count: 100,
+    runtime: 100,
+};
+
+/// Tests that we have no estimate available until enough transactions were confirmed.
+#[test]
+fn test_empty_fee_estimator() {
+    let metric = ProportionalDotProduct::new(10_000);
+    let mut estimator = instantiate_test_db(metric);
+    assert_eq!(
+        estimator
+            .get_rate_estimates()
+            .expect_err("Empty rate estimator should error."),
+        EstimatorError::NoEstimateAvailable
+    );
+
+    // a few transactions are not enough to judge by
+    let tx_receipts: Vec<_> = (0..5).map(|nonce| make_dummy_cc_tx(1000, nonce)).collect();
+    let accept_times = tx_receipts
+        .iter()
+        .map(|receipt| (receipt_txid(receipt), 1000))
+        .collect();
+    estimator
+        .record_confirmations(
+            &make_block_receipt(tx_receipts),
+            &block_limit,
+            &accept_times,
+            &[],
+            1010,
+        )
+        .expect("Should be able to process a block");
+    assert_eq!(
+        estimator
+            .get_rate_estimate_for_target(1, 0.95)
+            .expect_err("Estimator should need more confirmations."),
+        EstimatorError::NoEstimateAvailable
+    );
+}
+
+/// Tests that high-fee transactions which confirmed quickly and low-fee transactions which waited
+/// produce estimates per confirmation target, and that they are forgotten outside of the window.
+#[test]
+fn test_confirm_time_targets() {
+    let metric = ProportionalDotProduct::new(10_000);
+    let mut estimator = instantiate_test_db(ProportionalDotProduct::new(10_000));
+
+    // six empty blocks, 10 seconds apart
+    for i in 0..6 {
+        estimator
+            .record_confirmations(
+                &make_block_receipt(vec![]),
+                &block_limit,
+                &HashMap::new(),
+                &[],
+                1000 + 10 * i,
+            )
+            .expect("Should be able to process an empty block");
+    }
+
+    // the low-fee transactions arrived after the first block, and so waited 6 blocks.
+    // the high-fee transactions arrived just before the block they confirmed in.
+    let low_fee_txs: Vec<_> = (0..20).map(|nonce| make_dummy_cc_tx(1000, nonce)).collect();
+    let high_fee_txs: Vec<_> = (20..40)
+        .map(|nonce| make_dummy_cc_tx(10_000, nonce))
+        .collect();
+    let mut accept_times = HashMap::new();
+    for receipt in low_fee_txs.iter() {
+        accept_times.insert(receipt_txid(receipt), 1005);
+    }
+    for receipt in high_fee_txs.iter() {
+        accept_times.insert(receipt_txid(receipt), 1055);
+    }
+    // transactions which never were in the mempool are not counted
+    let unseen_tx = make_dummy_cc_tx(1, 40);
+
+    let low_fee_rate = fee_rate_and_weight_from_receipt(&metric, &low_fee_txs[0], &block_limit)
+        .unwrap()
+        .fee_rate;
+    let high_fee_rate = fee_rate_and_weight_from_receipt(&metric, &high_fee_txs[0], &block_limit)
+        .unwrap()
+        .fee_rate;
+
+    let mut tx_receipts = low_fee_txs;
+    tx_receipts.extend(high_fee_txs);
+    tx_receipts.push(unseen_tx);
+    estimator
+        .record_confirmations(
+            &make_block_receipt(tx_receipts),
+            &block_limit,
+            &accept_times,
+            &[],
+            1060,
+        )
+        .expect("Should be able to process a block");
+
+    assert_eq!(
+        estimator.get_rate_estimate_for_target(1, 0.95),
+        Ok(high_fee_rate)
+    );
+    assert_eq!(
+        estimator.get_rate_estimate_for_target(5, 0.95),
+        Ok(high_fee_rate)
+    );
+    assert_eq!(
+        estimator.get_rate_estimate_for_target(6, 0.95),
+        Ok(low_fee_rate)
+    );
+    assert_eq!(
+        estimator.get_rate_estimates(),
+        Ok(FeeRateEstimate {
+            high: high_fee_rate,
+            middle: high_fee_rate,
+            low: low_fee_rate,
+        })
+    );
+
+    // targets must be meaningful
+    assert!(estimator.get_rate_estimate_for_target(0, 0.95).is_err());
+    assert!(estimator.get_rate_estimate_for_target(1, 0.0).is_err());
+    assert!(estimator.get_rate_estimate_for_target(1, 1.5).is_err());
+
+    // once the block leaves the window, its confirmations are forgotten
+    for i in 0..10 {
+        estimator
+            .record_confirmations(
+                &make_block_receipt(vec![]),
+                &block_limit,
+                &HashMap::new(),
+                &[],
+                1070 + 10 * i,
+            )
+            .expect("Should be able to process an empty block");
+    }
+    assert_eq!(
+        estimator
+            .get_rate_estimate_for_target(6, 0.95)
+            .expect_err("Estimates should have left the window."),
+        EstimatorError::NoEstimateAvailable
+    );
+}
+
+/// Tests that transactions still waiting in the mempool count against the targets they have
+/// already missed, unless they were mined.
+#[test]
+fn test_waiting_txs_fail() {
+    let metric = ProportionalDotProduct::new(10_000);
+    let mut estimator = instantiate_test_db(ProportionalDotProduct::new(10_000));
+
+    // six empty blocks, 10 seconds apart
+    for i in 0..6 {
+        estimator
+            .record_confirmations(
+                &make_block_receipt(vec![]),
+                &block_limit,
+                &HashMap::new(),
+                &[],
+                1000 + 10 * i,
+            )
+            .expect("Should be able to process an empty block");
+    }
+
+    // high- and low-fee transactions confirm in the first block after they arrive, while
+    // middle-fee transactions have been waiting since after the first block
+    let high_fee_txs: Vec<_> = (0..20)
+        .map(|nonce| make_dummy_cc_tx(10_000, nonce))
+        .collect();
+    let low_fee_txs: Vec<_> = (20..40)
+        .map(|nonce| make_dummy_cc_tx(1000, nonce))
+        .collect();
+    let high_fee_rate = fee_rate_and_weight_from_receipt(&metric, &high_fee_txs[0], &block_limit)
+        .unwrap()
+        .fee_rate;
+    let low_fee_rate = fee_rate_and_weight_from_receipt(&metric, &low_fee_txs[0], &block_limit)
+        .unwrap()
+        .fee_rate;
+    let middle_fee_rate = (high_fee_rate + low_fee_rate) / 2.0;
+
+    let mut tx_receipts = high_fee_txs;
+    tx_receipts.extend(low_fee_txs);
+    let accept_times: HashMap<_, _> = tx_receipts
+        .iter()
+        .map(|receipt| (receipt_txid(receipt), 1055))
+        .collect();
+    let mut waiting: Vec<_> = (0..20u8)
+        .map(|i| (Txid([i; 32]), middle_fee_rate, 1005))
+        .collect();
+    // mined transactions stay in the mempool until garbage-collected
+    waiting.extend(
+        accept_times
+            .keys()
+            .map(|txid| (txid.clone(), high_fee_rate, 1055)),
+    );
+    estimator
+        .record_confirmations(
+            &make_block_receipt(tx_receipts),
+            &block_limit,
+            &accept_times,
+            &waiting,
+            1060,
+        )
+        .expect("Should be able to process a block");
+
+    // the waiting transactions missed 1- and 5-block targets, but could still make 6
+    assert_eq!(
+        estimator.get_rate_estimate_for_target(1, 0.95),
+        Ok(high_fee_rate)
+    );
+    assert_eq!(
+        estimator.get_rate_estimate_for_target(5, 0.95),
+        Ok(high_fee_rate)
+    );
+    assert_eq!(
+        estimator.get_rate_estimate_for_target(6, 0.95),
+        Ok(low_fee_rate)
+    );
+
+    // the mined transactions are not taken to be waiting as more blocks arrive
+    estimator
+        .record_confirmations(
+            &make_block_receipt(vec![]),
+            &block_limit,
+            &HashMap::new(),
+            &waiting,
+            1070,
+        )
+        .expect("Should be able to process an empty block");
+    assert_eq!(
+        estimator.get_rate_estimate_for_target(1, 0.95),
+        Ok(high_fee_rate)
+    );
+    assert_eq!(
+        estimator.get_rate_estimate_for_target(6, 0.95),
+        Ok(high_fee_rate)
+    );
+
+    // once they leave the mempool, the transactions are no longer counted
+    estimator
+        .record_confirmations(
+            &make_block_receipt(vec![]),
+            &block_limit,
+            &HashMap::new(),
+            &[],
+            1080,
+        )
+        .expect("Should be able to process an empty block");
+    assert_eq!(
+        estimator.get_rate_estimate_for_target(1, 0.95),
+        Ok(low_fee_rate)
+    );
+}
+
+/// Tests that a target without an estimate falls back to the others.
+#[test]
+fn test_rate_estimates_fallback() {
+    let metric = ProportionalDotProduct::new(10_000);
+    let mut estimator = instantiate_test_db(ProportionalDotProduct::new(10_000));
+
+    for i in 0..6 {
+        estimator
+            .record_confirmations(
+                &make_block_receipt(vec![]),
+                &block_limit,
+                &HashMap::new(),
+                &[],
+                1000 + 10 * i,
+            )
+            .expect("Should be able to process an empty block");
+    }
+
+    // every transaction waited 6 blocks, so only the 10-block target has an estimate
+    let tx_receipts: Vec<_> = (0..20).map(|nonce| make_dummy_cc_tx(1000, nonce)).collect();
+    let fee_rate = fee_rate_and_weight_from_receipt(&metric, &tx_receipts[0], &block_limit)
+        .unwrap()
+        .fee_rate;
+    let accept_times = tx_receipts
+        .iter()
+        .map(|receipt| (receipt_txid(receipt), 1005))
+        .collect();
+    estimator
+        .record_confirmations(
+            &make_block_receipt(tx_receipts),
+            &block_limit,
+            &accept_times,
+            &[],
+            1060,
+        )
+        .expect("Should be able to process a block");
+
+    assert!(estimator.get_rate_estimate_for_target(1, 0.95).is_err());
+    assert!(estimator.get_rate_estimate_for_target(3, 0.95).is_err());
+    assert_eq!(
+        estimator.get_rate_estimates(),
+        Ok(FeeRateEstimate {
+            high: fee_rate,
+            middle: fee_rate,
+            low: fee_rate,
+        })
+    );
+}
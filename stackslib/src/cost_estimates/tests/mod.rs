@@ -2,6 +2,7 @@ use crate::cost_estimates::FeeRateEstimate;
 
 pub mod common;
 pub mod cost_estimators;
+pub mod fee_confirm_time;
 pub mod fee_medians;
 pub mod fee_rate_fuzzer;
 pub mod fee_scalar;
@@ -37,7 +37,7 @@ use std::fs::File;
 use std::io::prelude::*;
 use std::io::BufReader;
 use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use std::{env, fs, io, process, thread};
 
 use blockstack_lib::burnchains::bitcoin::{spv, BitcoinNetworkType};
@@ -53,18 +53,27 @@ use blockstack_lib::chainstate::nakamoto::miner::NakamotoBlockBuilder;
 use blockstack_lib::chainstate::nakamoto::shadow::{
     process_shadow_block, shadow_chainstate_repair,
 };
-use blockstack_lib::chainstate::nakamoto::{NakamotoBlock, NakamotoChainState};
+use blockstack_lib::chainstate::nakamoto::{
+    NakamotoBlock, NakamotoBlockHeader, NakamotoChainState,
+};
 use blockstack_lib::chainstate::stacks::db::blocks::{DummyEventDispatcher, StagingBlock};
 use blockstack_lib::chainstate::stacks::db::{
     ChainStateBootData, StacksBlockHeaderTypes, StacksChainState,
 };
 use blockstack_lib::chainstate::stacks::index::marf::{MARFOpenOpts, MarfConnection, MARF};
+use blockstack_lib::chainstate::stacks::index::prune::prune_marf;
 use blockstack_lib::chainstate::stacks::index::ClarityMarfTrieId;
 use blockstack_lib::chainstate::stacks::miner::*;
 use blockstack_lib::chainstate::stacks::{StacksBlockHeader, *};
 use blockstack_lib::clarity::vm::costs::ExecutionCost;
-use blockstack_lib::clarity::vm::types::StacksAddressExtensions;
-use blockstack_lib::clarity::vm::ClarityVersion;
+use blockstack_lib::clarity::vm::database::sqlite::side_store_value_to_string;
+use blockstack_lib::clarity::vm::database::SqliteConnection;
+use blockstack_lib::clarity::vm::types::{
+    PrincipalData, QualifiedContractIdentifier, StacksAddressExtensions,
+};
+use blockstack_lib::clarity::vm::{ClarityName, ClarityVersion, Value as ClarityValue};
+use blockstack_lib::clarity_vm::proofs::{ClarityStateKey, ClarityStateValue};
+use blockstack_lib::config::DEFAULT_MAINNET_CONFIG;
 use blockstack_lib::core::{MemPoolDB, *};
 use blockstack_lib::cost_estimates::metrics::UnitMetric;
 use blockstack_lib::cost_estimates::UnitEstimator;
@@ -73,16 +82,19 @@ use blockstack_lib::net::db::LocalPeer;
 use blockstack_lib::net::httpcore::{send_http_request, StacksHttpRequest};
 use blockstack_lib::net::p2p::PeerNetwork;
 use blockstack_lib::net::relay::Relayer;
-use blockstack_lib::net::{GetNakamotoInvData, HandshakeData, StacksMessage, StacksMessageType};
+use blockstack_lib::net::{
+    GetNakamotoInvData, HandshakeData, StacksMessage, StacksMessageType, TipRequest,
+};
 use blockstack_lib::util_lib::db::sqlite_open;
 use blockstack_lib::util_lib::strings::UrlString;
 use blockstack_lib::{clarity_cli, cli};
+use libmarfproof::MarfProof;
 use libstackerdb::StackerDBChunkData;
 use rusqlite::{params, Connection, Error as SqliteError, OpenFlags};
 use serde_json::{json, Value};
 use stacks_common::codec::{read_next, StacksMessageCodec};
 use stacks_common::types::chainstate::{
-    BlockHeaderHash, BurnchainHeaderHash, StacksAddress, StacksBlockId,
+    BlockHeaderHash, BurnchainHeaderHash, StacksAddress, StacksBlockId, TrieHash,
 };
 use stacks_common::types::net::{PeerAddress, PeerHost};
 use stacks_common::types::sqlite::NO_PARAMS;
@@ -998,7 +1010,7 @@ check if the associated microblocks can be downloaded
         let mut stmt = conn.prepare(&query).unwrap();
         let mut rows = stmt.query(NO_PARAMS).unwrap();
         while let Ok(Some(row)) = rows.next() {
-            let val_string: String = row.get(0).unwrap();
+            let val_string = side_store_value_to_string(row.get_ref(0).unwrap()).unwrap();
             let clarity_value = match clarity::vm::Value::try_deserialize_hex_untyped(&val_string) {
                 Ok(x) => x,
                 Err(_e) => continue,
@@ -1009,6 +1021,224 @@ check if the associated microblocks can be downloaded
         process::exit(0);
     }
 
+    if argv[1] == "compact-clarity-side-store" {
+        if argv.len() < 3 {
+            eprintln!(
+                "Usage: {} compact-clarity-side-store CLARITY_MARF_SQLITE_PATH",
+                &argv[0]
+            );
+            process::exit(1);
+        }
+        // values written before the side store kept them as bytes are still read correctly,
+        //  so this only reclaims space, and may be interrupted and run again at any time.
+        let db_path = &argv[2];
+        let mut conn = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_WRITE)
+            .unwrap_or_else(|e| panic!("Failed to open {db_path}: {e:?}"));
+        let batch_size = 10_000;
+        let mut total = 0u64;
+        let mut last_rowid = 0;
+        loop {
+            let tx = conn.transaction().unwrap();
+            let (compacted, next_rowid) =
+                SqliteConnection::compact_data_table(&tx, last_rowid, batch_size).unwrap();
+            tx.commit().unwrap();
+            last_rowid = next_rowid;
+            total += u64::from(compacted);
+            eprintln!("Compacted {} values", total);
+            if compacted < batch_size {
+                break;
+            }
+        }
+        conn.execute_batch("VACUUM").unwrap();
+        println!("Compacted {} values in {}", total, db_path);
+        process::exit(0);
+    }
+
+    if argv[1] == "prune-marf" {
+        if argv.len() < 5 {
+            eprintln!(
+                "Usage: {} [--config FILE | --network NETWORK] prune-marf MARF_SQLITE_PATH TIP_INDEX_BLOCK_HASH KEEP_RECENT [SORTITION_DB_PATH]",
+                &argv[0]
+            );
+            process::exit(1);
+        }
+        // keeps the state of the most recent blocks, and of the PoX anchor blocks if the
+        //  sortition DB is given. The node must be stopped, and can't evaluate `at-block` in
+        //  pruned blocks afterwards.
+        let db_path = &argv[2];
+        let tip = StacksBlockId::from_hex(&argv[3])
+            .unwrap_or_else(|e| panic!("Failed to parse tip {}: {e:?}", &argv[3]));
+        let keep_recent: u32 = argv[4].parse().expect("Failed to parse KEEP_RECENT");
+        let anchors = match argv.get(5) {
+            Some(sortdb_path) => {
+                let conf = common_opts
+                    .config
+                    .as_ref()
+                    .unwrap_or(&DEFAULT_MAINNET_CONFIG);
+                let pox_constants = conf.get_burnchain().pox_constants;
+                let sort_db = SortitionDB::open(sortdb_path, false, pox_constants)
+                    .unwrap_or_else(|e| panic!("Failed to open {sortdb_path}: {e:?}"));
+                SortitionDB::get_pox_anchor_block_ids(sort_db.conn())
+                    .expect("Failed to load PoX anchor blocks")
+            }
+            None => HashSet::new(),
+        };
+        let stats = prune_marf(db_path, &tip, keep_recent, &anchors)
+            .unwrap_or_else(|e| panic!("Failed to prune {db_path}: {e:?}"));
+        println!(
+            "Kept {} tries and compacted {} to {} nodes; {} is now {} bytes (was {})",
+            stats.retained_tries,
+            stats.pruned_tries,
+            stats.kept_nodes,
+            db_path,
+            stats.blobs_size_after,
+            stats.blobs_size_before
+        );
+        process::exit(0);
+    }
+
+    if argv[1] == "verify-proof" {
+        let usage = format!(
+            "Usage: {} verify-proof NODE_HOST:PORT TIP_INDEX_BLOCK_HASH (data-var CONTRACT VAR_NAME | map-entry CONTRACT MAP_NAME KEY_HEX | stx-balance PRINCIPAL)",
+            &argv[0]
+        );
+        if argv.len() < 6 {
+            eprintln!("{usage}");
+            process::exit(1);
+        }
+        // checks a piece of Clarity state end-to-end against a trusted tip: the tip's header
+        //  must hash to the tip, and the node's proof must lead to the header's index root.
+        //  The ancestor tries which the proof passes through are found by walking back the
+        //  parent headers, so only Nakamoto blocks can be checked.
+        let (host, port) = argv[2]
+            .rsplit_once(':')
+            .and_then(|(host, port)| Some((host.to_string(), port.parse::<u16>().ok()?)))
+            .unwrap_or_else(|| panic!("Expected NODE_HOST:PORT, got {}", &argv[2]));
+        let tip = StacksBlockId::from_hex(&argv[3])
+            .unwrap_or_else(|e| panic!("Failed to parse tip {}: {e:?}", &argv[3]));
+        let parse_contract = |contract: &str| {
+            QualifiedContractIdentifier::parse(contract)
+                .unwrap_or_else(|e| panic!("Failed to parse contract {contract}: {e:?}"))
+        };
+        let parse_name = |name: &str| {
+            ClarityName::try_from(name.to_string())
+                .unwrap_or_else(|e| panic!("Failed to parse name {name}: {e:?}"))
+        };
+        let key = match (argv[4].as_str(), argv.len()) {
+            ("data-var", 7) => ClarityStateKey::DataVar {
+                contract: parse_contract(&argv[5]),
+                var_name: parse_name(&argv[6]),
+            },
+            ("map-entry", 8) => ClarityStateKey::MapEntry {
+                contract: parse_contract(&argv[5]),
+                map_name: parse_name(&argv[6]),
+                key: ClarityValue::try_deserialize_hex_untyped(&argv[7])
+                    .unwrap_or_else(|e| panic!("Failed to decode map key {}: {e:?}", &argv[7])),
+            },
+            ("stx-balance", 6) => ClarityStateKey::StxBalance(
+                PrincipalData::parse(&argv[5])
+                    .unwrap_or_else(|e| panic!("Failed to parse principal {}: {e:?}", &argv[5])),
+            ),
+            _ => {
+                eprintln!("{usage}");
+                process::exit(1);
+            }
+        };
+        let marf_key = key.marf_key().unwrap_or_else(|e| panic!("{e}"));
+        let marf_path = key.marf_path().unwrap_or_else(|e| panic!("{e}"));
+
+        let peer = PeerHost::from_host_port(host.clone(), port);
+        let timeout = Duration::from_secs(60);
+        // fetches a block's header along with the headers of its ancestors in the same tenure,
+        //  newest first, so walking back takes one request per tenure rather than per block
+        let fetch_headers = |block_id: &StacksBlockId| -> Result<Vec<NakamotoBlockHeader>, String> {
+            let blocks = send_http_request(
+                &host,
+                port,
+                StacksHttpRequest::new_get_nakamoto_tenure(peer.clone(), block_id.clone(), None),
+                timeout,
+            )
+            .map_err(|e| format!("Failed to query /v3/tenures/{block_id}: {e:?}"))?
+            .decode_nakamoto_tenure()
+            .map_err(|e| format!("Failed to decode the tenure of {block_id}: {e:?}"))?;
+            if blocks.is_empty() {
+                return Err(format!("Node returned no blocks for {block_id}"));
+            }
+            let mut expected = block_id.clone();
+            let mut headers = vec![];
+            for block in blocks {
+                if block.header.block_id() != expected {
+                    return Err(format!("Node returned a different block for {expected}"));
+                }
+                expected = block.header.parent_block_id.clone();
+                headers.push(block.header);
+            }
+            Ok(headers)
+        };
+        let tip_headers = fetch_headers(&tip).unwrap_or_else(|e| panic!("{e}"));
+        let index_root = tip_headers[0].state_index_root.clone();
+
+        let resp = send_http_request(
+            &host,
+            port,
+            StacksHttpRequest::new_getclaritymarf(
+                peer.clone(),
+                marf_path,
+                TipRequest::SpecificTip(tip.clone()),
+                true,
+            ),
+            timeout,
+        )
+        .unwrap_or_else(|e| panic!("Failed to query /v2/clarity/marf/{marf_path}: {e:?}"))
+        .decode_clarity_marf_response()
+        .unwrap_or_else(|e| panic!("Failed to load {marf_key} at {tip}: {e:?}"));
+        let proof = resp
+            .marf_proof
+            .as_deref()
+            .map(MarfProof::from_hex)
+            .unwrap_or_else(|| panic!("Node returned no proof for {marf_key}"))
+            .unwrap_or_else(|e| panic!("Failed to decode proof of {marf_key}: {e:?}"));
+
+        let learn_headers = |root_to_block: &mut HashMap<TrieHash, StacksBlockId>,
+                             next_parent: &mut Option<StacksBlockId>,
+                             headers: Vec<NakamotoBlockHeader>| {
+            for header in headers {
+                root_to_block.insert(header.state_index_root.clone(), header.block_id());
+                *next_parent = Some(header.parent_block_id);
+            }
+        };
+        let mut root_to_block = HashMap::new();
+        let mut next_parent = None;
+        learn_headers(&mut root_to_block, &mut next_parent, tip_headers);
+        let block_for_root = |root: &TrieHash| {
+            while !root_to_block.contains_key(root) {
+                let parent = next_parent.take()?;
+                match fetch_headers(&parent) {
+                    Ok(headers) => learn_headers(&mut root_to_block, &mut next_parent, headers),
+                    Err(e) => {
+                        eprintln!("Stopped walking back from {tip}: {e}");
+                        return None;
+                    }
+                }
+            }
+            root_to_block.get(root).cloned()
+        };
+        let valid = key
+            .verify(&resp.data, &proof, &index_root, block_for_root)
+            .unwrap_or_else(|e| panic!("{e}"));
+        if !valid {
+            eprintln!("Proof of {marf_key} at {tip} (index root {index_root}) is INVALID");
+            process::exit(1);
+        }
+        let value = match key.decode_data(&resp.data) {
+            Ok(ClarityStateValue::Value(value)) => value.to_string(),
+            Ok(ClarityStateValue::StxBalance(balance)) => format!("{balance:?}"),
+            Err(e) => panic!("{e}"),
+        };
+        println!("Proof of {marf_key} at {tip} (index root {index_root}) is valid: {value}");
+        process::exit(0);
+    }
+
     if argv[1] == "check-deser-data" {
         if argv.len() < 3 {
             eprintln!("Usage: {} check-file.txt", &argv[0]);
@@ -1431,6 +1661,8 @@ check if the associated microblocks can be downloaded
 
         let mut known_stacks_blocks = HashSet::new();
         let mut next_arrival = 0;
+        let mut num_processed_blocks = 0usize;
+        let replay_start = Instant::now();
 
         let epochs = StacksEpoch::all(first_burnchain_block_height, u64::MAX, u64::MAX);
 
@@ -1587,6 +1819,10 @@ check if the associated microblocks can be downloaded
                 if receipts.is_empty() {
                     break;
                 }
+                num_processed_blocks += receipts
+                    .iter()
+                    .filter(|(receipt, _)| receipt.is_some())
+                    .count();
             }
         }
 
@@ -1595,6 +1831,13 @@ check if the associated microblocks can be downloaded
             next_arrival,
             stacks_blocks_arrival_order.len()
         );
+        let elapsed = replay_start.elapsed().as_secs_f64();
+        println!(
+            "Processed {} blocks in {:.3}s ({:.3} blocks/s)",
+            num_processed_blocks,
+            elapsed,
+            num_processed_blocks as f64 / elapsed
+        );
         return;
     }
 
@@ -1685,7 +1928,12 @@ simulating a miner.
     info!("Clearing mempool");
     let min_height = u32::MAX as u64;
     mempool_db
-        .garbage_collect(min_height, &MempoolCollectionBehavior::ByStacksHeight, None)
+        .garbage_collect(
+            min_height,
+            &MempoolCollectionBehavior::ByStacksHeight,
+            None,
+            |_| 0,
+        )
         .unwrap();
 
     let header_tip = NakamotoChainState::get_canonical_block_header(chain_state.db(), &sort_db)
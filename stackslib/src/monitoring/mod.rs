@@ -23,12 +23,16 @@ use std::{fmt, fs};
 use clarity::vm::costs::ExecutionCost;
 use lazy_static::lazy_static;
 use rusqlite::{OpenFlags, OptionalExtension};
+use stacks_common::types::chainstate::StacksBlockId;
 use stacks_common::types::sqlite::NO_PARAMS;
 use stacks_common::util::get_epoch_time_secs;
 use stacks_common::util::uint::{Uint256, Uint512};
 
 use crate::burnchains::{BurnchainSigner, Txid};
-use crate::core::MemPoolDB;
+use crate::chainstate::burn::db::sortdb::SortitionDB;
+use crate::chainstate::stacks::db::StacksChainState;
+use crate::core::mempool::MemPoolStats;
+use crate::core::{MemPoolDB, StacksEpoch};
 use crate::net::httpcore::{StacksHttpRequest, StacksHttpResponse};
 use crate::net::rpc::ConversationHttp;
 use crate::net::Error as net_error;
@@ -282,6 +286,80 @@ pub fn log_transaction_processed(
     Ok(())
 }
 
+/// Log a summary of the mempool.
+#[allow(unused_variables)]
+pub fn set_mempool_stats(stats: &MemPoolStats) {
+    #[cfg(feature = "monitoring_prom")]
+    {
+        let to_i64 = |value: u64| i64::try_from(value).unwrap_or(i64::MAX);
+        prometheus::MEMPOOL_TX_COUNT.set(to_i64(stats.tx_count));
+        prometheus::MEMPOOL_SIZE_BYTES.set(to_i64(stats.total_bytes));
+        prometheus::MEMPOOL_OLDEST_TX_AGE.set(to_i64(stats.oldest_tx_age_secs.unwrap_or(0)));
+
+        for (tx_type, count) in [
+            ("TokenTransfer", stats.tx_types.token_transfer),
+            ("SmartContract", stats.tx_types.smart_contract),
+            ("ContractCall", stats.tx_types.contract_call),
+            ("Other", stats.tx_types.other),
+        ] {
+            prometheus::MEMPOOL_TX_TYPE_COUNT
+                .with_label_values(&[tx_type])
+                .set(to_i64(count));
+        }
+
+        // buckets come and go, so drop the ones no longer in the mempool
+        prometheus::MEMPOOL_FEE_RATE_BUCKET_TXS.reset();
+        prometheus::MEMPOOL_FEE_RATE_BUCKET_BYTES.reset();
+        prometheus::MEMPOOL_FEE_RATE_BUCKET_BLOCKS_TO_CLEAR.reset();
+        let by_cost = &stats.fee_rate_by_cost;
+        for (dimension, histogram) in [
+            ("fee_per_byte", &stats.fee_per_byte),
+            ("fee_rate", &stats.fee_rate),
+            ("runtime", &by_cost.runtime),
+            ("read_count", &by_cost.read_count),
+            ("read_length", &by_cost.read_length),
+            ("write_count", &by_cost.write_count),
+            ("write_length", &by_cost.write_length),
+        ] {
+            for bucket in histogram.buckets.iter() {
+                let min_fee_rate = bucket.min_fee_rate.to_string();
+                let labels = [dimension, min_fee_rate.as_str()];
+                prometheus::MEMPOOL_FEE_RATE_BUCKET_TXS
+                    .with_label_values(&labels)
+                    .set(to_i64(bucket.tx_count));
+                prometheus::MEMPOOL_FEE_RATE_BUCKET_BYTES
+                    .with_label_values(&labels)
+                    .set(to_i64(bucket.total_bytes));
+                prometheus::MEMPOOL_FEE_RATE_BUCKET_BLOCKS_TO_CLEAR
+                    .with_label_values(&labels)
+                    .set(to_i64(bucket.blocks_to_clear));
+            }
+        }
+    }
+}
+
+/// Refresh the mempool summary gauges, if the mempool's summary is due to be refreshed (see
+/// `MemPoolDB::get_stats_snapshot`)
+#[allow(unused_variables)]
+pub fn update_mempool_stats(
+    mempool: &mut MemPoolDB,
+    chainstate: &mut StacksChainState,
+    sortdb: &SortitionDB,
+    tip: &StacksBlockId,
+    stacks_epoch: &StacksEpoch,
+) {
+    #[cfg(feature = "monitoring_prom")]
+    if let Err(e) = mempool.get_stats_snapshot(
+        chainstate,
+        sortdb,
+        tip,
+        &stacks_epoch.block_limit,
+        &stacks_epoch.epoch_id,
+    ) {
+        warn!("Failed to summarize mempool: {e:?}");
+    }
+}
+
 #[allow(unused_variables)]
 pub fn update_active_miners_count_gauge(value: i64) {
     #[cfg(feature = "monitoring_prom")]
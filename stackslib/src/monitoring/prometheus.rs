@@ -17,8 +17,9 @@
 use lazy_static::lazy_static;
 use prometheus::{
     histogram_opts, labels, opts, register_gauge, register_histogram, register_histogram_vec,
-    register_int_counter, register_int_counter_vec, register_int_gauge, Gauge, Histogram,
-    HistogramTimer, HistogramVec, IntCounter, IntCounterVec, IntGauge,
+    register_int_counter, register_int_counter_vec, register_int_gauge, register_int_gauge_vec,
+    Gauge, Histogram, HistogramTimer, HistogramVec, IntCounter, IntCounterVec, IntGauge,
+    IntGaugeVec,
 };
 
 lazy_static! {
@@ -230,6 +231,45 @@ lazy_static! {
         labels! {"handler".to_string() => "all".to_string(),}
     )).unwrap();
 
+    pub static ref MEMPOOL_TX_COUNT: IntGauge = register_int_gauge!(opts!(
+        "stacks_node_mempool_tx_count",
+        "Number of transactions held in the mempool"
+    )).unwrap();
+
+    pub static ref MEMPOOL_SIZE_BYTES: IntGauge = register_int_gauge!(opts!(
+        "stacks_node_mempool_size_bytes",
+        "Total size in bytes of the transactions held in the mempool"
+    )).unwrap();
+
+    pub static ref MEMPOOL_OLDEST_TX_AGE: IntGauge = register_int_gauge!(opts!(
+        "stacks_node_mempool_oldest_tx_age_seconds",
+        "Time (seconds) since the oldest transaction in the mempool was received"
+    )).unwrap();
+
+    pub static ref MEMPOOL_TX_TYPE_COUNT: IntGaugeVec = register_int_gauge_vec!(
+        "stacks_node_mempool_tx_type_count",
+        "Number of transactions held in the mempool by type",
+        &["type"]
+    ).unwrap();
+
+    pub static ref MEMPOOL_FEE_RATE_BUCKET_TXS: IntGaugeVec = register_int_gauge_vec!(
+        "stacks_node_mempool_fee_rate_bucket_txs",
+        "Number of transactions held in the mempool by fee rate bucket",
+        &["dimension", "min_fee_rate"]
+    ).unwrap();
+
+    pub static ref MEMPOOL_FEE_RATE_BUCKET_BYTES: IntGaugeVec = register_int_gauge_vec!(
+        "stacks_node_mempool_fee_rate_bucket_bytes",
+        "Total size in bytes of the transactions held in the mempool by fee rate bucket",
+        &["dimension", "min_fee_rate"]
+    ).unwrap();
+
+    pub static ref MEMPOOL_FEE_RATE_BUCKET_BLOCKS_TO_CLEAR: IntGaugeVec = register_int_gauge_vec!(
+        "stacks_node_mempool_fee_rate_bucket_blocks_to_clear",
+        "Estimated number of blocks to mine the mempool's transactions at or above a fee rate bucket",
+        &["dimension", "min_fee_rate"]
+    ).unwrap();
+
     pub static ref COMPUTED_RELATIVE_MINER_SCORE: Gauge = register_gauge!(opts!(
         "stacks_node_computed_relative_miner_score",
         "Percentage of the u256 range that this miner is assigned in a particular round of sortition"
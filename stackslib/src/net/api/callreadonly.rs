@@ -16,7 +16,7 @@
 
 use std::io::{Read, Write};
 
-use clarity::vm::analysis::CheckErrors;
+use clarity::vm::analysis::{AnalysisDatabase, CheckErrors};
 use clarity::vm::ast::parser::v1::CLARITY_NAME_REGEX;
 use clarity::vm::clarity::ClarityConnection;
 use clarity::vm::costs::{ExecutionCost, LimitedCostTracker};
@@ -27,14 +27,14 @@ use clarity::vm::representations::{
     CONTRACT_NAME_REGEX_STRING, PRINCIPAL_DATA_REGEX_STRING, STANDARD_PRINCIPAL_REGEX_STRING,
 };
 use clarity::vm::types::{
-    PrincipalData, QualifiedContractIdentifier, StandardPrincipalData,
+    FixedFunction, FunctionType, PrincipalData, QualifiedContractIdentifier, StandardPrincipalData,
     BOUND_VALUE_SERIALIZATION_HEX,
 };
 use clarity::vm::{ClarityName, ClarityVersion, ContractName, SymbolicExpression, Value};
 use regex::{Captures, Regex};
 use stacks_common::types::chainstate::{StacksAddress, StacksBlockId};
 use stacks_common::types::net::PeerHost;
-use stacks_common::types::Address;
+use stacks_common::types::{Address, StacksEpochId};
 use stacks_common::util::hash::{to_hex, Sha256Sum};
 
 use crate::burnchains::Burnchain;
@@ -61,7 +61,14 @@ pub struct CallReadOnlyRequestBody {
     pub sender: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sponsor: Option<String>,
+    /// Hex-encoded arguments
+    #[serde(default)]
     pub arguments: Vec<String>,
+    /// Arguments in the JSON representation of Clarity values, which is
+    ///  decoded against the function's signature. Replaces `arguments`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json_arguments: Option<Vec<serde_json::Value>>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -86,6 +93,8 @@ pub struct RPCCallReadOnlyRequestHandler {
     pub sender: Option<PrincipalData>,
     pub sponsor: Option<PrincipalData>,
     pub arguments: Option<Vec<Value>>,
+    /// JSON arguments, which are decoded once the function's signature is loaded
+    pub json_arguments: Option<Vec<serde_json::Value>>,
 }
 
 impl RPCCallReadOnlyRequestHandler {
@@ -98,10 +107,52 @@ impl RPCCallReadOnlyRequestHandler {
             sender: None,
             sponsor: None,
             arguments: None,
+            json_arguments: None,
         }
     }
 }
 
+/// Decode JSON arguments against the signature of the public or read-only function
+///  which will be called
+pub(crate) fn decode_json_arguments(
+    analysis_db: &mut AnalysisDatabase,
+    contract_identifier: &QualifiedContractIdentifier,
+    function: &str,
+    epoch: &StacksEpochId,
+    arguments: &[serde_json::Value],
+) -> Result<Vec<Value>, String> {
+    let function_type = match analysis_db
+        .get_read_only_function_type(contract_identifier, function, epoch)
+        .map_err(|e| e.to_string())?
+    {
+        Some(function_type) => Some(function_type),
+        None => analysis_db
+            .get_public_function_type(contract_identifier, function, epoch)
+            .map_err(|e| e.to_string())?,
+    };
+    let Some(FunctionType::Fixed(FixedFunction { args, .. })) = function_type else {
+        return Err(format!(
+            "No public or read-only function `{}` in {}",
+            function, contract_identifier
+        ));
+    };
+    if args.len() != arguments.len() {
+        return Err(format!(
+            "`{}` expects {} arguments, but {} were given",
+            function,
+            args.len(),
+            arguments.len()
+        ));
+    }
+    args.iter()
+        .zip(arguments)
+        .map(|(arg, json)| {
+            Value::from_json(json, &arg.signature)
+                .map_err(|e| format!("Failed to decode argument `{}`: {}", arg.name, e))
+        })
+        .collect()
+}
+
 /// Decode the HTTP request
 impl HttpRequest for RPCCallReadOnlyRequestHandler {
     fn verb(&self) -> &'static str {
@@ -160,18 +211,34 @@ impl HttpRequest for RPCCallReadOnlyRequestHandler {
         };
 
         // arguments must be valid Clarity values
-        let arguments = body
-            .arguments
-            .into_iter()
-            .map(|hex| Value::try_deserialize_hex_untyped(&hex).ok())
-            .collect::<Option<Vec<Value>>>()
-            .ok_or_else(|| Error::DecodeError("Failed to deserialize argument value".into()))?;
+        let (arguments, json_arguments) = match body.json_arguments {
+            Some(json_arguments) => {
+                if !body.arguments.is_empty() {
+                    return Err(Error::DecodeError(
+                        "Expected either `arguments` or `json_arguments`, not both".into(),
+                    ));
+                }
+                (None, Some(json_arguments))
+            }
+            None => {
+                let arguments = body
+                    .arguments
+                    .into_iter()
+                    .map(|hex| Value::try_deserialize_hex_untyped(&hex).ok())
+                    .collect::<Option<Vec<Value>>>()
+                    .ok_or_else(|| {
+                        Error::DecodeError("Failed to deserialize argument value".into())
+                    })?;
+                (Some(arguments), None)
+            }
+        };
 
         self.contract_identifier = Some(contract_identifier);
         self.function = Some(function);
         self.sender = Some(sender);
         self.sponsor = sponsor;
-        self.arguments = Some(arguments);
+        self.arguments = arguments;
+        self.json_arguments = json_arguments;
 
         Ok(HttpRequestContents::new().query_string(query))
     }
@@ -186,6 +253,7 @@ impl RPCRequestHandler for RPCCallReadOnlyRequestHandler {
         self.sender = None;
         self.sponsor = None;
         self.arguments = None;
+        self.json_arguments = None;
     }
 
     /// Make the response
@@ -215,10 +283,49 @@ impl RPCRequestHandler for RPCCallReadOnlyRequestHandler {
             .take()
             .ok_or(NetError::SendError("Missing `sender`".into()))?;
         let sponsor = self.sponsor.clone();
-        let arguments = self
-            .arguments
-            .take()
-            .ok_or(NetError::SendError("Missing `arguments`".into()))?;
+        let arguments = match self.json_arguments.take() {
+            Some(json_arguments) => {
+                let arguments =
+                    node.with_node_state(|_network, sortdb, chainstate, _mempool, _rpc_args| {
+                        chainstate.maybe_read_only_clarity_tx(
+                            &sortdb.index_handle_at_block(chainstate, &tip)?,
+                            &tip,
+                            |clarity_tx| {
+                                let epoch = clarity_tx.get_epoch();
+                                clarity_tx.with_analysis_db_readonly(|analysis_db| {
+                                    decode_json_arguments(
+                                        analysis_db,
+                                        &contract_identifier,
+                                        function.as_str(),
+                                        &epoch,
+                                        &json_arguments,
+                                    )
+                                })
+                            },
+                        )
+                    });
+                match arguments {
+                    Ok(Some(Ok(arguments))) => arguments,
+                    Ok(Some(Err(msg))) => {
+                        return StacksHttpResponse::new_error(&preamble, &HttpBadRequest::new(msg))
+                            .try_into_contents()
+                            .map_err(NetError::from);
+                    }
+                    Ok(None) | Err(_) => {
+                        return StacksHttpResponse::new_error(
+                            &preamble,
+                            &HttpNotFound::new("Chain tip not found".to_string()),
+                        )
+                        .try_into_contents()
+                        .map_err(NetError::from);
+                    }
+                }
+            }
+            None => self
+                .arguments
+                .take()
+                .ok_or(NetError::SendError("Missing `arguments`".into()))?,
+        };
 
         // run the read-only call
         let data_resp =
@@ -368,6 +475,39 @@ impl StacksHttpRequest {
                     sender: sender.to_string(),
                     sponsor: sponsor.map(|s| s.to_string()),
                     arguments: function_args.into_iter().map(|v| v.to_string()).collect(),
+                    json_arguments: None,
+                })
+                .expect("FATAL: failed to encode infallible data"),
+            ),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+
+    /// Make a new request to run a read-only function, with arguments in the
+    ///  JSON representation of Clarity values
+    pub fn new_callreadonlyfunction_json(
+        host: PeerHost,
+        contract_addr: StacksAddress,
+        contract_name: ContractName,
+        sender: PrincipalData,
+        sponsor: Option<PrincipalData>,
+        function_name: ClarityName,
+        function_args: Vec<serde_json::Value>,
+        tip_req: TipRequest,
+    ) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "POST".into(),
+            format!(
+                "/v2/contracts/call-read/{}/{}/{}",
+                &contract_addr, &contract_name, &function_name
+            ),
+            HttpRequestContents::new().for_tip(tip_req).payload_json(
+                serde_json::to_value(CallReadOnlyRequestBody {
+                    sender: sender.to_string(),
+                    sponsor: sponsor.map(|s| s.to_string()),
+                    arguments: vec![],
+                    json_arguments: Some(function_args),
                 })
                 .expect("FATAL: failed to encode infallible data"),
             ),
@@ -0,0 +1,700 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use clarity::vm::analysis::CheckErrors;
+use clarity::vm::clarity::ClarityConnection;
+use clarity::vm::costs::{ExecutionCost, LimitedCostTracker};
+use clarity::vm::database::{ClarityDatabase, StoreType};
+use clarity::vm::errors::Error::Unchecked;
+use clarity::vm::types::{PrincipalData, QualifiedContractIdentifier};
+use clarity::vm::{ClarityName, SymbolicExpression, Value};
+use regex::{Captures, Regex};
+use stacks_common::types::chainstate::StacksBlockId;
+use stacks_common::types::net::PeerHost;
+use stacks_common::util::hash::to_hex;
+
+use crate::net::api::callreadonly::decode_json_arguments;
+use crate::net::http::{
+    parse_json, Error, HttpContentType, HttpNotFound, HttpRequest, HttpRequestContents,
+    HttpRequestPreamble, HttpResponse, HttpResponseContents, HttpResponsePayload,
+    HttpResponsePreamble,
+};
+use crate::net::httpcore::{
+    HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler, StacksHttpRequest,
+    StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState, TipRequest};
+
+/// The most items a batch may contain
+pub const MAX_BATCH_READ_ITEMS: usize = 100;
+
+/// One read of a batch, as it appears in the request body
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BatchReadItem {
+    /// Call a public or read-only function, as `/v2/contracts/call-read` does
+    Call {
+        contract: String,
+        function: String,
+        /// Hex-encoded arguments
+        #[serde(default)]
+        arguments: Vec<String>,
+        /// Arguments in the JSON representation of Clarity values. Replaces `arguments`.
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        json_arguments: Option<Vec<serde_json::Value>>,
+    },
+    /// Read a data-var, as `/v2/data_var` does
+    DataVar {
+        contract: String,
+        name: String,
+        #[serde(default)]
+        proof: bool,
+    },
+    /// Look up a map entry, as `/v2/map_entry` does
+    MapEntry {
+        contract: String,
+        name: String,
+        /// Hex-encoded key
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        key: Option<String>,
+        /// The key in the JSON representation of Clarity values. Replaces `key`.
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        json_key: Option<serde_json::Value>,
+        #[serde(default)]
+        proof: bool,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchReadRequestBody {
+    pub sender: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sponsor: Option<String>,
+    pub items: Vec<BatchReadItem>,
+}
+
+/// A `BatchReadItem` whose names and hex-encoded values have been decoded
+#[derive(Debug, Clone, PartialEq)]
+pub enum BatchRead {
+    Call {
+        contract_identifier: QualifiedContractIdentifier,
+        function: ClarityName,
+        arguments: Option<Vec<Value>>,
+        json_arguments: Option<Vec<serde_json::Value>>,
+    },
+    DataVar {
+        contract_identifier: QualifiedContractIdentifier,
+        name: ClarityName,
+        with_proof: bool,
+    },
+    MapEntry {
+        contract_identifier: QualifiedContractIdentifier,
+        name: ClarityName,
+        key: Option<Value>,
+        json_key: Option<serde_json::Value>,
+        with_proof: bool,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchReadResult {
+    pub okay: bool,
+    /// The hex-encoded value which was read or returned
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<String>,
+    #[serde(rename = "proof")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub marf_proof: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cause: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchReadResponse {
+    /// The block every item was evaluated at
+    pub tip: StacksBlockId,
+    /// The cost of the whole batch, which is bounded by the node's read-only call limit
+    pub cost: ExecutionCost,
+    /// The result of each item, in the order of the request
+    pub results: Vec<BatchReadResult>,
+}
+
+impl BatchReadResult {
+    fn ok(result: String, marf_proof: Option<String>) -> Self {
+        Self {
+            okay: true,
+            result: Some(format!("0x{}", result)),
+            marf_proof,
+            cause: None,
+        }
+    }
+
+    fn err(cause: String) -> Self {
+        Self {
+            okay: false,
+            result: None,
+            marf_proof: None,
+            cause: Some(cause),
+        }
+    }
+}
+
+fn parse_contract_identifier(contract: &str) -> Result<QualifiedContractIdentifier, Error> {
+    QualifiedContractIdentifier::parse(contract)
+        .map_err(|_e| Error::DecodeError(format!("Failed to parse contract `{}`", contract)))
+}
+
+fn parse_clarity_name(name: String) -> Result<ClarityName, Error> {
+    ClarityName::try_from(name.clone())
+        .map_err(|_e| Error::DecodeError(format!("Invalid Clarity name `{}`", name)))
+}
+
+impl TryFrom<BatchReadItem> for BatchRead {
+    type Error = Error;
+
+    fn try_from(item: BatchReadItem) -> Result<Self, Error> {
+        match item {
+            BatchReadItem::Call {
+                contract,
+                function,
+                arguments,
+                json_arguments,
+            } => {
+                let arguments = match json_arguments {
+                    Some(_) if !arguments.is_empty() => {
+                        return Err(Error::DecodeError(
+                            "Expected either `arguments` or `json_arguments`, not both".into(),
+                        ));
+                    }
+                    Some(_) => None,
+                    None => Some(
+                        arguments
+                            .iter()
+                            .map(|hex| Value::try_deserialize_hex_untyped(hex).ok())
+                            .collect::<Option<Vec<Value>>>()
+                            .ok_or_else(|| {
+                                Error::DecodeError("Failed to deserialize argument value".into())
+                            })?,
+                    ),
+                };
+                Ok(BatchRead::Call {
+                    contract_identifier: parse_contract_identifier(&contract)?,
+                    function: parse_clarity_name(function)?,
+                    arguments,
+                    json_arguments,
+                })
+            }
+            BatchReadItem::DataVar {
+                contract,
+                name,
+                proof,
+            } => Ok(BatchRead::DataVar {
+                contract_identifier: parse_contract_identifier(&contract)?,
+                name: parse_clarity_name(name)?,
+                with_proof: proof,
+            }),
+            BatchReadItem::MapEntry {
+                contract,
+                name,
+                key,
+                json_key,
+                proof,
+            } => {
+                let key = match (key, &json_key) {
+                    (Some(hex), None) => {
+                        Some(Value::try_deserialize_hex_untyped(&hex).map_err(|_e| {
+                            Error::DecodeError("Failed to deserialize key value".into())
+                        })?)
+                    }
+                    (None, Some(_)) => None,
+                    _ => {
+                        return Err(Error::DecodeError(
+                            "Expected exactly one of `key` or `json_key`".into(),
+                        ))
+                    }
+                };
+                Ok(BatchRead::MapEntry {
+                    contract_identifier: parse_contract_identifier(&contract)?,
+                    name: parse_clarity_name(name)?,
+                    key,
+                    json_key,
+                    with_proof: proof,
+                })
+            }
+        }
+    }
+}
+
+/// Evaluates the items of a batch against one chain state, charging each
+///  of them to a shared cost budget
+struct BatchEvaluator {
+    mainnet: bool,
+    chain_id: u32,
+    sender: PrincipalData,
+    sponsor: Option<PrincipalData>,
+    budget: ExecutionCost,
+    consumed: ExecutionCost,
+}
+
+impl BatchEvaluator {
+    /// What is left of the budget, if anything
+    fn remaining(&self) -> Option<ExecutionCost> {
+        let mut remaining = self.budget.clone();
+        remaining.sub(&self.consumed).ok()?;
+        Some(remaining)
+    }
+
+    /// Charge `cost` to the budget. Once the budget is exceeded, every
+    ///  following item fails.
+    fn charge(&mut self, cost: &ExecutionCost) {
+        if self.consumed.add(cost).is_err() {
+            self.consumed = ExecutionCost::max_value();
+        }
+    }
+
+    fn evaluate<C: ClarityConnection>(
+        &mut self,
+        clarity_tx: &mut C,
+        read: BatchRead,
+    ) -> BatchReadResult {
+        if self.remaining().is_none() {
+            return BatchReadResult::err("CostBalanceExceeded".into());
+        }
+        match read {
+            BatchRead::Call {
+                contract_identifier,
+                function,
+                arguments,
+                json_arguments,
+            } => {
+                let arguments = match (arguments, json_arguments) {
+                    (_, Some(json_arguments)) => {
+                        let epoch = clarity_tx.get_epoch();
+                        let decoded = clarity_tx.with_analysis_db_readonly(|analysis_db| {
+                            decode_json_arguments(
+                                analysis_db,
+                                &contract_identifier,
+                                function.as_str(),
+                                &epoch,
+                                &json_arguments,
+                            )
+                        });
+                        match decoded {
+                            Ok(arguments) => arguments,
+                            Err(msg) => return BatchReadResult::err(msg),
+                        }
+                    }
+                    (Some(arguments), None) => arguments,
+                    (None, None) => vec![],
+                };
+                self.call(clarity_tx, &contract_identifier, &function, arguments)
+            }
+            BatchRead::DataVar {
+                contract_identifier,
+                name,
+                with_proof,
+            } => {
+                let key = ClarityDatabase::make_key_for_trip(
+                    &contract_identifier,
+                    StoreType::Variable,
+                    &name,
+                );
+                match self.read(clarity_tx, &key, with_proof) {
+                    Ok(Some((value_hex, marf_proof))) => BatchReadResult::ok(value_hex, marf_proof),
+                    Ok(None) => BatchReadResult::err("Data var not found".into()),
+                    Err(cause) => BatchReadResult::err(cause),
+                }
+            }
+            BatchRead::MapEntry {
+                contract_identifier,
+                name,
+                key,
+                json_key,
+                with_proof,
+            } => {
+                let key = match (key, json_key) {
+                    (_, Some(json_key)) => {
+                        let decoded = clarity_tx.with_clarity_db_readonly(|clarity_db| {
+                            let map =
+                                clarity_db
+                                    .load_map(&contract_identifier, &name)
+                                    .map_err(|_| {
+                                        format!("No map `{}` in {}", &name, &contract_identifier)
+                                    })?;
+                            Value::from_json(&json_key, &map.key_type)
+                                .map_err(|e| format!("Failed to decode the map key: {}", e))
+                        });
+                        match decoded {
+                            Ok(key) => key,
+                            Err(msg) => return BatchReadResult::err(msg),
+                        }
+                    }
+                    (Some(key), None) => key,
+                    (None, None) => return BatchReadResult::err("Missing map key".into()),
+                };
+                let key = match ClarityDatabase::make_key_for_data_map_entry(
+                    &contract_identifier,
+                    &name,
+                    &key,
+                ) {
+                    Ok(key) => key,
+                    Err(e) => return BatchReadResult::err(e.to_string()),
+                };
+                match self.read(clarity_tx, &key, with_proof) {
+                    Ok(Some((value_hex, marf_proof))) => BatchReadResult::ok(value_hex, marf_proof),
+                    // as in `/v2/map_entry`, a missing entry reads as `none`
+                    Ok(None) => match Value::none().serialize_to_hex() {
+                        Ok(none_hex) => BatchReadResult::ok(none_hex, with_proof.then(String::new)),
+                        Err(e) => BatchReadResult::err(format!("{e:?}")),
+                    },
+                    Err(cause) => BatchReadResult::err(cause),
+                }
+            }
+        }
+    }
+
+    /// Run a function without writes, under what is left of the budget
+    fn call<C: ClarityConnection>(
+        &mut self,
+        clarity_tx: &mut C,
+        contract_identifier: &QualifiedContractIdentifier,
+        function: &ClarityName,
+        arguments: Vec<Value>,
+    ) -> BatchReadResult {
+        let Some(mut cost_limit) = self.remaining() else {
+            return BatchReadResult::err("CostBalanceExceeded".into());
+        };
+        cost_limit.write_length = 0;
+        cost_limit.write_count = 0;
+
+        let (mainnet, chain_id) = (self.mainnet, self.chain_id);
+        let epoch = clarity_tx.get_epoch();
+        let Ok(cost_track) = clarity_tx.with_clarity_db_readonly(|clarity_db| {
+            LimitedCostTracker::new_mid_block(mainnet, chain_id, cost_limit, clarity_db, epoch)
+        }) else {
+            return BatchReadResult::err("CostContractLoadFailure".into());
+        };
+        let Ok(clarity_version) = clarity_tx.with_analysis_db_readonly(|analysis_db| {
+            analysis_db.get_clarity_version(contract_identifier)
+        }) else {
+            return BatchReadResult::err(
+                CheckErrors::NoSuchContract(contract_identifier.to_string()).to_string(),
+            );
+        };
+
+        let args: Vec<_> = arguments
+            .into_iter()
+            .map(SymbolicExpression::atom_value)
+            .collect();
+        let mut cost = ExecutionCost::ZERO;
+        let result = clarity_tx.with_readonly_clarity_env(
+            mainnet,
+            chain_id,
+            clarity_version,
+            self.sender.clone(),
+            self.sponsor.clone(),
+            cost_track,
+            |env| {
+                // as in `/v2/contracts/call-read`, any function may be called as long as it
+                // makes no writes
+                let result =
+                    env.execute_contract(contract_identifier, function.as_str(), &args, false);
+                cost = env.global_context.cost_track.get_total();
+                result
+            },
+        );
+        self.charge(&cost);
+
+        match result {
+            Ok(value) => match value.serialize_to_hex() {
+                Ok(hex_result) => BatchReadResult::ok(hex_result, None),
+                Err(e) => BatchReadResult::err(format!("{e:?}")),
+            },
+            Err(Unchecked(CheckErrors::CostBalanceExceeded(actual_cost, _)))
+                if actual_cost.write_count > 0 =>
+            {
+                BatchReadResult::err("NotReadOnly".into())
+            }
+            Err(e) => BatchReadResult::err(e.to_string()),
+        }
+    }
+
+    /// Read a hex-encoded value, and its proof if requested. Each read costs
+    ///  one read, of the length of the value.
+    fn read<C: ClarityConnection>(
+        &mut self,
+        clarity_tx: &mut C,
+        key: &str,
+        with_proof: bool,
+    ) -> Result<Option<(String, Option<String>)>, String> {
+        let data = clarity_tx.with_clarity_db_readonly(|clarity_db| {
+            if with_proof {
+                clarity_db
+                    .get_data_with_proof::<String>(key)
+                    .map(|data| data.map(|(a, b)| (a, Some(format!("0x{}", to_hex(&b))))))
+            } else {
+                clarity_db
+                    .get_data::<String>(key)
+                    .map(|data| data.map(|a| (a, None)))
+            }
+        });
+        let data = data.map_err(|e| e.to_string())?;
+
+        let cost = ExecutionCost {
+            read_count: 1,
+            read_length: data
+                .as_ref()
+                .map(|(value_hex, _)| value_hex.len() as u64 / 2)
+                .unwrap_or(0),
+            ..ExecutionCost::ZERO
+        };
+        self.charge(&cost);
+        if self.remaining().is_none() {
+            return Err("CostBalanceExceeded".into());
+        }
+        Ok(data)
+    }
+}
+
+#[derive(Clone)]
+pub struct RPCCallReadOnlyBatchRequestHandler {
+    maximum_call_argument_size: u32,
+    read_only_call_limit: ExecutionCost,
+
+    /// Runtime fields
+    pub sender: Option<PrincipalData>,
+    pub sponsor: Option<PrincipalData>,
+    pub items: Option<Vec<BatchRead>>,
+}
+
+impl RPCCallReadOnlyBatchRequestHandler {
+    pub fn new(maximum_call_argument_size: u32, read_only_call_limit: ExecutionCost) -> Self {
+        Self {
+            maximum_call_argument_size,
+            read_only_call_limit,
+            sender: None,
+            sponsor: None,
+            items: None,
+        }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCCallReadOnlyBatchRequestHandler {
+    fn verb(&self) -> &'static str {
+        "POST"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(r#"^/v2/contracts/call-read-batch$"#).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v2/contracts/call-read-batch"
+    }
+
+    /// Try to decode this request.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        let content_len = preamble.get_content_length();
+        if !(content_len > 0 && content_len < self.maximum_call_argument_size) {
+            return Err(Error::DecodeError(format!(
+                "Invalid Http request: invalid body length for CallReadOnlyBatch ({})",
+                content_len
+            )));
+        }
+
+        if preamble.content_type != Some(HttpContentType::JSON) {
+            return Err(Error::DecodeError(
+                "Invalid content-type: expected application/json".to_string(),
+            ));
+        }
+
+        let body: BatchReadRequestBody = serde_json::from_slice(body)
+            .map_err(|_e| Error::DecodeError("Failed to parse JSON body".into()))?;
+
+        if body.items.is_empty() || body.items.len() > MAX_BATCH_READ_ITEMS {
+            return Err(Error::DecodeError(format!(
+                "Expected between 1 and {} items, got {}",
+                MAX_BATCH_READ_ITEMS,
+                body.items.len()
+            )));
+        }
+
+        let sender = PrincipalData::parse(&body.sender)
+            .map_err(|_e| Error::DecodeError("Failed to parse sender principal".into()))?;
+
+        let sponsor = if let Some(sponsor) = body.sponsor {
+            Some(
+                PrincipalData::parse(&sponsor)
+                    .map_err(|_e| Error::DecodeError("Failed to parse sponsor principal".into()))?,
+            )
+        } else {
+            None
+        };
+
+        let items = body
+            .items
+            .into_iter()
+            .map(BatchRead::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.sender = Some(sender);
+        self.sponsor = sponsor;
+        self.items = Some(items);
+
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+/// Handle the HTTP request
+impl RPCRequestHandler for RPCCallReadOnlyBatchRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.sender = None;
+        self.sponsor = None;
+        self.items = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let tip = match node.load_stacks_chain_tip(&preamble, &contents) {
+            Ok(tip) => tip,
+            Err(error_resp) => {
+                return error_resp.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let sender = self
+            .sender
+            .take()
+            .ok_or(NetError::SendError("Missing `sender`".into()))?;
+        let sponsor = self.sponsor.take();
+        let items = self
+            .items
+            .take()
+            .ok_or(NetError::SendError("Missing `items`".into()))?;
+
+        // every item is evaluated in the same read-only connection, so they all see the
+        // state as of `tip`, even if a block arrives in the meantime
+        let data_resp =
+            node.with_node_state(|_network, sortdb, chainstate, _mempool, _rpc_args| {
+                let mut evaluator = BatchEvaluator {
+                    mainnet: chainstate.mainnet,
+                    chain_id: chainstate.chain_id,
+                    sender,
+                    sponsor,
+                    budget: self.read_only_call_limit.clone(),
+                    consumed: ExecutionCost::ZERO,
+                };
+                chainstate.maybe_read_only_clarity_tx(
+                    &sortdb.index_handle_at_block(chainstate, &tip)?,
+                    &tip,
+                    |clarity_tx| {
+                        let results: Vec<_> = items
+                            .into_iter()
+                            .map(|item| evaluator.evaluate(clarity_tx, item))
+                            .collect();
+                        BatchReadResponse {
+                            tip: tip.clone(),
+                            cost: evaluator.consumed.clone(),
+                            results,
+                        }
+                    },
+                )
+            });
+
+        let data_resp = match data_resp {
+            Ok(Some(data)) => data,
+            Ok(None) | Err(_) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpNotFound::new("Chain tip not found".to_string()),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+        };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&data_resp)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCCallReadOnlyBatchRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let batch: BatchReadResponse = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(batch)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request to evaluate a batch of reads at one tip
+    pub fn new_callreadonlybatch(
+        host: PeerHost,
+        sender: PrincipalData,
+        sponsor: Option<PrincipalData>,
+        items: Vec<BatchReadItem>,
+        tip_req: TipRequest,
+    ) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "POST".into(),
+            "/v2/contracts/call-read-batch".into(),
+            HttpRequestContents::new().for_tip(tip_req).payload_json(
+                serde_json::to_value(BatchReadRequestBody {
+                    sender: sender.to_string(),
+                    sponsor: sponsor.map(|s| s.to_string()),
+                    items,
+                })
+                .expect("FATAL: failed to encode infallible data"),
+            ),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_call_readonly_batch_response(self) -> Result<BatchReadResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let resp: BatchReadResponse = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(resp)
+    }
+}
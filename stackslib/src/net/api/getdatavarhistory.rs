@@ -0,0 +1,286 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use clarity::vm::ast::parser::v1::CLARITY_NAME_REGEX;
+use clarity::vm::database::{ClarityDatabase, StoreType};
+use clarity::vm::representations::{CONTRACT_NAME_REGEX_STRING, STANDARD_PRINCIPAL_REGEX_STRING};
+use clarity::vm::types::QualifiedContractIdentifier;
+use clarity::vm::{ClarityName, ContractName};
+use regex::{Captures, Regex};
+use stacks_common::types::chainstate::{StacksAddress, StacksBlockId};
+use stacks_common::types::net::PeerHost;
+
+use crate::clarity_vm::database::history::{get_data_key_changes, DataKeyChange};
+use crate::net::http::{
+    parse_json, Error, HttpNotFound, HttpRequest, HttpRequestContents, HttpRequestPreamble,
+    HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble, HttpServerError,
+};
+use crate::net::httpcore::{
+    request, HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler,
+    StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState, TipRequest};
+
+/// The most changes in a page, and the number returned unless `?limit=` asks for fewer
+pub const MAX_DATA_KEY_HISTORY_PAGE: usize = 200;
+
+/// The changes of a data-var or map entry, newest first
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DataKeyHistoryResponse {
+    pub changes: Vec<DataKeyChange>,
+    /// The `?before=` height of the next page, if there may be one
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_before: Option<u32>,
+}
+
+/// Parse the `?before=` and `?limit=` query parameters of a history request
+pub(crate) fn parse_history_page(
+    contents: &HttpRequestContents,
+) -> Result<(Option<u32>, usize), Error> {
+    let before = contents
+        .get_query_arg("before")
+        .map(|before| before.parse::<u32>())
+        .transpose()
+        .map_err(|e| Error::DecodeError(format!("Failed to parse before= query parameter: {e}")))?;
+    let limit = contents
+        .get_query_arg("limit")
+        .map(|limit| limit.parse::<usize>())
+        .transpose()
+        .map_err(|e| Error::DecodeError(format!("Failed to parse limit= query parameter: {e}")))?
+        .unwrap_or(MAX_DATA_KEY_HISTORY_PAGE);
+    if limit == 0 || limit > MAX_DATA_KEY_HISTORY_PAGE {
+        return Err(Error::DecodeError(format!(
+            "Expected a limit= between 1 and {MAX_DATA_KEY_HISTORY_PAGE}"
+        )));
+    }
+    Ok((before, limit))
+}
+
+/// Load a page of the history of the MARF key `key` in the fork of `tip`, and make the
+///  response
+pub(crate) fn make_history_response(
+    preamble: &HttpRequestPreamble,
+    node: &mut StacksNodeState,
+    tip: &StacksBlockId,
+    key: &str,
+    before: Option<u32>,
+    limit: usize,
+) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+    let changes = node.with_node_state(|_network, _sortdb, chainstate, _mempool, _rpc_args| {
+        chainstate
+            .clarity_state
+            .with_marf(|marf| get_data_key_changes(marf, tip, key, before, limit))
+    });
+
+    let changes = match changes {
+        Ok(Some(changes)) => changes,
+        Ok(None) => {
+            return StacksHttpResponse::new_error(
+                preamble,
+                &HttpNotFound::new("Chain tip not found".to_string()),
+            )
+            .try_into_contents()
+            .map_err(NetError::from);
+        }
+        Err(e) => {
+            return StacksHttpResponse::new_error(
+                preamble,
+                &HttpServerError::new(format!("Failed to load history: {:?}", &e)),
+            )
+            .try_into_contents()
+            .map_err(NetError::from);
+        }
+    };
+
+    let next_before = if changes.len() == limit {
+        changes.last().map(|change| change.block_height)
+    } else {
+        None
+    };
+    let data_resp = DataKeyHistoryResponse {
+        changes,
+        next_before,
+    };
+
+    let mut preamble = HttpResponsePreamble::ok_json(preamble);
+    preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+    let body = HttpResponseContents::try_from_json(&data_resp)?;
+    Ok((preamble, body))
+}
+
+#[derive(Clone)]
+pub struct RPCGetDataVarHistoryRequestHandler {
+    pub contract_identifier: Option<QualifiedContractIdentifier>,
+    pub varname: Option<ClarityName>,
+    pub before: Option<u32>,
+    pub limit: Option<usize>,
+}
+impl RPCGetDataVarHistoryRequestHandler {
+    pub fn new() -> Self {
+        Self {
+            contract_identifier: None,
+            varname: None,
+            before: None,
+            limit: None,
+        }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetDataVarHistoryRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(&format!(
+            "^/v2/data_var_history/(?P<address>{})/(?P<contract>{})/(?P<varname>{})$",
+            *STANDARD_PRINCIPAL_REGEX_STRING, *CONTRACT_NAME_REGEX_STRING, *CLARITY_NAME_REGEX
+        ))
+        .unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v2/data_var_history/:principal/:contract_name/:var_name"
+    }
+
+    /// Try to decode this request.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+
+        let contract_identifier = request::get_contract_address(captures, "address", "contract")?;
+        let varname = request::get_clarity_name(captures, "varname")?;
+
+        let contents = HttpRequestContents::new().query_string(query);
+        let (before, limit) = parse_history_page(&contents)?;
+
+        self.contract_identifier = Some(contract_identifier);
+        self.varname = Some(varname);
+        self.before = before;
+        self.limit = Some(limit);
+
+        Ok(contents)
+    }
+}
+
+/// Handle the HTTP request
+impl RPCRequestHandler for RPCGetDataVarHistoryRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.contract_identifier = None;
+        self.varname = None;
+        self.before = None;
+        self.limit = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let contract_identifier = self.contract_identifier.take().ok_or(NetError::SendError(
+            "`contract_identifier` not set".to_string(),
+        ))?;
+        let var_name = self
+            .varname
+            .take()
+            .ok_or(NetError::SendError("`varname` not set".to_string()))?;
+        let limit = self
+            .limit
+            .take()
+            .ok_or(NetError::SendError("`limit` not set".to_string()))?;
+        let before = self.before.take();
+
+        let tip = match node.load_stacks_chain_tip(&preamble, &contents) {
+            Ok(tip) => tip,
+            Err(error_resp) => {
+                return error_resp.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let key = ClarityDatabase::make_key_for_trip(
+            &contract_identifier,
+            StoreType::Variable,
+            &var_name,
+        );
+        make_history_response(&preamble, node, &tip, &key, before, limit)
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetDataVarHistoryRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let history: DataKeyHistoryResponse = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(history)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for the history of a data var
+    pub fn new_getdatavarhistory(
+        host: PeerHost,
+        contract_addr: StacksAddress,
+        contract_name: ContractName,
+        var_name: ClarityName,
+        tip_req: TipRequest,
+        before: Option<u32>,
+        limit: Option<usize>,
+    ) -> StacksHttpRequest {
+        let mut contents = HttpRequestContents::new().for_tip(tip_req);
+        if let Some(before) = before {
+            contents = contents.query_arg("before".into(), before.to_string());
+        }
+        if let Some(limit) = limit {
+            contents = contents.query_arg("limit".into(), limit.to_string());
+        }
+        StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            format!(
+                "/v2/data_var_history/{}/{}/{}",
+                &contract_addr, &contract_name, &var_name
+            ),
+            contents,
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_data_key_history_response(self) -> Result<DataKeyHistoryResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let resp: DataKeyHistoryResponse = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(resp)
+    }
+}
@@ -0,0 +1,325 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use clarity::vm::ast::parser::v1::CLARITY_NAME_REGEX;
+use clarity::vm::database::{ClarityDatabase, StoreType};
+use clarity::vm::representations::{CONTRACT_NAME_REGEX_STRING, STANDARD_PRINCIPAL_REGEX_STRING};
+use clarity::vm::types::QualifiedContractIdentifier;
+use clarity::vm::{ClarityName, ContractName, Value};
+use regex::{Captures, Regex};
+use stacks_common::types::chainstate::{StacksAddress, StacksBlockId};
+use stacks_common::types::net::PeerHost;
+use stacks_common::util::hash::{hex_bytes, to_hex};
+
+use crate::clarity_vm::database::keys::{scan_data_keys, DataKeyScan};
+use crate::net::http::{
+    parse_json, Error, HttpNotFound, HttpRequest, HttpRequestContents, HttpRequestPreamble,
+    HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble, HttpServerError,
+};
+use crate::net::httpcore::{
+    request, HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler,
+    StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState, TipRequest};
+
+/// The most entries in a page, and the number returned unless `?limit=` asks for fewer
+pub const MAX_DATA_KEY_SCAN_PAGE: usize = 200;
+/// The most keys which are visited to fill one page
+pub const MAX_DATA_KEY_SCAN_KEYS: usize = 4_096;
+
+/// One entry of a data map
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MapEntry {
+    /// The hex-serialized key
+    pub key: String,
+    /// The hex-serialized value, as `/v2/map_entry` would return it
+    pub data: String,
+}
+
+/// A page of the entries of a data map, in no particular order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MapEntriesResponse {
+    pub entries: Vec<MapEntry>,
+    /// The `?cursor=` of the next page, if there may be one
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    /// Whether the node can list every entry. A node which had chainstate before it could
+    ///  list entries only knows of the entries written since.
+    pub complete: bool,
+}
+
+/// Encode the key a scan stopped at as a `?cursor=`
+pub(crate) fn encode_scan_cursor(key: &str) -> String {
+    to_hex(key.as_bytes())
+}
+
+/// Parse the `?cursor=` and `?limit=` query parameters of a scan request
+pub(crate) fn parse_scan_page(
+    contents: &HttpRequestContents,
+) -> Result<(Option<String>, usize), Error> {
+    let cursor = contents
+        .get_query_arg("cursor")
+        .map(|cursor| {
+            let bytes = hex_bytes(cursor).map_err(|e| e.to_string())?;
+            String::from_utf8(bytes).map_err(|e| e.to_string())
+        })
+        .transpose()
+        .map_err(|e| Error::DecodeError(format!("Failed to parse cursor= query parameter: {e}")))?;
+    let limit = contents
+        .get_query_arg("limit")
+        .map(|limit| limit.parse::<usize>())
+        .transpose()
+        .map_err(|e| Error::DecodeError(format!("Failed to parse limit= query parameter: {e}")))?
+        .unwrap_or(MAX_DATA_KEY_SCAN_PAGE);
+    if limit == 0 || limit > MAX_DATA_KEY_SCAN_PAGE {
+        return Err(Error::DecodeError(format!(
+            "Expected a limit= between 1 and {MAX_DATA_KEY_SCAN_PAGE}"
+        )));
+    }
+    Ok((cursor, limit))
+}
+
+/// Scan the contract data keys starting with `prefixes` in the MARF of `tip`, from after
+///  the key `cursor`. On failure, returns the error response to send.
+pub(crate) fn load_data_key_scan(
+    preamble: &HttpRequestPreamble,
+    node: &mut StacksNodeState,
+    tip: &StacksBlockId,
+    prefixes: &[String],
+    cursor: Option<&str>,
+    limit: usize,
+) -> Result<DataKeyScan, StacksHttpResponse> {
+    let scan = node.with_node_state(|_network, _sortdb, chainstate, _mempool, _rpc_args| {
+        chainstate.clarity_state.with_marf(|marf| {
+            scan_data_keys(marf, tip, prefixes, cursor, limit, MAX_DATA_KEY_SCAN_KEYS)
+        })
+    });
+
+    match scan {
+        Ok(Some(scan)) => Ok(scan),
+        Ok(None) => Err(StacksHttpResponse::new_error(
+            preamble,
+            &HttpNotFound::new("Chain tip not found".to_string()),
+        )),
+        Err(e) => Err(StacksHttpResponse::new_error(
+            preamble,
+            &HttpServerError::new(format!("Failed to scan contract data: {:?}", &e)),
+        )),
+    }
+}
+
+#[derive(Clone)]
+pub struct RPCGetMapEntriesRequestHandler {
+    pub contract_identifier: Option<QualifiedContractIdentifier>,
+    pub map_name: Option<ClarityName>,
+    pub cursor: Option<String>,
+    pub limit: Option<usize>,
+}
+impl RPCGetMapEntriesRequestHandler {
+    pub fn new() -> Self {
+        Self {
+            contract_identifier: None,
+            map_name: None,
+            cursor: None,
+            limit: None,
+        }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetMapEntriesRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(&format!(
+            "^/v2/map_entries/(?P<address>{})/(?P<contract>{})/(?P<map>{})$",
+            *STANDARD_PRINCIPAL_REGEX_STRING, *CONTRACT_NAME_REGEX_STRING, *CLARITY_NAME_REGEX
+        ))
+        .unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v2/map_entries/:principal/:contract_name/:map_name"
+    }
+
+    /// Try to decode this request.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+
+        let contract_identifier = request::get_contract_address(captures, "address", "contract")?;
+        let map_name = request::get_clarity_name(captures, "map")?;
+
+        let contents = HttpRequestContents::new().query_string(query);
+        let (cursor, limit) = parse_scan_page(&contents)?;
+
+        self.contract_identifier = Some(contract_identifier);
+        self.map_name = Some(map_name);
+        self.cursor = cursor;
+        self.limit = Some(limit);
+
+        Ok(contents)
+    }
+}
+
+/// Handle the HTTP request
+impl RPCRequestHandler for RPCGetMapEntriesRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.contract_identifier = None;
+        self.map_name = None;
+        self.cursor = None;
+        self.limit = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let contract_identifier = self
+            .contract_identifier
+            .take()
+            .ok_or(NetError::SendError("`contract_identifier` not set".into()))?;
+        let map_name = self
+            .map_name
+            .take()
+            .ok_or(NetError::SendError("`map_name` not set".into()))?;
+        let limit = self
+            .limit
+            .take()
+            .ok_or(NetError::SendError("`limit` not set".into()))?;
+        let cursor = self.cursor.take();
+
+        let tip = match node.load_stacks_chain_tip(&preamble, &contents) {
+            Ok(tip) => tip,
+            Err(error_resp) => {
+                return error_resp.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let prefix = ClarityDatabase::make_key_for_quad(
+            &contract_identifier,
+            StoreType::DataMap,
+            &map_name,
+            "",
+        );
+        let none_hex = Value::none()
+            .serialize_to_hex()
+            .map_err(|e| NetError::SerializeError(format!("{:?}", &e)))?;
+
+        let scan = match load_data_key_scan(
+            &preamble,
+            node,
+            &tip,
+            &[prefix.clone()],
+            cursor.as_deref(),
+            limit,
+        ) {
+            Ok(scan) => scan,
+            Err(error_resp) => {
+                return error_resp.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        // deleted entries are left in the MARF as `none`
+        let entries = scan
+            .entries
+            .into_iter()
+            .filter(|(_key, data)| data != &none_hex)
+            .map(|(key, data)| MapEntry {
+                key: format!("0x{}", &key[prefix.len()..]),
+                data: format!("0x{}", data),
+            })
+            .collect();
+        let data_resp = MapEntriesResponse {
+            entries,
+            next_cursor: scan.next.as_deref().map(encode_scan_cursor),
+            complete: scan.complete,
+        };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&data_resp)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetMapEntriesRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let entries: MapEntriesResponse = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(entries)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for a page of the entries of a map
+    pub fn new_getmapentries(
+        host: PeerHost,
+        contract_addr: StacksAddress,
+        contract_name: ContractName,
+        map_name: ClarityName,
+        tip_req: TipRequest,
+        cursor: Option<String>,
+        limit: Option<usize>,
+    ) -> StacksHttpRequest {
+        let mut contents = HttpRequestContents::new().for_tip(tip_req);
+        if let Some(cursor) = cursor {
+            contents = contents.query_arg("cursor".into(), cursor);
+        }
+        if let Some(limit) = limit {
+            contents = contents.query_arg("limit".into(), limit.to_string());
+        }
+        StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            format!(
+                "/v2/map_entries/{}/{}/{}",
+                &contract_addr, &contract_name, &map_name
+            ),
+            contents,
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_map_entries_response(self) -> Result<MapEntriesResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let resp: MapEntriesResponse = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(resp)
+    }
+}
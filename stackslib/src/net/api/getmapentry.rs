@@ -40,9 +40,9 @@ use crate::chainstate::stacks::db::StacksChainState;
 use crate::chainstate::stacks::Error as ChainError;
 use crate::core::mempool::MemPoolDB;
 use crate::net::http::{
-    parse_json, Error, HttpContentType, HttpNotFound, HttpRequest, HttpRequestContents,
-    HttpRequestPayload, HttpRequestPreamble, HttpResponse, HttpResponseContents,
-    HttpResponsePayload, HttpResponsePreamble, HttpServerError,
+    parse_json, Error, HttpBadRequest, HttpContentType, HttpNotFound, HttpRequest,
+    HttpRequestContents, HttpRequestPayload, HttpRequestPreamble, HttpResponse,
+    HttpResponseContents, HttpResponsePayload, HttpResponsePreamble, HttpServerError,
 };
 use crate::net::httpcore::{
     request, HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp,
@@ -67,6 +67,8 @@ pub struct RPCGetMapEntryRequestHandler {
     pub contract_identifier: Option<QualifiedContractIdentifier>,
     pub map_name: Option<ClarityName>,
     pub key: Option<Value>,
+    /// JSON key, which is decoded once the map's key type is loaded
+    pub json_key: Option<serde_json::Value>,
 }
 impl RPCGetMapEntryRequestHandler {
     pub fn new() -> Self {
@@ -74,6 +76,7 @@ impl RPCGetMapEntryRequestHandler {
             contract_identifier: None,
             map_name: None,
             key: None,
+            json_key: None,
         }
     }
 }
@@ -99,6 +102,8 @@ impl HttpRequest for RPCGetMapEntryRequestHandler {
     /// Try to decode this request.
     /// The body must be a hex string, encoded as a JSON string.
     /// So, something like `"123abc"`.  It encodes the map key as a serialized Clarity value.
+    /// Alternatively, the body can be an object like `{"key": ...}`, with the key in the
+    /// JSON representation of Clarity values.
     fn try_parse_request(
         &mut self,
         preamble: &HttpRequestPreamble,
@@ -124,15 +129,29 @@ impl HttpRequest for RPCGetMapEntryRequestHandler {
         let map_name = request::get_clarity_name(captures, "map")?;
 
         let mut body_ptr = body;
-        let value_hex: String = serde_json::from_reader(&mut body_ptr)
+        let body: serde_json::Value = serde_json::from_reader(&mut body_ptr)
             .map_err(|_e| Error::DecodeError("Failed to parse JSON body".into()))?;
 
-        let value = Value::try_deserialize_hex_untyped(&value_hex)
-            .map_err(|_e| Error::DecodeError("Failed to deserialize key value".into()))?;
+        let (key, json_key) = match body {
+            serde_json::Value::String(value_hex) => {
+                let value = Value::try_deserialize_hex_untyped(&value_hex)
+                    .map_err(|_e| Error::DecodeError("Failed to deserialize key value".into()))?;
+                (Some(value), None)
+            }
+            serde_json::Value::Object(mut body) if body.len() == 1 && body.contains_key("key") => {
+                (None, body.remove("key"))
+            }
+            _ => {
+                return Err(Error::DecodeError(
+                    "Expected a hex string or a `{\"key\": ...}` object".into(),
+                ))
+            }
+        };
 
         self.contract_identifier = Some(contract_identifier);
         self.map_name = Some(map_name);
-        self.key = Some(value);
+        self.key = key;
+        self.json_key = json_key;
 
         Ok(HttpRequestContents::new().query_string(query))
     }
@@ -145,6 +164,7 @@ impl RPCRequestHandler for RPCGetMapEntryRequestHandler {
         self.contract_identifier = None;
         self.map_name = None;
         self.key = None;
+        self.json_key = None;
     }
 
     /// Make the response
@@ -162,10 +182,6 @@ impl RPCRequestHandler for RPCGetMapEntryRequestHandler {
             .map_name
             .take()
             .ok_or(NetError::SendError("`map_name` not set".into()))?;
-        let key = self
-            .key
-            .take()
-            .ok_or(NetError::SendError("`key` not set".into()))?;
 
         let tip = match node.load_stacks_chain_tip(&preamble, &contents) {
             Ok(tip) => tip,
@@ -173,6 +189,52 @@ impl RPCRequestHandler for RPCGetMapEntryRequestHandler {
                 return error_resp.try_into_contents().map_err(NetError::from);
             }
         };
+
+        let key = match self.json_key.take() {
+            Some(json_key) => {
+                let key =
+                    node.with_node_state(|_network, sortdb, chainstate, _mempool, _rpc_args| {
+                        chainstate.maybe_read_only_clarity_tx(
+                            &sortdb.index_handle_at_block(chainstate, &tip)?,
+                            &tip,
+                            |clarity_tx| {
+                                clarity_tx.with_clarity_db_readonly(|clarity_db| {
+                                    let map = clarity_db
+                                        .load_map(&contract_identifier, &map_name)
+                                        .map_err(|_| {
+                                            format!(
+                                                "No map `{}` in {}",
+                                                &map_name, &contract_identifier
+                                            )
+                                        })?;
+                                    Value::from_json(&json_key, &map.key_type)
+                                        .map_err(|e| format!("Failed to decode the map key: {}", e))
+                                })
+                            },
+                        )
+                    });
+                match key {
+                    Ok(Some(Ok(key))) => key,
+                    Ok(Some(Err(msg))) => {
+                        return StacksHttpResponse::new_error(&preamble, &HttpBadRequest::new(msg))
+                            .try_into_contents()
+                            .map_err(NetError::from);
+                    }
+                    Ok(None) | Err(_) => {
+                        return StacksHttpResponse::new_error(
+                            &preamble,
+                            &HttpNotFound::new("Chain tip not found".to_string()),
+                        )
+                        .try_into_contents()
+                        .map_err(NetError::from);
+                    }
+                }
+            }
+            None => self
+                .key
+                .take()
+                .ok_or(NetError::SendError("`key` not set".into()))?,
+        };
         let with_proof = contents.get_with_proof();
         let key =
             ClarityDatabase::make_key_for_data_map_entry(&contract_identifier, &map_name, &key)
@@ -276,6 +338,32 @@ impl StacksHttpRequest {
         )
         .expect("FATAL: failed to construct request from infallible data")
     }
+
+    /// Make a new request for a data map, with the key in the JSON representation of
+    ///  Clarity values
+    pub fn new_getmapentry_json(
+        host: PeerHost,
+        contract_addr: StacksAddress,
+        contract_name: ContractName,
+        map_name: ClarityName,
+        key: serde_json::Value,
+        tip_req: TipRequest,
+        with_proof: bool,
+    ) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "POST".into(),
+            format!(
+                "/v2/map_entry/{}/{}/{}",
+                &contract_addr, &contract_name, &map_name
+            ),
+            HttpRequestContents::new()
+                .for_tip(tip_req)
+                .query_arg("proof".into(), if with_proof { "1" } else { "0" }.into())
+                .payload_json(serde_json::json!({ "key": key })),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
 }
 
 impl StacksHttpResponse {
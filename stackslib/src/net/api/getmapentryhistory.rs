@@ -0,0 +1,216 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use clarity::vm::ast::parser::v1::CLARITY_NAME_REGEX;
+use clarity::vm::database::ClarityDatabase;
+use clarity::vm::representations::{CONTRACT_NAME_REGEX_STRING, STANDARD_PRINCIPAL_REGEX_STRING};
+use clarity::vm::types::{QualifiedContractIdentifier, BOUND_VALUE_SERIALIZATION_HEX};
+use clarity::vm::{ClarityName, ContractName, Value};
+use regex::{Captures, Regex};
+use stacks_common::types::chainstate::StacksAddress;
+use stacks_common::types::net::PeerHost;
+
+use crate::net::api::getdatavarhistory::{
+    make_history_response, parse_history_page, DataKeyHistoryResponse,
+};
+use crate::net::http::{
+    parse_json, Error, HttpContentType, HttpRequest, HttpRequestContents, HttpRequestPreamble,
+    HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble,
+};
+use crate::net::httpcore::{
+    request, HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler,
+    StacksHttpRequest,
+};
+use crate::net::{Error as NetError, StacksNodeState, TipRequest};
+
+#[derive(Clone)]
+pub struct RPCGetMapEntryHistoryRequestHandler {
+    pub contract_identifier: Option<QualifiedContractIdentifier>,
+    pub map_name: Option<ClarityName>,
+    pub key: Option<Value>,
+    pub before: Option<u32>,
+    pub limit: Option<usize>,
+}
+impl RPCGetMapEntryHistoryRequestHandler {
+    pub fn new() -> Self {
+        Self {
+            contract_identifier: None,
+            map_name: None,
+            key: None,
+            before: None,
+            limit: None,
+        }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetMapEntryHistoryRequestHandler {
+    fn verb(&self) -> &'static str {
+        "POST"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(&format!(
+            "^/v2/map_entry_history/(?P<address>{})/(?P<contract>{})/(?P<map>{})$",
+            *STANDARD_PRINCIPAL_REGEX_STRING, *CONTRACT_NAME_REGEX_STRING, *CLARITY_NAME_REGEX
+        ))
+        .unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v2/map_entry_history/:principal/:contract_name/:map_name"
+    }
+
+    /// Try to decode this request.
+    /// As with `/v2/map_entry`, the body is the hex-serialized map key, encoded as a JSON
+    /// string.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        captures: &Captures,
+        query: Option<&str>,
+        body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        let content_len = preamble.get_content_length();
+        if !(content_len > 0 && content_len < BOUND_VALUE_SERIALIZATION_HEX) {
+            return Err(Error::DecodeError(format!(
+                "Invalid Http request: invalid body length for GetMapEntryHistory ({})",
+                content_len
+            )));
+        }
+
+        if preamble.content_type != Some(HttpContentType::JSON) {
+            return Err(Error::DecodeError(
+                "Invalid content-type: expected application/json".into(),
+            ));
+        }
+
+        let contract_identifier = request::get_contract_address(captures, "address", "contract")?;
+        let map_name = request::get_clarity_name(captures, "map")?;
+
+        let value_hex: String = serde_json::from_slice(body)
+            .map_err(|_e| Error::DecodeError("Failed to parse JSON body".into()))?;
+        let key = Value::try_deserialize_hex_untyped(&value_hex)
+            .map_err(|_e| Error::DecodeError("Failed to deserialize key value".into()))?;
+
+        let contents = HttpRequestContents::new().query_string(query);
+        let (before, limit) = parse_history_page(&contents)?;
+
+        self.contract_identifier = Some(contract_identifier);
+        self.map_name = Some(map_name);
+        self.key = Some(key);
+        self.before = before;
+        self.limit = Some(limit);
+
+        Ok(contents)
+    }
+}
+
+/// Handle the HTTP request
+impl RPCRequestHandler for RPCGetMapEntryHistoryRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.contract_identifier = None;
+        self.map_name = None;
+        self.key = None;
+        self.before = None;
+        self.limit = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let contract_identifier = self
+            .contract_identifier
+            .take()
+            .ok_or(NetError::SendError("`contract_identifier` not set".into()))?;
+        let map_name = self
+            .map_name
+            .take()
+            .ok_or(NetError::SendError("`map_name` not set".into()))?;
+        let key = self
+            .key
+            .take()
+            .ok_or(NetError::SendError("`key` not set".into()))?;
+        let limit = self
+            .limit
+            .take()
+            .ok_or(NetError::SendError("`limit` not set".into()))?;
+        let before = self.before.take();
+
+        let tip = match node.load_stacks_chain_tip(&preamble, &contents) {
+            Ok(tip) => tip,
+            Err(error_resp) => {
+                return error_resp.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let key =
+            ClarityDatabase::make_key_for_data_map_entry(&contract_identifier, &map_name, &key)
+                .map_err(|e| NetError::SerializeError(format!("{:?}", &e)))?;
+        make_history_response(&preamble, node, &tip, &key, before, limit)
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetMapEntryHistoryRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let history: DataKeyHistoryResponse = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(history)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for the history of a map entry
+    pub fn new_getmapentryhistory(
+        host: PeerHost,
+        contract_addr: StacksAddress,
+        contract_name: ContractName,
+        map_name: ClarityName,
+        key: Value,
+        tip_req: TipRequest,
+        before: Option<u32>,
+        limit: Option<usize>,
+    ) -> StacksHttpRequest {
+        let mut contents = HttpRequestContents::new().for_tip(tip_req);
+        if let Some(before) = before {
+            contents = contents.query_arg("before".into(), before.to_string());
+        }
+        if let Some(limit) = limit {
+            contents = contents.query_arg("limit".into(), limit.to_string());
+        }
+        StacksHttpRequest::new_for_peer(
+            host,
+            "POST".into(),
+            format!(
+                "/v2/map_entry_history/{}/{}/{}",
+                &contract_addr, &contract_name, &map_name
+            ),
+            contents.payload_json(serde_json::Value::String(
+                key.serialize_to_hex()
+                    .expect("FATAL: invalid key could not be serialized"),
+            )),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
@@ -0,0 +1,249 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use clarity::vm::representations::STANDARD_PRINCIPAL_REGEX_STRING;
+use regex::{Captures, Regex};
+use stacks_common::types::chainstate::StacksAddress;
+use stacks_common::types::net::PeerHost;
+use stacks_common::types::Address;
+
+use crate::burnchains::Txid;
+use crate::chainstate::stacks::db::StacksChainState;
+use crate::core::mempool::{MemPoolDB, MemPoolNonceChain};
+use crate::net::http::{
+    parse_json, Error, HttpNotFound, HttpRequest, HttpRequestContents, HttpRequestPreamble,
+    HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble, HttpServerError,
+};
+use crate::net::httpcore::{
+    HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler, StacksHttpRequest,
+    StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState, TipRequest};
+
+/// A transaction waiting in the mempool on one of the account's nonces
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MempoolPendingTxEntry {
+    pub txid: Txid,
+    pub nonce: u64,
+    /// Whether the account is the transaction's origin, rather than its sponsor
+    pub is_origin: bool,
+    pub fee: u64,
+    pub fee_rate: Option<f64>,
+    /// Whether the transaction can be mined once the ones before it are, i.e. no nonce is
+    /// missing before it
+    pub executable: bool,
+    /// The aggregate fee rate of the pending transactions up to and including this one
+    pub package_fee_rate: Option<f64>,
+}
+
+/// The chain of transactions waiting in the mempool on an account's nonces
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MempoolAccountResponse {
+    /// The account's nonce at the chain tip
+    pub nonce: u64,
+    /// The nonce the account's next transaction should have
+    pub next_nonce: u64,
+    /// The missing nonce which keeps some pending transactions from being mined, if any
+    pub first_gap: Option<u64>,
+    pub pending: Vec<MempoolPendingTxEntry>,
+}
+
+impl From<MemPoolNonceChain> for MempoolAccountResponse {
+    fn from(chain: MemPoolNonceChain) -> Self {
+        let pending = chain
+            .txs
+            .iter()
+            .map(|tx| MempoolPendingTxEntry {
+                txid: tx.txid.clone(),
+                nonce: tx.nonce,
+                is_origin: tx.is_origin,
+                fee: tx.tx_fee,
+                fee_rate: tx.fee_rate,
+                executable: chain.is_executable(tx.nonce),
+                package_fee_rate: chain.package_fee_rate(tx.nonce),
+            })
+            .collect();
+        MempoolAccountResponse {
+            nonce: chain.account_nonce,
+            next_nonce: chain.next_nonce(),
+            first_gap: chain.first_gap(),
+            pending,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RPCGetMempoolAccountRequestHandler {
+    pub address: Option<StacksAddress>,
+}
+impl RPCGetMempoolAccountRequestHandler {
+    pub fn new() -> Self {
+        Self { address: None }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetMempoolAccountRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(&format!(
+            "^/v2/mempool/accounts/(?P<address>{})$",
+            *STANDARD_PRINCIPAL_REGEX_STRING
+        ))
+        .unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v2/mempool/accounts/:principal"
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+
+        let address = captures
+            .name("address")
+            .and_then(|value| StacksAddress::from_string(value.as_str()))
+            .ok_or_else(|| Error::DecodeError("Failed to parse `principal` field".to_string()))?;
+
+        self.address = Some(address);
+
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+/// Handle the HTTP request
+impl RPCRequestHandler for RPCGetMempoolAccountRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.address = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let tip = match node.load_stacks_chain_tip(&preamble, &contents) {
+            Ok(tip) => tip,
+            Err(error_resp) => {
+                return error_resp.try_into_contents().map_err(NetError::from);
+            }
+        };
+        let address = self
+            .address
+            .take()
+            .ok_or(NetError::SendError("`address` not set".into()))?;
+
+        let chain_res = node.with_node_state(|_network, sortdb, chainstate, mempool, _rpc_args| {
+            let account_nonce = sortdb
+                .index_handle_at_block(chainstate, &tip)
+                .ok()
+                .and_then(|sort_handle| {
+                    chainstate
+                        .maybe_read_only_clarity_tx(&sort_handle, &tip, |clarity_tx| {
+                            StacksChainState::get_nonce(clarity_tx, &address.clone().into())
+                        })
+                        .ok()
+                        .flatten()
+                });
+            let Some(account_nonce) = account_nonce else {
+                return Ok(None);
+            };
+            MemPoolDB::get_nonce_chain(mempool.conn(), &address, account_nonce).map(Some)
+        });
+
+        let chain = match chain_res {
+            Ok(Some(chain)) => chain,
+            Ok(None) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpNotFound::new(format!("Chain tip '{}' not found", &tip)),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+            Err(e) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpServerError::new(format!("Failed to load pending transactions: {e:?}")),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+        };
+        let data_resp = MempoolAccountResponse::from(chain);
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&data_resp)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetMempoolAccountRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let account: MempoolAccountResponse = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(account)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for the transactions pending on an account's nonces
+    pub fn new_getmempoolaccount(
+        host: PeerHost,
+        address: StacksAddress,
+        tip_req: TipRequest,
+    ) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            format!("/v2/mempool/accounts/{}", &address),
+            HttpRequestContents::new().for_tip(tip_req),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_mempool_account_response(self) -> Result<MempoolAccountResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let resp: MempoolAccountResponse = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(resp)
+    }
+}
@@ -0,0 +1,148 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use regex::{Captures, Regex};
+use stacks_common::types::net::PeerHost;
+
+use crate::core::mempool::MemPoolStats;
+use crate::net::http::{
+    parse_json, Error, HttpRequest, HttpRequestContents, HttpRequestPreamble, HttpResponse,
+    HttpResponseContents, HttpResponsePayload, HttpResponsePreamble, HttpServerError,
+};
+use crate::net::httpcore::{
+    HttpPreambleExtensions, RPCRequestHandler, StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState};
+
+#[derive(Clone)]
+pub struct RPCGetMempoolStatsRequestHandler {}
+impl RPCGetMempoolStatsRequestHandler {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetMempoolStatsRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(r#"^/v2/mempool/stats$"#).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v2/mempool/stats"
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+/// Handle the HTTP request
+impl RPCRequestHandler for RPCGetMempoolStatsRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {}
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let data_resp = node.with_node_state(|_network, sortdb, chainstate, mempool, _rpc_args| {
+            let tip = self.get_canonical_burn_chain_tip(&preamble, sortdb)?;
+            let stacks_epoch = self.get_stacks_epoch(&preamble, sortdb, tip.block_height)?;
+            let stacks_tip = self.get_stacks_chain_tip(&preamble, sortdb, chainstate)?;
+            mempool
+                .get_stats_snapshot(
+                    chainstate,
+                    sortdb,
+                    &stacks_tip.index_block_hash(),
+                    &stacks_epoch.block_limit,
+                    &stacks_epoch.epoch_id,
+                )
+                .map_err(|e| {
+                    StacksHttpResponse::new_error(
+                        &preamble,
+                        &HttpServerError::new(format!("Failed to load mempool stats: {e:?}")),
+                    )
+                })
+        });
+
+        let data_resp = match data_resp {
+            Ok(data) => data,
+            Err(response) => {
+                return response.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&data_resp)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetMempoolStatsRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let stats: MemPoolStats = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(stats)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for a summary of the mempool
+    pub fn new_getmempoolstats(host: PeerHost) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            "/v2/mempool/stats".into(),
+            HttpRequestContents::new(),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_mempool_stats(self) -> Result<MemPoolStats, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let resp: MemPoolStats = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(resp)
+    }
+}
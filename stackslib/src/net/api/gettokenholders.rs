@@ -0,0 +1,309 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use clarity::vm::ast::parser::v1::CLARITY_NAME_REGEX;
+use clarity::vm::database::{ClarityDatabase, ClarityDeserializable, StoreType};
+use clarity::vm::representations::{CONTRACT_NAME_REGEX_STRING, STANDARD_PRINCIPAL_REGEX_STRING};
+use clarity::vm::types::{PrincipalData, QualifiedContractIdentifier};
+use clarity::vm::{ClarityName, ContractName, Value};
+use regex::{Captures, Regex};
+use stacks_common::types::chainstate::StacksAddress;
+use stacks_common::types::net::PeerHost;
+
+use crate::net::api::getmapentries::{encode_scan_cursor, load_data_key_scan, parse_scan_page};
+use crate::net::http::{
+    parse_json, Error, HttpRequest, HttpRequestContents, HttpRequestPreamble, HttpResponse,
+    HttpResponseContents, HttpResponsePayload, HttpResponsePreamble, HttpServerError,
+};
+use crate::net::httpcore::{
+    request, HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler,
+    StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState, TipRequest};
+
+/// A holder of a fungible token, or the owner of one non-fungible token
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenHolder {
+    pub principal: String,
+    /// The balance of a fungible token, as a decimal string
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance: Option<String>,
+    /// The hex-serialized identifier of a non-fungible token
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asset: Option<String>,
+}
+
+/// A page of the holders of a token, in no particular order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenHoldersResponse {
+    pub holders: Vec<TokenHolder>,
+    /// The `?cursor=` of the next page, if there may be one
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    /// Whether the node can list every holder. A node which had chainstate before it could
+    ///  list holders only knows of the balances written since.
+    pub complete: bool,
+}
+
+/// Decode one fungible token balance entry. Returns `None` for an emptied balance.
+fn decode_ft_holder(suffix: &str, data: &str) -> Result<Option<TokenHolder>, String> {
+    let principal = PrincipalData::deserialize(suffix)
+        .map_err(|e| format!("Failed to decode token holder {suffix}: {e:?}"))?;
+    let balance = u128::deserialize(data)
+        .map_err(|e| format!("Failed to decode balance of {principal}: {e:?}"))?;
+    if balance == 0 {
+        return Ok(None);
+    }
+    Ok(Some(TokenHolder {
+        principal: principal.to_string(),
+        balance: Some(balance.to_string()),
+        asset: None,
+    }))
+}
+
+/// Decode one non-fungible token owner entry. Returns `None` for a burnt token.
+fn decode_nft_holder(suffix: &str, data: &str) -> Result<Option<TokenHolder>, String> {
+    let owner = Value::try_deserialize_hex_untyped(data)
+        .ok()
+        .and_then(|value| value.expect_optional().ok())
+        .ok_or_else(|| format!("Failed to decode owner of 0x{suffix}"))?;
+    let Some(owner) = owner else {
+        return Ok(None);
+    };
+    let principal = owner
+        .expect_principal()
+        .map_err(|e| format!("Failed to decode owner of 0x{suffix}: {e:?}"))?;
+    Ok(Some(TokenHolder {
+        principal: principal.to_string(),
+        balance: None,
+        asset: Some(format!("0x{suffix}")),
+    }))
+}
+
+#[derive(Clone)]
+pub struct RPCGetTokenHoldersRequestHandler {
+    pub contract_identifier: Option<QualifiedContractIdentifier>,
+    pub token_name: Option<ClarityName>,
+    pub cursor: Option<String>,
+    pub limit: Option<usize>,
+}
+impl RPCGetTokenHoldersRequestHandler {
+    pub fn new() -> Self {
+        Self {
+            contract_identifier: None,
+            token_name: None,
+            cursor: None,
+            limit: None,
+        }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetTokenHoldersRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(&format!(
+            "^/v2/token_holders/(?P<address>{})/(?P<contract>{})/(?P<token>{})$",
+            *STANDARD_PRINCIPAL_REGEX_STRING, *CONTRACT_NAME_REGEX_STRING, *CLARITY_NAME_REGEX
+        ))
+        .unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v2/token_holders/:principal/:contract_name/:token_name"
+    }
+
+    /// Try to decode this request.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+
+        let contract_identifier = request::get_contract_address(captures, "address", "contract")?;
+        let token_name = request::get_clarity_name(captures, "token")?;
+
+        let contents = HttpRequestContents::new().query_string(query);
+        let (cursor, limit) = parse_scan_page(&contents)?;
+
+        self.contract_identifier = Some(contract_identifier);
+        self.token_name = Some(token_name);
+        self.cursor = cursor;
+        self.limit = Some(limit);
+
+        Ok(contents)
+    }
+}
+
+/// Handle the HTTP request
+impl RPCRequestHandler for RPCGetTokenHoldersRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.contract_identifier = None;
+        self.token_name = None;
+        self.cursor = None;
+        self.limit = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let contract_identifier = self
+            .contract_identifier
+            .take()
+            .ok_or(NetError::SendError("`contract_identifier` not set".into()))?;
+        let token_name = self
+            .token_name
+            .take()
+            .ok_or(NetError::SendError("`token_name` not set".into()))?;
+        let limit = self
+            .limit
+            .take()
+            .ok_or(NetError::SendError("`limit` not set".into()))?;
+        let cursor = self.cursor.take();
+
+        let tip = match node.load_stacks_chain_tip(&preamble, &contents) {
+            Ok(tip) => tip,
+            Err(error_resp) => {
+                return error_resp.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        // fungible and non-fungible tokens share a namespace, so at most one of these matches
+        let ft_prefix = ClarityDatabase::make_key_for_quad(
+            &contract_identifier,
+            StoreType::FungibleToken,
+            &token_name,
+            "",
+        );
+        let nft_prefix = ClarityDatabase::make_key_for_quad(
+            &contract_identifier,
+            StoreType::NonFungibleToken,
+            &token_name,
+            "",
+        );
+
+        let scan = match load_data_key_scan(
+            &preamble,
+            node,
+            &tip,
+            &[ft_prefix.clone(), nft_prefix.clone()],
+            cursor.as_deref(),
+            limit,
+        ) {
+            Ok(scan) => scan,
+            Err(error_resp) => {
+                return error_resp.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let holders: Result<Vec<_>, String> = scan
+            .entries
+            .iter()
+            .filter_map(|(key, data)| match key.strip_prefix(&ft_prefix) {
+                Some(suffix) => decode_ft_holder(suffix, data).transpose(),
+                None => decode_nft_holder(&key[nft_prefix.len()..], data).transpose(),
+            })
+            .collect();
+        let holders = match holders {
+            Ok(holders) => holders,
+            Err(msg) => {
+                return StacksHttpResponse::new_error(&preamble, &HttpServerError::new(msg))
+                    .try_into_contents()
+                    .map_err(NetError::from);
+            }
+        };
+        let data_resp = TokenHoldersResponse {
+            holders,
+            next_cursor: scan.next.as_deref().map(encode_scan_cursor),
+            complete: scan.complete,
+        };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&data_resp)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetTokenHoldersRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let holders: TokenHoldersResponse = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(holders)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for a page of the holders of a token
+    pub fn new_gettokenholders(
+        host: PeerHost,
+        contract_addr: StacksAddress,
+        contract_name: ContractName,
+        token_name: ClarityName,
+        tip_req: TipRequest,
+        cursor: Option<String>,
+        limit: Option<usize>,
+    ) -> StacksHttpRequest {
+        let mut contents = HttpRequestContents::new().for_tip(tip_req);
+        if let Some(cursor) = cursor {
+            contents = contents.query_arg("cursor".into(), cursor);
+        }
+        if let Some(limit) = limit {
+            contents = contents.query_arg("limit".into(), limit.to_string());
+        }
+        StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            format!(
+                "/v2/token_holders/{}/{}/{}",
+                &contract_addr, &contract_name, &token_name
+            ),
+            contents,
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_token_holders_response(self) -> Result<TokenHoldersResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let resp: TokenHoldersResponse = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(resp)
+    }
+}
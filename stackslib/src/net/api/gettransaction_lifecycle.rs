@@ -0,0 +1,329 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use regex::{Captures, Regex};
+use stacks_common::types::chainstate::{StacksAddress, StacksBlockId};
+use stacks_common::types::net::PeerHost;
+
+use crate::burnchains::Txid;
+use crate::chainstate::burn::db::sortdb::SortitionDB;
+use crate::chainstate::nakamoto::NakamotoChainState;
+use crate::chainstate::stacks::db::StacksChainState;
+use crate::core::mempool::MemPoolDB;
+use crate::net::http::{
+    parse_json, Error, HttpNotFound, HttpRequest, HttpRequestContents, HttpRequestPreamble,
+    HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble, HttpServerError,
+};
+use crate::net::httpcore::{
+    request, HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler,
+    StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState, TipRequest};
+
+/// What became of a transaction this node has seen.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TransactionLifecycleStatus {
+    /// Mined in a block on the chain tip's fork.
+    /// Only reported if the node logs its transactions (`STACKS_TRANSACTION_LOG=1`).
+    Mined {
+        index_block_hash: StacksBlockId,
+        block_height: u64,
+    },
+    /// Its origin or sponsor nonce has been used at the chain tip, so it was either mined or
+    /// superseded by another transaction.  Which one can only be told if the node logs its
+    /// transactions, in which case a mined transaction is reported as `Mined`.
+    MinedOrSuperseded,
+    /// Waiting in the mempool. Ranks start at 1, for the best-paying transaction, and only count
+    /// the transactions which are still waiting.  `mempool_tx_count` is as of the mempool's last
+    /// summary (see `/v2/mempool/stats`).
+    Pending {
+        accept_time: u64,
+        fee_rate: Option<f64>,
+        fee_per_byte_rank: u64,
+        fee_rate_rank: Option<u64>,
+        mempool_tx_count: u64,
+    },
+    /// Dropped from the mempool as problematic, and refused until the blacklist entry expires
+    Blacklisted,
+    /// Replaced in the mempool by another transaction with the same nonce
+    Replaced {
+        replaced_by: Txid,
+        reason: String,
+        drop_time: u64,
+    },
+    /// Dropped from the mempool without being replaced
+    Dropped { reason: String, drop_time: u64 },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransactionLifecycleResponse {
+    pub txid: Txid,
+    pub status: TransactionLifecycleStatus,
+}
+
+#[derive(Clone)]
+pub struct RPCGetTransactionLifecycleRequestHandler {
+    pub txid: Option<Txid>,
+}
+impl RPCGetTransactionLifecycleRequestHandler {
+    pub fn new() -> Self {
+        Self { txid: None }
+    }
+
+    /// Run `todo` with a lookup of accounts' nonces at the given chain tip.  Accounts are taken
+    /// to be unused if the tip's state is not available.
+    fn with_account_nonces<F, R>(
+        sortdb: &SortitionDB,
+        chainstate: &mut StacksChainState,
+        tip: &StacksBlockId,
+        mut todo: F,
+    ) -> Result<R, NetError>
+    where
+        F: FnMut(&mut dyn FnMut(&StacksAddress) -> u64) -> R,
+    {
+        let sort_handle = sortdb.index_handle_at_block(chainstate, tip)?;
+        let result = chainstate.maybe_read_only_clarity_tx(&sort_handle, tip, |clarity_tx| {
+            todo(&mut |address: &StacksAddress| {
+                StacksChainState::get_nonce(clarity_tx, &address.clone().into())
+            })
+        })?;
+        match result {
+            Some(result) => Ok(result),
+            None => Ok(todo(&mut |_: &StacksAddress| 0)),
+        }
+    }
+
+    /// The number of transactions waiting in the mempool, as of its last summary
+    fn get_mempool_tx_count(
+        sortdb: &SortitionDB,
+        chainstate: &mut StacksChainState,
+        mempool: &mut MemPoolDB,
+    ) -> Result<u64, NetError> {
+        let burn_tip = SortitionDB::get_canonical_burn_chain_tip(sortdb.conn())?;
+        let stacks_epoch = SortitionDB::get_stacks_epoch(sortdb.conn(), burn_tip.block_height)?
+            .expect("FATAL: no epoch defined");
+        let stacks_tip =
+            NakamotoChainState::get_canonical_block_header(chainstate.db(), sortdb)?
+                .ok_or_else(|| NetError::ChainstateError("No canonical Stacks tip".into()))?;
+        let stats = mempool.get_stats_snapshot(
+            chainstate,
+            sortdb,
+            &stacks_tip.index_block_hash(),
+            &stacks_epoch.block_limit,
+            &stacks_epoch.epoch_id,
+        )?;
+        Ok(stats.tx_count)
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetTransactionLifecycleRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(r#"^/v2/transactions/lifecycle/(?P<txid>[0-9a-f]{64})$"#).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v2/transactions/lifecycle/:txid"
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body for GetTransactionLifecycle"
+                    .to_string(),
+            ));
+        }
+
+        let txid = request::get_txid(captures, "txid")?;
+        self.txid = Some(txid);
+
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCGetTransactionLifecycleRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.txid = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let tip = match node.load_stacks_chain_tip(&preamble, &contents) {
+            Ok(tip) => tip,
+            Err(error_resp) => {
+                return error_resp.try_into_contents().map_err(NetError::from);
+            }
+        };
+        let txid = self
+            .txid
+            .take()
+            .ok_or(NetError::SendError("`txid` not set".into()))?;
+
+        let status_res =
+            node.with_node_state(|_network, sortdb, chainstate, mempool, _rpc_args| {
+                // mined on this fork?
+                for block_id in
+                    StacksChainState::get_index_block_hashes_for_tx(chainstate.db(), &txid)?
+                {
+                    if let Some(block_height) = chainstate
+                        .index_conn()
+                        .get_ancestor_block_height(&block_id, &tip)?
+                    {
+                        return Ok(TransactionLifecycleStatus::Mined {
+                            index_block_hash: block_id,
+                            block_height,
+                        });
+                    }
+                }
+
+                // still in the mempool?  Mined transactions stay there until garbage collection.
+                if let Some(txinfo) = MemPoolDB::get_tx(mempool.conn(), &txid)? {
+                    let metadata = &txinfo.metadata;
+                    let rank =
+                        Self::with_account_nonces(sortdb, chainstate, &tip, |account_nonce| {
+                            if account_nonce(&metadata.origin_address) > metadata.origin_nonce
+                                || account_nonce(&metadata.sponsor_address) > metadata.sponsor_nonce
+                            {
+                                return Ok(None);
+                            }
+                            MemPoolDB::get_tx_rank(mempool.conn(), &txid, account_nonce)
+                        })??;
+                    let Some(rank) = rank else {
+                        return Ok(TransactionLifecycleStatus::MinedOrSuperseded);
+                    };
+                    return Ok(TransactionLifecycleStatus::Pending {
+                        accept_time: metadata.accept_time,
+                        fee_rate: rank.fee_rate,
+                        fee_per_byte_rank: rank.fee_per_byte_rank,
+                        fee_rate_rank: rank.fee_rate_rank,
+                        mempool_tx_count: Self::get_mempool_tx_count(sortdb, chainstate, mempool)?,
+                    });
+                }
+
+                if mempool.is_tx_blacklisted(&txid)? {
+                    return Ok(TransactionLifecycleStatus::Blacklisted);
+                }
+
+                // dropped or replaced?
+                let Some(entry) = MemPoolDB::get_tx_history(mempool.conn(), &txid)? else {
+                    return Err(NetError::NotFoundError);
+                };
+                if let Some(replaced_by) = entry.replaced_by {
+                    return Ok(TransactionLifecycleStatus::Replaced {
+                        replaced_by,
+                        reason: entry.reason.to_string(),
+                        drop_time: entry.drop_time,
+                    });
+                }
+                let account_nonce =
+                    Self::with_account_nonces(sortdb, chainstate, &tip, |account_nonce| {
+                        account_nonce(&entry.origin_address)
+                    })?;
+                if account_nonce > entry.origin_nonce {
+                    return Ok(TransactionLifecycleStatus::MinedOrSuperseded);
+                }
+                Ok(TransactionLifecycleStatus::Dropped {
+                    reason: entry.reason.to_string(),
+                    drop_time: entry.drop_time,
+                })
+            });
+
+        let status = match status_res {
+            Ok(status) => status,
+            Err(NetError::NotFoundError) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpNotFound::new(format!("Transaction {} not known to this node", &txid)),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+            Err(e) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpServerError::new(format!(
+                        "Failed to query transaction {}: {:?}",
+                        &txid, &e
+                    )),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+        };
+        let data_resp = TransactionLifecycleResponse { txid, status };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&data_resp)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetTransactionLifecycleRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let lifecycle: TransactionLifecycleResponse = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(lifecycle)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for what became of a transaction
+    pub fn new_gettransaction_lifecycle(
+        host: PeerHost,
+        txid: Txid,
+        tip_req: TipRequest,
+    ) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            format!("/v2/transactions/lifecycle/{}", &txid),
+            HttpRequestContents::new().for_tip(tip_req),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_transaction_lifecycle(self) -> Result<TransactionLifecycleResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let resp: TransactionLifecycleResponse = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(resp)
+    }
+}
@@ -36,6 +36,7 @@ use crate::net::Error as NetError;
 use crate::stacks_common::codec::StacksMessageCodec;
 
 pub mod callreadonly;
+pub mod callreadonlybatch;
 pub mod get_tenures_fork_info;
 pub mod getaccount;
 pub mod getattachment;
@@ -49,10 +50,15 @@ pub mod getconstantval;
 pub mod getcontractabi;
 pub mod getcontractsrc;
 pub mod getdatavar;
+pub mod getdatavarhistory;
 pub mod getheaders;
 pub mod getinfo;
 pub mod getistraitimplemented;
+pub mod getmapentries;
 pub mod getmapentry;
+pub mod getmapentryhistory;
+pub mod getmempoolaccount;
+pub mod getmempoolstats;
 pub mod getmicroblocks_confirmed;
 pub mod getmicroblocks_indexed;
 pub mod getmicroblocks_unconfirmed;
@@ -67,6 +73,8 @@ pub mod getstxtransfercost;
 pub mod gettenure;
 pub mod gettenureinfo;
 pub mod gettenuretip;
+pub mod gettokenholders;
+pub mod gettransaction_lifecycle;
 pub mod gettransaction_unconfirmed;
 pub mod liststackerdbreplicas;
 pub mod postblock;
@@ -76,6 +84,7 @@ pub mod postblock_v3;
 pub mod postfeerate;
 pub mod postmempoolquery;
 pub mod postmicroblock;
+pub mod postsimulatetransaction;
 pub mod poststackerdbchunk;
 pub mod posttransaction;
 
@@ -90,6 +99,10 @@ impl StacksHttp {
             self.maximum_call_argument_size,
             self.read_only_call_limit.clone(),
         ));
+        self.register_rpc_endpoint(callreadonlybatch::RPCCallReadOnlyBatchRequestHandler::new(
+            self.maximum_call_argument_size,
+            self.read_only_call_limit.clone(),
+        ));
         self.register_rpc_endpoint(getaccount::RPCGetAccountRequestHandler::new());
         self.register_rpc_endpoint(getattachment::RPCGetAttachmentRequestHandler::new());
         self.register_rpc_endpoint(getattachmentsinv::RPCGetAttachmentsInvRequestHandler::new());
@@ -102,12 +115,17 @@ impl StacksHttp {
         self.register_rpc_endpoint(getcontractabi::RPCGetContractAbiRequestHandler::new());
         self.register_rpc_endpoint(getcontractsrc::RPCGetContractSrcRequestHandler::new());
         self.register_rpc_endpoint(getdatavar::RPCGetDataVarRequestHandler::new());
+        self.register_rpc_endpoint(getdatavarhistory::RPCGetDataVarHistoryRequestHandler::new());
         self.register_rpc_endpoint(getheaders::RPCHeadersRequestHandler::new());
         self.register_rpc_endpoint(getinfo::RPCPeerInfoRequestHandler::new());
         self.register_rpc_endpoint(
             getistraitimplemented::RPCGetIsTraitImplementedRequestHandler::new(),
         );
+        self.register_rpc_endpoint(getmapentries::RPCGetMapEntriesRequestHandler::new());
         self.register_rpc_endpoint(getmapentry::RPCGetMapEntryRequestHandler::new());
+        self.register_rpc_endpoint(getmapentryhistory::RPCGetMapEntryHistoryRequestHandler::new());
+        self.register_rpc_endpoint(getmempoolaccount::RPCGetMempoolAccountRequestHandler::new());
+        self.register_rpc_endpoint(getmempoolstats::RPCGetMempoolStatsRequestHandler::new());
         self.register_rpc_endpoint(
             getmicroblocks_confirmed::RPCMicroblocksConfirmedRequestHandler::new(),
         );
@@ -129,7 +147,11 @@ impl StacksHttp {
         self.register_rpc_endpoint(gettenure::RPCNakamotoTenureRequestHandler::new());
         self.register_rpc_endpoint(gettenureinfo::RPCNakamotoTenureInfoRequestHandler::new());
         self.register_rpc_endpoint(gettenuretip::RPCNakamotoTenureTipRequestHandler::new());
+        self.register_rpc_endpoint(gettokenholders::RPCGetTokenHoldersRequestHandler::new());
         self.register_rpc_endpoint(get_tenures_fork_info::GetTenuresForkInfo::default());
+        self.register_rpc_endpoint(
+            gettransaction_lifecycle::RPCGetTransactionLifecycleRequestHandler::new(),
+        );
         self.register_rpc_endpoint(
             gettransaction_unconfirmed::RPCGetTransactionUnconfirmedRequestHandler::new(),
         );
@@ -147,6 +169,11 @@ impl StacksHttp {
         self.register_rpc_endpoint(postfeerate::RPCPostFeeRateRequestHandler::new());
         self.register_rpc_endpoint(postmempoolquery::RPCMempoolQueryRequestHandler::new());
         self.register_rpc_endpoint(postmicroblock::RPCPostMicroblockRequestHandler::new());
+        self.register_rpc_endpoint(
+            postsimulatetransaction::RPCPostSimulateTransactionRequestHandler::new(
+                self.read_only_call_limit.clone(),
+            ),
+        );
         self.register_rpc_endpoint(poststackerdbchunk::RPCPostStackerDBChunkRequestHandler::new());
         self.register_rpc_endpoint(posttransaction::RPCPostTransactionRequestHandler::new());
     }
@@ -14,6 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::cmp;
 use std::io::{Read, Write};
 
 use clarity::vm::costs::ExecutionCost;
@@ -81,12 +82,29 @@ impl RPCFeeEstimate {
     }
 }
 
+/// The (blocks, confidence) confirmation targets estimated in addition to the low, middle and
+/// high fee rates, if the fee estimator tracks confirmation times
+pub const CONFIRMATION_TARGETS: [(u64, f64); 4] = [(1, 0.95), (3, 0.95), (6, 0.95), (12, 0.95)];
+
+/// The fee to pay for a transaction to be confirmed within `target_blocks` blocks of its arrival
+/// in the mempool, with probability `confidence`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RPCConfirmationFeeEstimate {
+    pub target_blocks: u64,
+    pub confidence: f64,
+    pub fee_rate: f64,
+    pub fee: u64,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RPCFeeEstimateResponse {
     pub estimated_cost: ExecutionCost,
     pub estimated_cost_scalar: u64,
     pub estimations: Vec<RPCFeeEstimate>,
     pub cost_scalar_change_by_byte: f64,
+    /// Estimates for the `CONFIRMATION_TARGETS` the fee estimator can answer
+    #[serde(default)]
+    pub confirmation_estimations: Vec<RPCConfirmationFeeEstimate>,
 }
 
 #[derive(Clone)]
@@ -137,11 +155,28 @@ impl RPCPostFeeRateRequestHandler {
             }
         }
 
+        let confirmation_estimations = CONFIRMATION_TARGETS
+            .iter()
+            .filter_map(|(target_blocks, confidence)| {
+                let fee_rate = fee_estimator
+                    .get_rate_estimate_for_target(*target_blocks, *confidence)
+                    .ok()?;
+                let fee = cmp::max((fee_rate * scalar_cost as f64) as u64, minimum_fee);
+                Some(RPCConfirmationFeeEstimate {
+                    target_blocks: *target_blocks,
+                    confidence: *confidence,
+                    fee_rate,
+                    fee,
+                })
+            })
+            .collect();
+
         Ok(RPCFeeEstimateResponse {
             estimated_cost,
             estimations,
             estimated_cost_scalar: scalar_cost,
             cost_scalar_change_by_byte: metric.change_per_byte(),
+            confirmation_estimations,
         })
     }
 }
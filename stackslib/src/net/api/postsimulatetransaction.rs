@@ -0,0 +1,423 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2023 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use clarity::vm::ast::ASTRules;
+use clarity::vm::costs::ExecutionCost;
+use clarity::vm::database::BurnStateDB;
+use regex::{Captures, Regex};
+use stacks_common::codec::{StacksMessageCodec, MAX_PAYLOAD_LEN};
+use stacks_common::types::net::PeerHost;
+use stacks_common::util::hash::{hex_bytes, to_hex};
+
+use crate::burnchains::Txid;
+use crate::chainstate::stacks::db::{DBConfig, StacksChainState};
+use crate::chainstate::stacks::events::StacksTransactionReceipt;
+use crate::chainstate::stacks::{Error as ChainError, StacksTransaction, TransactionPayload};
+use crate::clarity_vm::clarity::{ClarityConnection, ClarityReadOnlyConnection};
+use crate::net::api::posttransaction::RPCPostTransactionRequestHandler;
+use crate::net::http::{
+    parse_json, Error, HttpBadRequest, HttpContentType, HttpNotFound, HttpRequest,
+    HttpRequestContents, HttpRequestPreamble, HttpResponse, HttpResponseContents,
+    HttpResponsePayload, HttpResponsePreamble, HttpServerError,
+};
+use crate::net::httpcore::{
+    HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler, StacksHttpRequest,
+    StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState, TipRequest};
+
+#[derive(Serialize, Deserialize)]
+pub struct SimulateTransactionRequestBody {
+    pub tx: String,
+}
+
+/// An account nonce which the simulated transaction got wrong
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulatedNonceMismatch {
+    pub principal: String,
+    pub is_origin: bool,
+    pub expected: u64,
+    pub actual: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulateTransactionResponse {
+    pub txid: Txid,
+    /// Whether or not the transaction, as given, could be mined on the tip
+    pub okay: bool,
+    /// Why the transaction could not be processed at all
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Nonces which were wrong. The transaction is simulated with the expected nonces.
+    pub nonce_errors: Vec<SimulatedNonceMismatch>,
+    /// Set if the payer cannot afford the fee. The transaction is then simulated with no fee.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_error: Option<String>,
+    /// Hex-serialized result of the transaction
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<String>,
+    pub post_condition_aborted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vm_error: Option<String>,
+    /// Events, in the same form as the event observer receives them
+    pub events: Vec<serde_json::Value>,
+    pub execution_cost: ExecutionCost,
+}
+
+#[derive(Clone)]
+pub struct RPCPostSimulateTransactionRequestHandler {
+    pub tx: Option<StacksTransaction>,
+    read_only_call_limit: ExecutionCost,
+}
+impl RPCPostSimulateTransactionRequestHandler {
+    pub fn new(read_only_call_limit: ExecutionCost) -> Self {
+        Self {
+            tx: None,
+            read_only_call_limit,
+        }
+    }
+
+    /// Decode a JSON-encoded transaction
+    fn parse_simulatetransaction_json(body: &[u8]) -> Result<StacksTransaction, Error> {
+        let body: SimulateTransactionRequestBody = serde_json::from_slice(body)
+            .map_err(|_e| Error::DecodeError("Failed to parse body".into()))?;
+        let tx_bytes =
+            hex_bytes(&body.tx).map_err(|_e| Error::DecodeError("Failed to parse tx".into()))?;
+        RPCPostTransactionRequestHandler::parse_posttransaction_octets(&tx_bytes)
+    }
+
+    /// Process `tx` on top of the chain tip, without ever writing to the chainstate.
+    /// Wrong nonces and an unaffordable fee are reported, and then corrected so that the
+    /// caller still learns what the transaction would do.
+    fn simulate(
+        clarity_conn: &mut ClarityReadOnlyConnection,
+        config: &DBConfig,
+        cost_limit: &ExecutionCost,
+        tx: &StacksTransaction,
+        ast_rules: ASTRules,
+    ) -> Result<
+        (
+            Vec<SimulatedNonceMismatch>,
+            Option<String>,
+            Result<(u64, StacksTransactionReceipt), ChainError>,
+        ),
+        ChainError,
+    > {
+        let mut tx = tx.clone();
+        let mut nonce_errors = vec![];
+
+        // the sponsor nonce is checked first, so this takes at most two passes
+        while let Err((mismatch, _)) =
+            StacksChainState::check_transaction_nonces(clarity_conn, &tx, true)
+        {
+            if mismatch.is_origin {
+                tx.set_origin_nonce(mismatch.expected);
+            } else {
+                tx.set_sponsor_nonce(mismatch.expected)?;
+            }
+            nonce_errors.push(SimulatedNonceMismatch {
+                principal: mismatch.principal.to_string(),
+                is_origin: mismatch.is_origin,
+                expected: mismatch.expected,
+                actual: mismatch.actual,
+            });
+        }
+        let processed = clarity_conn.as_simulated_transaction(
+            config.mainnet,
+            config.chain_id,
+            cost_limit.clone(),
+            |transaction| {
+                StacksChainState::process_transaction_uncommitted(
+                    transaction,
+                    config,
+                    &tx,
+                    true,
+                    ast_rules,
+                )
+            },
+        )?;
+
+        let Err(ChainError::InvalidFee) = processed else {
+            return Ok((nonce_errors, None, processed));
+        };
+
+        // before epoch 2.1, the fee is paid after the payload runs, so retry without it
+        let fee_error = format!("Payer cannot afford the fee of {} uSTX", tx.get_tx_fee());
+        tx.set_tx_fee(0);
+        let processed = clarity_conn.as_simulated_transaction(
+            config.mainnet,
+            config.chain_id,
+            cost_limit.clone(),
+            |transaction| {
+                StacksChainState::process_transaction_uncommitted(
+                    transaction,
+                    config,
+                    &tx,
+                    true,
+                    ast_rules,
+                )
+            },
+        )?;
+
+        Ok((nonce_errors, Some(fee_error), processed))
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCPostSimulateTransactionRequestHandler {
+    fn verb(&self) -> &'static str {
+        "POST"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(r#"^/v2/transactions/simulate$"#).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v2/transactions/simulate"
+    }
+
+    /// Try to decode this request.
+    /// The transaction need not be signed, since it will never be mined.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() == 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected non-zero-length body for SimulateTransaction"
+                    .to_string(),
+            ));
+        }
+
+        if preamble.get_content_length() > MAX_PAYLOAD_LEN {
+            return Err(Error::DecodeError(
+                "Invalid Http request: SimulateTransaction body is too big".to_string(),
+            ));
+        }
+
+        let tx = match preamble.content_type {
+            None => {
+                return Err(Error::DecodeError(
+                    "Missing Content-Type for transaction".to_string(),
+                ));
+            }
+            Some(HttpContentType::Bytes) => {
+                RPCPostTransactionRequestHandler::parse_posttransaction_octets(body)?
+            }
+            Some(HttpContentType::JSON) => Self::parse_simulatetransaction_json(body)?,
+            _ => {
+                return Err(Error::DecodeError(
+                    "Wrong Content-Type for transaction; expected application/json or application/octet-stream".to_string(),
+                ));
+            }
+        };
+
+        match tx.payload {
+            TransactionPayload::TokenTransfer(..)
+            | TransactionPayload::ContractCall(..)
+            | TransactionPayload::SmartContract(..) => {}
+            _ => {
+                return Err(Error::DecodeError(format!(
+                    "Cannot simulate a {} transaction",
+                    tx.payload.name()
+                )));
+            }
+        }
+
+        self.tx = Some(tx);
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCPostSimulateTransactionRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.tx = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let tx = self
+            .tx
+            .take()
+            .ok_or(NetError::SendError("`tx` not set".into()))?;
+
+        let tip = match node.load_stacks_chain_tip(&preamble, &contents) {
+            Ok(tip) => tip,
+            Err(error_resp) => {
+                return error_resp.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let simulation =
+            node.with_node_state(|network, sortdb, chainstate, _mempool, _rpc_args| {
+                let burn_dbconn = sortdb
+                    .index_handle_at_block(chainstate, &tip)
+                    .map_err(|e| {
+                        StacksHttpResponse::new_error(
+                            &preamble,
+                            &HttpNotFound::new(format!("Failed to load burnchain view: {:?}", &e)),
+                        )
+                    })?;
+                let config = chainstate.config();
+                let ast_rules = network.ast_rules;
+                let read_only_call_limit = &self.read_only_call_limit;
+                let simulation =
+                    chainstate.maybe_read_only_clarity_tx(&burn_dbconn, &tip, |clarity_conn| {
+                        // the transaction may write, but only to the discarded edit log, so
+                        // writes are bounded by the block limit and everything else by the
+                        // read-only call limit
+                        let epoch = clarity_conn.get_epoch();
+                        let block_limit = burn_dbconn
+                            .get_stacks_epoch_by_epoch_id(&epoch)
+                            .ok_or(ChainError::InvalidChainstateDB)?
+                            .block_limit;
+                        let cost_limit = ExecutionCost {
+                            write_length: block_limit.write_length,
+                            write_count: block_limit.write_count,
+                            ..read_only_call_limit.clone()
+                        };
+                        Self::simulate(clarity_conn, &config, &cost_limit, &tx, ast_rules)
+                    });
+                match simulation {
+                    Ok(Some(Ok(simulation))) => Ok(simulation),
+                    Ok(None) => Err(StacksHttpResponse::new_error(
+                        &preamble,
+                        &HttpNotFound::new(format!("No such chain tip {}", &tip)),
+                    )),
+                    Ok(Some(Err(e))) => Err(StacksHttpResponse::new_error(
+                        &preamble,
+                        &HttpBadRequest::new(format!("Failed to simulate transaction: {}", &e)),
+                    )),
+                    Err(e) => Err(StacksHttpResponse::new_error(
+                        &preamble,
+                        &HttpServerError::new(format!("Failed to load chain tip: {:?}", &e)),
+                    )),
+                }
+            });
+
+        let (nonce_errors, fee_error, processed) = match simulation {
+            Ok(simulation) => simulation,
+            Err(response) => {
+                return response.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let txid = tx.txid();
+        let data_resp = match processed {
+            Ok((_fee, receipt)) => {
+                let hex_result = receipt
+                    .result
+                    .serialize_to_hex()
+                    .map_err(|e| NetError::SerializeError(format!("{:?}", &e)))?;
+                let events = receipt
+                    .events
+                    .iter()
+                    .enumerate()
+                    .map(|(event_index, event)| {
+                        event.json_serialize(event_index, &txid, !receipt.post_condition_aborted)
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| NetError::SerializeError(format!("{:?}", &e)))?;
+
+                SimulateTransactionResponse {
+                    txid,
+                    okay: nonce_errors.is_empty() && fee_error.is_none(),
+                    error: None,
+                    nonce_errors,
+                    fee_error,
+                    result: Some(format!("0x{}", hex_result)),
+                    post_condition_aborted: receipt.post_condition_aborted,
+                    vm_error: receipt.vm_error,
+                    events,
+                    execution_cost: receipt.execution_cost,
+                }
+            }
+            Err(e) => SimulateTransactionResponse {
+                txid,
+                okay: false,
+                error: Some(e.to_string()),
+                nonce_errors,
+                fee_error,
+                result: None,
+                post_condition_aborted: false,
+                vm_error: None,
+                events: vec![],
+                execution_cost: ExecutionCost::ZERO,
+            },
+        };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&data_resp)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCPostSimulateTransactionRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let simulation: SimulateTransactionResponse = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(simulation)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request to simulate a transaction
+    pub fn new_post_simulate_transaction(
+        host: PeerHost,
+        tx: StacksTransaction,
+        tip_req: TipRequest,
+    ) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "POST".into(),
+            "/v2/transactions/simulate".to_string(),
+            HttpRequestContents::new().for_tip(tip_req).payload_json(
+                serde_json::to_value(SimulateTransactionRequestBody {
+                    tx: to_hex(&tx.serialize_to_vec()),
+                })
+                .expect("FATAL: failed to construct request from infallible data"),
+            ),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_simulate_transaction_response(
+        self,
+    ) -> Result<SimulateTransactionResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let resp: SimulateTransactionResponse = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(resp)
+    }
+}
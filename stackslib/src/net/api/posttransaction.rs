@@ -67,7 +67,9 @@ impl RPCPostTransactionRequestHandler {
     }
 
     /// Decode a bare transaction from the body
-    fn parse_posttransaction_octets(mut body: &[u8]) -> Result<StacksTransaction, Error> {
+    pub(crate) fn parse_posttransaction_octets(
+        mut body: &[u8],
+    ) -> Result<StacksTransaction, Error> {
         let tx = StacksTransaction::consensus_deserialize(&mut body).map_err(|e| {
             if let CodecError::DeserializeError(msg) = e {
                 Error::DecodeError(format!("Failed to deserialize posted transaction: {}", msg))
@@ -229,6 +231,26 @@ impl RPCRequestHandler for RPCPostTransactionRequestHandler {
 
             let stacks_tip = self.get_stacks_chain_tip(&preamble, sortdb, chainstate)?;
 
+            // a transaction which skips a nonce can't be mined until the gap is filled, so if
+            // configured to, tell the client now instead of holding the transaction
+            if network.get_connection_opts().mempool_reject_nonce_gaps {
+                let nonce_gap_check = MemPoolDB::get_account_nonces(
+                    chainstate,
+                    sortdb,
+                    &stacks_tip.index_block_hash(),
+                    &tx,
+                )
+                .and_then(|(origin_nonce, sponsor_nonce)| {
+                    MemPoolDB::check_nonce_gaps(mempool.conn(), &tx, origin_nonce, sponsor_nonce)
+                });
+                if let Err(e) = nonce_gap_check {
+                    return Err(StacksHttpResponse::new_error(
+                        &preamble,
+                        &HttpBadRequest::new_json(e.into_json(&txid)),
+                    ));
+                }
+            }
+
             // accept to mempool
             if let Err(e) = mempool.submit(
                 chainstate,
@@ -101,6 +101,36 @@ fn test_try_parse_request() {
     assert!(handler.sender.is_none());
     assert!(handler.sponsor.is_none());
     assert!(handler.arguments.is_none());
+
+    // JSON arguments are decoded when the request is handled
+    let request = StacksHttpRequest::new_callreadonlyfunction_json(
+        addr.into(),
+        StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap(),
+        "hello-world".try_into().unwrap(),
+        StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R")
+            .unwrap()
+            .to_account_principal(),
+        None,
+        "set-bar".try_into().unwrap(),
+        vec![serde_json::json!("10"), serde_json::json!("2")],
+        TipRequest::UseLatestAnchoredTip,
+    );
+    let bytes = request.try_serialize().unwrap();
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    http.handle_try_parse_request(
+        &mut handler,
+        &parsed_preamble.expect_request(),
+        &bytes[offset..],
+    )
+    .unwrap();
+    assert!(handler.arguments.is_none());
+    assert_eq!(
+        handler.json_arguments,
+        Some(vec![serde_json::json!("10"), serde_json::json!("2")])
+    );
+
+    handler.restart();
+    assert!(handler.json_arguments.is_none());
 }
 
 #[test]
@@ -184,6 +214,36 @@ fn test_try_make_response() {
     );
     requests.push(request);
 
+    // JSON arguments
+    let request = StacksHttpRequest::new_callreadonlyfunction_json(
+        addr.into(),
+        StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap(),
+        "hello-world".try_into().unwrap(),
+        StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R")
+            .unwrap()
+            .to_account_principal(),
+        None,
+        "set-bar".try_into().unwrap(),
+        vec![serde_json::json!("10"), serde_json::json!("0")],
+        TipRequest::UseLatestAnchoredTip,
+    );
+    requests.push(request);
+
+    // JSON arguments of the wrong type
+    let request = StacksHttpRequest::new_callreadonlyfunction_json(
+        addr.into(),
+        StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap(),
+        "hello-world".try_into().unwrap(),
+        StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R")
+            .unwrap()
+            .to_account_principal(),
+        None,
+        "set-bar".try_into().unwrap(),
+        vec![serde_json::json!(10), serde_json::json!("2")],
+        TipRequest::UseLatestAnchoredTip,
+    );
+    requests.push(request);
+
     let mut responses = test_rpc(function_name!(), requests);
 
     // confirmed tip
@@ -270,4 +330,26 @@ fn test_try_make_response() {
 
     let (preamble, payload) = response.destruct();
     assert_eq!(preamble.status_code, 404);
+
+    // JSON arguments are decoded against the signature of `set-bar`, which then divides by zero
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    let resp = response.decode_call_readonly_response().unwrap();
+
+    assert!(!resp.okay);
+    assert!(resp.cause.unwrap().find("DivisionByZero").is_some());
+
+    // JSON arguments of the wrong type
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    let (preamble, payload) = response.destruct();
+    assert_eq!(preamble.status_code, 400);
 }
@@ -0,0 +1,311 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use clarity::vm::types::{PrincipalData, QualifiedContractIdentifier};
+use clarity::vm::Value;
+use stacks_common::types::chainstate::StacksBlockId;
+
+use super::test_rpc;
+use crate::core::BLOCK_LIMIT_MAINNET_21;
+use crate::net::api::callreadonlybatch::{BatchRead, BatchReadItem};
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{
+    HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp,
+    StacksHttpRequest,
+};
+use crate::net::{ProtocolFamily, TipRequest};
+
+const CONTRACT: &str = "ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R.hello-world";
+
+fn sender() -> PrincipalData {
+    PrincipalData::parse("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap()
+}
+
+fn call(function: &str) -> BatchReadItem {
+    BatchReadItem::Call {
+        contract: CONTRACT.into(),
+        function: function.into(),
+        arguments: vec![],
+        json_arguments: None,
+    }
+}
+
+fn data_var(name: &str) -> BatchReadItem {
+    BatchReadItem::DataVar {
+        contract: CONTRACT.into(),
+        name: name.into(),
+        proof: true,
+    }
+}
+
+fn map_entry(key: Value, proof: bool) -> BatchReadItem {
+    BatchReadItem::MapEntry {
+        contract: CONTRACT.into(),
+        name: "test-map".into(),
+        key: Some(key.serialize_to_hex().unwrap()),
+        json_key: None,
+        proof,
+    }
+}
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let request = StacksHttpRequest::new_callreadonlybatch(
+        addr.into(),
+        sender(),
+        None,
+        vec![
+            call("ro-confirmed"),
+            data_var("bar"),
+            map_entry(Value::UInt(1), false),
+        ],
+        TipRequest::SpecificTip(StacksBlockId([0x22; 32])),
+    );
+    assert_eq!(
+        request.contents().tip_request(),
+        TipRequest::SpecificTip(StacksBlockId([0x22; 32]))
+    );
+    let bytes = request.try_serialize().unwrap();
+
+    debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler =
+        callreadonlybatch::RPCCallReadOnlyBatchRequestHandler::new(4096, BLOCK_LIMIT_MAINNET_21);
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    let contract_identifier = QualifiedContractIdentifier::parse(CONTRACT).unwrap();
+    assert_eq!(handler.sender, Some(sender()));
+    assert_eq!(handler.sponsor, None);
+    assert_eq!(
+        handler.items,
+        Some(vec![
+            BatchRead::Call {
+                contract_identifier: contract_identifier.clone(),
+                function: "ro-confirmed".into(),
+                arguments: Some(vec![]),
+                json_arguments: None,
+            },
+            BatchRead::DataVar {
+                contract_identifier: contract_identifier.clone(),
+                name: "bar".into(),
+                with_proof: true,
+            },
+            BatchRead::MapEntry {
+                contract_identifier,
+                name: "test-map".into(),
+                key: Some(Value::UInt(1)),
+                json_key: None,
+                with_proof: false,
+            },
+        ])
+    );
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    let (preamble, contents) = parsed_request.destruct();
+
+    assert_eq!(&preamble, request.preamble());
+
+    // restart clears the handler state
+    handler.restart();
+    assert!(handler.sender.is_none());
+    assert!(handler.sponsor.is_none());
+    assert!(handler.items.is_none());
+
+    // a batch may not be empty, and a map key is given one way or the other
+    for items in [
+        vec![],
+        vec![BatchReadItem::MapEntry {
+            contract: CONTRACT.into(),
+            name: "test-map".into(),
+            key: Some(Value::UInt(1).serialize_to_hex().unwrap()),
+            json_key: Some(serde_json::json!("1")),
+            proof: false,
+        }],
+    ] {
+        let request = StacksHttpRequest::new_callreadonlybatch(
+            addr.into(),
+            sender(),
+            None,
+            items,
+            TipRequest::UseLatestAnchoredTip,
+        );
+        let bytes = request.try_serialize().unwrap();
+        let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+        assert!(http
+            .handle_try_parse_request(
+                &mut handler,
+                &parsed_preamble.expect_request(),
+                &bytes[offset..],
+            )
+            .is_err());
+    }
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let mut requests = vec![];
+
+    let request = StacksHttpRequest::new_callreadonlybatch(
+        addr.into(),
+        sender(),
+        None,
+        vec![
+            call("ro-confirmed"),
+            data_var("bar"),
+            map_entry(Value::UInt(1), true),
+            BatchReadItem::MapEntry {
+                contract: CONTRACT.into(),
+                name: "test-map".into(),
+                key: None,
+                json_key: Some(serde_json::json!("1")),
+                proof: false,
+            },
+            map_entry(Value::UInt(9), true),
+            data_var("does-not-exist"),
+            call("does-not-exist"),
+        ],
+        TipRequest::UseLatestAnchoredTip,
+    );
+    requests.push(request);
+
+    // query non-existent tip
+    let request = StacksHttpRequest::new_callreadonlybatch(
+        addr.into(),
+        sender(),
+        None,
+        vec![call("ro-confirmed")],
+        TipRequest::SpecificTip(StacksBlockId([0x11; 32])),
+    );
+    requests.push(request);
+
+    let mut responses = test_rpc(function_name!(), requests);
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    assert_eq!(
+        response.preamble().get_canonical_stacks_tip_height(),
+        Some(1)
+    );
+
+    let resp = response.decode_call_readonly_batch_response().unwrap();
+    assert_eq!(resp.results.len(), 7);
+    assert!(resp.cost.runtime > 0);
+    assert!(resp.cost.read_count > 0);
+    assert_eq!(resp.cost.write_count, 0);
+
+    // u1
+    assert!(resp.results[0].okay);
+    assert_eq!(
+        resp.results[0].result.as_deref(),
+        Some("0x0100000000000000000000000000000001")
+    );
+    assert!(resp.results[0].marf_proof.is_none());
+
+    // 0
+    assert!(resp.results[1].okay);
+    assert_eq!(
+        resp.results[1].result.as_deref(),
+        Some("0x0000000000000000000000000000000000")
+    );
+    assert!(resp.results[1].marf_proof.is_some());
+
+    // (some u2), whether the key is hex or JSON
+    for result in &resp.results[2..4] {
+        assert!(result.okay);
+        assert_eq!(
+            result.result.as_deref(),
+            Some("0x0a0100000000000000000000000000000002")
+        );
+    }
+    assert!(resp.results[2].marf_proof.is_some());
+    assert!(resp.results[3].marf_proof.is_none());
+
+    // no such entry reads as `none`
+    assert!(resp.results[4].okay);
+    assert_eq!(resp.results[4].result.as_deref(), Some("0x09"));
+    assert_eq!(resp.results[4].marf_proof.as_deref(), Some(""));
+
+    // failed items do not fail the batch
+    assert!(!resp.results[5].okay);
+    assert_eq!(resp.results[5].cause.as_deref(), Some("Data var not found"));
+    assert!(!resp.results[6].okay);
+    assert!(resp.results[6]
+        .cause
+        .as_ref()
+        .unwrap()
+        .contains("UndefinedFunction"));
+
+    // non-existent tip
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    let (preamble, payload) = response.destruct();
+    assert_eq!(preamble.status_code, 404);
+}
+
+#[test]
+fn test_shared_budget() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let requests = [1, 3]
+        .into_iter()
+        .map(|count| {
+            StacksHttpRequest::new_callreadonlybatch(
+                addr.into(),
+                sender(),
+                None,
+                vec![call("ro-confirmed"); count],
+                TipRequest::UseLatestAnchoredTip,
+            )
+        })
+        .collect();
+
+    let mut responses = test_rpc(function_name!(), requests);
+    let single = responses
+        .remove(0)
+        .decode_call_readonly_batch_response()
+        .unwrap();
+    let batch = responses
+        .remove(0)
+        .decode_call_readonly_batch_response()
+        .unwrap();
+
+    // each call is charged to the batch
+    assert!(batch.results.iter().all(|result| result.okay));
+    assert_eq!(batch.cost.runtime, 3 * single.cost.runtime);
+}
@@ -0,0 +1,187 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use clarity::vm::types::QualifiedContractIdentifier;
+use stacks_common::types::chainstate::{StacksAddress, StacksBlockId};
+use stacks_common::types::Address;
+
+use super::test_rpc;
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{
+    HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp,
+    StacksHttpRequest,
+};
+use crate::net::TipRequest;
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let request = StacksHttpRequest::new_getdatavarhistory(
+        addr.into(),
+        StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap(),
+        "hello-world".try_into().unwrap(),
+        "bar".try_into().unwrap(),
+        TipRequest::SpecificTip(StacksBlockId([0x22; 32])),
+        Some(10),
+        Some(5),
+    );
+    assert_eq!(
+        request.contents().tip_request(),
+        TipRequest::SpecificTip(StacksBlockId([0x22; 32]))
+    );
+
+    let bytes = request.try_serialize().unwrap();
+
+    debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = getdatavarhistory::RPCGetDataVarHistoryRequestHandler::new();
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    let (preamble, contents) = parsed_request.destruct();
+
+    assert_eq!(
+        handler.contract_identifier,
+        Some(
+            QualifiedContractIdentifier::parse(
+                "ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R.hello-world"
+            )
+            .unwrap()
+        )
+    );
+    assert_eq!(handler.varname, Some("bar".into()));
+    assert_eq!(handler.before, Some(10));
+    assert_eq!(handler.limit, Some(5));
+
+    assert_eq!(&preamble, request.preamble());
+
+    handler.restart();
+    assert!(handler.contract_identifier.is_none());
+    assert!(handler.varname.is_none());
+    assert!(handler.before.is_none());
+    assert!(handler.limit.is_none());
+
+    // pages are bounded
+    let request = StacksHttpRequest::new_getdatavarhistory(
+        addr.into(),
+        StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap(),
+        "hello-world".try_into().unwrap(),
+        "bar".try_into().unwrap(),
+        TipRequest::UseLatestAnchoredTip,
+        None,
+        Some(getdatavarhistory::MAX_DATA_KEY_HISTORY_PAGE + 1),
+    );
+    let bytes = request.try_serialize().unwrap();
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    assert!(http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .is_err());
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let mut requests = vec![];
+
+    // query existing
+    let request = StacksHttpRequest::new_getdatavarhistory(
+        addr.into(),
+        StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap(),
+        "hello-world".try_into().unwrap(),
+        "bar".try_into().unwrap(),
+        TipRequest::UseLatestAnchoredTip,
+        None,
+        None,
+    );
+    requests.push(request);
+
+    // query non-existant var
+    let request = StacksHttpRequest::new_getdatavarhistory(
+        addr.into(),
+        StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap(),
+        "hello-world".try_into().unwrap(),
+        "does-not-exist".try_into().unwrap(),
+        TipRequest::UseLatestAnchoredTip,
+        None,
+        None,
+    );
+    requests.push(request);
+
+    // query non-existant tip
+    let request = StacksHttpRequest::new_getdatavarhistory(
+        addr.into(),
+        StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap(),
+        "hello-world".try_into().unwrap(),
+        "bar".try_into().unwrap(),
+        TipRequest::SpecificTip(StacksBlockId([0x11; 32])),
+        None,
+        None,
+    );
+    requests.push(request);
+
+    let mut responses = test_rpc(function_name!(), requests);
+
+    // the var was set once, when the contract was deployed
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    assert_eq!(
+        response.preamble().get_canonical_stacks_tip_height(),
+        Some(1)
+    );
+
+    let resp = response.decode_data_key_history_response().unwrap();
+    assert_eq!(resp.changes.len(), 1);
+    assert_eq!(resp.changes[0].data, "0x0000000000000000000000000000000000");
+    assert!(resp.changes[0].block_height <= 1);
+    assert!(resp.next_before.is_none());
+
+    // no such var
+    let response = responses.remove(0);
+    let resp = response.decode_data_key_history_response().unwrap();
+    assert!(resp.changes.is_empty());
+    assert!(resp.next_before.is_none());
+
+    // no such tip
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    let (preamble, body) = response.destruct();
+    assert_eq!(preamble.status_code, 404);
+}
@@ -16,7 +16,9 @@
 
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 
-use clarity::vm::types::{PrincipalData, QualifiedContractIdentifier, StacksAddressExtensions};
+use clarity::vm::types::{
+    PrincipalData, QualifiedContractIdentifier, StacksAddressExtensions, TupleData,
+};
 use clarity::vm::{ClarityName, ContractName, Value};
 use stacks_common::codec::StacksMessageCodec;
 use stacks_common::types::chainstate::StacksAddress;
@@ -90,6 +92,30 @@ fn test_try_parse_request() {
     assert!(handler.contract_identifier.is_none());
     assert!(handler.map_name.is_none());
     assert!(handler.key.is_none());
+
+    // a JSON key is decoded when the request is handled
+    let request = StacksHttpRequest::new_getmapentry_json(
+        addr.into(),
+        StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap(),
+        "hello-world".try_into().unwrap(),
+        "test-map".into(),
+        serde_json::json!("13"),
+        TipRequest::UseLatestAnchoredTip,
+        false,
+    );
+    let bytes = request.try_serialize().unwrap();
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    http.handle_try_parse_request(
+        &mut handler,
+        &parsed_preamble.expect_request(),
+        &bytes[offset..],
+    )
+    .unwrap();
+    assert!(handler.key.is_none());
+    assert_eq!(handler.json_key, Some(serde_json::json!("13")));
+
+    handler.restart();
+    assert!(handler.json_key.is_none());
 }
 
 #[test]
@@ -146,6 +172,30 @@ fn test_try_make_response() {
     );
     requests.push(request);
 
+    // query with a JSON key
+    let request = StacksHttpRequest::new_getmapentry_json(
+        addr.into(),
+        StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap(),
+        "hello-world".try_into().unwrap(),
+        "unit-map".try_into().unwrap(),
+        serde_json::json!({"account": "ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R"}),
+        TipRequest::UseLatestAnchoredTip,
+        false,
+    );
+    requests.push(request);
+
+    // query with a JSON key of the wrong type
+    let request = StacksHttpRequest::new_getmapentry_json(
+        addr.into(),
+        StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap(),
+        "hello-world".try_into().unwrap(),
+        "unit-map".try_into().unwrap(),
+        serde_json::json!({"account": 1}),
+        TipRequest::UseLatestAnchoredTip,
+        false,
+    );
+    requests.push(request);
+
     let mut responses = test_rpc(function_name!(), requests);
 
     // latest data
@@ -211,6 +261,33 @@ fn test_try_make_response() {
     let resp = response.decode_map_entry_response().unwrap();
     assert_eq!(resp.data, "0x09");
     assert_eq!(resp.marf_proof, Some("".to_string()));
+
+    // JSON key
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    let resp = response.decode_map_entry_response().unwrap();
+    assert_eq!(
+        Value::try_deserialize_hex_untyped(&resp.data).unwrap(),
+        Value::some(Value::from(
+            TupleData::from_data(vec![("units".into(), Value::Int(123))]).unwrap()
+        ))
+        .unwrap()
+    );
+    assert!(resp.marf_proof.is_none());
+
+    // JSON key of the wrong type
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    let (preamble, payload) = response.destruct();
+    assert_eq!(preamble.status_code, 400);
 }
 
 /*
@@ -0,0 +1,136 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use stacks_common::types::chainstate::{StacksAddress, StacksBlockId};
+use stacks_common::types::Address;
+
+use super::TestRPC;
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{
+    HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp,
+    StacksHttpRequest,
+};
+use crate::net::TipRequest;
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let address = StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap();
+    let request = StacksHttpRequest::new_getmempoolaccount(
+        addr.into(),
+        address.clone(),
+        TipRequest::SpecificTip(StacksBlockId([0x22; 32])),
+    );
+    assert_eq!(
+        request.contents().tip_request(),
+        TipRequest::SpecificTip(StacksBlockId([0x22; 32]))
+    );
+
+    let bytes = request.try_serialize().unwrap();
+
+    debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = getmempoolaccount::RPCGetMempoolAccountRequestHandler::new();
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    let (preamble, contents) = parsed_request.destruct();
+
+    assert_eq!(handler.address, Some(address));
+    assert_eq!(&preamble, request.preamble());
+
+    handler.restart();
+    assert!(handler.address.is_none());
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let rpc_test = TestRPC::setup(function_name!());
+    let mempool_txids = rpc_test.mempool_txids.clone();
+    let mut requests = vec![];
+
+    // the mempool has this account's nonces 0 through 9
+    let request = StacksHttpRequest::new_getmempoolaccount(
+        addr.into(),
+        StacksAddress::from_string("STVN97YYA10MY5F6KQJHKNYJNM24C4A1AT39WRW").unwrap(),
+        TipRequest::UseLatestAnchoredTip,
+    );
+    requests.push(request);
+
+    // nothing is pending for this account
+    let request = StacksHttpRequest::new_getmempoolaccount(
+        addr.into(),
+        StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap(),
+        TipRequest::UseLatestAnchoredTip,
+    );
+    requests.push(request);
+
+    // query non-existant tip
+    let request = StacksHttpRequest::new_getmempoolaccount(
+        addr.into(),
+        StacksAddress::from_string("STVN97YYA10MY5F6KQJHKNYJNM24C4A1AT39WRW").unwrap(),
+        TipRequest::SpecificTip(StacksBlockId([0x11; 32])),
+    );
+    requests.push(request);
+
+    let mut responses = rpc_test.run(requests);
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    let resp = response.decode_mempool_account_response().unwrap();
+    assert_eq!(resp.nonce, 0);
+    assert_eq!(resp.next_nonce, 10);
+    assert_eq!(resp.first_gap, None);
+    let txids: Vec<_> = resp.pending.iter().map(|tx| tx.txid.clone()).collect();
+    assert_eq!(txids, mempool_txids);
+    for (nonce, tx) in resp.pending.iter().enumerate() {
+        assert_eq!(tx.nonce, nonce as u64);
+        assert!(tx.is_origin);
+        assert_eq!(tx.fee, 1000);
+        assert!(tx.executable);
+        // these transactions were stored without a fee rate
+        assert!(tx.fee_rate.is_none());
+        assert!(tx.package_fee_rate.is_none());
+    }
+
+    let response = responses.remove(0);
+    let resp = response.decode_mempool_account_response().unwrap();
+    assert!(resp.pending.is_empty());
+    assert_eq!(resp.next_nonce, resp.nonce);
+    assert_eq!(resp.first_gap, None);
+
+    let response = responses.remove(0);
+    let (preamble, _body) = response.destruct();
+    assert_eq!(preamble.status_code, 404);
+}
@@ -0,0 +1,100 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use super::TestRPC;
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{RPCRequestHandler, StacksHttp, StacksHttpRequest};
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let request = StacksHttpRequest::new_getmempoolstats(addr.into());
+    let bytes = request.try_serialize().unwrap();
+
+    debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = getmempoolstats::RPCGetMempoolStatsRequestHandler::new();
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    let (preamble, _contents) = parsed_request.destruct();
+
+    assert_eq!(&preamble, request.preamble());
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let rpc_test = TestRPC::setup(function_name!());
+    let requests = vec![
+        StacksHttpRequest::new_getmempoolstats(addr.into()),
+        StacksHttpRequest::new_getmempoolstats(addr.into()),
+    ];
+    let mut responses = rpc_test.run(requests);
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    // the mempool has 10 token transfers of the same size and fee, none of them estimated
+    let stats = response.decode_mempool_stats().unwrap();
+    assert_eq!(stats.tx_count, 10);
+    assert!(stats.oldest_tx_age_secs.is_some());
+    assert_eq!(stats.tx_types.token_transfer, 10);
+    assert_eq!(stats.tx_types.other, 0);
+
+    assert_eq!(stats.fee_per_byte.buckets.len(), 1);
+    let bucket = &stats.fee_per_byte.buckets[0];
+    assert_eq!(bucket.tx_count, 10);
+    assert_eq!(bucket.total_bytes, stats.total_bytes);
+    assert_eq!(bucket.blocks_to_clear, 1);
+
+    assert!(stats.fee_rate.buckets.is_empty());
+    assert_eq!(stats.fee_rate.unestimated_tx_count, 10);
+
+    // the unit estimator costs each of them something in every dimension
+    let by_cost = &stats.fee_rate_by_cost;
+    for histogram in [
+        &by_cost.runtime,
+        &by_cost.read_count,
+        &by_cost.read_length,
+        &by_cost.write_count,
+        &by_cost.write_length,
+    ] {
+        assert_eq!(histogram.buckets.len(), 1);
+        assert_eq!(histogram.buckets[0].tx_count, 10);
+        assert_eq!(histogram.unestimated_tx_count, 0);
+    }
+
+    // the second request is served the same summary
+    let response = responses.remove(0);
+    assert_eq!(response.decode_mempool_stats().unwrap(), stats);
+}
@@ -0,0 +1,120 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use super::TestRPC;
+use crate::net::api::gettransaction_lifecycle::TransactionLifecycleStatus;
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{RPCRequestHandler, StacksHttp, StacksHttpRequest};
+use crate::net::TipRequest;
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let request = StacksHttpRequest::new_gettransaction_lifecycle(
+        addr.into(),
+        Txid([0x11; 32]),
+        TipRequest::UseLatestAnchoredTip,
+    );
+    let bytes = request.try_serialize().unwrap();
+
+    debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = gettransaction_lifecycle::RPCGetTransactionLifecycleRequestHandler::new();
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    assert_eq!(handler.txid, Some(Txid([0x11; 32])));
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    let (preamble, _contents) = parsed_request.destruct();
+
+    assert_eq!(&preamble, request.preamble());
+
+    handler.restart();
+    assert!(handler.txid.is_none());
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let rpc_test = TestRPC::setup(function_name!());
+    let mut requests = vec![];
+    let pending_txid = rpc_test.mempool_txids[0].clone();
+
+    // pending in the mempool
+    let request = StacksHttpRequest::new_gettransaction_lifecycle(
+        addr.into(),
+        pending_txid.clone(),
+        TipRequest::UseLatestAnchoredTip,
+    );
+    requests.push(request);
+
+    // never seen
+    let request = StacksHttpRequest::new_gettransaction_lifecycle(
+        addr.into(),
+        Txid([0x21; 32]),
+        TipRequest::UseLatestAnchoredTip,
+    );
+    requests.push(request);
+
+    let mut responses = rpc_test.run(requests);
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    // the mempool has 10 token transfers of the same size and fee, none of them estimated
+    let resp = response.decode_transaction_lifecycle().unwrap();
+    assert_eq!(resp.txid, pending_txid);
+    match resp.status {
+        TransactionLifecycleStatus::Pending {
+            fee_rate,
+            fee_per_byte_rank,
+            fee_rate_rank,
+            mempool_tx_count,
+            ..
+        } => {
+            assert!(fee_rate.is_none());
+            assert_eq!(fee_per_byte_rank, 1);
+            assert!(fee_rate_rank.is_none());
+            assert_eq!(mempool_tx_count, 10);
+        }
+        status => panic!("Not pending: {status:?}"),
+    }
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+    let (preamble, _body) = response.destruct();
+
+    assert_eq!(preamble.status_code, 404);
+}
@@ -54,6 +54,7 @@ use crate::net::{
 };
 
 mod callreadonly;
+mod callreadonlybatch;
 mod get_tenures_fork_info;
 mod getaccount;
 mod getattachment;
@@ -67,10 +68,15 @@ mod getconstantval;
 mod getcontractabi;
 mod getcontractsrc;
 mod getdatavar;
+mod getdatavarhistory;
 mod getheaders;
 mod getinfo;
 mod getistraitimplemented;
+mod getmapentries;
 mod getmapentry;
+mod getmapentryhistory;
+mod getmempoolaccount;
+mod getmempoolstats;
 mod getmicroblocks_confirmed;
 mod getmicroblocks_indexed;
 mod getmicroblocks_unconfirmed;
@@ -84,6 +90,8 @@ mod getstxtransfercost;
 mod gettenure;
 mod gettenureinfo;
 mod gettenuretip;
+mod gettokenholders;
+mod gettransaction_lifecycle;
 mod gettransaction_unconfirmed;
 mod liststackerdbreplicas;
 mod postblock;
@@ -92,6 +100,7 @@ mod postblock_v3;
 mod postfeerate;
 mod postmempoolquery;
 mod postmicroblock;
+mod postsimulatetransaction;
 mod poststackerdbchunk;
 mod posttransaction;
 
@@ -0,0 +1,309 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2023 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use clarity::vm::Value;
+use stacks_common::address::{AddressHashMode, C32_ADDRESS_VERSION_TESTNET_SINGLESIG};
+use stacks_common::types::chainstate::{
+    StacksAddress, StacksBlockId, StacksPrivateKey, StacksPublicKey,
+};
+
+use super::TestRPC;
+use crate::chainstate::stacks::{
+    CoinbasePayload, StacksTransaction, TokenTransferMemo, TransactionAuth, TransactionPayload,
+    TransactionVersion,
+};
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{
+    HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp, StacksHttpRequest,
+};
+use crate::net::test::TestEventObserver;
+use crate::net::{ProtocolFamily, TipRequest};
+
+/// An unsigned contract-call to `hello-world.set-bar` from ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R
+fn make_set_bar_tx(x: i128, y: i128, nonce: u64, fee: u64) -> StacksTransaction {
+    let privk1 = StacksPrivateKey::from_hex(
+        "9f1f85a512a96a244e4c0d762788500687feb97481639572e3bffbd6860e6ab001",
+    )
+    .unwrap();
+    let addr1 = StacksAddress::from_public_keys(
+        C32_ADDRESS_VERSION_TESTNET_SINGLESIG,
+        &AddressHashMode::SerializeP2PKH,
+        1,
+        &vec![StacksPublicKey::from_private(&privk1)],
+    )
+    .unwrap();
+
+    let mut tx = StacksTransaction::new(
+        TransactionVersion::Testnet,
+        TransactionAuth::from_p2pkh(&privk1).unwrap(),
+        TransactionPayload::new_contract_call(
+            addr1,
+            "hello-world",
+            "set-bar",
+            vec![Value::Int(x), Value::Int(y)],
+        )
+        .unwrap(),
+    );
+    tx.chain_id = 0x80000000;
+    tx.set_origin_nonce(nonce);
+    tx.set_tx_fee(fee);
+    tx
+}
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let tx = make_set_bar_tx(10, 2, 0, 0);
+    let request = StacksHttpRequest::new_post_simulate_transaction(
+        addr.into(),
+        tx.clone(),
+        TipRequest::SpecificTip(StacksBlockId([0x22; 32])),
+    );
+    let bytes = request.try_serialize().unwrap();
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = postsimulatetransaction::RPCPostSimulateTransactionRequestHandler::new(
+        ConnectionOptions::default().read_only_call_limit,
+    );
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    assert_eq!(handler.tx, Some(tx));
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    let (preamble, contents) = parsed_request.destruct();
+
+    assert_eq!(&preamble, request.preamble());
+    assert_eq!(
+        contents.tip_request(),
+        TipRequest::SpecificTip(StacksBlockId([0x22; 32]))
+    );
+
+    handler.restart();
+    assert!(handler.tx.is_none());
+
+    // coinbases cannot be simulated
+    let coinbase = StacksTransaction::new(
+        TransactionVersion::Testnet,
+        make_set_bar_tx(0, 0, 0, 0).auth,
+        TransactionPayload::Coinbase(CoinbasePayload([0u8; 32]), None, None),
+    );
+    let request = StacksHttpRequest::new_post_simulate_transaction(
+        addr.into(),
+        coinbase,
+        TipRequest::UseLatestAnchoredTip,
+    );
+    let bytes = request.try_serialize().unwrap();
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    assert!(http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .is_err());
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let rpc_test = TestRPC::setup(function_name!());
+    let sendable_txs = rpc_test.sendable_txs.clone();
+
+    let mut requests = vec![];
+
+    // unsigned, with a stale nonce
+    let request = StacksHttpRequest::new_post_simulate_transaction(
+        addr.into(),
+        make_set_bar_tx(10, 2, 1000, 0),
+        TipRequest::UseLatestAnchoredTip,
+    );
+    requests.push(request);
+
+    // runtime error
+    let request = StacksHttpRequest::new_post_simulate_transaction(
+        addr.into(),
+        make_set_bar_tx(10, 0, 1000, 0),
+        TipRequest::UseLatestAnchoredTip,
+    );
+    requests.push(request);
+
+    // unaffordable fee
+    let request = StacksHttpRequest::new_post_simulate_transaction(
+        addr.into(),
+        make_set_bar_tx(10, 2, 1000, u64::MAX),
+        TipRequest::UseLatestAnchoredTip,
+    );
+    requests.push(request);
+
+    // signed STX transfer
+    let request = StacksHttpRequest::new_post_simulate_transaction(
+        addr.into(),
+        sendable_txs[0].clone(),
+        TipRequest::UseLatestAnchoredTip,
+    );
+    requests.push(request);
+
+    // more reads than the read-only call limit allows
+    let mut tx = make_set_bar_tx(0, 0, 2, 0);
+    tx.payload = TransactionPayload::new_smart_contract(
+        "too-many-reads",
+        "(define-data-var v int 0) (var-get v) (var-get v) (var-get v) (var-get v) (var-get v)",
+        None,
+    )
+    .unwrap();
+    let request = StacksHttpRequest::new_post_simulate_transaction(
+        addr.into(),
+        tx,
+        TipRequest::UseLatestAnchoredTip,
+    );
+    requests.push(request);
+
+    // no such tip
+    let request = StacksHttpRequest::new_post_simulate_transaction(
+        addr.into(),
+        make_set_bar_tx(10, 2, 1000, 0),
+        TipRequest::SpecificTip(StacksBlockId([0x22; 32])),
+    );
+    requests.push(request);
+
+    let mut responses = rpc_test.run(requests);
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    let resp = response.decode_simulate_transaction_response().unwrap();
+    assert!(!resp.okay);
+    assert!(resp.error.is_none());
+    assert_eq!(resp.nonce_errors.len(), 1);
+    assert!(resp.nonce_errors[0].is_origin);
+    assert_eq!(resp.nonce_errors[0].actual, 1000);
+    assert_eq!(
+        resp.nonce_errors[0].principal,
+        "ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R"
+    );
+    assert!(resp.fee_error.is_none());
+    assert_eq!(
+        Value::try_deserialize_hex_untyped(&resp.result.unwrap()).unwrap(),
+        Value::okay(Value::Int(5)).unwrap()
+    );
+    assert!(!resp.post_condition_aborted);
+    assert!(resp.execution_cost.runtime > 0);
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    let resp = response.decode_simulate_transaction_response().unwrap();
+    assert!(resp.error.is_none());
+    assert!(resp.vm_error.unwrap().contains("DivisionByZero"));
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    let resp = response.decode_simulate_transaction_response().unwrap();
+    assert!(!resp.okay);
+    assert!(resp.fee_error.is_some());
+    assert!(resp.result.is_some());
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    let resp = response.decode_simulate_transaction_response().unwrap();
+    assert_eq!(resp.txid, sendable_txs[0].txid());
+    assert!(resp.error.is_none());
+    assert_eq!(resp.events.len(), 1);
+    assert_eq!(resp.events[0]["type"], "stx_transfer_event");
+    assert_eq!(resp.events[0]["stx_transfer_event"]["amount"], "123");
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    let resp = response.decode_simulate_transaction_response().unwrap();
+    assert!(!resp.okay);
+    assert!(resp.result.is_none());
+    assert!(resp.error.unwrap().contains("Cost"));
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    let (preamble, body) = response.destruct();
+    assert_eq!(preamble.status_code, 404);
+}
+
+#[test]
+fn test_try_make_response_nakamoto() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let test_observer = TestEventObserver::new();
+    let rpc_test = TestRPC::setup_nakamoto(function_name!(), &test_observer);
+    let canonical_tip = rpc_test.canonical_tip.clone();
+
+    let mut tx = make_set_bar_tx(0, 0, 0, 0);
+    tx.payload = TransactionPayload::TokenTransfer(
+        StacksAddress::burn_address(false).into(),
+        1,
+        TokenTransferMemo([0u8; 34]),
+    );
+    let request = StacksHttpRequest::new_post_simulate_transaction(
+        addr.into(),
+        tx,
+        TipRequest::SpecificTip(canonical_tip.clone()),
+    );
+
+    let mut responses = rpc_test.run(vec![request]);
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    // simulated on the Nakamoto tip itself, rather than in a 2.x block built on it
+    let resp = response.decode_simulate_transaction_response().unwrap();
+    assert!(resp.nonce_errors.is_empty());
+    assert!(resp.fee_error.is_none());
+}
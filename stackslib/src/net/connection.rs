@@ -442,6 +442,14 @@ pub struct ConnectionOptions {
     pub block_proposal_max_age_secs: u64,
     /// StackerDB replicas to talk to for a particular smart contract
     pub stackerdb_hint_replicas: HashMap<QualifiedContractIdentifier, Vec<NeighborAddress>>,
+    /// Whether to reject transactions POSTed over RPC whose nonces leave a gap in their
+    /// accounts' pending transactions
+    pub mempool_reject_nonce_gaps: bool,
+    /// If set, the most bytes of transactions the mempool holds before it evicts the ones
+    /// with the lowest estimated fee rate, from the top of each account's nonce chain
+    pub mempool_max_size_bytes: Option<u64>,
+    /// If set, the most pending transactions an address may have in the mempool
+    pub mempool_max_txs_per_address: Option<u64>,
 
     // fault injection
     /// Disable neighbor walk and discovery
@@ -576,6 +584,9 @@ impl std::default::Default for ConnectionOptions {
             auth_token: None,
             block_proposal_max_age_secs: DEFAULT_BLOCK_PROPOSAL_MAX_AGE_SECS,
             stackerdb_hint_replicas: HashMap::new(),
+            mempool_reject_nonce_gaps: false,
+            mempool_max_size_bytes: None,
+            mempool_max_txs_per_address: None,
 
             // no faults on by default
             disable_neighbor_walk: false,
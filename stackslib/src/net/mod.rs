@@ -73,6 +73,7 @@ use crate::chainstate::stacks::boot::{
 };
 use crate::chainstate::stacks::db::blocks::MemPoolRejection;
 use crate::chainstate::stacks::db::StacksChainState;
+use crate::chainstate::stacks::index::marf::MarfConnection;
 use crate::chainstate::stacks::index::Error as marf_error;
 use crate::chainstate::stacks::{
     Error as chainstate_error, Error as chain_error, StacksBlock, StacksBlockHeader,
@@ -85,7 +86,7 @@ use crate::cost_estimates::metrics::CostMetric;
 use crate::cost_estimates::{CostEstimator, FeeEstimator, FeeRateEstimate};
 use crate::net::atlas::{Attachment, AttachmentInstance};
 use crate::net::dns::*;
-use crate::net::http::error::{HttpNotFound, HttpServerError};
+use crate::net::http::error::{HttpError, HttpNotFound, HttpServerError};
 use crate::net::http::{
     Error as HttpErr, HttpRequestContents, HttpRequestPreamble, HttpResponsePreamble,
 };
@@ -762,6 +763,31 @@ impl<'a> StacksNodeState<'a> {
         contents: &HttpRequestContents,
     ) -> Result<StacksBlockId, StacksHttpResponse> {
         self.with_node_state(|_network, sortdb, chainstate, _mempool, _rpc_args| {
+            // a proof may need to walk any ancestor trie, and pruned tries can't be walked
+            if contents.get_with_proof() {
+                match chainstate
+                    .clarity_state
+                    .with_marf(|marf| marf.has_pruned_blocks())
+                {
+                    Ok(false) => {}
+                    Ok(true) => {
+                        return Err(StacksHttpResponse::new_error(
+                            preamble,
+                            &HttpError::new(
+                                410,
+                                "MARF proofs are unavailable on a pruned node; retry with proof=0"
+                                    .to_string(),
+                            ),
+                        ));
+                    }
+                    Err(e) => {
+                        return Err(StacksHttpResponse::new_error(
+                            preamble,
+                            &HttpServerError::new(format!("Failed to load chain tip: {:?}", &e)),
+                        ));
+                    }
+                }
+            }
             let tip_req = contents.tip_request();
             match tip_req {
                 TipRequest::UseLatestUnconfirmedTip => {
@@ -809,7 +835,26 @@ impl<'a> StacksNodeState<'a> {
                         }
                     }
                 }
-                TipRequest::SpecificTip(tip) => Ok(tip.clone()),
+                TipRequest::SpecificTip(tip) => {
+                    // historical state may have been pruned away
+                    match chainstate
+                        .clarity_state
+                        .with_marf(|marf| marf.is_block_pruned(&tip))
+                    {
+                        Ok(false) => Ok(tip.clone()),
+                        Ok(true) => Err(StacksHttpResponse::new_error(
+                            preamble,
+                            &HttpError::new(
+                                410,
+                                format!("The state of chain tip {tip} has been pruned"),
+                            ),
+                        )),
+                        Err(e) => Err(StacksHttpResponse::new_error(
+                            preamble,
+                            &HttpServerError::new(format!("Failed to load chain tip: {:?}", &e)),
+                        )),
+                    }
+                }
                 TipRequest::UseLatestAnchoredTip => {
                     match NakamotoChainState::get_canonical_block_header(chainstate.db(), sortdb) {
                         Ok(Some(tip)) => Ok(StacksBlockId::new(
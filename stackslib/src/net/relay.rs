@@ -50,7 +50,7 @@ use crate::chainstate::stacks::events::StacksTransactionReceipt;
 use crate::chainstate::stacks::{StacksBlockHeader, TransactionPayload};
 use crate::clarity_vm::clarity::Error as clarity_error;
 use crate::core::mempool::{MemPoolDB, *};
-use crate::monitoring::update_stacks_tip_height;
+use crate::monitoring::{update_mempool_stats, update_stacks_tip_height};
 use crate::net::chat::*;
 use crate::net::connection::*;
 use crate::net::db::*;
@@ -2283,9 +2283,10 @@ impl Relayer {
                     return Ok(vec![]);
                 }
             };
-        let epoch_id = SortitionDB::get_stacks_epoch(sortdb.conn(), network_result.burn_height)?
-            .expect("FATAL: no epoch defined")
-            .epoch_id;
+        let stacks_epoch =
+            SortitionDB::get_stacks_epoch(sortdb.conn(), network_result.burn_height)?
+                .expect("FATAL: no epoch defined");
+        let epoch_id = stacks_epoch.epoch_id;
 
         let chain_height = chain_tip.anchored_header.height();
         Relayer::filter_problematic_transactions(network_result, chainstate.mainnet, epoch_id);
@@ -2315,11 +2316,27 @@ impl Relayer {
             ret.push((vec![], tx.clone()));
         }
 
-        mempool.garbage_collect(
-            chain_height,
-            &epoch_id.mempool_garbage_behavior(),
-            event_observer,
-        )?;
+        let gc_behavior = epoch_id.mempool_garbage_behavior();
+        let tip = chain_tip.index_block_hash();
+        let sort_handle = sortdb.index_handle_at_block(chainstate, &tip)?;
+        let collected =
+            chainstate.maybe_read_only_clarity_tx(&sort_handle, &tip, |clarity_tx| {
+                mempool.garbage_collect(chain_height, &gc_behavior, event_observer, |address| {
+                    StacksChainState::get_nonce(clarity_tx, &address.clone().into())
+                })
+            })?;
+        match collected {
+            Some(collected) => collected?,
+            // accounts are taken to be unused if the tip's state is not available
+            None => mempool.garbage_collect(chain_height, &gc_behavior, event_observer, |_| 0)?,
+        }
+        update_mempool_stats(
+            mempool,
+            chainstate,
+            sortdb,
+            &chain_tip.index_block_hash(),
+            &stacks_epoch,
+        );
 
         Ok(ret)
     }
@@ -2787,9 +2787,6 @@ impl RelayerThread {
         let config = runloop.config().clone();
         let globals = runloop.get_globals();
         let burn_db_path = config.get_burn_db_file_path();
-        let stacks_chainstate_path = config.get_chainstate_path_str();
-        let is_mainnet = config.is_mainnet();
-        let chain_id = config.burnchain.chain_id;
 
         let sortdb = SortitionDB::open(&burn_db_path, true, runloop.get_burnchain().pox_constants)
             .expect("FATAL: failed to open burnchain DB");
@@ -2797,21 +2794,9 @@ impl RelayerThread {
         let chainstate =
             open_chainstate_with_faults(&config).expect("FATAL: failed to open chainstate DB");
 
-        let cost_estimator = config
-            .make_cost_estimator()
-            .unwrap_or_else(|| Box::new(UnitEstimator));
-        let metric = config
-            .make_cost_metric()
-            .unwrap_or_else(|| Box::new(UnitMetric));
-
-        let mempool = MemPoolDB::open(
-            is_mainnet,
-            chain_id,
-            &stacks_chainstate_path,
-            cost_estimator,
-            metric,
-        )
-        .expect("Database failure opening mempool");
+        let mempool = config
+            .connect_mempool_db()
+            .expect("Database failure opening mempool");
 
         let keychain = Keychain::default(config.node.seed.clone());
         let bitcoin_controller = BitcoinRegtestController::new_dummy(config.clone());
@@ -4330,22 +4315,9 @@ pub struct PeerThread {
 impl PeerThread {
     /// set up the mempool DB connection
     pub fn connect_mempool_db(config: &Config) -> MemPoolDB {
-        // create estimators, metric instances for RPC handler
-        let cost_estimator = config
-            .make_cost_estimator()
-            .unwrap_or_else(|| Box::new(UnitEstimator));
-        let metric = config
-            .make_cost_metric()
-            .unwrap_or_else(|| Box::new(UnitMetric));
-
-        MemPoolDB::open(
-            config.is_mainnet(),
-            config.burnchain.chain_id,
-            &config.get_chainstate_path_str(),
-            cost_estimator,
-            metric,
-        )
-        .expect("Database failure opening mempool")
+        config
+            .connect_mempool_db()
+            .expect("Database failure opening mempool")
     }
 
     /// Instantiate the p2p thread.
@@ -288,7 +288,10 @@ impl RunLoop {
             .collect();
         atlas_config.genesis_attachments = Some(genesis_attachments);
 
-        let chain_state_db = self.boot_chainstate(burnchain_config);
+        let mut chain_state_db = self.boot_chainstate(burnchain_config);
+        chain_state_db
+            .clarity_state
+            .set_tx_trace_config(self.config.node.get_tx_trace_config());
 
         // NOTE: re-instantiate AtlasConfig so we don't have to keep the genesis attachments around
         let moved_atlas_config = self.config.atlas.clone();
@@ -679,7 +679,8 @@ fn integration_test_get_info() {
                 let body = CallReadOnlyRequestBody {
                     sender: "'SP139Q3N9RXCJCD1XVA4N5RYWQ5K9XQ0T9PKQ8EE5".into(),
                     sponsor: None,
-                    arguments: vec![Value::UInt(3).serialize_to_hex().unwrap()]
+                    arguments: vec![Value::UInt(3).serialize_to_hex().unwrap()],
+                    json_arguments: None
                 };
 
                 let res = client.post(&path)
@@ -701,7 +702,8 @@ fn integration_test_get_info() {
                 let body = CallReadOnlyRequestBody {
                     sender: "'SP139Q3N9RXCJCD1XVA4N5RYWQ5K9XQ0T9PKQ8EE5".into(),
                     sponsor: None,
-                    arguments: vec![]
+                    arguments: vec![],
+                    json_arguments: None
                 };
 
                 let res = client.post(&path)
@@ -728,7 +730,8 @@ fn integration_test_get_info() {
                 let body = CallReadOnlyRequestBody {
                     sender: "'SP139Q3N9RXCJCD1XVA4N5RYWQ5K9XQ0T9PKQ8EE5".into(),
                     sponsor: None,
-                    arguments: vec![]
+                    arguments: vec![],
+                    json_arguments: None
                 };
 
                 let res = client.post(&path)
@@ -746,7 +749,8 @@ fn integration_test_get_info() {
                 let body = CallReadOnlyRequestBody {
                     sender: "'SP139Q3N9RXCJCD1XVA4N5RYWQ5K9XQ0T9PKQ8EE5".into(),
                     sponsor: None,
-                    arguments: vec![Value::UInt(3).serialize_to_hex().unwrap()]
+                    arguments: vec![Value::UInt(3).serialize_to_hex().unwrap()],
+                    json_arguments: None
                 };
 
                 let res = client.post(&path)
@@ -769,7 +773,8 @@ fn integration_test_get_info() {
                 let body = CallReadOnlyRequestBody {
                     sender: "'SP139Q3N9RXCJCD1XVA4N5RYWQ5K9XQ0T9PKQ8EE5".into(),
                     sponsor: None,
-                    arguments: vec![Value::UInt(100).serialize_to_hex().unwrap()]
+                    arguments: vec![Value::UInt(100).serialize_to_hex().unwrap()],
+                    json_arguments: None
                 };
 
                 let res = client.post(&path)
@@ -788,7 +793,8 @@ fn integration_test_get_info() {
                 let body = CallReadOnlyRequestBody {
                     sender: "'SP139Q3N9RXCJCD1XVA4N5RYWQ5K9XQ0T9PKQ8EE5".into(),
                     sponsor: None,
-                    arguments: vec![]
+                    arguments: vec![],
+                    json_arguments: None
                 };
 
                 let res = client.post(&path)
@@ -1181,6 +1181,7 @@ pub fn get_key_for_cycle(
         arguments: vec![clarity::vm::Value::UInt(reward_cycle as u128)
             .serialize_to_hex()
             .map_err(|_| "Failed to serialize reward cycle")?],
+        json_arguments: None,
     };
     let res = client
         .post(&path)